@@ -1,18 +1,29 @@
 use parking_lot::Mutex;
-use russh::ChannelMsg;
+use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::runtime::Runtime as TokioRuntime;
 use tokio::sync::Mutex as TokioMutex;
 use uuid::Uuid;
 
 use gpui::*;
 
-use crate::config::AppConfig;
-use crate::session::{LocalSession, Session, SessionGroup, SessionManager, SshSession, SsmSession};
+use crate::cli::LaunchTarget;
+use crate::config::{AppConfig, SessionSort, WorkspaceState, WorkspaceTab};
+use crate::hooks::{self, HookContext, SessionEnd};
+use crate::metrics::metrics;
+use crate::session::{
+    AuthMethod, K8sSession, KeepAliveCommand, LocalSession, MergeStrategy, SerialSession, Session, SessionGroup,
+    SessionLink, SessionManager, SshSession, SsmSession, TelnetSession,
+};
 use crate::sftp::SftpBrowser;
-use crate::terminal::{K8sBackend, SshBackend, SsmBackend, SsmMessageBuilder, Terminal, TerminalConfig, TerminalSize, connect_websocket, handle_ssm_message};
-use futures::{SinkExt, StreamExt};
-use tokio_tungstenite::tungstenite::Message as WsMessage;
+use crate::terminal::{AuthPromptRequest, AuthPrompter, BackendIo, BackendRead, HostKeyRequest, ConnectionStatus, K8sBackend, K8sLogsBackend, LineEvent, LineInput, SessionRecorder, ExecOutput, SshBackend, SshConnectionPool, SsmBackend, Terminal, TerminalBackend, TerminalConfig, TerminalSize};
+use crate::terminal::ssh_backend::expand_home;
+use crate::theme_import::import_theme;
+#[cfg(feature = "serial")]
+use crate::terminal::SerialBackend;
+#[cfg(feature = "telnet")]
+use crate::terminal::TelnetBackend;
 
 /// Represents an open terminal tab
 pub struct TerminalTab {
@@ -24,6 +35,8 @@ pub struct TerminalTab {
     pub terminal: Arc<Mutex<Terminal>>,
     /// Tab title (may differ from terminal title)
     pub title: String,
+    /// Name the user gave the tab, shown instead of `title`
+    pub custom_title: Option<String>,
     /// Whether the tab has unsaved state
     pub dirty: bool,
     /// Color scheme override for this tab
@@ -40,11 +53,46 @@ impl TerminalTab {
             session_id,
             terminal: Arc::new(Mutex::new(terminal)),
             title,
+            custom_title: None,
             dirty: false,
             color_scheme,
             sftp_browser: None,
         }
     }
+
+    /// Title to show for this tab: the user's name for it, if any
+    pub fn display_title(&self) -> &str {
+        self.custom_title.as_deref().unwrap_or(&self.title)
+    }
+}
+
+/// Delay between reconnects when reconnecting several dropped sessions
+pub const RECONNECT_STAGGER: Duration = Duration::from_millis(300);
+
+/// How long transient notices (e.g. the theme name after switching) stay visible
+pub const NOTICE_DURATION: Duration = Duration::from_millis(1500);
+
+/// How often the config file is checked for edits made outside the app
+pub const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Outcome of a "reconnect dropped sessions" batch
+#[derive(Debug, Default)]
+pub struct ReconnectSummary {
+    /// Tabs whose session was reopened
+    pub reconnected: usize,
+    /// Errors for tabs that could not be reopened
+    pub failed: Vec<String>,
+}
+
+impl ReconnectSummary {
+    /// One-line notice for a batch of `total` tabs, naming the first failure
+    pub fn notice(&self, total: usize) -> String {
+        let mut notice = format!("Reconnected {} of {} dropped sessions", self.reconnected, total);
+        if let Some(first) = self.failed.first() {
+            notice += &format!(" ({} failed: {})", self.failed.len(), first);
+        }
+        notice
+    }
 }
 
 /// Main application state
@@ -59,6 +107,12 @@ pub struct RedPillApp {
     pub active_tab: Option<usize>,
     /// Whether the session tree is visible
     pub session_tree_visible: bool,
+    /// Transient notice shown over the terminal area, with the time it was set
+    notice: Option<(String, Instant)>,
+    /// Authenticated SSH connections shared by tabs of the same session
+    ssh_pool: SshConnectionPool,
+    /// Tabs whose typed input is repeated in each other (empty = broadcast off)
+    broadcast_tabs: HashSet<Uuid>,
 }
 
 impl RedPillApp {
@@ -77,12 +131,55 @@ impl RedPillApp {
             tabs: Vec::new(),
             active_tab: None,
             session_tree_visible,
+            notice: None,
+            ssh_pool: SshConnectionPool::new(),
+            broadcast_tabs: HashSet::new(),
+        }
+    }
+
+    /// Terminal configuration derived from the app config
+    pub fn terminal_config(&self) -> TerminalConfig {
+        TerminalConfig {
+            scrollback_lines: self.config.scrollback_lines,
+            scrollback_memory_limit: self.config.scrollback_memory_mb * 1024 * 1024,
+            tab_width: self.config.tab_width,
+            ambiguous_wide: self.config.ambiguous_width_wide,
+            zmodem_enabled: self.config.zmodem.enabled,
+            connection_marker: self.config.connection_marker.active_format(),
+            ..Default::default()
+        }
+    }
+
+    /// Attach a transcript recorder if recording is enabled for this session
+    ///
+    /// `record_session` is the per-session override; `None` falls back to the
+    /// global setting. Failing to start a recording never blocks the session.
+    fn start_recording(&self, terminal: &mut Terminal, session_name: &str, record_session: Option<bool>) {
+        if !record_session.unwrap_or(self.config.recording.enabled) {
+            return;
+        }
+
+        match SessionRecorder::create(&self.config.recording, session_name) {
+            Ok(recorder) => {
+                let mut notice = format!(
+                    "\x1b[2mRecording session to {}\x1b[0m\r\n",
+                    recorder.path().display()
+                );
+                if recorder.records_input() {
+                    notice += "\x1b[1;33mKeyboard input is recorded too, including any passwords typed\x1b[0m\r\n";
+                }
+                terminal.set_recorder(recorder);
+                terminal.write_to_pty(notice.as_bytes());
+            }
+            Err(e) => {
+                tracing::error!("Failed to start recording for {}: {}", session_name, e);
+            }
         }
     }
 
     /// Open a new local terminal tab
     pub fn open_local_terminal(&mut self) -> Result<Uuid, String> {
-        let config = TerminalConfig::default();
+        let config = self.terminal_config();
         let terminal =
             Terminal::new_local(config).map_err(|e| format!("Failed to create terminal: {}", e))?;
 
@@ -96,8 +193,72 @@ impl RedPillApp {
         Ok(id)
     }
 
-    /// Open a terminal for an SSH session (sync wrapper that spawns async task)
-    pub fn open_ssh_session(&mut self, session_id: Uuid, runtime: &TokioRuntime) -> Result<Uuid, String> {
+    /// Open a tab for a saved session of any kind
+    ///
+    /// With `force_prompt`, an SSH session asks for its password or key
+    /// passphrase in the tab even if one is saved.
+    pub fn open_session(&mut self, session_id: Uuid, runtime: &TokioRuntime, force_prompt: bool) -> Result<Uuid, String> {
+        match self.session_manager.get_session(session_id) {
+            Some(Session::Ssh(_)) => self.open_saved_ssh_session(session_id, runtime, force_prompt),
+            Some(Session::Ssm(_)) => self.open_ssm_session(session_id, runtime),
+            Some(Session::Local(_)) => self.open_local_session(session_id),
+            Some(Session::K8s(_)) => self.open_k8s_session(session_id, runtime),
+            Some(Session::Serial(_)) => self.open_serial_session(session_id, runtime),
+            Some(Session::Telnet(_)) => self.open_telnet_session(session_id, runtime),
+            None => Err("Session not found".to_string()),
+        }
+    }
+
+    /// Open a terminal for a saved local session
+    fn open_local_session(&mut self, session_id: Uuid) -> Result<Uuid, String> {
+        let local_session = match self.session_manager.get_session(session_id) {
+            Some(Session::Local(local)) => local.clone(),
+            Some(_) => return Err("Not a local session".to_string()),
+            None => return Err("Session not found".to_string()),
+        };
+
+        // An unset working directory means home, as the session dialog says
+        let config = TerminalConfig {
+            shell: local_session.shell.clone(),
+            working_directory: local_session
+                .working_dir
+                .as_deref()
+                .map(expand_home)
+                .or_else(dirs::home_dir),
+            env: local_session.env.clone(),
+            ..self.terminal_config()
+        };
+        let terminal =
+            Terminal::new_local(config).map_err(|e| format!("Failed to create terminal: {}", e))?;
+
+        // No session ID: the tab is a plain local shell, not a remote connection
+        let tab = TerminalTab::new(terminal, None, local_session.name, local_session.color_scheme);
+        let id = tab.id;
+
+        self.tabs.push(tab);
+        self.active_tab = Some(self.tabs.len() - 1);
+        self.record_connected(session_id);
+
+        tracing::info!("Opened local session tab: {}", id);
+        Ok(id)
+    }
+
+    /// Stamp a saved session's last-connected time and persist it
+    fn record_connected(&mut self, session_id: Uuid) {
+        let saved = self
+            .session_manager
+            .mark_connected(session_id)
+            .and_then(|()| self.session_manager.save());
+        if let Err(e) = saved {
+            tracing::warn!("Failed to record connection to {}: {}", session_id, e);
+        }
+    }
+
+    /// Open a terminal for a saved SSH session (sync wrapper that spawns async task)
+    ///
+    /// With `force_prompt`, a saved password or key passphrase is ignored and
+    /// asked for in the tab instead.
+    fn open_saved_ssh_session(&mut self, session_id: Uuid, runtime: &TokioRuntime, force_prompt: bool) -> Result<Uuid, String> {
         let session = self
             .session_manager
             .get_session(session_id)
@@ -108,27 +269,98 @@ impl RedPillApp {
         // Get SSH session config
         let (ssh_session, color_scheme) = match session {
             Session::Ssh(ssh) => (ssh.clone(), ssh.color_scheme.clone()),
-            Session::Local(_) => {
-                // For local sessions, just open a local terminal
-                return self.open_local_terminal();
-            }
-            Session::Ssm(_) => {
-                // For SSM sessions, use the SSM method
-                return self.open_ssm_session(session_id, runtime);
+            _ => return Err("Not an SSH session".to_string()),
+        };
+
+        let id = self.open_ssh_terminal(ssh_session, Some(session_id), title, color_scheme, runtime, force_prompt)?;
+        self.record_connected(session_id);
+        Ok(id)
+    }
+
+    /// Open the session a `redpill://` or `ssh://` link points at
+    ///
+    /// Links matching a saved session open it as usual. SSH links that match
+    /// nothing open a one-off session that isn't added to the session list.
+    pub fn open_link(&mut self, link: &SessionLink, runtime: &TokioRuntime) -> Result<Uuid, String> {
+        if let Some(session_id) = link.find_saved(&self.session_manager).map(Session::id) {
+            return self.open_session(session_id, runtime, false);
+        }
+
+        if let SessionLink::Saved(target) = link {
+            return Err(format!("No saved session named \"{}\"", target));
+        }
+
+        let ssh_session = link
+            .to_transient_session()
+            .ok_or_else(|| "Not an SSH link".to_string())?;
+        let title = ssh_session.name.clone();
+        self.open_ssh_terminal(ssh_session, None, title, None, runtime, false)
+    }
+
+    /// Open a session requested on the command line
+    ///
+    /// SSM and K8s targets open one-off sessions that aren't saved.
+    pub fn open_launch_target(&mut self, target: &LaunchTarget, runtime: &TokioRuntime) -> Result<Uuid, String> {
+        match target {
+            LaunchTarget::Link(link) => self.open_link(link, runtime),
+            LaunchTarget::Ssm(ssm_session) => {
+                let title = ssm_session.name.clone();
+                self.open_ssm_terminal(ssm_session.clone(), None, title, None, runtime)
             }
-            Session::K8s(_) => {
-                // For K8s sessions, use the K8s method
-                return self.open_k8s_session(session_id, runtime);
+            LaunchTarget::K8s(k8s_session) => self.open_k8s_terminal(k8s_session.clone(), None, None, runtime),
+        }
+    }
+
+    /// Open a tab connected to an SSH session, saved or not
+    fn open_ssh_terminal(
+        &mut self,
+        ssh_session: SshSession,
+        session_id: Option<Uuid>,
+        title: String,
+        color_scheme: Option<String>,
+        runtime: &TokioRuntime,
+        force_prompt: bool,
+    ) -> Result<Uuid, String> {
+        let record_session = ssh_session.record_session;
+        let keep_alive = ssh_session.keep_alive.clone();
+        let hooks_allowed = self.config.allow_session_hooks;
+        let pre_connect = ssh_session.pre_connect_command.clone().filter(|_| hooks_allowed);
+        let post_disconnect = ssh_session.post_disconnect_command.clone().filter(|_| hooks_allowed);
+        let hook_context = HookContext {
+            name: ssh_session.name.clone(),
+            host: ssh_session.host.clone(),
+            end: None,
+        };
+        let sudo_password = match &ssh_session.auth {
+            AuthMethod::Password { password: Some(password), .. } if ssh_session.sudo_autofill && !force_prompt => {
+                Some(password.clone())
             }
+            _ => None,
+        };
+        let config = TerminalConfig {
+            newline_mode: ssh_session.newline_mode,
+            ..self.terminal_config()
         };
 
         // Create SSH backend (not connected yet)
-        let backend = SshBackend::new(ssh_session);
+        let mut backend = SshBackend::new(ssh_session);
+        if let (Some(session_id), true) = (session_id, self.config.share_ssh_connections) {
+            backend.share_connection(self.ssh_pool.clone(), session_id);
+        }
+        let (mut prompter, auth_prompts) = AuthPrompter::channel();
+        let host_key_requests = prompter.host_key_channel();
+        backend.set_prompter(prompter);
+        backend.set_auth_fallback(self.config.auth_fallback.clone());
+        backend.set_timeouts(self.config.ssh_timeouts);
+        backend.set_force_prompt(force_prompt);
 
         // Create terminal in SSH mode with tokio handle for async operations
-        let config = TerminalConfig::default();
-        let terminal = Terminal::new_ssh(config, backend, runtime.handle().clone())
+        let mut terminal = Terminal::new_ssh(config, backend, runtime.handle().clone())
             .map_err(|e| format!("Failed to create SSH terminal: {}", e))?;
+        self.start_recording(&mut terminal, &title, record_session);
+        if let Some(password) = sudo_password {
+            terminal.set_sudo_autofill(password);
+        }
 
         // Get the backend for the reader task
         let backend_arc = terminal
@@ -137,90 +369,37 @@ impl RedPillApp {
 
         let terminal_arc = Arc::new(Mutex::new(terminal));
 
-        // Spawn the async connection and reader task on Tokio runtime
-        let terminal_weak = Arc::downgrade(&terminal_arc);
-        let backend_for_connect = backend_arc.clone();
+        runtime.spawn(serve_auth_prompts(Arc::downgrade(&terminal_arc), auth_prompts));
+        runtime.spawn(serve_host_key_prompts(Arc::downgrade(&terminal_arc), host_key_requests));
 
+        // Run the session's pre-connect hook, connect and run the I/O loop
+        // on the Tokio runtime, then run its post-disconnect hook
+        let terminal_weak = Arc::downgrade(&terminal_arc);
         runtime.spawn(async move {
-            // Connect to SSH server and take channel for I/O
-            let io_handles = {
-                let mut backend = backend_for_connect.lock().await;
-                match backend.connect().await {
-                    Ok(()) => {
-                        tracing::info!("SSH connection established");
-                        // Take the channel out of the backend for direct I/O
-                        backend.take_channel_for_io()
-                    }
-                    Err(e) => {
-                        tracing::error!("SSH connection failed: {}", e);
-                        // Display error message in terminal with nice formatting
-                        if let Some(term_arc) = terminal_weak.upgrade() {
-                            let term = term_arc.lock();
-                            let error_text = e.to_string();
-                            let error_msg = format!(
-                                "\x1b[2J\x1b[H\r\n\
-                                \x1b[1;31m  Connection Failed\x1b[0m\r\n\
-                                \r\n\
-                                \x1b[33m  {}\x1b[0m\r\n",
-                                error_text
-                            );
-                            term.write_to_pty(error_msg.as_bytes());
-                        }
-                        return;
-                    }
-                }
+            let ready = match pre_connect {
+                Some(command) => run_pre_connect_hook(&terminal_weak, &command, &hook_context).await,
+                None => true,
             };
-
-            let (channel, write_rx) = match io_handles {
-                Some(handles) => handles,
-                None => {
-                    tracing::error!("Failed to get SSH channel for I/O");
-                    return;
-                }
-            };
-
-            // Create resize channel for sending window size changes to I/O loop
-            let (resize_tx, resize_rx) = tokio::sync::mpsc::unbounded_channel();
-
-            // Update the terminal's write_tx and resize_tx to point to our new channels
-            // Also get the current size to send immediately after setup
-            let write_tx = backend_for_connect.lock().await.get_write_sender();
-            let current_size = if let (Some(term_arc), Some(tx)) = (terminal_weak.upgrade(), write_tx) {
-                let mut term = term_arc.lock();
-                term.set_write_tx(tx);
-                term.set_resize_tx(resize_tx);
-                Some(term.size())
+            let end = if ready {
+                run_backend_session(terminal_weak, backend_arc).await
             } else {
-                None
+                SessionEnd::Failed
             };
-
-            // Send immediate resize with the terminal's current size
-            // This ensures the SSH server gets correct dimensions even if the first
-            // UI paint happened before the channels were connected
-            if let Some(size) = current_size {
-                if size.cols > 0 && size.rows > 0 {
-                    tracing::info!("SSH immediate resize after channel setup: {}x{} ({}x{} px)",
-                        size.cols, size.rows, size.pixel_width, size.pixel_height);
-                    if let Err(e) = channel.window_change(
-                        size.cols as u32,
-                        size.rows as u32,
-                        size.pixel_width as u32,
-                        size.pixel_height as u32,
-                    ).await {
-                        tracing::error!("SSH immediate resize error: {}", e);
-                    }
-                }
+            if let Some(command) = post_disconnect {
+                let context = HookContext { end: Some(end), ..hook_context };
+                hooks::spawn(&command, &context);
             }
-
-            // Start the combined I/O loop using select!
-            spawn_ssh_io_loop(terminal_weak, backend_for_connect, channel, write_rx, resize_rx).await;
         });
+        if let Some(keep_alive) = keep_alive {
+            runtime.spawn(run_keep_alive(Arc::downgrade(&terminal_arc), keep_alive));
+        }
 
         let tab = TerminalTab {
             id: Uuid::new_v4(),
-            session_id: Some(session_id),
+            session_id,
             terminal: terminal_arc,
             title,
+            custom_title: None,
             dirty: false,
             color_scheme,
             sftp_browser: None, // Initialized on-demand when SFTP panel is opened
@@ -231,15 +410,15 @@ impl RedPillApp {
         self.active_tab = Some(self.tabs.len() - 1);
 
         tracing::info!(
-            "Opened SSH session tab: {} for session: {}",
+            "Opened SSH session tab: {} for session: {:?}",
             id,
             session_id
         );
         Ok(id)
     }
 
-    /// Open a terminal for an SSM session (sync wrapper that spawns async task)
-    pub fn open_ssm_session(&mut self, session_id: Uuid, runtime: &TokioRuntime) -> Result<Uuid, String> {
+    /// Open a terminal for a saved SSM session (sync wrapper that spawns async task)
+    fn open_ssm_session(&mut self, session_id: Uuid, runtime: &TokioRuntime) -> Result<Uuid, String> {
         let session = self
             .session_manager
             .get_session(session_id)
@@ -250,27 +429,33 @@ impl RedPillApp {
         // Get SSM session config
         let (ssm_session, color_scheme) = match session {
             Session::Ssm(ssm) => (ssm.clone(), ssm.color_scheme.clone()),
-            Session::Ssh(_) => {
-                // For SSH sessions, use the SSH method
-                return self.open_ssh_session(session_id, runtime);
-            }
-            Session::Local(_) => {
-                // For local sessions, just open a local terminal
-                return self.open_local_terminal();
-            }
-            Session::K8s(_) => {
-                // For K8s sessions, use the K8s method
-                return self.open_k8s_session(session_id, runtime);
-            }
+            _ => return Err("Not an SSM session".to_string()),
         };
 
+        let id = self.open_ssm_terminal(ssm_session, Some(session_id), title, color_scheme, runtime)?;
+        self.record_connected(session_id);
+        Ok(id)
+    }
+
+    /// Open a tab connected to an SSM session, saved or not
+    fn open_ssm_terminal(
+        &mut self,
+        ssm_session: SsmSession,
+        session_id: Option<Uuid>,
+        title: String,
+        color_scheme: Option<String>,
+        runtime: &TokioRuntime,
+    ) -> Result<Uuid, String> {
+        let record_session = ssm_session.record_session;
+
         // Create SSM backend (not connected yet)
         let backend = SsmBackend::new(ssm_session);
 
         // Create terminal in SSM mode with tokio handle for async operations
-        let config = TerminalConfig::default();
-        let terminal = Terminal::new_ssm(config, backend, runtime.handle().clone())
+        let config = self.terminal_config();
+        let mut terminal = Terminal::new_ssm(config, backend, runtime.handle().clone())
             .map_err(|e| format!("Failed to create SSM terminal: {}", e))?;
+        self.start_recording(&mut terminal, &title, record_session);
 
         // Get the backend for the I/O task
         let backend_arc = terminal
@@ -279,80 +464,15 @@ impl RedPillApp {
 
         let terminal_arc = Arc::new(Mutex::new(terminal));
 
-        // Spawn the async connection and I/O task on Tokio runtime
-        let terminal_weak = Arc::downgrade(&terminal_arc);
-        let backend_for_connect = backend_arc.clone();
-
-        runtime.spawn(async move {
-            // Connect to SSM (get WebSocket URL and token)
-            let (write_rx, resize_rx) = {
-                let mut backend = backend_for_connect.lock().await;
-                match backend.connect().await {
-                    Ok(()) => {
-                        tracing::info!("SSM session started");
-                        backend.setup_channels()
-                    }
-                    Err(e) => {
-                        tracing::error!("SSM connection failed: {}", e);
-                        // Display error message in terminal
-                        if let Some(term_arc) = terminal_weak.upgrade() {
-                            let term = term_arc.lock();
-                            let error_msg = format!(
-                                "\x1b[2J\x1b[H\r\n\
-                                \x1b[1;31m  SSM Connection Failed\x1b[0m\r\n\
-                                \r\n\
-                                \x1b[33m  {}\x1b[0m\r\n",
-                                e
-                            );
-                            term.write_to_pty(error_msg.as_bytes());
-                        }
-                        return;
-                    }
-                }
-            };
-
-            // Connect to WebSocket
-            let ws_stream = {
-                let mut backend = backend_for_connect.lock().await;
-                match connect_websocket(&mut backend).await {
-                    Ok(ws) => ws,
-                    Err(e) => {
-                        tracing::error!("SSM WebSocket connection failed: {}", e);
-                        if let Some(term_arc) = terminal_weak.upgrade() {
-                            let term = term_arc.lock();
-                            let error_msg = format!(
-                                "\r\n\x1b[1;31m  WebSocket Connection Failed\x1b[0m\r\n\
-                                \r\n\x1b[33m  {}\x1b[0m\r\n",
-                                e
-                            );
-                            term.write_to_pty(error_msg.as_bytes());
-                        }
-                        return;
-                    }
-                }
-            };
-
-            // Update terminal's write_tx and resize_tx
-            let write_tx = backend_for_connect.lock().await.get_write_sender();
-            let resize_tx = backend_for_connect.lock().await.get_resize_sender();
-
-            if let (Some(term_arc), Some(wtx), Some(rtx)) =
-                (terminal_weak.upgrade(), write_tx, resize_tx)
-            {
-                let mut term = term_arc.lock();
-                term.set_write_tx(wtx);
-                term.set_resize_tx(rtx);
-            }
-
-            // Start the I/O loop
-            spawn_ssm_io_loop(terminal_weak, backend_for_connect, ws_stream, write_rx, resize_rx).await;
-        });
+        // Connect and run the I/O loop on the Tokio runtime
+        runtime.spawn(run_backend_session(Arc::downgrade(&terminal_arc), backend_arc));
 
         let tab = TerminalTab {
             id: Uuid::new_v4(),
-            session_id: Some(session_id),
+            session_id,
             terminal: terminal_arc,
             title,
+            custom_title: None,
             dirty: false,
             color_scheme,
             sftp_browser: None,
@@ -363,7 +483,7 @@ impl RedPillApp {
         self.active_tab = Some(self.tabs.len() - 1);
 
         tracing::info!(
-            "Opened SSM session tab: {} for session: {}",
+            "Opened SSM session tab: {} for session: {:?}",
             id,
             session_id
         );
@@ -371,7 +491,7 @@ impl RedPillApp {
     }
 
     /// Open a terminal for a K8s pod exec session
-    pub fn open_k8s_session(&mut self, session_id: Uuid, runtime: &TokioRuntime) -> Result<Uuid, String> {
+    fn open_k8s_session(&mut self, session_id: Uuid, runtime: &TokioRuntime) -> Result<Uuid, String> {
         let session = self
             .session_manager
             .get_session(session_id)
@@ -382,15 +502,30 @@ impl RedPillApp {
             _ => return Err("Not a K8s session".to_string()),
         };
 
+        let id = self.open_k8s_terminal(k8s_session, Some(session_id), color_scheme, runtime)?;
+        self.record_connected(session_id);
+        Ok(id)
+    }
+
+    /// Open a tab exec'd into a K8s pod, saved session or not
+    fn open_k8s_terminal(
+        &mut self,
+        k8s_session: K8sSession,
+        session_id: Option<Uuid>,
+        color_scheme: Option<String>,
+        runtime: &TokioRuntime,
+    ) -> Result<Uuid, String> {
         let title = format!("{}:{}", k8s_session.namespace, k8s_session.pod);
+        let record_session = k8s_session.record_session;
 
         // Create K8s backend (not connected yet)
         let backend = K8sBackend::new(k8s_session);
 
         // Create terminal in K8s mode
-        let config = TerminalConfig::default();
-        let terminal = Terminal::new_k8s(config, backend, runtime.handle().clone())
+        let config = self.terminal_config();
+        let mut terminal = Terminal::new_k8s(config, backend, runtime.handle().clone())
             .map_err(|e| format!("Failed to create K8s terminal: {}", e))?;
+        self.start_recording(&mut terminal, &title, record_session);
 
         // Get the backend for the connection task
         let backend_arc = terminal
@@ -399,105 +534,166 @@ impl RedPillApp {
 
         let terminal_arc = Arc::new(Mutex::new(terminal));
 
-        // Spawn the async connection task
-        let terminal_weak = Arc::downgrade(&terminal_arc);
-        let backend_for_connect = backend_arc.clone();
+        // Connect and run the I/O loop on the Tokio runtime
+        runtime.spawn(run_backend_session(Arc::downgrade(&terminal_arc), backend_arc));
 
-        runtime.spawn(async move {
-            // Connect to K8s and get I/O channels
-            let io_handles = {
-                let mut backend = backend_for_connect.lock().await;
-                // Set terminal size
-                if let Some(term_arc) = terminal_weak.upgrade() {
-                    let term = term_arc.lock();
-                    let size = term.size();
-                    backend.set_size(crate::terminal::k8s_backend::TerminalSize::new(size.cols, size.rows));
-                }
+        let tab = TerminalTab {
+            id: Uuid::new_v4(),
+            session_id,
+            terminal: terminal_arc,
+            title,
+            custom_title: None,
+            dirty: false,
+            color_scheme,
+            sftp_browser: None,
+        };
+        let id = tab.id;
 
-                match backend.connect().await {
-                    Ok(handles) => {
-                        tracing::info!("K8s pod exec connection established");
-                        Some(handles)
-                    }
-                    Err(e) => {
-                        tracing::error!("K8s connection failed: {}", e);
-                        // Display error message in terminal
-                        if let Some(term_arc) = terminal_weak.upgrade() {
-                            let term = term_arc.lock();
-                            let error_msg = format!(
-                                "\x1b[2J\x1b[H\r\n\
-                                \x1b[1;31m  Connection Failed\x1b[0m\r\n\
-                                \r\n\
-                                \x1b[33m  {}\x1b[0m\r\n",
-                                e
-                            );
-                            term.write_to_pty(error_msg.as_bytes());
-                        }
-                        return;
-                    }
-                }
-            };
+        self.tabs.push(tab);
+        self.active_tab = Some(self.tabs.len() - 1);
 
-            let (write_tx, mut read_rx, resize_tx) = match io_handles {
-                Some(handles) => handles,
-                None => {
-                    tracing::error!("Failed to get K8s I/O handles");
-                    return;
-                }
-            };
+        tracing::info!(
+            "Opened K8s session tab: {} for session: {:?}",
+            id,
+            session_id
+        );
+        Ok(id)
+    }
 
-            // Create unbounded channels for the terminal
-            let (term_write_tx, mut term_write_rx) = tokio::sync::mpsc::unbounded_channel::<Vec<u8>>();
-            let (term_resize_tx, mut term_resize_rx) = tokio::sync::mpsc::unbounded_channel::<TerminalSize>();
+    /// Open a read-only tab streaming a pod's log, following new lines if `follow`
+    pub fn open_pod_logs(
+        &mut self,
+        context: &str,
+        namespace: &str,
+        pod: &str,
+        container: Option<String>,
+        follow: bool,
+        runtime: &TokioRuntime,
+    ) -> Result<Uuid, String> {
+        let title = match &container {
+            Some(container) => format!("logs {}:{}/{}", namespace, pod, container),
+            None => format!("logs {}:{}", namespace, pod),
+        };
 
-            // Update terminal channels
-            if let Some(term_arc) = terminal_weak.upgrade() {
-                let mut term = term_arc.lock();
-                term.set_write_tx(term_write_tx);
-                term.set_resize_tx(term_resize_tx);
-            }
+        let config = self.terminal_config();
+        let terminal = Terminal::new_backend(config, "Logs")
+            .map_err(|e| format!("Failed to create log terminal: {}", e))?;
 
-            // I/O loop
-            loop {
-                tokio::select! {
-                    // Terminal wants to write to pod
-                    Some(data) = term_write_rx.recv() => {
-                        if write_tx.send(data).await.is_err() {
-                            tracing::info!("K8s write channel closed");
-                            break;
-                        }
-                    }
+        let backend = Arc::new(TokioMutex::new(K8sLogsBackend::new(context, namespace, pod, container, follow)));
+        let terminal_arc = Arc::new(Mutex::new(terminal));
 
-                    // Data from pod to display
-                    Some(data) = read_rx.recv() => {
-                        if let Some(term_arc) = terminal_weak.upgrade() {
-                            let term = term_arc.lock();
-                            term.write_to_pty(&data);
-                        } else {
-                            break;
-                        }
-                    }
+        // Open the stream and copy it into the terminal on the Tokio runtime
+        runtime.spawn(run_backend_session(Arc::downgrade(&terminal_arc), backend));
 
-                    // Terminal resize
-                    Some(size) = term_resize_rx.recv() => {
-                        let k8s_size = crate::terminal::k8s_backend::TerminalSize::new(size.cols, size.rows);
-                        if resize_tx.send(k8s_size).await.is_err() {
-                            tracing::warn!("K8s resize channel closed");
-                        }
-                    }
+        let tab = TerminalTab {
+            id: Uuid::new_v4(),
+            session_id: None,
+            terminal: terminal_arc,
+            title,
+            custom_title: None,
+            dirty: false,
+            color_scheme: None,
+            sftp_browser: None,
+        };
+        let id = tab.id;
 
-                    else => break,
-                }
-            }
+        self.tabs.push(tab);
+        self.active_tab = Some(self.tabs.len() - 1);
 
-            tracing::info!("K8s I/O loop ended");
-        });
+        tracing::info!("Opened pod log tab: {}", id);
+        Ok(id)
+    }
+
+    /// Open a terminal for a serial console session
+    #[cfg(feature = "serial")]
+    fn open_serial_session(&mut self, session_id: Uuid, runtime: &TokioRuntime) -> Result<Uuid, String> {
+        let session = self
+            .session_manager
+            .get_session(session_id)
+            .ok_or_else(|| "Session not found".to_string())?;
+
+        let (serial_session, color_scheme) = match session {
+            Session::Serial(serial) => (serial.clone(), serial.color_scheme.clone()),
+            _ => return Err("Not a serial session".to_string()),
+        };
+
+        let title = serial_session.name.clone();
+        let record_session = serial_session.record_session;
+
+        let config = self.terminal_config();
+        let mut terminal = Terminal::new_backend(config, "Serial")
+            .map_err(|e| format!("Failed to create serial terminal: {}", e))?;
+        self.start_recording(&mut terminal, &title, record_session);
+
+        let backend = Arc::new(TokioMutex::new(SerialBackend::new(serial_session)));
+        let terminal_arc = Arc::new(Mutex::new(terminal));
+
+        // Open the port and run the I/O loop on the Tokio runtime
+        runtime.spawn(run_backend_session(Arc::downgrade(&terminal_arc), backend));
+
+        let tab = TerminalTab {
+            id: Uuid::new_v4(),
+            session_id: Some(session_id),
+            terminal: terminal_arc,
+            title,
+            custom_title: None,
+            dirty: false,
+            color_scheme,
+            sftp_browser: None,
+        };
+        let id = tab.id;
+
+        self.tabs.push(tab);
+        self.active_tab = Some(self.tabs.len() - 1);
+        self.record_connected(session_id);
+
+        tracing::info!(
+            "Opened serial session tab: {} for session: {}",
+            id,
+            session_id
+        );
+        Ok(id)
+    }
+
+    /// Serial sessions need the `serial` feature
+    #[cfg(not(feature = "serial"))]
+    fn open_serial_session(&mut self, _session_id: Uuid, _runtime: &TokioRuntime) -> Result<Uuid, String> {
+        Err("Serial support is not enabled in this build (rebuild with --features serial)".to_string())
+    }
+
+    /// Open a terminal for a telnet session
+    #[cfg(feature = "telnet")]
+    fn open_telnet_session(&mut self, session_id: Uuid, runtime: &TokioRuntime) -> Result<Uuid, String> {
+        let session = self
+            .session_manager
+            .get_session(session_id)
+            .ok_or_else(|| "Session not found".to_string())?;
+
+        let (telnet_session, color_scheme) = match session {
+            Session::Telnet(telnet) => (telnet.clone(), telnet.color_scheme.clone()),
+            _ => return Err("Not a telnet session".to_string()),
+        };
+
+        let title = telnet_session.name.clone();
+        let record_session = telnet_session.record_session;
+
+        let config = self.terminal_config();
+        let mut terminal = Terminal::new_backend(config, "Telnet")
+            .map_err(|e| format!("Failed to create telnet terminal: {}", e))?;
+        self.start_recording(&mut terminal, &title, record_session);
+
+        let backend = Arc::new(TokioMutex::new(TelnetBackend::new(telnet_session)));
+        let terminal_arc = Arc::new(Mutex::new(terminal));
+
+        // Connect and run the I/O loop on the Tokio runtime
+        runtime.spawn(run_backend_session(Arc::downgrade(&terminal_arc), backend));
 
         let tab = TerminalTab {
             id: Uuid::new_v4(),
             session_id: Some(session_id),
             terminal: terminal_arc,
             title,
+            custom_title: None,
             dirty: false,
             color_scheme,
             sftp_browser: None,
@@ -506,20 +702,33 @@ impl RedPillApp {
 
         self.tabs.push(tab);
         self.active_tab = Some(self.tabs.len() - 1);
+        self.record_connected(session_id);
 
         tracing::info!(
-            "Opened K8s session tab: {} for session: {}",
+            "Opened telnet session tab: {} for session: {}",
             id,
             session_id
         );
         Ok(id)
     }
 
+    /// Telnet sessions need the `telnet` feature
+    #[cfg(not(feature = "telnet"))]
+    fn open_telnet_session(&mut self, _session_id: Uuid, _runtime: &TokioRuntime) -> Result<Uuid, String> {
+        Err("Telnet support is not enabled in this build (rebuild with --features telnet)".to_string())
+    }
+
     /// Close a terminal tab
     pub fn close_tab(&mut self, tab_id: Uuid) {
         if let Some(index) = self.tabs.iter().position(|t| t.id == tab_id) {
             self.tabs.remove(index);
 
+            // Broadcasting to a single tab is just typing
+            self.broadcast_tabs.remove(&tab_id);
+            if self.broadcast_tabs.len() < 2 {
+                self.broadcast_tabs.clear();
+            }
+
             // Adjust active tab
             if self.tabs.is_empty() {
                 self.active_tab = None;
@@ -535,6 +744,71 @@ impl RedPillApp {
         }
     }
 
+    /// Move the tab at `from` so it ends up at index `to`
+    ///
+    /// The active tab stays active wherever it lands.
+    pub fn reorder_tab(&mut self, from: usize, to: usize) {
+        if from >= self.tabs.len() || to >= self.tabs.len() || from == to {
+            return;
+        }
+        let active_id = self.active_tab().map(|t| t.id);
+        let tab = self.tabs.remove(from);
+        self.tabs.insert(to, tab);
+        if let Some(id) = active_id {
+            self.set_active_tab_by_id(id);
+        }
+    }
+
+    // === Broadcast input ===
+
+    /// Tabs that turning on broadcast input would cover
+    ///
+    /// If the active tab is a saved session in a group, that's the open tabs
+    /// of every session in that group; otherwise it's every open tab.
+    pub fn broadcast_candidates(&self) -> Vec<Uuid> {
+        let group_of = |tab: &TerminalTab| {
+            tab.session_id
+                .and_then(|id| self.session_manager.get_session(id))
+                .and_then(Session::group_id)
+        };
+        let group = self.active_tab().and_then(group_of);
+        self.tabs
+            .iter()
+            .filter(|tab| group.is_none() || group_of(tab) == group)
+            .map(|tab| tab.id)
+            .collect()
+    }
+
+    /// Repeat input typed in any of these tabs in all the others
+    pub fn start_broadcast(&mut self, tab_ids: Vec<Uuid>) {
+        self.broadcast_tabs = tab_ids.into_iter().filter(|id| self.get_tab(*id).is_some()).collect();
+        tracing::info!("Broadcasting input to {} tabs", self.broadcast_tabs.len());
+    }
+
+    /// Turn broadcast input off
+    pub fn stop_broadcast(&mut self) {
+        self.broadcast_tabs.clear();
+    }
+
+    /// Number of tabs in the broadcast set (0 when broadcast is off)
+    pub fn broadcast_tab_count(&self) -> usize {
+        self.broadcast_tabs.len()
+    }
+
+    /// Terminals that should also get the input typed into `terminal`
+    ///
+    /// Empty unless `terminal` belongs to a tab in the broadcast set.
+    pub fn broadcast_peers(&self, terminal: &Arc<Mutex<Terminal>>) -> Vec<Arc<Mutex<Terminal>>> {
+        let members = || self.tabs.iter().filter(|tab| self.broadcast_tabs.contains(&tab.id));
+        if !members().any(|tab| Arc::ptr_eq(&tab.terminal, terminal)) {
+            return Vec::new();
+        }
+        members()
+            .filter(|tab| !Arc::ptr_eq(&tab.terminal, terminal))
+            .map(|tab| tab.terminal.clone())
+            .collect()
+    }
+
     /// Get the currently active tab
     pub fn active_tab(&self) -> Option<&TerminalTab> {
         self.active_tab.and_then(|i| self.tabs.get(i))
@@ -564,6 +838,14 @@ impl RedPillApp {
         self.tabs.iter().find(|t| t.id == tab_id)
     }
 
+    /// Give a tab a name of its own; a blank name restores its original title
+    pub fn rename_tab(&mut self, tab_id: Uuid, name: &str) {
+        if let Some(tab) = self.tabs.iter_mut().find(|t| t.id == tab_id) {
+            let name = name.trim();
+            tab.custom_title = (!name.is_empty()).then(|| name.to_string());
+        }
+    }
+
     /// Toggle session tree visibility
     pub fn toggle_session_tree(&mut self) {
         self.session_tree_visible = !self.session_tree_visible;
@@ -571,12 +853,91 @@ impl RedPillApp {
         let _ = self.config.save();
     }
 
+    /// Change how the session tree orders sessions
+    pub fn set_session_sort(&mut self, sort: SessionSort) {
+        self.config.session_tree.sort = sort;
+        let _ = self.config.save();
+    }
+
+    /// Show a short-lived notice over the terminal area
+    pub fn show_notice(&mut self, text: impl Into<String>) {
+        self.notice = Some((text.into(), Instant::now()));
+    }
+
+    /// Current notice, if it hasn't expired yet
+    pub fn notice(&self) -> Option<&str> {
+        self.notice
+            .as_ref()
+            .filter(|(_, shown_at)| shown_at.elapsed() < NOTICE_DURATION)
+            .map(|(text, _)| text.as_str())
+    }
+
+    /// Switch the global color scheme to the next available one
+    pub fn cycle_theme(&mut self) -> String {
+        let name = self.config.appearance.cycle_scheme();
+        if let Err(e) = self.config.save() {
+            tracing::warn!("Failed to save config: {}", e);
+        }
+        self.show_notice(format!("Theme: {}", name));
+        name
+    }
+
     /// Count the number of active SSH connections (tabs with session_id)
     #[must_use]
     pub fn active_ssh_connection_count(&self) -> usize {
         self.tabs.iter().filter(|tab| tab.session_id.is_some()).count()
     }
 
+    /// Tabs whose remote session has failed or disconnected
+    #[must_use]
+    pub fn dropped_tabs(&self) -> Vec<Uuid> {
+        self.tabs
+            .iter()
+            .filter(|tab| tab.session_id.is_some() && tab.terminal.lock().status().is_dropped())
+            .map(|tab| tab.id)
+            .collect()
+    }
+
+    /// Reopen a tab's session in place, using the session's current config
+    ///
+    /// The replacement tab takes the old tab's position, any name the user
+    /// gave it and its place in the broadcast set; returns its ID.
+    pub fn reconnect_tab(&mut self, tab_id: Uuid, runtime: &TokioRuntime) -> Result<Uuid, String> {
+        let index = self
+            .tabs
+            .iter()
+            .position(|t| t.id == tab_id)
+            .ok_or_else(|| "Tab not found".to_string())?;
+        let session_id = self.tabs[index]
+            .session_id
+            .ok_or_else(|| "Tab has no session".to_string())?;
+        let active_id = self.active_tab().map(|t| t.id);
+
+        let new_id = self.open_session(session_id, runtime, false)?;
+
+        // Swap the new tab into the old one's slot and close the old one
+        let new_index = self
+            .tabs
+            .iter()
+            .position(|t| t.id == new_id)
+            .ok_or_else(|| "Reopened tab not found".to_string())?;
+        let mut new_tab = self.tabs.remove(new_index);
+        new_tab.custom_title = self.tabs[index].custom_title.clone();
+        self.tabs.insert(index, new_tab);
+        // Keep broadcasting to the tab if it was in the set
+        if self.broadcast_tabs.remove(&tab_id) {
+            self.broadcast_tabs.insert(new_id);
+        }
+        self.close_tab(tab_id);
+        let active_id = active_id.map(|id| if id == tab_id { new_id } else { id });
+        if let Some(id) = active_id {
+            self.set_active_tab_by_id(id);
+        }
+
+        tracing::info!("Reconnected tab {} as {}", tab_id, new_id);
+        Ok(new_id)
+    }
+
     /// Mass connect to all sessions in a group
     pub fn mass_connect(&mut self, group_id: Uuid, runtime: &TokioRuntime) -> Vec<Result<Uuid, String>> {
         let session_ids = self
@@ -585,10 +946,122 @@ impl RedPillApp {
 
         session_ids
             .into_iter()
-            .map(|id| self.open_ssh_session(id, runtime))
+            .map(|id| self.open_session(id, runtime, false))
             .collect()
     }
 
+    /// Run `command` on SSH sessions without opening tabs
+    ///
+    /// Hosts run concurrently on `runtime`, over a shared connection when a
+    /// tab already has one. Sessions that aren't SSH are skipped. Nothing is
+    /// prompted for, so sessions without a saved secret fail to authenticate.
+    /// Resolves to (session name, result) per host, in the order given.
+    pub fn exec_on_sessions(
+        &self,
+        session_ids: &[Uuid],
+        command: &str,
+        runtime: &TokioRuntime,
+    ) -> impl std::future::Future<Output = Vec<(String, Result<ExecOutput, String>)>> + 'static {
+        let handles: Vec<_> = session_ids
+            .iter()
+            .filter_map(|&id| match self.session_manager.get_session(id) {
+                Some(Session::Ssh(ssh)) => Some((id, ssh.clone())),
+                _ => None,
+            })
+            .map(|(id, ssh)| {
+                let name = ssh.name.clone();
+                let mut backend = SshBackend::new(ssh);
+                backend.set_timeouts(self.config.ssh_timeouts);
+                if self.config.share_ssh_connections {
+                    backend.share_connection(self.ssh_pool.clone(), id);
+                }
+                let command = command.to_string();
+                let handle = runtime.spawn(async move {
+                    let result = backend.exec_capture(&command).await.map_err(|e| e.to_string());
+                    let _ = backend.close().await;
+                    result
+                });
+                (name, handle)
+            })
+            .collect();
+
+        async move {
+            let mut results = Vec::with_capacity(handles.len());
+            for (name, handle) in handles {
+                let result = handle.await.unwrap_or_else(|e| Err(e.to_string()));
+                results.push((name, result));
+            }
+            results
+        }
+    }
+
+    /// The open tabs, as saved for `restore_sessions`
+    pub fn workspace_state(&self) -> WorkspaceState {
+        WorkspaceState {
+            tabs: self
+                .tabs
+                .iter()
+                .map(|tab| WorkspaceTab {
+                    session_id: tab.session_id,
+                    title: tab.title.clone(),
+                    custom_title: tab.custom_title.clone(),
+                })
+                .collect(),
+            active_tab: self.active_tab,
+        }
+    }
+
+    /// Record the window geometry and open tabs in the config, if
+    /// `restore_sessions` is on
+    pub fn save_workspace(&mut self) {
+        if !self.config.restore_sessions {
+            return;
+        }
+        self.config.workspace = self.workspace_state();
+        if let Err(e) = self.config.save() {
+            tracing::warn!("Failed to save workspace: {}", e);
+        }
+    }
+
+    /// Reopen the tabs saved by [`save_workspace`](Self::save_workspace)
+    ///
+    /// Tabs whose session has since been deleted or fails to open are
+    /// skipped; local shell tabs come back as fresh shells.
+    pub fn restore_workspace(&mut self, runtime: &TokioRuntime) {
+        let workspace = self.config.workspace.clone();
+        let mut active_tab = None;
+        let mut restored = 0;
+
+        for (index, saved) in workspace.tabs.iter().enumerate() {
+            let opened = match saved.session_id {
+                Some(session_id) => self.open_session(session_id, runtime, false),
+                None => self.open_local_terminal(),
+            };
+            let tab_id = match opened {
+                Ok(tab_id) => tab_id,
+                Err(e) => {
+                    tracing::warn!("Failed to restore tab {:?}: {}", saved.title, e);
+                    continue;
+                }
+            };
+            if let Some(tab) = self.tabs.iter_mut().find(|t| t.id == tab_id) {
+                if saved.session_id.is_none() && !saved.title.is_empty() {
+                    tab.title = saved.title.clone();
+                }
+                tab.custom_title = saved.custom_title.clone();
+            }
+            if workspace.active_tab == Some(index) {
+                active_tab = Some(tab_id);
+            }
+            restored += 1;
+        }
+
+        if let Some(tab_id) = active_tab {
+            self.set_active_tab_by_id(tab_id);
+        }
+        tracing::info!("Restored {} of {} tabs", restored, workspace.tabs.len());
+    }
+
     /// Save application state
     pub fn save(&mut self) -> Result<(), String> {
         self.session_manager
@@ -623,13 +1096,15 @@ impl RedPillApp {
     }
 
     /// Add a new group
-    pub fn add_group(&mut self, name: String, parent_id: Option<Uuid>) -> Uuid {
+    pub fn add_group(&mut self, name: String, parent_id: Option<Uuid>) -> Result<Uuid, String> {
         let group = if let Some(pid) = parent_id {
             SessionGroup::new_nested(name, pid)
         } else {
             SessionGroup::new(name)
         };
-        self.session_manager.add_group(group)
+        self.session_manager
+            .add_group(group)
+            .map_err(|e| e.to_string())
     }
 
     /// Add a new SSH session
@@ -642,6 +1117,16 @@ impl RedPillApp {
         self.session_manager.add_local_session(session)
     }
 
+    /// Add a new serial session
+    pub fn add_serial_session(&mut self, session: SerialSession) -> Uuid {
+        self.session_manager.add_serial_session(session)
+    }
+
+    /// Add a new telnet session
+    pub fn add_telnet_session(&mut self, session: TelnetSession) -> Uuid {
+        self.session_manager.add_telnet_session(session)
+    }
+
     /// Add a new SSM session
     pub fn add_ssm_session(&mut self, session: SsmSession) -> Uuid {
         self.session_manager.add_ssm_session(session)
@@ -683,242 +1168,610 @@ impl RedPillApp {
     }
 }
 
-/// Combined SSH I/O loop using tokio::select! for concurrent read/write/resize
+/// Rotate to the next color scheme and briefly show its name
 ///
-/// This follows the recommended russh pattern where a single task handles
-/// both reading from the channel and writing user input, using select!
-/// to multiplex between them without locks.
-async fn spawn_ssh_io_loop(
-    terminal: std::sync::Weak<Mutex<Terminal>>,
-    backend: Arc<TokioMutex<SshBackend>>,
-    mut channel: russh::Channel<russh::client::Msg>,
-    mut write_rx: tokio::sync::mpsc::UnboundedReceiver<Vec<u8>>,
-    mut resize_rx: tokio::sync::mpsc::UnboundedReceiver<TerminalSize>,
-) {
-    loop {
-        tokio::select! {
-            // Handle user input (keyboard -> SSH)
-            Some(data) = write_rx.recv() => {
-                tracing::debug!("SSH write: sending {} bytes", data.len());
-                if let Err(e) = channel.data(&data[..]).await {
-                    tracing::error!("SSH write error: {}", e);
-                    break;
-                }
+/// Open terminals pick up the new scheme on their next render.
+pub fn cycle_theme(cx: &mut App) {
+    let Some(state) = cx.try_global::<AppState>() else {
+        return;
+    };
+    let name = state.app.lock().cycle_theme();
+    tracing::info!("Switched theme to {}", name);
+    cx.refresh_windows();
+
+    // Redraw once more so the notice disappears when it expires
+    cx.spawn(async move |cx: &mut AsyncApp| {
+        cx.background_executor().timer(NOTICE_DURATION).await;
+        let _ = cx.update(|cx| cx.refresh_windows());
+    })
+    .detach();
+}
+
+/// Import the hosts in `~/.ssh/config` into a new group and briefly show the outcome
+pub fn import_ssh_config(cx: &mut App) {
+    let Some(path) = crate::session::host_suggestions::ssh_config_path() else {
+        return;
+    };
+    let Some(state) = cx.try_global::<AppState>() else {
+        return;
+    };
+    {
+        let mut app = state.app.lock();
+        let notice = match app.session_manager.import_from_ssh_config(&path) {
+            Ok(result) if result.created.is_empty() && result.skipped.is_empty() => {
+                format!("No hosts found in {}", path.display())
             }
+            Ok(result) => match app.save() {
+                Ok(()) => result.summary(),
+                Err(e) => format!("Failed to save sessions: {}", e),
+            },
+            Err(e) => {
+                tracing::error!("Failed to import {}: {}", path.display(), e);
+                format!("Failed to import {}: {}", path.display(), e)
+            }
+        };
+        app.show_notice(notice);
+    }
+    cx.refresh_windows();
+
+    // Redraw once more so the notice disappears when it expires
+    cx.spawn(async move |cx: &mut AsyncApp| {
+        cx.background_executor().timer(NOTICE_DURATION).await;
+        let _ = cx.update(|cx| cx.refresh_windows());
+    })
+    .detach();
+}
 
-            // Handle resize requests (window resize -> SSH PTY)
-            Some(size) = resize_rx.recv() => {
-                tracing::debug!("SSH resize: sending {}x{}", size.cols, size.rows);
-                if let Err(e) = channel.window_change(
-                    size.cols as u32,
-                    size.rows as u32,
-                    size.pixel_width as u32,
-                    size.pixel_height as u32,
-                ).await {
-                    tracing::error!("SSH resize error: {}", e);
-                    // Don't break on resize error - connection may still be usable
+/// Ask where to save, then export every session to a portable JSON file
+///
+/// With `include_secrets`, saved passwords and passphrases are written in plain text.
+pub fn export_sessions(include_secrets: bool, cx: &mut App) {
+    let directory = dirs::document_dir().or_else(dirs::home_dir).unwrap_or_default();
+    let path = cx.prompt_for_new_path(&directory, Some("redpill-sessions.json"));
+    cx.spawn(async move |cx: &mut AsyncApp| {
+        let Ok(Ok(Some(path))) = path.await else {
+            return;
+        };
+        let _ = cx.update(|cx| {
+            let Some(state) = cx.try_global::<AppState>() else {
+                return;
+            };
+            let notice = match state.app.lock().session_manager.export(&path, include_secrets) {
+                Ok(count) => format!("Exported {} session{} to {}", count, if count == 1 { "" } else { "s" }, path.display()),
+                Err(e) => {
+                    tracing::error!("Failed to export sessions to {}: {}", path.display(), e);
+                    format!("Failed to export sessions: {}", e)
                 }
-            }
+            };
+            flash_notice(notice, cx);
+        });
+    })
+    .detach();
+}
 
-            // Handle SSH channel messages (SSH -> terminal)
-            msg = channel.wait() => {
-                match msg {
-                    Some(ChannelMsg::Data { data }) => {
-                        if let Some(term_arc) = terminal.upgrade() {
-                            let term = term_arc.lock();
-                            term.write_to_pty(&data);
-                        } else {
-                            tracing::info!("Terminal dropped, stopping SSH I/O");
-                            break;
-                        }
-                    }
-                    Some(ChannelMsg::ExtendedData { data, .. }) => {
-                        // Handle stderr
-                        if let Some(term_arc) = terminal.upgrade() {
-                            let term = term_arc.lock();
-                            term.write_to_pty(&data);
-                        } else {
-                            break;
-                        }
-                    }
-                    Some(ChannelMsg::Eof) => {
-                        tracing::info!("SSH channel EOF");
-                        break;
-                    }
-                    Some(ChannelMsg::Close) => {
-                        tracing::info!("SSH channel closed");
-                        break;
-                    }
-                    Some(ChannelMsg::ExitStatus { exit_status }) => {
-                        tracing::info!("Remote process exited with status: {}", exit_status);
-                        break;
-                    }
-                    Some(_) => {
-                        // Other protocol messages (WindowAdjust, Success, etc.)
-                        // Just continue - these don't need special handling
+/// Ask for an export file and add its groups and sessions
+pub fn import_sessions_file(cx: &mut App) {
+    let paths = cx.prompt_for_paths(PathPromptOptions {
+        files: true,
+        directories: false,
+        multiple: false,
+        prompt: Some("Import".into()),
+    });
+    cx.spawn(async move |cx: &mut AsyncApp| {
+        let Ok(Ok(Some(paths))) = paths.await else {
+            return;
+        };
+        let Some(path) = paths.into_iter().next() else {
+            return;
+        };
+        let _ = cx.update(|cx| {
+            let Some(state) = cx.try_global::<AppState>() else {
+                return;
+            };
+            let notice = {
+                let mut app = state.app.lock();
+                match app.session_manager.import(&path, MergeStrategy::Append) {
+                    Ok(result) => match app.save() {
+                        Ok(()) => result.summary(),
+                        Err(e) => format!("Failed to save sessions: {}", e),
+                    },
+                    Err(e) => {
+                        tracing::error!("Failed to import {}: {}", path.display(), e);
+                        format!("Failed to import {}: {}", path.display(), e)
                     }
-                    None => {
-                        tracing::info!("SSH channel closed (None)");
-                        break;
+                }
+            };
+            flash_notice(notice, cx);
+        });
+    })
+    .detach();
+}
+
+/// Ask for an Alacritty or iTerm2 theme file and add it as a custom color scheme
+pub fn import_theme_file(cx: &mut App) {
+    let paths = cx.prompt_for_paths(PathPromptOptions {
+        files: true,
+        directories: false,
+        multiple: false,
+        prompt: Some("Import".into()),
+    });
+    cx.spawn(async move |cx: &mut AsyncApp| {
+        let Ok(Ok(Some(paths))) = paths.await else {
+            return;
+        };
+        let Some(path) = paths.into_iter().next() else {
+            return;
+        };
+        let _ = cx.update(|cx| {
+            let Some(state) = cx.try_global::<AppState>() else {
+                return;
+            };
+            let notice = match import_theme(&path) {
+                Ok(mut scheme) => {
+                    let mut app = state.app.lock();
+                    let appearance = &mut app.config.appearance;
+                    scheme.name = appearance.unique_scheme_name(&scheme.name);
+                    let name = scheme.name.clone();
+                    match appearance.save_custom_scheme(None, scheme) {
+                        Ok(()) => match app.config.save() {
+                            Ok(()) => format!("Imported color scheme \"{}\"", name),
+                            Err(e) => format!("Failed to save config: {}", e),
+                        },
+                        Err(e) => format!("Failed to import {}: {}", path.display(), e),
                     }
                 }
+                Err(e) => {
+                    tracing::error!("Failed to import {}: {}", path.display(), e);
+                    format!("Failed to import {}: {}", path.display(), e)
+                }
+            };
+            flash_notice(notice, cx);
+        });
+    })
+    .detach();
+}
+
+/// Show a notice, then redraw again once it has expired
+pub fn flash_notice(notice: String, cx: &mut App) {
+    if let Some(state) = cx.try_global::<AppState>() {
+        state.app.lock().show_notice(notice);
+    }
+    cx.refresh_windows();
+
+    cx.spawn(async move |cx: &mut AsyncApp| {
+        cx.background_executor().timer(NOTICE_DURATION).await;
+        let _ = cx.update(|cx| cx.refresh_windows());
+    })
+    .detach();
+}
+
+/// Watch the config file and apply outside edits while running
+///
+/// The file is polled every [`CONFIG_POLL_INTERVAL`] and reloaded once it has
+/// gone a full interval without changing, so an editor's burst of writes is
+/// read once. Theme, scheme and display settings apply to open terminals on
+/// the next render; settings like the scrollback size apply to new tabs.
+pub fn watch_config(cx: &mut App) {
+    let Ok(path) = AppConfig::config_path() else {
+        return;
+    };
+    let modified = move || std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+
+    cx.spawn(async move |cx: &mut AsyncApp| {
+        let mut seen = modified();
+        let mut changed = false;
+        loop {
+            cx.background_executor().timer(CONFIG_POLL_INTERVAL).await;
+            let current = modified();
+            if current != seen {
+                // Still being written; wait for it to settle
+                seen = current;
+                changed = true;
+                continue;
+            }
+            if !changed {
+                continue;
+            }
+            changed = false;
+            // App is shutting down
+            if cx.update(reload_config).is_err() {
+                break;
+            }
+        }
+    })
+    .detach();
+}
+
+/// Replace the config with the file on disk, keeping it if the file is malformed
+fn reload_config(cx: &mut App) {
+    let Some(state) = cx.try_global::<AppState>() else {
+        return;
+    };
+    let reloaded = {
+        let mut app = state.app.lock();
+        match app.config.reload() {
+            Ok(Some(config)) => {
+                app.config = config;
+                true
+            }
+            Ok(None) => false,
+            Err(e) => {
+                tracing::error!("Ignoring config file change, keeping current settings: {}", e);
+                false
+            }
+        }
+    };
+    if reloaded {
+        cx.refresh_windows();
+    }
+}
+
+/// Reconnect every tab whose session failed or disconnected
+///
+/// Reconnects are staggered by [`RECONNECT_STAGGER`] so a burst of tabs doesn't
+/// hammer the network (or an SSO prompt) all at once. A summary is shown as a
+/// notice when the batch finishes.
+pub fn reconnect_dropped_sessions(cx: &mut App) {
+    let dropped = cx
+        .try_global::<AppState>()
+        .map(|state| state.app.lock().dropped_tabs())
+        .unwrap_or_default();
+    if dropped.is_empty() {
+        flash_notice("No dropped sessions to reconnect".to_string(), cx);
+        return;
+    }
+
+    tracing::info!("Reconnecting {} dropped sessions", dropped.len());
+    cx.spawn(async move |cx: &mut AsyncApp| {
+        let total = dropped.len();
+        let mut summary = ReconnectSummary::default();
+
+        for (i, tab_id) in dropped.into_iter().enumerate() {
+            if i > 0 {
+                cx.background_executor().timer(RECONNECT_STAGGER).await;
+            }
+            let result = cx.update_global::<AppState, _>(|state, cx| {
+                let result = state.app.lock().reconnect_tab(tab_id, &state.tokio_runtime);
+                cx.refresh_windows();
+                result
+            });
+            match result {
+                Ok(Ok(_)) => summary.reconnected += 1,
+                Ok(Err(e)) => {
+                    tracing::error!("Failed to reconnect tab {}: {}", tab_id, e);
+                    summary.failed.push(e);
+                }
+                // App is shutting down
+                Err(_) => break,
+            }
+        }
+
+        let notice = summary.notice(total);
+        tracing::info!("{}", notice);
+        let _ = cx.update(|cx| flash_notice(notice, cx));
+    })
+    .detach();
+}
+
+/// Record a session's connection state on its terminal (if still open)
+fn set_connection_status(terminal: &std::sync::Weak<Mutex<Terminal>>, status: ConnectionStatus) {
+    if let Some(term_arc) = terminal.upgrade() {
+        term_arc.lock().set_status(status);
+    }
+}
+
+/// Run a session's pre-connect command, showing progress in its terminal
+///
+/// Returns false, after writing the command's output to the terminal and
+/// marking the session failed, if the command didn't succeed.
+async fn run_pre_connect_hook(
+    terminal: &std::sync::Weak<Mutex<Terminal>>,
+    command: &str,
+    context: &HookContext,
+) -> bool {
+    if let Some(term_arc) = terminal.upgrade() {
+        let msg = format!("\x1b[2m  Running pre-connect command: {}\x1b[0m\r\n", command);
+        term_arc.lock().write_to_pty(msg.as_bytes());
+    }
+    let Err(e) = hooks::run_pre_connect(command, context).await else {
+        return true;
+    };
+
+    tracing::error!("Pre-connect command failed: {}", e);
+    if let Some(term_arc) = terminal.upgrade() {
+        let mut msg = format!(
+            "\r\n\x1b[1;31m  Pre-connect command failed\x1b[0m\r\n\r\n\x1b[33m  {}\x1b[0m\r\n",
+            e
+        );
+        if !e.output().is_empty() {
+            msg.push_str("\r\n");
+            for line in e.output().lines() {
+                msg.push_str(&format!("  {}\r\n", line));
             }
         }
+        term_arc.lock().write_to_pty(msg.as_bytes());
     }
+    set_connection_status(terminal, ConnectionStatus::Failed);
+    false
+}
 
-    // Clean up - close the channel
-    let _ = channel.eof().await;
-    let _ = channel.close().await;
+/// Answer a backend's auth prompts from what is typed in its terminal
+///
+/// Each prompt is written to the terminal and the terminal's input is
+/// captured until Enter (or Ctrl-C/Escape to cancel). Runs until the backend
+/// drops its prompter or the tab is closed.
+async fn serve_auth_prompts(
+    terminal: std::sync::Weak<Mutex<Terminal>>,
+    mut requests: tokio::sync::mpsc::UnboundedReceiver<AuthPromptRequest>,
+) {
+    while let Some(request) = requests.recv().await {
+        let (input_tx, mut input_rx) = tokio::sync::mpsc::unbounded_channel::<Vec<u8>>();
+        let Some(term_arc) = terminal.upgrade() else {
+            request.respond(None);
+            break;
+        };
+        {
+            let mut term = term_arc.lock();
+            let prompt = request.prompt.replace('\n', "\r\n");
+            term.write_to_pty(format!("\r\n{}", prompt).as_bytes());
+            term.capture_auth_input(input_tx);
+        }
+        drop(term_arc);
 
-    // Update backend state
-    let mut b = backend.lock().await;
-    let _ = b.close().await;
+        let mut line = LineInput::default();
+        let answer = loop {
+            // The sender lives in the terminal, so None means the tab is gone
+            let Some(data) = input_rx.recv().await else {
+                break None;
+            };
+            let (echo, event) = line.feed(&data, request.echo);
+            if let Some(term_arc) = terminal.upgrade() {
+                let term = term_arc.lock();
+                if !echo.is_empty() {
+                    term.write_to_pty(&echo);
+                }
+                if event.is_some() {
+                    term.write_to_pty(b"\r\n");
+                }
+            }
+            match event {
+                Some(LineEvent::Submitted(answer)) => break Some(answer),
+                Some(LineEvent::Cancelled) => break None,
+                None => {}
+            }
+        };
+        request.respond(answer);
+    }
 }
 
-/// Combined SSM I/O loop using tokio::select! for concurrent read/write/resize
+/// Hand a backend's host key questions to its terminal, which shows them in a dialog
 ///
-/// This handles the AWS SSM Session Manager WebSocket protocol, including:
-/// - Sending input data with proper SSM message framing
-/// - Receiving output data and parsing SSM message headers
-/// - Sending acknowledgements for received messages
-/// - Handling resize events
-async fn spawn_ssm_io_loop(
+/// Runs until the backend drops its prompter or the tab is closed; a question
+/// for a closed tab is rejected.
+async fn serve_host_key_prompts(
     terminal: std::sync::Weak<Mutex<Terminal>>,
-    backend: Arc<TokioMutex<SsmBackend>>,
-    ws_stream: crate::terminal::SsmWebSocket,
-    mut write_rx: tokio::sync::mpsc::UnboundedReceiver<Vec<u8>>,
-    mut resize_rx: tokio::sync::mpsc::UnboundedReceiver<TerminalSize>,
+    mut requests: tokio::sync::mpsc::UnboundedReceiver<HostKeyRequest>,
 ) {
-    let (mut ws_sink, mut ws_stream) = ws_stream.split();
-    let mut msg_builder = SsmMessageBuilder::new();
+    while let Some(request) = requests.recv().await {
+        let Some(term_arc) = terminal.upgrade() else {
+            request.respond(false);
+            break;
+        };
+        term_arc.lock().ask_host_key(request);
+    }
+}
+
+/// Connect a remote backend and pump its I/O until the session ends
+///
+/// A single task multiplexes user input, resize requests and backend output
+/// with select!, so no locks are held while waiting on the connection.
+/// Returns how the session ended, for its post-disconnect hook.
+async fn run_backend_session<B: TerminalBackend>(
+    terminal: std::sync::Weak<Mutex<Terminal>>,
+    backend: Arc<TokioMutex<B>>,
+) -> SessionEnd {
+    let initial_size = terminal
+        .upgrade()
+        .map(|term_arc| term_arc.lock().size())
+        .unwrap_or_default();
+
+    let (kind, io, notices) = {
+        let mut b = backend.lock().await;
+        let kind = b.kind();
+        if let Err(e) = b.connect(initial_size).await {
+            tracing::error!("{} connection failed: {}", kind.label(), e.message);
+            // Display error message in terminal with nice formatting
+            if let Some(term_arc) = terminal.upgrade() {
+                let term = term_arc.lock();
+                let error_msg = format!(
+                    "\x1b[2J\x1b[H\r\n\
+                    \x1b[1;31m  {}\x1b[0m\r\n\
+                    \r\n\
+                    \x1b[33m  {}\x1b[0m\r\n",
+                    e.title, e.message
+                );
+                term.write_to_pty(error_msg.as_bytes());
+            }
+            set_connection_status(&terminal, ConnectionStatus::Failed);
+            return SessionEnd::Failed;
+        }
+        (kind, b.io_channels(), b.connect_notices())
+    };
+
+    let mut io = match io {
+        Some(io) => io,
+        None => {
+            tracing::error!("Failed to get {} I/O channels", kind.label());
+            set_connection_status(&terminal, ConnectionStatus::Failed);
+            return SessionEnd::Failed;
+        }
+    };
+
+    // Point the terminal's write and resize senders at this loop, and get the
+    // current size to send immediately after setup
+    let (write_tx, mut write_rx) = tokio::sync::mpsc::unbounded_channel::<Vec<u8>>();
+    let (resize_tx, mut resize_rx) = tokio::sync::mpsc::unbounded_channel::<TerminalSize>();
+    let current_size = terminal.upgrade().map(|term_arc| {
+        let mut term = term_arc.lock();
+        term.set_write_tx(write_tx);
+        term.set_resize_tx(resize_tx);
+        term.size()
+    });
+
+    // This ensures the server gets correct dimensions even if the first
+    // UI paint happened before the channels were connected
+    if let Some(size) = current_size {
+        if size.cols > 0 && size.rows > 0 {
+            tracing::info!("{} immediate resize after channel setup: {}x{} ({}x{} px)",
+                kind.label(), size.cols, size.rows, size.pixel_width, size.pixel_height);
+            if let Err(e) = io.resize(size).await {
+                tracing::error!("{} immediate resize error: {}", kind.label(), e);
+            }
+        }
+    }
+
+    let _connection = metrics().connection_opened(kind);
+    set_connection_status(&terminal, ConnectionStatus::Connected);
+    if let Some(term_arc) = terminal.upgrade().filter(|_| !notices.is_empty()) {
+        let term = term_arc.lock();
+        for notice in notices {
+            term.print_notice(&format!("\x1b[33m  {}\x1b[0m\r\n", notice));
+        }
+    }
 
+    let mut end = SessionEnd::Closed;
     loop {
         tokio::select! {
-            // Handle user input (keyboard -> SSM)
-            Some(data) = write_rx.recv() => {
-                tracing::debug!("SSM write: sending {} bytes", data.len());
-                let msg = msg_builder.build_input(&data);
-                if let Err(e) = ws_sink.send(WsMessage::Binary(msg.into())).await {
-                    tracing::error!("SSM write error: {}", e);
+            // Handle user input (keyboard -> remote)
+            data = write_rx.recv() => {
+                // The sender lives in the terminal, so None means the tab is gone
+                let Some(data) = data else {
+                    tracing::info!("Terminal dropped, stopping {} I/O", kind.label());
+                    break;
+                };
+                tracing::debug!("{} write: sending {} bytes", kind.label(), data.len());
+                if let Err(e) = io.write(&data).await {
+                    tracing::error!("{} write error: {}", kind.label(), e);
                     break;
                 }
+                metrics().add_bytes_sent(kind, data.len());
             }
 
-            // Handle resize requests (window resize -> SSM)
+            // Handle resize requests (window resize -> remote PTY)
             Some(size) = resize_rx.recv() => {
-                tracing::debug!("SSM resize: sending {}x{}", size.cols, size.rows);
-                let msg = msg_builder.build_resize(size.cols, size.rows);
-                if let Err(e) = ws_sink.send(WsMessage::Binary(msg.into())).await {
-                    tracing::error!("SSM resize error: {}", e);
-                    // Don't break on resize error
+                tracing::debug!("{} resize: sending {}x{}", kind.label(), size.cols, size.rows);
+                if let Err(e) = io.resize(size).await {
+                    tracing::error!("{} resize error: {}", kind.label(), e);
+                    // Don't break on resize error - connection may still be usable
                 }
             }
 
-            // Handle SSM WebSocket messages (SSM -> terminal)
-            msg = ws_stream.next() => {
-                match msg {
-                    Some(Ok(WsMessage::Binary(data))) => {
-                        match handle_ssm_message(&data) {
-                            Ok((Some(output), ack_info)) => {
-                                // Write output to terminal
-                                if let Some(term_arc) = terminal.upgrade() {
-                                    let term = term_arc.lock();
-                                    term.write_to_pty(&output);
-                                } else {
-                                    tracing::info!("Terminal dropped, stopping SSM I/O");
-                                    break;
-                                }
-
-                                // Send ACK if required
-                                if let Some((msg_id, seq)) = ack_info {
-                                    let ack = msg_builder.build_ack(msg_id, seq);
-                                    if let Err(e) = ws_sink.send(WsMessage::Binary(ack.into())).await {
-                                        tracing::warn!("SSM ACK send error: {}", e);
-                                    }
-                                }
-                            }
-                            Ok((None, Some((msg_id, seq)))) => {
-                                // Non-output message that needs ACK
-                                let ack = msg_builder.build_ack(msg_id, seq);
-                                if let Err(e) = ws_sink.send(WsMessage::Binary(ack.into())).await {
-                                    tracing::warn!("SSM ACK send error: {}", e);
-                                }
-                            }
-                            Ok((None, None)) => {
-                                // No action needed
-                            }
-                            Err(e) => {
-                                tracing::warn!("SSM message parse error: {}", e);
-                                // Check if this is a session closed error
-                                if matches!(e, crate::terminal::SsmError::SessionClosed(_)) {
-                                    if let Some(term_arc) = terminal.upgrade() {
-                                        let term = term_arc.lock();
-                                        term.write_to_pty(b"\r\n\x1b[1;33m  Session closed by server\x1b[0m\r\n");
-                                    }
-                                    break;
-                                }
-                            }
-                        }
-                    }
-                    Some(Ok(WsMessage::Text(text))) => {
-                        // Text messages are usually control/status messages
-                        tracing::debug!("SSM text message: {}", text);
-                    }
-                    Some(Ok(WsMessage::Close(_))) => {
-                        tracing::info!("SSM WebSocket closed");
+            // Handle backend output (remote -> terminal)
+            read = io.read() => {
+                match read {
+                    BackendRead::Output(data) => {
+                        metrics().add_bytes_received(kind, data.len());
                         if let Some(term_arc) = terminal.upgrade() {
                             let term = term_arc.lock();
-                            term.write_to_pty(b"\r\n\x1b[1;33m  Connection closed\x1b[0m\r\n");
+                            term.write_to_pty(&data);
+                        } else {
+                            tracing::info!("Terminal dropped, stopping {} I/O", kind.label());
+                            break;
                         }
-                        break;
                     }
-                    Some(Ok(WsMessage::Ping(data))) => {
-                        // Respond to ping with pong
-                        if let Err(e) = ws_sink.send(WsMessage::Pong(data)).await {
-                            tracing::warn!("SSM pong send error: {}", e);
+                    BackendRead::Idle => {}
+                    BackendRead::Closed(notice) => {
+                        if let (Some(notice), Some(term_arc)) = (notice, terminal.upgrade()) {
+                            let msg = format!("\r\n\x1b[1;33m  {}\x1b[0m\r\n", notice);
+                            term_arc.lock().write_to_pty(msg.as_bytes());
                         }
+                        end = SessionEnd::Disconnected;
+                        break;
                     }
-                    Some(Ok(WsMessage::Pong(_))) | Some(Ok(WsMessage::Frame(_))) => {
-                        // Ignore pong and frame messages
+                    BackendRead::Exited(status) => {
+                        end = SessionEnd::Exited(status);
+                        break;
                     }
-                    Some(Err(e)) => {
-                        tracing::error!("SSM WebSocket error: {}", e);
+                    BackendRead::Failed(error) => {
                         if let Some(term_arc) = terminal.upgrade() {
-                            let term = term_arc.lock();
-                            let error_msg = format!("\r\n\x1b[1;31m  WebSocket error: {}\x1b[0m\r\n", e);
-                            term.write_to_pty(error_msg.as_bytes());
+                            let msg = format!("\r\n\x1b[1;31m  {}\x1b[0m\r\n", error);
+                            term_arc.lock().write_to_pty(msg.as_bytes());
+                        }
+                        if !backend.lock().await.reconnects() {
+                            break;
+                        }
+                        io.close().await;
+                        match attempt_reconnect(&terminal, &backend).await {
+                            Some(new_io) => {
+                                io = new_io;
+                                continue;
+                            }
+                            None => break,
                         }
-                        break;
-                    }
-                    None => {
-                        tracing::info!("SSM WebSocket stream ended");
-                        break;
                     }
                 }
+                // Send any acks or pongs the read produced
+                io.flush().await;
             }
         }
     }
 
-    // Clean up
-    let _ = ws_sink.close().await;
-    let mut b = backend.lock().await;
-    let _ = b.close().await;
+    set_connection_status(&terminal, ConnectionStatus::Disconnected);
+    tracing::info!("{} I/O loop ended", kind.label());
+
+    // Clean up - close the stream, then update backend state
+    io.close().await;
+    backend.lock().await.close().await;
+    end
 }
 
-/// Attempt to reconnect to SSH server with exponential backoff
+/// Send a session's keep-alive command whenever it has gone a full interval
+/// without input
 ///
-/// Returns true if reconnection succeeded and we should continue reading,
-/// false if reconnection failed or terminal was dropped.
-async fn attempt_reconnect(
+/// Runs until the session drops or its tab is closed. Nothing is sent before
+/// the session connects.
+async fn run_keep_alive(terminal: std::sync::Weak<Mutex<Terminal>>, keep_alive: KeepAliveCommand) {
+    let interval = keep_alive.interval();
+    let data = keep_alive.bytes();
+    let mut wait = interval;
+    loop {
+        tokio::time::sleep(wait).await;
+        let Some(term_arc) = terminal.upgrade() else {
+            break;
+        };
+        let term = term_arc.lock();
+        let status = term.status();
+        if status.is_dropped() {
+            break;
+        }
+
+        let idle = term.idle_time();
+        wait = if idle < interval {
+            interval - idle
+        } else {
+            if status == ConnectionStatus::Connected {
+                tracing::debug!("Sending keep-alive command after {:?} idle", idle);
+                term.write(&data);
+            }
+            interval
+        };
+    }
+}
+
+/// Attempt to reconnect a dropped backend with exponential backoff
+///
+/// Returns the new I/O stream, already sized to the terminal, or `None` if
+/// reconnection failed or the terminal was dropped.
+async fn attempt_reconnect<B: TerminalBackend>(
     terminal: &std::sync::Weak<Mutex<Terminal>>,
-    backend: &Arc<TokioMutex<SshBackend>>,
-) -> bool {
+    backend: &Arc<TokioMutex<B>>,
+) -> Option<Box<dyn BackendIo>> {
     // Check if terminal still exists
     let term_arc = match terminal.upgrade() {
         Some(t) => t,
         None => {
             tracing::info!("Terminal dropped during reconnection");
-            return false;
+            return None;
         }
     };
 
@@ -928,22 +1781,34 @@ async fn attempt_reconnect(
         let msg = "\r\n\x1b[1;33m  Connection lost. Attempting to reconnect...\x1b[0m\r\n";
         term.write_to_pty(msg.as_bytes());
     }
+    drop(term_arc);
+    set_connection_status(terminal, ConnectionStatus::Connecting);
 
     // Attempt reconnection
     let result = {
         let mut b = backend.lock().await;
-        b.reconnect().await
+        match b.reconnect().await {
+            Ok(()) => b.io_channels().ok_or_else(|| "no I/O stream after reconnecting".to_string()),
+            Err(e) => Err(e.message),
+        }
     };
 
     match result {
-        Ok(()) => {
+        Ok(mut io) => {
             // Display success message
             if let Some(term_arc) = terminal.upgrade() {
-                let term = term_arc.lock();
-                let msg = "\r\n\x1b[1;32m  Reconnected successfully!\x1b[0m\r\n";
-                term.write_to_pty(msg.as_bytes());
+                let size = {
+                    let term = term_arc.lock();
+                    let msg = "\r\n\x1b[1;32m  Reconnected successfully!\x1b[0m\r\n";
+                    term.write_to_pty(msg.as_bytes());
+                    term.size()
+                };
+                if let Err(e) = io.resize(size).await {
+                    tracing::error!("Resize after reconnecting failed: {}", e);
+                }
             }
-            true
+            set_connection_status(terminal, ConnectionStatus::Connected);
+            Some(io)
         }
         Err(e) => {
             // Display failure message
@@ -955,7 +1820,7 @@ async fn attempt_reconnect(
                 );
                 term.write_to_pty(msg.as_bytes());
             }
-            false
+            None
         }
     }
 }
@@ -970,8 +1835,27 @@ pub struct AppState {
 impl AppState {
     pub fn new() -> Self {
         let tokio_runtime = TokioRuntime::new().expect("Failed to create Tokio runtime");
+        let app = RedPillApp::new();
+
+        if app.config.metrics.enabled {
+            match app.config.metrics.listen_addr.parse() {
+                Ok(addr) => {
+                    tokio_runtime.spawn(async move {
+                        if let Err(e) = crate::metrics::serve(addr).await {
+                            tracing::error!("Metrics endpoint stopped: {}", e);
+                        }
+                    });
+                }
+                Err(e) => tracing::error!(
+                    "Invalid metrics listen address {:?}: {}",
+                    app.config.metrics.listen_addr,
+                    e
+                ),
+            }
+        }
+
         Self {
-            app: Arc::new(Mutex::new(RedPillApp::new())),
+            app: Arc::new(Mutex::new(app)),
             tokio_runtime: Arc::new(tokio_runtime),
         }
     }
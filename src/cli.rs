@@ -0,0 +1,231 @@
+//! Command-line arguments
+//!
+//! RedPill can be told what to open on startup, which makes it scriptable
+//! from shell aliases and launchers:
+//!
+//! ```text
+//! redpill ssh [-p PORT] [user@]host[:port]
+//! redpill --session "prod-db"
+//! redpill --ssm i-0123456789abcdef0 [--region REGION] [--profile PROFILE]
+//! redpill --pod POD [--context CONTEXT] [--namespace NAMESPACE] [--container NAME]
+//! redpill redpill://session/prod-db
+//! ```
+
+use thiserror::Error;
+
+use crate::session::{K8sSession, LinkError, SessionLink, SsmSession};
+
+/// Usage text for `--help`
+pub const USAGE: &str = "\
+Usage: redpill [TARGET]...
+
+Targets:
+  ssh [-p PORT] [user@]host[:port]   Connect over SSH (matches saved sessions)
+  --session NAME|ID                  Open a saved session
+  --ssm INSTANCE_ID                  Start an SSM session
+      --region REGION                  AWS region
+      --profile PROFILE                AWS profile
+  --pod POD                          Exec into a Kubernetes pod
+      --context CONTEXT                kubeconfig context (default: current)
+      --namespace NAMESPACE            Namespace (default: default)
+      --container NAME                 Container (default: first)
+  redpill://... | ssh://...          Open a session link
+
+Options:
+  -h, --help                         Show this help
+  -V, --version                      Show the version";
+
+/// Errors in the command line
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum CliError {
+    #[error("Missing value for {0}")]
+    MissingValue(String),
+
+    #[error("Unknown argument: {0}")]
+    UnknownArgument(String),
+
+    #[error("{0} needs --{1}")]
+    Requires(String, &'static str),
+
+    #[error(transparent)]
+    Link(#[from] LinkError),
+}
+
+/// Something to open on startup
+#[derive(Debug, Clone)]
+pub enum LaunchTarget {
+    /// A saved session or SSH host, resolved like a session link
+    Link(SessionLink),
+    /// A one-off SSM session
+    Ssm(SsmSession),
+    /// A one-off exec into a pod
+    K8s(K8sSession),
+}
+
+/// What the command line asks for
+#[derive(Debug, Default)]
+pub struct CliArgs {
+    /// Sessions to open, in order
+    pub targets: Vec<LaunchTarget>,
+    pub help: bool,
+    pub version: bool,
+}
+
+/// Options that apply to the `--ssm` or `--pod` target
+#[derive(Default)]
+struct Modifiers {
+    region: Option<String>,
+    profile: Option<String>,
+    context: Option<String>,
+    namespace: Option<String>,
+    container: Option<String>,
+}
+
+impl CliArgs {
+    /// Parse arguments (without the program name)
+    pub fn parse<I, S>(args: I) -> Result<Self, CliError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut args = args.into_iter().map(Into::into);
+        let mut parsed = CliArgs::default();
+        let mut ssm: Option<String> = None;
+        let mut pod: Option<String> = None;
+        let mut modifiers = Modifiers::default();
+
+        while let Some(arg) = args.next() {
+            // `--flag=value` is the same as `--flag value`
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+                _ => (arg.clone(), None),
+            };
+            let mut value = |name: &str| -> Result<String, CliError> {
+                inline
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| CliError::MissingValue(name.to_string()))
+            };
+
+            match flag.as_str() {
+                "-h" | "--help" => parsed.help = true,
+                "-V" | "--version" => parsed.version = true,
+                "--session" => {
+                    let target = value("--session")?;
+                    parsed.targets.push(LaunchTarget::Link(SessionLink::Saved(target)));
+                }
+                "--ssm" => ssm = Some(value("--ssm")?),
+                "--region" => modifiers.region = Some(value("--region")?),
+                "--profile" => modifiers.profile = Some(value("--profile")?),
+                "--pod" => pod = Some(value("--pod")?),
+                "--context" => modifiers.context = Some(value("--context")?),
+                "-n" | "--namespace" => modifiers.namespace = Some(value("--namespace")?),
+                "-c" | "--container" => modifiers.container = Some(value("--container")?),
+                "ssh" => {
+                    let mut port = None;
+                    let mut destination = value("ssh")?;
+                    if destination == "-p" {
+                        port = Some(value("-p")?);
+                        destination = value("ssh")?;
+                    }
+                    let url = match port {
+                        Some(port) => format!("ssh://{}:{}", destination, port),
+                        None => format!("ssh://{}", destination),
+                    };
+                    parsed.targets.push(LaunchTarget::Link(SessionLink::parse(&url)?));
+                }
+                // Finder adds a process serial number on older macOS
+                _ if arg.starts_with("-psn_") => {}
+                _ if SessionLink::is_link(&arg) => {
+                    parsed.targets.push(LaunchTarget::Link(SessionLink::parse(&arg)?));
+                }
+                _ => return Err(CliError::UnknownArgument(arg)),
+            }
+        }
+
+        if let Some(instance_id) = ssm {
+            parsed.targets.push(LaunchTarget::Ssm(SsmSession::with_config(
+                instance_id.as_str(),
+                instance_id.as_str(),
+                modifiers.region.take(),
+                modifiers.profile.take(),
+            )));
+        }
+        if let Some(pod) = pod {
+            // An empty context means the kubeconfig's current context
+            let mut session = K8sSession::new(
+                pod.as_str(),
+                modifiers.context.take().unwrap_or_default(),
+                modifiers.namespace.take().unwrap_or_else(|| "default".to_string()),
+                pod.as_str(),
+            );
+            session.container = modifiers.container.take();
+            parsed.targets.push(LaunchTarget::K8s(session));
+        }
+
+        // Options left over had no target to apply to, which is almost certainly a mistake
+        for (flag, set, target) in [
+            ("--region", modifiers.region.is_some(), "ssm"),
+            ("--profile", modifiers.profile.is_some(), "ssm"),
+            ("--context", modifiers.context.is_some(), "pod"),
+            ("--namespace", modifiers.namespace.is_some(), "pod"),
+            ("--container", modifiers.container.is_some(), "pod"),
+        ] {
+            if set {
+                return Err(CliError::Requires(flag.to_string(), target));
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ssh_and_saved_sessions() {
+        let args = CliArgs::parse(["ssh", "-p", "2222", "deploy@web1", "--session=prod-db"]).unwrap();
+        assert_eq!(args.targets.len(), 2);
+        assert!(matches!(
+            &args.targets[0],
+            LaunchTarget::Link(SessionLink::Ssh { host, port: Some(2222), username: Some(user) })
+                if host == "web1" && user == "deploy"
+        ));
+        assert!(matches!(&args.targets[1], LaunchTarget::Link(SessionLink::Saved(name)) if name == "prod-db"));
+        assert!(CliArgs::parse(Vec::<String>::new()).unwrap().targets.is_empty());
+    }
+
+    #[test]
+    fn test_parse_ssm_and_k8s() {
+        let args = CliArgs::parse([
+            "--ssm", "i-abc", "--region", "eu-west-1", "--pod", "api-0", "--namespace", "prod",
+        ])
+        .unwrap();
+        let [LaunchTarget::Ssm(ssm), LaunchTarget::K8s(k8s)] = args.targets.as_slice() else {
+            panic!("expected an SSM and a K8s target, got {:?}", args.targets);
+        };
+        assert_eq!(ssm.instance_id, "i-abc");
+        assert_eq!(ssm.region.as_deref(), Some("eu-west-1"));
+        assert_eq!(k8s.pod, "api-0");
+        assert_eq!(k8s.namespace, "prod");
+        assert!(k8s.context.is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            CliArgs::parse(["--session"]).unwrap_err(),
+            CliError::MissingValue("--session".into())
+        );
+        assert_eq!(
+            CliArgs::parse(["--namespace", "prod"]).unwrap_err(),
+            CliError::Requires("--namespace".into(), "pod")
+        );
+        assert_eq!(
+            CliArgs::parse(["--bogus"]).unwrap_err(),
+            CliError::UnknownArgument("--bogus".into())
+        );
+    }
+}
@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use thiserror::Error;
+use uuid::Uuid;
 
 /// Errors that can occur during config operations
 #[derive(Debug, Error)]
@@ -16,6 +18,19 @@ pub enum ConfigError {
     ConfigDirNotFound,
 }
 
+/// Reasons a custom color scheme can't be saved
+#[derive(Debug, Error, PartialEq)]
+pub enum SchemeError {
+    #[error("Scheme name is required")]
+    EmptyName,
+
+    #[error("\"{0}\" is a built-in scheme; pick another name")]
+    BuiltinName(String),
+
+    #[error("A scheme named \"{0}\" already exists")]
+    DuplicateName(String),
+}
+
 /// Window state for persistence
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WindowState {
@@ -43,6 +58,31 @@ impl Default for WindowState {
     }
 }
 
+/// Tabs open when RedPill last quit, reopened at launch with `restore_sessions`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceState {
+    /// Open tabs, in tab bar order
+    #[serde(default)]
+    pub tabs: Vec<WorkspaceTab>,
+    /// Index of the active tab
+    #[serde(default)]
+    pub active_tab: Option<usize>,
+}
+
+/// A tab saved in the workspace
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceTab {
+    /// Saved session the tab connects to (None = a local shell)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<Uuid>,
+    /// Title of a local shell tab
+    #[serde(default)]
+    pub title: String,
+    /// Name the user gave the tab
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_title: Option<String>,
+}
+
 /// Terminal appearance settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TerminalAppearance {
@@ -58,8 +98,38 @@ pub struct TerminalAppearance {
     pub max_font_size: f32,
     /// Line height multiplier
     pub line_height: f32,
+    /// Let the font join character sequences like `->` or `!=` into ligatures
+    ///
+    /// Off by default, as in most terminals. Fonts built for coding keep one
+    /// glyph per cell either way, so the grid stays aligned.
+    #[serde(default)]
+    pub ligatures: bool,
+    /// Shape of the focused cursor, unless the application picks one
+    #[serde(default)]
+    pub cursor_shape: CursorShape,
+    /// Blink the focused cursor; applications can still ask for a steady one
+    #[serde(default = "default_true")]
+    pub cursor_blink: bool,
+    /// Time the cursor stays on, then off, while blinking
+    #[serde(default = "default_cursor_blink_interval_ms")]
+    pub cursor_blink_interval_ms: u64,
+    /// Copy selected text to the clipboard as soon as the mouse is released
+    #[serde(default)]
+    pub copy_on_select: bool,
+    /// Paste the clipboard with the middle mouse button
+    #[serde(default)]
+    pub middle_click_paste: bool,
     /// Theme name
     pub theme: String,
+    /// User-defined color schemes, selectable by name alongside the built-ins
+    #[serde(default)]
+    pub custom_schemes: Vec<ColorScheme>,
+    /// Schemes to list first, in this order; the rest follow in their usual order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scheme_order: Vec<String>,
+    /// Schemes left out of the View menu, theme cycling and session dialogs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hidden_schemes: Vec<String>,
 }
 
 fn default_min_font_size() -> f32 {
@@ -70,6 +140,10 @@ fn default_max_font_size() -> f32 {
     32.0
 }
 
+fn default_cursor_blink_interval_ms() -> u64 {
+    500
+}
+
 impl Default for TerminalAppearance {
     fn default() -> Self {
         Self {
@@ -78,7 +152,16 @@ impl Default for TerminalAppearance {
             min_font_size: 8.0,
             max_font_size: 32.0,
             line_height: 1.2,
+            ligatures: false,
+            cursor_shape: CursorShape::Block,
+            cursor_blink: true,
+            cursor_blink_interval_ms: 500,
+            copy_on_select: false,
+            middle_click_paste: false,
             theme: "default".to_string(),
+            custom_schemes: Vec::new(),
+            scheme_order: Vec::new(),
+            hidden_schemes: Vec::new(),
         }
     }
 }
@@ -101,15 +184,152 @@ impl TerminalAppearance {
 
     /// Get the current color scheme
     pub fn color_scheme(&self) -> ColorScheme {
-        ColorScheme::builtin(&self.theme).unwrap_or_else(ColorScheme::default_dark)
+        self.find_scheme(&self.theme).unwrap_or_else(ColorScheme::default_dark)
+    }
+
+    /// Look up a built-in or custom scheme by name
+    pub fn find_scheme(&self, name: &str) -> Option<ColorScheme> {
+        ColorScheme::builtin(name).or_else(|| {
+            self.custom_schemes.iter().find(|s| s.name == name).cloned()
+        })
+    }
+
+    /// Names of all available schemes
+    ///
+    /// Names in `scheme_order` come first; the rest follow with built-ins
+    /// before custom schemes.
+    pub fn scheme_names(&self) -> Vec<String> {
+        let mut available: Vec<String> = ColorScheme::builtin_names().iter().map(|n| n.to_string()).collect();
+        for scheme in &self.custom_schemes {
+            if !available.contains(&scheme.name) {
+                available.push(scheme.name.clone());
+            }
+        }
+
+        let mut names: Vec<String> = Vec::with_capacity(available.len());
+        for name in &self.scheme_order {
+            if available.contains(name) && !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+        for name in available {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// Schemes offered in menus and selectors, in display order
+    ///
+    /// Hiding every scheme would leave nothing to pick, so in that case all
+    /// of them are shown.
+    pub fn visible_scheme_names(&self) -> Vec<String> {
+        let names = self.scheme_names();
+        let visible: Vec<String> = names
+            .iter()
+            .filter(|name| !self.hidden_schemes.contains(name))
+            .cloned()
+            .collect();
+        if visible.is_empty() {
+            names
+        } else {
+            visible
+        }
     }
 
     /// Set color scheme by name
     pub fn set_scheme(&mut self, name: &str) {
-        if ColorScheme::builtin(name).is_some() {
+        if self.find_scheme(name).is_some() {
             self.theme = name.to_string();
         }
     }
+
+    /// Add a custom scheme, or replace the one called `original`
+    ///
+    /// A renamed scheme keeps its place in the theme, ordering and hidden
+    /// lists under the new name.
+    pub fn save_custom_scheme(&mut self, original: Option<&str>, mut scheme: ColorScheme) -> Result<(), SchemeError> {
+        scheme.name = scheme.name.trim().to_string();
+        let name = scheme.name.clone();
+        if name.is_empty() {
+            return Err(SchemeError::EmptyName);
+        }
+        if ColorScheme::builtin(&name).is_some() {
+            return Err(SchemeError::BuiltinName(name));
+        }
+        if original != Some(name.as_str()) && self.custom_schemes.iter().any(|s| s.name == name) {
+            return Err(SchemeError::DuplicateName(name));
+        }
+
+        match original.and_then(|old| self.custom_schemes.iter().position(|s| s.name == old)) {
+            Some(index) => {
+                let old = std::mem::replace(&mut self.custom_schemes[index], scheme).name;
+                if old != name {
+                    if self.theme == old {
+                        self.theme = name.clone();
+                    }
+                    for listed in self.scheme_order.iter_mut().chain(self.hidden_schemes.iter_mut()) {
+                        if *listed == old {
+                            *listed = name.clone();
+                        }
+                    }
+                }
+            }
+            None => self.custom_schemes.push(scheme),
+        }
+        Ok(())
+    }
+
+    /// `base`, or `base 2`, `base 3`, ... if a scheme already has that name
+    pub fn unique_scheme_name(&self, base: &str) -> String {
+        let base = match base.trim() {
+            "" => "Imported",
+            trimmed => trimmed,
+        };
+        let names = self.scheme_names();
+        let mut name = base.to_string();
+        let mut suffix = 2;
+        while names.contains(&name) {
+            name = format!("{} {}", base, suffix);
+            suffix += 1;
+        }
+        name
+    }
+
+    /// Delete a custom scheme, falling back to the default theme if it was in use
+    pub fn remove_custom_scheme(&mut self, name: &str) {
+        self.custom_schemes.retain(|s| s.name != name);
+        self.scheme_order.retain(|n| n != name);
+        self.hidden_schemes.retain(|n| n != name);
+        if self.theme == name {
+            self.theme = "default".to_string();
+        }
+    }
+
+    /// Switch to the next visible scheme and return its name
+    pub fn cycle_scheme(&mut self) -> String {
+        let names = self.visible_scheme_names();
+        let next = names
+            .iter()
+            .position(|n| *n == self.theme)
+            .map(|i| (i + 1) % names.len())
+            .unwrap_or(0);
+        self.theme = names[next].clone();
+        self.theme.clone()
+    }
+}
+
+/// Shape of the terminal cursor
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CursorShape {
+    #[default]
+    Block,
+    /// Thin vertical bar before the character
+    Bar,
+    /// Line under the character
+    Underline,
 }
 
 /// Terminal color scheme
@@ -253,10 +473,113 @@ impl ColorScheme {
         }
     }
 
+    /// Labels of the editable colors, in the order of [`colors`](Self::colors)
+    pub const COLOR_LABELS: [&'static str; 19] = [
+        "Foreground",
+        "Background",
+        "Cursor",
+        "Black",
+        "Red",
+        "Green",
+        "Yellow",
+        "Blue",
+        "Magenta",
+        "Cyan",
+        "White",
+        "Bright Black",
+        "Bright Red",
+        "Bright Green",
+        "Bright Yellow",
+        "Bright Blue",
+        "Bright Magenta",
+        "Bright Cyan",
+        "Bright White",
+    ];
+
+    /// Every color: foreground, background, cursor, then the 16 ANSI colors
+    pub fn colors(&self) -> [u32; 19] {
+        [
+            self.foreground,
+            self.background,
+            self.cursor,
+            self.black,
+            self.red,
+            self.green,
+            self.yellow,
+            self.blue,
+            self.magenta,
+            self.cyan,
+            self.white,
+            self.bright_black,
+            self.bright_red,
+            self.bright_green,
+            self.bright_yellow,
+            self.bright_blue,
+            self.bright_magenta,
+            self.bright_cyan,
+            self.bright_white,
+        ]
+    }
+
+    /// Set the color at `index` in [`colors`](Self::colors) order
+    pub fn set_color(&mut self, index: usize, color: u32) {
+        let slots = [
+            &mut self.foreground,
+            &mut self.background,
+            &mut self.cursor,
+            &mut self.black,
+            &mut self.red,
+            &mut self.green,
+            &mut self.yellow,
+            &mut self.blue,
+            &mut self.magenta,
+            &mut self.cyan,
+            &mut self.white,
+            &mut self.bright_black,
+            &mut self.bright_red,
+            &mut self.bright_green,
+            &mut self.bright_yellow,
+            &mut self.bright_blue,
+            &mut self.bright_magenta,
+            &mut self.bright_cyan,
+            &mut self.bright_white,
+        ];
+        if let Some(slot) = slots.into_iter().nth(index) {
+            *slot = color & 0xffffff;
+        }
+    }
+
+    /// Parse a `#rrggbb` (or `rrggbb`) color
+    pub fn parse_hex(text: &str) -> Option<u32> {
+        let hex = text.trim();
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        u32::from_str_radix(hex, 16).ok()
+    }
+
+    /// Format a color as `#rrggbb`
+    pub fn hex(color: u32) -> String {
+        format!("#{:06x}", color & 0xffffff)
+    }
+
     /// List all built-in scheme names
     pub fn builtin_names() -> &'static [&'static str] {
         &["default", "light", "matrix", "red"]
     }
+
+    /// Name to show in menus: built-ins are capitalized, custom names kept as written
+    pub fn display_name(name: &str) -> String {
+        if !Self::builtin_names().contains(&name) {
+            return name.to_string();
+        }
+        let mut chars = name.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => String::new(),
+        }
+    }
 }
 
 /// Session tree panel settings
@@ -266,6 +589,12 @@ pub struct SessionTreeSettings {
     pub width: u32,
     /// Whether the panel is visible
     pub visible: bool,
+    /// Seconds between pod list refreshes in an expanded namespace (0 = never)
+    #[serde(default = "default_pod_refresh_secs")]
+    pub pod_refresh_secs: u64,
+    /// Order of sessions within each group
+    #[serde(default)]
+    pub sort: SessionSort,
 }
 
 impl Default for SessionTreeSettings {
@@ -273,10 +602,27 @@ impl Default for SessionTreeSettings {
         Self {
             width: 250,
             visible: true,
+            pod_refresh_secs: default_pod_refresh_secs(),
+            sort: SessionSort::default(),
         }
     }
 }
 
+fn default_pod_refresh_secs() -> u64 {
+    15
+}
+
+/// How sessions are ordered in the session tree
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SessionSort {
+    /// The order they were added or dragged into
+    #[default]
+    Manual,
+    /// Most recently connected first; never-connected sessions last
+    Recent,
+}
+
 /// Agent panel settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentPanelSettings {
@@ -295,6 +641,226 @@ impl Default for AgentPanelSettings {
     }
 }
 
+/// Metrics endpoint settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsSettings {
+    /// Whether to serve Prometheus metrics
+    #[serde(default)]
+    pub enabled: bool,
+    /// Address to listen on (keep it on loopback)
+    #[serde(default = "default_metrics_listen_addr")]
+    pub listen_addr: String,
+}
+
+impl Default for MetricsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen_addr: default_metrics_listen_addr(),
+        }
+    }
+}
+
+fn default_metrics_listen_addr() -> String {
+    "127.0.0.1:9464".to_string()
+}
+
+/// Session transcript recording settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingSettings {
+    /// Record every session by default (sessions can override this)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Directory for transcript files (defaults to `<config dir>/transcripts`)
+    #[serde(default)]
+    pub directory: Option<PathBuf>,
+    /// Strip ANSI escape sequences from recorded output
+    #[serde(default = "default_true")]
+    pub strip_ansi: bool,
+    /// Also record keyboard input.
+    ///
+    /// Anything typed is captured, including passwords entered at prompts.
+    #[serde(default)]
+    pub record_input: bool,
+    /// Rotate transcript files once they reach this size (0 = unlimited)
+    #[serde(default = "default_max_transcript_size_mb")]
+    pub max_file_size_mb: u64,
+}
+
+impl Default for RecordingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: None,
+            strip_ansi: true,
+            record_input: false,
+            max_file_size_mb: default_max_transcript_size_mb(),
+        }
+    }
+}
+
+impl RecordingSettings {
+    /// Directory transcripts are written to
+    pub fn directory(&self) -> std::io::Result<PathBuf> {
+        match &self.directory {
+            Some(dir) => Ok(dir.clone()),
+            None => AppConfig::config_dir()
+                .map(|dir| dir.join("transcripts"))
+                .map_err(|e| std::io::Error::other(e.to_string())),
+        }
+    }
+}
+
+fn default_max_transcript_size_mb() -> u64 {
+    50
+}
+
+/// Desktop notification settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationSettings {
+    /// Post OS notifications at all
+    #[serde(default)]
+    pub enabled: bool,
+    /// Notify when a tab that isn't focused rings the bell
+    #[serde(default = "default_true")]
+    pub on_bell: bool,
+    /// Notify when a command finishes (needs shell integration emitting OSC 133)
+    #[serde(default = "default_true")]
+    pub on_command_finish: bool,
+    /// Only notify for commands that ran at least this long
+    #[serde(default = "default_min_command_secs")]
+    pub min_command_secs: u64,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            on_bell: true,
+            on_command_finish: true,
+            min_command_secs: default_min_command_secs(),
+        }
+    }
+}
+
+fn default_min_command_secs() -> u64 {
+    10
+}
+
+/// SSH connection timeouts and keepalives
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SshTimeouts {
+    /// Seconds allowed for the SSH handshake, not counting time spent on a
+    /// host key question
+    #[serde(default = "default_connect_timeout_secs")]
+    pub connect_secs: u64,
+    /// Seconds without any traffic before the connection is dropped (0 = never)
+    #[serde(default = "default_inactivity_timeout_secs")]
+    pub inactivity_secs: u64,
+    /// Seconds of silence before the server is asked if it's still there (0 = never)
+    #[serde(default = "default_keepalive_interval_secs")]
+    pub keepalive_interval_secs: u64,
+    /// Unanswered keepalives before the connection is given up on
+    #[serde(default = "default_keepalive_max")]
+    pub keepalive_max: usize,
+}
+
+impl Default for SshTimeouts {
+    fn default() -> Self {
+        Self {
+            connect_secs: default_connect_timeout_secs(),
+            inactivity_secs: default_inactivity_timeout_secs(),
+            keepalive_interval_secs: default_keepalive_interval_secs(),
+            keepalive_max: default_keepalive_max(),
+        }
+    }
+}
+
+impl SshTimeouts {
+    /// Time allowed for the handshake; at least a second
+    pub fn connect_timeout(&self) -> Duration {
+        Duration::from_secs(self.connect_secs.max(1))
+    }
+
+    pub fn inactivity_timeout(&self) -> Option<Duration> {
+        (self.inactivity_secs > 0).then(|| Duration::from_secs(self.inactivity_secs))
+    }
+
+    pub fn keepalive_interval(&self) -> Option<Duration> {
+        (self.keepalive_interval_secs > 0).then(|| Duration::from_secs(self.keepalive_interval_secs))
+    }
+}
+
+fn default_connect_timeout_secs() -> u64 {
+    5
+}
+
+fn default_inactivity_timeout_secs() -> u64 {
+    300
+}
+
+fn default_keepalive_interval_secs() -> u64 {
+    30
+}
+
+fn default_keepalive_max() -> usize {
+    3
+}
+
+/// ZMODEM (lrzsz `sz`/`rz`) file transfer settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ZmodemSettings {
+    /// Watch remote output for ZMODEM transfers and offer to accept them
+    #[serde(default)]
+    pub enabled: bool,
+    /// Where received files are saved (defaults to the Downloads folder)
+    #[serde(default)]
+    pub download_dir: Option<PathBuf>,
+}
+
+impl ZmodemSettings {
+    /// Directory received files go to
+    pub fn download_dir(&self) -> PathBuf {
+        self.download_dir
+            .clone()
+            .or_else(dirs::download_dir)
+            .or_else(dirs::home_dir)
+            .unwrap_or_else(|| PathBuf::from("."))
+    }
+}
+
+/// Separator line written into the scrollback when a session connects
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionMarkerSettings {
+    /// Write a marker on every connect and reconnect
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Marker text: `{event}` becomes "Connected" or "Reconnected", `{time}`
+    /// the time (UTC)
+    #[serde(default = "default_connection_marker_format")]
+    pub format: String,
+}
+
+impl Default for ConnectionMarkerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            format: default_connection_marker_format(),
+        }
+    }
+}
+
+impl ConnectionMarkerSettings {
+    /// The marker format, or `None` when markers are off
+    pub fn active_format(&self) -> Option<String> {
+        self.enabled.then(|| self.format.clone())
+    }
+}
+
+fn default_connection_marker_format() -> String {
+    "── {event} {time} ──".to_string()
+}
+
 /// Keyboard shortcut definitions
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KeyBindings {
@@ -328,6 +894,29 @@ impl Default for KeyBindings {
     }
 }
 
+/// What happens to a local terminal's tab when its shell exits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ShellExitAction {
+    /// Close the tab
+    Close,
+    /// Leave the tab open with a notice showing the exit status
+    #[default]
+    Keep,
+    /// Start a new shell in the same tab
+    Restart,
+}
+
+/// A method to try when a session's keys are rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AuthFallback {
+    /// Answer the server's questions (usually a password or one-time code)
+    KeyboardInteractive,
+    /// Send a password typed at the prompt
+    Password,
+}
+
 /// Application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
@@ -355,17 +944,119 @@ pub struct AppConfig {
     #[serde(default = "default_scrollback_lines")]
     pub scrollback_lines: usize,
 
+    /// Per-terminal scrollback memory cap in megabytes (0 = no cap)
+    ///
+    /// When a terminal's buffer grows past this, its oldest history is
+    /// trimmed even if `scrollback_lines` hasn't been reached.
+    #[serde(default)]
+    pub scrollback_memory_mb: usize,
+
     /// Whether to confirm before closing tabs
     #[serde(default = "default_true")]
     pub confirm_close: bool,
 
-    /// Whether to restore sessions on startup
+    /// Ask before Ctrl-C, Ctrl-D or Ctrl-Z is broadcast to several sessions
+    #[serde(default = "default_true")]
+    pub confirm_broadcast_control_keys: bool,
+
+    /// Ask before turning on broadcast input for more than this many tabs
+    /// (0 = always ask)
+    #[serde(default = "default_confirm_broadcast_above")]
+    pub confirm_broadcast_above: usize,
+
+    /// Whether to reopen the last window size, position and tabs on startup
     #[serde(default)]
     pub restore_sessions: bool,
 
+    /// Tabs saved at quit for `restore_sessions`
+    #[serde(default)]
+    pub workspace: WorkspaceState,
+
     /// Whether to show scrollbar indicator
     #[serde(default = "default_true")]
     pub show_scrollbar: bool,
+
+    /// Mark lines that soft-wrap with a faint bar in the rightmost column
+    #[serde(default)]
+    pub show_wrap_indicator: bool,
+
+    /// Draw a faint vertical ruler after this many columns (0 = no ruler)
+    #[serde(default)]
+    pub column_ruler: usize,
+
+    /// Blank space in pixels between the terminal grid and the view edges
+    #[serde(default)]
+    pub terminal_padding: f32,
+
+    /// Typing while scrolled back jumps to the live screen first
+    #[serde(default = "default_true")]
+    pub scroll_to_bottom_on_input: bool,
+
+    /// Columns between tab stops
+    #[serde(default = "default_tab_width")]
+    pub tab_width: usize,
+
+    /// Treat ambiguous-width characters (box drawing, arrows, many symbols)
+    /// as two cells wide, as CJK locales and fonts do
+    ///
+    /// Applies to remote sessions; local shells are parsed by alacritty's
+    /// PTY loop and always use the narrow width.
+    #[serde(default)]
+    pub ambiguous_width_wide: bool,
+
+    /// Rebuild a terminal's display when its tab regains focus
+    ///
+    /// Clears anything left over from while the tab was in the background
+    /// and moves the alternate screen (which has no scrollback) back to the
+    /// bottom.
+    #[serde(default = "default_true")]
+    pub refresh_on_focus: bool,
+
+    /// What to do when a local terminal's shell exits
+    #[serde(default)]
+    pub local_shell_exit: ShellExitAction,
+
+    /// Open further tabs for a saved SSH session as new channels on its
+    /// existing connection, like OpenSSH's `ControlMaster`
+    #[serde(default = "default_true")]
+    pub share_ssh_connections: bool,
+
+    /// Methods to offer, in order, when an SSH session's keys are rejected
+    ///
+    /// Each is only tried if the server still accepts it, and prompts for
+    /// its answers in the tab. Empty to fail straight away.
+    #[serde(default = "default_auth_fallback")]
+    pub auth_fallback: Vec<AuthFallback>,
+
+    /// SSH handshake timeout and keepalives
+    #[serde(default)]
+    pub ssh_timeouts: SshTimeouts,
+
+    /// Run the local commands sessions name for their connection events
+    ///
+    /// Off by default: an imported session file could otherwise run
+    /// anything on this machine.
+    #[serde(default)]
+    pub allow_session_hooks: bool,
+
+    /// Prometheus metrics endpoint
+    #[serde(default)]
+    pub metrics: MetricsSettings,
+
+    /// Session transcript recording
+    #[serde(default)]
+    pub recording: RecordingSettings,
+
+    /// Desktop notifications for bells and finished commands
+    #[serde(default)]
+    pub notifications: NotificationSettings,
+
+    /// ZMODEM file transfer over the shell
+    #[serde(default)]
+    pub zmodem: ZmodemSettings,
+    /// Separator written into the scrollback on connect and reconnect
+    #[serde(default)]
+    pub connection_marker: ConnectionMarkerSettings,
 }
 
 impl Default for AppConfig {
@@ -377,9 +1068,30 @@ impl Default for AppConfig {
             agent_panel: AgentPanelSettings::default(),
             keybindings: KeyBindings::default(),
             scrollback_lines: 10000,
+            scrollback_memory_mb: 0,
             confirm_close: true,
+            confirm_broadcast_control_keys: true,
+            confirm_broadcast_above: default_confirm_broadcast_above(),
             restore_sessions: false,
+            workspace: WorkspaceState::default(),
             show_scrollbar: true,
+            show_wrap_indicator: false,
+            column_ruler: 0,
+            terminal_padding: 0.0,
+            scroll_to_bottom_on_input: true,
+            tab_width: 8,
+            ambiguous_width_wide: false,
+            refresh_on_focus: true,
+            local_shell_exit: ShellExitAction::Keep,
+            share_ssh_connections: true,
+            auth_fallback: default_auth_fallback(),
+            ssh_timeouts: SshTimeouts::default(),
+            allow_session_hooks: false,
+            metrics: MetricsSettings::default(),
+            recording: RecordingSettings::default(),
+            notifications: NotificationSettings::default(),
+            zmodem: ZmodemSettings::default(),
+            connection_marker: ConnectionMarkerSettings::default(),
         }
     }
 }
@@ -388,6 +1100,18 @@ fn default_scrollback_lines() -> usize {
     10000
 }
 
+fn default_tab_width() -> usize {
+    8
+}
+
+fn default_auth_fallback() -> Vec<AuthFallback> {
+    vec![AuthFallback::KeyboardInteractive, AuthFallback::Password]
+}
+
+fn default_confirm_broadcast_above() -> usize {
+    4
+}
+
 fn default_true() -> bool {
     true
 }
@@ -445,6 +1169,30 @@ impl AppConfig {
         Ok(())
     }
 
+    /// Re-read the config file after it changed on disk
+    ///
+    /// Returns `Ok(None)` if the file is gone or holds exactly what
+    /// [`save`](Self::save) would write for `self`, so the app's own saves
+    /// aren't mistaken for edits. A file that doesn't parse is an error,
+    /// leaving the caller's settings as they were.
+    pub fn reload(&self) -> Result<Option<Self>, ConfigError> {
+        self.reload_from(&Self::config_path()?)
+    }
+
+    fn reload_from(&self, path: &Path) -> Result<Option<Self>, ConfigError> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        if contents == serde_json::to_string_pretty(self)? {
+            return Ok(None);
+        }
+        let config: Self = serde_json::from_str(&contents)?;
+        tracing::info!("Reloaded configuration from {:?}", path);
+        Ok(Some(config))
+    }
+
     /// Reset to defaults and save
     pub fn reset(&mut self) -> Result<(), ConfigError> {
         *self = Self::default();
@@ -462,7 +1210,152 @@ mod tests {
         let config = AppConfig::default();
         assert_eq!(config.window.width, 1200);
         assert_eq!(config.appearance.font_size, 13.0);
+        assert!(!config.appearance.ligatures);
+        assert_eq!(config.appearance.cursor_shape, CursorShape::Block);
+        assert!(config.appearance.cursor_blink);
+        assert_eq!(config.appearance.cursor_blink_interval_ms, 500);
+        assert!(!config.appearance.copy_on_select);
+        assert!(!config.appearance.middle_click_paste);
         assert_eq!(config.scrollback_lines, 10000);
+        assert_eq!(config.scrollback_memory_mb, 0);
+        assert_eq!(config.tab_width, 8);
+        assert!(!config.ambiguous_width_wide);
+        assert!(config.refresh_on_focus);
+        assert_eq!(config.local_shell_exit, ShellExitAction::Keep);
+        assert!(config.scroll_to_bottom_on_input);
+        assert!(!config.show_wrap_indicator);
+        assert_eq!(config.column_ruler, 0);
+        assert_eq!(config.terminal_padding, 0.0);
+        assert!(config.share_ssh_connections);
+        assert_eq!(config.auth_fallback, [AuthFallback::KeyboardInteractive, AuthFallback::Password]);
+        assert_eq!(config.ssh_timeouts.connect_timeout(), Duration::from_secs(5));
+        assert_eq!(config.ssh_timeouts.inactivity_timeout(), Some(Duration::from_secs(300)));
+        assert_eq!(config.ssh_timeouts.keepalive_interval(), Some(Duration::from_secs(30)));
+        assert_eq!(config.ssh_timeouts.keepalive_max, 3);
+        assert!(!config.allow_session_hooks);
+        assert_eq!(
+            config.connection_marker.active_format().as_deref(),
+            Some("── {event} {time} ──")
+        );
+        assert!(config.confirm_broadcast_control_keys);
+        assert_eq!(config.confirm_broadcast_above, 4);
+        assert!(!config.recording.enabled);
+        assert!(!config.recording.record_input);
+        assert!(!config.notifications.enabled);
+        assert_eq!(config.notifications.min_command_secs, 10);
+        assert!(!config.zmodem.enabled);
+    }
+
+    #[test]
+    fn test_cycle_scheme_includes_custom() {
+        let mut appearance = TerminalAppearance::default();
+        let mut custom = ColorScheme::default_dark();
+        custom.name = "solarized".to_string();
+        appearance.custom_schemes.push(custom);
+
+        let mut seen = Vec::new();
+        for _ in 0..appearance.scheme_names().len() {
+            seen.push(appearance.cycle_scheme());
+        }
+        assert_eq!(seen, vec!["light", "matrix", "red", "solarized", "default"]);
+
+        appearance.set_scheme("solarized");
+        assert_eq!(appearance.color_scheme().name, "solarized");
+        appearance.set_scheme("missing");
+        assert_eq!(appearance.theme, "solarized");
+    }
+
+    #[test]
+    fn test_scheme_order_and_hidden() {
+        let mut appearance = TerminalAppearance::default();
+        let mut custom = ColorScheme::default_dark();
+        custom.name = "nord".to_string();
+        appearance.custom_schemes.push(custom);
+        appearance.scheme_order = vec!["nord".into(), "missing".into(), "matrix".into()];
+        appearance.hidden_schemes = vec!["light".into(), "red".into()];
+
+        assert_eq!(appearance.scheme_names(), vec!["nord", "matrix", "default", "light", "red"]);
+        assert_eq!(appearance.visible_scheme_names(), vec!["nord", "matrix", "default"]);
+        assert_eq!(appearance.cycle_scheme(), "nord");
+        assert_eq!(ColorScheme::display_name("matrix"), "Matrix");
+        assert_eq!(ColorScheme::display_name("nord"), "nord");
+
+        // Hiding everything falls back to showing everything
+        appearance.hidden_schemes = appearance.scheme_names();
+        assert_eq!(appearance.visible_scheme_names().len(), 5);
+    }
+
+    #[test]
+    fn test_save_and_remove_custom_scheme() {
+        let mut appearance = TerminalAppearance::default();
+        let mut dracula = ColorScheme::default_dark();
+        dracula.name = " dracula ".to_string();
+        dracula.set_color(1, 0x282a36);
+        appearance.save_custom_scheme(None, dracula.clone()).unwrap();
+        assert_eq!(appearance.find_scheme("dracula").unwrap().background, 0x282a36);
+
+        assert_eq!(
+            appearance.save_custom_scheme(None, dracula.clone()),
+            Err(SchemeError::DuplicateName("dracula".to_string()))
+        );
+        dracula.name = "light".to_string();
+        assert_eq!(appearance.save_custom_scheme(None, dracula.clone()), Err(SchemeError::BuiltinName("light".to_string())));
+        dracula.name = "  ".to_string();
+        assert_eq!(appearance.save_custom_scheme(None, dracula.clone()), Err(SchemeError::EmptyName));
+
+        // Renaming follows the scheme into the theme and ordering
+        appearance.set_scheme("dracula");
+        appearance.scheme_order = vec!["dracula".to_string()];
+        dracula.name = "dracula-pro".to_string();
+        appearance.save_custom_scheme(Some("dracula"), dracula).unwrap();
+        assert_eq!(appearance.custom_schemes.len(), 1);
+        assert_eq!(appearance.theme, "dracula-pro");
+        assert_eq!(appearance.scheme_order, vec!["dracula-pro"]);
+        assert_eq!(appearance.unique_scheme_name("dracula-pro"), "dracula-pro 2");
+        assert_eq!(appearance.unique_scheme_name("matrix"), "matrix 2");
+        assert_eq!(appearance.unique_scheme_name(" nord "), "nord");
+
+        appearance.remove_custom_scheme("dracula-pro");
+        assert!(appearance.custom_schemes.is_empty());
+        assert!(appearance.scheme_order.is_empty());
+        assert_eq!(appearance.theme, "default");
+    }
+
+    #[test]
+    fn test_scheme_colors_and_hex() {
+        let mut scheme = ColorScheme::default_dark();
+        assert_eq!(scheme.colors()[0], scheme.foreground);
+        assert_eq!(scheme.colors()[18], scheme.bright_white);
+        scheme.set_color(4, 0xff5555);
+        assert_eq!(scheme.red, 0xff5555);
+        scheme.set_color(19, 0x123456);
+        assert_eq!(scheme.colors().len(), ColorScheme::COLOR_LABELS.len());
+
+        assert_eq!(ColorScheme::parse_hex("#FF5555"), Some(0xff5555));
+        assert_eq!(ColorScheme::parse_hex(" 282a36 "), Some(0x282a36));
+        assert_eq!(ColorScheme::parse_hex("#fff"), None);
+        assert_eq!(ColorScheme::parse_hex("#gggggg"), None);
+        assert_eq!(ColorScheme::hex(0x282a36), "#282a36");
+    }
+
+    #[test]
+    fn test_reload_from() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        let config = AppConfig::default();
+        assert!(config.reload_from(&path).unwrap().is_none());
+
+        // What the app itself saved isn't a change
+        fs::write(&path, serde_json::to_string_pretty(&config).unwrap()).unwrap();
+        assert!(config.reload_from(&path).unwrap().is_none());
+
+        fs::write(&path, r#"{"scrollback_lines": 500}"#).unwrap();
+        let reloaded = config.reload_from(&path).unwrap().unwrap();
+        assert_eq!(reloaded.scrollback_lines, 500);
+
+        // A half-written edit is an error, not a reset to defaults
+        fs::write(&path, r#"{"scrollback_lines": "#).unwrap();
+        assert!(config.reload_from(&path).is_err());
     }
 
     #[test]
@@ -473,5 +1366,33 @@ mod tests {
 
         assert_eq!(config.window.width, parsed.window.width);
         assert_eq!(config.appearance.font_family, parsed.appearance.font_family);
+        assert_eq!(config.auth_fallback, parsed.auth_fallback);
+        assert!(json.contains("\"keyboard-interactive\""));
+
+        let parsed: AppConfig = serde_json::from_str(r#"{"local_shell_exit":"restart"}"#).unwrap();
+        assert_eq!(parsed.local_shell_exit, ShellExitAction::Restart);
+        assert_eq!(parsed.workspace, WorkspaceState::default());
+
+        let session_id = Uuid::new_v4();
+        let config = AppConfig {
+            workspace: WorkspaceState {
+                tabs: vec![
+                    WorkspaceTab { session_id: Some(session_id), title: "web".to_string(), custom_title: Some("logs".to_string()) },
+                    WorkspaceTab { session_id: None, title: "Local".to_string(), custom_title: None },
+                ],
+                active_tab: Some(1),
+            },
+            ..AppConfig::default()
+        };
+        let parsed: AppConfig = serde_json::from_str(&serde_json::to_string(&config).unwrap()).unwrap();
+        assert_eq!(parsed.workspace, config.workspace);
+        assert_eq!(serde_json::from_str::<CursorShape>(r#""bar""#).unwrap(), CursorShape::Bar);
+
+        // Values left out keep their defaults; zero turns keepalives off
+        let parsed: AppConfig =
+            serde_json::from_str(r#"{"ssh_timeouts":{"keepalive_interval_secs":0,"inactivity_secs":3600}}"#).unwrap();
+        assert_eq!(parsed.ssh_timeouts.keepalive_interval(), None);
+        assert_eq!(parsed.ssh_timeouts.inactivity_timeout(), Some(Duration::from_secs(3600)));
+        assert_eq!(parsed.ssh_timeouts.connect_secs, 5);
     }
 }
@@ -0,0 +1,79 @@
+//! Human-readable sizes, durations and ages shared by the UI and terminal
+
+use std::time::{Duration, SystemTime};
+
+/// Format file size to human-readable string
+pub fn format_size(size: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = KB * 1024;
+    const GB: u64 = MB * 1024;
+
+    if size < KB {
+        format!("{} B", size)
+    } else if size < MB {
+        format!("{:.1} KB", size as f64 / KB as f64)
+    } else if size < GB {
+        format!("{:.1} MB", size as f64 / MB as f64)
+    } else {
+        format!("{:.1} GB", size as f64 / GB as f64)
+    }
+}
+
+/// Format a duration as its two largest units, like "1h 5m" or "42s"
+pub fn format_duration(d: Duration) -> String {
+    let secs = d.as_secs();
+    if secs >= 3600 {
+        format!("{}h {}m", secs / 3600, (secs / 60) % 60)
+    } else if secs >= 60 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// Short age of a past time, like "5m ago"
+pub fn relative_time(time: SystemTime, now: SystemTime) -> String {
+    let secs = now.duration_since(time).unwrap_or(Duration::ZERO).as_secs();
+    match secs {
+        0..=59 => "just now".to_string(),
+        60..=3_599 => format!("{}m ago", secs / 60),
+        3_600..=86_399 => format!("{}h ago", secs / 3_600),
+        86_400..=2_591_999 => format!("{}d ago", secs / 86_400),
+        2_592_000..=31_535_999 => format!("{}mo ago", secs / 2_592_000),
+        _ => format!("{}y ago", secs / 31_536_000),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GB");
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(42)), "42s");
+        assert_eq!(format_duration(Duration::from_secs(75)), "1m 15s");
+        assert_eq!(format_duration(Duration::from_secs(3700)), "1h 1m");
+    }
+
+    #[test]
+    fn test_relative_time() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let ago = |secs| relative_time(now - Duration::from_secs(secs), now);
+        assert_eq!(ago(5), "just now");
+        assert_eq!(ago(5 * 60), "5m ago");
+        assert_eq!(ago(2 * 3_600 + 59), "2h ago");
+        assert_eq!(ago(3 * 86_400), "3d ago");
+        assert_eq!(ago(60 * 86_400), "2mo ago");
+        assert_eq!(ago(800 * 86_400), "2y ago");
+        // Clock changes can put the time in the future
+        assert_eq!(relative_time(now + Duration::from_secs(60), now), "just now");
+    }
+}
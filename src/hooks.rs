@@ -0,0 +1,262 @@
+//! Local commands run around a session's connection
+//!
+//! A session can name commands to run on this machine before it connects
+//! (e.g. `aws sso login` or bringing up a VPN) and after its connection ends
+//! (e.g. updating a status file). `{name}`, `{host}`, `{status}` and
+//! `{exit}` in a command are replaced with shell-quoted values, which are
+//! also passed in the environment as `REDPILL_SESSION`, `REDPILL_HOST`,
+//! `REDPILL_STATUS` and `REDPILL_EXIT_STATUS`.
+//!
+//! Hooks only run when `allow_session_hooks` is set in the config, since an
+//! imported session file could otherwise run anything.
+
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use thiserror::Error;
+
+/// How long a pre-connect command may run before the connect is abandoned
+pub const PRE_CONNECT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Most lines of a failed pre-connect command's output shown in the terminal
+const MAX_OUTPUT_LINES: usize = 20;
+
+/// How a session's connection ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionEnd {
+    /// The connection was never established
+    Failed,
+    /// The remote side closed the session
+    Disconnected,
+    /// The remote shell exited with this status
+    Exited(u32),
+    /// The tab was closed or the transport broke
+    Closed,
+}
+
+impl SessionEnd {
+    /// Value substituted for `{status}`
+    pub fn status(self) -> &'static str {
+        match self {
+            SessionEnd::Failed => "failed",
+            SessionEnd::Disconnected => "disconnected",
+            SessionEnd::Exited(_) => "exited",
+            SessionEnd::Closed => "closed",
+        }
+    }
+
+    /// Value substituted for `{exit}`: the remote exit status, if there was one
+    pub fn exit_status(self) -> String {
+        match self {
+            SessionEnd::Exited(code) => code.to_string(),
+            _ => String::new(),
+        }
+    }
+}
+
+/// What a hook is told about the session it runs for
+#[derive(Debug, Clone)]
+pub struct HookContext {
+    pub name: String,
+    pub host: String,
+    /// How the connection ended; None before connecting
+    pub end: Option<SessionEnd>,
+}
+
+impl HookContext {
+    fn vars(&self) -> [(&'static str, &'static str, String); 4] {
+        let status = self.end.map_or("connecting", SessionEnd::status);
+        let exit = self.end.map(SessionEnd::exit_status).unwrap_or_default();
+        [
+            ("{name}", "REDPILL_SESSION", self.name.clone()),
+            ("{host}", "REDPILL_HOST", self.host.clone()),
+            ("{status}", "REDPILL_STATUS", status.to_string()),
+            ("{exit}", "REDPILL_EXIT_STATUS", exit),
+        ]
+    }
+}
+
+/// Why a pre-connect command stopped the connection
+#[derive(Debug, Error)]
+pub enum HookError {
+    #[error("Could not run `{0}`: {1}")]
+    Spawn(String, String),
+
+    #[error("`{command}` exited with {status}")]
+    Failed {
+        command: String,
+        status: String,
+        /// The end of what the command printed, stdout then stderr
+        output: String,
+    },
+
+    #[error("`{0}` did not finish within {1} seconds")]
+    Timeout(String, u64),
+}
+
+impl HookError {
+    /// What the command printed, if it ran to completion
+    pub fn output(&self) -> &str {
+        match self {
+            HookError::Failed { output, .. } => output,
+            _ => "",
+        }
+    }
+}
+
+/// Replace placeholders in `command` with shell-quoted values
+///
+/// Unknown placeholders are left as written.
+pub fn expand(command: &str, context: &HookContext) -> String {
+    let vars = context.vars();
+    let mut expanded = String::with_capacity(command.len());
+    let mut rest = command;
+    'outer: while let Some(start) = rest.find('{') {
+        expanded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        for (placeholder, _, value) in &vars {
+            if let Some(after) = rest.strip_prefix(placeholder) {
+                expanded.push_str(&shell_quote(value));
+                rest = after;
+                continue 'outer;
+            }
+        }
+        expanded.push('{');
+        rest = &rest[1..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+/// Build the process for a hook, with placeholders and environment filled in
+fn hook_command(command: &str, context: &HookContext) -> Command {
+    let mut cmd = shell_command(&expand(command, context));
+    for (_, env, value) in context.vars() {
+        cmd.env(env, value);
+    }
+    cmd.stdin(Stdio::null());
+    cmd
+}
+
+/// Run a hook in the background
+///
+/// Failures are logged; nothing waits for the command to finish.
+pub fn spawn(command: &str, context: &HookContext) {
+    let mut cmd = hook_command(command, context);
+    let command = command.to_string();
+    std::thread::spawn(move || match cmd.status() {
+        Ok(status) if !status.success() => tracing::warn!("Session hook `{}` exited with {}", command, status),
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Failed to run session hook `{}`: {}", command, e),
+    });
+}
+
+/// Run a pre-connect command and wait for it to succeed
+///
+/// The command is killed if it outlives [`PRE_CONNECT_TIMEOUT`].
+pub async fn run_pre_connect(command: &str, context: &HookContext) -> Result<(), HookError> {
+    let mut cmd = tokio::process::Command::from(hook_command(command, context));
+    cmd.kill_on_drop(true);
+    let output = match tokio::time::timeout(PRE_CONNECT_TIMEOUT, cmd.output()).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => return Err(HookError::Spawn(command.to_string(), e.to_string())),
+        Err(_) => return Err(HookError::Timeout(command.to_string(), PRE_CONNECT_TIMEOUT.as_secs())),
+    };
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let mut printed = String::from_utf8_lossy(&output.stdout).into_owned();
+    printed.push_str(&String::from_utf8_lossy(&output.stderr));
+    Err(HookError::Failed {
+        command: command.to_string(),
+        status: output.status.to_string(),
+        output: last_lines(&printed, MAX_OUTPUT_LINES),
+    })
+}
+
+/// The last `count` non-blank lines of `text`
+fn last_lines(text: &str, count: usize) -> String {
+    let lines: Vec<&str> = text.lines().map(str::trim_end).filter(|line| !line.is_empty()).collect();
+    lines[lines.len().saturating_sub(count)..].join("\n")
+}
+
+#[cfg(unix)]
+fn shell_command(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(command);
+    cmd
+}
+
+#[cfg(windows)]
+fn shell_command(command: &str) -> Command {
+    use std::os::windows::process::CommandExt;
+
+    let mut cmd = Command::new("cmd");
+    cmd.arg("/C").raw_arg(command);
+    cmd
+}
+
+/// Quote a value so the shell passes it through as one word
+#[cfg(unix)]
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(windows)]
+fn shell_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(end: SessionEnd) -> HookContext {
+        HookContext {
+            name: "prod db".to_string(),
+            host: "db.example.com".to_string(),
+            end: Some(end),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_expand() {
+        assert_eq!(
+            expand("notify {name}@{host}: {status} {exit}", &context(SessionEnd::Exited(1))),
+            "notify 'prod db'@'db.example.com': 'exited' '1'"
+        );
+        assert_eq!(expand("echo {status}", &context(SessionEnd::Failed)), "echo 'failed'");
+        // Braces that aren't placeholders are kept
+        assert_eq!(expand("echo {other} {", &context(SessionEnd::Closed)), "echo {other} {");
+        let mut before = context(SessionEnd::Closed);
+        before.end = None;
+        assert_eq!(expand("echo {status}{exit}", &before), "echo 'connecting'''");
+
+        let mut quoted = context(SessionEnd::Closed);
+        quoted.name = "it's; rm -rf ~".to_string();
+        assert_eq!(expand("echo {name}", &quoted), r"echo 'it'\''s; rm -rf ~'");
+    }
+
+    #[test]
+    fn test_last_lines() {
+        assert_eq!(last_lines("a\n\nb\r\nc\n", 2), "b\nc");
+        assert_eq!(last_lines("", 5), "");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_pre_connect() {
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        runtime.block_on(async {
+            let mut before = context(SessionEnd::Closed);
+            before.end = None;
+            assert!(run_pre_connect("test \"$REDPILL_HOST\" = {host}", &before).await.is_ok());
+
+            let error = run_pre_connect("echo token expired >&2; exit 3", &before).await.unwrap_err();
+            assert!(matches!(error, HookError::Failed { .. }));
+            assert_eq!(error.output(), "token expired");
+        });
+    }
+}
@@ -5,7 +5,7 @@
 use std::collections::HashMap;
 use std::sync::OnceLock;
 use kube::{
-    api::{Api, ListParams},
+    api::{Api, ListParams, LogParams},
     Client, Config,
     runtime::watcher::{self, Event as WatchEvent},
 };
@@ -14,6 +14,9 @@ use thiserror::Error;
 use tokio::sync::RwLock;
 use futures::StreamExt;
 
+/// Lines of earlier log output shown when opening a pod's log
+const LOG_TAIL_LINES: i64 = 5000;
+
 /// Global client cache - avoids recreating clients (expensive TLS handshake) for each request
 static CLIENT_CACHE: OnceLock<RwLock<HashMap<String, Client>>> = OnceLock::new();
 
@@ -211,6 +214,29 @@ impl KubeClient {
         })
     }
 
+    /// Stream a container's log as raw bytes
+    ///
+    /// Starts with the last `LOG_TAIL_LINES` lines. With `follow`, the stream
+    /// stays open for new lines until the container stops; without it, it
+    /// ends after what's already logged. `container` may be left out for
+    /// single-container pods.
+    pub async fn stream_logs(
+        &self,
+        namespace: &str,
+        pod: &str,
+        container: Option<&str>,
+        follow: bool,
+    ) -> Result<impl futures::AsyncBufRead + Send, KubeClientError> {
+        let pods: Api<Pod> = Api::namespaced(self.client.clone(), namespace);
+        let params = LogParams {
+            container: container.map(str::to_string),
+            follow,
+            tail_lines: Some(LOG_TAIL_LINES),
+            ..Default::default()
+        };
+        Ok(pods.log_stream(pod, &params).await?)
+    }
+
     /// Watch namespaces for changes and send updates via the channel
     pub async fn watch_namespaces<F>(&self, mut on_event: F) -> Result<(), KubeClientError>
     where
@@ -2,6 +2,10 @@
 //!
 //! Parses kubeconfig files (typically ~/.kube/config) to extract
 //! clusters, contexts, and authentication information.
+//!
+//! When `KUBECONFIG` lists several files they are merged the way kubectl
+//! (and the kube client used to connect) does it: the first file to define a
+//! context or cluster name wins, and so does the first `current-context`.
 
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -43,6 +47,7 @@ pub struct KubeContext {
 /// Parsed kubeconfig
 #[derive(Debug, Clone)]
 pub struct KubeConfig {
+    /// First file the config was loaded from
     pub path: PathBuf,
     pub current_context: Option<String>,
     pub contexts: Vec<KubeContext>,
@@ -50,19 +55,26 @@ pub struct KubeConfig {
 }
 
 impl KubeConfig {
-    /// Load kubeconfig from the default location (~/.kube/config)
+    /// Load kubeconfig from `KUBECONFIG` or the default location (~/.kube/config)
     pub fn load_default() -> Result<Self, KubeConfigError> {
-        let path = Self::default_path()?;
-        Self::load_from(&path)
+        let paths = Self::default_paths()?;
+        Self::load_all(&paths)
     }
 
-    /// Get the default kubeconfig path
+    /// Get the default kubeconfig path (the first one, if `KUBECONFIG` lists several)
     pub fn default_path() -> Result<PathBuf, KubeConfigError> {
-        // Check KUBECONFIG env var first
-        if let Ok(kubeconfig) = std::env::var("KUBECONFIG") {
-            let path = PathBuf::from(kubeconfig.split(':').next().unwrap_or(&kubeconfig));
-            if path.exists() {
-                return Ok(path);
+        Self::default_paths()?.into_iter().next().ok_or(KubeConfigError::NotFound)
+    }
+
+    /// Get every kubeconfig file to load, in precedence order
+    pub fn default_paths() -> Result<Vec<PathBuf>, KubeConfigError> {
+        // Check KUBECONFIG env var first; files that don't exist are skipped, like kubectl
+        if let Some(kubeconfig) = std::env::var_os("KUBECONFIG") {
+            let paths: Vec<PathBuf> = std::env::split_paths(&kubeconfig)
+                .filter(|path| !path.as_os_str().is_empty() && path.exists())
+                .collect();
+            if !paths.is_empty() {
+                return Ok(paths);
             }
         }
 
@@ -70,7 +82,7 @@ impl KubeConfig {
         let home = dirs::home_dir().ok_or(KubeConfigError::NotFound)?;
         let path = home.join(".kube").join("config");
         if path.exists() {
-            Ok(path)
+            Ok(vec![path])
         } else {
             Err(KubeConfigError::NotFound)
         }
@@ -82,6 +94,46 @@ impl KubeConfig {
         Self::parse(&content, path.clone())
     }
 
+    /// Load and merge several kubeconfig files, earlier files taking precedence
+    ///
+    /// A file that can't be read or parsed is skipped so one broken file doesn't
+    /// hide the rest; the error is only returned if nothing loads.
+    pub fn load_all(paths: &[PathBuf]) -> Result<Self, KubeConfigError> {
+        let mut merged: Option<Self> = None;
+        let mut first_error = None;
+        for path in paths {
+            match Self::load_from(path) {
+                Ok(config) => match merged.as_mut() {
+                    Some(merged) => merged.merge(config),
+                    None => merged = Some(config),
+                },
+                Err(e) => {
+                    tracing::warn!("Skipping kubeconfig {}: {}", path.display(), e);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        merged.ok_or_else(|| first_error.unwrap_or(KubeConfigError::NotFound))
+    }
+
+    /// Merge a lower-precedence config into this one
+    ///
+    /// Names already defined here are kept; only new contexts and clusters are
+    /// added, and `current-context` is only taken if this config has none.
+    fn merge(&mut self, other: Self) {
+        if self.current_context.is_none() {
+            self.current_context = other.current_context;
+        }
+        for (name, cluster) in other.clusters {
+            self.clusters.entry(name).or_insert(cluster);
+        }
+        for context in other.contexts {
+            if self.get_context(&context.name).is_none() {
+                self.contexts.push(context);
+            }
+        }
+    }
+
     /// Parse kubeconfig YAML content
     fn parse(content: &str, path: PathBuf) -> Result<Self, KubeConfigError> {
         // Use serde_json to parse YAML (kube crate handles this internally,
@@ -101,6 +153,9 @@ impl KubeConfig {
                     cluster.get("name").and_then(|v| v.as_str()),
                     cluster.get("cluster"),
                 ) {
+                    if clusters.contains_key(name) {
+                        continue;
+                    }
                     let server = cluster_data.get("server")
                         .and_then(|v| v.as_str())
                         .unwrap_or("")
@@ -131,6 +186,10 @@ impl KubeConfig {
                     context.get("name").and_then(|v| v.as_str()),
                     context.get("context"),
                 ) {
+                    // A repeated name within one file: the first entry wins
+                    if contexts.iter().any(|c: &KubeContext| c.name == name) {
+                        continue;
+                    }
                     contexts.push(KubeContext {
                         name: name.to_string(),
                         cluster: context_data.get("cluster")
@@ -229,4 +288,95 @@ users:
         let cluster = config.get_cluster("production").unwrap();
         assert!(cluster.insecure_skip_tls_verify);
     }
+
+    fn config(yaml: &str, path: &str) -> KubeConfig {
+        KubeConfig::parse(yaml, PathBuf::from(path)).unwrap()
+    }
+
+    #[test]
+    fn test_merge_first_file_wins() {
+        let mut merged = config(r#"
+clusters:
+- name: shared
+  cluster:
+    server: https://first.example.com
+contexts:
+- name: dev
+  context:
+    cluster: shared
+    user: first-user
+"#, "/test/first");
+        merged.merge(config(r#"
+current-context: prod
+clusters:
+- name: shared
+  cluster:
+    server: https://second.example.com
+- name: prod
+  cluster:
+    server: https://prod.example.com
+contexts:
+- name: dev
+  context:
+    cluster: shared
+    user: second-user
+- name: prod
+  context:
+    cluster: prod
+    user: admin
+"#, "/test/second"));
+
+        assert_eq!(merged.path, PathBuf::from("/test/first"));
+        // The first file had no current-context, so the second one's is used
+        assert_eq!(merged.current_context, Some("prod".to_string()));
+        // Names defined earlier keep their first definition
+        assert_eq!(merged.get_cluster("shared").unwrap().server, "https://first.example.com");
+        assert_eq!(merged.get_context("dev").unwrap().user, "first-user");
+        // New names from later files are added, without duplicates
+        assert_eq!(merged.get_cluster("prod").unwrap().server, "https://prod.example.com");
+        let names: Vec<&str> = merged.contexts.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["dev", "prod"]);
+
+        // An earlier current-context isn't replaced
+        merged.merge(config("current-context: dev\n", "/test/third"));
+        assert_eq!(merged.current_context, Some("prod".to_string()));
+    }
+
+    #[test]
+    fn test_parse_dedupes_names() {
+        let config = config(r#"
+clusters:
+- name: a
+  cluster:
+    server: https://one
+- name: a
+  cluster:
+    server: https://two
+contexts:
+- name: a
+  context:
+    cluster: a
+    user: one
+- name: a
+  context:
+    cluster: a
+    user: two
+"#, "/test/config");
+        assert_eq!(config.contexts.len(), 1);
+        assert_eq!(config.contexts[0].user, "one");
+        assert_eq!(config.get_cluster("a").unwrap().server, "https://one");
+    }
+
+    #[test]
+    fn test_load_all_skips_missing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let good = dir.join("config");
+        std::fs::write(&good, "contexts:\n- name: only\n  context:\n    cluster: c\n    user: u\n").unwrap();
+
+        let merged = KubeConfig::load_all(&[dir.join("missing"), good.clone()]).unwrap();
+        assert_eq!(merged.path, good);
+        assert_eq!(merged.contexts.len(), 1);
+        assert!(KubeConfig::load_all(&[dir.join("missing")]).is_err());
+    }
 }
@@ -1,9 +1,15 @@
 pub mod app;
+pub mod cli;
 pub mod config;
+pub mod format;
+pub mod hooks;
 pub mod kubernetes;
+pub mod metrics;
+pub mod notifications;
 pub mod session;
 pub mod sftp;
 pub mod terminal;
+pub mod theme_import;
 pub mod ui;
 
 pub use app::{AppState, RedPillApp};
@@ -1,9 +1,15 @@
 mod app;
+mod cli;
 mod config;
+mod format;
+mod hooks;
 mod kubernetes;
+mod metrics;
+mod notifications;
 mod session;
 mod sftp;
 mod terminal;
+mod theme_import;
 mod ui;
 
 use gpui::*;
@@ -50,7 +56,26 @@ fn set_dock_icon() {
 }
 
 use crate::app::AppState;
-use crate::ui::{open_main_window, QuitConfirmDialog, SessionDialog, SsmSessionDialog};
+use crate::cli::{CliArgs, LaunchTarget};
+use crate::session::SessionLink;
+use crate::ui::{open_main_window, toggle_broadcast, ColorSchemeEditor, ConnectionsDashboard, QuitConfirmDialog, SessionDialog, SsmSessionDialog};
+#[cfg(target_os = "macos")]
+use crate::ui::{ClosePane, SplitHorizontal, SplitVertical};
+
+/// Open sessions from the command line or `redpill://` / `ssh://` links
+fn open_launch_targets(targets: Vec<LaunchTarget>, cx: &mut App) {
+    if let Some(state) = cx.try_global::<AppState>() {
+        let mut app = state.app.lock();
+        for target in &targets {
+            tracing::info!("Opening {:?}", target);
+            if let Err(e) = app.open_launch_target(target, &state.tokio_runtime) {
+                tracing::error!("Failed to open {:?}: {}", target, e);
+            }
+        }
+    }
+    cx.activate(true);
+    cx.refresh_windows();
+}
 
 fn main() {
     // Initialize logging
@@ -64,12 +89,47 @@ fn main() {
         .install_default()
         .expect("Failed to install rustls crypto provider");
 
+    let args = match CliArgs::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("redpill: {}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+    if args.help {
+        println!("{}", cli::USAGE);
+        return;
+    }
+    if args.version {
+        println!("redpill {}", env!("CARGO_PKG_VERSION"));
+        return;
+    }
+
     tracing::info!("Starting RedPill");
 
+    // Sessions to open arrive as launch arguments or as open-URL events
+    // (macOS, including the one that launched the app)
+    let (link_tx, link_rx) = async_channel::unbounded::<Vec<LaunchTarget>>();
+    if !args.targets.is_empty() {
+        let _ = link_tx.try_send(args.targets);
+    }
+
     // Initialize the gpui application
-    Application::new()
-        .with_quit_mode(QuitMode::LastWindowClosed)
-        .run(|cx: &mut App| {
+    let application = Application::new().with_quit_mode(QuitMode::LastWindowClosed);
+    application.on_open_urls(move |urls| {
+        let targets = urls
+            .iter()
+            .filter_map(|url| match SessionLink::parse(url) {
+                Ok(link) => Some(LaunchTarget::Link(link)),
+                Err(e) => {
+                    tracing::error!("Ignoring link {}: {}", url, e);
+                    None
+                }
+            })
+            .collect();
+        let _ = link_tx.try_send(targets);
+    });
+    application.run(move |cx: &mut App| {
         // Set dock icon (macOS)
         set_dock_icon();
 
@@ -93,6 +153,13 @@ fn main() {
                         MenuItem::action("New Terminal", NewTerminal),
                         MenuItem::action("New SSH Session...", NewSshSession),
                         MenuItem::action("New SSM Session...", NewSsmSession),
+                        MenuItem::action("Import from ~/.ssh/config", ImportSshConfig),
+                        MenuItem::action("Import Sessions...", ImportSessions),
+                        MenuItem::action("Export Sessions...", ExportSessions { include_secrets: false }),
+                        MenuItem::action("Export Sessions with Passwords...", ExportSessions { include_secrets: true }),
+                        MenuItem::separator(),
+                        MenuItem::action("Reconnect Dropped Sessions", ReconnectDropped),
+                        MenuItem::action("Broadcast Input", ToggleBroadcast),
                         MenuItem::separator(),
                         MenuItem::action("Close Tab", CloseTab),
                     ],
@@ -110,16 +177,28 @@ fn main() {
                     name: "View".into(),
                     items: vec![
                         MenuItem::action("Toggle Session Tree", ToggleSessionTree),
+                        MenuItem::action("Connections", ShowConnections),
+                        MenuItem::separator(),
+                        MenuItem::action("Split Right", SplitHorizontal),
+                        MenuItem::action("Split Down", SplitVertical),
+                        MenuItem::action("Close Pane", ClosePane),
+                        MenuItem::separator(),
                         MenuItem::action("Show Scrollbar", ToggleScrollbar),
+                        MenuItem::action("Show Wrap Indicator", ToggleWrapIndicator),
                         MenuItem::separator(),
                         MenuItem::action("Zoom In", ZoomIn),
                         MenuItem::action("Zoom Out", ZoomOut),
                         MenuItem::action("Reset Zoom", ZoomReset),
                         MenuItem::separator(),
-                        MenuItem::action("Theme: Default", SchemeDefault),
-                        MenuItem::action("Theme: Light", SchemeLight),
-                        MenuItem::action("Theme: Matrix", SchemeMatrix),
-                    ],
+                    ]
+                    .into_iter()
+                    .chain(theme_menu_items())
+                    .chain([
+                        MenuItem::action("Next Theme", CycleTheme),
+                        MenuItem::action("Edit Color Schemes...", EditColorSchemes),
+                        MenuItem::action("Import Theme...", ImportTheme),
+                    ])
+                    .collect(),
                 },
             ]);
         }
@@ -166,6 +245,21 @@ fn main() {
             SsmSessionDialog::open_new(cx);
         });
 
+        // ImportSshConfig - add the hosts in ~/.ssh/config as a new group
+        cx.on_action(|_: &ImportSshConfig, cx| {
+            crate::app::import_ssh_config(cx);
+        });
+
+        // ImportSessions - add the sessions from an exported JSON file
+        cx.on_action(|_: &ImportSessions, cx| {
+            crate::app::import_sessions_file(cx);
+        });
+
+        // ExportSessions - write all sessions to a portable JSON file
+        cx.on_action(|action: &ExportSessions, cx| {
+            crate::app::export_sessions(action.include_secrets, cx);
+        });
+
         // CloseTab - close the active tab
         cx.on_action(|_: &CloseTab, cx| {
             if let Some(state) = cx.try_global::<AppState>() {
@@ -178,6 +272,21 @@ fn main() {
             cx.refresh_windows();
         });
 
+        // ReconnectDropped - reopen every failed/disconnected session tab, staggered
+        cx.on_action(|_: &ReconnectDropped, cx| {
+            crate::app::reconnect_dropped_sessions(cx);
+        });
+
+        // ToggleBroadcast - type into every tab of the active session's group at once
+        cx.on_action(|_: &ToggleBroadcast, cx| {
+            toggle_broadcast(cx);
+        });
+
+        // ShowConnections - open the connections dashboard
+        cx.on_action(|_: &ShowConnections, cx| {
+            ConnectionsDashboard::open(cx);
+        });
+
         // ToggleSessionTree - toggle session tree visibility
         cx.on_action(|_: &ToggleSessionTree, cx| {
             if let Some(state) = cx.try_global::<AppState>() {
@@ -196,6 +305,16 @@ fn main() {
             cx.refresh_windows();
         });
 
+        // ToggleWrapIndicator - toggle soft-wrap markers
+        cx.on_action(|_: &ToggleWrapIndicator, cx| {
+            if let Some(state) = cx.try_global::<AppState>() {
+                let mut app = state.app.lock();
+                app.config.show_wrap_indicator = !app.config.show_wrap_indicator;
+                let _ = app.config.save();
+            }
+            cx.refresh_windows();
+        });
+
         // ZoomIn - increase font size
         cx.on_action(|_: &ZoomIn, cx| {
             if let Some(state) = cx.try_global::<AppState>() {
@@ -226,34 +345,29 @@ fn main() {
             cx.refresh_windows();
         });
 
-        // SchemeDefault - switch to default dark theme
-        cx.on_action(|_: &SchemeDefault, cx| {
+        // SelectScheme - switch to a scheme picked from the View menu
+        cx.on_action(|action: &SelectScheme, cx| {
             if let Some(state) = cx.try_global::<AppState>() {
                 let mut app = state.app.lock();
-                app.config.appearance.set_scheme("default");
+                app.config.appearance.set_scheme(&action.name);
                 let _ = app.config.save();
             }
             cx.refresh_windows();
         });
 
-        // SchemeLight - switch to light theme
-        cx.on_action(|_: &SchemeLight, cx| {
-            if let Some(state) = cx.try_global::<AppState>() {
-                let mut app = state.app.lock();
-                app.config.appearance.set_scheme("light");
-                let _ = app.config.save();
-            }
-            cx.refresh_windows();
+        // CycleTheme - rotate through built-in and custom schemes
+        cx.on_action(|_: &CycleTheme, cx| {
+            crate::app::cycle_theme(cx);
         });
 
-        // SchemeMatrix - switch to matrix theme
-        cx.on_action(|_: &SchemeMatrix, cx| {
-            if let Some(state) = cx.try_global::<AppState>() {
-                let mut app = state.app.lock();
-                app.config.appearance.set_scheme("matrix");
-                let _ = app.config.save();
-            }
-            cx.refresh_windows();
+        // EditColorSchemes - create and change custom color schemes
+        cx.on_action(|_: &EditColorSchemes, cx| {
+            ColorSchemeEditor::open(cx);
+        });
+
+        // ImportTheme - add an Alacritty or iTerm2 theme as a custom scheme
+        cx.on_action(|_: &ImportTheme, cx| {
+            crate::app::import_theme_file(cx);
         });
 
         // ShowSettings - placeholder for settings dialog
@@ -268,6 +382,16 @@ fn main() {
         // Open the main window and activate the app
         open_main_window(cx);
         cx.activate(true);
+
+        // Open requested sessions once the main window (and app state) exists
+        cx.spawn(async move |cx: &mut AsyncApp| {
+            while let Ok(targets) = link_rx.recv().await {
+                if cx.update(|cx| open_launch_targets(targets, cx)).is_err() {
+                    break;
+                }
+            }
+        })
+        .detach();
     });
 }
 
@@ -281,17 +405,53 @@ actions!(
         NewTerminal,
         NewSshSession,
         NewSsmSession,
+        ImportSshConfig,
+        ImportSessions,
         CloseTab,
+        ReconnectDropped,
+        ToggleBroadcast,
         Copy,
         Paste,
         SelectAll,
         ToggleSessionTree,
+        ShowConnections,
         ToggleScrollbar,
+        ToggleWrapIndicator,
         ZoomIn,
         ZoomOut,
         ZoomReset,
-        SchemeDefault,
-        SchemeLight,
-        SchemeMatrix,
+        CycleTheme,
+        EditColorSchemes,
+        ImportTheme,
     ]
 );
+
+/// Switch the global theme to a scheme by name
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = redpill, no_json)]
+struct SelectScheme {
+    name: String,
+}
+
+/// Export every session to a JSON file the user picks
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = redpill, no_json)]
+struct ExportSessions {
+    /// Write saved passwords and passphrases too, in plain text
+    include_secrets: bool,
+}
+
+/// One View menu item per visible scheme, in the configured order
+#[cfg(target_os = "macos")]
+fn theme_menu_items() -> Vec<MenuItem> {
+    crate::config::AppConfig::load()
+        .unwrap_or_default()
+        .appearance
+        .visible_scheme_names()
+        .into_iter()
+        .map(|name| {
+            let label = format!("Theme: {}", crate::config::ColorScheme::display_name(&name));
+            MenuItem::action(label, SelectScheme { name })
+        })
+        .collect()
+}
@@ -0,0 +1,266 @@
+//! Connection metrics in Prometheus text format
+//!
+//! Counters are plain atomics updated from the I/O loops and backends. When
+//! enabled in the config, [`serve`] exposes them on a local HTTP endpoint.
+
+use std::fmt::Write as _;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Kind of remote connection being measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConnectionKind {
+    Ssh,
+    Ssm,
+    K8s,
+    Serial,
+    Telnet,
+}
+
+impl ConnectionKind {
+    const ALL: [ConnectionKind; 5] = [
+        ConnectionKind::Ssh,
+        ConnectionKind::Ssm,
+        ConnectionKind::K8s,
+        ConnectionKind::Serial,
+        ConnectionKind::Telnet,
+    ];
+
+    /// Label value used in the exported metrics
+    pub fn label(self) -> &'static str {
+        match self {
+            ConnectionKind::Ssh => "ssh",
+            ConnectionKind::Ssm => "ssm",
+            ConnectionKind::K8s => "k8s",
+            ConnectionKind::Serial => "serial",
+            ConnectionKind::Telnet => "telnet",
+        }
+    }
+}
+
+/// Counters for a single connection kind
+#[derive(Debug)]
+struct KindCounters {
+    active: AtomicU64,
+    opened: AtomicU64,
+    bytes_received: AtomicU64,
+    bytes_sent: AtomicU64,
+    reconnects: AtomicU64,
+    connected_millis: AtomicU64,
+}
+
+impl KindCounters {
+    const fn new() -> Self {
+        Self {
+            active: AtomicU64::new(0),
+            opened: AtomicU64::new(0),
+            bytes_received: AtomicU64::new(0),
+            bytes_sent: AtomicU64::new(0),
+            reconnects: AtomicU64::new(0),
+            connected_millis: AtomicU64::new(0),
+        }
+    }
+}
+
+/// Process-wide connection metrics
+#[derive(Debug)]
+pub struct Metrics {
+    ssh: KindCounters,
+    ssm: KindCounters,
+    k8s: KindCounters,
+    serial: KindCounters,
+    telnet: KindCounters,
+}
+
+static METRICS: Metrics = Metrics::new();
+
+/// Get the global metrics registry
+pub fn metrics() -> &'static Metrics {
+    &METRICS
+}
+
+impl Metrics {
+    const fn new() -> Self {
+        Self {
+            ssh: KindCounters::new(),
+            ssm: KindCounters::new(),
+            k8s: KindCounters::new(),
+            serial: KindCounters::new(),
+            telnet: KindCounters::new(),
+        }
+    }
+
+    fn counters(&self, kind: ConnectionKind) -> &KindCounters {
+        match kind {
+            ConnectionKind::Ssh => &self.ssh,
+            ConnectionKind::Ssm => &self.ssm,
+            ConnectionKind::K8s => &self.k8s,
+            ConnectionKind::Serial => &self.serial,
+            ConnectionKind::Telnet => &self.telnet,
+        }
+    }
+
+    /// Mark a connection as established
+    ///
+    /// The returned guard records the connection as closed (and its duration)
+    /// when dropped, so keep it alive for as long as the I/O loop runs.
+    #[must_use]
+    pub fn connection_opened(&'static self, kind: ConnectionKind) -> ConnectionGuard {
+        let counters = self.counters(kind);
+        counters.active.fetch_add(1, Ordering::Relaxed);
+        counters.opened.fetch_add(1, Ordering::Relaxed);
+        ConnectionGuard {
+            metrics: self,
+            kind,
+            started: Instant::now(),
+        }
+    }
+
+    fn connection_closed(&self, kind: ConnectionKind, duration: Duration) {
+        let counters = self.counters(kind);
+        counters.active.fetch_sub(1, Ordering::Relaxed);
+        counters
+            .connected_millis
+            .fetch_add(duration.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    /// Record bytes received from the remote side
+    pub fn add_bytes_received(&self, kind: ConnectionKind, bytes: usize) {
+        self.counters(kind).bytes_received.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    /// Record bytes sent to the remote side
+    pub fn add_bytes_sent(&self, kind: ConnectionKind, bytes: usize) {
+        self.counters(kind).bytes_sent.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    /// Record a reconnection attempt
+    pub fn add_reconnect(&self, kind: ConnectionKind) {
+        self.counters(kind).reconnects.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Number of currently active connections of a kind
+    #[must_use]
+    pub fn active(&self, kind: ConnectionKind) -> u64 {
+        self.counters(kind).active.load(Ordering::Relaxed)
+    }
+
+    /// Render all metrics in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        let families: [(&str, &str, &str, fn(&KindCounters) -> String); 6] = [
+            ("redpill_active_connections", "gauge", "Currently open connections", |c| {
+                c.active.load(Ordering::Relaxed).to_string()
+            }),
+            ("redpill_connections_total", "counter", "Connections established", |c| {
+                c.opened.load(Ordering::Relaxed).to_string()
+            }),
+            ("redpill_bytes_received_total", "counter", "Bytes received from remote hosts", |c| {
+                c.bytes_received.load(Ordering::Relaxed).to_string()
+            }),
+            ("redpill_bytes_sent_total", "counter", "Bytes sent to remote hosts", |c| {
+                c.bytes_sent.load(Ordering::Relaxed).to_string()
+            }),
+            ("redpill_reconnects_total", "counter", "Reconnection attempts", |c| {
+                c.reconnects.load(Ordering::Relaxed).to_string()
+            }),
+            ("redpill_connection_duration_seconds_total", "counter", "Total time spent connected (closed connections)", |c| {
+                format!("{:.3}", c.connected_millis.load(Ordering::Relaxed) as f64 / 1000.0)
+            }),
+        ];
+
+        for (name, kind, help, value) in families {
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+            let _ = writeln!(out, "# TYPE {} {}", name, kind);
+            for conn in ConnectionKind::ALL {
+                let _ = writeln!(out, "{}{{type=\"{}\"}} {}", name, conn.label(), value(self.counters(conn)));
+            }
+        }
+
+        out
+    }
+}
+
+/// Keeps a connection counted as active until dropped
+#[derive(Debug)]
+pub struct ConnectionGuard {
+    metrics: &'static Metrics,
+    kind: ConnectionKind,
+    started: Instant,
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.metrics.connection_closed(self.kind, self.started.elapsed());
+    }
+}
+
+/// Serve the metrics over HTTP until the listener fails
+///
+/// Every request gets the full metrics page regardless of path; this is only
+/// meant for a local Prometheus scraper.
+pub async fn serve(addr: SocketAddr) -> std::io::Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    tracing::info!("Serving metrics on http://{}/metrics", addr);
+
+    loop {
+        let (mut stream, peer) = listener.accept().await?;
+        tokio::spawn(async move {
+            // Read (and ignore) the request head
+            let mut buf = [0u8; 1024];
+            if let Err(e) = stream.read(&mut buf).await {
+                tracing::debug!("Metrics request from {} failed: {}", peer, e);
+                return;
+            }
+
+            let body = metrics().render();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\n\
+                Content-Type: text/plain; version=0.0.4\r\n\
+                Content-Length: {}\r\n\
+                Connection: close\r\n\
+                \r\n\
+                {}",
+                body.len(),
+                body
+            );
+            if let Err(e) = stream.write_all(response.as_bytes()).await {
+                tracing::debug!("Metrics response to {} failed: {}", peer, e);
+            }
+            let _ = stream.shutdown().await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_format() {
+        static LOCAL: Metrics = Metrics::new();
+
+        {
+            let _guard = LOCAL.connection_opened(ConnectionKind::Ssh);
+            LOCAL.add_bytes_received(ConnectionKind::Ssh, 100);
+            LOCAL.add_bytes_sent(ConnectionKind::Ssh, 7);
+            LOCAL.add_reconnect(ConnectionKind::Ssh);
+            assert_eq!(LOCAL.active(ConnectionKind::Ssh), 1);
+        }
+        assert_eq!(LOCAL.active(ConnectionKind::Ssh), 0);
+
+        let text = LOCAL.render();
+        assert!(text.contains("# TYPE redpill_active_connections gauge"));
+        assert!(text.contains("redpill_active_connections{type=\"ssh\"} 0"));
+        assert!(text.contains("redpill_connections_total{type=\"ssh\"} 1"));
+        assert!(text.contains("redpill_bytes_received_total{type=\"ssh\"} 100"));
+        assert!(text.contains("redpill_bytes_sent_total{type=\"ssh\"} 7"));
+        assert!(text.contains("redpill_reconnects_total{type=\"ssh\"} 1"));
+        assert!(text.contains("redpill_connections_total{type=\"k8s\"} 0"));
+    }
+}
@@ -0,0 +1,148 @@
+//! Desktop notifications for terminal events
+//!
+//! Posts OS notifications when a background tab rings the bell or a long
+//! command finishes. Uses each platform's own tooling (`osascript`,
+//! `notify-send`, PowerShell toasts) so no extra dependencies are needed.
+
+use std::process::Command;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use gpui::App;
+use parking_lot::Mutex;
+
+use crate::app::AppState;
+use crate::format::format_duration;
+use crate::terminal::{Terminal, TerminalEvent};
+
+/// Minimum gap between bell notifications, so a noisy tab can't spam
+const BELL_THROTTLE: Duration = Duration::from_secs(5);
+
+static LAST_BELL: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Post notifications for a batch of events from one terminal
+///
+/// `in_background` is true when the terminal's view doesn't have focus.
+pub fn notify_for_events(terminal: &Arc<Mutex<Terminal>>, events: &[TerminalEvent], in_background: bool, cx: &App) {
+    let Some(state) = cx.try_global::<AppState>() else {
+        return;
+    };
+    let (settings, session_name) = {
+        let app = state.app.lock();
+        let settings = app.config.notifications.clone();
+        let name = app
+            .tabs
+            .iter()
+            .find(|tab| Arc::ptr_eq(&tab.terminal, terminal))
+            .map(|tab| tab.display_title().to_string());
+        (settings, name)
+    };
+    if !settings.enabled {
+        return;
+    }
+    let session_name = session_name.unwrap_or_else(|| "Terminal".to_string());
+
+    for event in events {
+        match event {
+            TerminalEvent::Bell if settings.on_bell && in_background => {
+                let mut last = LAST_BELL.lock();
+                if last.is_some_and(|t| t.elapsed() < BELL_THROTTLE) {
+                    continue;
+                }
+                *last = Some(Instant::now());
+                send(&session_name, "Bell");
+            }
+            TerminalEvent::CommandFinished { exit_code, duration } if settings.on_command_finish => {
+                let long_enough = duration.is_some_and(|d| d.as_secs() >= settings.min_command_secs);
+                if long_enough {
+                    send(&session_name, &command_finished_body(*exit_code, *duration));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Notification text for a finished command
+fn command_finished_body(exit_code: Option<i32>, duration: Option<Duration>) -> String {
+    let status = match exit_code {
+        Some(0) => "Command succeeded".to_string(),
+        Some(code) => format!("Command failed (exit {})", code),
+        None => "Command finished".to_string(),
+    };
+    match duration {
+        Some(d) => format!("{} after {}", status, format_duration(d)),
+        None => status,
+    }
+}
+
+/// Post a notification without blocking the caller
+pub fn send(title: &str, body: &str) {
+    let title = format!("RedPill: {}", title);
+    let body = body.to_string();
+    std::thread::spawn(move || {
+        if let Err(e) = notification_command(&title, &body).status() {
+            tracing::warn!("Failed to post notification: {}", e);
+        }
+    });
+}
+
+#[cfg(target_os = "macos")]
+fn notification_command(title: &str, body: &str) -> Command {
+    let script = format!(
+        "display notification \"{}\" with title \"{}\"",
+        applescript_escape(body),
+        applescript_escape(title)
+    );
+    let mut cmd = Command::new("osascript");
+    cmd.arg("-e").arg(script);
+    cmd
+}
+
+#[cfg(target_os = "macos")]
+fn applescript_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(windows)]
+fn notification_command(title: &str, body: &str) -> Command {
+    let quote = |s: &str| format!("'{}'", s.replace('\'', "''"));
+    let script = format!(
+        "[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] | Out-Null; \
+         $xml = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02); \
+         $text = $xml.GetElementsByTagName('text'); \
+         $text.Item(0).AppendChild($xml.CreateTextNode({})) | Out-Null; \
+         $text.Item(1).AppendChild($xml.CreateTextNode({})) | Out-Null; \
+         [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier('RedPill').Show([Windows.UI.Notifications.ToastNotification]::new($xml))",
+        quote(title),
+        quote(body)
+    );
+    let mut cmd = Command::new("powershell");
+    cmd.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+    cmd
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn notification_command(title: &str, body: &str) -> Command {
+    let mut cmd = Command::new("notify-send");
+    cmd.arg("--app-name=RedPill").arg(title).arg(body);
+    cmd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_finished_body() {
+        assert_eq!(
+            command_finished_body(Some(0), Some(Duration::from_secs(75))),
+            "Command succeeded after 1m 15s"
+        );
+        assert_eq!(command_finished_body(Some(2), None), "Command failed (exit 2)");
+        assert_eq!(
+            command_finished_body(None, Some(Duration::from_secs(3700))),
+            "Command finished after 1h 1m"
+        );
+    }
+}
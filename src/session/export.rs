@@ -0,0 +1,193 @@
+//! Portable session files for moving sessions between machines
+//!
+//! An export holds every group and session in a versioned JSON document:
+//!
+//! ```json
+//! { "version": 1, "groups": [SessionGroup, ...], "sessions": [Session, ...] }
+//! ```
+//!
+//! Groups and sessions use the same serde layout as `sessions.json`, so new
+//! optional fields don't need a version bump. [`EXPORT_VERSION`] is bumped
+//! only for changes an older reader would get wrong; files with a newer
+//! version are refused rather than half-read, and older versions are migrated
+//! in [`SessionExport::from_json`].
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::models::{Session, SessionData, SessionGroup};
+
+/// Schema version written to new export files
+pub const EXPORT_VERSION: u32 = 1;
+
+/// Errors that can occur while writing or reading an export file
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error("Failed to access export file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to parse export file: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Export file version {0} is newer than this version of RedPill supports")]
+    UnsupportedVersion(u32),
+}
+
+/// What happens to the existing sessions when a file is imported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Add the imported groups and sessions next to the existing ones
+    Append,
+    /// Delete every existing group and session first
+    Replace,
+}
+
+/// Contents of an export file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionExport {
+    /// Schema version, see [`EXPORT_VERSION`]
+    pub version: u32,
+    #[serde(default)]
+    pub groups: Vec<SessionGroup>,
+    #[serde(default)]
+    pub sessions: Vec<Session>,
+}
+
+impl SessionExport {
+    /// Snapshot all groups and sessions
+    ///
+    /// Without `include_secrets`, saved passwords and key passphrases are left
+    /// out; the sessions then ask for them when connecting.
+    pub fn new(data: &SessionData, include_secrets: bool) -> Self {
+        let mut sessions = data.sessions.clone();
+        if !include_secrets {
+            for session in &mut sessions {
+                redact(session);
+            }
+        }
+        Self {
+            version: EXPORT_VERSION,
+            groups: data.groups.clone(),
+            sessions,
+        }
+    }
+
+    /// Parse an export file, refusing versions this build doesn't know
+    pub fn from_json(json: &str) -> Result<Self, ExportError> {
+        let export: Self = serde_json::from_str(json)?;
+        if export.version > EXPORT_VERSION {
+            return Err(ExportError::UnsupportedVersion(export.version));
+        }
+        Ok(export)
+    }
+
+    /// Give every group and session a fresh ID, keeping the hierarchy
+    ///
+    /// Parents and groups that aren't in the file are dropped, so their
+    /// children land at the top level instead of pointing nowhere.
+    pub fn with_new_ids(mut self) -> Self {
+        let group_ids: HashMap<Uuid, Uuid> = self.groups.iter().map(|g| (g.id, Uuid::new_v4())).collect();
+        let remap = |id: Option<Uuid>| id.and_then(|id| group_ids.get(&id).copied());
+
+        for group in &mut self.groups {
+            group.id = group_ids[&group.id];
+            group.parent_id = remap(group.parent_id);
+        }
+        for session in &mut self.sessions {
+            session.set_id(Uuid::new_v4());
+            session.set_group_id(remap(session.group_id()));
+        }
+        self
+    }
+}
+
+/// Drop the saved secrets of an SSH session and its jump host
+fn redact(session: &mut Session) {
+    if let Session::Ssh(ssh) = session {
+        ssh.auth = ssh.auth.without_secrets();
+        if let Some(jump) = &mut ssh.jump_host {
+            jump.auth = jump.auth.without_secrets();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::{AuthMethod, SshSession, SsmSession};
+
+    fn sample_data() -> SessionData {
+        let parent = SessionGroup::new("Prod".to_string());
+        let mut child = SessionGroup::new("Web".to_string());
+        child.parent_id = Some(parent.id);
+
+        let mut ssh = SshSession::new("web-1", "10.0.0.1", "deploy");
+        ssh.auth = AuthMethod::Password {
+            password: Some("hunter2".to_string()),
+            use_keychain: false,
+        };
+        ssh.group_id = Some(child.id);
+        let ssm = SsmSession::new("bastion", "i-0123456789abcdef0");
+
+        SessionData {
+            groups: vec![parent, child],
+            sessions: vec![Session::Ssh(ssh), Session::Ssm(ssm)],
+        }
+    }
+
+    fn password(session: &Session) -> Option<String> {
+        match session {
+            Session::Ssh(SshSession {
+                auth: AuthMethod::Password { password, .. },
+                ..
+            }) => password.clone(),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_export_redacts_secrets() {
+        let data = sample_data();
+        assert_eq!(password(&SessionExport::new(&data, false).sessions[0]), None);
+        assert_eq!(password(&SessionExport::new(&data, true).sessions[0]).as_deref(), Some("hunter2"));
+    }
+
+    #[test]
+    fn test_round_trip_and_version_check() {
+        let json = serde_json::to_string(&SessionExport::new(&sample_data(), false)).unwrap();
+        let export = SessionExport::from_json(&json).unwrap();
+        assert_eq!(export.version, EXPORT_VERSION);
+        assert_eq!(export.groups.len(), 2);
+        assert_eq!(export.sessions.len(), 2);
+
+        let newer = json.replacen("\"version\":1", "\"version\":99", 1);
+        assert!(matches!(SessionExport::from_json(&newer), Err(ExportError::UnsupportedVersion(99))));
+    }
+
+    #[test]
+    fn test_new_ids_keep_hierarchy() {
+        let data = sample_data();
+        let export = SessionExport::new(&data, false).with_new_ids();
+
+        let (parent, child) = (&export.groups[0], &export.groups[1]);
+        assert_ne!(parent.id, data.groups[0].id);
+        assert_ne!(child.id, data.groups[1].id);
+        assert_eq!(child.parent_id, Some(parent.id));
+
+        assert_ne!(export.sessions[0].id(), data.sessions[0].id());
+        assert_eq!(export.sessions[0].group_id(), Some(child.id));
+        assert_eq!(export.sessions[1].group_id(), None);
+    }
+
+    #[test]
+    fn test_new_ids_drop_missing_groups() {
+        let mut data = sample_data();
+        data.groups.remove(0);
+        let export = SessionExport::new(&data, false).with_new_ids();
+        assert_eq!(export.groups[0].parent_id, None);
+        assert_eq!(export.sessions[0].group_id(), Some(export.groups[0].id));
+    }
+}
@@ -0,0 +1,308 @@
+//! Host suggestions for the session dialog
+//!
+//! Candidates come from saved SSH sessions and `Host` entries in
+//! `~/.ssh/config`, and are ranked against what the user has typed with a
+//! simple subsequence match (like a fuzzy file finder).
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use super::manager::SessionManager;
+use super::models::Session;
+
+/// Where a suggestion came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuggestionSource {
+    SavedSession,
+    SshConfig,
+}
+
+/// A host the user might mean
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostSuggestion {
+    /// Session name or ssh config alias, shown alongside the host
+    pub alias: Option<String>,
+    pub host: String,
+    pub username: Option<String>,
+    pub port: Option<u16>,
+    pub source: SuggestionSource,
+}
+
+impl HostSuggestion {
+    /// Text the query is matched against
+    pub fn match_text(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.host)
+    }
+
+    /// Secondary text: `user@host:port` without the parts that are unset
+    pub fn detail(&self) -> String {
+        let mut detail = String::new();
+        if let Some(user) = &self.username {
+            detail.push_str(user);
+            detail.push('@');
+        }
+        detail.push_str(&self.host);
+        if let Some(port) = self.port.filter(|&p| p != 22) {
+            detail.push_str(&format!(":{}", port));
+        }
+        detail
+    }
+}
+
+/// A suggestion that matched the query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RankedSuggestion {
+    pub suggestion: HostSuggestion,
+    /// Character positions in `match_text()` that matched the query
+    pub positions: Vec<usize>,
+    score: i32,
+}
+
+/// Path of the user's OpenSSH client config
+pub fn ssh_config_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".ssh").join("config"))
+}
+
+/// Gather suggestions from saved sessions and the user's ssh config
+///
+/// Saved sessions come first; config entries for a host that is already
+/// saved with the same user are skipped.
+pub fn collect_host_suggestions(manager: &SessionManager) -> Vec<HostSuggestion> {
+    let config = ssh_config_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .unwrap_or_default();
+
+    let mut suggestions: Vec<HostSuggestion> = manager
+        .all_sessions()
+        .iter()
+        .filter_map(|session| match session {
+            Session::Ssh(ssh) if !ssh.host.is_empty() => Some(HostSuggestion {
+                alias: Some(ssh.name.clone()).filter(|name| *name != ssh.host),
+                host: ssh.host.clone(),
+                username: Some(ssh.username.clone()).filter(|u| !u.is_empty()),
+                port: Some(ssh.port),
+                source: SuggestionSource::SavedSession,
+            }),
+            _ => None,
+        })
+        .collect();
+
+    let mut seen: HashSet<(String, Option<String>)> = suggestions
+        .iter()
+        .map(|s| (s.host.to_ascii_lowercase(), s.username.clone()))
+        .collect();
+    for entry in parse_ssh_config_hosts(&config) {
+        if seen.insert((entry.host.to_ascii_lowercase(), entry.username.clone())) {
+            suggestions.push(entry);
+        }
+    }
+    suggestions
+}
+
+/// Read concrete `Host` entries (no wildcards) from an ssh config
+///
+/// Only `HostName`, `User` and `Port` are picked up; `Include` and `Match`
+/// blocks are not followed.
+pub fn parse_ssh_config_hosts(config: &str) -> Vec<HostSuggestion> {
+    let mut entries: Vec<HostSuggestion> = Vec::new();
+    // Indices of the entries the current `Host` line opened
+    let mut current: Vec<usize> = Vec::new();
+
+    for line in config.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = match line.split_once(|c: char| c.is_whitespace() || c == '=') {
+            Some((key, value)) => (key, value.trim_start_matches(|c: char| c.is_whitespace() || c == '=').trim()),
+            None => continue,
+        };
+
+        match key.to_ascii_lowercase().as_str() {
+            "host" => {
+                current.clear();
+                for alias in value.split_whitespace() {
+                    if alias.contains(['*', '?', '!']) {
+                        continue;
+                    }
+                    current.push(entries.len());
+                    entries.push(HostSuggestion {
+                        alias: Some(alias.to_string()),
+                        host: alias.to_string(),
+                        username: None,
+                        port: None,
+                        source: SuggestionSource::SshConfig,
+                    });
+                }
+            }
+            "match" => current.clear(),
+            "hostname" => {
+                for &i in &current {
+                    entries[i].host = value.to_string();
+                }
+            }
+            "user" => {
+                for &i in &current {
+                    entries[i].username = Some(value.to_string());
+                }
+            }
+            "port" => {
+                if let Ok(port) = value.parse() {
+                    for &i in &current {
+                        entries[i].port = Some(port);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // An alias that is just the hostname needs no separate label
+    for entry in &mut entries {
+        if entry.alias.as_deref() == Some(entry.host.as_str()) {
+            entry.alias = None;
+        }
+    }
+    entries
+}
+
+/// Match `query` as a case-insensitive subsequence of `text`
+///
+/// Returns a score (higher is better) and the matched character positions.
+/// Consecutive matches and matches at the start of a word score higher;
+/// every place the first query character occurs is tried as a starting point.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<(i32, Vec<usize>)> {
+    let query: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_lowercase).collect();
+    let text: Vec<char> = text.chars().collect();
+    let first = *query.first()?;
+
+    let best = (0..text.len())
+        .filter(|&start| text[start].to_lowercase().eq(std::iter::once(first)))
+        .filter_map(|start| match_from(&query, &text, start))
+        .max_by_key(|(score, _)| *score)?;
+
+    // Prefer shorter candidates among equal matches
+    Some((best.0 - (text.len() / 8) as i32, best.1))
+}
+
+/// Greedily match `query` against `text` starting at `start`
+fn match_from(query: &[char], text: &[char], start: usize) -> Option<(i32, Vec<usize>)> {
+    let mut positions: Vec<usize> = Vec::with_capacity(query.len());
+    let mut score = 0;
+    let mut query = query.iter().peekable();
+
+    for (i, &c) in text.iter().enumerate().skip(start) {
+        let Some(&&q) = query.peek() else {
+            break;
+        };
+        if !c.to_lowercase().eq(std::iter::once(q)) {
+            continue;
+        }
+        score += 1;
+        if i == 0 || matches!(text[i - 1], '.' | '-' | '_' | '@' | ' ') {
+            score += 3;
+        }
+        if positions.last().is_some_and(|&last| last + 1 == i) {
+            score += 5;
+        }
+        positions.push(i);
+        query.next();
+    }
+
+    query.peek().is_none().then_some((score, positions))
+}
+
+/// Rank suggestions against a query, best first
+///
+/// Suggestions are matched on their alias (or host); an exact match of what
+/// was typed isn't worth suggesting and is left out.
+pub fn rank_suggestions(query: &str, suggestions: &[HostSuggestion], limit: usize) -> Vec<RankedSuggestion> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut ranked: Vec<RankedSuggestion> = suggestions
+        .iter()
+        .filter(|s| !s.match_text().eq_ignore_ascii_case(query) || s.alias.is_some())
+        .filter_map(|s| {
+            let (score, positions) = fuzzy_match(query, s.match_text())
+                .or_else(|| fuzzy_match(query, &s.host).map(|(score, _)| (score - 2, Vec::new())))?;
+            Some(RankedSuggestion {
+                suggestion: s.clone(),
+                positions,
+                score,
+            })
+        })
+        .collect();
+    // Stable sort keeps saved sessions ahead of config entries on ties
+    ranked.sort_by_key(|r| std::cmp::Reverse(r.score));
+    ranked.truncate(limit);
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ssh_config_hosts() {
+        let config = "\
+Host *
+    ServerAliveInterval 30
+
+Host bastion bastion-eu
+    HostName bastion.prod.example.com
+    User ops
+    Port=2222
+
+Host db1.internal
+    User postgres
+";
+        let entries = parse_ssh_config_hosts(config);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].alias.as_deref(), Some("bastion"));
+        assert_eq!(entries[1].host, "bastion.prod.example.com");
+        assert_eq!(entries[1].port, Some(2222));
+        assert_eq!(entries[1].username.as_deref(), Some("ops"));
+        assert_eq!(entries[2].alias, None);
+        assert_eq!(entries[2].detail(), "postgres@db1.internal");
+    }
+
+    #[test]
+    fn test_fuzzy_match() {
+        let (_, positions) = fuzzy_match("db", "prod-db-01").unwrap();
+        assert_eq!(positions, vec![5, 6]);
+        let (_, positions) = fuzzy_match("PDB", "prod-db-01").unwrap();
+        assert_eq!(positions.len(), 3);
+        assert!(fuzzy_match("xyz", "prod-db-01").is_none());
+
+        // Word starts and runs beat scattered matches
+        let (start, _) = fuzzy_match("db", "prod-db").unwrap();
+        let (scattered, _) = fuzzy_match("db", "dashboard").unwrap();
+        assert!(start > scattered);
+    }
+
+    #[test]
+    fn test_rank_suggestions() {
+        let suggestion = |alias: Option<&str>, host: &str| HostSuggestion {
+            alias: alias.map(str::to_string),
+            host: host.to_string(),
+            username: None,
+            port: None,
+            source: SuggestionSource::SshConfig,
+        };
+        let suggestions = vec![
+            suggestion(None, "web-01.example.com"),
+            suggestion(Some("db"), "db-primary.example.com"),
+            suggestion(None, "dashboard.example.com"),
+        ];
+
+        let ranked = rank_suggestions("db", &suggestions, 5);
+        assert_eq!(ranked[0].suggestion.host, "db-primary.example.com");
+        assert_eq!(ranked.len(), 2);
+        assert!(rank_suggestions("", &suggestions, 5).is_empty());
+        // Typing a full host that has no alias offers nothing new
+        assert!(rank_suggestions("web-01.example.com", &suggestions, 5).is_empty());
+    }
+}
@@ -0,0 +1,92 @@
+//! iTerm2 profile import
+//!
+//! Reads profiles exported from Settings > Profiles > "Export All Profiles as
+//! JSON", or Dynamic Profiles files. Only profiles whose custom command runs
+//! `ssh` become sessions.
+
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use super::{session_from_ssh_args, split_command_line, ImportError};
+use crate::session::models::SshSession;
+
+/// Parse a profiles JSON file, or every `.json` file in a Dynamic Profiles directory
+pub fn parse_path(path: &Path) -> Result<Vec<SshSession>, ImportError> {
+    if path.is_dir() {
+        let mut files: Vec<_> = fs::read_dir(path)?
+            .filter_map(Result::ok)
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        files.sort();
+
+        let mut sessions = Vec::new();
+        for file in files {
+            sessions.extend(parse_json(&fs::read_to_string(&file)?)?);
+        }
+        return Ok(sessions);
+    }
+    parse_json(&fs::read_to_string(path)?)
+}
+
+/// Parse profiles from JSON: `{"Profiles": [..]}`, a bare array, or a single profile
+pub fn parse_json(text: &str) -> Result<Vec<SshSession>, ImportError> {
+    let value: Value = serde_json::from_str(text)?;
+    let profiles = match &value {
+        Value::Object(obj) => match obj.get("Profiles") {
+            Some(Value::Array(items)) => items.clone(),
+            Some(_) => return Err(ImportError::Format("\"Profiles\" is not an array".into())),
+            None => vec![value.clone()],
+        },
+        Value::Array(items) => items.clone(),
+        _ => return Err(ImportError::Format("expected iTerm2 profiles".into())),
+    };
+
+    Ok(profiles.iter().filter_map(session_from_profile).collect())
+}
+
+fn session_from_profile(profile: &Value) -> Option<SshSession> {
+    // "Custom Command" is "Yes" when the profile runs a command instead of the login shell
+    if profile.get("Custom Command").and_then(Value::as_str) != Some("Yes") {
+        return None;
+    }
+    let name = profile.get("Name").and_then(Value::as_str).unwrap_or("");
+    let command = profile.get("Command").and_then(Value::as_str)?;
+
+    let words = split_command_line(command);
+    let ssh_index = words
+        .iter()
+        .position(|w| Path::new(w).file_name().is_some_and(|f| f == "ssh"))?;
+    session_from_ssh_args(name, &words[ssh_index + 1..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_profiles() {
+        let json = r#"{"Profiles": [
+            {"Name": "Default", "Custom Command": "No", "Command": ""},
+            {"Name": "Bastion", "Custom Command": "Yes", "Command": "/usr/bin/ssh -p 2022 ops@bastion.example.com"},
+            {"Name": "Script", "Custom Command": "Yes", "Command": "/bin/zsh -l"},
+            {"Name": "Wrapped", "Custom Command": "Yes", "Command": "env TERM=xterm ssh -i ~/.ssh/k host2"}
+        ]}"#;
+        let sessions = parse_json(json).unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].name, "Bastion");
+        assert_eq!(sessions[0].host, "bastion.example.com");
+        assert_eq!(sessions[0].port, 2022);
+        assert_eq!(sessions[0].username, "ops");
+        assert_eq!((sessions[1].name.as_str(), sessions[1].host.as_str()), ("Wrapped", "host2"));
+    }
+
+    #[test]
+    fn test_parse_single_profile() {
+        let json = r#"{"Name": "One", "Custom Command": "Yes", "Command": "ssh one"}"#;
+        assert_eq!(parse_json(json).unwrap().len(), 1);
+        assert!(parse_json("42").is_err());
+    }
+}
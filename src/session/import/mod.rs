@@ -0,0 +1,370 @@
+//! Importers for sessions defined in other terminal managers
+//!
+//! Each importer parses its source into plain [`SshSession`]s without touching
+//! the session store, so the UI can let the user pick which hosts to keep
+//! before [`import_sessions`] adds them under a new group.
+
+pub mod iterm;
+pub mod putty;
+pub mod ssh_config;
+pub mod termius;
+
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::host_suggestions::ssh_config_path;
+use super::manager::SessionManager;
+use super::models::{AuthMethod, Session, SessionGroup, SshSession};
+
+/// Errors that can occur while reading an import source
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("Failed to read import file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to parse JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Unrecognized format: {0}")]
+    Format(String),
+}
+
+/// Application sessions can be imported from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportSource {
+    Putty,
+    Termius,
+    Iterm2,
+    SshConfig,
+}
+
+impl ImportSource {
+    /// All supported sources, in display order
+    pub const ALL: [ImportSource; 4] = [
+        ImportSource::Putty,
+        ImportSource::Termius,
+        ImportSource::Iterm2,
+        ImportSource::SshConfig,
+    ];
+
+    /// Display name
+    pub fn label(self) -> &'static str {
+        match self {
+            ImportSource::Putty => "PuTTY",
+            ImportSource::Termius => "Termius",
+            ImportSource::Iterm2 => "iTerm2",
+            ImportSource::SshConfig => "SSH config",
+        }
+    }
+
+    /// Where this application usually keeps its sessions, if there's a standard location
+    pub fn default_path(self) -> Option<PathBuf> {
+        match self {
+            ImportSource::Putty => dirs::home_dir().map(|h| h.join(".putty").join("sessions")),
+            ImportSource::Termius => None,
+            ImportSource::Iterm2 => dirs::home_dir().map(|h| {
+                h.join("Library")
+                    .join("Application Support")
+                    .join("iTerm2")
+                    .join("DynamicProfiles")
+            }),
+            ImportSource::SshConfig => ssh_config_path(),
+        }
+    }
+
+    /// Parse sessions from a file or directory exported by this source
+    pub fn parse(self, path: &Path) -> Result<Vec<SshSession>, ImportError> {
+        match self {
+            ImportSource::Putty => putty::parse_path(path),
+            ImportSource::Termius => termius::parse_file(path),
+            ImportSource::Iterm2 => iterm::parse_path(path),
+            ImportSource::SshConfig => ssh_config::parse_path(path),
+        }
+    }
+}
+
+/// Outcome of importing a batch of sessions
+#[derive(Debug, Default)]
+pub struct ImportResult {
+    /// IDs of the sessions that were added
+    pub created: Vec<Uuid>,
+    /// Names of sessions left out (e.g. already present)
+    pub skipped: Vec<String>,
+    /// Problems that stopped individual sessions (or the whole batch) from importing
+    pub errors: Vec<String>,
+}
+
+impl ImportResult {
+    /// One-line summary for display
+    pub fn summary(&self) -> String {
+        format!(
+            "Imported {} session{}, skipped {}, {} error{}",
+            self.created.len(),
+            if self.created.len() == 1 { "" } else { "s" },
+            self.skipped.len(),
+            self.errors.len(),
+            if self.errors.len() == 1 { "" } else { "s" },
+        )
+    }
+}
+
+/// Add the given sessions to a new group named `group_name`
+///
+/// Sessions that duplicate an existing SSH session (same host, port and
+/// username) are skipped. The manager is left dirty; the caller saves it.
+pub fn import_sessions(manager: &mut SessionManager, group_name: &str, sessions: Vec<SshSession>) -> ImportResult {
+    let mut result = ImportResult::default();
+    if sessions.is_empty() {
+        return result;
+    }
+
+    let group_id = match manager.add_group(SessionGroup::new(group_name)) {
+        Ok(id) => id,
+        Err(e) => {
+            result.errors.push(e.to_string());
+            return result;
+        }
+    };
+
+    for mut session in sessions {
+        if session.host.is_empty() {
+            result.errors.push(format!("{}: no host name", session.name));
+            continue;
+        }
+        if is_duplicate(manager, &session) {
+            result.skipped.push(session.name);
+            continue;
+        }
+        session.group_id = Some(group_id);
+        result.created.push(manager.add_ssh_session(session));
+    }
+
+    tracing::info!("Session import into {:?}: {}", group_name, result.summary());
+    result
+}
+
+fn is_duplicate(manager: &SessionManager, session: &SshSession) -> bool {
+    manager.all_sessions().iter().any(|existing| match existing {
+        Session::Ssh(ssh) => {
+            ssh.host.eq_ignore_ascii_case(&session.host)
+                && ssh.port == session.port
+                && ssh.username == session.username
+        }
+        _ => false,
+    })
+}
+
+/// Build an imported session, choosing key auth when a key file is known
+///
+/// Without a key the user is prompted for a password at connect time, which is
+/// what the source applications fall back to as well.
+pub(crate) fn build_session(
+    name: &str,
+    host: &str,
+    port: Option<u16>,
+    username: Option<&str>,
+    key_path: Option<&str>,
+) -> SshSession {
+    let name = if name.trim().is_empty() { host } else { name.trim() };
+    let mut session = SshSession::new(name, host.trim(), username.unwrap_or("").trim());
+    session.port = port.unwrap_or(22);
+    session.auth = match key_path.map(str::trim).filter(|p| !p.is_empty()) {
+        Some(path) => AuthMethod::PrivateKey {
+            path: PathBuf::from(path),
+            passphrase: None,
+            use_keychain: false,
+            extra_keys: Vec::new(),
+            identities_only: false,
+        },
+        None => AuthMethod::Password {
+            password: None,
+            use_keychain: false,
+        },
+    };
+    session
+}
+
+/// Split a command line into words, honouring single/double quotes and backslashes
+pub(crate) fn split_command_line(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') | (None, '\\') => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+                in_word = true;
+            }
+            (Some(_), c) => current.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(current);
+    }
+    words
+}
+
+/// Split one CSV line into fields, handling quoted fields and doubled quotes
+pub(crate) fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.trim_end_matches('\r').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    fields.push(current);
+    fields
+}
+
+/// Parse the arguments of an `ssh` command line into a session
+///
+/// `args` excludes the `ssh` program itself. Returns `None` if no destination
+/// host is found.
+pub(crate) fn session_from_ssh_args(name: &str, args: &[String]) -> Option<SshSession> {
+    // Options that take a separate argument we don't map
+    const WITH_ARG: &[&str] = &[
+        "-B", "-b", "-c", "-D", "-E", "-e", "-F", "-I", "-J", "-L", "-m", "-O", "-o", "-P", "-Q", "-R", "-S", "-W", "-w",
+    ];
+
+    let mut port = None;
+    let mut user = None;
+    let mut key = None;
+    let mut destination = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-p" => port = iter.next().and_then(|p| p.parse().ok()),
+            "-l" => user = iter.next().cloned(),
+            "-i" => key = iter.next().cloned(),
+            a if WITH_ARG.contains(&a) => {
+                iter.next();
+            }
+            a if a.starts_with("-p") && a.len() > 2 => port = a[2..].parse().ok(),
+            a if a.starts_with("-i") && a.len() > 2 => key = Some(a[2..].to_string()),
+            a if a.starts_with("-l") && a.len() > 2 => user = Some(a[2..].to_string()),
+            a if a.starts_with('-') => {}
+            a => {
+                // First non-option is the destination; anything after is the remote command
+                destination = Some(a.to_string());
+                break;
+            }
+        }
+    }
+
+    let destination = destination?;
+    let destination = destination.strip_prefix("ssh://").unwrap_or(&destination);
+    let (dest_user, host_port) = match destination.rsplit_once('@') {
+        Some((u, h)) => (Some(u.to_string()), h),
+        None => (None, destination),
+    };
+    let (host, dest_port) = match host_port.rsplit_once(':') {
+        Some((h, p)) if p.parse::<u16>().is_ok() => (h, p.parse().ok()),
+        _ => (host_port, None),
+    };
+    if host.is_empty() {
+        return None;
+    }
+
+    let user = user.or(dest_user);
+    Some(build_session(name, host, port.or(dest_port), user.as_deref(), key.as_deref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::SessionStorage;
+
+    #[test]
+    fn test_split_command_line() {
+        assert_eq!(
+            split_command_line(r#"ssh -i "/keys/my key" 'user'@host\ name"#),
+            vec!["ssh", "-i", "/keys/my key", "user@host name"]
+        );
+        assert!(split_command_line("   ").is_empty());
+    }
+
+    #[test]
+    fn test_session_from_ssh_args() {
+        let args = split_command_line("-o StrictHostKeyChecking=no -p 2222 -i ~/.ssh/id_ed25519 deploy@web1 uptime");
+        let session = session_from_ssh_args("web", &args).unwrap();
+        assert_eq!(session.name, "web");
+        assert_eq!(session.host, "web1");
+        assert_eq!(session.port, 2222);
+        assert_eq!(session.username, "deploy");
+        assert!(matches!(session.auth, AuthMethod::PrivateKey { ref path, .. } if path == Path::new("~/.ssh/id_ed25519")));
+
+        let session = session_from_ssh_args("", &split_command_line("-l root ssh://db:2200")).unwrap();
+        assert_eq!((session.name.as_str(), session.host.as_str(), session.port), ("db", "db", 2200));
+        assert_eq!(session.username, "root");
+        assert!(matches!(session.auth, AuthMethod::Password { password: None, .. }));
+
+        assert!(session_from_ssh_args("x", &split_command_line("-v")).is_none());
+    }
+
+    #[test]
+    fn test_split_csv_line() {
+        assert_eq!(
+            split_csv_line("a,\"b, c\",\"say \"\"hi\"\"\",\r"),
+            vec!["a", "b, c", "say \"hi\"", ""]
+        );
+    }
+
+    #[test]
+    fn test_import_sessions_skips_duplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = SessionStorage::with_path(dir.path().join("sessions.json"));
+        let mut manager = SessionManager::with_storage(storage).unwrap();
+        manager.add_ssh_session(SshSession::new("existing", "web.example.com", "deploy"));
+
+        let sessions = vec![
+            build_session("web", "WEB.example.com", None, Some("deploy"), None),
+            build_session("db", "db.example.com", Some(5022), Some("admin"), None),
+            build_session("blank", "", None, None, None),
+        ];
+        let result = import_sessions(&mut manager, "Imported from PuTTY", sessions);
+
+        assert_eq!(result.created.len(), 1);
+        assert_eq!(result.skipped, vec!["web".to_string()]);
+        assert_eq!(result.errors.len(), 1);
+
+        let group = manager.all_groups().iter().find(|g| g.name == "Imported from PuTTY").unwrap();
+        let imported = manager.get_session(result.created[0]).unwrap();
+        assert_eq!(imported.group_id(), Some(group.id));
+
+        assert!(import_sessions(&mut manager, "Empty", Vec::new()).created.is_empty());
+        assert_eq!(manager.all_groups().len(), 1);
+    }
+}
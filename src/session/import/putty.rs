@@ -0,0 +1,240 @@
+//! PuTTY session import
+//!
+//! Reads either a Windows registry export (`reg export
+//! HKCU\Software\SimonTatham\PuTTY\Sessions`) or the Unix `~/.putty/sessions`
+//! directory, where each file holds one session as `Key=Value` lines.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use super::{build_session, ImportError};
+use crate::session::models::SshSession;
+
+/// Registry key prefix for PuTTY sessions
+const SESSIONS_KEY: &str = "\\Software\\SimonTatham\\PuTTY\\Sessions\\";
+
+/// Parse a `.reg` export, a sessions directory, or a single session file
+pub fn parse_path(path: &Path) -> Result<Vec<SshSession>, ImportError> {
+    if path.is_dir() {
+        let mut entries: Vec<_> = fs::read_dir(path)?.filter_map(Result::ok).collect();
+        entries.sort_by_key(|e| e.file_name());
+
+        let mut sessions = Vec::new();
+        for entry in entries {
+            let file_path = entry.path();
+            if !file_path.is_file() {
+                continue;
+            }
+            let name = unescape_session_name(&entry.file_name().to_string_lossy());
+            let text = fs::read_to_string(&file_path)?;
+            if let Some(session) = session_from_values(&name, &parse_unix_session(&text)) {
+                sessions.push(session);
+            }
+        }
+        return Ok(sessions);
+    }
+
+    let text = decode_text(&fs::read(path)?);
+    if text.trim_start().starts_with("Windows Registry Editor") || text.trim_start().starts_with("REGEDIT") {
+        Ok(parse_reg(&text))
+    } else {
+        let name = path
+            .file_name()
+            .map(|n| unescape_session_name(&n.to_string_lossy()))
+            .unwrap_or_default();
+        Ok(session_from_values(&name, &parse_unix_session(&text)).into_iter().collect())
+    }
+}
+
+/// Parse a registry export into sessions
+pub fn parse_reg(text: &str) -> Vec<SshSession> {
+    let mut sessions = Vec::new();
+    let mut current: Option<(String, HashMap<String, String>)> = None;
+
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(key) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            if let Some((name, values)) = current.take() {
+                sessions.extend(session_from_values(&name, &values));
+            }
+            current = key
+                .find(SESSIONS_KEY)
+                .map(|i| &key[i + SESSIONS_KEY.len()..])
+                .filter(|name| !name.is_empty() && !name.contains('\\'))
+                .map(|name| (unescape_session_name(name), HashMap::new()));
+            continue;
+        }
+
+        let Some((_, values)) = current.as_mut() else {
+            continue;
+        };
+        if let Some((key, value)) = parse_reg_value(line) {
+            values.insert(key, value);
+        }
+    }
+    if let Some((name, values)) = current {
+        sessions.extend(session_from_values(&name, &values));
+    }
+    sessions
+}
+
+/// Parse a `"Name"="string"` or `"Name"=dword:0000abcd` registry line
+fn parse_reg_value(line: &str) -> Option<(String, String)> {
+    let rest = line.strip_prefix('"')?;
+    let (key, value) = rest.split_once("\"=")?;
+    let value = if let Some(hex) = value.strip_prefix("dword:") {
+        u32::from_str_radix(hex.trim(), 16).ok()?.to_string()
+    } else {
+        let quoted = value.strip_prefix('"')?.strip_suffix('"')?;
+        quoted.replace("\\\\", "\\").replace("\\\"", "\"")
+    };
+    Some((key.to_string(), value))
+}
+
+/// Parse a Unix session file (`Key=Value` per line)
+fn parse_unix_session(text: &str) -> HashMap<String, String> {
+    text.lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(k, v)| (k.trim().to_string(), v.trim_end_matches('\r').to_string()))
+        .collect()
+}
+
+/// Build a session from PuTTY settings, ignoring non-SSH and default entries
+fn session_from_values(name: &str, values: &HashMap<String, String>) -> Option<SshSession> {
+    if name == "Default Settings" {
+        return None;
+    }
+    let protocol = values.get("Protocol").map(String::as_str).unwrap_or("ssh");
+    if protocol != "ssh" {
+        tracing::debug!("Skipping PuTTY session {:?} with protocol {}", name, protocol);
+        return None;
+    }
+    let host = values.get("HostName").map(|h| h.trim()).filter(|h| !h.is_empty())?;
+
+    // PuTTY allows "user@host" in the host name field
+    let (user_from_host, host) = match host.rsplit_once('@') {
+        Some((u, h)) => (Some(u), h),
+        None => (None, host),
+    };
+    let username = values
+        .get("UserName")
+        .map(String::as_str)
+        .filter(|u| !u.is_empty())
+        .or(user_from_host);
+    let port = values.get("PortNumber").and_then(|p| p.parse().ok());
+    let key = values.get("PublicKeyFile").map(String::as_str);
+    if key.is_some_and(|k| k.to_ascii_lowercase().ends_with(".ppk")) {
+        tracing::warn!("PuTTY session {:?} uses a .ppk key; convert it to OpenSSH format with puttygen", name);
+    }
+
+    Some(build_session(name, host, port, username, key))
+}
+
+/// Decode PuTTY's percent-escaped session names (`My%20Server`)
+fn unescape_session_name(name: &str) -> String {
+    let bytes = name.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let Some(b) = name.get(i + 1..i + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                out.push(b);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Registry exports from regedit are UTF-16LE with a BOM
+fn decode_text(bytes: &[u8]) -> String {
+    if let Some(rest) = bytes.strip_prefix(&[0xff, 0xfe]) {
+        let units: Vec<u16> = rest.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+        String::from_utf16_lossy(&units)
+    } else {
+        String::from_utf8_lossy(bytes.strip_prefix(&[0xef, 0xbb, 0xbf]).unwrap_or(bytes)).into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::models::AuthMethod;
+    use tempfile::tempdir;
+
+    const REG: &str = r#"Windows Registry Editor Version 5.00
+
+[HKEY_CURRENT_USER\Software\SimonTatham\PuTTY\Sessions]
+
+[HKEY_CURRENT_USER\Software\SimonTatham\PuTTY\Sessions\Default%20Settings]
+"HostName"=""
+
+[HKEY_CURRENT_USER\Software\SimonTatham\PuTTY\Sessions\Prod%20Web]
+"HostName"="web.example.com"
+"PortNumber"=dword:00000916
+"UserName"="deploy"
+"Protocol"="ssh"
+"PublicKeyFile"="C:\\Users\\me\\.ssh\\id_rsa"
+
+[HKEY_CURRENT_USER\Software\SimonTatham\PuTTY\Sessions\Serial]
+"HostName"="COM1"
+"Protocol"="serial"
+
+[HKEY_CURRENT_USER\Software\SimonTatham\PuTTY\Sessions\db]
+"HostName"="admin@db.internal"
+"PortNumber"=dword:00000016
+"#;
+
+    #[test]
+    fn test_parse_reg() {
+        let sessions = parse_reg(REG);
+        assert_eq!(sessions.len(), 2);
+
+        let web = &sessions[0];
+        assert_eq!(web.name, "Prod Web");
+        assert_eq!(web.host, "web.example.com");
+        assert_eq!(web.port, 2326);
+        assert_eq!(web.username, "deploy");
+        assert!(
+            matches!(web.auth, AuthMethod::PrivateKey { ref path, .. } if path.to_string_lossy() == r"C:\Users\me\.ssh\id_rsa")
+        );
+
+        let db = &sessions[1];
+        assert_eq!((db.host.as_str(), db.username.as_str(), db.port), ("db.internal", "admin", 22));
+        assert!(matches!(db.auth, AuthMethod::Password { password: None, .. }));
+    }
+
+    #[test]
+    fn test_parse_utf16_reg_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("putty.reg");
+        let mut bytes = vec![0xff, 0xfe];
+        for unit in REG.encode_utf16() {
+            bytes.extend(unit.to_le_bytes());
+        }
+        fs::write(&path, bytes).unwrap();
+
+        assert_eq!(parse_path(&path).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_parse_unix_sessions_dir() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("Build%20Box"),
+            "HostName=build.local\nPortNumber=2200\nUserName=ci\nProtocol=ssh\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("Default%20Settings"), "HostName=\n").unwrap();
+
+        let sessions = parse_path(dir.path()).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].name, "Build Box");
+        assert_eq!(sessions[0].port, 2200);
+        assert_eq!(sessions[0].username, "ci");
+    }
+}
@@ -0,0 +1,375 @@
+//! OpenSSH client config import (`~/.ssh/config`)
+//!
+//! Every concrete `Host` alias becomes a session. Its settings are gathered
+//! the way `ssh` does it: each `Host` block whose patterns match the alias
+//! contributes, and the first value seen for a keyword wins, so `Host *`
+//! defaults at the end of the file still apply. `Include` files are read in
+//! place. `Match` blocks and keywords with no session equivalent are skipped.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::ImportError;
+use crate::session::models::{AuthMethod, JumpHostConfig, SshSession};
+
+/// Deepest chain of `Include`s followed, as a guard against include loops
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// A `Host` (or `Match`) block and the options set inside it
+struct HostBlock {
+    /// Host patterns; empty for a `Match` block, which never matches
+    patterns: Vec<String>,
+    /// Options as (lowercased keyword, value), in file order
+    options: Vec<(String, String)>,
+}
+
+/// Settings for one alias after applying every matching block
+#[derive(Default)]
+struct HostSettings {
+    hostname: Option<String>,
+    user: Option<String>,
+    port: Option<u16>,
+    identity_files: Vec<String>,
+    identities_only: Option<bool>,
+    proxy_jump: Option<String>,
+}
+
+/// Parse a config file, following its `Include`s
+///
+/// Relative `Include` paths are taken from the config's own directory, as
+/// `ssh` does for `~/.ssh/config`.
+pub fn parse_path(path: &Path) -> Result<Vec<SshSession>, ImportError> {
+    let text = fs::read_to_string(path)?;
+    let base = path.parent().unwrap_or(Path::new("."));
+    let mut blocks = vec![HostBlock::everything()];
+    read_blocks(&text, base, &mut blocks, 0);
+    Ok(sessions_from_blocks(&blocks))
+}
+
+/// Parse config text without following `Include`s
+pub fn parse_config(text: &str) -> Vec<SshSession> {
+    let mut blocks = vec![HostBlock::everything()];
+    read_blocks(text, Path::new("."), &mut blocks, MAX_INCLUDE_DEPTH);
+    sessions_from_blocks(&blocks)
+}
+
+impl HostBlock {
+    /// Options before the first `Host` line apply to every host
+    fn everything() -> Self {
+        Self {
+            patterns: vec!["*".to_string()],
+            options: Vec::new(),
+        }
+    }
+
+    /// Whether `alias` matches the pattern list: some pattern matches and no negated one does
+    fn matches(&self, alias: &str) -> bool {
+        let mut matched = false;
+        for pattern in &self.patterns {
+            match pattern.strip_prefix('!') {
+                Some(negated) if wildcard_match(negated, alias) => return false,
+                Some(_) => {}
+                None => matched |= wildcard_match(pattern, alias),
+            }
+        }
+        matched
+    }
+}
+
+/// Append the blocks in `text` to `blocks`, reading included files at `depth + 1`
+fn read_blocks(text: &str, base: &Path, blocks: &mut Vec<HostBlock>, depth: usize) {
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = match line.split_once(|c: char| c.is_whitespace() || c == '=') {
+            Some((key, value)) => (key, value.trim_start_matches(|c: char| c.is_whitespace() || c == '=').trim()),
+            None => continue,
+        };
+
+        match key.to_ascii_lowercase().as_str() {
+            "host" => blocks.push(HostBlock {
+                patterns: value.split_whitespace().map(|p| unquote(p).to_string()).collect(),
+                options: Vec::new(),
+            }),
+            "match" => blocks.push(HostBlock {
+                patterns: Vec::new(),
+                options: Vec::new(),
+            }),
+            "include" if depth < MAX_INCLUDE_DEPTH => {
+                for pattern in value.split_whitespace() {
+                    for file in include_files(unquote(pattern), base) {
+                        match fs::read_to_string(&file) {
+                            Ok(text) => read_blocks(&text, base, blocks, depth + 1),
+                            Err(e) => tracing::warn!("Skipping ssh config include {}: {}", file.display(), e),
+                        }
+                    }
+                }
+            }
+            "include" => tracing::warn!("Not following ssh config include {}: nested too deeply", value),
+            key => {
+                if let Some(block) = blocks.last_mut() {
+                    block.options.push((key.to_string(), unquote(value).to_string()));
+                }
+            }
+        }
+    }
+}
+
+/// Files named by an `Include` pattern, sorted
+///
+/// Wildcards are supported in the file name only, e.g. `config.d/*`.
+fn include_files(pattern: &str, base: &Path) -> Vec<PathBuf> {
+    let path = match pattern.strip_prefix("~/") {
+        Some(rest) => match dirs::home_dir() {
+            Some(home) => home.join(rest),
+            None => return Vec::new(),
+        },
+        None => base.join(pattern),
+    };
+    let Some(name) = path.file_name().map(|n| n.to_string_lossy().into_owned()) else {
+        return Vec::new();
+    };
+    if !name.contains(['*', '?']) {
+        return vec![path];
+    }
+
+    let Some(Ok(entries)) = path.parent().map(fs::read_dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.file_name().is_some_and(|f| wildcard_match(&name, &f.to_string_lossy())))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Turn every concrete alias into a session, in the order they first appear
+fn sessions_from_blocks(blocks: &[HostBlock]) -> Vec<SshSession> {
+    let mut aliases: Vec<&str> = Vec::new();
+    for pattern in blocks.iter().flat_map(|block| &block.patterns) {
+        if !pattern.contains(['*', '?', '!']) && !aliases.contains(&pattern.as_str()) {
+            aliases.push(pattern);
+        }
+    }
+
+    aliases
+        .iter()
+        .map(|alias| {
+            let settings = resolve(blocks, alias);
+            let mut session = SshSession::new(*alias, host_for(alias, &settings), settings.user.as_deref().unwrap_or(""));
+            session.port = settings.port.unwrap_or(22);
+            session.auth = auth_for(&settings);
+            session.jump_host = settings
+                .proxy_jump
+                .as_deref()
+                .and_then(|spec| jump_host_for(spec, blocks, &aliases));
+            session
+        })
+        .collect()
+}
+
+/// Collect the settings for `alias` from every block that matches it, first value winning
+fn resolve(blocks: &[HostBlock], alias: &str) -> HostSettings {
+    let mut settings = HostSettings::default();
+    for (key, value) in blocks
+        .iter()
+        .filter(|block| block.matches(alias))
+        .flat_map(|block| &block.options)
+    {
+        match key.as_str() {
+            "hostname" if settings.hostname.is_none() => settings.hostname = Some(value.clone()),
+            "user" if settings.user.is_none() => settings.user = Some(value.clone()),
+            "port" if settings.port.is_none() => settings.port = value.parse().ok(),
+            "identityfile" if !settings.identity_files.contains(value) => settings.identity_files.push(value.clone()),
+            "identitiesonly" if settings.identities_only.is_none() => {
+                settings.identities_only = Some(value.eq_ignore_ascii_case("yes"));
+            }
+            "proxyjump" if settings.proxy_jump.is_none() => settings.proxy_jump = Some(value.clone()),
+            _ => {}
+        }
+    }
+    settings
+}
+
+/// The address to connect to: `HostName` with `%h` filled in, or the alias itself
+fn host_for(alias: &str, settings: &HostSettings) -> String {
+    match &settings.hostname {
+        Some(hostname) => hostname.replace("%h", alias),
+        None => alias.to_string(),
+    }
+}
+
+/// Key auth with the configured identity files, or the agent like `ssh` without any
+fn auth_for(settings: &HostSettings) -> AuthMethod {
+    let mut keys = settings.identity_files.iter().map(PathBuf::from);
+    match keys.next() {
+        Some(path) => AuthMethod::PrivateKey {
+            path,
+            passphrase: None,
+            use_keychain: false,
+            extra_keys: keys.collect(),
+            identities_only: settings.identities_only.unwrap_or(false),
+        },
+        None => AuthMethod::Agent,
+    }
+}
+
+/// The first hop of a `ProxyJump` value, resolved through the config if it names an alias
+///
+/// Later hops of a multi-hop chain are dropped; only one jump host is supported.
+fn jump_host_for(spec: &str, blocks: &[HostBlock], aliases: &[&str]) -> Option<JumpHostConfig> {
+    let first_hop = spec.split(',').next()?.trim();
+    let first_hop = first_hop.strip_prefix("ssh://").unwrap_or(first_hop);
+    if first_hop.eq_ignore_ascii_case("none") {
+        return None;
+    }
+    let mut jump = JumpHostConfig::parse(first_hop)?;
+    if aliases.contains(&jump.host.as_str()) {
+        let settings = resolve(blocks, &jump.host);
+        if jump.username.is_empty() {
+            jump.username = settings.user.clone().unwrap_or_default();
+        }
+        if jump.port == 22 {
+            jump.port = settings.port.unwrap_or(22);
+        }
+        jump.auth = auth_for(&settings);
+        jump.host = host_for(&jump.host, &settings);
+    }
+    Some(jump)
+}
+
+/// Strip one pair of surrounding double quotes
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// Case-insensitive match of `text` against a pattern with `*` and `?` wildcards
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position after the last `*` and the text position it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p + 1, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((after_star, tried)) => {
+                    p = after_star;
+                    t = tried + 1;
+                    backtrack = Some((after_star, tried + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config = r#"
+# Personal servers
+Host web web-alias
+    HostName web.example.com
+    User deploy
+    Port 2222
+    IdentityFile ~/.ssh/id_ed25519
+    IdentityFile "~/.ssh/deploy key"
+    IdentitiesOnly yes
+
+Host bastion
+    HostName %h.example.com
+    User ops
+
+Host db
+    HostName=10.0.0.5
+    ProxyJump bastion,other
+
+Match host *.internal
+    User ignored
+
+Host * !db
+    User fallback
+    Port 2200
+
+Host *
+    ForwardAgent yes
+    User everyone
+"#;
+        let sessions = parse_config(config);
+        let names: Vec<&str> = sessions.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["web", "web-alias", "bastion", "db"]);
+
+        let web = &sessions[0];
+        assert_eq!((web.host.as_str(), web.port, web.username.as_str()), ("web.example.com", 2222, "deploy"));
+        assert!(matches!(
+            &web.auth,
+            AuthMethod::PrivateKey { path, extra_keys, identities_only: true, .. }
+                if path == Path::new("~/.ssh/id_ed25519") && extra_keys == &[PathBuf::from("~/.ssh/deploy key")]
+        ));
+        assert_eq!(sessions[1].host, "web.example.com");
+
+        // Host * defaults fill in what the host's own block left out
+        let bastion = &sessions[2];
+        assert_eq!((bastion.host.as_str(), bastion.port, bastion.username.as_str()), ("bastion.example.com", 2200, "ops"));
+        assert_eq!(bastion.auth, AuthMethod::Agent);
+        assert!(bastion.jump_host.is_none());
+
+        // Negated patterns exclude, and the jump host alias is resolved
+        let db = &sessions[3];
+        assert_eq!((db.host.as_str(), db.port, db.username.as_str()), ("10.0.0.5", 22, "everyone"));
+        let jump = db.jump_host.as_ref().unwrap();
+        assert_eq!((jump.host.as_str(), jump.port, jump.username.as_str()), ("bastion.example.com", 2200, "ops"));
+
+        assert!(parse_config("Host *\n  User me\n").is_empty());
+    }
+
+    #[test]
+    fn test_parse_path_follows_includes() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("config.d")).unwrap();
+        fs::write(dir.path().join("config.d").join("10-work"), "Host work\n  HostName work.example.com\n").unwrap();
+        fs::write(dir.path().join("config.d").join("20-lab"), "Host lab\n  User root\n").unwrap();
+        fs::write(dir.path().join("loop"), "Include loop\nHost looped\n").unwrap();
+        let config = dir.path().join("config");
+        fs::write(&config, "Include config.d/*\nInclude missing loop\n\nHost home\n").unwrap();
+
+        let sessions = parse_path(&config).unwrap();
+        let names: Vec<&str> = sessions.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["work", "lab", "looped", "home"]);
+        assert_eq!(sessions[0].host, "work.example.com");
+        assert_eq!(sessions[1].username, "root");
+
+        assert!(parse_path(&dir.path().join("nope")).is_err());
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*", "anything"));
+        assert!(wildcard_match("*.example.com", "Web.Example.com"));
+        assert!(wildcard_match("web-?", "web-1"));
+        assert!(wildcard_match("a*b*c", "axxbyybc"));
+        assert!(!wildcard_match("web-?", "web-10"));
+        assert!(!wildcard_match("*.example.com", "example.com"));
+    }
+}
@@ -0,0 +1,142 @@
+//! Termius export import
+//!
+//! Termius exports hosts as JSON (either a bare array or an object with a
+//! `hosts` array) or as CSV with a header row. Field names differ between
+//! versions, so the common spellings are all accepted.
+
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use super::{build_session, split_csv_line, ImportError};
+use crate::session::models::SshSession;
+
+const NAME_FIELDS: &[&str] = &["label", "name", "title"];
+const HOST_FIELDS: &[&str] = &["address", "host", "hostname", "ip"];
+const PORT_FIELDS: &[&str] = &["port", "ssh_port"];
+const USER_FIELDS: &[&str] = &["username", "user", "login"];
+const KEY_FIELDS: &[&str] = &["key", "key_path", "identity_file", "ssh_key"];
+
+/// Parse a Termius JSON or CSV export
+pub fn parse_file(path: &Path) -> Result<Vec<SshSession>, ImportError> {
+    let text = fs::read_to_string(path)?;
+    let trimmed = text.trim_start_matches('\u{feff}').trim_start();
+    if trimmed.starts_with('[') || trimmed.starts_with('{') {
+        parse_json(trimmed)
+    } else {
+        parse_csv(trimmed)
+    }
+}
+
+/// Parse a JSON export
+pub fn parse_json(text: &str) -> Result<Vec<SshSession>, ImportError> {
+    let value: Value = serde_json::from_str(text)?;
+    let hosts = match &value {
+        Value::Array(items) => items,
+        Value::Object(obj) => match obj.get("hosts") {
+            Some(Value::Array(items)) => items,
+            _ => return Err(ImportError::Format("expected a \"hosts\" array".into())),
+        },
+        _ => return Err(ImportError::Format("expected an array of hosts".into())),
+    };
+
+    Ok(hosts.iter().filter_map(session_from_json).collect())
+}
+
+fn session_from_json(host: &Value) -> Option<SshSession> {
+    let field = |names: &[&str]| -> Option<String> {
+        names.iter().find_map(|name| match host.get(*name)? {
+            Value::String(s) if !s.is_empty() => Some(s.clone()),
+            Value::Number(n) => Some(n.to_string()),
+            // Newer exports nest the key as {"key": {"label": .., "path": ..}}
+            Value::Object(obj) => obj.get("path").and_then(Value::as_str).map(str::to_string),
+            _ => None,
+        })
+    };
+
+    let address = field(HOST_FIELDS)?;
+    let name = field(NAME_FIELDS).unwrap_or_default();
+    let port = field(PORT_FIELDS).and_then(|p| p.parse().ok());
+    Some(build_session(
+        &name,
+        &address,
+        port,
+        field(USER_FIELDS).as_deref(),
+        field(KEY_FIELDS).as_deref(),
+    ))
+}
+
+/// Parse a CSV export with a header row
+pub fn parse_csv(text: &str) -> Result<Vec<SshSession>, ImportError> {
+    let mut lines = text.lines().filter(|l| !l.trim().is_empty());
+    let header: Vec<String> = lines
+        .next()
+        .map(split_csv_line)
+        .ok_or_else(|| ImportError::Format("empty CSV file".into()))?
+        .into_iter()
+        .map(|h| h.trim().to_ascii_lowercase())
+        .collect();
+
+    let column = |names: &[&str]| header.iter().position(|h| names.contains(&h.as_str()));
+    let host_col = column(HOST_FIELDS).ok_or_else(|| ImportError::Format("CSV has no address column".into()))?;
+    let name_col = column(NAME_FIELDS);
+    let port_col = column(PORT_FIELDS);
+    let user_col = column(USER_FIELDS);
+    let key_col = column(KEY_FIELDS);
+
+    let sessions = lines
+        .filter_map(|line| {
+            let row = split_csv_line(line);
+            let get = |col: Option<usize>| col.and_then(|c| row.get(c)).map(String::as_str);
+            let host = get(Some(host_col)).filter(|h| !h.trim().is_empty())?;
+            Some(build_session(
+                get(name_col).unwrap_or(""),
+                host,
+                get(port_col).and_then(|p| p.trim().parse().ok()),
+                get(user_col),
+                get(key_col),
+            ))
+        })
+        .collect();
+    Ok(sessions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::models::AuthMethod;
+
+    #[test]
+    fn test_parse_json_variants() {
+        let json = r#"{"hosts": [
+            {"label": "web", "address": "10.0.0.5", "port": 2222, "username": "ubuntu",
+             "key": {"label": "deploy", "path": "~/.ssh/deploy"}},
+            {"name": "db", "hostname": "db.local"},
+            {"label": "no address"}
+        ]}"#;
+        let sessions = parse_json(json).unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].name, "web");
+        assert_eq!(sessions[0].port, 2222);
+        assert_eq!(sessions[0].username, "ubuntu");
+        assert!(matches!(sessions[0].auth, AuthMethod::PrivateKey { ref path, .. } if path == Path::new("~/.ssh/deploy")));
+        assert_eq!((sessions[1].name.as_str(), sessions[1].port), ("db", 22));
+
+        assert_eq!(parse_json(r#"[{"host": "a"}]"#).unwrap().len(), 1);
+        assert!(parse_json(r#"{"groups": []}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let csv = "Label,Address,Port,Username\n\"Web, prod\",web.example.com,22,admin\n,bare.host,,\n";
+        let sessions = parse_csv(csv).unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].name, "Web, prod");
+        assert_eq!(sessions[0].username, "admin");
+        assert_eq!(sessions[1].name, "bare.host");
+        assert!(matches!(sessions[1].auth, AuthMethod::Password { password: None, .. }));
+
+        assert!(parse_csv("Label,Port\nx,22\n").is_err());
+    }
+}
@@ -0,0 +1,232 @@
+//! `redpill://` and `ssh://` links
+//!
+//! Links let wikis and dashboards open a session with one click. Supported
+//! forms:
+//!
+//! - `ssh://[user@]host[:port]`
+//! - `redpill://ssh/[user@]host[:port]`
+//! - `redpill://session/<id or name>` for a saved session
+//!
+//! Anything after the authority (`/path`, `?query`, `#fragment`) is ignored
+//! for SSH targets.
+
+use thiserror::Error;
+
+use super::manager::SessionManager;
+use super::models::{Session, SshSession};
+
+/// Errors that can occur while parsing a link
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum LinkError {
+    #[error("Unsupported link: {0}")]
+    Unsupported(String),
+
+    #[error("Link has no host")]
+    MissingHost,
+
+    #[error("Invalid port: {0}")]
+    InvalidPort(String),
+
+    #[error("Invalid percent-encoding in link")]
+    InvalidEncoding,
+}
+
+/// What a link asks to open
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionLink {
+    /// A saved session, by ID or name
+    Saved(String),
+    /// An SSH host, matched against saved sessions or opened as a one-off
+    Ssh {
+        host: String,
+        port: Option<u16>,
+        username: Option<String>,
+    },
+}
+
+impl SessionLink {
+    /// Whether a command-line argument looks like a link
+    pub fn is_link(arg: &str) -> bool {
+        let lower = arg.to_ascii_lowercase();
+        lower.starts_with("redpill://") || lower.starts_with("ssh://")
+    }
+
+    /// Parse a `redpill://` or `ssh://` URL
+    pub fn parse(url: &str) -> Result<Self, LinkError> {
+        let url = url.trim();
+        let (scheme, rest) = url
+            .split_once("://")
+            .ok_or_else(|| LinkError::Unsupported(url.to_string()))?;
+
+        match scheme.to_ascii_lowercase().as_str() {
+            "ssh" => parse_ssh_target(rest),
+            "redpill" => {
+                let (kind, target) = rest.split_once('/').unwrap_or((rest, ""));
+                match kind.to_ascii_lowercase().as_str() {
+                    "ssh" => parse_ssh_target(target),
+                    "session" => {
+                        let target = strip_suffix(target).trim_end_matches('/');
+                        let name = percent_decode(target)?;
+                        if name.trim().is_empty() {
+                            return Err(LinkError::Unsupported(url.to_string()));
+                        }
+                        Ok(SessionLink::Saved(name))
+                    }
+                    _ => Err(LinkError::Unsupported(url.to_string())),
+                }
+            }
+            _ => Err(LinkError::Unsupported(url.to_string())),
+        }
+    }
+
+    /// Find the saved session this link refers to, if any
+    ///
+    /// Saved links match by ID first, then by name (case-insensitive). SSH
+    /// links match a saved SSH session with the same host, and the same port
+    /// and username when the link gives them.
+    pub fn find_saved<'a>(&self, manager: &'a SessionManager) -> Option<&'a Session> {
+        match self {
+            SessionLink::Saved(target) => {
+                if let Ok(id) = target.parse() {
+                    if let Some(session) = manager.get_session(id) {
+                        return Some(session);
+                    }
+                }
+                manager
+                    .all_sessions()
+                    .iter()
+                    .find(|s| s.name().eq_ignore_ascii_case(target))
+            }
+            SessionLink::Ssh { host, port, username } => manager.all_sessions().iter().find(|s| match s {
+                Session::Ssh(ssh) => {
+                    ssh.host.eq_ignore_ascii_case(host)
+                        && !matches!(port, Some(p) if *p != ssh.port)
+                        && !matches!(username, Some(u) if *u != ssh.username)
+                }
+                _ => false,
+            }),
+        }
+    }
+
+    /// A one-off SSH session for a link that matches nothing saved
+    ///
+    /// Like `ssh`, a link without a username logs in as the local user.
+    /// Returns `None` for links to saved sessions.
+    pub fn to_transient_session(&self) -> Option<SshSession> {
+        match self {
+            SessionLink::Saved(_) => None,
+            SessionLink::Ssh { host, port, username } => {
+                let username = username
+                    .clone()
+                    .or_else(|| std::env::var("USER").or_else(|_| std::env::var("USERNAME")).ok())
+                    .unwrap_or_default();
+                let mut session = SshSession::new(host.as_str(), host.as_str(), username);
+                session.port = port.unwrap_or(22);
+                Some(session)
+            }
+        }
+    }
+}
+
+/// Drop any path, query or fragment after the authority
+fn strip_suffix(s: &str) -> &str {
+    s.split(['?', '#']).next().unwrap_or("")
+}
+
+/// Parse `[user@]host[:port]`, where host may be a bracketed IPv6 address
+fn parse_ssh_target(target: &str) -> Result<SessionLink, LinkError> {
+    let authority = strip_suffix(target).split('/').next().unwrap_or("");
+    let (username, host_port) = match authority.rsplit_once('@') {
+        Some((user, rest)) => (Some(percent_decode(user)?).filter(|u| !u.is_empty()), rest),
+        None => (None, authority),
+    };
+
+    let (host, port) = if let Some(bracketed) = host_port.strip_prefix('[') {
+        let (host, after) = bracketed.split_once(']').ok_or(LinkError::MissingHost)?;
+        (host, after.strip_prefix(':'))
+    } else {
+        match host_port.split_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (host_port, None),
+        }
+    };
+
+    if host.is_empty() {
+        return Err(LinkError::MissingHost);
+    }
+    let port = match port.filter(|p| !p.is_empty()) {
+        Some(p) => Some(p.parse::<u16>().map_err(|_| LinkError::InvalidPort(p.to_string()))?),
+        None => None,
+    };
+
+    Ok(SessionLink::Ssh {
+        host: host.to_string(),
+        port,
+        username,
+    })
+}
+
+/// Decode `%XX` escapes
+fn percent_decode(s: &str) -> Result<String, LinkError> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = bytes.get(i + 1..i + 3).ok_or(LinkError::InvalidEncoding)?;
+            let hex = std::str::from_utf8(hex).map_err(|_| LinkError::InvalidEncoding)?;
+            if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(LinkError::InvalidEncoding);
+            }
+            out.push(u8::from_str_radix(hex, 16).map_err(|_| LinkError::InvalidEncoding)?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).map_err(|_| LinkError::InvalidEncoding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ssh(host: &str, port: Option<u16>, username: Option<&str>) -> SessionLink {
+        SessionLink::Ssh {
+            host: host.to_string(),
+            port,
+            username: username.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_parse_ssh_links() {
+        assert_eq!(SessionLink::parse("ssh://example.com"), Ok(ssh("example.com", None, None)));
+        assert_eq!(
+            SessionLink::parse("ssh://deploy@10.0.0.5:2222/"),
+            Ok(ssh("10.0.0.5", Some(2222), Some("deploy")))
+        );
+        assert_eq!(
+            SessionLink::parse("redpill://ssh/ops%40corp@[::1]:22?x=1"),
+            Ok(ssh("::1", Some(22), Some("ops@corp")))
+        );
+        assert_eq!(SessionLink::parse("ssh://"), Err(LinkError::MissingHost));
+        assert_eq!(
+            SessionLink::parse("ssh://host:99999"),
+            Err(LinkError::InvalidPort("99999".into()))
+        );
+    }
+
+    #[test]
+    fn test_parse_saved_links() {
+        assert_eq!(
+            SessionLink::parse("redpill://session/Prod%20DB"),
+            Ok(SessionLink::Saved("Prod DB".into()))
+        );
+        assert!(matches!(SessionLink::parse("redpill://session/"), Err(LinkError::Unsupported(_))));
+        assert!(matches!(SessionLink::parse("https://example.com"), Err(LinkError::Unsupported(_))));
+        assert!(SessionLink::is_link("SSH://host"));
+        assert!(!SessionLink::is_link("--session"));
+    }
+}
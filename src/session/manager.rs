@@ -1,7 +1,15 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::time::SystemTime;
 use thiserror::Error;
 use uuid::Uuid;
 
-use super::models::{K8sSession, LocalSession, Session, SessionData, SessionGroup, SshSession, SsmSession};
+use super::export::{ExportError, MergeStrategy, SessionExport};
+use super::import::{import_sessions, ssh_config, ImportError, ImportResult, ImportSource};
+use super::models::{
+    GroupDefaults, K8sSession, LocalSession, SerialSession, Session, SessionData, SessionGroup, SshSession, SsmSession,
+    TelnetSession,
+};
 use super::storage::{SessionStorage, StorageError};
 
 /// Errors that can occur during session management
@@ -19,6 +27,9 @@ pub enum ManagerError {
     #[error("Cannot delete group with children")]
     GroupHasChildren,
 
+    #[error("A group cannot be placed inside itself or one of its sub-groups")]
+    CyclicGroup,
+
     #[error("Invalid operation: {0}")]
     InvalidOperation(String),
 }
@@ -121,6 +132,24 @@ impl SessionManager {
         id
     }
 
+    /// Add a new serial session
+    pub fn add_serial_session(&mut self, session: SerialSession) -> Uuid {
+        let id = session.id;
+        self.data.sessions.push(Session::Serial(session));
+        self.dirty = true;
+        tracing::info!("Added serial session: {}", id);
+        id
+    }
+
+    /// Add a new telnet session
+    pub fn add_telnet_session(&mut self, session: TelnetSession) -> Uuid {
+        let id = session.id;
+        self.data.sessions.push(Session::Telnet(session));
+        self.dirty = true;
+        tracing::info!("Added telnet session: {}", id);
+        id
+    }
+
     /// Get a session by ID
     pub fn get_session(&self, id: Uuid) -> Option<&Session> {
         self.data.find_session(id)
@@ -137,7 +166,9 @@ impl SessionManager {
         let existing = self.data.sessions.iter_mut().find(|s| s.id() == id);
         match existing {
             Some(s) => {
+                let last_connected = s.last_connected();
                 *s = Session::Ssh(session);
+                s.set_last_connected(last_connected);
                 self.dirty = true;
                 Ok(())
             }
@@ -150,7 +181,9 @@ impl SessionManager {
         let existing = self.data.sessions.iter_mut().find(|s| s.id() == id);
         match existing {
             Some(s) => {
+                let last_connected = s.last_connected();
                 *s = Session::Local(session);
+                s.set_last_connected(last_connected);
                 self.dirty = true;
                 Ok(())
             }
@@ -163,7 +196,54 @@ impl SessionManager {
         let existing = self.data.sessions.iter_mut().find(|s| s.id() == id);
         match existing {
             Some(s) => {
+                let last_connected = s.last_connected();
                 *s = Session::Ssm(session);
+                s.set_last_connected(last_connected);
+                self.dirty = true;
+                Ok(())
+            }
+            None => Err(ManagerError::SessionNotFound(id)),
+        }
+    }
+
+    /// Update a K8s session
+    pub fn update_k8s_session(&mut self, id: Uuid, session: K8sSession) -> Result<(), ManagerError> {
+        let existing = self.data.sessions.iter_mut().find(|s| s.id() == id);
+        match existing {
+            Some(s) => {
+                let last_connected = s.last_connected();
+                *s = Session::K8s(session);
+                s.set_last_connected(last_connected);
+                self.dirty = true;
+                Ok(())
+            }
+            None => Err(ManagerError::SessionNotFound(id)),
+        }
+    }
+
+    /// Update a serial session
+    pub fn update_serial_session(&mut self, id: Uuid, session: SerialSession) -> Result<(), ManagerError> {
+        let existing = self.data.sessions.iter_mut().find(|s| s.id() == id);
+        match existing {
+            Some(s) => {
+                let last_connected = s.last_connected();
+                *s = Session::Serial(session);
+                s.set_last_connected(last_connected);
+                self.dirty = true;
+                Ok(())
+            }
+            None => Err(ManagerError::SessionNotFound(id)),
+        }
+    }
+
+    /// Update a telnet session
+    pub fn update_telnet_session(&mut self, id: Uuid, session: TelnetSession) -> Result<(), ManagerError> {
+        let existing = self.data.sessions.iter_mut().find(|s| s.id() == id);
+        match existing {
+            Some(s) => {
+                let last_connected = s.last_connected();
+                *s = Session::Telnet(session);
+                s.set_last_connected(last_connected);
                 self.dirty = true;
                 Ok(())
             }
@@ -191,6 +271,31 @@ impl SessionManager {
         }
     }
 
+    /// Duplicate a session, inserting the copy right after the original
+    pub fn duplicate_session(&mut self, id: Uuid) -> Result<Uuid, ManagerError> {
+        let index = self
+            .data
+            .sessions
+            .iter()
+            .position(|s| s.id() == id)
+            .ok_or(ManagerError::SessionNotFound(id))?;
+
+        let copy = self.data.sessions[index].duplicate();
+        let new_id = copy.id();
+        self.data.sessions.insert(index + 1, copy);
+        self.dirty = true;
+        tracing::info!("Duplicated session {} as {}", id, new_id);
+        Ok(new_id)
+    }
+
+    /// Note that a tab was just opened for a session
+    pub fn mark_connected(&mut self, id: Uuid) -> Result<(), ManagerError> {
+        let session = self.data.find_session_mut(id).ok_or(ManagerError::SessionNotFound(id))?;
+        session.set_last_connected(Some(SystemTime::now()));
+        self.dirty = true;
+        Ok(())
+    }
+
     /// Get all sessions
     pub fn all_sessions(&self) -> &[Session] {
         &self.data.sessions
@@ -226,12 +331,20 @@ impl SessionManager {
     // === Group CRUD Operations ===
 
     /// Add a new group
-    pub fn add_group(&mut self, group: SessionGroup) -> Uuid {
+    ///
+    /// Fails if the parent doesn't exist or the group would end up as its own
+    /// ancestor.
+    pub fn add_group(&mut self, group: SessionGroup) -> Result<Uuid, ManagerError> {
+        if self.data.find_group(group.id).is_some() {
+            return Err(ManagerError::InvalidOperation(format!("Group {} already exists", group.id)));
+        }
+        self.check_parent(group.id, group.parent_id)?;
+
         let id = group.id;
         self.data.groups.push(group);
         self.dirty = true;
         tracing::info!("Added group: {}", id);
-        id
+        Ok(id)
     }
 
     /// Get a group by ID
@@ -247,17 +360,63 @@ impl SessionManager {
 
     /// Update a group
     pub fn update_group(&mut self, id: Uuid, group: SessionGroup) -> Result<(), ManagerError> {
+        if self.data.find_group(id).is_none() {
+            return Err(ManagerError::GroupNotFound(id));
+        }
+        self.check_parent(id, group.parent_id)?;
+
         let existing = self.data.groups.iter_mut().find(|g| g.id == id);
         match existing {
             Some(g) => {
                 *g = group;
                 self.dirty = true;
+                self.apply_group_defaults(id);
                 Ok(())
             }
             None => Err(ManagerError::GroupNotFound(id)),
         }
     }
 
+    /// Defaults for sessions in a group, with unset fields taken from its ancestors
+    pub fn group_defaults(&self, group_id: Uuid) -> GroupDefaults {
+        let mut defaults = GroupDefaults::default();
+        let mut visited = HashSet::new();
+        let mut current = self.data.find_group(group_id);
+        while let Some(group) = current {
+            if !visited.insert(group.id) {
+                break;
+            }
+            defaults = defaults.or(&group.defaults);
+            current = group.parent_id.and_then(|parent_id| self.data.find_group(parent_id));
+        }
+        defaults
+    }
+
+    /// Bring the inherited settings of SSH sessions under a group up to date
+    fn apply_group_defaults(&mut self, group_id: Uuid) {
+        let session_ids: HashSet<Uuid> = self.get_all_sessions_in_group_recursive(group_id).into_iter().collect();
+        let defaults_by_group: HashMap<Uuid, GroupDefaults> = self
+            .data
+            .sessions
+            .iter()
+            .filter(|session| session_ids.contains(&session.id()))
+            .filter_map(|session| session.group_id())
+            .map(|id| (id, self.group_defaults(id)))
+            .collect();
+
+        for session in &mut self.data.sessions {
+            let Session::Ssh(ssh) = session else {
+                continue;
+            };
+            if !session_ids.contains(&ssh.id) {
+                continue;
+            }
+            if let Some(defaults) = ssh.group_id.and_then(|id| defaults_by_group.get(&id)) {
+                ssh.apply_group_defaults(defaults);
+            }
+        }
+    }
+
     /// Delete a group (fails if it has children)
     pub fn delete_group(&mut self, id: Uuid) -> Result<SessionGroup, ManagerError> {
         // Check for child groups
@@ -321,26 +480,7 @@ impl SessionManager {
 
     /// Move a group to a different parent
     pub fn move_group(&mut self, group_id: Uuid, new_parent_id: Option<Uuid>) -> Result<(), ManagerError> {
-        // Verify new parent exists if specified
-        if let Some(pid) = new_parent_id {
-            if self.data.find_group(pid).is_none() {
-                return Err(ManagerError::GroupNotFound(pid));
-            }
-
-            // Prevent circular references
-            if pid == group_id {
-                return Err(ManagerError::InvalidOperation(
-                    "Cannot make a group its own parent".to_string()
-                ));
-            }
-
-            // Check if new_parent_id is a descendant of group_id
-            if self.is_descendant(pid, group_id) {
-                return Err(ManagerError::InvalidOperation(
-                    "Cannot move a group to one of its descendants".to_string()
-                ));
-            }
-        }
+        self.check_parent(group_id, new_parent_id)?;
 
         let group = self.data.find_group_mut(group_id)
             .ok_or(ManagerError::GroupNotFound(group_id))?;
@@ -350,10 +490,34 @@ impl SessionManager {
         Ok(())
     }
 
+    /// Verify that `parent_id` is a valid parent for group `group_id`
+    ///
+    /// The parent must exist and must not be the group itself or one of its
+    /// descendants.
+    fn check_parent(&self, group_id: Uuid, parent_id: Option<Uuid>) -> Result<(), ManagerError> {
+        let Some(pid) = parent_id else {
+            return Ok(());
+        };
+        if self.data.find_group(pid).is_none() {
+            return Err(ManagerError::GroupNotFound(pid));
+        }
+        if pid == group_id || self.is_descendant(pid, group_id) {
+            return Err(ManagerError::CyclicGroup);
+        }
+        Ok(())
+    }
+
     /// Check if `potential_descendant` is a descendant of `ancestor`
+    ///
+    /// Stops if the parent chain loops back on itself (corrupt data), so this
+    /// always terminates.
     fn is_descendant(&self, potential_descendant: Uuid, ancestor: Uuid) -> bool {
+        let mut visited = HashSet::new();
         let mut current = self.data.find_group(potential_descendant);
         while let Some(group) = current {
+            if !visited.insert(group.id) {
+                break;
+            }
             if let Some(parent_id) = group.parent_id {
                 if parent_id == ancestor {
                     return true;
@@ -371,6 +535,17 @@ impl SessionManager {
     /// Get all session IDs in a group (including nested groups)
     pub fn get_all_sessions_in_group_recursive(&self, group_id: Uuid) -> Vec<Uuid> {
         let mut session_ids = Vec::new();
+        self.collect_sessions_in_group(group_id, &mut HashSet::new(), &mut session_ids);
+        session_ids
+    }
+
+    /// Depth-first walk of a group's subtree, skipping groups already visited
+    /// so a cyclic hierarchy in a corrupt sessions file can't recurse forever
+    fn collect_sessions_in_group(&self, group_id: Uuid, visited: &mut HashSet<Uuid>, session_ids: &mut Vec<Uuid>) {
+        if !visited.insert(group_id) {
+            tracing::warn!("Group hierarchy contains a cycle at {}", group_id);
+            return;
+        }
 
         // Add sessions directly in this group
         for session in self.data.sessions_in_group(group_id) {
@@ -379,10 +554,20 @@ impl SessionManager {
 
         // Recursively add sessions from child groups
         for child_group in self.data.child_groups(Some(group_id)) {
-            session_ids.extend(self.get_all_sessions_in_group_recursive(child_group.id));
+            self.collect_sessions_in_group(child_group.id, visited, session_ids);
         }
+    }
 
-        session_ids
+    // === Import ===
+
+    /// Add the concrete hosts of an OpenSSH client config as sessions in a new group
+    ///
+    /// Hosts already saved with the same address and user are skipped. The
+    /// manager is left dirty; the caller saves it.
+    pub fn import_from_ssh_config(&mut self, path: &Path) -> Result<ImportResult, ImportError> {
+        let sessions = ssh_config::parse_path(path)?;
+        let group_name = format!("Imported from {}", ImportSource::SshConfig.label());
+        Ok(import_sessions(self, &group_name, sessions))
     }
 
     // === Persistence ===
@@ -406,6 +591,59 @@ impl SessionManager {
         Ok(())
     }
 
+    /// Write every group and session to a portable export file
+    ///
+    /// Saved passwords and passphrases are only written with `include_secrets`,
+    /// and then in plain text. Returns the number of sessions exported.
+    pub fn export(&self, path: &Path, include_secrets: bool) -> Result<usize, ExportError> {
+        let export = SessionExport::new(&self.data, include_secrets);
+        std::fs::write(path, serde_json::to_string_pretty(&export)?)?;
+        tracing::info!("Exported {} sessions to {:?}", export.sessions.len(), path);
+        Ok(export.sessions.len())
+    }
+
+    /// Add the groups and sessions from an export file
+    ///
+    /// Everything imported gets a new ID so it can't collide with existing
+    /// sessions, even when a file is imported twice. Groups whose parents loop
+    /// back to them are moved to the top level. The manager is left dirty;
+    /// the caller saves it.
+    pub fn import(&mut self, path: &Path, strategy: MergeStrategy) -> Result<ImportResult, ExportError> {
+        let export = SessionExport::from_json(&std::fs::read_to_string(path)?)?.with_new_ids();
+
+        if strategy == MergeStrategy::Replace {
+            for session in std::mem::take(&mut self.data.sessions) {
+                if let Session::Ssh(ref ssh_session) = session {
+                    ssh_session.delete_credentials_from_keychain();
+                }
+            }
+            self.data.groups.clear();
+        }
+
+        let mut result = ImportResult::default();
+        let imported: Vec<Uuid> = export.groups.iter().map(|g| g.id).collect();
+        self.data.groups.extend(export.groups);
+        // A hand-edited file can make groups each other's parent; move the
+        // groups that close a loop to the top level
+        for id in imported {
+            let parent_id = self.data.find_group(id).and_then(|g| g.parent_id);
+            if let Some(pid) = parent_id.filter(|&pid| pid == id || self.is_descendant(pid, id)) {
+                tracing::warn!("Imported group {} is its own ancestor via {}; moving it to the top level", id, pid);
+                if let Some(group) = self.data.find_group_mut(id) {
+                    group.parent_id = None;
+                }
+            }
+        }
+        for session in export.sessions {
+            result.created.push(session.id());
+            self.data.sessions.push(session);
+        }
+        self.dirty = true;
+
+        tracing::info!("Session import from {:?}: {}", path, result.summary());
+        Ok(result)
+    }
+
     /// Reload data from storage, discarding unsaved changes
     pub fn reload(&mut self) -> Result<(), ManagerError> {
         self.data = self.storage.load()?;
@@ -470,17 +708,115 @@ mod tests {
         assert!(manager.get_session(id).is_none());
     }
 
+    #[test]
+    fn test_update_k8s_session() {
+        let mut manager = create_test_manager();
+        let id = manager.add_k8s_session(K8sSession::new("api", "prod", "defualt", "api-0"));
+
+        let mut fixed = K8sSession::new("api", "prod", "default", "api-0");
+        fixed.id = id;
+        manager.update_k8s_session(id, fixed).unwrap();
+        match manager.get_session(id) {
+            Some(Session::K8s(k8s)) => assert_eq!(k8s.namespace, "default"),
+            other => panic!("expected a K8s session, got {:?}", other),
+        }
+
+        assert!(manager.update_k8s_session(Uuid::new_v4(), K8sSession::new("x", "", "ns", "pod")).is_err());
+    }
+
+    #[test]
+    fn test_mark_connected_survives_edits() {
+        let mut manager = create_test_manager();
+        let id = manager.add_ssh_session(SshSession::new("web", "10.0.0.1", "deploy"));
+        assert!(manager.get_session(id).unwrap().last_connected().is_none());
+
+        manager.mark_connected(id).unwrap();
+        let connected = manager.get_session(id).unwrap().last_connected();
+        assert!(connected.is_some());
+
+        // Editing keeps the timestamp; a duplicate starts without one
+        let mut edited = SshSession::new("web", "10.0.0.2", "deploy");
+        edited.id = id;
+        manager.update_ssh_session(id, edited).unwrap();
+        assert_eq!(manager.get_session(id).unwrap().last_connected(), connected);
+
+        let copy = manager.duplicate_session(id).unwrap();
+        assert!(manager.get_session(copy).unwrap().last_connected().is_none());
+
+        assert!(manager.mark_connected(Uuid::new_v4()).is_err());
+    }
+
+    #[test]
+    fn test_export_import() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("export.json");
+
+        let mut manager = create_test_manager();
+        let group_id = manager.add_group(SessionGroup::new("Pods".to_string())).unwrap();
+        let mut session = K8sSession::new("api", "prod", "default", "api-0");
+        session.group_id = Some(group_id);
+        let id = manager.add_k8s_session(session);
+        assert_eq!(manager.export(&path, false).unwrap(), 1);
+
+        // Importing into the same manager twice duplicates rather than collides
+        manager.import(&path, MergeStrategy::Append).unwrap();
+        let result = manager.import(&path, MergeStrategy::Append).unwrap();
+        assert_eq!(result.created.len(), 1);
+        assert_eq!(manager.all_sessions().len(), 3);
+        assert_eq!(manager.data().groups.len(), 3);
+        let imported = manager.get_session(result.created[0]).unwrap();
+        assert_ne!(imported.id(), id);
+        assert_ne!(imported.group_id(), Some(group_id));
+        assert!(imported.group_id().is_some());
+
+        manager.import(&path, MergeStrategy::Replace).unwrap();
+        assert_eq!(manager.all_sessions().len(), 1);
+        assert_eq!(manager.data().groups.len(), 1);
+        assert!(manager.get_session(id).is_none());
+    }
+
+    #[test]
+    fn test_import_breaks_group_cycles() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("export.json");
+
+        // A and B are each other's parent; C hangs off B
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let group = |id: Uuid, name: &str, parent: Uuid| {
+            format!(r#"{{"id":"{}","name":"{}","parent_id":"{}","color":null}}"#, id, name, parent)
+        };
+        std::fs::write(
+            &path,
+            format!(
+                r#"{{"version":1,"groups":[{},{},{}],"sessions":[]}}"#,
+                group(a, "A", b),
+                group(b, "B", a),
+                group(c, "C", b)
+            ),
+        )
+        .unwrap();
+
+        let mut manager = create_test_manager();
+        manager.import(&path, MergeStrategy::Append).unwrap();
+        assert_eq!(manager.data().groups.len(), 3);
+        assert_eq!(manager.top_level_groups().len(), 1);
+        let top = manager.top_level_groups()[0].id;
+        for group in manager.data().groups.clone() {
+            assert!(group.id == top || manager.is_descendant(group.id, top));
+        }
+    }
+
     #[test]
     fn test_group_operations() {
         let mut manager = create_test_manager();
 
         // Create parent group
         let parent = SessionGroup::new("Parent".to_string());
-        let parent_id = manager.add_group(parent);
+        let parent_id = manager.add_group(parent).unwrap();
 
         // Create child group
         let child = SessionGroup::new_nested("Child".to_string(), parent_id);
-        let child_id = manager.add_group(child);
+        let child_id = manager.add_group(child).unwrap();
 
         // Verify hierarchy
         assert_eq!(manager.child_groups(parent_id).len(), 1);
@@ -496,13 +832,43 @@ mod tests {
         manager.delete_group(parent_id).unwrap();
     }
 
+    #[test]
+    fn test_group_defaults_update_inheriting_sessions() {
+        let mut manager = create_test_manager();
+
+        let mut parent = SessionGroup::new("Prod".to_string());
+        parent.defaults.username = Some("admin".to_string());
+        let parent_id = manager.add_group(parent).unwrap();
+        let child_id = manager.add_group(SessionGroup::new_nested("Web".to_string(), parent_id)).unwrap();
+        assert_eq!(manager.group_defaults(child_id).username.as_deref(), Some("admin"));
+
+        let mut inheriting = SshSession::new("web1", "web1", "admin");
+        inheriting.group_id = Some(child_id);
+        inheriting.inherited = inheriting.matching_defaults(&manager.group_defaults(child_id));
+        let inheriting_id = manager.add_ssh_session(inheriting);
+        let mut overridden = SshSession::new("web2", "web2", "root");
+        overridden.group_id = Some(child_id);
+        let overridden_id = manager.add_ssh_session(overridden);
+
+        let mut parent = manager.get_group(parent_id).unwrap().clone();
+        parent.defaults.username = Some("ops".to_string());
+        manager.update_group(parent_id, parent).unwrap();
+
+        let username = |manager: &SessionManager, id| match manager.get_session(id) {
+            Some(Session::Ssh(ssh)) => ssh.username.clone(),
+            _ => panic!("not an SSH session"),
+        };
+        assert_eq!(username(&manager, inheriting_id), "ops");
+        assert_eq!(username(&manager, overridden_id), "root");
+    }
+
     #[test]
     fn test_mass_connect() {
         let mut manager = create_test_manager();
 
         // Create group
         let group = SessionGroup::new("Servers".to_string());
-        let group_id = manager.add_group(group);
+        let group_id = manager.add_group(group).unwrap();
 
         // Add sessions to group
         for i in 0..3 {
@@ -519,4 +885,89 @@ mod tests {
         let session_ids = manager.get_all_sessions_in_group_recursive(group_id);
         assert_eq!(session_ids.len(), 3);
     }
+
+    #[test]
+    fn test_duplicate_session() {
+        let mut manager = create_test_manager();
+
+        let group_id = manager.add_group(SessionGroup::new("Pods".to_string())).unwrap();
+        let mut session = K8sSession::new("api", "prod", "default", "api-0");
+        session.group_id = Some(group_id);
+        let id = manager.add_k8s_session(session);
+        manager.add_local_session(LocalSession::new("Local"));
+
+        let copy_id = manager.duplicate_session(id).unwrap();
+        assert_ne!(copy_id, id);
+        assert_eq!(manager.all_sessions()[1].id(), copy_id);
+        assert_eq!(manager.sessions_in_group(group_id).len(), 2);
+
+        assert!(matches!(
+            manager.duplicate_session(Uuid::new_v4()),
+            Err(ManagerError::SessionNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_cyclic_groups_rejected() {
+        let mut manager = create_test_manager();
+
+        let root_id = manager.add_group(SessionGroup::new("Root".to_string())).unwrap();
+        let child_id = manager.add_group(SessionGroup::new_nested("Child".to_string(), root_id)).unwrap();
+        let grandchild_id = manager
+            .add_group(SessionGroup::new_nested("Grandchild".to_string(), child_id))
+            .unwrap();
+
+        assert!(matches!(manager.move_group(root_id, Some(grandchild_id)), Err(ManagerError::CyclicGroup)));
+        assert!(matches!(manager.move_group(root_id, Some(root_id)), Err(ManagerError::CyclicGroup)));
+
+        let mut edited = manager.get_group(child_id).unwrap().clone();
+        edited.parent_id = Some(grandchild_id);
+        assert!(matches!(manager.update_group(child_id, edited), Err(ManagerError::CyclicGroup)));
+
+        let mut self_parent = SessionGroup::new("Loop".to_string());
+        self_parent.parent_id = Some(self_parent.id);
+        assert!(manager.add_group(self_parent).is_err());
+
+        // Moving within the tree is still allowed
+        manager.move_group(grandchild_id, Some(root_id)).unwrap();
+    }
+
+    #[test]
+    fn test_recursive_sessions_with_corrupt_cycle() {
+        let mut manager = create_test_manager();
+
+        let a = manager.add_group(SessionGroup::new("A".to_string())).unwrap();
+        let b = manager.add_group(SessionGroup::new_nested("B".to_string(), a)).unwrap();
+        // Simulate a corrupt sessions file where A and B are each other's parent
+        manager.get_group_mut(a).unwrap().parent_id = Some(b);
+
+        let mut session = SshSession::new("web", "10.0.0.1", "admin");
+        session.group_id = Some(b);
+        manager.add_ssh_session(session);
+
+        assert_eq!(manager.get_all_sessions_in_group_recursive(a).len(), 1);
+        assert!(matches!(manager.move_group(b, Some(a)), Err(ManagerError::CyclicGroup)));
+    }
+
+    #[test]
+    fn test_import_from_ssh_config() {
+        let mut manager = create_test_manager();
+        manager.add_ssh_session(SshSession::new("existing", "db.example.com", "admin"));
+
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config");
+        std::fs::write(
+            &config,
+            "Host web\n  HostName web.example.com\n  User deploy\n\nHost db\n  HostName db.example.com\n  User admin\n\nHost *\n  User nobody\n",
+        )
+        .unwrap();
+
+        let result = manager.import_from_ssh_config(&config).unwrap();
+        assert_eq!(result.created.len(), 1);
+        assert_eq!(result.skipped, vec!["db".to_string()]);
+        let group = manager.all_groups().iter().find(|g| g.name == "Imported from SSH config").unwrap();
+        assert_eq!(manager.sessions_in_group(group.id).len(), 1);
+
+        assert!(manager.import_from_ssh_config(&dir.path().join("missing")).is_err());
+    }
 }
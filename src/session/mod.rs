@@ -1,9 +1,16 @@
 pub mod credentials;
+pub mod export;
+pub mod host_suggestions;
+pub mod import;
+pub mod link;
 pub mod manager;
 pub mod models;
 pub mod storage;
 
 pub use credentials::{CredentialManager, CredentialType};
+pub use export::{ExportError, MergeStrategy, SessionExport};
+pub use host_suggestions::{HostSuggestion, RankedSuggestion};
+pub use link::{LinkError, SessionLink};
 pub use manager::SessionManager;
 pub use models::*;
 pub use storage::SessionStorage;
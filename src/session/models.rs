@@ -1,10 +1,11 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
 use uuid::Uuid;
 
 /// Authentication method for SSH connections
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum AuthMethod {
     /// Password authentication
@@ -26,9 +27,19 @@ pub enum AuthMethod {
         /// Whether to save the passphrase to the OS keychain (not plaintext)
         #[serde(default)]
         use_keychain: bool,
+        /// Further keys to try, in order, if the server rejects `path`
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        extra_keys: Vec<PathBuf>,
+        /// Only offer the configured keys; don't fall back to the SSH agent
+        /// (like OpenSSH's `IdentitiesOnly yes`)
+        #[serde(default)]
+        identities_only: bool,
     },
     /// SSH agent authentication
     Agent,
+    /// Keyboard-interactive authentication: the server's questions, such as
+    /// a password and a one-time code, are answered as they come
+    KeyboardInteractive,
 }
 
 impl Default for AuthMethod {
@@ -37,6 +48,50 @@ impl Default for AuthMethod {
     }
 }
 
+impl AuthMethod {
+    /// Key files to offer, in order (empty for password and agent auth)
+    pub fn key_paths(&self) -> Vec<&PathBuf> {
+        match self {
+            AuthMethod::PrivateKey { path, extra_keys, .. } => std::iter::once(path).chain(extra_keys).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether a password or key passphrase is saved (in the session or the keychain)
+    pub fn has_saved_secret(&self) -> bool {
+        match self {
+            AuthMethod::Password { password, use_keychain } => password.is_some() || *use_keychain,
+            AuthMethod::PrivateKey { passphrase, use_keychain, .. } => passphrase.is_some() || *use_keychain,
+            AuthMethod::Agent | AuthMethod::KeyboardInteractive => false,
+        }
+    }
+
+    /// The same method with any saved password or passphrase left out
+    pub fn without_secrets(&self) -> Self {
+        match self {
+            AuthMethod::Password { use_keychain, .. } => AuthMethod::Password {
+                password: None,
+                use_keychain: *use_keychain,
+            },
+            AuthMethod::PrivateKey {
+                path,
+                use_keychain,
+                extra_keys,
+                identities_only,
+                ..
+            } => AuthMethod::PrivateKey {
+                path: path.clone(),
+                passphrase: None,
+                use_keychain: *use_keychain,
+                extra_keys: extra_keys.clone(),
+                identities_only: *identities_only,
+            },
+            AuthMethod::Agent => AuthMethod::Agent,
+            AuthMethod::KeyboardInteractive => AuthMethod::KeyboardInteractive,
+        }
+    }
+}
+
 /// An SSH session configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SshSession {
@@ -60,6 +115,310 @@ pub struct SshSession {
     /// Optional color scheme override for this session
     #[serde(skip_serializing_if = "Option::is_none")]
     pub color_scheme: Option<String>,
+    /// Transcript recording override (None = use the global setting)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub record_session: Option<bool>,
+    /// What the Enter key sends
+    #[serde(default, skip_serializing_if = "NewlineMode::is_auto")]
+    pub newline_mode: NewlineMode,
+    /// Input sent after a stretch without typing, for servers that drop idle shells
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_alive: Option<KeepAliveCommand>,
+    /// Offer to type the stored password at `sudo` prompts
+    #[serde(default)]
+    pub sudo_autofill: bool,
+    /// Let the server use the local SSH agent, like `ssh -A`
+    #[serde(default)]
+    pub agent_forward: bool,
+    /// Local command that must succeed before connecting (see `crate::hooks`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_connect_command: Option<String>,
+    /// Local command run when the connection ends (see `crate::hooks`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_disconnect_command: Option<String>,
+    /// Settings that follow the group's defaults when those change
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inherited: Vec<InheritedSetting>,
+    /// Bastion to tunnel the connection through, like OpenSSH's `ProxyJump`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jump_host: Option<JumpHostConfig>,
+    /// Ports tunnelled over the connection, like OpenSSH's `-L` and `-R`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub port_forwards: Vec<PortForward>,
+    /// When a tab was last opened for this session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_connected: Option<SystemTime>,
+}
+
+/// A host the SSH connection is tunnelled through to reach the session's host
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JumpHostConfig {
+    pub host: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// Username on the jump host (empty = the session's username)
+    #[serde(default)]
+    pub username: String,
+    /// How to log in to the jump host; passwords are asked for when connecting
+    #[serde(default)]
+    pub auth: AuthMethod,
+}
+
+impl JumpHostConfig {
+    /// Parse `[user@]host[:port]`, as written for OpenSSH's `ProxyJump`
+    ///
+    /// IPv6 addresses with a port go in brackets: `[2001:db8::1]:2222`.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let spec = spec.trim();
+        let (username, rest) = spec.rsplit_once('@').unwrap_or(("", spec));
+        let (host, port) = if let Some(bracketed) = rest.strip_prefix('[') {
+            let (host, after) = bracketed.split_once(']')?;
+            match after.strip_prefix(':') {
+                Some(port) => (host, port.parse().ok()?),
+                None if after.is_empty() => (host, default_port()),
+                None => return None,
+            }
+        } else {
+            match rest.rsplit_once(':') {
+                // A bare IPv6 address has colons of its own
+                Some((host, port)) if !host.contains(':') => (host, port.parse().ok()?),
+                _ => (rest, default_port()),
+            }
+        };
+        if host.is_empty() || host.contains(char::is_whitespace) {
+            return None;
+        }
+        Some(Self {
+            host: host.to_string(),
+            port,
+            username: username.to_string(),
+            auth: AuthMethod::Agent,
+        })
+    }
+
+    /// The `[user@]host[:port]` form, leaving out the default port
+    pub fn spec(&self) -> String {
+        let host = if self.host.contains(':') {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        };
+        let user = if self.username.is_empty() {
+            String::new()
+        } else {
+            format!("{}@", self.username)
+        };
+        if self.port == default_port() {
+            format!("{}{}", user, host)
+        } else {
+            format!("{}{}:{}", user, host, self.port)
+        }
+    }
+}
+
+/// Which end of the connection listens for a port forward
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForwardDirection {
+    /// Listen on this machine and connect out from the server (`-L`)
+    Local,
+    /// Listen on the server and connect out from this machine (`-R`)
+    Remote,
+}
+
+/// A TCP port tunnelled over an SSH connection
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortForward {
+    pub direction: ForwardDirection,
+    /// Address to listen on, on the listening end
+    #[serde(default = "default_bind_address")]
+    pub bind_address: String,
+    pub bind_port: u16,
+    /// Where connections are sent, as resolved by the other end
+    pub target_host: String,
+    pub target_port: u16,
+}
+
+fn default_bind_address() -> String {
+    "127.0.0.1".to_string()
+}
+
+impl PortForward {
+    /// Parse `[bind_address:]port:host:hostport`, as written for `ssh -L`/`-R`
+    ///
+    /// IPv6 addresses go in brackets: `[::1]:8080:[2001:db8::5]:80`.
+    pub fn parse(direction: ForwardDirection, spec: &str) -> Option<Self> {
+        let mut fields = Vec::new();
+        let mut rest = spec.trim();
+        loop {
+            let (field, after) = match rest.strip_prefix('[') {
+                Some(bracketed) => bracketed.split_once(']')?,
+                None => rest.split_at(rest.find(':').unwrap_or(rest.len())),
+            };
+            fields.push(field);
+            match after.strip_prefix(':') {
+                Some(next) => rest = next,
+                None if after.is_empty() => break,
+                None => return None,
+            }
+        }
+        let (bind_address, fields) = match fields.as_slice() {
+            [port, host, hostport] => (default_bind_address(), [*port, *host, *hostport]),
+            [address, port, host, hostport] if !address.is_empty() => (address.to_string(), [*port, *host, *hostport]),
+            _ => return None,
+        };
+        let [bind_port, target_host, target_port] = fields;
+        let port = |field: &str| field.parse::<u16>().ok().filter(|&port| port != 0);
+        if target_host.is_empty() || spec.contains(char::is_whitespace) {
+            return None;
+        }
+        Some(Self {
+            direction,
+            bind_address,
+            bind_port: port(bind_port)?,
+            target_host: target_host.to_string(),
+            target_port: port(target_port)?,
+        })
+    }
+
+    /// The `[bind_address:]port:host:hostport` form, leaving out the default address
+    pub fn spec(&self) -> String {
+        let bracket = |host: &str| {
+            if host.contains(':') {
+                format!("[{}]", host)
+            } else {
+                host.to_string()
+            }
+        };
+        let forward = format!("{}:{}:{}", self.bind_port, bracket(&self.target_host), self.target_port);
+        if self.bind_address == default_bind_address() {
+            forward
+        } else {
+            format!("{}:{}", bracket(&self.bind_address), forward)
+        }
+    }
+
+    /// The spec with its `ssh` flag, e.g. `-L 8080:db:5432`
+    pub fn label(&self) -> String {
+        let flag = match self.direction {
+            ForwardDirection::Local => "-L",
+            ForwardDirection::Remote => "-R",
+        };
+        format!("{} {}", flag, self.spec())
+    }
+}
+
+/// A session setting that can be taken from its group's defaults
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InheritedSetting {
+    Username,
+    Port,
+    Auth,
+    ColorScheme,
+}
+
+/// Line ending sent when Enter is pressed
+///
+/// Some network equipment wants a bare CR or LF and double-spaces (or never
+/// sees the line) otherwise.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NewlineMode {
+    /// CR, or CR LF while the host has line feed/new line mode (LNM) set
+    #[default]
+    Auto,
+    Cr,
+    Lf,
+    CrLf,
+}
+
+impl NewlineMode {
+    /// All modes, in display order
+    pub const ALL: [NewlineMode; 4] = [NewlineMode::Auto, NewlineMode::Cr, NewlineMode::Lf, NewlineMode::CrLf];
+
+    /// Display name
+    pub fn label(self) -> &'static str {
+        match self {
+            NewlineMode::Auto => "Auto",
+            NewlineMode::Cr => "CR",
+            NewlineMode::Lf => "LF",
+            NewlineMode::CrLf => "CR+LF",
+        }
+    }
+
+    pub fn is_auto(&self) -> bool {
+        *self == NewlineMode::Auto
+    }
+}
+
+fn default_keep_alive_interval() -> u64 {
+    240
+}
+
+/// Text typed into an idle shell to keep the server from timing it out
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeepAliveCommand {
+    /// Text to send; `\r`, `\n`, `\t`, `\b`, `\\` and `\xNN` escapes are expanded
+    pub command: String,
+    /// Seconds without user input before the command is sent
+    #[serde(default = "default_keep_alive_interval")]
+    pub interval_secs: u64,
+}
+
+impl KeepAliveCommand {
+    /// Shortest interval accepted, so a typo can't flood the server
+    pub const MIN_INTERVAL_SECS: u64 = 5;
+
+    pub fn new(command: impl Into<String>, interval_secs: u64) -> Self {
+        Self {
+            command: command.into(),
+            interval_secs,
+        }
+    }
+
+    /// How long the session must be idle before sending
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs.max(Self::MIN_INTERVAL_SECS))
+    }
+
+    /// The bytes to send, with escapes expanded
+    ///
+    /// Unknown escapes are sent as written.
+    pub fn bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.command.len());
+        let mut chars = self.command.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                let mut buf = [0u8; 4];
+                out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                continue;
+            }
+            let (byte, len) = match chars.peek() {
+                Some('r') => (Some(b'\r'), 1),
+                Some('n') => (Some(b'\n'), 1),
+                Some('t') => (Some(b'\t'), 1),
+                Some('b') => (Some(0x08), 1),
+                Some('\\') => (Some(b'\\'), 1),
+                Some('x') => {
+                    let hex: String = chars.clone().skip(1).take(2).collect();
+                    let valid = hex.len() == 2 && hex.chars().all(|c| c.is_ascii_hexdigit());
+                    (valid.then(|| u8::from_str_radix(&hex, 16).unwrap_or_default()), 3)
+                }
+                _ => (None, 0),
+            };
+            match byte {
+                Some(byte) => {
+                    out.push(byte);
+                    for _ in 0..len {
+                        chars.next();
+                    }
+                }
+                None => out.push(b'\\'),
+            }
+        }
+        out
+    }
 }
 
 fn default_port() -> u16 {
@@ -79,9 +438,83 @@ impl SshSession {
             group_id: None,
             color_tag: None,
             color_scheme: None,
+            last_connected: None,
+            record_session: None,
+            newline_mode: NewlineMode::Auto,
+            keep_alive: None,
+            sudo_autofill: false,
+            agent_forward: false,
+            pre_connect_command: None,
+            post_disconnect_command: None,
+            inherited: Vec::new(),
+            jump_host: None,
+            port_forwards: Vec::new(),
         }
     }
 
+    /// The settings that currently match `defaults`, i.e. weren't overridden
+    pub fn matching_defaults(&self, defaults: &GroupDefaults) -> Vec<InheritedSetting> {
+        let mut inherited = Vec::new();
+        if defaults.username.as_ref() == Some(&self.username) {
+            inherited.push(InheritedSetting::Username);
+        }
+        if defaults.port == Some(self.port) {
+            inherited.push(InheritedSetting::Port);
+        }
+        if defaults.auth.as_ref() == Some(&self.auth.without_secrets()) {
+            inherited.push(InheritedSetting::Auth);
+        }
+        if defaults.color_scheme.is_some() && defaults.color_scheme == self.color_scheme {
+            inherited.push(InheritedSetting::ColorScheme);
+        }
+        inherited
+    }
+
+    /// Update the inherited settings from the group's defaults
+    ///
+    /// Settings the group no longer sets keep their last value. Saved
+    /// secrets are only dropped if the auth method itself changes, and then
+    /// from the keychain too. Returns whether anything changed.
+    pub fn apply_group_defaults(&mut self, defaults: &GroupDefaults) -> bool {
+        let mut changed = false;
+        for setting in self.inherited.clone() {
+            match setting {
+                InheritedSetting::Username => {
+                    if let Some(username) = defaults.username.as_ref().filter(|u| **u != self.username) {
+                        self.username = username.clone();
+                        changed = true;
+                    }
+                }
+                InheritedSetting::Port => {
+                    if let Some(port) = defaults.port.filter(|p| *p != self.port) {
+                        self.port = port;
+                        changed = true;
+                    }
+                }
+                InheritedSetting::Auth => {
+                    if let Some(auth) = defaults.auth.as_ref().filter(|a| **a != self.auth.without_secrets()) {
+                        // The keychain entry belongs to the old method
+                        if matches!(
+                            self.auth,
+                            AuthMethod::Password { use_keychain: true, .. } | AuthMethod::PrivateKey { use_keychain: true, .. }
+                        ) {
+                            self.delete_credentials_from_keychain();
+                        }
+                        self.auth = auth.clone();
+                        changed = true;
+                    }
+                }
+                InheritedSetting::ColorScheme => {
+                    if defaults.color_scheme.is_some() && defaults.color_scheme != self.color_scheme {
+                        self.color_scheme = defaults.color_scheme.clone();
+                        changed = true;
+                    }
+                }
+            }
+        }
+        changed
+    }
+
     /// Get the connection address string
     pub fn address(&self) -> String {
         format!("{}:{}", self.host, self.port)
@@ -124,7 +557,7 @@ impl SshSession {
                     }
                 }
             }
-            AuthMethod::Agent => {}
+            AuthMethod::Agent | AuthMethod::KeyboardInteractive => {}
         }
     }
 
@@ -166,7 +599,7 @@ impl SshSession {
                     }
                 }
             }
-            AuthMethod::Agent => {}
+            AuthMethod::Agent | AuthMethod::KeyboardInteractive => {}
         }
     }
 
@@ -193,6 +626,12 @@ pub struct LocalSession {
     pub env: HashMap<String, String>,
     /// Optional group membership
     pub group_id: Option<Uuid>,
+    /// Optional color scheme override for this session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color_scheme: Option<String>,
+    /// When a tab was last opened for this session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_connected: Option<SystemTime>,
 }
 
 impl Default for LocalSession {
@@ -204,6 +643,8 @@ impl Default for LocalSession {
             working_dir: None,
             env: HashMap::new(),
             group_id: None,
+            color_scheme: None,
+            last_connected: None,
         }
     }
 }
@@ -239,6 +680,25 @@ pub struct SsmSession {
     /// Optional color scheme override for this session
     #[serde(skip_serializing_if = "Option::is_none")]
     pub color_scheme: Option<String>,
+    /// Transcript recording override (None = use the global setting)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub record_session: Option<bool>,
+    /// Forward a port on the instance instead of opening a shell
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port_forward: Option<SsmPortForward>,
+    /// When a tab was last opened for this session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_connected: Option<SystemTime>,
+}
+
+/// A port on an SSM instance tunnelled to this machine, using the
+/// `AWS-StartPortForwardingSession` document
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SsmPortForward {
+    /// Port listened on at 127.0.0.1
+    pub local_port: u16,
+    /// Port on the instance connections are forwarded to
+    pub remote_port: u16,
 }
 
 impl SsmSession {
@@ -252,6 +712,9 @@ impl SsmSession {
             profile: None,
             group_id: None,
             color_scheme: None,
+            last_connected: None,
+            record_session: None,
+            port_forward: None,
         }
     }
 
@@ -270,10 +733,27 @@ impl SsmSession {
             profile,
             group_id: None,
             color_scheme: None,
+            last_connected: None,
+            record_session: None,
+            port_forward: None,
         }
     }
 }
 
+impl SshSession {
+    /// Create an SSH session for the same host as an SSM session
+    ///
+    /// SSM sessions only know the instance ID, so the caller supplies the
+    /// host (e.g. the instance's IP). Group and color scheme carry over.
+    pub fn from_ssm(session: &SsmSession, host: impl Into<String>, username: impl Into<String>) -> Self {
+        let mut ssh = Self::new(session.name.clone(), host, username);
+        ssh.group_id = session.group_id;
+        ssh.color_scheme = session.color_scheme.clone();
+        ssh.record_session = session.record_session;
+        ssh
+    }
+}
+
 /// A session group for organizing sessions
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionGroup {
@@ -285,6 +765,42 @@ pub struct SessionGroup {
     pub parent_id: Option<Uuid>,
     /// Optional color for visual identification
     pub color: Option<String>,
+    /// Settings new SSH sessions in the group start with
+    #[serde(default, skip_serializing_if = "GroupDefaults::is_empty")]
+    pub defaults: GroupDefaults,
+}
+
+/// Settings a group hands down to the SSH sessions created in it
+///
+/// Unset fields are taken from the parent group, if any.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GroupDefaults {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    /// Auth method, never with a saved password or passphrase
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<AuthMethod>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color_scheme: Option<String>,
+}
+
+impl GroupDefaults {
+    /// Whether no default is set
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// These defaults, with unset fields filled in from `parent`
+    pub fn or(self, parent: &GroupDefaults) -> Self {
+        Self {
+            username: self.username.or_else(|| parent.username.clone()),
+            port: self.port.or(parent.port),
+            auth: self.auth.or_else(|| parent.auth.clone()),
+            color_scheme: self.color_scheme.or_else(|| parent.color_scheme.clone()),
+        }
+    }
 }
 
 impl SessionGroup {
@@ -295,6 +811,7 @@ impl SessionGroup {
             name: name.into(),
             parent_id: None,
             color: None,
+            defaults: GroupDefaults::default(),
         }
     }
 
@@ -305,6 +822,7 @@ impl SessionGroup {
             name: name.into(),
             parent_id: Some(parent_id),
             color: None,
+            defaults: GroupDefaults::default(),
         }
     }
 }
@@ -316,7 +834,7 @@ pub struct K8sSession {
     pub id: Uuid,
     /// Display name for the session
     pub name: String,
-    /// Kubernetes context name (from kubeconfig)
+    /// Kubernetes context name (from kubeconfig; empty = current context)
     pub context: String,
     /// Namespace
     pub namespace: String,
@@ -325,11 +843,27 @@ pub struct K8sSession {
     /// Container name (optional, uses first container if None)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub container: Option<String>,
+    /// Command to run instead of a login shell, like `kubectl exec -- <cmd>`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<Vec<String>>,
+    /// Allocate a TTY for the exec, like `kubectl exec -t`
+    #[serde(default = "default_k8s_tty")]
+    pub tty: bool,
     /// Optional group membership
     pub group_id: Option<Uuid>,
     /// Optional color scheme override for this session
     #[serde(skip_serializing_if = "Option::is_none")]
     pub color_scheme: Option<String>,
+    /// Transcript recording override (None = use the global setting)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub record_session: Option<bool>,
+    /// When a tab was last opened for this session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_connected: Option<SystemTime>,
+}
+
+fn default_k8s_tty() -> bool {
+    true
 }
 
 impl K8sSession {
@@ -347,8 +881,12 @@ impl K8sSession {
             namespace: namespace.into(),
             pod: pod.into(),
             container: None,
+            command: None,
+            tty: true,
             group_id: None,
             color_scheme: None,
+            last_connected: None,
+            record_session: None,
         }
     }
 
@@ -367,10 +905,198 @@ impl K8sSession {
             namespace: namespace.into(),
             pod: pod.into(),
             container: Some(container.into()),
+            command: None,
+            tty: true,
+            group_id: None,
+            color_scheme: None,
+            last_connected: None,
+            record_session: None,
+        }
+    }
+}
+
+/// Parity checking on a serial line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SerialParity {
+    #[default]
+    None,
+    Odd,
+    Even,
+}
+
+impl SerialParity {
+    /// All settings, in display order
+    pub const ALL: [SerialParity; 3] = [SerialParity::None, SerialParity::Odd, SerialParity::Even];
+
+    /// Display name
+    pub fn label(self) -> &'static str {
+        match self {
+            SerialParity::None => "None",
+            SerialParity::Odd => "Odd",
+            SerialParity::Even => "Even",
+        }
+    }
+}
+
+/// Flow control on a serial line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SerialFlowControl {
+    #[default]
+    None,
+    /// XON/XOFF
+    Software,
+    /// RTS/CTS
+    Hardware,
+}
+
+impl SerialFlowControl {
+    /// All settings, in display order
+    pub const ALL: [SerialFlowControl; 3] = [
+        SerialFlowControl::None,
+        SerialFlowControl::Software,
+        SerialFlowControl::Hardware,
+    ];
+
+    /// Display name
+    pub fn label(self) -> &'static str {
+        match self {
+            SerialFlowControl::None => "None",
+            SerialFlowControl::Software => "XON/XOFF",
+            SerialFlowControl::Hardware => "RTS/CTS",
+        }
+    }
+}
+
+fn default_baud_rate() -> u32 {
+    115_200
+}
+
+fn default_data_bits() -> u8 {
+    8
+}
+
+fn default_stop_bits() -> u8 {
+    1
+}
+
+/// A serial console session configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerialSession {
+    /// Unique identifier
+    pub id: Uuid,
+    /// Display name for the session
+    pub name: String,
+    /// Device path (e.g. /dev/ttyUSB0 or COM3)
+    pub device: String,
+    /// Line speed in bits per second
+    #[serde(default = "default_baud_rate")]
+    pub baud_rate: u32,
+    /// Data bits per character (5-8)
+    #[serde(default = "default_data_bits")]
+    pub data_bits: u8,
+    /// Stop bits (1 or 2)
+    #[serde(default = "default_stop_bits")]
+    pub stop_bits: u8,
+    #[serde(default)]
+    pub parity: SerialParity,
+    #[serde(default)]
+    pub flow_control: SerialFlowControl,
+    /// Optional group membership
+    pub group_id: Option<Uuid>,
+    /// Optional color scheme override for this session
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color_scheme: Option<String>,
+    /// Transcript recording override (None = use the global setting)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub record_session: Option<bool>,
+    /// When a tab was last opened for this session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_connected: Option<SystemTime>,
+}
+
+impl SerialSession {
+    /// Common line speeds, offered as presets
+    pub const BAUD_RATES: [u32; 8] = [1200, 2400, 4800, 9600, 19_200, 38_400, 57_600, 115_200];
+
+    /// Create a new serial session with 115200 8N1 and no flow control
+    pub fn new(name: impl Into<String>, device: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name: name.into(),
+            device: device.into(),
+            baud_rate: default_baud_rate(),
+            data_bits: default_data_bits(),
+            stop_bits: default_stop_bits(),
+            parity: SerialParity::None,
+            flow_control: SerialFlowControl::None,
             group_id: None,
             color_scheme: None,
+            last_connected: None,
+            record_session: None,
         }
     }
+
+    /// Line settings in the usual shorthand, e.g. "115200 8N1"
+    pub fn line_settings(&self) -> String {
+        let parity = match self.parity {
+            SerialParity::None => 'N',
+            SerialParity::Odd => 'O',
+            SerialParity::Even => 'E',
+        };
+        format!("{} {}{}{}", self.baud_rate, self.data_bits, parity, self.stop_bits)
+    }
+}
+
+fn default_telnet_port() -> u16 {
+    23
+}
+
+/// A telnet session configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelnetSession {
+    /// Unique identifier
+    pub id: Uuid,
+    /// Display name for the session
+    pub name: String,
+    /// Hostname or IP address
+    pub host: String,
+    /// Port number (default: 23)
+    #[serde(default = "default_telnet_port")]
+    pub port: u16,
+    /// Optional group membership
+    pub group_id: Option<Uuid>,
+    /// Optional color scheme override for this session
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color_scheme: Option<String>,
+    /// Transcript recording override (None = use the global setting)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub record_session: Option<bool>,
+    /// When a tab was last opened for this session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_connected: Option<SystemTime>,
+}
+
+impl TelnetSession {
+    /// Create a new telnet session on the default port
+    pub fn new(name: impl Into<String>, host: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name: name.into(),
+            host: host.into(),
+            port: default_telnet_port(),
+            group_id: None,
+            color_scheme: None,
+            last_connected: None,
+            record_session: None,
+        }
+    }
+
+    /// Get the connection address (host:port)
+    pub fn address(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
 }
 
 /// Union type for different session types
@@ -381,6 +1107,8 @@ pub enum Session {
     Local(LocalSession),
     Ssm(SsmSession),
     K8s(K8sSession),
+    Serial(SerialSession),
+    Telnet(TelnetSession),
 }
 
 impl Session {
@@ -391,6 +1119,8 @@ impl Session {
             Session::Local(s) => s.id,
             Session::Ssm(s) => s.id,
             Session::K8s(s) => s.id,
+            Session::Serial(s) => s.id,
+            Session::Telnet(s) => s.id,
         }
     }
 
@@ -401,6 +1131,8 @@ impl Session {
             Session::Local(s) => &s.name,
             Session::Ssm(s) => &s.name,
             Session::K8s(s) => &s.name,
+            Session::Serial(s) => &s.name,
+            Session::Telnet(s) => &s.name,
         }
     }
 
@@ -411,6 +1143,20 @@ impl Session {
             Session::Local(s) => s.group_id,
             Session::Ssm(s) => s.group_id,
             Session::K8s(s) => s.group_id,
+            Session::Serial(s) => s.group_id,
+            Session::Telnet(s) => s.group_id,
+        }
+    }
+
+    /// Set the session's ID
+    pub fn set_id(&mut self, id: Uuid) {
+        match self {
+            Session::Ssh(s) => s.id = id,
+            Session::Local(s) => s.id = id,
+            Session::Ssm(s) => s.id = id,
+            Session::K8s(s) => s.id = id,
+            Session::Serial(s) => s.id = id,
+            Session::Telnet(s) => s.id = id,
         }
     }
 
@@ -421,8 +1167,73 @@ impl Session {
             Session::Local(s) => s.group_id = group_id,
             Session::Ssm(s) => s.group_id = group_id,
             Session::K8s(s) => s.group_id = group_id,
+            Session::Serial(s) => s.group_id = group_id,
+            Session::Telnet(s) => s.group_id = group_id,
         }
     }
+
+    /// When a tab was last opened for this session
+    pub fn last_connected(&self) -> Option<SystemTime> {
+        match self {
+            Session::Ssh(s) => s.last_connected,
+            Session::Local(s) => s.last_connected,
+            Session::Ssm(s) => s.last_connected,
+            Session::K8s(s) => s.last_connected,
+            Session::Serial(s) => s.last_connected,
+            Session::Telnet(s) => s.last_connected,
+        }
+    }
+
+    /// Set when a tab was last opened for this session
+    pub fn set_last_connected(&mut self, time: Option<SystemTime>) {
+        match self {
+            Session::Ssh(s) => s.last_connected = time,
+            Session::Local(s) => s.last_connected = time,
+            Session::Ssm(s) => s.last_connected = time,
+            Session::K8s(s) => s.last_connected = time,
+            Session::Serial(s) => s.last_connected = time,
+            Session::Telnet(s) => s.last_connected = time,
+        }
+    }
+
+    /// Clone this session under a new ID and a "(copy)" name
+    ///
+    /// Group, color scheme and all connection settings carry over; the copy
+    /// has never been connected.
+    #[must_use]
+    pub fn duplicate(&self) -> Session {
+        let mut copy = self.clone();
+        copy.set_last_connected(None);
+        let id = Uuid::new_v4();
+        let name = format!("{} (copy)", self.name());
+        match &mut copy {
+            Session::Ssh(s) => {
+                s.id = id;
+                s.name = name;
+            }
+            Session::Local(s) => {
+                s.id = id;
+                s.name = name;
+            }
+            Session::Ssm(s) => {
+                s.id = id;
+                s.name = name;
+            }
+            Session::K8s(s) => {
+                s.id = id;
+                s.name = name;
+            }
+            Session::Serial(s) => {
+                s.id = id;
+                s.name = name;
+            }
+            Session::Telnet(s) => {
+                s.id = id;
+                s.name = name;
+            }
+        }
+        copy
+    }
 }
 
 /// The complete session data structure for persistence
@@ -503,6 +1314,47 @@ mod tests {
         assert_eq!(session.address(), "192.168.1.1:22");
     }
 
+    #[test]
+    fn test_jump_host_spec() {
+        let jump = JumpHostConfig::parse("ops@bastion.example.com:2222").unwrap();
+        assert_eq!((jump.username.as_str(), jump.host.as_str(), jump.port), ("ops", "bastion.example.com", 2222));
+        assert_eq!(jump.spec(), "ops@bastion.example.com:2222");
+
+        let jump = JumpHostConfig::parse(" bastion ").unwrap();
+        assert_eq!((jump.username.as_str(), jump.port), ("", 22));
+        assert_eq!(jump.spec(), "bastion");
+
+        let jump = JumpHostConfig::parse("[2001:db8::1]:2200").unwrap();
+        assert_eq!((jump.host.as_str(), jump.port), ("2001:db8::1", 2200));
+        assert_eq!(jump.spec(), "[2001:db8::1]:2200");
+        assert_eq!(JumpHostConfig::parse("2001:db8::1").unwrap().port, 22);
+
+        assert!(JumpHostConfig::parse("").is_none());
+        assert!(JumpHostConfig::parse("ops@").is_none());
+        assert!(JumpHostConfig::parse("bastion:ssh").is_none());
+    }
+
+    #[test]
+    fn test_port_forward_spec() {
+        let forward = PortForward::parse(ForwardDirection::Local, "8080:db.internal:5432").unwrap();
+        assert_eq!(forward.bind_address, "127.0.0.1");
+        assert_eq!((forward.bind_port, forward.target_host.as_str(), forward.target_port), (8080, "db.internal", 5432));
+        assert_eq!(forward.spec(), "8080:db.internal:5432");
+        assert_eq!(forward.label(), "-L 8080:db.internal:5432");
+
+        let forward = PortForward::parse(ForwardDirection::Remote, "0.0.0.0:9000:localhost:3000").unwrap();
+        assert_eq!(forward.bind_address, "0.0.0.0");
+        assert_eq!(forward.label(), "-R 0.0.0.0:9000:localhost:3000");
+
+        let forward = PortForward::parse(ForwardDirection::Local, "[::1]:8080:[2001:db8::5]:80").unwrap();
+        assert_eq!((forward.bind_address.as_str(), forward.target_host.as_str()), ("::1", "2001:db8::5"));
+        assert_eq!(forward.spec(), "[::1]:8080:[2001:db8::5]:80");
+
+        for bad in ["", "8080", "8080:db", "0:db:80", "8080:db:http", "8080::80", ":8080:db:80", "a:b:8080:db:80", "8080:db:80:", "[::1]x:8080:db:80", "8080:db:80 x"] {
+            assert!(PortForward::parse(ForwardDirection::Local, bad).is_none(), "{:?}", bad);
+        }
+    }
+
     #[test]
     fn test_session_data_operations() {
         let mut data = SessionData::new();
@@ -522,4 +1374,178 @@ mod tests {
         assert_eq!(data.sessions_in_group(group_id).len(), 1);
         assert_eq!(data.ungrouped_sessions().len(), 0);
     }
+
+    #[test]
+    fn test_duplicate_session() {
+        let group_id = Uuid::new_v4();
+        let mut k8s = K8sSession::with_container("api", "prod", "default", "api-0", "app");
+        k8s.group_id = Some(group_id);
+        k8s.color_scheme = Some("matrix".to_string());
+        let original = Session::K8s(k8s);
+
+        let copy = original.duplicate();
+        assert_ne!(copy.id(), original.id());
+        assert_eq!(copy.name(), "api (copy)");
+        assert_eq!(copy.group_id(), Some(group_id));
+        match copy {
+            Session::K8s(s) => {
+                assert_eq!(s.pod, "api-0");
+                assert_eq!(s.container.as_deref(), Some("app"));
+                assert_eq!(s.color_scheme.as_deref(), Some("matrix"));
+            }
+            _ => panic!("Duplicate changed session type"),
+        }
+
+        let ssm = Session::Ssm(SsmSession::new("bastion", "i-0123456789abcdef0"));
+        let copy = ssm.duplicate();
+        assert_ne!(copy.id(), ssm.id());
+        assert!(matches!(copy, Session::Ssm(ref s) if s.instance_id == "i-0123456789abcdef0"));
+    }
+
+    #[test]
+    fn test_private_key_extra_keys() {
+        // Sessions saved before multiple keys were supported still load
+        let json = r#"{"type":"PrivateKey","path":"~/.ssh/id_rsa"}"#;
+        let auth: AuthMethod = serde_json::from_str(json).unwrap();
+        assert!(matches!(auth, AuthMethod::PrivateKey { identities_only: false, .. }));
+        assert_eq!(auth.key_paths(), vec![&PathBuf::from("~/.ssh/id_rsa")]);
+
+        let auth = AuthMethod::PrivateKey {
+            path: PathBuf::from("a"),
+            passphrase: None,
+            use_keychain: false,
+            extra_keys: vec![PathBuf::from("b"), PathBuf::from("c")],
+            identities_only: true,
+        };
+        let names: Vec<_> = auth.key_paths().iter().map(|p| p.to_string_lossy().into_owned()).collect();
+        assert_eq!(names, vec!["a", "b", "c"]);
+        assert!(AuthMethod::Agent.key_paths().is_empty());
+        assert!(!auth.has_saved_secret());
+        assert!(AuthMethod::Password { password: None, use_keychain: true }.has_saved_secret());
+    }
+
+    #[test]
+    fn test_keyboard_interactive_auth() {
+        let auth: AuthMethod = serde_json::from_str(r#"{"type":"KeyboardInteractive"}"#).unwrap();
+        assert_eq!(auth, AuthMethod::KeyboardInteractive);
+        assert_eq!(serde_json::to_string(&auth).unwrap(), r#"{"type":"KeyboardInteractive"}"#);
+        // Nothing to save: every answer is asked for when connecting
+        assert!(!auth.has_saved_secret());
+        assert_eq!(auth.without_secrets(), auth);
+        assert!(auth.key_paths().is_empty());
+    }
+
+    #[test]
+    fn test_group_defaults() {
+        let parent = GroupDefaults {
+            username: Some("admin".to_string()),
+            port: Some(2222),
+            ..Default::default()
+        };
+        let defaults = GroupDefaults {
+            username: Some("deploy".to_string()),
+            color_scheme: Some("dracula".to_string()),
+            ..Default::default()
+        }
+        .or(&parent);
+        assert_eq!(defaults.username.as_deref(), Some("deploy"));
+        assert_eq!(defaults.port, Some(2222));
+        assert!(GroupDefaults::default().is_empty());
+
+        let mut session = SshSession::new("web", "web.example.com", "deploy");
+        session.port = 22;
+        session.auth = AuthMethod::Password {
+            password: Some("secret".to_string()),
+            use_keychain: false,
+        };
+        session.color_scheme = Some("dracula".to_string());
+        session.inherited = session.matching_defaults(&defaults);
+        assert_eq!(session.inherited, vec![InheritedSetting::Username, InheritedSetting::ColorScheme]);
+
+        // Only inherited settings follow the group; the overridden port stays
+        let changed = GroupDefaults {
+            username: Some("ops".to_string()),
+            port: Some(2200),
+            auth: Some(AuthMethod::Password {
+                password: None,
+                use_keychain: false,
+            }),
+            color_scheme: None,
+        };
+        assert!(session.apply_group_defaults(&changed));
+        assert_eq!(session.username, "ops");
+        assert_eq!(session.port, 22);
+        assert!(session.auth.has_saved_secret());
+        assert_eq!(session.color_scheme.as_deref(), Some("dracula"));
+        assert!(!session.apply_group_defaults(&changed));
+
+        // Groups and sessions saved before defaults existed still load
+        let group: SessionGroup = serde_json::from_str(&format!(r#"{{"id":"{}","name":"g","parent_id":null,"color":null}}"#, Uuid::new_v4())).unwrap();
+        assert!(group.defaults.is_empty());
+        assert!(!serde_json::to_string(&group).unwrap().contains("defaults"));
+    }
+
+    #[test]
+    fn test_keep_alive_escapes() {
+        assert_eq!(KeepAliveCommand::new(" \\b", 60).bytes(), b" \x08");
+        assert_eq!(KeepAliveCommand::new("true\\r", 60).bytes(), b"true\r");
+        assert_eq!(KeepAliveCommand::new("\\x00\\\\", 60).bytes(), b"\x00\\");
+        // Unknown or incomplete escapes are sent as written
+        assert_eq!(KeepAliveCommand::new("a\\q\\x4", 60).bytes(), b"a\\q\\x4");
+        assert_eq!(KeepAliveCommand::new("x", 0).interval(), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_newline_mode_serialization() {
+        let mut session = SshSession::new("switch", "10.0.0.1", "admin");
+        let json = serde_json::to_string(&session).unwrap();
+        assert!(!json.contains("newline_mode"));
+
+        session.newline_mode = NewlineMode::CrLf;
+        let json = serde_json::to_string(&session).unwrap();
+        assert!(json.contains(r#""newline_mode":"crlf""#));
+        let parsed: SshSession = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.newline_mode, NewlineMode::CrLf);
+    }
+
+    #[test]
+    fn test_ssh_from_ssm() {
+        let mut ssm = SsmSession::new("bastion", "i-0123456789abcdef0");
+        ssm.group_id = Some(Uuid::new_v4());
+        ssm.color_scheme = Some("red".to_string());
+
+        let ssh = SshSession::from_ssm(&ssm, "10.0.0.5", "ec2-user");
+        assert_ne!(ssh.id, ssm.id);
+        assert_eq!(ssh.host, "10.0.0.5");
+        assert_eq!(ssh.group_id, ssm.group_id);
+        assert_eq!(ssh.color_scheme, ssm.color_scheme);
+    }
+
+    #[test]
+    fn test_serial_session_defaults() {
+        let json = r#"{"session_type":"Serial","id":"6f1c2b1e-8f4a-4a53-9a0e-2b7f1d3c4e5a","name":"router","device":"/dev/ttyUSB0","group_id":null}"#;
+        let session: Session = serde_json::from_str(json).unwrap();
+        let Session::Serial(serial) = session else {
+            panic!("expected a serial session");
+        };
+        assert_eq!(serial.device, "/dev/ttyUSB0");
+        assert_eq!(serial.line_settings(), "115200 8N1");
+        assert_eq!(serial.flow_control, SerialFlowControl::None);
+
+        let mut serial = SerialSession::new("modem", "COM3");
+        serial.baud_rate = 9600;
+        serial.data_bits = 7;
+        serial.parity = SerialParity::Even;
+        serial.stop_bits = 2;
+        assert_eq!(serial.line_settings(), "9600 7E2");
+    }
+
+    #[test]
+    fn test_telnet_session_default_port() {
+        let json = r#"{"session_type":"Telnet","id":"0d9b7c1a-2e3f-4a5b-8c6d-7e8f9a0b1c2d","name":"switch","host":"10.0.0.2","group_id":null}"#;
+        let Session::Telnet(telnet) = serde_json::from_str(json).unwrap() else {
+            panic!("expected a telnet session");
+        };
+        assert_eq!(telnet.address(), "10.0.0.2:23");
+    }
 }
@@ -6,6 +6,8 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
 use thiserror::Error;
 
+use crate::format::format_size;
+
 /// SFTP error types
 #[derive(Debug, Error)]
 pub enum SftpError {
@@ -49,6 +51,30 @@ pub struct DirEntry {
     pub permissions: String,
 }
 
+/// What a recursive delete would remove
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeletePreview {
+    /// Files, symlinks and other non-directories
+    pub files: u64,
+    /// Directories, including the one being deleted
+    pub dirs: u64,
+    /// Total size of the files
+    pub bytes: u64,
+}
+
+impl DeletePreview {
+    /// e.g. "12 files and 3 folders, 4.5 MB"
+    pub fn summary(&self) -> String {
+        let plural = |count: u64, one: &str, many: &str| format!("{} {}", count, if count == 1 { one } else { many });
+        format!(
+            "{} and {}, {}",
+            plural(self.files, "file", "files"),
+            plural(self.dirs, "folder", "folders"),
+            format_size(self.bytes)
+        )
+    }
+}
+
 /// Transfer progress for file operations
 #[derive(Debug, Clone)]
 pub struct TransferProgress {
@@ -127,6 +153,16 @@ impl SftpBrowser {
 
     /// List directory contents
     pub async fn list_dir(&mut self, path: &Path) -> Result<Vec<DirEntry>, SftpError> {
+        let entries = self.read_entries(path).await?;
+
+        self.entries = entries.clone();
+        self.current_path = path.to_path_buf();
+
+        Ok(entries)
+    }
+
+    /// Read a directory's entries without changing the current directory
+    async fn read_entries(&self, path: &Path) -> Result<Vec<DirEntry>, SftpError> {
         let session = self.session.as_ref().ok_or(SftpError::NotConnected)?;
 
         let path_str = path.to_string_lossy().to_string();
@@ -181,9 +217,6 @@ impl SftpBrowser {
             }
         });
 
-        self.entries = entries.clone();
-        self.current_path = path.to_path_buf();
-
         Ok(entries)
     }
 
@@ -298,6 +331,51 @@ impl SftpBrowser {
         Ok(())
     }
 
+    /// Count what a recursive delete of `path` would remove, without deleting anything
+    ///
+    /// Symlinks are counted as files; their targets are not followed.
+    pub async fn preview_remove(&self, path: &Path) -> Result<DeletePreview, SftpError> {
+        let mut preview = DeletePreview::default();
+        let mut pending = vec![path.to_path_buf()];
+        while let Some(dir) = pending.pop() {
+            preview.dirs += 1;
+            for entry in self.read_entries(&dir).await? {
+                if entry.entry_type == EntryType::Directory {
+                    pending.push(dir.join(&entry.name));
+                } else {
+                    preview.files += 1;
+                    preview.bytes += entry.size;
+                }
+            }
+        }
+        Ok(preview)
+    }
+
+    /// Delete a directory and everything in it
+    ///
+    /// Files are removed as each directory is walked, then the directories
+    /// themselves, deepest first. Stops at the first error.
+    pub async fn remove_recursive(&self, path: &Path) -> Result<(), SftpError> {
+        let mut dirs = Vec::new();
+        let mut pending = vec![path.to_path_buf()];
+        while let Some(dir) = pending.pop() {
+            for entry in self.read_entries(&dir).await? {
+                let entry_path = dir.join(&entry.name);
+                if entry.entry_type == EntryType::Directory {
+                    pending.push(entry_path);
+                } else {
+                    self.remove_file(&entry_path).await?;
+                }
+            }
+            dirs.push(dir);
+        }
+        // Parents are walked before their children
+        for dir in dirs.iter().rev() {
+            self.remove_dir(dir).await?;
+        }
+        Ok(())
+    }
+
     /// Rename a file or directory
     pub async fn rename(&self, old_path: &Path, new_path: &Path) -> Result<(), SftpError> {
         let session = self.session.as_ref().ok_or(SftpError::NotConnected)?;
@@ -350,19 +428,18 @@ fn format_permissions(mode: u32) -> String {
     s
 }
 
-/// Format file size to human-readable string
-pub fn format_size(size: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-
-    if size < KB {
-        format!("{} B", size)
-    } else if size < MB {
-        format!("{:.1} KB", size as f64 / KB as f64)
-    } else if size < GB {
-        format!("{:.1} MB", size as f64 / MB as f64)
-    } else {
-        format!("{:.1} GB", size as f64 / GB as f64)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delete_preview_summary() {
+        let preview = DeletePreview {
+            files: 12,
+            dirs: 1,
+            bytes: 4 * 1024 * 1024 + 512 * 1024,
+        };
+        assert_eq!(preview.summary(), "12 files and 1 folder, 4.5 MB");
+        assert_eq!(DeletePreview { files: 1, dirs: 2, bytes: 0 }.summary(), "1 file and 2 folders, 0 B");
     }
 }
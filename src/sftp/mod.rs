@@ -2,4 +2,4 @@
 
 mod browser;
 
-pub use browser::{SftpBrowser, SftpError, DirEntry, EntryType, TransferProgress, format_size};
+pub use browser::{SftpBrowser, SftpError, DeletePreview, DirEntry, EntryType, TransferProgress};
@@ -0,0 +1,200 @@
+//! Ambiguous-width characters
+//!
+//! Unicode leaves the width of some characters (box drawing, arrows,
+//! circled numbers and many other symbols) to the context: CJK locales and fonts
+//! treat them as two cells wide, everyone else as one. Alacritty always lays
+//! them out narrow, so a TUI that assumes wide drifts out of alignment.
+//! [`AmbiguousWide`] wraps the VT handler and gives those characters a wide
+//! cell instead.
+//!
+//! Line-drawing characters selected through the DEC special graphics charset
+//! are mapped inside alacritty and stay narrow.
+
+use alacritty_terminal::event::EventListener;
+use alacritty_terminal::index::Column;
+use alacritty_terminal::term::cell::Flags;
+use alacritty_terminal::term::Term;
+use alacritty_terminal::vte::ansi::cursor_icon::CursorIcon;
+use alacritty_terminal::vte::ansi::{
+    Attr, CharsetIndex, ClearMode, CursorShape, CursorStyle, Handler, Hyperlink, KeyboardModes,
+    KeyboardModesApplyBehavior, LineClearMode, Mode, ModifyOtherKeys, PrivateMode, Rgb, ScpCharPath, ScpUpdateMode,
+    StandardCharset, TabulationClearMode,
+};
+use unicode_width::UnicodeWidthChar;
+
+/// A character alacritty lays out as a wide cell, written in place of the
+/// ambiguous one and then swapped out
+const WIDE_PLACEHOLDER: char = '\u{3000}';
+
+/// Whether `c` is one cell wide normally but two in East Asian contexts
+pub fn is_ambiguous_width(c: char) -> bool {
+    c.width() == Some(1) && c.width_cjk() == Some(2)
+}
+
+/// VT handler that treats ambiguous-width characters as wide
+///
+/// Everything except printable input is passed straight to the terminal.
+pub struct AmbiguousWide<'a, T: EventListener> {
+    term: &'a mut Term<T>,
+}
+
+impl<'a, T: EventListener> AmbiguousWide<'a, T> {
+    pub fn new(term: &'a mut Term<T>) -> Self {
+        Self { term }
+    }
+
+    /// Write `c` as a wide cell
+    ///
+    /// Letting alacritty write a real wide character takes care of wrapping,
+    /// insert mode and the spacer cell; only the glyph is replaced afterwards.
+    fn input_wide(&mut self, c: char) {
+        self.term.input(WIDE_PLACEHOLDER);
+
+        // The cursor sits after the spacer, or on it when the line is full
+        let cursor = &self.term.grid().cursor;
+        let back = if cursor.input_needs_wrap { 1 } else { 2 };
+        let line = cursor.point.line;
+        let Some(column) = cursor.point.column.0.checked_sub(back) else {
+            return;
+        };
+
+        // Nothing is written when the glyph doesn't fit and wrapping is off
+        let cell = &mut self.term.grid_mut()[line][Column(column)];
+        if cell.c == WIDE_PLACEHOLDER && cell.flags.contains(Flags::WIDE_CHAR) {
+            cell.c = c;
+        }
+    }
+}
+
+macro_rules! forward {
+    ($(fn $name:ident(&mut self $(, $arg:ident: $ty:ty)*);)*) => {
+        $(
+            fn $name(&mut self $(, $arg: $ty)*) {
+                self.term.$name($($arg),*)
+            }
+        )*
+    };
+}
+
+impl<T: EventListener> Handler for AmbiguousWide<'_, T> {
+    fn input(&mut self, c: char) {
+        if is_ambiguous_width(c) {
+            self.input_wide(c);
+        } else {
+            self.term.input(c);
+        }
+    }
+
+    forward! {
+        fn set_title(&mut self, title: Option<String>);
+        fn set_cursor_style(&mut self, style: Option<CursorStyle>);
+        fn set_cursor_shape(&mut self, shape: CursorShape);
+        fn goto(&mut self, line: i32, col: usize);
+        fn goto_line(&mut self, line: i32);
+        fn goto_col(&mut self, col: usize);
+        fn insert_blank(&mut self, count: usize);
+        fn move_up(&mut self, rows: usize);
+        fn move_down(&mut self, rows: usize);
+        fn identify_terminal(&mut self, intermediate: Option<char>);
+        fn device_status(&mut self, arg: usize);
+        fn move_forward(&mut self, cols: usize);
+        fn move_backward(&mut self, cols: usize);
+        fn move_down_and_cr(&mut self, rows: usize);
+        fn move_up_and_cr(&mut self, rows: usize);
+        fn put_tab(&mut self, count: u16);
+        fn backspace(&mut self);
+        fn carriage_return(&mut self);
+        fn linefeed(&mut self);
+        fn bell(&mut self);
+        fn substitute(&mut self);
+        fn newline(&mut self);
+        fn set_horizontal_tabstop(&mut self);
+        fn scroll_up(&mut self, lines: usize);
+        fn scroll_down(&mut self, lines: usize);
+        fn insert_blank_lines(&mut self, lines: usize);
+        fn delete_lines(&mut self, lines: usize);
+        fn erase_chars(&mut self, count: usize);
+        fn delete_chars(&mut self, count: usize);
+        fn move_backward_tabs(&mut self, count: u16);
+        fn move_forward_tabs(&mut self, count: u16);
+        fn save_cursor_position(&mut self);
+        fn restore_cursor_position(&mut self);
+        fn clear_line(&mut self, mode: LineClearMode);
+        fn clear_screen(&mut self, mode: ClearMode);
+        fn clear_tabs(&mut self, mode: TabulationClearMode);
+        fn set_tabs(&mut self, interval: u16);
+        fn reset_state(&mut self);
+        fn reverse_index(&mut self);
+        fn terminal_attribute(&mut self, attr: Attr);
+        fn set_mode(&mut self, mode: Mode);
+        fn unset_mode(&mut self, mode: Mode);
+        fn report_mode(&mut self, mode: Mode);
+        fn set_private_mode(&mut self, mode: PrivateMode);
+        fn unset_private_mode(&mut self, mode: PrivateMode);
+        fn report_private_mode(&mut self, mode: PrivateMode);
+        fn set_scrolling_region(&mut self, top: usize, bottom: Option<usize>);
+        fn set_keypad_application_mode(&mut self);
+        fn unset_keypad_application_mode(&mut self);
+        fn set_active_charset(&mut self, index: CharsetIndex);
+        fn configure_charset(&mut self, index: CharsetIndex, charset: StandardCharset);
+        fn set_color(&mut self, index: usize, color: Rgb);
+        fn dynamic_color_sequence(&mut self, prefix: String, index: usize, terminator: &str);
+        fn reset_color(&mut self, index: usize);
+        fn clipboard_store(&mut self, clipboard: u8, data: &[u8]);
+        fn clipboard_load(&mut self, clipboard: u8, terminator: &str);
+        fn decaln(&mut self);
+        fn push_title(&mut self);
+        fn pop_title(&mut self);
+        fn text_area_size_pixels(&mut self);
+        fn text_area_size_chars(&mut self);
+        fn set_hyperlink(&mut self, hyperlink: Option<Hyperlink>);
+        fn set_mouse_cursor_icon(&mut self, icon: CursorIcon);
+        fn report_keyboard_mode(&mut self);
+        fn push_keyboard_mode(&mut self, mode: KeyboardModes);
+        fn pop_keyboard_modes(&mut self, to_pop: u16);
+        fn set_keyboard_mode(&mut self, mode: KeyboardModes, behavior: KeyboardModesApplyBehavior);
+        fn set_modify_other_keys(&mut self, mode: ModifyOtherKeys);
+        fn report_modify_other_keys(&mut self);
+        fn set_scp(&mut self, char_path: ScpCharPath, update_mode: ScpUpdateMode);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::event::VoidListener;
+    use alacritty_terminal::index::Line;
+    use alacritty_terminal::term::Config as TermConfig;
+    use alacritty_terminal::vte::ansi::{Processor, StdSyncHandler};
+
+    use crate::terminal::terminal::SizeInfo;
+
+    fn advance(term: &mut Term<VoidListener>, bytes: &[u8]) {
+        let mut processor = Processor::<StdSyncHandler>::new();
+        processor.advance(&mut AmbiguousWide::new(term), bytes);
+    }
+
+    #[test]
+    fn test_is_ambiguous_width() {
+        assert!(is_ambiguous_width('─'));
+        assert!(is_ambiguous_width('→'));
+        assert!(!is_ambiguous_width('a'));
+        assert!(!is_ambiguous_width('漢'));
+    }
+
+    #[test]
+    fn test_ambiguous_chars_take_two_cells() {
+        let mut term = Term::new(TermConfig::default(), &SizeInfo::new(5, 2), VoidListener);
+        advance(&mut term, "a─b".as_bytes());
+
+        let row = &term.grid()[Line(0)];
+        assert_eq!(row[Column(1)].c, '─');
+        assert!(row[Column(1)].flags.contains(Flags::WIDE_CHAR));
+        assert!(row[Column(2)].flags.contains(Flags::WIDE_CHAR_SPACER));
+        assert_eq!(row[Column(3)].c, 'b');
+
+        // A wide cell that doesn't fit wraps like any other wide character
+        advance(&mut term, "\r\nabcd─".as_bytes());
+        assert_eq!(term.grid()[Line(1)][Column(0)].c, '─');
+    }
+}
@@ -0,0 +1,214 @@
+//! Secrets asked for in the terminal while connecting
+//!
+//! When a session's key is rejected, the SSH backend can fall back to
+//! password or keyboard-interactive auth. It asks through an [`AuthPrompter`];
+//! whoever holds the receiving end shows the prompt in the tab and reads the
+//! answer from what the user types, using [`LineInput`].
+//!
+//! Server host keys that known_hosts doesn't vouch for are asked about on a
+//! separate channel as [`HostKeyRequest`]s, answered with accept or reject.
+
+use tokio::sync::{mpsc, oneshot};
+
+/// A question from the backend, waiting for the user's answer
+#[derive(Debug)]
+pub struct AuthPromptRequest {
+    /// Text to show, possibly several lines; the last one is the prompt itself
+    pub prompt: String,
+    /// Whether the answer may be shown as it is typed (e.g. a username)
+    pub echo: bool,
+    reply: oneshot::Sender<Option<String>>,
+}
+
+impl AuthPromptRequest {
+    /// Answer the request; None if the user cancelled
+    pub fn respond(self, answer: Option<String>) {
+        let _ = self.reply.send(answer);
+    }
+}
+
+/// A server host key that isn't trusted yet, waiting for the user to accept or reject it
+#[derive(Debug)]
+pub struct HostKeyRequest {
+    /// Host the key was presented for
+    pub host: String,
+    /// Key algorithm, e.g. `ssh-ed25519`
+    pub key_type: String,
+    /// SHA256 fingerprint, as `ssh` shows it
+    pub fingerprint: String,
+    /// known_hosts has a different key for this host
+    pub changed: bool,
+    reply: oneshot::Sender<bool>,
+}
+
+impl HostKeyRequest {
+    /// Accept or reject the key
+    pub fn respond(self, accept: bool) {
+        let _ = self.reply.send(accept);
+    }
+}
+
+/// Sending side of the prompt channel, held by the backend
+#[derive(Debug, Clone)]
+pub struct AuthPrompter {
+    secrets: mpsc::UnboundedSender<AuthPromptRequest>,
+    /// Where host key questions go; without it untrusted keys are rejected
+    host_keys: Option<mpsc::UnboundedSender<HostKeyRequest>>,
+}
+
+impl AuthPrompter {
+    /// A prompter and the receiver its requests arrive on
+    pub fn channel() -> (Self, mpsc::UnboundedReceiver<AuthPromptRequest>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (
+            Self {
+                secrets: tx,
+                host_keys: None,
+            },
+            rx,
+        )
+    }
+
+    /// Also ask about untrusted host keys; their requests arrive on the returned receiver
+    pub fn host_key_channel(&mut self) -> mpsc::UnboundedReceiver<HostKeyRequest> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.host_keys = Some(tx);
+        rx
+    }
+
+    /// Ask whether to trust a host key and wait for the decision
+    ///
+    /// Returns false if the user rejected the key or closed the question
+    /// unanswered, or if nobody is listening for host key questions.
+    pub async fn confirm_host_key(
+        &self,
+        host: impl Into<String>,
+        key_type: impl Into<String>,
+        fingerprint: impl Into<String>,
+        changed: bool,
+    ) -> bool {
+        let Some(host_keys) = &self.host_keys else {
+            return false;
+        };
+        let (reply, answer) = oneshot::channel();
+        let request = HostKeyRequest {
+            host: host.into(),
+            key_type: key_type.into(),
+            fingerprint: fingerprint.into(),
+            changed,
+            reply,
+        };
+        if host_keys.send(request).is_err() {
+            return false;
+        }
+        answer.await.unwrap_or(false)
+    }
+
+    /// Ask the user and wait for the answer
+    ///
+    /// Returns None if the user cancelled or nobody is listening.
+    pub async fn ask(&self, prompt: impl Into<String>, echo: bool) -> Option<String> {
+        let (reply, answer) = oneshot::channel();
+        let request = AuthPromptRequest {
+            prompt: prompt.into(),
+            echo,
+            reply,
+        };
+        self.secrets.send(request).ok()?;
+        answer.await.ok().flatten()
+    }
+}
+
+/// How a line of input ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineEvent {
+    /// Enter was pressed
+    Submitted(String),
+    /// Ctrl-C, Ctrl-D or Escape was pressed
+    Cancelled,
+}
+
+/// A line being typed at a prompt, fed with the terminal's input bytes
+#[derive(Debug, Default)]
+pub struct LineInput {
+    line: String,
+}
+
+impl LineInput {
+    /// Feed a chunk of input
+    ///
+    /// Returns the bytes to echo back (only when `echo` is set) and, once the
+    /// line is finished, how it ended. Input after the end of the line is
+    /// dropped. Escape sequences (arrow keys and the like) are ignored.
+    pub fn feed(&mut self, data: &[u8], echo: bool) -> (Vec<u8>, Option<LineEvent>) {
+        let mut echoed = Vec::new();
+        if data == b"\x1b" {
+            return (echoed, Some(LineEvent::Cancelled));
+        }
+        if data.first() == Some(&0x1b) {
+            return (echoed, None);
+        }
+
+        for c in String::from_utf8_lossy(data).chars() {
+            match c {
+                '\r' | '\n' => return (echoed, Some(LineEvent::Submitted(std::mem::take(&mut self.line)))),
+                '\x03' | '\x04' => return (echoed, Some(LineEvent::Cancelled)),
+                '\x7f' | '\x08' => {
+                    if self.line.pop().is_some() && echo {
+                        echoed.extend_from_slice(b"\x08 \x08");
+                    }
+                }
+                c if c.is_control() => {}
+                c => {
+                    self.line.push(c);
+                    if echo {
+                        let mut buf = [0; 4];
+                        echoed.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                    }
+                }
+            }
+        }
+        (echoed, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_input() {
+        let mut input = LineInput::default();
+        assert_eq!(input.feed(b"hunter", false), (Vec::new(), None));
+        assert_eq!(input.feed(b"\x7f\x7fer2", false), (Vec::new(), None));
+        // Arrow keys don't end up in the password
+        assert_eq!(input.feed(b"\x1b[D", false), (Vec::new(), None));
+        assert_eq!(
+            input.feed(b"\rignored", false),
+            (Vec::new(), Some(LineEvent::Submitted("hunter2".to_string())))
+        );
+
+        let (echoed, event) = input.feed("bé\x7f".as_bytes(), true);
+        assert_eq!(echoed, "bé\x08 \x08".as_bytes());
+        assert_eq!(event, None);
+        assert_eq!(input.feed(b"\x03", true).1, Some(LineEvent::Cancelled));
+        assert_eq!(input.feed(b"\x1b", false).1, Some(LineEvent::Cancelled));
+    }
+
+    #[tokio::test]
+    async fn test_confirm_host_key() {
+        let (mut prompter, _secrets) = AuthPrompter::channel();
+        assert!(!prompter.confirm_host_key("web", "ssh-ed25519", "SHA256:abc", false).await);
+
+        let mut requests = prompter.host_key_channel();
+        tokio::spawn(async move {
+            let request = requests.recv().await.unwrap();
+            assert_eq!((request.host.as_str(), request.changed), ("web", true));
+            request.respond(true);
+            // Dropped unanswered, e.g. the dialog was closed
+            drop(requests.recv().await.unwrap());
+        });
+        assert!(prompter.confirm_host_key("web", "ssh-ed25519", "SHA256:abc", true).await);
+        assert!(!prompter.confirm_host_key("web", "ssh-ed25519", "SHA256:abc", false).await);
+    }
+}
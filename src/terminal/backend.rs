@@ -0,0 +1,104 @@
+//! Common interface over the remote session backends
+//!
+//! Each transport (SSH, SSM, K8s) connects in its own way, but once a session
+//! is up they all move bytes in both directions and accept window size
+//! changes. [`TerminalBackend`] covers the connection step and hands out a
+//! [`BackendIo`] stream that a single I/O loop can drive.
+
+use async_trait::async_trait;
+
+use crate::metrics::ConnectionKind;
+use super::TerminalSize;
+
+/// Result of waiting on a backend's output
+#[derive(Debug)]
+pub enum BackendRead {
+    /// Bytes to display in the terminal
+    Output(Vec<u8>),
+    /// Protocol traffic was handled; nothing to display
+    Idle,
+    /// The session ended, with an optional message for the user
+    Closed(Option<String>),
+    /// The remote process exited with this status
+    Exited(u32),
+    /// The session ended because of a transport error
+    Failed(String),
+}
+
+/// Why a backend failed to connect
+#[derive(Debug, Clone)]
+pub struct ConnectError {
+    /// Heading shown above the message in the terminal
+    pub title: &'static str,
+    pub message: String,
+}
+
+impl ConnectError {
+    pub fn new(title: &'static str, message: impl ToString) -> Self {
+        Self {
+            title,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// The I/O side of a connected session
+///
+/// `read` is polled inside `tokio::select!` alongside user input, so it must
+/// be cancel safe: dropping it before it resolves must not lose data.
+/// Replies that have to go out after a read (acks, pongs) are queued and sent
+/// by `flush`, which the loop calls after handling every read.
+#[async_trait]
+pub trait BackendIo: Send {
+    /// Wait for the next output from the remote side
+    async fn read(&mut self) -> BackendRead;
+
+    /// Send user input to the remote side
+    async fn write(&mut self, data: &[u8]) -> Result<(), String>;
+
+    /// Tell the remote side about a new window size
+    async fn resize(&mut self, size: TerminalSize) -> Result<(), String>;
+
+    /// Send anything queued while reading
+    async fn flush(&mut self) {}
+
+    /// Shut the stream down once the loop ends
+    async fn close(&mut self);
+}
+
+/// A remote session backend
+#[async_trait]
+pub trait TerminalBackend: Send + 'static {
+    /// Kind used for metrics and logs
+    fn kind(&self) -> ConnectionKind;
+
+    /// Connect and open an interactive session of the given size
+    async fn connect(&mut self, size: TerminalSize) -> Result<(), ConnectError>;
+
+    /// Take the I/O stream opened by `connect`
+    ///
+    /// Returns `None` if not connected or the stream was already taken.
+    fn io_channels(&mut self) -> Option<Box<dyn BackendIo>>;
+
+    /// Release the connection after the I/O stream has closed
+    async fn close(&mut self);
+
+    /// Things to tell the user once connected, e.g. port forwards that failed
+    fn connect_notices(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Whether a connection lost to a transport error is reconnected
+    /// automatically, rather than ending the session
+    fn reconnects(&self) -> bool {
+        false
+    }
+
+    /// Connect again after the connection was lost, retrying with backoff
+    ///
+    /// Only called for backends whose `reconnects` is true; the new stream is
+    /// taken with `io_channels` as after `connect`.
+    async fn reconnect(&mut self) -> Result<(), ConnectError> {
+        Err(ConnectError::new("Reconnection Failed", "This connection can't be resumed"))
+    }
+}
@@ -0,0 +1,251 @@
+//! Where each shell command and its output sit in the scrollback
+//!
+//! OSC 133 marks (see [`super::shell_marks`]) are recorded against the
+//! cursor position they arrived at. Positions are absolute lines counted from
+//! the top of the scrollback, which stay put while output scrolls in but
+//! shift once the scrollback is full and its oldest lines are dropped. Each
+//! region keeps the text of its command line so the shift can be found again.
+
+use std::collections::VecDeque;
+
+use super::shell_marks::ShellMark;
+
+/// Most regions remembered; older ones are forgotten first
+pub const MAX_REGIONS: usize = 500;
+
+/// A cell position, with the line counted from the top of the scrollback
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridPos {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// One command: the line it was typed on and the output it produced
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandRegion {
+    /// Start of the command line, after the prompt (`B`)
+    pub command: GridPos,
+    /// Start of the output (`C`)
+    pub output: Option<GridPos>,
+    /// End of the output (`D`)
+    pub end: Option<GridPos>,
+    pub exit_code: Option<i32>,
+    /// Text of the row `command` is on, once the command was submitted
+    pub anchor: String,
+}
+
+impl CommandRegion {
+    /// Whether the command has finished and its output is complete
+    pub fn is_finished(&self) -> bool {
+        self.output.is_some() && self.end.is_some()
+    }
+}
+
+/// A finished command's text, as extracted from the grid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandOutput {
+    pub command: String,
+    pub output: String,
+    pub exit_code: Option<i32>,
+}
+
+/// The command regions of one terminal, oldest first
+#[derive(Debug, Default)]
+pub struct CommandRegions {
+    regions: VecDeque<CommandRegion>,
+}
+
+impl CommandRegions {
+    /// Record a mark the cursor reached at `pos`
+    ///
+    /// Returns the region a `C` mark just opened output for, so the caller
+    /// can fill in its anchor.
+    pub fn record(&mut self, mark: ShellMark, pos: GridPos) -> Option<&mut CommandRegion> {
+        match mark {
+            ShellMark::PromptStart => None,
+            ShellMark::CommandStart => {
+                self.push(pos);
+                None
+            }
+            ShellMark::OutputStart => {
+                // Integrations that skip `B` start the region here
+                let awaiting_output = self.regions.back().is_some_and(|region| region.output.is_none());
+                if !awaiting_output {
+                    self.push(pos);
+                }
+                let region = self.regions.back_mut()?;
+                region.output = Some(pos);
+                Some(region)
+            }
+            ShellMark::CommandFinished(exit_code) => {
+                let region = self.regions.back_mut()?;
+                if region.output.is_some() && region.end.is_none() {
+                    region.end = Some(pos);
+                    region.exit_code = exit_code;
+                }
+                None
+            }
+        }
+    }
+
+    fn push(&mut self, pos: GridPos) {
+        // A prompt that was never submitted (Ctrl-C at the prompt) leaves
+        // an empty region behind; replace it
+        if self.regions.back().is_some_and(|region| region.output.is_none()) {
+            self.regions.pop_back();
+        }
+        if self.regions.len() == MAX_REGIONS {
+            self.regions.pop_front();
+        }
+        self.regions.push_back(CommandRegion {
+            command: pos,
+            output: None,
+            end: None,
+            exit_code: None,
+            anchor: String::new(),
+        });
+    }
+
+    /// Finished regions, oldest first
+    pub fn finished(&self) -> impl Iterator<Item = &CommandRegion> {
+        self.regions.iter().filter(|region| region.is_finished())
+    }
+
+    /// Forget every region, e.g. after the grid was reflowed
+    pub fn clear(&mut self) {
+        self.regions.clear();
+    }
+
+    /// Move every region up by `lines`, dropping those that fall off the top
+    pub fn shift(&mut self, lines: usize) {
+        if lines == 0 {
+            return;
+        }
+        self.regions.retain(|region| region.command.line >= lines);
+        for region in &mut self.regions {
+            for pos in [Some(&mut region.command), region.output.as_mut(), region.end.as_mut()]
+                .into_iter()
+                .flatten()
+            {
+                pos.line -= lines;
+            }
+        }
+    }
+
+    /// Correct for scrollback dropped since the regions were recorded
+    ///
+    /// Run before recording a mark, so new and old positions agree, and
+    /// before reading regions back. `row_text` returns the text of an
+    /// absolute line, or None past the end. The newest anchored command line is looked for at or above where it
+    /// was recorded; if it is gone, so is everything older, and all regions
+    /// are forgotten.
+    pub fn rebase(&mut self, row_text: impl Fn(usize) -> Option<String>) {
+        let Some(newest) = self.regions.iter().rev().find(|region| !region.anchor.is_empty()) else {
+            return;
+        };
+        let expected = newest.command.line;
+        if row_text(expected).as_deref() == Some(newest.anchor.as_str()) {
+            return;
+        }
+        let found = (0..expected).rev().find(|&line| row_text(line).as_deref() == Some(newest.anchor.as_str()));
+        match found {
+            Some(line) => self.shift(expected - line),
+            None => self.clear(),
+        }
+    }
+}
+
+/// Text from `start` up to (not including) `end`
+///
+/// `row` returns a line's text, one char per column with NUL for cells to
+/// skip (wide character spacers), and whether it wraps onto the next line;
+/// wrapped rows are joined without a line break. Trailing blanks are trimmed
+/// from each line and trailing empty lines dropped.
+pub fn text_between(start: GridPos, end: GridPos, row: impl Fn(usize) -> Option<(String, bool)>) -> String {
+    let mut text = String::new();
+    for line in start.line..=end.line {
+        let Some((content, wrapped)) = row(line) else {
+            break;
+        };
+        let chars: Vec<char> = content.chars().collect();
+        let from = if line == start.line { start.column.min(chars.len()) } else { 0 };
+        let to = if line == end.line { end.column.min(chars.len()) } else { chars.len() };
+        let segment: String = chars[from..to.max(from)].iter().filter(|&&c| c != '\0').collect();
+        if wrapped && line != end.line {
+            text.push_str(&segment);
+        } else {
+            text.push_str(segment.trim_end());
+            text.push('\n');
+        }
+    }
+    text.trim_end_matches('\n').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(line: usize, column: usize) -> GridPos {
+        GridPos { line, column }
+    }
+
+    #[test]
+    fn test_record_regions() {
+        let mut regions = CommandRegions::default();
+        regions.record(ShellMark::PromptStart, pos(0, 0));
+        regions.record(ShellMark::CommandStart, pos(0, 2));
+        regions.record(ShellMark::OutputStart, pos(1, 0)).unwrap().anchor = "$ ls".to_string();
+        assert_eq!(regions.finished().count(), 0);
+        regions.record(ShellMark::CommandFinished(Some(0)), pos(3, 0));
+
+        // An abandoned prompt is replaced by the next one
+        regions.record(ShellMark::CommandStart, pos(3, 2));
+        regions.record(ShellMark::CommandStart, pos(4, 2));
+        regions.record(ShellMark::OutputStart, pos(5, 0));
+        regions.record(ShellMark::CommandFinished(Some(1)), pos(6, 0));
+
+        // A shell that only sends C and D
+        regions.record(ShellMark::OutputStart, pos(7, 0));
+        regions.record(ShellMark::CommandFinished(None), pos(8, 0));
+
+        let finished: Vec<&CommandRegion> = regions.finished().collect();
+        assert_eq!(finished.len(), 3);
+        assert_eq!(finished[0].command, pos(0, 2));
+        assert_eq!(finished[0].end, Some(pos(3, 0)));
+        assert_eq!(finished[1].command, pos(4, 2));
+        assert_eq!(finished[1].exit_code, Some(1));
+        assert_eq!(finished[2].command, pos(7, 0));
+    }
+
+    #[test]
+    fn test_rebase_after_dropped_scrollback() {
+        let mut regions = CommandRegions::default();
+        regions.record(ShellMark::CommandStart, pos(2, 2));
+        regions.record(ShellMark::OutputStart, pos(3, 0)).unwrap().anchor = "$ old".to_string();
+        regions.record(ShellMark::CommandFinished(None), pos(4, 0));
+        regions.record(ShellMark::CommandStart, pos(10, 2));
+        regions.record(ShellMark::OutputStart, pos(11, 0)).unwrap().anchor = "$ new".to_string();
+        regions.record(ShellMark::CommandFinished(None), pos(12, 0));
+
+        // Three lines dropped from the top: "$ new" moved from 10 to 7
+        let rows = ["x", "x", "x", "x", "x", "x", "x", "$ new"];
+        regions.rebase(|line| rows.get(line).map(|s| s.to_string()));
+        let finished: Vec<&CommandRegion> = regions.finished().collect();
+        assert_eq!(finished.len(), 1);
+        assert_eq!(finished[0].output, Some(pos(8, 0)));
+
+        regions.rebase(|_| Some("cleared".to_string()));
+        assert_eq!(regions.finished().count(), 0);
+    }
+
+    #[test]
+    fn test_text_between() {
+        let rows = [("$ echo hi   ", false), ("hi", false), ("long lin", true), ("e wrapped", false), ("$ 日\0", false)];
+        let row = |line: usize| rows.get(line).map(|(text, wrapped)| (text.to_string(), *wrapped));
+        assert_eq!(text_between(pos(0, 2), pos(1, 0), row), "echo hi");
+        assert_eq!(text_between(pos(1, 0), pos(4, 0), row), "hi\nlong line wrapped");
+        // Output that didn't end in a newline
+        assert_eq!(text_between(pos(1, 0), pos(1, 2), row), "hi");
+        assert_eq!(text_between(pos(4, 2), pos(5, 0), row), "日");
+    }
+}
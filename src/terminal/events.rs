@@ -1,7 +1,10 @@
 use std::sync::mpsc::{channel, Receiver, Sender};
+use std::time::Duration;
 
 use alacritty_terminal::event::{Event as AlacEvent, EventListener};
 
+use super::zmodem::ZmodemDirection;
+
 /// Events emitted by the terminal
 #[derive(Debug, Clone)]
 pub enum TerminalEvent {
@@ -11,10 +14,25 @@ pub enum TerminalEvent {
     TitleChanged(String),
     /// Bell rang
     Bell,
-    /// Terminal exited with status code
-    Exit(i32),
+    /// The local shell exited, with its status if it exited normally
+    ///
+    /// Alacritty reports an exit twice, with the status and then without;
+    /// `Terminal::poll_events` passes on only the first.
+    Exit(Option<i32>),
     /// Clipboard store request
     ClipboardStore(String),
+    /// A command finished (from shell integration marks)
+    CommandFinished {
+        exit_code: Option<i32>,
+        /// Time since the command's output started, if seen
+        duration: Option<Duration>,
+    },
+    /// The remote started a ZMODEM transfer (`sz`/`rz`) and is waiting for an answer
+    ZmodemDetected(ZmodemDirection),
+    /// The remote is waiting at a `sudo` password prompt (autofill sessions only)
+    SudoPrompt,
+    /// The server's host key needs the user's OK (see `Terminal::take_host_key_request`)
+    HostKeyPrompt,
 }
 
 impl From<AlacEvent> for TerminalEvent {
@@ -23,7 +41,8 @@ impl From<AlacEvent> for TerminalEvent {
             AlacEvent::Wakeup => TerminalEvent::Wakeup,
             AlacEvent::Title(t) => TerminalEvent::TitleChanged(t),
             AlacEvent::Bell => TerminalEvent::Bell,
-            AlacEvent::Exit => TerminalEvent::Exit(0),
+            AlacEvent::ChildExit(code) => TerminalEvent::Exit(Some(code)),
+            AlacEvent::Exit => TerminalEvent::Exit(None),
             AlacEvent::ClipboardStore(_, data) => TerminalEvent::ClipboardStore(data),
             _ => TerminalEvent::Wakeup,
         }
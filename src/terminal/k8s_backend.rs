@@ -2,6 +2,7 @@
 //!
 //! Provides terminal I/O for Kubernetes pod exec sessions.
 
+use async_trait::async_trait;
 use futures::SinkExt;
 use kube::{
     api::{Api, AttachParams},
@@ -12,7 +13,9 @@ use thiserror::Error;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::sync::mpsc;
 
+use crate::metrics::ConnectionKind;
 use crate::session::K8sSession;
+use super::backend::{BackendIo, BackendRead, ConnectError, TerminalBackend};
 
 /// Errors that can occur during K8s exec operations
 #[derive(Debug, Error)]
@@ -81,6 +84,8 @@ pub struct K8sBackend {
     session: K8sSession,
     state: ConnectionState,
     size: TerminalSize,
+    /// Channels opened by the trait `connect`, until taken for I/O
+    io: Option<K8sIo>,
 }
 
 impl K8sBackend {
@@ -90,6 +95,7 @@ impl K8sBackend {
             session,
             state: ConnectionState::Disconnected,
             size: TerminalSize::default(),
+            io: None,
         }
     }
 
@@ -118,9 +124,9 @@ impl K8sBackend {
     )> {
         self.state = ConnectionState::Connecting;
 
-        // Create K8s client for the specific context
+        // Create K8s client for the specific context (empty = current context)
         let options = kube::config::KubeConfigOptions {
-            context: Some(self.session.context.clone()),
+            context: Some(self.session.context.clone()).filter(|c| !c.is_empty()),
             ..Default::default()
         };
         let config = Config::from_kubeconfig(&options).await?;
@@ -133,21 +139,9 @@ impl K8sBackend {
             K8sError::PodNotFound(self.session.namespace.clone(), self.session.pod.clone())
         })?;
 
-        // Set up attach parameters
-        let mut attach_params = AttachParams::interactive_tty();
-        if let Some(ref container) = self.session.container {
-            attach_params = attach_params.container(container);
-        }
-
-        // Command to exec - prefer bash over sh
-        let cmd = vec![
-            "/bin/sh",
-            "-c",
-            "command -v bash >/dev/null && exec bash || exec sh",
-        ];
-
         // Start exec
-        let mut attached = pods.exec(&self.session.pod, cmd, &attach_params).await?;
+        let attach_params = attach_params(&self.session);
+        let mut attached = pods.exec(&self.session.pod, exec_command(&self.session), &attach_params).await?;
 
         // Create channels for I/O
         let (write_tx, mut write_rx) = mpsc::channel::<Vec<u8>>(256);
@@ -206,3 +200,117 @@ impl K8sBackend {
         Ok((write_tx, read_rx, resize_tx))
     }
 }
+
+/// Attach parameters for a session: stdin and stdout, with a TTY unless the
+/// session turned it off
+fn attach_params(session: &K8sSession) -> AttachParams {
+    let mut params = AttachParams::interactive_tty().tty(session.tty);
+    if let Some(ref container) = session.container {
+        params = params.container(container);
+    }
+    params
+}
+
+/// The session's own command, or a shell preferring bash over sh
+fn exec_command(session: &K8sSession) -> Vec<String> {
+    match &session.command {
+        Some(command) if !command.is_empty() => command.clone(),
+        _ => vec![
+            "/bin/sh".to_string(),
+            "-c".to_string(),
+            "command -v bash >/dev/null && exec bash || exec sh".to_string(),
+        ],
+    }
+}
+
+#[async_trait]
+impl TerminalBackend for K8sBackend {
+    fn kind(&self) -> ConnectionKind {
+        ConnectionKind::K8s
+    }
+
+    async fn connect(&mut self, size: super::TerminalSize) -> Result<(), ConnectError> {
+        self.set_size(TerminalSize::new(size.cols, size.rows));
+        let (write_tx, read_rx, resize_tx) = K8sBackend::connect(self)
+            .await
+            .map_err(|e| ConnectError::new("Connection Failed", e))?;
+        tracing::info!("K8s pod exec connection established");
+        self.io = Some(K8sIo {
+            write_tx,
+            read_rx,
+            resize_tx,
+        });
+        Ok(())
+    }
+
+    fn io_channels(&mut self) -> Option<Box<dyn BackendIo>> {
+        self.io.take().map(|io| Box::new(io) as Box<dyn BackendIo>)
+    }
+
+    async fn close(&mut self) {
+        self.state = ConnectionState::Disconnected;
+    }
+}
+
+/// Channels to the pod exec task, driven by the shared I/O loop
+struct K8sIo {
+    write_tx: mpsc::Sender<Vec<u8>>,
+    read_rx: mpsc::Receiver<Vec<u8>>,
+    resize_tx: mpsc::Sender<TerminalSize>,
+}
+
+#[async_trait]
+impl BackendIo for K8sIo {
+    async fn read(&mut self) -> BackendRead {
+        match self.read_rx.recv().await {
+            Some(data) => BackendRead::Output(data),
+            None => BackendRead::Closed(None),
+        }
+    }
+
+    async fn write(&mut self, data: &[u8]) -> Result<(), String> {
+        self.write_tx
+            .send(data.to_vec())
+            .await
+            .map_err(|_| "K8s write channel closed".to_string())
+    }
+
+    async fn resize(&mut self, size: super::TerminalSize) -> Result<(), String> {
+        self.resize_tx
+            .send(TerminalSize::new(size.cols, size.rows))
+            .await
+            .map_err(|_| "K8s resize channel closed".to_string())
+    }
+
+    async fn close(&mut self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exec_command() {
+        let mut session = K8sSession::new("api", "prod", "default", "api-0");
+        assert_eq!(exec_command(&session)[0], "/bin/sh");
+
+        session.command = Some(vec![]);
+        assert_eq!(exec_command(&session)[0], "/bin/sh");
+
+        session.command = Some(vec!["redis-cli".to_string(), "-n".to_string(), "1".to_string()]);
+        assert_eq!(exec_command(&session), vec!["redis-cli", "-n", "1"]);
+    }
+
+    #[test]
+    fn test_attach_params_tty() {
+        let mut session = K8sSession::with_container("api", "prod", "default", "api-0", "app");
+        let params = attach_params(&session);
+        assert!(params.tty && params.stdin && params.stdout);
+        assert_eq!(params.container.as_deref(), Some("app"));
+
+        session.tty = false;
+        let params = attach_params(&session);
+        assert!(!params.tty);
+        assert!(params.stdin && params.stdout);
+    }
+}
@@ -0,0 +1,142 @@
+//! Kubernetes pod log viewer
+//!
+//! A read-only backend that streams a container's log into a terminal tab,
+//! like `kubectl logs [-f]`. The bytes are passed through untouched apart
+//! from line endings, so ANSI-colored logs render as they would in a shell.
+
+use std::pin::Pin;
+
+use async_trait::async_trait;
+use futures::{AsyncBufRead, AsyncReadExt};
+
+use crate::kubernetes::KubeClient;
+use crate::metrics::ConnectionKind;
+use super::backend::{BackendIo, BackendRead, ConnectError, TerminalBackend};
+use super::TerminalSize;
+
+/// Streams one container's log
+pub struct K8sLogsBackend {
+    context: String,
+    namespace: String,
+    pod: String,
+    /// Container to read; `None` for single-container pods
+    container: Option<String>,
+    /// Keep streaming new lines instead of stopping at the end
+    follow: bool,
+    /// Log stream opened by `connect`, until taken for I/O
+    stream: Option<LogStream>,
+}
+
+type LogStream = Pin<Box<dyn AsyncBufRead + Send>>;
+
+impl K8sLogsBackend {
+    pub fn new(context: &str, namespace: &str, pod: &str, container: Option<String>, follow: bool) -> Self {
+        Self {
+            context: context.to_string(),
+            namespace: namespace.to_string(),
+            pod: pod.to_string(),
+            container,
+            follow,
+            stream: None,
+        }
+    }
+}
+
+#[async_trait]
+impl TerminalBackend for K8sLogsBackend {
+    fn kind(&self) -> ConnectionKind {
+        ConnectionKind::K8s
+    }
+
+    async fn connect(&mut self, _size: TerminalSize) -> Result<(), ConnectError> {
+        let client = KubeClient::for_context(&self.context)
+            .await
+            .map_err(|e| ConnectError::new("Connection Failed", e))?;
+        let stream = client
+            .stream_logs(&self.namespace, &self.pod, self.container.as_deref(), self.follow)
+            .await
+            .map_err(|e| ConnectError::new("Could Not Read Logs", e))?;
+        tracing::info!("Streaming logs of {}/{}", self.namespace, self.pod);
+        self.stream = Some(Box::pin(stream));
+        Ok(())
+    }
+
+    fn io_channels(&mut self) -> Option<Box<dyn BackendIo>> {
+        let stream = self.stream.take()?;
+        Some(Box::new(LogsIo {
+            stream,
+            follow: self.follow,
+            buf: vec![0; 16 * 1024],
+            after_cr: false,
+        }))
+    }
+
+    async fn close(&mut self) {
+        self.stream = None;
+    }
+}
+
+/// A log stream driven by the shared I/O loop; input is ignored
+struct LogsIo {
+    stream: LogStream,
+    follow: bool,
+    buf: Vec<u8>,
+    /// The last byte shown was a carriage return
+    after_cr: bool,
+}
+
+#[async_trait]
+impl BackendIo for LogsIo {
+    async fn read(&mut self) -> BackendRead {
+        match self.stream.read(&mut self.buf).await {
+            Ok(0) if self.follow => BackendRead::Closed(Some("Log stream ended".into())),
+            Ok(0) => BackendRead::Closed(Some("End of log".into())),
+            Ok(n) => BackendRead::Output(crlf(&self.buf[..n], &mut self.after_cr)),
+            Err(e) => BackendRead::Failed(format!("Log stream error: {}", e)),
+        }
+    }
+
+    async fn write(&mut self, _data: &[u8]) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn resize(&mut self, _size: TerminalSize) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn close(&mut self) {}
+}
+
+/// Turn bare line feeds into CR LF, since there is no PTY to do it
+///
+/// `after_cr` carries whether the previous chunk ended in a carriage return,
+/// so a CR LF split across chunks isn't doubled.
+fn crlf(data: &[u8], after_cr: &mut bool) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() + data.len() / 32);
+    for &byte in data {
+        if byte == b'\n' && !*after_cr {
+            out.push(b'\r');
+        }
+        out.push(byte);
+        *after_cr = byte == b'\r';
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crlf() {
+        let mut after_cr = false;
+        assert_eq!(crlf(b"one\ntwo\r\n\x1b[31mred\x1b[0m\n", &mut after_cr), b"one\r\ntwo\r\n\x1b[31mred\x1b[0m\r\n");
+        assert!(!after_cr);
+
+        // A CR LF split across reads stays as it is
+        assert_eq!(crlf(b"three\r", &mut after_cr), b"three\r");
+        assert!(after_cr);
+        assert_eq!(crlf(b"\nfour", &mut after_cr), b"\nfour");
+        assert!(!after_cr);
+    }
+}
@@ -3,6 +3,97 @@ use std::borrow::Cow;
 use alacritty_terminal::term::TermMode;
 use gpui::Keystroke;
 
+use crate::session::NewlineMode;
+
+/// What a plain Enter sends for the session's newline mode
+fn enter_sequence(newline: NewlineMode, mode: &TermMode) -> &'static str {
+    match newline {
+        NewlineMode::Auto if mode.contains(TermMode::LINE_FEED_NEW_LINE) => "\r\n",
+        NewlineMode::Auto | NewlineMode::Cr => "\r",
+        NewlineMode::Lf => "\n",
+        NewlineMode::CrLf => "\r\n",
+    }
+}
+
+/// Text typed or committed by an input method, ready to send
+///
+/// Input methods deliver whole grapheme clusters (ZWJ emoji, flags, letters
+/// with combining accents), and the result is meant to be written in one go
+/// so the remote never sees a cluster split across writes. Control
+/// characters other than tab are dropped; those come from key events.
+pub fn text_input(text: &str) -> Option<Cow<'_, str>> {
+    let keep = |c: char| !c.is_control() || c == '\t';
+    let text = if text.chars().all(keep) {
+        Cow::Borrowed(text)
+    } else {
+        Cow::Owned(text.chars().filter(|&c| keep(c)).collect())
+    };
+    (!text.is_empty()).then_some(text)
+}
+
+/// Name of a control key that interrupts, ends or suspends the foreground job
+///
+/// Used to ask before such a key goes to several sessions at once. Only a
+/// write consisting of just the control byte counts, so pastes that happen to
+/// contain one are not flagged.
+pub fn disruptive_control_key(bytes: &[u8]) -> Option<&'static str> {
+    match bytes {
+        [0x03] => Some("Ctrl-C"),
+        [0x04] => Some("Ctrl-D"),
+        [0x1a] => Some("Ctrl-Z"),
+        _ => None,
+    }
+}
+
+/// Pasted text, wrapped in bracketed-paste markers when the application asked for them
+pub fn paste_sequence<'a>(text: &'a str, mode: &TermMode) -> Cow<'a, str> {
+    if mode.contains(TermMode::BRACKETED_PASTE) {
+        Cow::Owned(format!("\x1b[200~{}\x1b[201~", text))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Numpad keys: name, what the key types, and what it sends in application
+/// keypad mode (DECKPAM)
+///
+/// Numpad Enter is handled separately since it follows the newline mode.
+const KEYPAD_KEYS: [(&str, &str, &str); 16] = [
+    ("numpad0", "0", "\x1bOp"),
+    ("numpad1", "1", "\x1bOq"),
+    ("numpad2", "2", "\x1bOr"),
+    ("numpad3", "3", "\x1bOs"),
+    ("numpad4", "4", "\x1bOt"),
+    ("numpad5", "5", "\x1bOu"),
+    ("numpad6", "6", "\x1bOv"),
+    ("numpad7", "7", "\x1bOw"),
+    ("numpad8", "8", "\x1bOx"),
+    ("numpad9", "9", "\x1bOy"),
+    ("numpadadd", "+", "\x1bOk"),
+    ("numpadsubtract", "-", "\x1bOm"),
+    ("numpadmultiply", "*", "\x1bOj"),
+    ("numpaddivide", "/", "\x1bOo"),
+    ("numpaddecimal", ".", "\x1bOn"),
+    ("numpadequal", "=", "\x1bOX"),
+];
+
+/// What an unmodified numpad key sends
+///
+/// Plain characters normally; SS3 sequences once the application has
+/// switched the keypad to application mode. Only applies when the platform
+/// reports numpad keys under their own names; otherwise they arrive as the
+/// characters they type.
+fn keypad_sequence(key: &str, mode: &TermMode, newline: NewlineMode) -> Option<&'static str> {
+    let app_keypad = mode.contains(TermMode::APP_KEYPAD);
+    if key == "numpadenter" {
+        return Some(if app_keypad { "\x1bOM" } else { enter_sequence(newline, mode) });
+    }
+    KEYPAD_KEYS
+        .iter()
+        .find(|(name, _, _)| *name == key)
+        .map(|&(_, text, app)| if app_keypad { app } else { text })
+}
+
 #[derive(Debug, PartialEq, Eq)]
 enum Modifiers {
     None,
@@ -43,24 +134,32 @@ impl Modifiers {
 
 /// Convert a keystroke to terminal escape sequence
 /// This function is terminal mode aware - arrow keys and other special keys
-/// send different sequences depending on whether APP_CURSOR mode is active
+/// send different sequences depending on whether APP_CURSOR mode is active.
+/// `newline` is the session's choice of what a plain Enter sends.
 pub fn keystroke_to_escape(
     keystroke: &Keystroke,
     mode: &TermMode,
     option_as_meta: bool,
+    newline: NewlineMode,
 ) -> Option<Cow<'static, str>> {
     // Debug logging for key events
     eprintln!("[KEY] key='{}' modifiers={:?}", keystroke.key, keystroke.modifiers);
 
     let modifiers = Modifiers::from_keystroke(keystroke);
 
+    if !modifiers.has_any() {
+        if let Some(sequence) = keypad_sequence(&keystroke.key, mode, newline) {
+            return Some(Cow::Borrowed(sequence));
+        }
+    }
+
     // Handle special keys with specific modifier combinations
     let special_key_result: Option<&'static str> = match (keystroke.key.as_ref(), &modifiers) {
         // Basic keys
         ("tab", Modifiers::None) => Some("\x09"),
         ("tab", Modifiers::Shift) => Some("\x1b[Z"),
         ("escape", Modifiers::None) => Some("\x1b"),
-        ("enter", Modifiers::None) => Some("\x0d"),
+        ("enter", Modifiers::None) => Some(enter_sequence(newline, mode)),
         ("enter", Modifiers::Shift) => Some("\x0a"),
         ("enter", Modifiers::Alt) => Some("\x1b\x0d"),
         ("backspace", Modifiers::None) => Some("\x7f"),
@@ -269,23 +368,28 @@ mod tests {
         }
     }
 
+    /// Bytes sent for a key, with Alt acting as Meta
+    fn send(key: &str, ctrl: bool, alt: bool, shift: bool, mode: TermMode) -> Option<String> {
+        keystroke_to_escape(&make_keystroke(key, ctrl, alt, shift), &mode, true, NewlineMode::Auto).map(Cow::into_owned)
+    }
+
     #[test]
     fn test_arrow_keys_normal_mode() {
         let mode = TermMode::NONE;
         assert_eq!(
-            keystroke_to_escape(&make_keystroke("up", false, false, false), &mode, false),
+            keystroke_to_escape(&make_keystroke("up", false, false, false), &mode, false, NewlineMode::Auto),
             Some(Cow::Borrowed("\x1b[A"))
         );
         assert_eq!(
-            keystroke_to_escape(&make_keystroke("down", false, false, false), &mode, false),
+            keystroke_to_escape(&make_keystroke("down", false, false, false), &mode, false, NewlineMode::Auto),
             Some(Cow::Borrowed("\x1b[B"))
         );
         assert_eq!(
-            keystroke_to_escape(&make_keystroke("right", false, false, false), &mode, false),
+            keystroke_to_escape(&make_keystroke("right", false, false, false), &mode, false, NewlineMode::Auto),
             Some(Cow::Borrowed("\x1b[C"))
         );
         assert_eq!(
-            keystroke_to_escape(&make_keystroke("left", false, false, false), &mode, false),
+            keystroke_to_escape(&make_keystroke("left", false, false, false), &mode, false, NewlineMode::Auto),
             Some(Cow::Borrowed("\x1b[D"))
         );
     }
@@ -294,28 +398,172 @@ mod tests {
     fn test_arrow_keys_app_cursor_mode() {
         let mode = TermMode::APP_CURSOR;
         assert_eq!(
-            keystroke_to_escape(&make_keystroke("up", false, false, false), &mode, false),
+            keystroke_to_escape(&make_keystroke("up", false, false, false), &mode, false, NewlineMode::Auto),
             Some(Cow::Borrowed("\x1bOA"))
         );
         assert_eq!(
-            keystroke_to_escape(&make_keystroke("down", false, false, false), &mode, false),
+            keystroke_to_escape(&make_keystroke("down", false, false, false), &mode, false, NewlineMode::Auto),
             Some(Cow::Borrowed("\x1bOB"))
         );
         assert_eq!(
-            keystroke_to_escape(&make_keystroke("right", false, false, false), &mode, false),
+            keystroke_to_escape(&make_keystroke("right", false, false, false), &mode, false, NewlineMode::Auto),
             Some(Cow::Borrowed("\x1bOC"))
         );
         assert_eq!(
-            keystroke_to_escape(&make_keystroke("left", false, false, false), &mode, false),
+            keystroke_to_escape(&make_keystroke("left", false, false, false), &mode, false, NewlineMode::Auto),
             Some(Cow::Borrowed("\x1bOD"))
         );
     }
 
+    #[test]
+    fn test_modified_arrows_ignore_cursor_mode() {
+        for mode in [TermMode::NONE, TermMode::APP_CURSOR] {
+            assert_eq!(send("up", false, false, true, mode).as_deref(), Some("\x1b[1;2A"));
+            assert_eq!(send("down", false, true, false, mode).as_deref(), Some("\x1b[1;3B"));
+            assert_eq!(send("right", true, false, false, mode).as_deref(), Some("\x1b[1;5C"));
+            assert_eq!(send("left", true, false, true, mode).as_deref(), Some("\x1b[1;6D"));
+            assert_eq!(send("up", true, true, false, mode).as_deref(), Some("\x1b[1;7A"));
+        }
+    }
+
+    #[test]
+    fn test_navigation_keys() {
+        for (key, normal, app_cursor) in [
+            ("home", "\x1b[H", "\x1bOH"),
+            ("end", "\x1b[F", "\x1bOF"),
+            ("insert", "\x1b[2~", "\x1b[2~"),
+            ("delete", "\x1b[3~", "\x1b[3~"),
+            ("pageup", "\x1b[5~", "\x1b[5~"),
+            ("pagedown", "\x1b[6~", "\x1b[6~"),
+        ] {
+            assert_eq!(send(key, false, false, false, TermMode::NONE).as_deref(), Some(normal), "{key}");
+            assert_eq!(send(key, false, false, false, TermMode::APP_CURSOR).as_deref(), Some(app_cursor), "{key}");
+        }
+
+        assert_eq!(send("home", true, false, false, TermMode::NONE).as_deref(), Some("\x1b[1;5H"));
+        assert_eq!(send("end", true, false, false, TermMode::NONE).as_deref(), Some("\x1b[1;5F"));
+        assert_eq!(send("delete", true, false, false, TermMode::NONE).as_deref(), Some("\x1b[3;5~"));
+        assert_eq!(send("pagedown", false, true, false, TermMode::NONE).as_deref(), Some("\x1b[6;3~"));
+    }
+
+    #[test]
+    fn test_shift_navigation_only_in_alt_screen() {
+        // In the main screen Shift+PageUp and friends are left for scrollback
+        for key in ["home", "end", "pageup", "pagedown"] {
+            assert_eq!(send(key, false, false, true, TermMode::NONE), None, "{key}");
+        }
+        let alt = TermMode::ALT_SCREEN;
+        assert_eq!(send("home", false, false, true, alt).as_deref(), Some("\x1b[1;2H"));
+        assert_eq!(send("end", false, false, true, alt).as_deref(), Some("\x1b[1;2F"));
+        assert_eq!(send("pageup", false, false, true, alt).as_deref(), Some("\x1b[5;2~"));
+        assert_eq!(send("pagedown", false, false, true, alt).as_deref(), Some("\x1b[6;2~"));
+    }
+
+    #[test]
+    fn test_function_keys() {
+        for (key, bytes) in [
+            ("f1", "\x1bOP"),
+            ("f2", "\x1bOQ"),
+            ("f3", "\x1bOR"),
+            ("f4", "\x1bOS"),
+            ("f5", "\x1b[15~"),
+            ("f6", "\x1b[17~"),
+            ("f7", "\x1b[18~"),
+            ("f8", "\x1b[19~"),
+            ("f9", "\x1b[20~"),
+            ("f10", "\x1b[21~"),
+            ("f11", "\x1b[23~"),
+            ("f12", "\x1b[24~"),
+        ] {
+            assert_eq!(send(key, false, false, false, TermMode::NONE).as_deref(), Some(bytes), "{key}");
+            assert_eq!(send(key, false, false, false, TermMode::APP_CURSOR).as_deref(), Some(bytes), "{key}");
+        }
+
+        assert_eq!(send("f1", false, false, true, TermMode::NONE).as_deref(), Some("\x1b[1;2P"));
+        assert_eq!(send("f4", true, false, false, TermMode::NONE).as_deref(), Some("\x1b[1;5S"));
+        assert_eq!(send("f5", true, false, false, TermMode::NONE).as_deref(), Some("\x1b[15;5~"));
+        assert_eq!(send("f12", true, true, true, TermMode::NONE).as_deref(), Some("\x1b[24;8~"));
+    }
+
+    #[test]
+    fn test_ctrl_keys() {
+        let ctrl = |key: &str| send(key, true, false, false, TermMode::NONE);
+        assert_eq!(ctrl("a").as_deref(), Some("\x01"));
+        assert_eq!(ctrl("z").as_deref(), Some("\x1a"));
+        assert_eq!(ctrl("space").as_deref(), Some("\x00"));
+        assert_eq!(ctrl("@").as_deref(), Some("\x00"));
+        assert_eq!(ctrl("[").as_deref(), Some("\x1b"));
+        assert_eq!(ctrl("\\").as_deref(), Some("\x1c"));
+        assert_eq!(ctrl("]").as_deref(), Some("\x1d"));
+        assert_eq!(ctrl("_").as_deref(), Some("\x1f"));
+        assert_eq!(ctrl("?").as_deref(), Some("\x7f"));
+        assert_eq!(ctrl("backspace").as_deref(), Some("\x08"));
+        assert_eq!(send("c", true, false, false, TermMode::APP_CURSOR).as_deref(), Some("\x03"));
+    }
+
+    #[test]
+    fn test_alt_as_meta() {
+        let mode = TermMode::NONE;
+        assert_eq!(send("x", false, true, false, mode).as_deref(), Some("\x1bx"));
+        assert_eq!(send("x", false, true, true, mode).as_deref(), Some("\x1bX"));
+        assert_eq!(send(".", false, true, false, mode).as_deref(), Some("\x1b."));
+        assert_eq!(send("backspace", false, true, false, mode).as_deref(), Some("\x1b\x7f"));
+        assert_eq!(send("enter", false, true, false, mode).as_deref(), Some("\x1b\r"));
+
+        // On macOS Option composes characters unless it's set to act as Meta
+        let alt_x = keystroke_to_escape(&make_keystroke("x", false, true, false), &mode, false, NewlineMode::Auto);
+        if cfg!(target_os = "macos") {
+            assert_eq!(alt_x, None);
+        } else {
+            assert_eq!(alt_x.as_deref(), Some("\x1bx"));
+        }
+
+        // Unmodified printable keys are text, which comes from the input handler
+        assert_eq!(send("x", false, false, false, mode), None);
+    }
+
+    #[test]
+    fn test_tab_escape_and_shifted_keys() {
+        let mode = TermMode::NONE;
+        assert_eq!(send("tab", false, false, false, mode).as_deref(), Some("\t"));
+        assert_eq!(send("tab", false, false, true, mode).as_deref(), Some("\x1b[Z"));
+        assert_eq!(send("escape", false, false, false, mode).as_deref(), Some("\x1b"));
+        assert_eq!(send("enter", false, false, true, mode).as_deref(), Some("\n"));
+        assert_eq!(send("backspace", false, false, true, mode).as_deref(), Some("\x7f"));
+    }
+
+    #[test]
+    fn test_numpad_keys() {
+        let normal = TermMode::NONE;
+        let app = TermMode::APP_KEYPAD;
+        assert_eq!(send("numpadenter", false, false, false, normal).as_deref(), Some("\r"));
+        assert_eq!(send("numpadenter", false, false, false, app).as_deref(), Some("\x1bOM"));
+        assert_eq!(send("numpadadd", false, false, false, normal).as_deref(), Some("+"));
+        assert_eq!(send("numpadadd", false, false, false, app).as_deref(), Some("\x1bOk"));
+        assert_eq!(send("numpadsubtract", false, false, false, normal).as_deref(), Some("-"));
+        assert_eq!(send("numpadsubtract", false, false, false, app).as_deref(), Some("\x1bOm"));
+        for digit in 0..10u8 {
+            let key = format!("numpad{digit}");
+            let app_sequence = format!("\x1bO{}", (b'p' + digit) as char);
+            assert_eq!(send(&key, false, false, false, normal), Some(digit.to_string()));
+            assert_eq!(send(&key, false, false, false, app), Some(app_sequence));
+        }
+
+        // Numpad Enter follows the newline mode outside application keypad mode
+        let enter = make_keystroke("numpadenter", false, false, false);
+        assert_eq!(
+            keystroke_to_escape(&enter, &normal, false, NewlineMode::CrLf).as_deref(),
+            Some("\r\n")
+        );
+        // The top-row keys are unaffected
+        assert_eq!(send("enter", false, false, false, app).as_deref(), Some("\r"));
+    }
+
     #[test]
     fn test_ctrl_c() {
         let mode = TermMode::NONE;
         assert_eq!(
-            keystroke_to_escape(&make_keystroke("c", true, false, false), &mode, false),
+            keystroke_to_escape(&make_keystroke("c", true, false, false), &mode, false, NewlineMode::Auto),
             Some(Cow::Borrowed("\x03"))
         );
     }
@@ -324,15 +572,54 @@ mod tests {
     fn test_enter_and_backspace() {
         let mode = TermMode::NONE;
         assert_eq!(
-            keystroke_to_escape(&make_keystroke("enter", false, false, false), &mode, false),
+            keystroke_to_escape(&make_keystroke("enter", false, false, false), &mode, false, NewlineMode::Auto),
             Some(Cow::Borrowed("\x0d"))
         );
         assert_eq!(
-            keystroke_to_escape(&make_keystroke("backspace", false, false, false), &mode, false),
+            keystroke_to_escape(&make_keystroke("backspace", false, false, false), &mode, false, NewlineMode::Auto),
             Some(Cow::Borrowed("\x7f"))
         );
     }
 
+    #[test]
+    fn test_enter_newline_modes() {
+        let enter = make_keystroke("enter", false, false, false);
+        let send = |mode: TermMode, newline| keystroke_to_escape(&enter, &mode, false, newline);
+        assert_eq!(send(TermMode::LINE_FEED_NEW_LINE, NewlineMode::Auto), Some(Cow::Borrowed("\r\n")));
+        assert_eq!(send(TermMode::NONE, NewlineMode::Cr), Some(Cow::Borrowed("\r")));
+        assert_eq!(send(TermMode::LINE_FEED_NEW_LINE, NewlineMode::Cr), Some(Cow::Borrowed("\r")));
+        assert_eq!(send(TermMode::NONE, NewlineMode::Lf), Some(Cow::Borrowed("\n")));
+        assert_eq!(send(TermMode::NONE, NewlineMode::CrLf), Some(Cow::Borrowed("\r\n")));
+    }
+
+    #[test]
+    fn test_grapheme_clusters_stay_whole() {
+        let flag = "\u{1F1EF}\u{1F1F5}";
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        let accented = "e\u{301}";
+        for text in [flag, family, accented] {
+            assert_eq!(text_input(text), Some(Cow::Borrowed(text)));
+            assert_eq!(paste_sequence(text, &TermMode::NONE), Cow::Borrowed(text));
+        }
+        assert_eq!(
+            paste_sequence(accented, &TermMode::BRACKETED_PASTE),
+            Cow::<str>::Owned(format!("\x1b[200~{}\x1b[201~", accented))
+        );
+        assert_eq!(text_input("a\u{7}\tb"), Some(Cow::Owned("a\tb".to_string())));
+        assert_eq!(text_input("\u{1b}"), None);
+    }
+
+    #[test]
+    fn test_disruptive_control_key() {
+        let mode = TermMode::NONE;
+        let ctrl = |key: &str| keystroke_to_escape(&make_keystroke(key, true, false, false), &mode, false, NewlineMode::Auto).unwrap();
+        assert_eq!(disruptive_control_key(ctrl("c").as_bytes()), Some("Ctrl-C"));
+        assert_eq!(disruptive_control_key(ctrl("d").as_bytes()), Some("Ctrl-D"));
+        assert_eq!(disruptive_control_key(ctrl("z").as_bytes()), Some("Ctrl-Z"));
+        assert_eq!(disruptive_control_key(ctrl("a").as_bytes()), None);
+        assert_eq!(disruptive_control_key(b"ls\x03"), None);
+    }
+
     #[test]
     fn test_modifier_code() {
         assert_eq!(compute_modifier_code(&make_keystroke("a", false, false, true)), 2);  // Shift
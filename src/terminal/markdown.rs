@@ -0,0 +1,97 @@
+//! Terminal text as Markdown, for pasting into chats and tickets
+
+/// Wrap `text` in a fenced code block
+///
+/// Escape sequences, other control characters and trailing whitespace are
+/// stripped, as are blank lines at either end. The fence is made longer than
+/// any run of backticks in the text so it can't be closed early. `header`,
+/// if given, goes on its own line above the block.
+pub fn markdown_code_block(text: &str, header: Option<&str>) -> String {
+    let cleaned = strip_escapes(text);
+    let lines: Vec<&str> = cleaned.lines().map(str::trim_end).collect();
+    let start = lines.iter().position(|line| !line.is_empty()).unwrap_or(lines.len());
+    let end = lines.iter().rposition(|line| !line.is_empty()).map_or(start, |i| i + 1);
+    let body = lines[start..end].join("\n");
+
+    let longest_run = body
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+
+    let mut block = String::new();
+    if let Some(header) = header {
+        block.push_str(header);
+        block.push('\n');
+    }
+    block.push_str(&fence);
+    block.push('\n');
+    if !body.is_empty() {
+        block.push_str(&body);
+        block.push('\n');
+    }
+    block.push_str(&fence);
+    block.push('\n');
+    block
+}
+
+/// Drop CSI and OSC sequences and control characters other than newline and tab
+fn strip_escapes(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                // CSI: parameters, then a final byte in @..~
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC: ends at BEL or ST (ESC \)
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' {
+                            break;
+                        }
+                        if c == '\x1b' && chars.peek() == Some(&'\\') {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                // Character set selection, e.g. ESC ( B
+                Some('(' | ')' | '*' | '+') => {
+                    chars.next();
+                }
+                // Other two-character sequences like ESC =
+                _ => {}
+            },
+            '\n' | '\t' => out.push(c),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_code_block() {
+        let text = "\n\x1b[1;32mok\x1b[0m   \r\n\x1b]0;title\x07\x1b(Bline two\t\n\n";
+        assert_eq!(markdown_code_block(text, None), "```\nok\nline two\n```\n");
+        assert_eq!(
+            markdown_code_block("up 3 days", Some("web-1 $ uptime")),
+            "web-1 $ uptime\n```\nup 3 days\n```\n"
+        );
+        // A fence inside the text needs a longer one around it
+        assert_eq!(markdown_code_block("```rust\n```", None), "````\n```rust\n```\n````\n");
+        assert_eq!(markdown_code_block("  \n", None), "```\n```\n");
+    }
+}
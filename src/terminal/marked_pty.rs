@@ -0,0 +1,328 @@
+//! Local PTY that catches OSC 133 marks on their way to the parser
+//!
+//! Local output is read and parsed by alacritty's event loop, which ignores
+//! the marks. The loop reads through this wrapper instead: each read stops
+//! just past a mark, and once the loop has parsed up to there the mark is
+//! recorded against the cursor, as `Terminal::write_to_pty` does for remote
+//! output.
+//!
+//! This leans on how the loop reads. When a read would block it parses what
+//! it has, and it only reads into its whole buffer when nothing is left
+//! unparsed; a read that would block at that point ends its pass and
+//! releases the terminal. The mark is recorded at the start of the next
+//! pass, which a wake-up source registered next to the PTY brings on even
+//! if the shell has gone quiet.
+//!
+//! None of that is a public contract, so alacritty_terminal is pinned to an
+//! exact version in Cargo.toml.
+
+use std::collections::VecDeque;
+use std::io::{self, Read};
+use std::sync::Arc;
+
+use alacritty_terminal::event::{OnResize, WindowSize};
+use alacritty_terminal::sync::FairMutex;
+use alacritty_terminal::term::Term;
+use alacritty_terminal::tty::{ChildEvent, EventedPty, EventedReadWrite};
+use polling::{Event, PollMode, Poller};
+
+use super::events::TerminalEventSender;
+use super::shell_marks::ShellMark;
+use super::terminal::ShellMarks;
+
+/// A PTY whose output is scanned for shell marks before alacritty parses it
+pub(crate) struct MarkedPty<P> {
+    pty: P,
+    term: Arc<FairMutex<Term<TerminalEventSender>>>,
+    marks: Arc<ShellMarks>,
+    /// Output read from the PTY but held back because it follows a mark
+    held: Vec<u8>,
+    /// Marks in `held`, with offsets relative to its start
+    held_marks: VecDeque<(usize, ShellMark)>,
+    /// Mark ending the output last handed over, not recorded yet
+    unrecorded: Option<ShellMark>,
+    /// The loop was sent to release the terminal; its next read records
+    released: bool,
+    /// Size of the loop's read buffer, which it reads into whole once
+    /// everything before has been parsed
+    full_read: usize,
+    waker: Waker,
+}
+
+impl<P: EventedReadWrite> MarkedPty<P> {
+    pub(crate) fn new(
+        pty: P,
+        term: Arc<FairMutex<Term<TerminalEventSender>>>,
+        marks: Arc<ShellMarks>,
+    ) -> io::Result<Self> {
+        Ok(Self {
+            pty,
+            term,
+            marks,
+            held: Vec::new(),
+            held_marks: VecDeque::new(),
+            unrecorded: None,
+            released: false,
+            full_read: 0,
+            waker: Waker::new()?,
+        })
+    }
+
+    /// Read fresh output, or what's held back, into `buf`
+    ///
+    /// Stops just past the first mark, which becomes `unrecorded`.
+    fn read_output(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.held.is_empty() {
+            let len = self.pty.reader().read(buf)?;
+            let mut marks = self.marks.scan(&buf[..len]).into_iter();
+            let Some((end, mark)) = marks.next() else {
+                return Ok(len);
+            };
+            self.held.extend_from_slice(&buf[end..len]);
+            self.held_marks.extend(marks.map(|(offset, mark)| (offset - end, mark)));
+            self.unrecorded = Some(mark);
+            return Ok(end);
+        }
+
+        let mut len = self.held.len().min(buf.len());
+        if let Some(&(end, mark)) = self.held_marks.front() {
+            if end <= len {
+                len = end;
+                self.unrecorded = Some(mark);
+                self.held_marks.pop_front();
+            }
+        }
+        buf[..len].copy_from_slice(&self.held[..len]);
+        self.held.drain(..len);
+        for (offset, _) in &mut self.held_marks {
+            *offset -= len;
+        }
+        Ok(len)
+    }
+}
+
+impl<P: EventedReadWrite> Read for MarkedPty<P> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.full_read = self.full_read.max(buf.len());
+
+        if let Some(mark) = self.unrecorded {
+            if !self.released {
+                // Have the loop parse up to the mark. With its whole buffer
+                // free it has, but it holds the terminal until this pass ends.
+                if buf.len() == self.full_read {
+                    self.released = true;
+                    self.waker.wake();
+                }
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+            self.marks.record(&self.term.lock_unfair(), mark);
+            self.unrecorded = None;
+            self.released = false;
+            self.waker.reset();
+        }
+
+        self.read_output(buf)
+    }
+}
+
+impl<P: EventedReadWrite> EventedReadWrite for MarkedPty<P> {
+    type Reader = Self;
+    type Writer = P::Writer;
+
+    unsafe fn register(&mut self, poll: &Arc<Poller>, interest: Event, mode: PollMode) -> io::Result<()> {
+        unsafe {
+            self.pty.register(poll, interest, mode)?;
+            self.waker.register(poll)
+        }
+    }
+
+    fn reregister(&mut self, poll: &Arc<Poller>, interest: Event, mode: PollMode) -> io::Result<()> {
+        self.pty.reregister(poll, interest, mode)
+    }
+
+    fn deregister(&mut self, poll: &Arc<Poller>) -> io::Result<()> {
+        self.waker.deregister(poll);
+        self.pty.deregister(poll)
+    }
+
+    fn reader(&mut self) -> &mut Self {
+        self
+    }
+
+    fn writer(&mut self) -> &mut P::Writer {
+        self.pty.writer()
+    }
+}
+
+impl<P: EventedPty> EventedPty for MarkedPty<P> {
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        self.pty.next_child_event()
+    }
+}
+
+impl<P: OnResize> OnResize for MarkedPty<P> {
+    fn on_resize(&mut self, window_size: WindowSize) {
+        self.pty.on_resize(window_size);
+    }
+}
+
+/// Event key alacritty reads the PTY under (not exported on unix; checked
+/// against 0.25.1, which Cargo.toml pins)
+#[cfg(unix)]
+const PTY_READ_WRITE_TOKEN: usize = 0;
+
+/// Makes the event loop read again when there's nothing new on the PTY
+///
+/// Readable under the PTY's own token while woken, so the loop can't tell it
+/// from output.
+#[cfg(unix)]
+struct Waker {
+    rx: std::os::unix::net::UnixStream,
+    tx: std::os::unix::net::UnixStream,
+    awake: bool,
+}
+
+#[cfg(unix)]
+impl Waker {
+    fn new() -> io::Result<Self> {
+        let (rx, tx) = std::os::unix::net::UnixStream::pair()?;
+        rx.set_nonblocking(true)?;
+        tx.set_nonblocking(true)?;
+        Ok(Self { rx, tx, awake: false })
+    }
+
+    /// # Safety
+    ///
+    /// Must be deregistered before it is dropped.
+    unsafe fn register(&mut self, poll: &Poller) -> io::Result<()> {
+        let interest = Event::readable(PTY_READ_WRITE_TOKEN);
+        unsafe { poll.add_with_mode(&self.rx, interest, PollMode::Level) }
+    }
+
+    fn deregister(&mut self, poll: &Poller) {
+        let _ = poll.delete(&self.rx);
+    }
+
+    fn wake(&mut self) {
+        if !self.awake {
+            self.awake = std::io::Write::write(&mut &self.tx, &[1]).is_ok();
+        }
+    }
+
+    fn reset(&mut self) {
+        if std::mem::take(&mut self.awake) {
+            let _ = (&self.rx).read(&mut [0; 8]);
+        }
+    }
+}
+
+/// Makes the event loop read again when there's nothing new on the PTY
+///
+/// Posts a readable event under the PTY's own token, so the loop can't tell
+/// it from output.
+#[cfg(windows)]
+struct Waker {
+    poller: Option<Arc<Poller>>,
+}
+
+#[cfg(windows)]
+impl Waker {
+    fn new() -> io::Result<Self> {
+        Ok(Self { poller: None })
+    }
+
+    unsafe fn register(&mut self, poll: &Arc<Poller>) -> io::Result<()> {
+        self.poller = Some(poll.clone());
+        Ok(())
+    }
+
+    fn deregister(&mut self, _poll: &Poller) {
+        self.poller = None;
+    }
+
+    fn wake(&mut self) {
+        use polling::os::iocp::{CompletionPacket, PollerIocpExt};
+
+        if let Some(poller) = &self.poller {
+            let event = Event::readable(alacritty_terminal::tty::PTY_READ_WRITE_TOKEN);
+            let _ = poller.post(CompletionPacket::new(event));
+        }
+    }
+
+    fn reset(&mut self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::command_regions::GridPos;
+    use crate::terminal::events::event_channel;
+    use crate::terminal::terminal::SizeInfo;
+    use alacritty_terminal::term::Config as TermConfig;
+    use alacritty_terminal::vte::ansi::{Processor, StdSyncHandler};
+    use std::io::Cursor;
+
+    /// Output already sitting in the PTY
+    struct FakePty {
+        output: Cursor<Vec<u8>>,
+        input: Vec<u8>,
+    }
+
+    impl EventedReadWrite for FakePty {
+        type Reader = Cursor<Vec<u8>>;
+        type Writer = Vec<u8>;
+
+        unsafe fn register(&mut self, _: &Arc<Poller>, _: Event, _: PollMode) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn reregister(&mut self, _: &Arc<Poller>, _: Event, _: PollMode) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn deregister(&mut self, _: &Arc<Poller>) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn reader(&mut self) -> &mut Self::Reader {
+            &mut self.output
+        }
+
+        fn writer(&mut self) -> &mut Self::Writer {
+            &mut self.input
+        }
+    }
+
+    #[test]
+    fn test_marks_recorded_where_they_were_parsed() {
+        let (event_tx, _events) = event_channel();
+        let term = Term::new(TermConfig::default(), &SizeInfo::new(40, 10), event_tx.clone());
+        let term = Arc::new(FairMutex::new(term));
+        let marks = Arc::new(ShellMarks::new(event_tx));
+        let output = b"\x1b]133;A\x07$ \x1b]133;B\x07ls\r\n\x1b]133;C\x07a b\r\n\x1b]133;D;0\x07".to_vec();
+        let fake = FakePty { output: Cursor::new(output), input: Vec::new() };
+        let mut pty = MarkedPty::new(fake, term.clone(), marks.clone()).unwrap();
+
+        // Read and parse the way alacritty's event loop does
+        let mut parser: Processor<StdSyncHandler> = Processor::new();
+        let mut buf = [0u8; 1024];
+        let mut blocked = 0;
+        loop {
+            match pty.read(&mut buf) {
+                Ok(0) => break,
+                Ok(len) => parser.advance(&mut *term.lock(), &buf[..len]),
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => blocked += 1,
+                Err(err) => panic!("{}", err),
+            }
+        }
+
+        // Each mark waited for its pass to end
+        assert_eq!(blocked, 4);
+        let regions = marks.regions.lock();
+        let region = regions.finished().next().unwrap();
+        assert_eq!(region.command, GridPos { line: 0, column: 2 });
+        assert_eq!(region.output, Some(GridPos { line: 1, column: 0 }));
+        assert_eq!(region.end, Some(GridPos { line: 2, column: 0 }));
+        assert_eq!(region.exit_code, Some(0));
+    }
+}
@@ -1,13 +1,45 @@
+pub mod ambiguous_width;
+pub mod auth_prompt;
+pub mod backend;
+pub mod command_regions;
 pub mod events;
 pub mod k8s_backend;
+pub mod k8s_logs;
 pub mod keys;
+pub mod markdown;
+pub mod marked_pty;
+pub mod precheck;
+pub mod recorder;
+#[cfg(feature = "serial")]
+pub mod serial_backend;
+pub mod shell_marks;
 pub mod ssh_backend;
+pub mod ssh_forward;
+pub mod ssh_pool;
 pub mod ssm_backend;
+pub mod sudo_prompt;
+#[cfg(feature = "telnet")]
+pub mod telnet_backend;
 pub mod terminal;
+pub mod urls;
+pub mod zmodem;
 
+pub use auth_prompt::{AuthPromptRequest, AuthPrompter, HostKeyRequest, LineEvent, LineInput};
+pub use backend::{BackendIo, BackendRead, ConnectError, TerminalBackend};
+pub use command_regions::CommandOutput;
 pub use events::{event_channel, TerminalEvent, TerminalEventSender};
 pub use k8s_backend::{K8sBackend, K8sError};
-pub use keys::keystroke_to_escape;
-pub use ssh_backend::SshBackend;
+pub use k8s_logs::K8sLogsBackend;
+pub use keys::{disruptive_control_key, keystroke_to_escape, paste_sequence, text_input};
+pub use markdown::markdown_code_block;
+pub use recorder::SessionRecorder;
+#[cfg(feature = "serial")]
+pub use serial_backend::SerialBackend;
+pub use shell_marks::{ShellMark, ShellMarkScanner};
+pub use ssh_backend::{exec_results_table, remove_host_key, ExecOutput, SshBackend, SshConnectionPool};
+pub use ssh_forward::{ForwardState, PortForwards};
 pub use ssm_backend::{SsmBackend, SsmError, SsmMessageBuilder, SsmWebSocket, connect_websocket, handle_ssm_message};
-pub use terminal::{IndexedCell, Terminal, TerminalConfig, TerminalContent, TerminalSize};
+#[cfg(feature = "telnet")]
+pub use telnet_backend::TelnetBackend;
+pub use terminal::{ConnectionStatus, IndexedCell, Terminal, TerminalConfig, TerminalContent, TerminalSize};
+pub use zmodem::ZmodemDirection;
@@ -0,0 +1,127 @@
+//! Fast reachability checks before a connection handshake
+//!
+//! A host that is down otherwise shows up as a generic timeout once the full
+//! handshake timeout expires. Resolving the name and opening the TCP
+//! connection separately, with short timeouts, fails in about a second and
+//! says which step went wrong. The connected stream is handed on to the
+//! handshake, so the host only sees one connection.
+
+use std::io;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use thiserror::Error;
+use tokio::net::TcpStream;
+
+/// How long name resolution may take
+pub const DNS_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long each resolved address gets to accept the TCP connection
+pub const TCP_PROBE_TIMEOUT: Duration = Duration::from_millis(900);
+
+/// Why a host could not be reached
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PrecheckError {
+    #[error("DNS resolution failed for {0}: {1}")]
+    DnsFailed(String, String),
+
+    #[error("DNS resolution for {0} timed out")]
+    DnsTimeout(String),
+
+    #[error("Connection refused by {0} (is the service running on that port?)")]
+    ConnectionRefused(SocketAddr),
+
+    #[error("No route to {0}")]
+    NoRoute(SocketAddr),
+
+    #[error("{0} did not answer within {1} ms")]
+    Timeout(SocketAddr, u128),
+
+    #[error("Could not connect to {0}: {1}")]
+    Io(SocketAddr, String),
+}
+
+/// Resolve `host` and open a TCP connection to it
+///
+/// Every resolved address is tried in turn; the error for the last one is
+/// returned if none accepts.
+pub async fn connect_tcp(host: &str, port: u16) -> Result<TcpStream, PrecheckError> {
+    let addrs: Vec<SocketAddr> = match tokio::time::timeout(DNS_TIMEOUT, tokio::net::lookup_host((host, port))).await {
+        Ok(Ok(addrs)) => addrs.collect(),
+        Ok(Err(e)) => return Err(PrecheckError::DnsFailed(host.to_string(), e.to_string())),
+        Err(_) => return Err(PrecheckError::DnsTimeout(host.to_string())),
+    };
+
+    let mut last_error = PrecheckError::DnsFailed(host.to_string(), "no addresses found".to_string());
+    for addr in addrs {
+        match tokio::time::timeout(TCP_PROBE_TIMEOUT, TcpStream::connect(addr)).await {
+            Ok(Ok(stream)) => return Ok(stream),
+            Ok(Err(e)) => last_error = classify_connect_error(addr, &e),
+            Err(_) => last_error = PrecheckError::Timeout(addr, TCP_PROBE_TIMEOUT.as_millis()),
+        }
+        tracing::debug!("Precheck: {}", last_error);
+    }
+    Err(last_error)
+}
+
+/// Turn a failed `connect` into the most specific error available
+fn classify_connect_error(addr: SocketAddr, error: &io::Error) -> PrecheckError {
+    match error.kind() {
+        io::ErrorKind::ConnectionRefused => PrecheckError::ConnectionRefused(addr),
+        io::ErrorKind::TimedOut => PrecheckError::Timeout(addr, TCP_PROBE_TIMEOUT.as_millis()),
+        _ if error.raw_os_error().is_some_and(is_unreachable_errno) => PrecheckError::NoRoute(addr),
+        _ => PrecheckError::Io(addr, error.to_string()),
+    }
+}
+
+/// EHOSTUNREACH / ENETUNREACH (`io::ErrorKind::HostUnreachable` needs a newer toolchain)
+fn is_unreachable_errno(code: i32) -> bool {
+    #[cfg(target_os = "linux")]
+    const CODES: [i32; 2] = [113, 101];
+    #[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+    const CODES: [i32; 2] = [65, 51];
+    #[cfg(windows)]
+    const CODES: [i32; 2] = [10065, 10051];
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "ios", target_os = "freebsd", windows)))]
+    const CODES: [i32; 0] = [];
+
+    CODES.contains(&code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_connect_error() {
+        let addr: SocketAddr = "192.0.2.1:22".parse().unwrap();
+        assert_eq!(
+            classify_connect_error(addr, &io::Error::from(io::ErrorKind::ConnectionRefused)),
+            PrecheckError::ConnectionRefused(addr)
+        );
+        #[cfg(target_os = "linux")]
+        assert_eq!(
+            classify_connect_error(addr, &io::Error::from_raw_os_error(113)),
+            PrecheckError::NoRoute(addr)
+        );
+        assert!(matches!(
+            classify_connect_error(addr, &io::Error::other("boom")),
+            PrecheckError::Io(_, _)
+        ));
+    }
+
+    #[test]
+    fn test_connect_tcp_to_closed_port() {
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        runtime.block_on(async {
+            // Bind and release a port so nothing is listening on it
+            let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+            let result = connect_tcp("127.0.0.1", port).await;
+            assert!(matches!(result, Err(PrecheckError::ConnectionRefused(_))));
+
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            let port = listener.local_addr().unwrap().port();
+            assert!(connect_tcp("127.0.0.1", port).await.is_ok());
+        });
+    }
+}
@@ -0,0 +1,314 @@
+//! Session transcript recording
+//!
+//! Tees remote session output (and optionally keyboard input) into
+//! timestamped files, rotating them once they reach the configured size.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::RecordingSettings;
+
+/// Number of rotated files kept next to the active transcript
+const MAX_ROTATED_FILES: usize = 5;
+
+/// A size-limited log file that rotates to `.1`, `.2`, ... when full
+struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    max_bytes: u64,
+}
+
+impl RotatingFile {
+    fn create(path: PathBuf, max_bytes: u64) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            path,
+            file,
+            written,
+            max_bytes,
+        })
+    }
+
+    fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        if self.max_bytes > 0 && self.written > 0 && self.written + data.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(data)?;
+        self.written += data.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        for n in (1..MAX_ROTATED_FILES).rev() {
+            let from = rotated_path(&self.path, n);
+            if from.exists() {
+                fs::rename(&from, rotated_path(&self.path, n + 1))?;
+            }
+        }
+        fs::rename(&self.path, rotated_path(&self.path, 1))?;
+
+        self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", n));
+    PathBuf::from(name)
+}
+
+/// Escape sequence parser state, kept across chunks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum StripState {
+    #[default]
+    Ground,
+    Escape,
+    Csi,
+    Osc,
+    OscEscape,
+}
+
+/// Removes ANSI escape sequences and control characters from a byte stream
+#[derive(Debug, Default)]
+struct AnsiStripper {
+    state: StripState,
+}
+
+impl AnsiStripper {
+    fn strip(&mut self, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(data.len());
+        for &b in data {
+            self.state = match self.state {
+                StripState::Ground => match b {
+                    0x1b => StripState::Escape,
+                    b'\n' | b'\t' => {
+                        out.push(b);
+                        StripState::Ground
+                    }
+                    // Other C0 controls (CR, BEL, BS, ...) and DEL
+                    0x00..=0x1f | 0x7f => StripState::Ground,
+                    _ => {
+                        out.push(b);
+                        StripState::Ground
+                    }
+                },
+                StripState::Escape => match b {
+                    b'[' => StripState::Csi,
+                    b']' | b'P' | b'_' | b'^' => StripState::Osc,
+                    // Charset designations etc. take one more byte
+                    b'(' | b')' | b'*' | b'+' | b'#' | b'%' => StripState::Csi,
+                    _ => StripState::Ground,
+                },
+                // CSI ends at a final byte in 0x40..=0x7e
+                StripState::Csi => match b {
+                    0x40..=0x7e => StripState::Ground,
+                    _ => StripState::Csi,
+                },
+                // OSC/DCS end at BEL or ST (ESC \)
+                StripState::Osc => match b {
+                    0x07 => StripState::Ground,
+                    0x1b => StripState::OscEscape,
+                    _ => StripState::Osc,
+                },
+                StripState::OscEscape => match b {
+                    b'\\' => StripState::Ground,
+                    _ => StripState::Osc,
+                },
+            };
+        }
+        out
+    }
+}
+
+/// Records a session's I/O to transcript files
+pub struct SessionRecorder {
+    output: RotatingFile,
+    input: Option<RotatingFile>,
+    stripper: Option<AnsiStripper>,
+}
+
+impl SessionRecorder {
+    /// Start a transcript for `session_name` in the configured directory
+    ///
+    /// Output goes to `<name>-<timestamp>.log`; input (when enabled) to a
+    /// sibling `<name>-<timestamp>.input.log`.
+    pub fn create(settings: &RecordingSettings, session_name: &str) -> io::Result<Self> {
+        let dir = settings.directory()?;
+        fs::create_dir_all(&dir)?;
+
+        let stem = format!("{}-{}", sanitize_file_name(session_name), file_timestamp(SystemTime::now()));
+        let max_bytes = settings.max_file_size_mb.saturating_mul(1024 * 1024);
+
+        let output = RotatingFile::create(dir.join(format!("{}.log", stem)), max_bytes)?;
+        let input = if settings.record_input {
+            tracing::warn!(
+                "Recording keyboard input for {}: passwords and other secrets typed will be captured",
+                session_name
+            );
+            Some(RotatingFile::create(dir.join(format!("{}.input.log", stem)), max_bytes)?)
+        } else {
+            None
+        };
+
+        tracing::info!("Recording session {} to {:?}", session_name, output.path);
+        Ok(Self {
+            output,
+            input,
+            stripper: settings.strip_ansi.then(AnsiStripper::default),
+        })
+    }
+
+    /// Path of the active output transcript
+    pub fn path(&self) -> &Path {
+        &self.output.path
+    }
+
+    /// Whether keyboard input is recorded too
+    pub fn records_input(&self) -> bool {
+        self.input.is_some()
+    }
+
+    /// Record data received from the remote side
+    pub fn record_output(&mut self, data: &[u8]) {
+        let result = match self.stripper.as_mut() {
+            Some(stripper) => {
+                let text = stripper.strip(data);
+                self.output.write(&text)
+            }
+            None => self.output.write(data),
+        };
+        if let Err(e) = result {
+            tracing::warn!("Failed to write session transcript: {}", e);
+        }
+    }
+
+    /// Record keyboard input sent to the remote side (if input recording is on)
+    pub fn record_input(&mut self, data: &[u8]) {
+        if let Some(input) = self.input.as_mut() {
+            if let Err(e) = input.write(data) {
+                tracing::warn!("Failed to write input transcript: {}", e);
+            }
+        }
+    }
+}
+
+/// Replace characters that are awkward in file names
+fn sanitize_file_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '_' })
+        .collect();
+    if cleaned.is_empty() {
+        "session".to_string()
+    } else {
+        cleaned
+    }
+}
+
+/// Format a time as `YYYYMMDD-HHMMSS` (UTC)
+fn file_timestamp(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        rem / 3600,
+        (rem % 3600) / 60,
+        rem % 60
+    )
+}
+
+/// Format a time as `YYYY-MM-DD HH:MM:SS UTC`, for showing to the user
+pub fn display_timestamp(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let rem = secs % 86_400;
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        rem / 3600,
+        (rem % 3600) / 60,
+        rem % 60
+    )
+}
+
+/// Convert days since the Unix epoch to a (year, month, day) date
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_strip_ansi() {
+        let mut stripper = AnsiStripper::default();
+        let out = stripper.strip(b"\x1b[1;31mred\x1b[0m plain\r\n\x1b]0;title\x07done\n");
+        assert_eq!(out, b"red plain\ndone\n");
+    }
+
+    #[test]
+    fn test_strip_ansi_across_chunks() {
+        let mut stripper = AnsiStripper::default();
+        let mut out = stripper.strip(b"a\x1b[3");
+        out.extend(stripper.strip(b"2mb"));
+        assert_eq!(out, b"ab");
+    }
+
+    #[test]
+    fn test_file_timestamp() {
+        let time = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        assert_eq!(file_timestamp(time), "20231114-221320");
+        assert_eq!(file_timestamp(UNIX_EPOCH), "19700101-000000");
+        assert_eq!(display_timestamp(time), "2023-11-14 22:13:20 UTC");
+    }
+
+    #[test]
+    fn test_sanitize_file_name() {
+        assert_eq!(sanitize_file_name("prod/web 1"), "prod_web_1");
+        assert_eq!(sanitize_file_name(""), "session");
+    }
+
+    #[test]
+    fn test_rotation() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("t.log");
+        let mut file = RotatingFile::create(path.clone(), 10).unwrap();
+
+        file.write(b"0123456789").unwrap();
+        file.write(b"abc").unwrap();
+        file.write(b"defghijklm").unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"defghijklm");
+        assert_eq!(fs::read(rotated_path(&path, 1)).unwrap(), b"abc");
+        assert_eq!(fs::read(rotated_path(&path, 2)).unwrap(), b"0123456789");
+    }
+}
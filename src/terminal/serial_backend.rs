@@ -0,0 +1,178 @@
+//! Serial console backend
+//!
+//! Opens a local serial port (USB adapters, on-board UARTs) and feeds it
+//! through the shared backend I/O loop. Serial lines have no notion of window
+//! size, so resizes are ignored.
+
+use async_trait::async_trait;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio_serial::{DataBits, FlowControl, Parity, SerialPortBuilderExt, SerialPortType, SerialStream, StopBits};
+
+use crate::metrics::ConnectionKind;
+use crate::session::{SerialFlowControl, SerialParity, SerialSession};
+use super::backend::{BackendIo, BackendRead, ConnectError, TerminalBackend};
+use super::TerminalSize;
+
+/// A serial port found on this machine
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SerialPortEntry {
+    /// Device path to open
+    pub device: String,
+    /// Product name for USB adapters, if known
+    pub description: Option<String>,
+}
+
+/// List the serial ports currently present
+///
+/// Returns an empty list if enumeration isn't supported or fails.
+pub fn available_ports() -> Vec<SerialPortEntry> {
+    match tokio_serial::available_ports() {
+        Ok(ports) => ports
+            .into_iter()
+            .map(|port| SerialPortEntry {
+                description: match port.port_type {
+                    SerialPortType::UsbPort(usb) => usb.product,
+                    _ => None,
+                },
+                device: port.port_name,
+            })
+            .collect(),
+        Err(e) => {
+            tracing::warn!("Failed to enumerate serial ports: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+fn data_bits(bits: u8) -> DataBits {
+    match bits {
+        5 => DataBits::Five,
+        6 => DataBits::Six,
+        7 => DataBits::Seven,
+        _ => DataBits::Eight,
+    }
+}
+
+fn stop_bits(bits: u8) -> StopBits {
+    if bits == 2 {
+        StopBits::Two
+    } else {
+        StopBits::One
+    }
+}
+
+fn parity(parity: SerialParity) -> Parity {
+    match parity {
+        SerialParity::None => Parity::None,
+        SerialParity::Odd => Parity::Odd,
+        SerialParity::Even => Parity::Even,
+    }
+}
+
+fn flow_control(flow: SerialFlowControl) -> FlowControl {
+    match flow {
+        SerialFlowControl::None => FlowControl::None,
+        SerialFlowControl::Software => FlowControl::Software,
+        SerialFlowControl::Hardware => FlowControl::Hardware,
+    }
+}
+
+/// Serial port backend
+pub struct SerialBackend {
+    session: SerialSession,
+    /// Port opened by `connect`, until taken for I/O
+    port: Option<SerialStream>,
+}
+
+impl SerialBackend {
+    /// Create a new serial backend (port not opened yet)
+    pub fn new(session: SerialSession) -> Self {
+        Self { session, port: None }
+    }
+
+    /// Get the session configuration
+    pub fn session(&self) -> &SerialSession {
+        &self.session
+    }
+}
+
+#[async_trait]
+impl TerminalBackend for SerialBackend {
+    fn kind(&self) -> ConnectionKind {
+        ConnectionKind::Serial
+    }
+
+    async fn connect(&mut self, _size: TerminalSize) -> Result<(), ConnectError> {
+        let session = &self.session;
+        let port = tokio_serial::new(&session.device, session.baud_rate)
+            .data_bits(data_bits(session.data_bits))
+            .stop_bits(stop_bits(session.stop_bits))
+            .parity(parity(session.parity))
+            .flow_control(flow_control(session.flow_control))
+            .open_native_async()
+            .map_err(|e| ConnectError::new("Serial Port Failed", format!("{}: {}", session.device, e)))?;
+
+        tracing::info!("Opened serial port {} ({})", session.device, session.line_settings());
+        self.port = Some(port);
+        Ok(())
+    }
+
+    fn io_channels(&mut self) -> Option<Box<dyn BackendIo>> {
+        let port = self.port.take()?;
+        Some(Box::new(SerialIo {
+            port,
+            buf: vec![0u8; 4096],
+        }))
+    }
+
+    async fn close(&mut self) {
+        self.port = None;
+    }
+}
+
+/// Open serial port driven by the shared I/O loop
+struct SerialIo {
+    port: SerialStream,
+    buf: Vec<u8>,
+}
+
+#[async_trait]
+impl BackendIo for SerialIo {
+    async fn read(&mut self) -> BackendRead {
+        match self.port.read(&mut self.buf).await {
+            Ok(0) => BackendRead::Closed(Some("Serial port closed".into())),
+            Ok(n) => BackendRead::Output(self.buf[..n].to_vec()),
+            Err(e) => {
+                tracing::error!("Serial read error: {}", e);
+                BackendRead::Failed(format!("Serial port error: {}", e))
+            }
+        }
+    }
+
+    async fn write(&mut self, data: &[u8]) -> Result<(), String> {
+        self.port.write_all(data).await.map_err(|e| e.to_string())
+    }
+
+    async fn resize(&mut self, _size: TerminalSize) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn close(&mut self) {
+        let _ = self.port.flush().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_settings_mapping() {
+        assert_eq!(data_bits(7), DataBits::Seven);
+        assert_eq!(data_bits(8), DataBits::Eight);
+        assert_eq!(stop_bits(2), StopBits::Two);
+        assert_eq!(stop_bits(1), StopBits::One);
+        assert_eq!(parity(SerialParity::Even), Parity::Even);
+        assert_eq!(flow_control(SerialFlowControl::Hardware), FlowControl::Hardware);
+    }
+}
@@ -0,0 +1,155 @@
+//! Shell integration marks (OSC 133)
+//!
+//! Shells with integration enabled wrap each prompt/command in
+//! `ESC ] 133 ; <A|B|C|D[;exit]> ST` sequences. alacritty ignores these, so
+//! output is scanned for them before it reaches the parser: remote output in
+//! `Terminal::write_to_pty`, local output in [`super::marked_pty`].
+
+use std::time::{Duration, Instant};
+
+/// Longest OSC 133 payload we bother buffering
+const MAX_PAYLOAD: usize = 64;
+
+/// A shell integration mark
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShellMark {
+    /// `A`: prompt is about to be drawn
+    PromptStart,
+    /// `B`: prompt done, user is typing a command
+    CommandStart,
+    /// `C`: command submitted, output follows
+    OutputStart,
+    /// `D[;code]`: command finished
+    CommandFinished(Option<i32>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScanState {
+    Ground,
+    Escape,
+    /// Inside an OSC; the payload is collected in `payload`
+    Osc,
+    /// Saw ESC inside an OSC (possible `ESC \` terminator)
+    OscEscape,
+}
+
+/// Incremental scanner for OSC 133 sequences split across reads
+#[derive(Debug)]
+pub struct ShellMarkScanner {
+    state: ScanState,
+    payload: Vec<u8>,
+    /// When the running command's output started (after `C`)
+    command_started: Option<Instant>,
+}
+
+impl Default for ShellMarkScanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShellMarkScanner {
+    pub fn new() -> Self {
+        Self {
+            state: ScanState::Ground,
+            payload: Vec::new(),
+            command_started: None,
+        }
+    }
+
+    /// Scan a chunk of output, returning any marks it completes
+    ///
+    /// Each mark comes with the offset in `data` just past its sequence.
+    pub fn scan(&mut self, data: &[u8]) -> Vec<(usize, ShellMark)> {
+        let mut marks = Vec::new();
+        for (i, &byte) in data.iter().enumerate() {
+            self.state = match (self.state, byte) {
+                (ScanState::Ground, 0x1b) => ScanState::Escape,
+                (ScanState::Ground, _) => ScanState::Ground,
+                (ScanState::Escape, b']') => {
+                    self.payload.clear();
+                    ScanState::Osc
+                }
+                (ScanState::Escape, 0x1b) => ScanState::Escape,
+                (ScanState::Escape, _) => ScanState::Ground,
+                (ScanState::Osc, 0x07) => {
+                    marks.extend(self.finish_osc().map(|mark| (i + 1, mark)));
+                    ScanState::Ground
+                }
+                (ScanState::Osc, 0x1b) => ScanState::OscEscape,
+                (ScanState::Osc, _) => {
+                    if self.payload.len() < MAX_PAYLOAD {
+                        self.payload.push(byte);
+                    }
+                    ScanState::Osc
+                }
+                (ScanState::OscEscape, b'\\') => {
+                    marks.extend(self.finish_osc().map(|mark| (i + 1, mark)));
+                    ScanState::Ground
+                }
+                // An ESC that isn't ST aborts the OSC and starts a new sequence
+                (ScanState::OscEscape, b']') => {
+                    self.payload.clear();
+                    ScanState::Osc
+                }
+                (ScanState::OscEscape, _) => ScanState::Ground,
+            };
+        }
+        marks
+    }
+
+    /// How long the command that just finished ran, if its start was seen
+    ///
+    /// Call after a [`ShellMark::CommandFinished`]; the start time is consumed.
+    pub fn take_command_duration(&mut self) -> Option<Duration> {
+        self.command_started.take().map(|t| t.elapsed())
+    }
+
+    fn finish_osc(&mut self) -> Option<ShellMark> {
+        let payload = std::str::from_utf8(&self.payload).ok()?;
+        let rest = payload.strip_prefix("133;")?;
+        let mut parts = rest.split(';');
+        let mark = match parts.next()? {
+            "A" => ShellMark::PromptStart,
+            "B" => ShellMark::CommandStart,
+            "C" => {
+                self.command_started = Some(Instant::now());
+                ShellMark::OutputStart
+            }
+            "D" => ShellMark::CommandFinished(parts.next().and_then(|code| code.trim().parse().ok())),
+            _ => return None,
+        };
+        Some(mark)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_marks() {
+        let mut scanner = ShellMarkScanner::new();
+        let marks = scanner.scan(b"\x1b]133;A\x07$ \x1b]133;B\x07make\r\n\x1b]133;C\x1b\\building...\x1b]133;D;2\x07");
+        assert_eq!(
+            marks,
+            vec![
+                (8, ShellMark::PromptStart),
+                (18, ShellMark::CommandStart),
+                (33, ShellMark::OutputStart),
+                (54, ShellMark::CommandFinished(Some(2))),
+            ]
+        );
+        assert!(scanner.take_command_duration().is_some());
+        assert!(scanner.take_command_duration().is_none());
+    }
+
+    #[test]
+    fn test_scan_split_and_unrelated_osc() {
+        let mut scanner = ShellMarkScanner::new();
+        assert!(scanner.scan(b"\x1b]0;title\x07\x1b]13").is_empty());
+        assert!(scanner.scan(b"3;D").is_empty());
+        assert_eq!(scanner.scan(b"\x1b\\"), vec![(2, ShellMark::CommandFinished(None))]);
+        assert!(scanner.scan(b"\x1b[31mred\x1b[0m").is_empty());
+    }
+}
@@ -1,23 +1,51 @@
-use russh::client::{self, Handle, Msg};
-use russh::keys::PublicKey;
-use russh::{Channel, ChannelMsg, Disconnect};
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use russh::client::{self, AuthResult, Handle, KeyboardInteractiveAuthResponse, Msg};
+use russh::keys::{HashAlg, PublicKey};
+use russh::{Channel, ChannelMsg, ChannelStream, Disconnect, MethodKind, MethodSet};
 use russh_sftp::client::SftpSession;
+use sha1::Sha1;
+use std::collections::VecDeque;
 use std::path::Path;
+use std::pin::Pin;
 use std::sync::Arc;
-use std::time::Duration;
+use std::task::Poll;
+use std::time::{Duration, Instant};
 use thiserror::Error;
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt, ReadBuf};
+use uuid::Uuid;
 
-use crate::session::models::{AuthMethod, SshSession};
+use crate::config::{AuthFallback, SshTimeouts};
+use crate::metrics::{metrics, ConnectionKind};
+use crate::session::models::{AuthMethod, ForwardDirection, JumpHostConfig, PortForward, SshSession};
+use super::auth_prompt::AuthPrompter;
+use super::backend::{BackendIo, BackendRead, ConnectError, TerminalBackend};
+use super::precheck::{connect_tcp, PrecheckError};
+use super::ssh_forward::{serve_agent, serve_remote, PortForwards};
+use super::ssh_pool::ConnectionPool;
 
-/// SSH connection configuration constants
-const CONNECTION_TIMEOUT_SECS: u64 = 5;
-const INACTIVITY_TIMEOUT_SECS: u64 = 300;
-const KEEPALIVE_INTERVAL_SECS: u64 = 30;
-const KEEPALIVE_MAX: usize = 3;
+/// Auth attempts made per connection. OpenSSH servers disconnect after
+/// `MaxAuthTries` (default 6) failures, counting every public key offered.
+const MAX_AUTH_ATTEMPTS: usize = 5;
 
-/// Reconnection configuration
-const MAX_RECONNECT_ATTEMPTS: u32 = 3;
-const INITIAL_RECONNECT_DELAY_SECS: u64 = 1;
+/// Times each fallback method is prompted for, like OpenSSH's
+/// `NumberOfPasswordPrompts`
+const FALLBACK_PROMPTS: usize = 3;
+
+/// Key files under ~/.ssh tried when falling back from the agent
+const DEFAULT_KEY_NAMES: [&str; 3] = ["id_ed25519", "id_rsa", "id_ecdsa"];
+
+/// Most input sent in one write; big pastes go out in pieces of this size
+const WRITE_CHUNK: usize = 16 * 1024;
+
+/// How long a command run with `exec_capture` may take
+const EXEC_TIMEOUT_SECS: u64 = 60;
+
+/// Reconnection configuration, shared with the SSM backend
+pub(super) const MAX_RECONNECT_ATTEMPTS: u32 = 3;
+pub(super) const INITIAL_RECONNECT_DELAY_SECS: u64 = 1;
 
 /// Errors that can occur during SSH operations
 #[derive(Debug, Error)]
@@ -28,9 +56,18 @@ pub enum SshError {
     #[error("Connection timed out after {0} seconds")]
     ConnectionTimeout(u64),
 
+    #[error("{0}")]
+    Unreachable(#[from] PrecheckError),
+
     #[error("Authentication failed: {0}")]
     AuthenticationFailed(String),
 
+    #[error("Too many authentication failures ({0} attempts); limit the keys offered for this session")]
+    TooManyAuthFailures(usize),
+
+    #[error("Key {0} is encrypted and no passphrase was given")]
+    KeyEncrypted(String),
+
     #[error("Host key verification failed: {0}")]
     HostKeyVerificationFailed(String),
 
@@ -46,6 +83,9 @@ pub enum SshError {
     #[error("Not connected")]
     NotConnected,
 
+    #[error("Command did not finish within {0} seconds")]
+    CommandTimeout(u64),
+
     #[error("SSH error: {0}")]
     SshError(String),
 }
@@ -73,6 +113,52 @@ impl TerminalSize {
     }
 }
 
+/// What a command run without a PTY printed, and how it exited
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExecOutput {
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    /// None if the server didn't report one, e.g. the command was killed by a signal
+    pub exit_status: Option<u32>,
+}
+
+impl ExecOutput {
+    /// Stdout followed by stderr, as text
+    pub fn text(&self) -> String {
+        let mut text = String::from_utf8_lossy(&self.stdout).into_owned();
+        if !self.stderr.is_empty() {
+            if !text.is_empty() && !text.ends_with('\n') {
+                text.push('\n');
+            }
+            text.push_str(&String::from_utf8_lossy(&self.stderr));
+        }
+        text
+    }
+
+    pub fn success(&self) -> bool {
+        self.exit_status == Some(0)
+    }
+}
+
+/// One line per host: its name, exit status and the first line of output
+///
+/// Failed connections show their error in place of the output.
+pub fn exec_results_table(results: &[(String, Result<ExecOutput, String>)]) -> String {
+    let name_width = results.iter().map(|(name, _)| name.chars().count()).max().unwrap_or(0).max("HOST".len());
+    let mut table = format!("{:<name_width$}  {:<6}  OUTPUT\n", "HOST", "EXIT");
+    for (name, result) in results {
+        let (status, summary) = match result {
+            Ok(output) => (
+                output.exit_status.map_or_else(|| "-".to_string(), |code| code.to_string()),
+                output.text().lines().find(|line| !line.trim().is_empty()).unwrap_or("").to_string(),
+            ),
+            Err(e) => ("error".to_string(), e.clone()),
+        };
+        table.push_str(&format!("{:<name_width$}  {:<6}  {}\n", name, status, summary));
+    }
+    table
+}
+
 /// Connection state of the SSH backend
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ConnectionState {
@@ -88,7 +174,7 @@ pub enum ConnectionState {
 pub enum HostKeyStatus {
     /// Key matches known_hosts entry
     Verified,
-    /// Host not in known_hosts, key was added (TOFU)
+    /// Host not in known_hosts; trusting it on first use needs the user's OK
     TrustOnFirstUse,
     /// Key mismatch - potential MITM attack
     Mismatch,
@@ -97,23 +183,55 @@ pub enum HostKeyStatus {
 }
 
 /// SSH client handler for russh
-struct SshClientHandler {
+pub(super) struct SshClientHandler {
     /// Server hostname for host key verification
     hostname: String,
-    /// Server host key verification callback result
-    verified: bool,
-    /// Host key verification status
-    host_key_status: Option<HostKeyStatus>,
+    /// Asks the user about keys known_hosts doesn't vouch for; without one
+    /// such keys are rejected
+    prompter: Option<AuthPrompter>,
+    /// Time spent waiting for that answer
+    prompt_time: PromptTime,
+    /// Remote forwards, to route the connections the server sends back
+    forwards: Vec<PortForward>,
+    /// Whether agent connections from the server are served
+    agent_forward: bool,
 }
 
 impl SshClientHandler {
-    fn new(hostname: &str) -> Self {
+    fn new(hostname: &str, prompter: Option<AuthPrompter>) -> Self {
         Self {
             hostname: hostname.to_string(),
-            verified: false,
-            host_key_status: None,
+            prompter,
+            prompt_time: PromptTime::default(),
+            forwards: Vec::new(),
+            agent_forward: false,
+        }
+    }
+}
+
+/// Time a handshake has spent waiting for the user to answer a host key question
+///
+/// Kept out of the connection timeout, which is meant for the network.
+#[derive(Clone, Default)]
+struct PromptTime(Arc<parking_lot::Mutex<(Duration, Option<Instant>)>>);
+
+impl PromptTime {
+    fn start(&self) {
+        self.0.lock().1 = Some(Instant::now());
+    }
+
+    fn stop(&self) {
+        let mut time = self.0.lock();
+        if let Some(started) = time.1.take() {
+            time.0 += started.elapsed();
         }
     }
+
+    /// Total time waited, including a wait still going on
+    fn total(&self) -> Duration {
+        let time = self.0.lock();
+        time.0 + time.1.map_or(Duration::ZERO, |started| started.elapsed())
+    }
 }
 
 impl client::Handler for SshClientHandler {
@@ -122,24 +240,53 @@ impl client::Handler for SshClientHandler {
     fn check_server_key(&mut self, server_public_key: &PublicKey) -> impl std::future::Future<Output = Result<bool, Self::Error>> + Send {
         let status = verify_host_key(&self.hostname, server_public_key);
         let hostname = self.hostname.clone();
+        let key = server_public_key.clone();
+        let prompter = self.prompter.clone();
+        let prompt_time = self.prompt_time.clone();
 
         async move {
-            match &status {
+            match status {
                 HostKeyStatus::Verified => {
                     tracing::info!("Host key verified for {}", hostname);
                     Ok(true)
                 }
-                HostKeyStatus::TrustOnFirstUse => {
-                    tracing::info!("New host key accepted for {} (TOFU)", hostname);
+                HostKeyStatus::TrustOnFirstUse | HostKeyStatus::Mismatch => {
+                    let changed = status == HostKeyStatus::Mismatch;
+                    if changed {
+                        tracing::error!(
+                            "HOST KEY VERIFICATION FAILED for {}! Potential MITM attack!",
+                            hostname
+                        );
+                    }
+                    let fingerprint = key.fingerprint(HashAlg::Sha256).to_string();
+                    let accepted = match &prompter {
+                        Some(prompter) => {
+                            prompt_time.start();
+                            let accepted = prompter
+                                .confirm_host_key(&hostname, key_type_string(&key), fingerprint, changed)
+                                .await;
+                            prompt_time.stop();
+                            accepted
+                        }
+                        None => false,
+                    };
+                    if !accepted {
+                        tracing::warn!("Host key for {} was not accepted", hostname);
+                        return Ok(false);
+                    }
+
+                    // Replace the old key rather than keep both
+                    if changed {
+                        if let Err(e) = remove_host_key(&hostname) {
+                            tracing::warn!("Failed to remove the old host key for {}: {}", hostname, e);
+                        }
+                    }
+                    match add_host_key_to_known_hosts(&hostname, &key) {
+                        Ok(()) => tracing::info!("Host key for {} accepted and saved", hostname),
+                        Err(e) => tracing::warn!("Host key for {} accepted but not saved: {}", hostname, e),
+                    }
                     Ok(true)
                 }
-                HostKeyStatus::Mismatch => {
-                    tracing::error!(
-                        "HOST KEY VERIFICATION FAILED for {}! Potential MITM attack!",
-                        hostname
-                    );
-                    Ok(false)
-                }
                 HostKeyStatus::Error(e) => {
                     tracing::warn!("Host key verification error for {}: {}", hostname, e);
                     Ok(true) // Allow connection but log warning
@@ -147,8 +294,117 @@ impl client::Handler for SshClientHandler {
             }
         }
     }
+
+    fn server_channel_open_forwarded_tcpip(
+        &mut self,
+        channel: Channel<Msg>,
+        connected_address: &str,
+        connected_port: u32,
+        _originator_address: &str,
+        _originator_port: u32,
+        _session: &mut client::Session,
+    ) -> impl std::future::Future<Output = Result<(), Self::Error>> + Send {
+        serve_remote(channel, &self.forwards, connected_address, connected_port);
+        async { Ok(()) }
+    }
+
+    fn server_channel_open_agent_forward(
+        &mut self,
+        channel: Channel<Msg>,
+        _session: &mut client::Session,
+    ) -> impl std::future::Future<Output = Result<(), Self::Error>> + Send {
+        let agent_forward = self.agent_forward;
+        async move {
+            if agent_forward {
+                serve_agent(channel);
+            } else {
+                // Only a misbehaving server asks without being offered the agent
+                tracing::warn!("Server opened an agent channel, but agent forwarding is off");
+                let _ = channel.close().await;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Do the SSH handshake over an open `stream`, with timeout
+async fn ssh_handshake<S>(stream: S, handler: SshClientHandler, timeouts: &SshTimeouts) -> SshResult<Handle<SshClientHandler>>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    // Create russh client config with timeouts and keepalive
+    let ssh_config = client::Config {
+        inactivity_timeout: timeouts.inactivity_timeout(),
+        keepalive_interval: timeouts.keepalive_interval(),
+        keepalive_max: timeouts.keepalive_max,
+        ..Default::default()
+    };
+    let timeout = timeouts.connect_timeout();
+    let hostname = handler.hostname.clone();
+    let prompt_time = handler.prompt_time.clone();
+    let connect_future = client::connect_stream(Arc::new(ssh_config), stream, handler);
+    tokio::pin!(connect_future);
+
+    // Time spent waiting on a host key question doesn't count
+    let started = tokio::time::Instant::now();
+    let deadline = || started + timeout + prompt_time.total();
+    let result = loop {
+        tokio::select! {
+            result = &mut connect_future => break result,
+            _ = tokio::time::sleep_until(deadline()) => {
+                if tokio::time::Instant::now() >= deadline() {
+                    return Err(SshError::ConnectionTimeout(timeout.as_secs()));
+                }
+            }
+        }
+    };
+
+    match result {
+        Ok(s) => Ok(s),
+        Err(russh::Error::UnknownKey) => Err(SshError::HostKeyVerificationFailed(format!(
+            "the host key for {} was not accepted; connect in a tab to review it",
+            hostname
+        ))),
+        Err(e) => Err(SshError::ConnectionFailed(e.to_string())),
+    }
+}
+
+/// Connection to a target host through a jump host's `direct-tcpip` channel
+///
+/// Owns the jump host's connection, so it stays up exactly as long as the
+/// target connection running over it.
+struct JumpStream {
+    stream: Pin<Box<ChannelStream<Msg>>>,
+    _jump: Handle<SshClientHandler>,
 }
 
+impl AsyncRead for JumpStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        self.get_mut().stream.as_mut().poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for JumpStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        self.get_mut().stream.as_mut().poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<std::io::Result<()>> {
+        self.get_mut().stream.as_mut().poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<std::io::Result<()>> {
+        self.get_mut().stream.as_mut().poll_shutdown(cx)
+    }
+}
 
 /// Path to the known_hosts file
 fn known_hosts_path() -> Option<std::path::PathBuf> {
@@ -156,6 +412,8 @@ fn known_hosts_path() -> Option<std::path::PathBuf> {
 }
 
 /// Verify a server's host key against known_hosts
+///
+/// Nothing is written; an unknown key is only added once the user accepts it.
 fn verify_host_key(hostname: &str, server_key: &PublicKey) -> HostKeyStatus {
     let known_hosts_path = match known_hosts_path() {
         Some(p) => p,
@@ -165,21 +423,20 @@ fn verify_host_key(hostname: &str, server_key: &PublicKey) -> HostKeyStatus {
     // Read known_hosts file
     let contents = match std::fs::read_to_string(&known_hosts_path) {
         Ok(c) => c,
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-            // File doesn't exist, use TOFU
-            return add_host_key_to_known_hosts(hostname, server_key);
-        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return HostKeyStatus::TrustOnFirstUse,
         Err(e) => return HostKeyStatus::Error(format!("Failed to read known_hosts: {}", e)),
     };
 
     // Convert russh key to base64 for comparison
-    let server_key_type = key_type_string(server_key);
     let server_key_base64 = match encode_public_key_base64(server_key) {
         Ok(k) => k,
         Err(e) => return HostKeyStatus::Error(format!("Failed to encode server key: {}", e)),
     };
+    known_hosts_status(&contents, hostname, &key_type_string(server_key), &server_key_base64)
+}
 
-    // Parse known_hosts and look for matching host
+/// Look up a host's key of type `key_type` in known_hosts `contents`
+fn known_hosts_status(contents: &str, hostname: &str, key_type: &str, key_base64: &str) -> HostKeyStatus {
     for line in contents.lines() {
         let line = line.trim();
         if line.is_empty() || line.starts_with('#') {
@@ -192,27 +449,23 @@ fn verify_host_key(hostname: &str, server_key: &PublicKey) -> HostKeyStatus {
             continue;
         }
 
-        let hosts = parts[0];
-        let key_type = parts[1];
-        let key_data = parts[2];
-
         // Check if this line matches our hostname
-        if !host_matches(hosts, hostname) {
+        if !host_matches(parts[0], hostname) {
             continue;
         }
 
         // Found a matching host - check if the key matches
-        if key_type == server_key_type && key_data == server_key_base64 {
+        if parts[1] == key_type && parts[2] == key_base64 {
             return HostKeyStatus::Verified;
-        } else if key_type == server_key_type {
+        } else if parts[1] == key_type {
             // Same key type but different key - this is a mismatch!
             return HostKeyStatus::Mismatch;
         }
         // Different key type - continue looking (host might have multiple keys)
     }
 
-    // Host not found, use TOFU
-    add_host_key_to_known_hosts(hostname, server_key)
+    // Host not found
+    HostKeyStatus::TrustOnFirstUse
 }
 
 /// Check if a hostname pattern matches a hostname
@@ -221,9 +474,11 @@ fn host_matches(pattern: &str, hostname: &str) -> bool {
     for host_pattern in pattern.split(',') {
         let host_pattern = host_pattern.trim();
 
-        // Handle hashed hosts (start with |)
+        // Hashed hosts (HashKnownHosts yes) can only be compared, not read
         if host_pattern.starts_with('|') {
-            // Hashed hosts are more complex to verify - skip for now
+            if hashed_host_matches(host_pattern, hostname) {
+                return true;
+            }
             continue;
         }
 
@@ -256,6 +511,24 @@ fn host_matches(pattern: &str, hostname: &str) -> bool {
     false
 }
 
+/// Whether a hashed known_hosts name, `|1|salt|hash`, is `hostname`
+///
+/// The hash is HMAC-SHA1 of the name keyed with the salt, both base64. Names
+/// hashed with a port (`[host]:port`) don't match a bare hostname.
+fn hashed_host_matches(entry: &str, hostname: &str) -> bool {
+    let Some((salt, hash)) = entry.strip_prefix("|1|").and_then(|rest| rest.split_once('|')) else {
+        return false;
+    };
+    let (Ok(salt), Ok(hash)) = (BASE64.decode(salt), BASE64.decode(hash)) else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha1>::new_from_slice(&salt) else {
+        return false;
+    };
+    mac.update(hostname.as_bytes());
+    mac.verify_slice(&hash).is_ok()
+}
+
 /// Get the SSH key type string for a public key
 fn key_type_string(key: &PublicKey) -> String {
     // Use the algorithm() method to get the algorithm identifier string
@@ -268,50 +541,106 @@ fn encode_public_key_base64(key: &PublicKey) -> Result<String, String> {
     Ok(key.public_key_base64())
 }
 
-/// Add a new host key to known_hosts (TOFU)
-fn add_host_key_to_known_hosts(hostname: &str, key: &PublicKey) -> HostKeyStatus {
-    let known_hosts_path = match known_hosts_path() {
-        Some(p) => p,
-        None => return HostKeyStatus::Error("Could not determine home directory".to_string()),
-    };
+/// Add an accepted host key to known_hosts
+fn add_host_key_to_known_hosts(hostname: &str, key: &PublicKey) -> Result<(), String> {
+    let known_hosts_path = known_hosts_path().ok_or("Could not determine home directory")?;
 
     // Ensure .ssh directory exists
     if let Some(ssh_dir) = known_hosts_path.parent() {
-        if let Err(e) = std::fs::create_dir_all(ssh_dir) {
-            return HostKeyStatus::Error(format!("Failed to create .ssh directory: {}", e));
-        }
+        std::fs::create_dir_all(ssh_dir).map_err(|e| format!("Failed to create .ssh directory: {}", e))?;
     }
 
-    let key_type = key_type_string(key);
-    let key_base64 = match encode_public_key_base64(key) {
-        Ok(k) => k,
-        Err(e) => return HostKeyStatus::Error(e),
-    };
-
-    let entry = format!("{} {} {}\n", hostname, key_type, key_base64);
+    let key_base64 = encode_public_key_base64(key)?;
+    let entry = format!("{} {} {}\n", hostname, key_type_string(key), key_base64);
 
     // Append to known_hosts
     use std::io::Write;
-    match std::fs::OpenOptions::new()
+    let mut file = std::fs::OpenOptions::new()
         .create(true)
         .append(true)
         .open(&known_hosts_path)
-    {
-        Ok(mut file) => {
-            if let Err(e) = file.write_all(entry.as_bytes()) {
-                return HostKeyStatus::Error(format!("Failed to write to known_hosts: {}", e));
+        .map_err(|e| format!("Failed to open known_hosts: {}", e))?;
+    file.write_all(entry.as_bytes())
+        .map_err(|e| format!("Failed to write to known_hosts: {}", e))?;
+    tracing::info!("Added host key for {} to known_hosts", hostname);
+    Ok(())
+}
+
+/// Remove every known_hosts entry for `hostname`, so the next connection
+/// trusts (and records) whatever key the server presents
+///
+/// Like `ssh-keygen -R`, whole lines are removed, hashed ones included.
+/// Entries for the host on a non-standard port (`[host]:port`) go too unless
+/// hashed; wildcard patterns and `@cert-authority`/`@revoked` lines are left
+/// alone. Returns how many lines were removed.
+pub fn remove_host_key(hostname: &str) -> Result<usize, SshError> {
+    let known_hosts_path = known_hosts_path().ok_or_else(|| {
+        SshError::IoError(std::io::Error::new(std::io::ErrorKind::NotFound, "Could not determine home directory"))
+    })?;
+    let contents = match std::fs::read_to_string(&known_hosts_path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e.into()),
+    };
+
+    let (kept, removed) = strip_host_entries(&contents, hostname);
+    if removed > 0 {
+        std::fs::write(&known_hosts_path, kept)?;
+        tracing::info!("Removed {} known_hosts entries for {}", removed, hostname);
+    }
+    Ok(removed)
+}
+
+/// Drop the known_hosts lines naming `hostname`, returning the rest and how
+/// many lines were dropped
+fn strip_host_entries(contents: &str, hostname: &str) -> (String, usize) {
+    let names_host = |line: &str| {
+        let Some(hosts) = line.split_whitespace().next() else {
+            return false;
+        };
+        hosts.split(',').any(|pattern| {
+            if pattern.starts_with('|') {
+                return hashed_host_matches(pattern, hostname);
             }
-            tracing::info!("Added host key for {} to known_hosts", hostname);
-            HostKeyStatus::TrustOnFirstUse
+            let pattern = match pattern.strip_prefix('[').and_then(|rest| rest.split_once("]:")) {
+                Some((host, _port)) => host,
+                None => pattern,
+            };
+            pattern == hostname
+        })
+    };
+
+    let mut kept = String::with_capacity(contents.len());
+    let mut removed = 0;
+    for line in contents.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if !trimmed.starts_with(['#', '@']) && names_host(trimmed) {
+            removed += 1;
+        } else {
+            kept.push_str(line);
         }
-        Err(e) => HostKeyStatus::Error(format!("Failed to open known_hosts: {}", e)),
+    }
+    (kept, removed)
+}
+
+/// Authenticated SSH connections shared between tabs of the same session
+#[derive(Clone, Default)]
+pub struct SshConnectionPool(ConnectionPool<Handle<SshClientHandler>>);
+
+impl SshConnectionPool {
+    pub fn new() -> Self {
+        Self::default()
     }
 }
 
 /// SSH backend implementation using russh
 pub struct SshBackend {
-    /// SSH session handle
-    session: Option<Handle<SshClientHandler>>,
+    /// SSH session handle (shared with other tabs when pooled)
+    session: Option<Arc<Handle<SshClientHandler>>>,
+    /// Pool to share the connection through, and this session's key in it
+    pool: Option<(SshConnectionPool, Uuid)>,
+    /// Whether `session` is the pooled connection
+    pooled: bool,
     /// SSH channel for PTY
     channel: Option<Channel<Msg>>,
     /// Current connection state
@@ -324,6 +653,17 @@ pub struct SshBackend {
     read_buffer: Vec<u8>,
     /// Channel for sending write requests (decoupled from read loop)
     write_tx: Option<tokio::sync::mpsc::UnboundedSender<Vec<u8>>>,
+    /// Methods to prompt for when the session's keys are rejected
+    auth_fallback: Vec<AuthFallback>,
+    /// Where to ask for missing secrets and fallback answers; nothing is
+    /// asked without one
+    prompter: Option<AuthPrompter>,
+    /// Ignore the saved password or passphrase and ask for it instead
+    force_prompt: bool,
+    /// Handshake timeout and keepalives
+    timeouts: SshTimeouts,
+    /// The session's port forwards, once running on this backend's connection
+    forwards: PortForwards,
 }
 
 impl SshBackend {
@@ -331,76 +671,308 @@ impl SshBackend {
     pub fn new(config: SshSession) -> Self {
         Self {
             session: None,
+            pool: None,
+            pooled: false,
             channel: None,
             state: ConnectionState::Disconnected,
             config,
             size: TerminalSize::new(80, 24),
             read_buffer: Vec::new(),
             write_tx: None,
+            auth_fallback: Vec::new(),
+            prompter: None,
+            force_prompt: false,
+            timeouts: SshTimeouts::default(),
+            forwards: PortForwards::default(),
         }
     }
 
+    /// Share this session's connection with other tabs through `pool`
+    ///
+    /// Connecting then opens a new channel on a live pooled connection for
+    /// the same session instead of doing another handshake.
+    pub fn share_connection(&mut self, pool: SshConnectionPool, session_id: Uuid) {
+        self.pool = Some((pool, session_id));
+    }
+
+    /// Ask for passwords, passphrases and fallback answers through `prompter`
+    pub fn set_prompter(&mut self, prompter: AuthPrompter) {
+        self.prompter = Some(prompter);
+    }
+
+    /// Fall back to `methods`, in order, when the session's keys are rejected
+    ///
+    /// Needs a prompter to ask for their answers.
+    pub fn set_auth_fallback(&mut self, methods: Vec<AuthFallback>) {
+        self.auth_fallback = methods;
+    }
+
+    /// Ask for the password or key passphrase even if one is saved
+    ///
+    /// For connecting from a machine the saved secret shouldn't be used on,
+    /// without editing the session.
+    pub fn set_force_prompt(&mut self, force_prompt: bool) {
+        self.force_prompt = force_prompt;
+    }
+
+    /// Use `timeouts` for the handshake and keepalives instead of the defaults
+    pub fn set_timeouts(&mut self, timeouts: SshTimeouts) {
+        self.timeouts = timeouts;
+    }
+
+    /// The session's port forwards and how they're doing
+    pub fn port_forwards(&self) -> PortForwards {
+        self.forwards.clone()
+    }
+
     /// Connect to the SSH server
+    ///
+    /// The session's port forwards start once the shell is open. A tab that
+    /// shares another tab's connection leaves them to that tab.
     pub async fn connect(&mut self) -> SshResult<()> {
         self.state = ConnectionState::Connecting;
 
-        // Create russh client config with timeouts and keepalive
-        let ssh_config = client::Config {
-            inactivity_timeout: Some(Duration::from_secs(INACTIVITY_TIMEOUT_SECS)),
-            keepalive_interval: Some(Duration::from_secs(KEEPALIVE_INTERVAL_SECS)),
-            keepalive_max: KEEPALIVE_MAX,
-            ..Default::default()
+        if let Some(session) = self.acquire_pooled() {
+            match self.open_shell(&session).await {
+                Ok(channel) => {
+                    tracing::info!("Reusing shared SSH connection to {}", self.description());
+                    self.session = Some(session);
+                    self.pooled = true;
+                    self.channel = Some(channel);
+                    self.state = ConnectionState::Connected;
+                    return Ok(());
+                }
+                Err(e) => {
+                    // e.g. the server's MaxSessions limit; fall back to a connection of our own
+                    tracing::warn!(
+                        "Shared SSH connection to {} refused a new channel ({}); connecting separately",
+                        self.description(),
+                        e
+                    );
+                    if let Some(session) = self.release_pooled(&session) {
+                        let _ = session.disconnect(Disconnect::ByApplication, "User disconnected", "en").await;
+                    }
+                }
+            }
+        }
+
+        let session = match self.handshake().await {
+            Ok(session) => session,
+            Err(e) => {
+                self.state = ConnectionState::Failed;
+                return Err(e);
+            }
+        };
+        let channel = match self.open_shell(&session).await {
+            Ok(channel) => channel,
+            Err(e) => {
+                self.state = ConnectionState::Failed;
+                return Err(e);
+            }
         };
-        let ssh_config = Arc::new(ssh_config);
 
-        // Connect to the server with timeout
-        let addr = format!("{}:{}", self.config.host, self.config.port);
-        tracing::info!("Connecting to SSH server: {}", addr);
+        if !self.config.port_forwards.is_empty() {
+            self.forwards.start(&session, &self.config.port_forwards).await;
+        }
+
+        self.pooled = self
+            .pool
+            .as_ref()
+            .is_some_and(|(pool, key)| pool.0.insert(*key, session.clone()));
+        self.session = Some(session);
+        self.channel = Some(channel);
+        self.state = ConnectionState::Connected;
 
-        let handler = SshClientHandler::new(&self.config.host);
-        let connect_future = client::connect(ssh_config, &addr, handler);
+        tracing::info!("SSH connection established to {}", self.description());
+        Ok(())
+    }
 
-        let mut session = match tokio::time::timeout(
-            Duration::from_secs(CONNECTION_TIMEOUT_SECS),
-            connect_future,
-        )
-        .await
-        {
-            Ok(Ok(s)) => s,
-            Ok(Err(e)) => {
-                self.state = ConnectionState::Failed;
-                return Err(SshError::ConnectionFailed(e.to_string()));
+    /// Open a TCP connection, do the SSH handshake and authenticate
+    ///
+    /// With a jump host, the connection to it is made first and the target's
+    /// handshake runs over a `direct-tcpip` channel through it.
+    async fn handshake(&self) -> SshResult<Arc<Handle<SshClientHandler>>> {
+        let mut handler = SshClientHandler::new(&self.config.host, self.prompter.clone());
+        handler.forwards = self
+            .config
+            .port_forwards
+            .iter()
+            .filter(|forward| forward.direction == ForwardDirection::Remote)
+            .cloned()
+            .collect();
+        handler.agent_forward = self.config.agent_forward;
+        let mut session = match &self.config.jump_host {
+            Some(jump) => {
+                let stream = self.open_jump_tunnel(jump).await?;
+                ssh_handshake(stream, handler, &self.timeouts).await?
             }
-            Err(_) => {
-                self.state = ConnectionState::Failed;
-                return Err(SshError::ConnectionTimeout(CONNECTION_TIMEOUT_SECS));
+            None => {
+                // Resolve and open the TCP connection first, so a host that is down
+                // fails in about a second with a precise reason
+                tracing::info!("Connecting to SSH server: {}:{}", self.config.host, self.config.port);
+                let stream = connect_tcp(&self.config.host, self.config.port).await?;
+                let _ = stream.set_nodelay(true);
+                ssh_handshake(stream, handler, &self.timeouts).await?
             }
         };
 
-        // Authenticate
-        let authenticated = self.authenticate(&mut session).await?;
-        if !authenticated {
-            self.state = ConnectionState::Failed;
+        if !self.authenticate(&mut session).await? {
             return Err(SshError::AuthenticationFailed(
                 "Authentication failed".to_string(),
             ));
         }
+        Ok(Arc::new(session))
+    }
 
-        // Open a session channel
-        let channel = match session.channel_open_session().await {
-            Ok(c) => c,
-            Err(e) => {
-                self.state = ConnectionState::Failed;
-                return Err(SshError::SshError(format!(
-                    "Failed to open channel: {}",
+    /// Log in to `jump` and open a `direct-tcpip` channel through it to this session's host
+    ///
+    /// The jump host gets the same prompter and fallback methods as the
+    /// session; an empty username there means the session's own.
+    async fn open_jump_tunnel(&self, jump: &JumpHostConfig) -> SshResult<JumpStream> {
+        let username = if jump.username.is_empty() {
+            self.config.username.clone()
+        } else {
+            jump.username.clone()
+        };
+        let mut jump_config = SshSession::new(jump.host.clone(), jump.host.clone(), username);
+        jump_config.port = jump.port;
+        jump_config.auth = jump.auth.clone();
+        let mut jump_backend = SshBackend::new(jump_config);
+        jump_backend.prompter = self.prompter.clone();
+        jump_backend.auth_fallback = self.auth_fallback.clone();
+        jump_backend.timeouts = self.timeouts;
+
+        let jump_error = |e: SshError| SshError::ConnectionFailed(format!("jump host {}: {}", jump.spec(), e));
+
+        tracing::info!("Connecting to jump host: {}:{}", jump.host, jump.port);
+        let stream = connect_tcp(&jump.host, jump.port).await.map_err(|e| jump_error(e.into()))?;
+        let _ = stream.set_nodelay(true);
+        let handler = SshClientHandler::new(&jump.host, jump_backend.prompter.clone());
+        let mut jump_session = ssh_handshake(stream, handler, &jump_backend.timeouts)
+            .await
+            .map_err(jump_error)?;
+        if !jump_backend.authenticate(&mut jump_session).await.map_err(jump_error)? {
+            return Err(jump_error(SshError::AuthenticationFailed(
+                "Authentication failed".to_string(),
+            )));
+        }
+
+        tracing::info!(
+            "Opening tunnel to {}:{} through {}",
+            self.config.host,
+            self.config.port,
+            jump.host
+        );
+        let channel = jump_session
+            .channel_open_direct_tcpip(self.config.host.clone(), self.config.port as u32, "127.0.0.1", 0)
+            .await
+            .map_err(|e| {
+                SshError::ConnectionFailed(format!(
+                    "jump host {} could not reach {}:{}: {}",
+                    jump.spec(),
+                    self.config.host,
+                    self.config.port,
                     e
-                )));
+                ))
+            })?;
+        Ok(JumpStream {
+            stream: Box::pin(channel.into_stream()),
+            _jump: jump_session,
+        })
+    }
+
+    /// Run `command` on its own channel, without a PTY, and collect its output
+    ///
+    /// Connects first if needed, reusing a shared connection when there is
+    /// one; no shell is started. Gives up after `EXEC_TIMEOUT_SECS`.
+    pub async fn exec_capture(&mut self, command: &str) -> SshResult<ExecOutput> {
+        if self.session.is_none() {
+            self.state = ConnectionState::Connecting;
+            let session = match self.acquire_pooled() {
+                Some(session) => {
+                    self.pooled = true;
+                    session
+                }
+                None => match self.handshake().await {
+                    Ok(session) => session,
+                    Err(e) => {
+                        self.state = ConnectionState::Failed;
+                        return Err(e);
+                    }
+                },
+            };
+            self.session = Some(session);
+            self.state = ConnectionState::Connected;
+        }
+        let session = self.session.as_ref().ok_or(SshError::NotConnected)?;
+
+        let mut channel = session
+            .channel_open_session()
+            .await
+            .map_err(|e| SshError::SshError(format!("Failed to open channel: {}", e)))?;
+        channel
+            .exec(true, command)
+            .await
+            .map_err(|e| SshError::SshError(format!("Failed to run command: {}", e)))?;
+
+        let collect = async {
+            let mut output = ExecOutput::default();
+            while let Some(msg) = channel.wait().await {
+                match msg {
+                    ChannelMsg::Data { data } => output.stdout.extend_from_slice(&data),
+                    ChannelMsg::ExtendedData { data, ext: 1 } => output.stderr.extend_from_slice(&data),
+                    ChannelMsg::ExitStatus { exit_status } => output.exit_status = Some(exit_status),
+                    _ => {}
+                }
             }
+            output
         };
+        tokio::time::timeout(Duration::from_secs(EXEC_TIMEOUT_SECS), collect)
+            .await
+            .map_err(|_| SshError::CommandTimeout(EXEC_TIMEOUT_SECS))
+    }
+
+    /// Whether connecting may reuse another tab's pooled connection
+    ///
+    /// A forced prompt has to authenticate itself, so it never does.
+    fn may_reuse_pooled(&self) -> bool {
+        self.pool.is_some() && !self.force_prompt
+    }
+
+    /// Take a live pooled connection for this session, if there is one
+    fn acquire_pooled(&self) -> Option<Arc<Handle<SshClientHandler>>> {
+        if !self.may_reuse_pooled() {
+            return None;
+        }
+        let (pool, key) = self.pool.as_ref()?;
+        pool.0.acquire(*key, |handle| !handle.is_closed())
+    }
+
+    /// Stop using a pooled connection, returning it if this was its last user
+    fn release_pooled(&self, session: &Arc<Handle<SshClientHandler>>) -> Option<Arc<Handle<SshClientHandler>>> {
+        let (pool, key) = self.pool.as_ref()?;
+        pool.0.release(*key, session)
+    }
+
+    /// Let go of this backend's connection, returning it if nobody else is using it
+    fn release_session(&mut self, session: Arc<Handle<SshClientHandler>>) -> Option<Arc<Handle<SshClientHandler>>> {
+        if std::mem::take(&mut self.pooled) {
+            self.release_pooled(&session)
+        } else {
+            Some(session)
+        }
+    }
+
+    /// Open a session channel with a PTY and a shell
+    async fn open_shell(&self, session: &Handle<SshClientHandler>) -> SshResult<Channel<Msg>> {
+        let channel = session
+            .channel_open_session()
+            .await
+            .map_err(|e| SshError::SshError(format!("Failed to open channel: {}", e)))?;
 
         // Request a PTY (want_reply=true to wait for server confirmation)
         tracing::info!("Requesting PTY...");
-        if let Err(e) = channel
+        channel
             .request_pty(
                 true,
                 "xterm-256color",
@@ -411,81 +983,193 @@ impl SshBackend {
                 &[], // Terminal modes
             )
             .await
-        {
-            self.state = ConnectionState::Failed;
-            return Err(SshError::SshError(format!("Failed to request PTY: {}", e)));
-        }
+            .map_err(|e| SshError::SshError(format!("Failed to request PTY: {}", e)))?;
         tracing::info!("PTY granted");
 
+        if self.config.agent_forward {
+            // No reply wanted, as with OpenSSH; a server that refuses just
+            // never opens agent channels
+            if let Err(e) = channel.agent_forward(false).await {
+                tracing::warn!("Failed to request agent forwarding: {}", e);
+            }
+        }
+
         // Request a shell (want_reply=true to wait for server confirmation)
         tracing::info!("Requesting shell...");
-        if let Err(e) = channel.request_shell(true).await {
-            self.state = ConnectionState::Failed;
-            return Err(SshError::SshError(format!(
-                "Failed to request shell: {}",
-                e
-            )));
-        }
+        channel
+            .request_shell(true)
+            .await
+            .map_err(|e| SshError::SshError(format!("Failed to request shell: {}", e)))?;
         tracing::info!("Shell started");
 
-        self.session = Some(session);
-        self.channel = Some(channel);
-        self.state = ConnectionState::Connected;
-
-        tracing::info!("SSH connection established to {}", addr);
-        Ok(())
+        Ok(channel)
     }
 
     /// Authenticate with the server using the configured method
+    ///
+    /// The configured method always runs first; discovered default keys are
+    /// only offered while the attempt budget allows. If no key is accepted,
+    /// the fallback methods the server still allows are prompted for.
     async fn authenticate(&self, session: &mut Handle<SshClientHandler>) -> SshResult<bool> {
         let username = &self.config.username;
         tracing::info!("Authenticating as user: {}", username);
 
+        let mut attempts = AuthAttempts::new(MAX_AUTH_ATTEMPTS);
+        let result = match self.authenticate_configured(session, username, &mut attempts).await {
+            Ok(false) => match self.authenticate_fallback(session, username, &mut attempts).await {
+                Some(result) => return result,
+                None => Ok(false),
+            },
+            result => result,
+        };
+        match result {
+            Ok(false) if attempts.exhausted() => Err(SshError::TooManyAuthFailures(attempts.used)),
+            result => result,
+        }
+    }
+
+    /// Prompt for the fallback methods after the session's keys were rejected
+    ///
+    /// Returns None if there is nothing to fall back to: password and
+    /// keyboard-interactive sessions, no prompter, or no configured method
+    /// the server still accepts.
+    async fn authenticate_fallback(
+        &self,
+        session: &mut Handle<SshClientHandler>,
+        username: &str,
+        attempts: &mut AuthAttempts,
+    ) -> Option<SshResult<bool>> {
+        let prompter = self.prompter.as_ref()?;
+        if matches!(self.config.auth, AuthMethod::Password { .. } | AuthMethod::KeyboardInteractive) {
+            return None;
+        }
+        let methods: Vec<AuthFallback> = self
+            .auth_fallback
+            .iter()
+            .copied()
+            .filter(|&method| attempts.server_allows(method))
+            .collect();
+        if methods.is_empty() {
+            return None;
+        }
+        tracing::info!("Keys not accepted, falling back to {:?}", methods);
+
+        let mut notice = Some(format!("Public key authentication for {}@{} failed.\n", username, self.config.host));
+        for method in methods {
+            for _ in 0..FALLBACK_PROMPTS {
+                if !attempts.server_allows(method) {
+                    break;
+                }
+                let result = match method {
+                    AuthFallback::KeyboardInteractive => {
+                        keyboard_interactive(session, username, prompter, &mut notice, attempts).await
+                    }
+                    AuthFallback::Password => {
+                        let prompt = format!(
+                            "{}{}@{}'s password: ",
+                            notice.take().unwrap_or_default(),
+                            username,
+                            self.config.host
+                        );
+                        prompted_password(session, username, prompter, prompt, attempts).await
+                    }
+                };
+                match result {
+                    Ok(Some(true)) => return Some(Ok(true)),
+                    Ok(Some(false)) => notice = Some("Permission denied, please try again.\n".to_string()),
+                    // The user cancelled the prompt
+                    Ok(None) => return Some(Ok(false)),
+                    Err(e) => return Some(Err(e)),
+                }
+            }
+        }
+        Some(Ok(false))
+    }
+
+    async fn authenticate_configured(
+        &self,
+        session: &mut Handle<SshClientHandler>,
+        username: &str,
+        attempts: &mut AuthAttempts,
+    ) -> SshResult<bool> {
         match &self.config.auth {
             AuthMethod::Password { password, .. } => {
                 tracing::info!("Using password authentication");
-                let password = password.as_ref().ok_or_else(|| {
-                    SshError::AuthenticationFailed("Password not provided".to_string())
-                })?;
+                let password = match (password.clone().filter(|_| !self.force_prompt), &self.prompter) {
+                    (Some(password), _) => password,
+                    (None, Some(prompter)) => {
+                        let prompt = format!("{}@{}'s password: ", username, self.config.host);
+                        prompter.ask(prompt, false).await.ok_or_else(|| {
+                            SshError::AuthenticationFailed("Password prompt cancelled".to_string())
+                        })?
+                    }
+                    (None, None) => {
+                        return Err(SshError::AuthenticationFailed("Password not provided".to_string()));
+                    }
+                };
 
+                attempts.used += 1;
                 match session.authenticate_password(username, password).await {
                     Ok(result) => {
                         tracing::info!("Password auth result: {:?}", result);
+                        if let AuthResult::Failure { remaining_methods, .. } = &result {
+                            attempts.rejected(remaining_methods);
+                        }
                         Ok(result.success())
                     }
                     Err(e) => {
                         tracing::error!("Password auth error: {}", e);
-                        Err(SshError::AuthenticationFailed(e.to_string()))
+                        Err(auth_error(e, attempts))
                     }
                 }
             }
 
             AuthMethod::PrivateKey {
-                path, passphrase, ..
+                passphrase, identities_only, ..
             } => {
-                tracing::info!("Using private key authentication from: {:?}", path);
-                let key = load_private_key(path, passphrase.as_deref())?;
-                let key_with_hash = russh::keys::PrivateKeyWithHashAlg::new(Arc::new(key), None);
-                match session.authenticate_publickey(username, key_with_hash).await {
-                    Ok(result) => {
-                        tracing::info!("Key auth result: {:?}", result);
-                        Ok(result.success())
+                let key_paths = self.config.auth.key_paths();
+                let passphrase = passphrase.as_deref().filter(|_| !self.force_prompt);
+                let mut last_error = None;
+
+                // Offer each configured key in turn; stop at the first the server accepts
+                for path in &key_paths {
+                    tracing::info!("Using private key authentication from: {:?}", path);
+                    match offer_key(session, username, path, passphrase, self.prompter.as_ref(), attempts).await {
+                        Ok(true) => return Ok(true),
+                        Ok(false) => {}
+                        Err(e @ SshError::TooManyAuthFailures(_)) => return Err(e),
+                        Err(e) => {
+                            tracing::warn!("Skipping key {:?}: {}", path, e);
+                            last_error = Some(e);
+                        }
                     }
-                    Err(e) => {
-                        tracing::error!("Key auth error: {}", e);
-                        Err(SshError::AuthenticationFailed(e.to_string()))
+                }
+
+                if !*identities_only && !attempts.exhausted() {
+                    tracing::info!("Configured keys not accepted, falling back to SSH agent");
+                    match self.authenticate_with_agent(session, username, attempts).await {
+                        Ok(true) => return Ok(true),
+                        Err(e @ SshError::TooManyAuthFailures(_)) => return Err(e),
+                        _ => {}
                     }
                 }
+
+                // A single unreadable key is a configuration error worth surfacing as-is
+                match last_error {
+                    Some(e) if key_paths.len() == 1 => Err(e),
+                    _ => Ok(false),
+                }
             }
 
             AuthMethod::Agent => {
                 tracing::info!("Using SSH agent authentication");
                 // Try to connect to SSH agent
-                match self.authenticate_with_agent(session, username).await {
+                match self.authenticate_with_agent(session, username, attempts).await {
                     Ok(result) => {
                         tracing::info!("Agent auth result: {}", result);
                         Ok(result)  // authenticate_with_agent already returns bool
                     }
+                    Err(e @ SshError::TooManyAuthFailures(_)) => Err(e),
                     Err(e) => {
                         tracing::error!("Agent auth error: {}", e);
                         Err(SshError::AuthenticationFailed(format!(
@@ -495,52 +1179,58 @@ impl SshBackend {
                     }
                 }
             }
+
+            AuthMethod::KeyboardInteractive => {
+                tracing::info!("Using keyboard-interactive authentication");
+                let prompter = self.prompter.as_ref().ok_or_else(|| {
+                    SshError::AuthenticationFailed("Keyboard-interactive authentication needs a terminal to answer in".to_string())
+                })?;
+
+                // Like a password, a mistyped code gets another try
+                let mut notice = None;
+                for _ in 0..FALLBACK_PROMPTS {
+                    match keyboard_interactive(session, username, prompter, &mut notice, attempts).await? {
+                        Some(true) => return Ok(true),
+                        Some(false) => notice = Some("Permission denied, please try again.\n".to_string()),
+                        None => {
+                            return Err(SshError::AuthenticationFailed("Keyboard-interactive prompt cancelled".to_string()));
+                        }
+                    }
+                    if attempts.exhausted() || !attempts.server_allows(AuthFallback::KeyboardInteractive) {
+                        break;
+                    }
+                }
+                Ok(false)
+            }
         }
     }
 
     /// Authenticate using SSH agent (Unix implementation)
+    ///
+    /// The agent signs for each key it holds; the default key files are only
+    /// tried if it holds none.
     #[cfg(unix)]
     async fn authenticate_with_agent(
         &self,
         session: &mut Handle<SshClientHandler>,
         username: &str,
+        attempts: &mut AuthAttempts,
     ) -> SshResult<bool> {
-        use russh_keys::agent::client::AgentClient;
+        use russh::keys::agent::client::AgentClient;
 
         // Get the SSH_AUTH_SOCK environment variable
         let socket_path = std::env::var("SSH_AUTH_SOCK").map_err(|_| {
             SshError::AuthenticationFailed("SSH_AUTH_SOCK not set".to_string())
         })?;
 
-        // Connect to the agent (verify it's accessible, though we fall back to default keys)
-        let _agent = AgentClient::connect_uds(&socket_path)
+        let mut agent = AgentClient::connect_uds(&socket_path)
             .await
             .map_err(|e| SshError::AuthenticationFailed(format!("Failed to connect to agent: {}", e)))?;
 
-        // Note: Agent authentication requires russh agent support which has complex API changes.
-        // For now, fallback to trying default key paths
-        tracing::warn!("SSH agent found but agent auth not fully supported, trying default keys");
-
-        // Try default key paths
-        let home = dirs::home_dir().ok_or_else(|| {
-            SshError::AuthenticationFailed("Could not determine home directory".to_string())
-        })?;
-
-        for key_name in &["id_ed25519", "id_rsa", "id_ecdsa"] {
-            let key_path = home.join(".ssh").join(key_name);
-            if key_path.exists() {
-                if let Ok(key) = load_private_key(&key_path, None) {
-                    let key_with_hash = russh::keys::PrivateKeyWithHashAlg::new(Arc::new(key), None);
-                    if let Ok(result) = session.authenticate_publickey(username, key_with_hash).await {
-                        if result.success() {
-                            tracing::info!("Authenticated with key: {:?}", key_path);
-                            return Ok(true);
-                        }
-                    }
-                }
-            }
+        match offer_agent_keys(session, username, &mut agent, attempts).await? {
+            Some(authenticated) => Ok(authenticated),
+            None => offer_default_keys(session, username, attempts).await,
         }
-        Ok(false)
     }
 
     /// Authenticate using SSH agent (Windows implementation)
@@ -551,16 +1241,15 @@ impl SshBackend {
         &self,
         session: &mut Handle<SshClientHandler>,
         username: &str,
+        attempts: &mut AuthAttempts,
     ) -> SshResult<bool> {
-        use russh_keys::agent::client::AgentClient;
-
         // Try Windows OpenSSH agent via named pipe first
-        if let Some(result) = self.try_openssh_agent(session, username).await {
+        if let Some(result) = self.try_openssh_agent(session, username, attempts).await {
             return result;
         }
 
         // Fall back to Pageant
-        self.try_pageant_agent(session, username).await
+        self.try_pageant_agent(session, username, attempts).await
     }
 
     /// Try to authenticate using Windows OpenSSH agent (named pipe)
@@ -569,15 +1258,16 @@ impl SshBackend {
         &self,
         session: &mut Handle<SshClientHandler>,
         username: &str,
+        attempts: &mut AuthAttempts,
     ) -> Option<SshResult<bool>> {
-        use russh_keys::agent::client::AgentClient;
+        use russh::keys::agent::client::AgentClient;
 
         // Try SSH_AUTH_SOCK first, then default pipe
         let pipe_path = std::env::var("SSH_AUTH_SOCK")
             .ok()
             .unwrap_or_else(|| r"\\.\pipe\openssh-ssh-agent".to_string());
 
-        let agent = match AgentClient::connect_named_pipe(&pipe_path).await {
+        let mut agent = match AgentClient::connect_named_pipe(&pipe_path).await {
             Ok(a) => a,
             Err(_) => {
                 // Try default pipe if SSH_AUTH_SOCK didn't work
@@ -592,26 +1282,12 @@ impl SshBackend {
             }
         };
 
-        // Try default key paths as fallback
-        let home = match dirs::home_dir() {
-            Some(h) => h,
-            None => return Some(Ok(false)),
-        };
-
-        for key_name in &["id_ed25519", "id_rsa", "id_ecdsa"] {
-            let key_path = home.join(".ssh").join(key_name);
-            if key_path.exists() {
-                if let Ok(key) = load_private_key(&key_path, None) {
-                    let key_with_hash = russh::keys::PrivateKeyWithHashAlg::new(Arc::new(key), None);
-                    if let Ok(result) = session.authenticate_publickey(username, key_with_hash).await {
-                        if result.success() {
-                            return Some(Ok(true));
-                        }
-                    }
-                }
-            }
-        }
-        Some(Ok(false))
+        // Key files only if the agent holds no keys
+        Some(match offer_agent_keys(session, username, &mut agent, attempts).await {
+            Ok(Some(authenticated)) => Ok(authenticated),
+            Ok(None) => offer_default_keys(session, username, attempts).await,
+            Err(e) => Err(e),
+        })
     }
 
     /// Try to authenticate using Pageant SSH agent
@@ -620,26 +1296,10 @@ impl SshBackend {
         &self,
         session: &mut Handle<SshClientHandler>,
         username: &str,
+        attempts: &mut AuthAttempts,
     ) -> SshResult<bool> {
         // Try default key paths as fallback
-        let home = dirs::home_dir().ok_or_else(|| {
-            SshError::AuthenticationFailed("Could not determine home directory".to_string())
-        })?;
-
-        for key_name in &["id_ed25519", "id_rsa", "id_ecdsa"] {
-            let key_path = home.join(".ssh").join(key_name);
-            if key_path.exists() {
-                if let Ok(key) = load_private_key(&key_path, None) {
-                    let key_with_hash = russh::keys::PrivateKeyWithHashAlg::new(Arc::new(key), None);
-                    if let Ok(result) = session.authenticate_publickey(username, key_with_hash).await {
-                        if result.success() {
-                            return Ok(true);
-                        }
-                    }
-                }
-            }
-        }
-        Ok(false)
+        offer_default_keys(session, username, attempts).await
     }
 
     /// Set up the write channel sender
@@ -796,7 +1456,12 @@ impl SshBackend {
             let _ = channel.eof().await;
         }
 
-        if let Some(session) = self.session.take() {
+        if let Some(session) = &self.session {
+            self.forwards.stop(session).await;
+        }
+
+        // A shared connection stays up until its last tab closes
+        if let Some(session) = self.session.take().and_then(|session| self.release_session(session)) {
             let _ = session
                 .disconnect(Disconnect::ByApplication, "User disconnected", "en")
                 .await;
@@ -841,9 +1506,13 @@ impl SshBackend {
 
             // Wait before attempting reconnection
             tokio::time::sleep(Duration::from_secs(delay_secs)).await;
+            metrics().add_reconnect(ConnectionKind::Ssh);
 
             // Clean up any existing connection state
-            self.session = None;
+            if let Some(session) = self.session.take() {
+                self.release_session(session);
+            }
+            self.forwards.abort();
             self.channel = None;
             self.read_buffer.clear();
             self.state = ConnectionState::Disconnected;
@@ -910,27 +1579,457 @@ impl SshBackend {
     }
 }
 
+#[async_trait]
+impl TerminalBackend for SshBackend {
+    fn kind(&self) -> ConnectionKind {
+        ConnectionKind::Ssh
+    }
+
+    async fn connect(&mut self, _size: super::TerminalSize) -> Result<(), ConnectError> {
+        SshBackend::connect(self)
+            .await
+            .map_err(|e| ConnectError::new("Connection Failed", e))?;
+        tracing::info!("SSH connection established");
+        Ok(())
+    }
+
+    fn io_channels(&mut self) -> Option<Box<dyn BackendIo>> {
+        let (channel, _write_rx) = self.take_channel_for_io()?;
+        Some(Box::new(SshIo::new(channel)))
+    }
+
+    async fn close(&mut self) {
+        let _ = SshBackend::close(self).await;
+    }
+
+    fn connect_notices(&self) -> Vec<String> {
+        self.forwards.failures()
+    }
+}
+
+/// SSH PTY channel driven by the shared I/O loop
+struct SshIo {
+    channel: Channel<Msg>,
+    /// Writes to the channel without borrowing it, so output can still be
+    /// read while input waits for the server to open its window
+    writer: Pin<Box<dyn AsyncWrite + Send>>,
+    /// Messages that arrived while a write was waiting for window space
+    received: VecDeque<Option<ChannelMsg>>,
+}
+
+impl SshIo {
+    fn new(channel: Channel<Msg>) -> Self {
+        let writer = Box::pin(channel.make_writer());
+        Self {
+            channel,
+            writer,
+            received: VecDeque::new(),
+        }
+    }
+
+    /// What a channel message means for the terminal; None for protocol
+    /// traffic (`WindowAdjusted`, `Success`, etc.) that needs no handling
+    fn handle(msg: Option<ChannelMsg>) -> Option<BackendRead> {
+        match msg {
+            // Stdout and stderr both go to the terminal
+            Some(ChannelMsg::Data { data }) | Some(ChannelMsg::ExtendedData { data, .. }) => {
+                Some(BackendRead::Output(data.to_vec()))
+            }
+            Some(ChannelMsg::Eof) => {
+                tracing::info!("SSH channel EOF");
+                Some(BackendRead::Closed(None))
+            }
+            Some(ChannelMsg::Close) => {
+                tracing::info!("SSH channel closed");
+                Some(BackendRead::Closed(None))
+            }
+            Some(ChannelMsg::ExitStatus { exit_status }) => {
+                tracing::info!("Remote process exited with status: {}", exit_status);
+                Some(BackendRead::Exited(exit_status))
+            }
+            Some(_) => None,
+            None => {
+                tracing::info!("SSH channel closed (None)");
+                Some(BackendRead::Closed(None))
+            }
+        }
+    }
+
+    /// Whether a message ends the session
+    fn is_final(msg: &Option<ChannelMsg>) -> bool {
+        matches!(
+            msg,
+            None | Some(ChannelMsg::Eof) | Some(ChannelMsg::Close) | Some(ChannelMsg::ExitStatus { .. })
+        )
+    }
+}
+
+#[async_trait]
+impl BackendIo for SshIo {
+    async fn read(&mut self) -> BackendRead {
+        while let Some(msg) = self.received.pop_front() {
+            if let Some(read) = Self::handle(msg) {
+                return read;
+            }
+        }
+        loop {
+            if let Some(read) = Self::handle(self.channel.wait().await) {
+                return read;
+            }
+        }
+    }
+
+    /// Send input in pieces of at most [`WRITE_CHUNK`] bytes
+    ///
+    /// Once the server's window is used up, the writer waits for it to send
+    /// a window adjust. Meanwhile the channel keeps being read, since output
+    /// left unread (like the echo of a big paste) would back up into the
+    /// session and keep the adjust from ever being processed. What is read
+    /// is held for [`BackendIo::read`]. If the session ends mid-write, the
+    /// rest of the input is dropped.
+    async fn write(&mut self, data: &[u8]) -> Result<(), String> {
+        if self.received.back().is_some_and(Self::is_final) {
+            return Ok(());
+        }
+        let mut rest = data;
+        while !rest.is_empty() {
+            let chunk = &rest[..rest.len().min(WRITE_CHUNK)];
+            tokio::select! {
+                written = self.writer.write(chunk) => {
+                    match written.map_err(|e| e.to_string())? {
+                        0 => return Err("SSH channel closed".to_string()),
+                        n => rest = &rest[n..],
+                    }
+                }
+                msg = self.channel.wait() => {
+                    let last = Self::is_final(&msg);
+                    self.received.push_back(msg);
+                    if last {
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn resize(&mut self, size: super::TerminalSize) -> Result<(), String> {
+        self.channel
+            .window_change(
+                size.cols as u32,
+                size.rows as u32,
+                size.pixel_width as u32,
+                size.pixel_height as u32,
+            )
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn close(&mut self) {
+        let _ = self.channel.eof().await;
+        let _ = self.channel.close().await;
+    }
+}
+
+/// Auth attempts made so far against the server's budget
+struct AuthAttempts {
+    used: usize,
+    budget: usize,
+    /// Attempts the server turned down
+    rejections: usize,
+    /// Key files already offered, so fallbacks don't repeat them
+    offered: Vec<std::path::PathBuf>,
+    /// Methods the server said may continue, as of its last rejection
+    remaining: Option<Vec<MethodKind>>,
+}
+
+impl AuthAttempts {
+    fn new(budget: usize) -> Self {
+        Self {
+            used: 0,
+            budget,
+            rejections: 0,
+            offered: Vec::new(),
+            remaining: None,
+        }
+    }
+
+    fn exhausted(&self) -> bool {
+        self.used >= self.budget
+    }
+
+    /// Remember which methods the server still accepts after a rejection
+    fn rejected(&mut self, remaining_methods: &MethodSet) {
+        self.rejections += 1;
+        self.remaining = Some(remaining_methods.iter().cloned().collect());
+    }
+
+    /// Whether the server still accepts `method`; assumed so until it says otherwise
+    fn server_allows(&self, method: AuthFallback) -> bool {
+        let kind = match method {
+            AuthFallback::KeyboardInteractive => MethodKind::KeyboardInteractive,
+            AuthFallback::Password => MethodKind::Password,
+        };
+        self.remaining.as_ref().map(|methods| methods.contains(&kind)).unwrap_or(true)
+    }
+}
+
+/// Map an error from an auth request, recognising the server giving up on us
+///
+/// A bare disconnect only counts as that once the server has turned down an
+/// attempt; before then it's the connection failing.
+fn auth_error(e: russh::Error, attempts: &AuthAttempts) -> SshError {
+    let message = e.to_string();
+    let disconnected = matches!(e, russh::Error::Disconnect);
+    if message.contains("Too many authentication failures") || (disconnected && attempts.rejections > 0) {
+        SshError::TooManyAuthFailures(attempts.used)
+    } else if disconnected {
+        SshError::ConnectionFailed(message)
+    } else {
+        SshError::AuthenticationFailed(message)
+    }
+}
+
+/// Offer one key file to the server, counting it against the budget
+///
+/// An encrypted key without a passphrase has one asked for through
+/// `prompter`, if given. Returns `Ok(false)` without contacting the server if
+/// the budget is spent, the key was already offered or the passphrase prompt
+/// was cancelled.
+async fn offer_key(
+    session: &mut Handle<SshClientHandler>,
+    username: &str,
+    path: &Path,
+    passphrase: Option<&str>,
+    prompter: Option<&AuthPrompter>,
+    attempts: &mut AuthAttempts,
+) -> SshResult<bool> {
+    let path = expand_home(path);
+    if attempts.offered.contains(&path) {
+        return Ok(false);
+    }
+    if attempts.exhausted() {
+        tracing::warn!("Auth attempt budget ({}) spent, not offering {:?}", attempts.budget, path);
+        return Ok(false);
+    }
+
+    let key = match (load_private_key(&path, passphrase), prompter) {
+        (Err(SshError::KeyEncrypted(_)), Some(prompter)) => {
+            let prompt = format!("Enter passphrase for key '{}': ", path.display());
+            let Some(passphrase) = prompter.ask(prompt, false).await else {
+                return Ok(false);
+            };
+            load_private_key(&path, Some(&passphrase))?
+        }
+        (result, _) => result?,
+    };
+    attempts.used += 1;
+    attempts.offered.push(path.clone());
+
+    let key_with_hash = russh::keys::PrivateKeyWithHashAlg::new(Arc::new(key), None);
+    match session.authenticate_publickey(username, key_with_hash).await {
+        Ok(result) if result.success() => {
+            tracing::info!("Authenticated with key: {:?}", path);
+            Ok(true)
+        }
+        Ok(result) => {
+            tracing::info!("Key {:?} rejected ({}/{} attempts): {:?}", path, attempts.used, attempts.budget, result);
+            if let AuthResult::Failure { remaining_methods, .. } = &result {
+                attempts.rejected(remaining_methods);
+            }
+            Ok(false)
+        }
+        Err(e) => {
+            tracing::error!("Key auth error: {}", e);
+            Err(auth_error(e, attempts))
+        }
+    }
+}
+
+/// Send a password asked for through `prompter`
+///
+/// Returns None if the user cancelled the prompt.
+async fn prompted_password(
+    session: &mut Handle<SshClientHandler>,
+    username: &str,
+    prompter: &AuthPrompter,
+    prompt: String,
+    attempts: &mut AuthAttempts,
+) -> SshResult<Option<bool>> {
+    let Some(password) = prompter.ask(prompt, false).await else {
+        return Ok(None);
+    };
+    attempts.used += 1;
+    match session.authenticate_password(username, password).await {
+        Ok(AuthResult::Success) => {
+            tracing::info!("Authenticated with prompted password");
+            Ok(Some(true))
+        }
+        Ok(AuthResult::Failure { remaining_methods, .. }) => {
+            attempts.rejected(&remaining_methods);
+            Ok(Some(false))
+        }
+        Err(e) => Err(auth_error(e, attempts)),
+    }
+}
+
+/// Run a keyboard-interactive exchange, asking each of the server's
+/// questions through `prompter`
+///
+/// `notice` is shown above the first question. Returns None if the user
+/// cancelled a prompt.
+async fn keyboard_interactive(
+    session: &mut Handle<SshClientHandler>,
+    username: &str,
+    prompter: &AuthPrompter,
+    notice: &mut Option<String>,
+    attempts: &mut AuthAttempts,
+) -> SshResult<Option<bool>> {
+    attempts.used += 1;
+    let mut response = session
+        .authenticate_keyboard_interactive_start(username, None::<String>)
+        .await
+        .map_err(|e| auth_error(e, attempts))?;
+    loop {
+        match response {
+            KeyboardInteractiveAuthResponse::Success => {
+                tracing::info!("Authenticated with keyboard-interactive");
+                return Ok(Some(true));
+            }
+            KeyboardInteractiveAuthResponse::Failure { remaining_methods, .. } => {
+                attempts.rejected(&remaining_methods);
+                return Ok(Some(false));
+            }
+            KeyboardInteractiveAuthResponse::InfoRequest { name, instructions, prompts } => {
+                let mut header = notice.take().unwrap_or_default();
+                for text in [name, instructions] {
+                    if !text.trim().is_empty() {
+                        header.push_str(text.trim_end());
+                        header.push('\n');
+                    }
+                }
+                // Servers may send a request with no questions, which still needs a reply
+                let mut answers = Vec::with_capacity(prompts.len());
+                for prompt in prompts {
+                    let text = format!("{}{}", std::mem::take(&mut header), prompt.prompt);
+                    let Some(answer) = prompter.ask(text, prompt.echo).await else {
+                        return Ok(None);
+                    };
+                    answers.push(answer);
+                }
+                response = session
+                    .authenticate_keyboard_interactive_respond(answers)
+                    .await
+                    .map_err(|e| auth_error(e, attempts))?;
+            }
+        }
+    }
+}
+
+/// Offer each key the agent holds, with the agent doing the signing
+///
+/// Works for keys that never leave the agent, like hardware keys. Returns
+/// `None` if the agent holds no keys, so the caller can try key files
+/// instead. A key the agent fails to sign with is skipped.
+async fn offer_agent_keys<S>(
+    session: &mut Handle<SshClientHandler>,
+    username: &str,
+    agent: &mut russh::keys::agent::client::AgentClient<S>,
+    attempts: &mut AuthAttempts,
+) -> SshResult<Option<bool>>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let identities = agent
+        .request_identities()
+        .await
+        .map_err(|e| SshError::AuthenticationFailed(format!("Failed to list agent keys: {}", e)))?;
+    if identities.is_empty() {
+        tracing::info!("SSH agent holds no keys, trying default key files");
+        return Ok(None);
+    }
+
+    // RSA keys are signed with SHA-2 where the server supports it
+    let rsa_hash = session.best_supported_rsa_hash().await.ok().flatten().flatten();
+    for key in identities {
+        if attempts.exhausted() {
+            tracing::warn!("Auth attempt budget ({}) spent, not offering more agent keys", attempts.budget);
+            break;
+        }
+        attempts.used += 1;
+
+        let fingerprint = key.fingerprint(HashAlg::Sha256);
+        match session.authenticate_publickey_with(username, key, rsa_hash, agent).await {
+            Ok(result) if result.success() => {
+                tracing::info!("Authenticated with agent key: {}", fingerprint);
+                return Ok(Some(true));
+            }
+            Ok(result) => {
+                tracing::info!(
+                    "Agent key {} rejected ({}/{} attempts): {:?}",
+                    fingerprint,
+                    attempts.used,
+                    attempts.budget,
+                    result
+                );
+                if let AuthResult::Failure { remaining_methods, .. } = &result {
+                    attempts.rejected(remaining_methods);
+                }
+            }
+            Err(e) => tracing::warn!("SSH agent could not sign with key {}: {}", fingerprint, e),
+        }
+    }
+    Ok(Some(false))
+}
+
+/// Offer the default ~/.ssh keys that exist, within the remaining budget
+async fn offer_default_keys(
+    session: &mut Handle<SshClientHandler>,
+    username: &str,
+    attempts: &mut AuthAttempts,
+) -> SshResult<bool> {
+    let home = dirs::home_dir().ok_or_else(|| {
+        SshError::AuthenticationFailed("Could not determine home directory".to_string())
+    })?;
+
+    for key_name in DEFAULT_KEY_NAMES {
+        let key_path = home.join(".ssh").join(key_name);
+        if !key_path.exists() {
+            continue;
+        }
+        match offer_key(session, username, &key_path, None, None, attempts).await {
+            Ok(true) => return Ok(true),
+            Err(e @ SshError::TooManyAuthFailures(_)) => return Err(e),
+            // Unreadable or passphrase-protected default keys are simply skipped
+            _ => {}
+        }
+    }
+    Ok(false)
+}
+
+/// Expand a leading `~` to the home directory
+pub(crate) fn expand_home(path: &Path) -> std::path::PathBuf {
+    if path.starts_with("~") {
+        if let Some(home) = dirs::home_dir() {
+            return home.join(path.strip_prefix("~").unwrap());
+        }
+    }
+    path.to_path_buf()
+}
+
 /// Load a private key from a file
 fn load_private_key(
     path: &Path,
     passphrase: Option<&str>,
 ) -> SshResult<russh::keys::PrivateKey> {
-    // Expand ~ in path
-    let path = if path.starts_with("~") {
-        if let Some(home) = dirs::home_dir() {
-            home.join(path.strip_prefix("~").unwrap())
-        } else {
-            path.to_path_buf()
-        }
-    } else {
-        path.to_path_buf()
-    };
+    let path = expand_home(path);
 
     let key_data = std::fs::read_to_string(&path)
         .map_err(|e| SshError::AuthenticationFailed(format!("Failed to read key file: {}", e)))?;
 
-    russh::keys::decode_secret_key(&key_data, passphrase).map_err(|e| {
-        SshError::AuthenticationFailed(format!("Failed to decode private key: {}", e))
+    russh::keys::decode_secret_key(&key_data, passphrase).map_err(|e| match e {
+        russh::keys::Error::KeyIsEncrypted => SshError::KeyEncrypted(path.display().to_string()),
+        e => SshError::AuthenticationFailed(format!("Failed to decode private key: {}", e)),
     })
 }
 
@@ -949,4 +2048,156 @@ mod tests {
         assert_eq!(backend.state(), ConnectionState::Disconnected);
         assert!(!backend.is_alive());
     }
+
+    #[test]
+    fn test_force_prompt_skips_pooled_connection() {
+        let session = SshSession::new("test".to_string(), "localhost".to_string(), "user".to_string());
+        let mut backend = SshBackend::new(session);
+        assert!(!backend.may_reuse_pooled());
+
+        backend.share_connection(SshConnectionPool::new(), Uuid::new_v4());
+        assert!(backend.may_reuse_pooled());
+
+        backend.set_force_prompt(true);
+        assert!(!backend.may_reuse_pooled());
+        assert!(backend.acquire_pooled().is_none());
+    }
+
+    #[test]
+    fn test_auth_attempt_budget() {
+        let mut attempts = AuthAttempts::new(MAX_AUTH_ATTEMPTS);
+        assert!(!attempts.exhausted());
+        attempts.used = MAX_AUTH_ATTEMPTS;
+        assert!(attempts.exhausted());
+
+        let err = SshError::TooManyAuthFailures(attempts.used);
+        assert!(err.to_string().starts_with("Too many authentication failures"));
+    }
+
+    #[test]
+    fn test_disconnect_during_auth() {
+        let mut attempts = AuthAttempts::new(MAX_AUTH_ATTEMPTS);
+        attempts.used = 1;
+        assert!(matches!(
+            auth_error(russh::Error::Disconnect, &attempts),
+            SshError::ConnectionFailed(_)
+        ));
+
+        attempts.used = 2;
+        attempts.rejections = 1;
+        assert!(matches!(
+            auth_error(russh::Error::Disconnect, &attempts),
+            SshError::TooManyAuthFailures(2)
+        ));
+    }
+
+    #[test]
+    fn test_fallback_follows_server_methods() {
+        let mut attempts = AuthAttempts::new(MAX_AUTH_ATTEMPTS);
+        // Nothing heard from the server yet
+        assert!(attempts.server_allows(AuthFallback::Password));
+
+        attempts.remaining = Some(vec![MethodKind::PublicKey, MethodKind::KeyboardInteractive]);
+        assert!(attempts.server_allows(AuthFallback::KeyboardInteractive));
+        assert!(!attempts.server_allows(AuthFallback::Password));
+    }
+
+    #[test]
+    fn test_strip_host_entries() {
+        let contents = "\
+# comment mentioning db.example.com
+db.example.com ssh-ed25519 AAAAold
+web.example.com,db.example.com ssh-rsa AAAAshared
+[db.example.com]:2222 ssh-ed25519 AAAAport
+*.example.com ssh-ed25519 AAAAwild
+db.example.com.evil ssh-ed25519 AAAAother
+|1|WkHvFlgrgvD3lsK8zLuOAQoZYGc=|9x2M8BWG5/7ZkU0HGhD1g8i2vfw= ssh-ed25519 AAAAhashed
+|1|AQIDBAUGBwgJCgsMDQ4PEBESExQ=|iYa4+uOFt89dlRDRVGHvdqFn7NM= ssh-ed25519 AAAAhashedport
+";
+        let (kept, removed) = strip_host_entries(contents, "db.example.com");
+        assert_eq!(removed, 4);
+        assert_eq!(
+            kept,
+            "# comment mentioning db.example.com\n*.example.com ssh-ed25519 AAAAwild\ndb.example.com.evil ssh-ed25519 AAAAother\n\
+             |1|AQIDBAUGBwgJCgsMDQ4PEBESExQ=|iYa4+uOFt89dlRDRVGHvdqFn7NM= ssh-ed25519 AAAAhashedport\n"
+        );
+        assert_eq!(strip_host_entries(contents, "unknown").1, 0);
+    }
+
+    #[test]
+    fn test_hashed_host_matches() {
+        // Written by `ssh-keygen -H` for db.example.com
+        let hashed = "|1|WkHvFlgrgvD3lsK8zLuOAQoZYGc=|9x2M8BWG5/7ZkU0HGhD1g8i2vfw=";
+        assert!(hashed_host_matches(hashed, "db.example.com"));
+        assert!(!hashed_host_matches(hashed, "web.example.com"));
+        assert!(host_matches(&format!("{},10.0.0.9", hashed), "db.example.com"));
+
+        // HMAC-SHA1 of "[db.example.com]:2222" with salt 0x01..0x14
+        let with_port = "|1|AQIDBAUGBwgJCgsMDQ4PEBESExQ=|iYa4+uOFt89dlRDRVGHvdqFn7NM=";
+        assert!(hashed_host_matches(with_port, "[db.example.com]:2222"));
+        assert!(!hashed_host_matches(with_port, "db.example.com"));
+
+        assert!(!hashed_host_matches("|1|not base64|9x2M8BWG5/7ZkU0HGhD1g8i2vfw=", "db.example.com"));
+        assert!(!hashed_host_matches("|2|WkHvFlgrgvD3lsK8zLuOAQoZYGc=|9x2M8BWG5/7ZkU0HGhD1g8i2vfw=", "db.example.com"));
+    }
+
+    #[test]
+    fn test_known_hosts_status() {
+        let known_hosts = "\
+# comment
+web.example.com,10.0.0.1 ssh-ed25519 AAAAkey1
+[db.example.com]:2222 ssh-rsa AAAArsa
+*.lab ssh-ed25519 AAAAlab
+|1|WkHvFlgrgvD3lsK8zLuOAQoZYGc=|9x2M8BWG5/7ZkU0HGhD1g8i2vfw= ssh-ed25519 AAAAhashed
+";
+        let status = |host, key_type, key| known_hosts_status(known_hosts, host, key_type, key);
+        assert_eq!(status("web.example.com", "ssh-ed25519", "AAAAkey1"), HostKeyStatus::Verified);
+        assert_eq!(status("10.0.0.1", "ssh-ed25519", "AAAAkey1"), HostKeyStatus::Verified);
+        assert_eq!(status("web.example.com", "ssh-ed25519", "AAAAother"), HostKeyStatus::Mismatch);
+        // Another key type for a known host isn't a mismatch
+        assert_eq!(status("web.example.com", "ssh-rsa", "AAAAother"), HostKeyStatus::TrustOnFirstUse);
+        assert_eq!(status("db.example.com", "ssh-rsa", "AAAArsa"), HostKeyStatus::Verified);
+        assert_eq!(status("box.lab", "ssh-ed25519", "AAAAlab"), HostKeyStatus::Verified);
+        // Hashed entries are checked too, not treated as a new host
+        assert_eq!(status("db.example.com", "ssh-ed25519", "AAAAhashed"), HostKeyStatus::Verified);
+        assert_eq!(status("db.example.com", "ssh-ed25519", "AAAAforged"), HostKeyStatus::Mismatch);
+        assert_eq!(status("new.example.com", "ssh-ed25519", "AAAAkey1"), HostKeyStatus::TrustOnFirstUse);
+    }
+
+    #[test]
+    fn test_exec_results_table() {
+        let ok = ExecOutput {
+            stdout: b"\n 10:00 up 3 days\n".to_vec(),
+            stderr: Vec::new(),
+            exit_status: Some(0),
+        };
+        let failed = ExecOutput {
+            stdout: b"partial".to_vec(),
+            stderr: b"df: /mnt: No such file\n".to_vec(),
+            exit_status: Some(1),
+        };
+        assert!(ok.success());
+        assert!(!failed.success());
+        assert_eq!(failed.text(), "partial\ndf: /mnt: No such file\n");
+
+        let results = vec![
+            ("web-1".to_string(), Ok(ok)),
+            ("database".to_string(), Ok(failed)),
+            ("db".to_string(), Err("Connection refused".to_string())),
+        ];
+        assert_eq!(
+            exec_results_table(&results),
+            "HOST      EXIT    OUTPUT\n\
+             web-1     0        10:00 up 3 days\n\
+             database  1       partial\n\
+             db        error   Connection refused\n"
+        );
+    }
+
+    #[test]
+    fn test_expand_home() {
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(expand_home(Path::new("~/.ssh/id_rsa")), home.join(".ssh/id_rsa"));
+        assert_eq!(expand_home(Path::new("/etc/key")), Path::new("/etc/key"));
+    }
 }
@@ -0,0 +1,286 @@
+//! Port forwarding over SSH, like OpenSSH's `-L` and `-R`
+//!
+//! Local forwards listen on this machine and open a `direct-tcpip` channel
+//! to the target for every connection. Remote forwards ask the server to
+//! listen; the connections it accepts arrive as `forwarded-tcpip` channels,
+//! which the client handler hands to `serve_remote`.
+//!
+//! Agent forwarding, like `-A`, works the same way: the server opens an
+//! `auth-agent@openssh.com` channel and `serve_agent` connects it to the
+//! local agent.
+
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use russh::client::{Handle, Msg};
+use russh::Channel;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+use crate::session::models::{ForwardDirection, PortForward};
+use super::ssh_backend::SshClientHandler;
+
+/// How one forward is doing
+#[derive(Debug, Clone)]
+pub struct ForwardState {
+    pub forward: PortForward,
+    /// Why it couldn't be set up or stopped; `None` while it runs
+    pub error: Option<String>,
+}
+
+/// The forwards set up on one connection
+///
+/// Cloning gives another handle to the same forwards, so the UI can show
+/// their state while the backend owns them.
+#[derive(Clone, Default)]
+pub struct PortForwards(Arc<Mutex<Forwards>>);
+
+#[derive(Default)]
+struct Forwards {
+    states: Vec<ForwardState>,
+    /// Accept loops of the local forwards
+    listeners: Vec<JoinHandle<()>>,
+}
+
+impl PortForwards {
+    /// Every forward with its state
+    pub fn states(&self) -> Vec<ForwardState> {
+        self.0.lock().states.clone()
+    }
+
+    /// Number of forwards that are up
+    pub fn running(&self) -> usize {
+        self.0.lock().states.iter().filter(|state| state.error.is_none()).count()
+    }
+
+    /// Number of forwards that failed to start or stopped on their own
+    pub fn failed(&self) -> usize {
+        self.0.lock().states.iter().filter(|state| state.error.is_some()).count()
+    }
+
+    /// Start `forwards` over `session`, replacing any started before
+    ///
+    /// A forward that can't be set up, e.g. because its port is taken, is
+    /// recorded with the reason; the others still start.
+    pub(super) async fn start(&self, session: &Arc<Handle<SshClientHandler>>, forwards: &[PortForward]) {
+        self.abort();
+        let mut states = Vec::with_capacity(forwards.len());
+        let mut listeners = Vec::new();
+        for forward in forwards {
+            let result = match forward.direction {
+                ForwardDirection::Local => TcpListener::bind((forward.bind_address.as_str(), forward.bind_port))
+                    .await
+                    .map(|listener| {
+                        let slot = (Arc::downgrade(&self.0), states.len());
+                        listeners.push(tokio::spawn(run_local(listener, Arc::downgrade(session), forward.clone(), slot)));
+                    })
+                    .map_err(|e| e.to_string()),
+                ForwardDirection::Remote => session
+                    .tcpip_forward(forward.bind_address.clone(), forward.bind_port as u32)
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| format!("the server refused it ({})", e)),
+            };
+            match &result {
+                Ok(()) => tracing::info!("Port forward {} started", forward.label()),
+                Err(e) => tracing::warn!("Port forward {} failed: {}", forward.label(), e),
+            }
+            states.push(ForwardState {
+                forward: forward.clone(),
+                error: result.err(),
+            });
+        }
+
+        let mut inner = self.0.lock();
+        inner.states = states;
+        inner.listeners = listeners;
+    }
+
+    /// Stop the forwards, asking the server to stop listening for the remote ones
+    ///
+    /// Connections already forwarded carry on until either end closes them.
+    pub(super) async fn stop(&self, session: &Handle<SshClientHandler>) {
+        let states = std::mem::take(&mut self.0.lock().states);
+        self.abort();
+        for state in states {
+            let forward = state.forward;
+            if forward.direction == ForwardDirection::Remote && state.error.is_none() {
+                let _ = session
+                    .cancel_tcpip_forward(forward.bind_address.clone(), forward.bind_port as u32)
+                    .await;
+            }
+        }
+    }
+
+    /// Stop listening locally and forget the forwards, for a connection that
+    /// is already gone
+    pub(super) fn abort(&self) {
+        let mut inner = self.0.lock();
+        for listener in inner.listeners.drain(..) {
+            listener.abort();
+        }
+        inner.states.clear();
+    }
+
+    /// Messages about forwards that failed to start
+    pub(super) fn failures(&self) -> Vec<String> {
+        self.0
+            .lock()
+            .states
+            .iter()
+            .filter_map(|state| {
+                let error = state.error.as_ref()?;
+                Some(format!("Port forward {} failed: {}", state.forward.label(), error))
+            })
+            .collect()
+    }
+}
+
+/// Delay after the first failed `accept`, doubled on each further failure
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+const ACCEPT_RETRY_MAX_DELAY: Duration = Duration::from_secs(5);
+/// Failed `accept`s in a row before a local forward gives up
+const ACCEPT_MAX_FAILURES: u32 = 10;
+
+/// Accept connections for a local forward and tunnel each to its target
+///
+/// Failing `accept`s (e.g. out of file descriptors) are retried with a
+/// growing delay; if they keep failing the forward stops and its state in
+/// `slot` records why.
+async fn run_local(
+    listener: TcpListener,
+    session: Weak<Handle<SshClientHandler>>,
+    forward: PortForward,
+    slot: (Weak<Mutex<Forwards>>, usize),
+) {
+    let mut failures = 0;
+    loop {
+        let (socket, peer) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                failures += 1;
+                if failures >= ACCEPT_MAX_FAILURES {
+                    tracing::error!("Port forward {} stopped: could not accept connections: {}", forward.label(), e);
+                    if let Some(forwards) = slot.0.upgrade() {
+                        if let Some(state) = forwards.lock().states.get_mut(slot.1) {
+                            state.error = Some(format!("stopped accepting connections ({})", e));
+                        }
+                    }
+                    break;
+                }
+                let delay = ACCEPT_RETRY_DELAY
+                    .saturating_mul(1 << (failures - 1))
+                    .min(ACCEPT_RETRY_MAX_DELAY);
+                tracing::warn!(
+                    "Port forward {} could not accept a connection, retrying in {:?}: {}",
+                    forward.label(),
+                    delay,
+                    e
+                );
+                tokio::time::sleep(delay).await;
+                continue;
+            }
+        };
+        failures = 0;
+        // The connection was closed without stopping us
+        let Some(session) = session.upgrade() else {
+            break;
+        };
+        let forward = forward.clone();
+        tokio::spawn(async move {
+            let channel = session
+                .channel_open_direct_tcpip(
+                    forward.target_host.clone(),
+                    forward.target_port as u32,
+                    peer.ip().to_string(),
+                    peer.port() as u32,
+                )
+                .await;
+            drop(session);
+            match channel {
+                Ok(channel) => pipe(socket, channel).await,
+                Err(e) => tracing::warn!(
+                    "Port forward {}: the server could not reach {}:{}: {}",
+                    forward.label(),
+                    forward.target_host,
+                    forward.target_port,
+                    e
+                ),
+            }
+        });
+    }
+}
+
+/// Connect a `forwarded-tcpip` channel the server opened to its forward's target
+///
+/// `address` and `port` are where the server accepted the connection. The
+/// channel is closed if no remote forward listens there or the target can't
+/// be reached.
+pub(super) fn serve_remote(channel: Channel<Msg>, forwards: &[PortForward], address: &str, port: u32) {
+    // Servers report the address as it was requested, so prefer an exact match
+    let target = forwards
+        .iter()
+        .filter(|forward| forward.direction == ForwardDirection::Remote && forward.bind_port as u32 == port)
+        .min_by_key(|forward| forward.bind_address != address)
+        .map(|forward| (forward.target_host.clone(), forward.target_port));
+    let address = address.to_string();
+
+    tokio::spawn(async move {
+        let Some((host, target_port)) = target else {
+            tracing::warn!("Server forwarded a connection for {}:{}, which isn't a remote forward", address, port);
+            let _ = channel.close().await;
+            return;
+        };
+        match TcpStream::connect((host.as_str(), target_port)).await {
+            Ok(socket) => pipe(socket, channel).await,
+            Err(e) => {
+                tracing::warn!("Remote port forward could not reach {}:{}: {}", host, target_port, e);
+                let _ = channel.close().await;
+            }
+        }
+    });
+}
+
+/// Connect an agent channel the server opened to the local SSH agent
+///
+/// The agent is found through `SSH_AUTH_SOCK`, or on Windows the OpenSSH
+/// agent's named pipe. The channel is closed if there is no agent.
+pub(super) fn serve_agent(channel: Channel<Msg>) {
+    tokio::spawn(async move {
+        let mut agent = match connect_agent().await {
+            Ok(agent) => agent,
+            Err(e) => {
+                tracing::warn!("Server asked for the SSH agent, but it can't be reached: {}", e);
+                let _ = channel.close().await;
+                return;
+            }
+        };
+        let mut stream = Box::pin(channel.into_stream());
+        if let Err(e) = tokio::io::copy_bidirectional(&mut agent, &mut stream).await {
+            tracing::debug!("Forwarded agent connection ended: {}", e);
+        }
+    });
+}
+
+#[cfg(unix)]
+async fn connect_agent() -> std::io::Result<tokio::net::UnixStream> {
+    let path = std::env::var_os("SSH_AUTH_SOCK")
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "SSH_AUTH_SOCK is not set"))?;
+    tokio::net::UnixStream::connect(path).await
+}
+
+#[cfg(windows)]
+async fn connect_agent() -> std::io::Result<tokio::net::windows::named_pipe::NamedPipeClient> {
+    let path = std::env::var_os("SSH_AUTH_SOCK").unwrap_or_else(|| r"\\.\pipe\openssh-ssh-agent".into());
+    tokio::net::windows::named_pipe::ClientOptions::new().open(path)
+}
+
+/// Copy between a TCP connection and a channel until both sides are done
+async fn pipe(mut socket: TcpStream, channel: Channel<Msg>) {
+    let _ = socket.set_nodelay(true);
+    let mut stream = Box::pin(channel.into_stream());
+    if let Err(e) = tokio::io::copy_bidirectional(&mut socket, &mut stream).await {
+        tracing::debug!("Forwarded connection ended: {}", e);
+    }
+}
@@ -0,0 +1,138 @@
+//! Connection sharing between tabs of the same session
+//!
+//! Like OpenSSH's `ControlMaster`, a second tab for a saved session opens a
+//! new channel on the first tab's authenticated connection instead of doing
+//! another handshake. Connections are reference counted; the last tab to
+//! close gets the connection back to disconnect it.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use uuid::Uuid;
+
+/// A shared connection and how many tabs are using it
+struct Entry<T> {
+    connection: Arc<T>,
+    users: usize,
+}
+
+/// Authenticated connections keyed by session ID
+///
+/// Cloning gives another handle to the same pool.
+pub struct ConnectionPool<T> {
+    entries: Arc<Mutex<HashMap<Uuid, Entry<T>>>>,
+}
+
+impl<T> Clone for ConnectionPool<T> {
+    fn clone(&self) -> Self {
+        Self {
+            entries: self.entries.clone(),
+        }
+    }
+}
+
+impl<T> Default for ConnectionPool<T> {
+    fn default() -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl<T> ConnectionPool<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start using the pooled connection for `key`, if there is a live one
+    ///
+    /// A connection `is_live` rejects is dropped from the pool.
+    pub fn acquire(&self, key: Uuid, is_live: impl Fn(&T) -> bool) -> Option<Arc<T>> {
+        let mut entries = self.entries.lock();
+        let entry = entries.get_mut(&key)?;
+        if !is_live(&entry.connection) {
+            entries.remove(&key);
+            return None;
+        }
+        entry.users += 1;
+        Some(entry.connection.clone())
+    }
+
+    /// Offer a new connection for others to share
+    ///
+    /// Returns false if another connection for `key` got there first; the
+    /// caller then keeps `connection` to itself.
+    pub fn insert(&self, key: Uuid, connection: Arc<T>) -> bool {
+        let mut entries = self.entries.lock();
+        if entries.contains_key(&key) {
+            return false;
+        }
+        entries.insert(key, Entry { connection, users: 1 });
+        true
+    }
+
+    /// Stop using a pooled connection
+    ///
+    /// Returns the connection when this was its last user, so the caller can
+    /// disconnect it. A connection that has since left the pool is ignored.
+    pub fn release(&self, key: Uuid, connection: &Arc<T>) -> Option<Arc<T>> {
+        let mut entries = self.entries.lock();
+        let entry = entries.get_mut(&key)?;
+        if !Arc::ptr_eq(&entry.connection, connection) {
+            return None;
+        }
+        entry.users -= 1;
+        if entry.users > 0 {
+            return None;
+        }
+        entries.remove(&key).map(|entry| entry.connection)
+    }
+
+    /// Number of tabs using the pooled connection for `key`
+    pub fn users(&self, key: Uuid) -> usize {
+        self.entries.lock().get(&key).map_or(0, |entry| entry.users)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_until_last_release() {
+        let pool: ConnectionPool<String> = ConnectionPool::new();
+        let key = Uuid::new_v4();
+        assert!(pool.acquire(key, |_| true).is_none());
+
+        let first = Arc::new("conn".to_string());
+        assert!(pool.insert(key, first.clone()));
+        let second = pool.acquire(key, |_| true).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(pool.users(key), 2);
+
+        // A racing connection for the same session stays private
+        assert!(!pool.insert(key, Arc::new("other".to_string())));
+
+        assert!(pool.release(key, &second).is_none());
+        let last = pool.release(key, &first).unwrap();
+        assert!(Arc::ptr_eq(&last, &first));
+        assert_eq!(pool.users(key), 0);
+    }
+
+    #[test]
+    fn test_dead_connections_are_dropped() {
+        let pool: ConnectionPool<String> = ConnectionPool::new();
+        let key = Uuid::new_v4();
+        let dead = Arc::new("dead".to_string());
+        pool.insert(key, dead.clone());
+
+        assert!(pool.acquire(key, |_| false).is_none());
+        assert_eq!(pool.users(key), 0);
+        // Its remaining user releasing it later doesn't touch a newer entry
+        let fresh = Arc::new("fresh".to_string());
+        assert!(pool.insert(key, fresh));
+        assert!(pool.release(key, &dead).is_none());
+        assert_eq!(pool.users(key), 1);
+    }
+}
@@ -4,14 +4,22 @@
 //! to EC2 instances and on-premises managed instances via AWS SSM.
 //!
 //! Protocol reference: AWS Session Manager Plugin source code
+//!
+//! Sessions with a port forward start the `AWS-StartPortForwardingSession`
+//! document instead of a shell and carry a local TCP connection over the
+//! same data channel (see [`SsmPortIo`]).
 
 use aws_config::BehaviorVersion;
 use aws_sdk_ssm::Client as SsmClient;
+use async_trait::async_trait;
+use futures::stream::{SplitSink, SplitStream};
 use futures::{SinkExt, StreamExt};
 use sha2::{Digest, Sha256};
+use std::sync::Arc;
 use std::time::Duration;
 use thiserror::Error;
-use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
 use tokio_tungstenite::{
     connect_async,
     tungstenite::Message as WsMessage,
@@ -19,7 +27,10 @@ use tokio_tungstenite::{
 };
 use uuid::Uuid;
 
+use crate::metrics::{metrics, ConnectionKind};
 use crate::session::SsmSession;
+use super::backend::{BackendIo, BackendRead, ConnectError, TerminalBackend};
+use super::ssh_backend::{INITIAL_RECONNECT_DELAY_SECS, MAX_RECONNECT_ATTEMPTS};
 use super::TerminalSize;
 
 /// SSM WebSocket message types
@@ -39,8 +50,20 @@ mod payload_type {
     pub const SIZE_DATA: u32 = 3;
     pub const HANDSHAKE_REQUEST: u32 = 5;
     pub const HANDSHAKE_COMPLETE: u32 = 6;
+    pub const FLAG: u32 = 10;
+}
+
+/// Flags exchanged during basic port forwarding
+mod port_flag {
+    /// Sent when the local connection closes, so the agent drops its own
+    pub const DISCONNECT_TO_PORT: u32 = 1;
+    /// Sent by the agent when it can't connect to the port on the instance
+    pub const CONNECT_TO_PORT_ERROR: u32 = 3;
 }
 
+/// SSM document that tunnels a port instead of starting a shell
+const PORT_FORWARDING_DOCUMENT: &str = "AWS-StartPortForwardingSession";
+
 /// Errors that can occur during SSM operations
 #[derive(Debug, Error)]
 pub enum SsmError {
@@ -261,6 +284,16 @@ fn build_size_message(sequence_number: i64, cols: u16, rows: u16) -> Vec<u8> {
     )
 }
 
+/// Build a port forwarding flag message
+fn build_flag_message(sequence_number: i64, flag: u32) -> Vec<u8> {
+    build_ssm_message(
+        message_type::INPUT_STREAM_DATA,
+        sequence_number,
+        payload_type::FLAG,
+        &flag.to_be_bytes(),
+    )
+}
+
 /// Build an input data message
 fn build_input_message(sequence_number: i64, data: &[u8]) -> Vec<u8> {
     build_ssm_message(
@@ -281,16 +314,16 @@ pub struct SsmBackend {
     size: TerminalSize,
     /// Outgoing sequence number counter
     sequence_number: i64,
-    /// Channel for sending write requests
-    write_tx: Option<UnboundedSender<Vec<u8>>>,
-    /// Channel for sending resize requests
-    resize_tx: Option<UnboundedSender<TerminalSize>>,
+    /// WebSocket opened by the trait `connect`, until taken for I/O
+    ws: Option<SsmWebSocket>,
     /// Stream URL from StartSession response
     stream_url: Option<String>,
     /// Token from StartSession response
     token: Option<String>,
     /// Session ID from StartSession response
     session_id: Option<String>,
+    /// Local end of a port forward; kept across reconnects
+    listener: Option<Arc<TcpListener>>,
 }
 
 impl SsmBackend {
@@ -301,11 +334,11 @@ impl SsmBackend {
             state: ConnectionState::Disconnected,
             size: TerminalSize::new(80, 24),
             sequence_number: 0,
-            write_tx: None,
-            resize_tx: None,
+            ws: None,
             stream_url: None,
             token: None,
             session_id: None,
+            listener: None,
         }
     }
 
@@ -327,33 +360,21 @@ impl SsmBackend {
     pub async fn connect(&mut self) -> SsmResult<()> {
         self.state = ConnectionState::Connecting;
 
-        // Build AWS config
-        let mut config_loader = aws_config::defaults(BehaviorVersion::latest());
-
-        // Apply profile if specified
-        if let Some(ref profile) = self.config.profile {
-            config_loader = config_loader.profile_name(profile);
-        }
-
-        // Apply region if specified
-        if let Some(ref region) = self.config.region {
-            config_loader = config_loader.region(aws_sdk_ssm::config::Region::new(region.clone()));
-        }
-
-        let aws_config = config_loader.load().await;
-
-        // Create SSM client
-        let ssm_client = SsmClient::new(&aws_config);
+        let ssm_client = self.client().await;
 
         tracing::info!("Starting SSM session to instance: {}", self.config.instance_id);
 
         // Call StartSession API
+        let mut request = ssm_client.start_session().target(&self.config.instance_id);
+        if let Some(forward) = self.config.port_forward {
+            request = request
+                .document_name(PORT_FORWARDING_DOCUMENT)
+                .parameters("portNumber", vec![forward.remote_port.to_string()])
+                .parameters("localPortNumber", vec![forward.local_port.to_string()]);
+        }
         let start_session_result = tokio::time::timeout(
             Duration::from_secs(30),
-            ssm_client
-                .start_session()
-                .target(&self.config.instance_id)
-                .send(),
+            request.send(),
         )
         .await
         .map_err(|_| SsmError::Timeout("StartSession API call timed out".into()))?
@@ -395,25 +416,39 @@ impl SsmBackend {
         Ok(())
     }
 
-    /// Set up the write and resize channels for I/O
-    ///
-    /// Returns receivers that should be used by the I/O loop.
-    pub fn setup_channels(&mut self) -> (UnboundedReceiver<Vec<u8>>, UnboundedReceiver<TerminalSize>) {
-        let (write_tx, write_rx) = tokio::sync::mpsc::unbounded_channel();
-        let (resize_tx, resize_rx) = tokio::sync::mpsc::unbounded_channel();
-        self.write_tx = Some(write_tx);
-        self.resize_tx = Some(resize_tx);
-        (write_rx, resize_rx)
-    }
+    /// SSM client for the session's profile and region
+    async fn client(&self) -> SsmClient {
+        // Build AWS config
+        let mut config_loader = aws_config::defaults(BehaviorVersion::latest());
+
+        // Apply profile if specified
+        if let Some(ref profile) = self.config.profile {
+            config_loader = config_loader.profile_name(profile);
+        }
+
+        // Apply region if specified
+        if let Some(ref region) = self.config.region {
+            config_loader = config_loader.region(aws_sdk_ssm::config::Region::new(region.clone()));
+        }
 
-    /// Get the write sender for sending data without locking
-    pub fn get_write_sender(&self) -> Option<UnboundedSender<Vec<u8>>> {
-        self.write_tx.clone()
+        let aws_config = config_loader.load().await;
+        SsmClient::new(&aws_config)
     }
 
-    /// Get the resize sender for sending resize requests without locking
-    pub fn get_resize_sender(&self) -> Option<UnboundedSender<TerminalSize>> {
-        self.resize_tx.clone()
+    /// End the current session on the AWS side, if one was started
+    ///
+    /// Otherwise it lingers, counting against the instance's session limit,
+    /// until SSM times it out. Failures are only logged.
+    async fn terminate_session(&mut self) {
+        let Some(session_id) = self.session_id.take() else {
+            return;
+        };
+        let request = self.client().await.terminate_session().session_id(&session_id).send();
+        match tokio::time::timeout(Duration::from_secs(10), request).await {
+            Ok(Ok(_)) => tracing::info!("Terminated SSM session {}", session_id),
+            Ok(Err(e)) => tracing::warn!("Failed to terminate SSM session {}: {}", session_id, e),
+            Err(_) => tracing::warn!("Terminating SSM session {} timed out", session_id),
+        }
     }
 
     /// Get the stream URL for WebSocket connection
@@ -455,11 +490,62 @@ impl SsmBackend {
         &self.config
     }
 
+    /// Start a new session after the WebSocket dropped, with exponential backoff
+    ///
+    /// SSM sessions can't be picked up again once their stream is gone, so
+    /// this terminates the old session, asks for a fresh session token and
+    /// URL and opens a new WebSocket, ready to be taken for I/O.
+    pub async fn reconnect(&mut self) -> SsmResult<()> {
+        let mut delay_secs = INITIAL_RECONNECT_DELAY_SECS;
+        let mut last_error = None;
+
+        for attempt in 1..=MAX_RECONNECT_ATTEMPTS {
+            tracing::info!(
+                "Reconnection attempt {}/{} to {} (waiting {}s)",
+                attempt,
+                MAX_RECONNECT_ATTEMPTS,
+                self.description(),
+                delay_secs
+            );
+            tokio::time::sleep(Duration::from_secs(delay_secs)).await;
+            metrics().add_reconnect(ConnectionKind::Ssm);
+
+            // End the old session (or the last attempt's); its token is no
+            // good for a new stream
+            self.terminate_session().await;
+            self.ws = None;
+            self.stream_url = None;
+            self.token = None;
+            self.sequence_number = 0;
+            self.state = ConnectionState::Disconnected;
+
+            let result = match SsmBackend::connect(self).await {
+                Ok(()) => connect_websocket(self).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(ws) => {
+                    tracing::info!("Reconnection successful on attempt {}", attempt);
+                    self.ws = Some(ws);
+                    return Ok(());
+                }
+                Err(e) => {
+                    tracing::warn!("Reconnection attempt {} failed: {}", attempt, e);
+                    last_error = Some(e);
+                    delay_secs *= 2;
+                }
+            }
+        }
+
+        self.state = ConnectionState::Failed;
+        Err(last_error.unwrap_or(SsmError::NotConnected))
+    }
+
     /// Close the SSM session
     pub async fn close(&mut self) -> SsmResult<()> {
         self.state = ConnectionState::Disconnecting;
-        // Note: The WebSocket will be closed by the I/O loop
-        // We might want to call TerminateSession API here for cleanup
+        // The WebSocket is closed by the I/O loop
+        self.terminate_session().await;
         self.state = ConnectionState::Disconnected;
         Ok(())
     }
@@ -522,12 +608,14 @@ pub async fn connect_websocket(backend: &mut SsmBackend) -> SsmResult<SsmWebSock
     // The server will send handshake request, we respond with handshake complete
     tracing::info!("SSM authentication complete, starting handshake...");
 
-    // Send initial terminal size
-    let size_msg = build_size_message(backend.next_sequence(), 80, 24);
-    ws_stream
-        .send(WsMessage::Binary(size_msg.into()))
-        .await
-        .map_err(|e| SsmError::WebSocket(format!("Failed to send size: {}", e)))?;
+    // Send initial terminal size; a port forward has no terminal
+    if backend.config.port_forward.is_none() {
+        let size_msg = build_size_message(backend.next_sequence(), 80, 24);
+        ws_stream
+            .send(WsMessage::Binary(size_msg.into()))
+            .await
+            .map_err(|e| SsmError::WebSocket(format!("Failed to send size: {}", e)))?;
+    }
 
     backend.set_state(ConnectionState::Connected);
     tracing::info!("SSM session connected and ready");
@@ -632,6 +720,11 @@ impl SsmMessageBuilder {
         build_size_message(self.next_sequence(), cols, rows)
     }
 
+    /// Build a port forwarding flag message
+    pub fn build_flag(&mut self, flag: u32) -> Vec<u8> {
+        build_flag_message(self.next_sequence(), flag)
+    }
+
     /// Build an ACK message
     pub fn build_ack(&mut self, message_id: Uuid, sequence_number: i64) -> Vec<u8> {
         build_ack_message(message_id, sequence_number)
@@ -644,6 +737,317 @@ impl Default for SsmMessageBuilder {
     }
 }
 
+#[async_trait]
+impl TerminalBackend for SsmBackend {
+    fn kind(&self) -> ConnectionKind {
+        ConnectionKind::Ssm
+    }
+
+    async fn connect(&mut self, _size: TerminalSize) -> Result<(), ConnectError> {
+        // Take the local port first, so a port in use fails before any AWS calls
+        if let (Some(forward), None) = (self.config.port_forward, &self.listener) {
+            let listener = TcpListener::bind(("127.0.0.1", forward.local_port))
+                .await
+                .map_err(|e| ConnectError::new("Port Forward Failed", format!("Can't listen on 127.0.0.1:{}: {}", forward.local_port, e)))?;
+            self.listener = Some(Arc::new(listener));
+        }
+
+        // Start the session (WebSocket URL and token), then open the WebSocket
+        SsmBackend::connect(self)
+            .await
+            .map_err(|e| ConnectError::new("SSM Connection Failed", e))?;
+        tracing::info!("SSM session started");
+
+        let ws = connect_websocket(self)
+            .await
+            .map_err(|e| ConnectError::new("WebSocket Connection Failed", e))?;
+        self.ws = Some(ws);
+        Ok(())
+    }
+
+    fn io_channels(&mut self) -> Option<Box<dyn BackendIo>> {
+        let (sink, stream) = self.ws.take()?.split();
+        let io = SsmIo {
+            sink,
+            stream,
+            builder: SsmMessageBuilder::new(),
+            pending: Vec::new(),
+        };
+        match (self.config.port_forward, &self.listener) {
+            (Some(forward), Some(listener)) => Some(Box::new(SsmPortIo {
+                io,
+                listener: listener.clone(),
+                remote_port: forward.remote_port,
+                local: None,
+                to_local: Vec::new(),
+                buf: vec![0; 16 * 1024],
+            })),
+            _ => Some(Box::new(io)),
+        }
+    }
+
+    async fn close(&mut self) {
+        self.listener = None;
+        let _ = SsmBackend::close(self).await;
+    }
+
+    fn connect_notices(&self) -> Vec<String> {
+        match self.config.port_forward {
+            Some(forward) => vec![format!(
+                "Forwarding 127.0.0.1:{} to port {} on {}. Close this tab to stop.",
+                forward.local_port, forward.remote_port, self.config.instance_id
+            )],
+            None => Vec::new(),
+        }
+    }
+
+    fn reconnects(&self) -> bool {
+        true
+    }
+
+    async fn reconnect(&mut self) -> Result<(), ConnectError> {
+        SsmBackend::reconnect(self)
+            .await
+            .map_err(|e| ConnectError::new("Reconnection Failed", e))
+    }
+}
+
+/// SSM WebSocket driven by the shared I/O loop
+///
+/// Handles the Session Manager framing: input and resize messages are wrapped
+/// in SSM headers, and received output is acknowledged.
+struct SsmIo {
+    sink: SplitSink<SsmWebSocket, WsMessage>,
+    stream: SplitStream<SsmWebSocket>,
+    builder: SsmMessageBuilder,
+    /// Acks and pongs waiting for `flush`
+    pending: Vec<WsMessage>,
+}
+
+#[async_trait]
+impl BackendIo for SsmIo {
+    async fn read(&mut self) -> BackendRead {
+        loop {
+            match self.stream.next().await {
+                Some(Ok(WsMessage::Binary(data))) => match handle_ssm_message(&data) {
+                    Ok((output, ack_info)) => {
+                        if let Some((msg_id, seq)) = ack_info {
+                            let ack = self.builder.build_ack(msg_id, seq);
+                            self.pending.push(WsMessage::Binary(ack.into()));
+                        }
+                        return match output {
+                            Some(output) => BackendRead::Output(output),
+                            None => BackendRead::Idle,
+                        };
+                    }
+                    Err(e) => {
+                        tracing::warn!("SSM message parse error: {}", e);
+                        if matches!(e, SsmError::SessionClosed(_)) {
+                            return BackendRead::Closed(Some("Session closed by server".into()));
+                        }
+                    }
+                },
+                Some(Ok(WsMessage::Text(text))) => {
+                    // Text messages are usually control/status messages
+                    tracing::debug!("SSM text message: {}", text);
+                }
+                Some(Ok(WsMessage::Close(_))) => {
+                    // The session itself ends with channel_closed; a bare
+                    // close (e.g. an idle timeout) is worth reconnecting
+                    tracing::info!("SSM WebSocket closed");
+                    return BackendRead::Failed("Connection closed by server".into());
+                }
+                Some(Ok(WsMessage::Ping(data))) => {
+                    self.pending.push(WsMessage::Pong(data));
+                    return BackendRead::Idle;
+                }
+                Some(Ok(WsMessage::Pong(_))) | Some(Ok(WsMessage::Frame(_))) => {}
+                Some(Err(e)) => {
+                    tracing::error!("SSM WebSocket error: {}", e);
+                    return BackendRead::Failed(format!("WebSocket error: {}", e));
+                }
+                None => {
+                    // Without a close frame or channel_closed, the connection was lost
+                    tracing::info!("SSM WebSocket stream ended");
+                    return BackendRead::Failed("Connection lost".into());
+                }
+            }
+        }
+    }
+
+    async fn write(&mut self, data: &[u8]) -> Result<(), String> {
+        let msg = self.builder.build_input(data);
+        self.sink
+            .send(WsMessage::Binary(msg.into()))
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn resize(&mut self, size: TerminalSize) -> Result<(), String> {
+        let msg = self.builder.build_resize(size.cols, size.rows);
+        self.sink
+            .send(WsMessage::Binary(msg.into()))
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn flush(&mut self) {
+        for msg in std::mem::take(&mut self.pending) {
+            if let Err(e) = self.sink.send(msg).await {
+                tracing::warn!("SSM reply send error: {}", e);
+            }
+        }
+    }
+
+    async fn close(&mut self) {
+        let _ = self.sink.close().await;
+    }
+}
+
+/// SSM port forwarding driven by the shared I/O loop
+///
+/// Basic port forwarding carries one TCP connection at a time, so the next
+/// local connection is only accepted once the current one closes. The agent
+/// is then sent `DISCONNECT_TO_PORT` and connects to the port again when new
+/// data arrives. Connections come and go as lines in the terminal; typing
+/// there does nothing.
+struct SsmPortIo {
+    io: SsmIo,
+    listener: Arc<TcpListener>,
+    remote_port: u16,
+    /// The local connection being forwarded
+    local: Option<TcpStream>,
+    /// Data from the instance waiting for `flush` to write it to `local`
+    to_local: Vec<u8>,
+    buf: Vec<u8>,
+}
+
+impl SsmPortIo {
+    /// Drop the local connection, telling the agent to drop its end too
+    fn disconnect(&mut self) {
+        self.local = None;
+        self.to_local.clear();
+        let flag = self.io.builder.build_flag(port_flag::DISCONNECT_TO_PORT);
+        self.io.pending.push(WsMessage::Binary(flag.into()));
+    }
+}
+
+/// Read from the local connection, or wait forever without one
+async fn read_local(local: &mut Option<TcpStream>, buf: &mut [u8]) -> std::io::Result<usize> {
+    match local {
+        Some(socket) => socket.read(buf).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// The flag in a port forwarding flag message from the agent
+fn agent_flag(data: &[u8]) -> Option<u32> {
+    let (header, payload) = SsmMessageHeader::parse(data).ok()?;
+    if header.message_type != message_type::OUTPUT_STREAM_DATA || header.payload_type != payload_type::FLAG {
+        return None;
+    }
+    Some(u32::from_be_bytes(payload.get(..4)?.try_into().ok()?))
+}
+
+#[async_trait]
+impl BackendIo for SsmPortIo {
+    async fn read(&mut self) -> BackendRead {
+        tokio::select! {
+            accepted = self.listener.accept(), if self.local.is_none() => match accepted {
+                Ok((socket, peer)) => {
+                    let _ = socket.set_nodelay(true);
+                    self.local = Some(socket);
+                    BackendRead::Output(format!("  Connection from {}\r\n", peer).into_bytes())
+                }
+                Err(e) => {
+                    tracing::warn!("SSM port forward could not accept a connection: {}", e);
+                    BackendRead::Idle
+                }
+            },
+            read = read_local(&mut self.local, &mut self.buf) => match read {
+                Ok(n) if n > 0 => {
+                    let msg = self.io.builder.build_input(&self.buf[..n]);
+                    self.io.pending.push(WsMessage::Binary(msg.into()));
+                    BackendRead::Idle
+                }
+                _ => {
+                    self.disconnect();
+                    BackendRead::Output(b"  Connection closed\r\n".to_vec())
+                }
+            },
+            read = self.io.stream.next() => match read {
+                Some(Ok(WsMessage::Binary(data))) if agent_flag(&data) == Some(port_flag::CONNECT_TO_PORT_ERROR) => {
+                    // Closing the local connection is how the client finds out
+                    self.local = None;
+                    self.to_local.clear();
+                    BackendRead::Output(format!("  The instance refused the connection to port {}\r\n", self.remote_port).into_bytes())
+                }
+                Some(Ok(WsMessage::Binary(data))) => match handle_ssm_message(&data) {
+                    Ok((output, ack_info)) => {
+                        if let Some((msg_id, seq)) = ack_info {
+                            let ack = self.io.builder.build_ack(msg_id, seq);
+                            self.io.pending.push(WsMessage::Binary(ack.into()));
+                        }
+                        match output {
+                            Some(output) if self.local.is_some() => self.to_local.extend_from_slice(&output),
+                            Some(output) => tracing::debug!("Dropping {} bytes for a closed port forward connection", output.len()),
+                            None => {}
+                        }
+                        BackendRead::Idle
+                    }
+                    Err(SsmError::SessionClosed(_)) => BackendRead::Closed(Some("Session closed by server".into())),
+                    Err(e) => {
+                        tracing::warn!("SSM message parse error: {}", e);
+                        BackendRead::Idle
+                    }
+                },
+                Some(Ok(WsMessage::Close(_))) => {
+                    tracing::info!("SSM WebSocket closed");
+                    BackendRead::Failed("Connection closed by server".into())
+                }
+                Some(Ok(WsMessage::Ping(data))) => {
+                    self.io.pending.push(WsMessage::Pong(data));
+                    BackendRead::Idle
+                }
+                Some(Ok(_)) => BackendRead::Idle,
+                Some(Err(e)) => {
+                    tracing::error!("SSM WebSocket error: {}", e);
+                    BackendRead::Failed(format!("WebSocket error: {}", e))
+                }
+                None => {
+                    tracing::info!("SSM WebSocket stream ended");
+                    BackendRead::Failed("Connection lost".into())
+                }
+            },
+        }
+    }
+
+    async fn write(&mut self, _data: &[u8]) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn resize(&mut self, _size: TerminalSize) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn flush(&mut self) {
+        if !self.to_local.is_empty() {
+            let data = std::mem::take(&mut self.to_local);
+            if let Some(local) = &mut self.local {
+                if let Err(e) = local.write_all(&data).await {
+                    tracing::debug!("SSM port forward connection ended: {}", e);
+                    self.disconnect();
+                }
+            }
+        }
+        self.io.flush().await;
+    }
+
+    async fn close(&mut self) {
+        self.io.close().await;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -665,4 +1069,18 @@ mod tests {
         let resize_msg = builder.build_resize(120, 40);
         assert!(resize_msg.len() >= 120);
     }
+
+    #[test]
+    fn test_port_flags() {
+        let error = build_ssm_message(message_type::OUTPUT_STREAM_DATA, 0, payload_type::FLAG, &3u32.to_be_bytes());
+        assert_eq!(agent_flag(&error), Some(port_flag::CONNECT_TO_PORT_ERROR));
+
+        // Only the agent's output carries flags for us
+        let mut builder = SsmMessageBuilder::new();
+        let disconnect = builder.build_flag(port_flag::DISCONNECT_TO_PORT);
+        let (header, payload) = SsmMessageHeader::parse(&disconnect).unwrap();
+        assert_eq!((header.payload_type, payload), (payload_type::FLAG, &[0, 0, 0, 1][..]));
+        assert_eq!(agent_flag(&disconnect), None);
+        assert_eq!(agent_flag(&builder.build_input(b"data")), None);
+    }
 }
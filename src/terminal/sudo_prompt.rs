@@ -0,0 +1,83 @@
+//! `sudo` password prompt detection
+//!
+//! Sessions that opt in are offered a one-click answer when `sudo` asks for
+//! a password. A prompt is a line of output that starts with `[sudo` and
+//! ends in a colon with nothing after it yet: `[sudo] password for alice: `,
+//! or sudo-rs' `[sudo: authenticate] Password: `.
+
+/// Longest line still considered a possible prompt
+const MAX_PROMPT_LEN: usize = 256;
+
+/// Watches remote output for a `sudo` password prompt
+///
+/// The prompt may be split across reads; only the line in progress is kept.
+#[derive(Debug, Default)]
+pub struct SudoPromptDetector {
+    line: Vec<u8>,
+    /// The current line is already too long to be a prompt
+    overflowed: bool,
+}
+
+impl SudoPromptDetector {
+    /// Scan a chunk of output
+    ///
+    /// Returns true if the chunk leaves the cursor sitting at a prompt. Each
+    /// prompt is reported once.
+    pub fn scan(&mut self, data: &[u8]) -> bool {
+        for &byte in data {
+            match byte {
+                b'\r' | b'\n' => {
+                    self.line.clear();
+                    self.overflowed = false;
+                }
+                _ if self.overflowed => {}
+                _ if self.line.len() == MAX_PROMPT_LEN => {
+                    self.line.clear();
+                    self.overflowed = true;
+                }
+                _ => self.line.push(byte),
+            }
+        }
+
+        if data.is_empty() || !is_prompt(&self.line) {
+            return false;
+        }
+        self.line.clear();
+        true
+    }
+}
+
+fn is_prompt(line: &[u8]) -> bool {
+    let end = line.iter().rposition(|b| !b.is_ascii_whitespace()).map_or(0, |i| i + 1);
+    line.starts_with(b"[sudo") && end > b"[sudo".len() && line[..end].ends_with(b":")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_sudo_prompts() {
+        let mut detector = SudoPromptDetector::default();
+        assert!(detector.scan(b"$ sudo apt update\r\n[sudo] password for alice: "));
+        // Reported once, not again on the next (empty or unrelated) chunk
+        assert!(!detector.scan(b""));
+        assert!(!detector.scan(b"\r\n"));
+
+        // Split across reads
+        assert!(!detector.scan(b"[sudo] passw"));
+        assert!(detector.scan(b"ord for bob: "));
+
+        assert!(detector.scan(b"\r\n[sudo: authenticate] Password: "));
+    }
+
+    #[test]
+    fn test_ignores_other_output() {
+        let mut detector = SudoPromptDetector::default();
+        assert!(!detector.scan(b"Password: "));
+        assert!(!detector.scan(b"\r\n[sudo] password for alice: wrong\r\n"));
+        assert!(!detector.scan(b"Sorry, try again.\r\n[sudo]"));
+        assert!(!detector.scan(&[b'x'; 300]));
+        assert!(!detector.scan(b"[sudo] password for alice: "));
+    }
+}
@@ -0,0 +1,465 @@
+//! Telnet backend
+//!
+//! Plain TCP with RFC 854 option negotiation, for routers, switches and
+//! other equipment that never learned SSH. The client offers terminal type
+//! and window size (NAWS) and accepts the server's echo and suppress-go-ahead;
+//! every other option is refused.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use crate::metrics::ConnectionKind;
+use crate::session::TelnetSession;
+use super::backend::{BackendIo, BackendRead, ConnectError, TerminalBackend};
+use super::TerminalSize;
+
+/// How long to wait for the TCP connection
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Terminal type reported to the server
+const TERMINAL_TYPE: &str = "xterm-256color";
+
+// Commands
+const IAC: u8 = 255;
+const DONT: u8 = 254;
+const DO: u8 = 253;
+const WONT: u8 = 252;
+const WILL: u8 = 251;
+const SB: u8 = 250;
+const SE: u8 = 240;
+
+// Options
+const ECHO: u8 = 1;
+const SGA: u8 = 3;
+const TTYPE: u8 = 24;
+const NAWS: u8 = 31;
+
+// Terminal-type subnegotiation
+const TTYPE_IS: u8 = 0;
+const TTYPE_SEND: u8 = 1;
+
+/// Longest subnegotiation kept; anything beyond is dropped
+const MAX_SUBNEGOTIATION: usize = 256;
+
+/// Negotiated state of one option on one side
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum OptionState {
+    #[default]
+    No,
+    /// We asked and are waiting for the answer
+    WantYes,
+    Yes,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ParseState {
+    Data,
+    /// Saw IAC in the data stream
+    Iac,
+    /// Saw IAC WILL/WONT/DO/DONT, waiting for the option byte
+    Negotiate(u8),
+    /// Inside IAC SB ... IAC SE
+    Sub,
+    /// Saw IAC inside a subnegotiation
+    SubIac,
+}
+
+/// Result of decoding a chunk of server output
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct TelnetDecoded {
+    /// Bytes for the terminal
+    pub data: Vec<u8>,
+    /// Negotiation replies to send back
+    pub reply: Vec<u8>,
+}
+
+/// Telnet protocol state for one connection
+///
+/// Splits server output into display data and negotiation, answers the
+/// negotiation, and escapes outgoing data.
+#[derive(Debug)]
+pub struct TelnetCodec {
+    state: ParseState,
+    /// Options we perform (answered with WILL/WONT)
+    local: [OptionState; 256],
+    /// Options the server performs (answered with DO/DONT)
+    remote: [OptionState; 256],
+    subnegotiation: Vec<u8>,
+    /// Last data byte was CR, so a following NUL is padding
+    after_cr: bool,
+    cols: u16,
+    rows: u16,
+}
+
+impl TelnetCodec {
+    pub fn new(cols: u16, rows: u16) -> Self {
+        Self {
+            state: ParseState::Data,
+            local: [OptionState::No; 256],
+            remote: [OptionState::No; 256],
+            subnegotiation: Vec::new(),
+            after_cr: false,
+            cols,
+            rows,
+        }
+    }
+
+    /// Options we offer right after connecting
+    pub fn start(&mut self) -> Vec<u8> {
+        let mut out = Vec::new();
+        for option in [TTYPE, NAWS] {
+            self.local[option as usize] = OptionState::WantYes;
+            out.extend_from_slice(&[IAC, WILL, option]);
+        }
+        self.remote[SGA as usize] = OptionState::WantYes;
+        out.extend_from_slice(&[IAC, DO, SGA]);
+        out
+    }
+
+    /// Whether the server echoes what we type
+    pub fn remote_echo(&self) -> bool {
+        self.remote[ECHO as usize] == OptionState::Yes
+    }
+
+    /// Split server output into terminal data and negotiation replies
+    pub fn decode(&mut self, input: &[u8]) -> TelnetDecoded {
+        let mut decoded = TelnetDecoded::default();
+
+        for &byte in input {
+            self.state = match self.state {
+                ParseState::Data => {
+                    if byte == IAC {
+                        ParseState::Iac
+                    } else {
+                        // CR NUL is a bare carriage return
+                        if !(self.after_cr && byte == 0) {
+                            decoded.data.push(byte);
+                        }
+                        self.after_cr = byte == b'\r';
+                        ParseState::Data
+                    }
+                }
+                ParseState::Iac => match byte {
+                    IAC => {
+                        decoded.data.push(IAC);
+                        self.after_cr = false;
+                        ParseState::Data
+                    }
+                    WILL | WONT | DO | DONT => ParseState::Negotiate(byte),
+                    SB => {
+                        self.subnegotiation.clear();
+                        ParseState::Sub
+                    }
+                    // GA, NOP, DM and the like carry nothing for a terminal
+                    _ => ParseState::Data,
+                },
+                ParseState::Negotiate(command) => {
+                    self.negotiate(command, byte, &mut decoded.reply);
+                    ParseState::Data
+                }
+                ParseState::Sub => {
+                    if byte == IAC {
+                        ParseState::SubIac
+                    } else {
+                        if self.subnegotiation.len() < MAX_SUBNEGOTIATION {
+                            self.subnegotiation.push(byte);
+                        }
+                        ParseState::Sub
+                    }
+                }
+                ParseState::SubIac => match byte {
+                    SE => {
+                        self.subnegotiate(&mut decoded.reply);
+                        ParseState::Data
+                    }
+                    IAC => {
+                        if self.subnegotiation.len() < MAX_SUBNEGOTIATION {
+                            self.subnegotiation.push(IAC);
+                        }
+                        ParseState::Sub
+                    }
+                    _ => ParseState::Sub,
+                },
+            };
+        }
+
+        decoded
+    }
+
+    /// Escape user input for the wire
+    ///
+    /// IAC is doubled, and a CR not followed by LF becomes CR NUL as the
+    /// protocol requires.
+    pub fn encode(&self, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(data.len() + 2);
+        for (i, &byte) in data.iter().enumerate() {
+            match byte {
+                IAC => out.extend_from_slice(&[IAC, IAC]),
+                b'\r' if data.get(i + 1) != Some(&b'\n') => out.extend_from_slice(b"\r\0"),
+                _ => out.push(byte),
+            }
+        }
+        out
+    }
+
+    /// Record a new window size; returns the NAWS update if the server wants one
+    pub fn resize(&mut self, cols: u16, rows: u16) -> Option<Vec<u8>> {
+        if (cols, rows) == (self.cols, self.rows) {
+            return None;
+        }
+        self.cols = cols;
+        self.rows = rows;
+        (self.local[NAWS as usize] == OptionState::Yes).then(|| self.window_size())
+    }
+
+    fn supports_local(option: u8) -> bool {
+        matches!(option, TTYPE | NAWS | SGA)
+    }
+
+    fn supports_remote(option: u8) -> bool {
+        matches!(option, ECHO | SGA)
+    }
+
+    fn negotiate(&mut self, command: u8, option: u8, reply: &mut Vec<u8>) {
+        let index = option as usize;
+        match command {
+            DO => match self.local[index] {
+                OptionState::Yes => {}
+                state if Self::supports_local(option) => {
+                    self.local[index] = OptionState::Yes;
+                    // Our own WILL already went out if we asked first
+                    if state == OptionState::No {
+                        reply.extend_from_slice(&[IAC, WILL, option]);
+                    }
+                    if option == NAWS {
+                        reply.extend_from_slice(&self.window_size());
+                    }
+                }
+                _ => reply.extend_from_slice(&[IAC, WONT, option]),
+            },
+            DONT => {
+                if self.local[index] == OptionState::Yes {
+                    reply.extend_from_slice(&[IAC, WONT, option]);
+                }
+                self.local[index] = OptionState::No;
+            }
+            WILL => match self.remote[index] {
+                OptionState::Yes => {}
+                state if Self::supports_remote(option) => {
+                    self.remote[index] = OptionState::Yes;
+                    if state == OptionState::No {
+                        reply.extend_from_slice(&[IAC, DO, option]);
+                    }
+                }
+                _ => reply.extend_from_slice(&[IAC, DONT, option]),
+            },
+            WONT => {
+                if self.remote[index] == OptionState::Yes {
+                    reply.extend_from_slice(&[IAC, DONT, option]);
+                }
+                self.remote[index] = OptionState::No;
+            }
+            _ => {}
+        }
+    }
+
+    fn subnegotiate(&mut self, reply: &mut Vec<u8>) {
+        if self.subnegotiation.as_slice() == [TTYPE, TTYPE_SEND] && self.local[TTYPE as usize] == OptionState::Yes {
+            reply.extend_from_slice(&[IAC, SB, TTYPE, TTYPE_IS]);
+            reply.extend_from_slice(TERMINAL_TYPE.as_bytes());
+            reply.extend_from_slice(&[IAC, SE]);
+        }
+    }
+
+    /// IAC SB NAWS <cols> <rows> IAC SE, with IAC bytes in the size doubled
+    fn window_size(&self) -> Vec<u8> {
+        let mut out = vec![IAC, SB, NAWS];
+        for byte in self.cols.to_be_bytes().into_iter().chain(self.rows.to_be_bytes()) {
+            out.push(byte);
+            if byte == IAC {
+                out.push(IAC);
+            }
+        }
+        out.extend_from_slice(&[IAC, SE]);
+        out
+    }
+}
+
+/// Telnet backend
+pub struct TelnetBackend {
+    session: TelnetSession,
+    /// Connection and codec opened by `connect`, until taken for I/O
+    io: Option<TelnetIo>,
+}
+
+impl TelnetBackend {
+    /// Create a new telnet backend (not connected)
+    pub fn new(session: TelnetSession) -> Self {
+        Self { session, io: None }
+    }
+
+    /// Get the session configuration
+    pub fn session(&self) -> &TelnetSession {
+        &self.session
+    }
+}
+
+#[async_trait]
+impl TerminalBackend for TelnetBackend {
+    fn kind(&self) -> ConnectionKind {
+        ConnectionKind::Telnet
+    }
+
+    async fn connect(&mut self, size: TerminalSize) -> Result<(), ConnectError> {
+        let address = self.session.address();
+        let mut stream = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(&address))
+            .await
+            .map_err(|_| ConnectError::new("Connection Failed", format!("{}: connection timed out", address)))?
+            .map_err(|e| ConnectError::new("Connection Failed", format!("{}: {}", address, e)))?;
+        let _ = stream.set_nodelay(true);
+
+        let mut codec = TelnetCodec::new(size.cols, size.rows);
+        stream
+            .write_all(&codec.start())
+            .await
+            .map_err(|e| ConnectError::new("Connection Failed", format!("{}: {}", address, e)))?;
+
+        tracing::info!("Telnet connection established to {}", address);
+        self.io = Some(TelnetIo {
+            stream,
+            codec,
+            buf: vec![0u8; 4096],
+            pending: Vec::new(),
+        });
+        Ok(())
+    }
+
+    fn io_channels(&mut self) -> Option<Box<dyn BackendIo>> {
+        self.io.take().map(|io| Box::new(io) as Box<dyn BackendIo>)
+    }
+
+    async fn close(&mut self) {
+        self.io = None;
+    }
+}
+
+/// Telnet connection driven by the shared I/O loop
+struct TelnetIo {
+    stream: TcpStream,
+    codec: TelnetCodec,
+    buf: Vec<u8>,
+    /// Negotiation replies waiting for `flush`
+    pending: Vec<u8>,
+}
+
+#[async_trait]
+impl BackendIo for TelnetIo {
+    async fn read(&mut self) -> BackendRead {
+        match self.stream.read(&mut self.buf).await {
+            Ok(0) => BackendRead::Closed(Some("Connection closed by foreign host".into())),
+            Ok(n) => {
+                let decoded = self.codec.decode(&self.buf[..n]);
+                self.pending.extend_from_slice(&decoded.reply);
+                if decoded.data.is_empty() {
+                    BackendRead::Idle
+                } else {
+                    BackendRead::Output(decoded.data)
+                }
+            }
+            Err(e) => {
+                tracing::error!("Telnet read error: {}", e);
+                BackendRead::Failed(format!("Connection error: {}", e))
+            }
+        }
+    }
+
+    async fn write(&mut self, data: &[u8]) -> Result<(), String> {
+        let encoded = self.codec.encode(data);
+        self.stream.write_all(&encoded).await.map_err(|e| e.to_string())
+    }
+
+    async fn resize(&mut self, size: TerminalSize) -> Result<(), String> {
+        match self.codec.resize(size.cols, size.rows) {
+            Some(update) => self.stream.write_all(&update).await.map_err(|e| e.to_string()),
+            None => Ok(()),
+        }
+    }
+
+    async fn flush(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let pending = std::mem::take(&mut self.pending);
+        if let Err(e) = self.stream.write_all(&pending).await {
+            tracing::warn!("Telnet negotiation send error: {}", e);
+        }
+    }
+
+    async fn close(&mut self) {
+        let _ = self.stream.shutdown().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_strips_negotiation_and_unescapes_iac() {
+        let mut codec = TelnetCodec::new(80, 24);
+        let decoded = codec.decode(&[b'a', IAC, IAC, b'b', IAC, WILL, ECHO, b'c', b'\r', 0, b'd']);
+        assert_eq!(decoded.data, vec![b'a', IAC, b'b', b'c', b'\r', b'd']);
+        assert_eq!(decoded.reply, vec![IAC, DO, ECHO]);
+        assert!(codec.remote_echo());
+    }
+
+    #[test]
+    fn test_negotiation_replies_only_on_change() {
+        let mut codec = TelnetCodec::new(80, 24);
+        let start = codec.start();
+        assert_eq!(start, vec![IAC, WILL, TTYPE, IAC, WILL, NAWS, IAC, DO, SGA]);
+
+        // Answers to our own requests need no further reply, except the size
+        let decoded = codec.decode(&[IAC, DO, TTYPE, IAC, WILL, SGA]);
+        assert!(decoded.reply.is_empty());
+        let decoded = codec.decode(&[IAC, DO, NAWS]);
+        assert_eq!(decoded.reply, vec![IAC, SB, NAWS, 0, 80, 0, 24, IAC, SE]);
+
+        // Repeats are ignored, unsupported options refused
+        assert!(codec.decode(&[IAC, DO, NAWS]).reply.is_empty());
+        assert_eq!(codec.decode(&[IAC, DO, 39]).reply, vec![IAC, WONT, 39]);
+        assert_eq!(codec.decode(&[IAC, WILL, 34]).reply, vec![IAC, DONT, 34]);
+    }
+
+    #[test]
+    fn test_terminal_type_and_window_size() {
+        let mut codec = TelnetCodec::new(80, 24);
+        codec.start();
+        codec.decode(&[IAC, DO, TTYPE, IAC, DO, NAWS]);
+
+        let decoded = codec.decode(&[IAC, SB, TTYPE, TTYPE_SEND, IAC, SE, b'$']);
+        assert_eq!(decoded.data, b"$");
+        let mut expected = vec![IAC, SB, TTYPE, TTYPE_IS];
+        expected.extend_from_slice(TERMINAL_TYPE.as_bytes());
+        expected.extend_from_slice(&[IAC, SE]);
+        assert_eq!(decoded.reply, expected);
+
+        // 255 columns must be escaped inside the subnegotiation
+        assert_eq!(
+            codec.resize(255, 50),
+            Some(vec![IAC, SB, NAWS, 0, IAC, IAC, 0, 50, IAC, SE])
+        );
+        assert_eq!(codec.resize(255, 50), None);
+    }
+
+    #[test]
+    fn test_encode_escapes_iac_and_bare_cr() {
+        let codec = TelnetCodec::new(80, 24);
+        assert_eq!(codec.encode(&[b'x', IAC, b'y']), vec![b'x', IAC, IAC, b'y']);
+        assert_eq!(codec.encode(b"ls\r"), b"ls\r\0");
+        assert_eq!(codec.encode(b"ls\r\n"), b"ls\r\n");
+    }
+}
@@ -1,14 +1,14 @@
-use alacritty_terminal::event::{Notify, WindowSize};
+use alacritty_terminal::event::{EventListener, Notify, WindowSize};
 use alacritty_terminal::event_loop::{EventLoop, Msg, Notifier};
-use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::grid::{Dimensions, Row};
 use alacritty_terminal::index::{Column, Line, Point, Side};
 use alacritty_terminal::selection::{Selection, SelectionRange, SelectionType};
 use alacritty_terminal::sync::FairMutex;
-use alacritty_terminal::term::cell::Cell;
+use alacritty_terminal::term::cell::{Cell, Flags};
 use alacritty_terminal::term::color::Colors;
 use alacritty_terminal::term::{Config as TermConfig, Term, TermMode};
 use alacritty_terminal::tty::{self, Options as PtyOptions};
-use alacritty_terminal::vte::ansi::{Color, NamedColor, Processor, Rgb, StdSyncHandler};
+use alacritty_terminal::vte::ansi::{Color, CursorShape, CursorStyle, Handler, NamedColor, Processor, Rgb, StdSyncHandler, TabulationClearMode};
 
 /// Indexed cell for rendering
 #[derive(Clone)]
@@ -27,19 +27,49 @@ pub struct TerminalContent {
     pub selection: Option<SelectionRange>,
     pub cursor_point: Point,
     pub colors: Colors,
+    /// Cursor shape the application asked for with DECSCUSR, if any
+    pub cursor_shape: Option<CursorShape>,
+    /// The application asked for a cursor that doesn't blink
+    pub cursor_steady: bool,
 }
+
+impl TerminalContent {
+    /// Cursor color set by the application via OSC 12 (cleared again by OSC 112)
+    pub fn cursor_color(&self) -> Option<Rgb> {
+        self.colors[NamedColor::Cursor]
+    }
+}
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::io;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::mpsc::Receiver;
 use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 use tokio::runtime::Handle as TokioHandle;
 use tokio::sync::Mutex as TokioMutex;
 use uuid::Uuid;
 
+use crate::session::NewlineMode;
+
+use super::ambiguous_width::AmbiguousWide;
+use super::auth_prompt::HostKeyRequest;
+use super::command_regions::{text_between, CommandOutput, CommandRegions, GridPos};
 use super::events::{event_channel, TerminalEvent, TerminalEventSender};
 use super::k8s_backend::K8sBackend;
+use super::marked_pty::MarkedPty;
+use super::recorder::{display_timestamp, SessionRecorder};
+use super::shell_marks::{ShellMark, ShellMarkScanner};
+use super::sudo_prompt::SudoPromptDetector;
+use super::urls;
+use super::zmodem::{
+    ZmodemDetector, ZmodemDirection, ZmodemEvent, ZmodemOutput, ZmodemReceiver, ZmodemSender, ZmodemTransfer,
+    ABORT_SEQUENCE,
+};
 use super::ssh_backend::SshBackend;
+use super::ssh_forward::PortForwards;
 use super::ssm_backend::SsmBackend;
 
 /// Terminal size in characters and pixels
@@ -106,19 +136,260 @@ impl Dimensions for SizeInfo {
 pub struct TerminalConfig {
     /// Number of lines to keep in scrollback
     pub scrollback_lines: usize,
+    /// Bytes of grid memory after which the oldest history is trimmed (0 = no cap)
+    pub scrollback_memory_limit: usize,
     /// Terminal size
     pub size: TerminalSize,
+    /// Columns between tab stops
+    pub tab_width: usize,
+    /// Watch remote output for ZMODEM transfers
+    pub zmodem_enabled: bool,
+    /// What the Enter key sends
+    pub newline_mode: NewlineMode,
+    /// Lay out ambiguous-width characters as two cells (remote sessions only)
+    pub ambiguous_wide: bool,    /// Separator format written on each connect (remote sessions only; see
+    /// `ConnectionMarkerSettings`)
+    pub connection_marker: Option<String>,
+    /// Command line a local PTY runs, e.g. `fish -l` (None = the default shell)
+    pub shell: Option<String>,
+    /// Directory a local shell starts in (None = inherited from RedPill)
+    pub working_directory: Option<PathBuf>,
+    /// Extra environment variables for a local shell
+    pub env: HashMap<String, String>,
 }
 
 impl Default for TerminalConfig {
     fn default() -> Self {
         Self {
             scrollback_lines: 10000,
+            scrollback_memory_limit: 0,
             size: TerminalSize::new(80, 24),
+            tab_width: DEFAULT_TAB_WIDTH,
+            zmodem_enabled: false,
+            newline_mode: NewlineMode::Auto,
+            ambiguous_wide: false,
+            connection_marker: None,
+            shell: None,
+            working_directory: None,
+            env: HashMap::new(),
         }
     }
 }
 
+/// Alacritty config for a terminal keeping `scrollback_lines` of history
+///
+/// The default cursor style is `Hidden`, which DECSCUSR can't select, so a
+/// shape chosen by the application can be told apart from none at all.
+/// Whether the cursor is shown at all is the separate DECTCEM mode. It blinks
+/// by default for the same reason: a steady style always came from the
+/// application, and otherwise the user's blink setting decides.
+fn term_config(scrollback_lines: usize) -> TermConfig {
+    TermConfig {
+        scrolling_history: scrollback_lines,
+        default_cursor_style: CursorStyle { shape: CursorShape::Hidden, blinking: true },
+        ..TermConfig::default()
+    }
+}
+
+/// Tab stop interval alacritty initializes the grid with
+pub const DEFAULT_TAB_WIDTH: usize = 8;
+
+/// A dimmed separator line marking a connect in the scrollback
+fn connection_marker_line(format: &str, reconnect: bool, time: SystemTime) -> String {
+    let event = if reconnect { "Reconnected" } else { "Connected" };
+    let text = format.replace("{event}", event).replace("{time}", &display_timestamp(time));
+    format!("\x1b[2m{}\x1b[0m\r\n", text)
+}
+
+/// Split a shell command line into the program and its arguments
+///
+/// Arguments are separated by whitespace; there is no quoting.
+fn shell_command(command: &str) -> Option<(String, Vec<String>)> {
+    let mut words = command.split_whitespace().map(String::from);
+    let program = words.next()?;
+    Some((program, words.collect()))
+}
+
+/// Start the configured shell on a new PTY whose output goes to `term`
+fn spawn_local_shell(
+    term: &Arc<FairMutex<Term<TerminalEventSender>>>,
+    event_tx: &TerminalEventSender,
+    window_size: WindowSize,
+    id: Uuid,
+    config: &TerminalConfig,
+    shell_marks: &Arc<ShellMarks>,
+) -> io::Result<Notifier> {
+    // Create PTY options with proper TERM environment variable
+    let mut env = HashMap::new();
+    env.insert("TERM".to_string(), "xterm-256color".to_string());
+    env.insert("COLORTERM".to_string(), "truecolor".to_string());
+    env.extend(config.env.clone());
+
+    let pty_config = PtyOptions {
+        shell: config
+            .shell
+            .as_deref()
+            .and_then(shell_command)
+            .map(|(program, args)| tty::Shell::new(program, args)),
+        working_directory: config.working_directory.clone(),
+        drain_on_exit: false,
+        env,
+    };
+
+    // Create PTY, watched for shell marks on the way to the parser
+    let pty = tty::new(&pty_config, window_size, id.as_u128() as u64)?;
+    let pty = MarkedPty::new(pty, term.clone(), shell_marks.clone())?;
+
+    // Create event loop (uses cloned event sender)
+    let event_loop = EventLoop::new(term.clone(), event_tx.clone(), pty, pty_config.drain_on_exit, false)?;
+
+    // Get notifier before starting the loop
+    let notifier = Notifier(event_loop.channel());
+
+    // Spawn the event loop
+    let _join_handle = event_loop.spawn();
+
+    Ok(notifier)
+}
+
+/// Set tab stops every `tab_width` columns, from `first_column` on
+///
+/// Alacritty always starts with 8-column stops (and uses 8 for columns added
+/// on resize), so this is applied to every column after creation and to the
+/// new columns after a resize. Stops the application set or cleared in the
+/// other columns are left alone. The cursor is left where it was.
+fn apply_tab_width<T: EventListener>(term: &mut Term<T>, tab_width: usize, first_column: usize) {
+    if tab_width == 0 || tab_width == DEFAULT_TAB_WIDTH {
+        return;
+    }
+
+    let cursor = term.grid().cursor.point;
+    let input_needs_wrap = term.grid().cursor.input_needs_wrap;
+
+    for col in first_column..term.columns() {
+        term.goto_col(col);
+        if col % tab_width == 0 {
+            term.set_horizontal_tabstop();
+        } else {
+            term.clear_tabs(TabulationClearMode::Current);
+        }
+    }
+
+    let grid = term.grid_mut();
+    grid.cursor.point = cursor;
+    grid.cursor.input_needs_wrap = input_needs_wrap;
+}
+
+/// Approximate bytes held by one grid row of `columns` cells
+fn row_bytes(columns: usize) -> usize {
+    columns * std::mem::size_of::<Cell>() + std::mem::size_of::<Row<Cell>>()
+}
+
+/// Approximate memory used by the terminal's screen and scrollback
+///
+/// Counts the cell storage only; wide/zero-width extras and hyperlinks are
+/// rare enough to ignore.
+fn grid_memory<T>(term: &Term<T>) -> usize {
+    (term.history_size() + term.screen_lines()) * row_bytes(term.columns())
+}
+
+/// Drop the oldest history until the grid fits in `limit` bytes
+///
+/// Trims to 90% of the limit so a busy terminal isn't trimmed on every
+/// write. The history limit is reset to `scrollback_lines` afterwards, so the
+/// buffer can grow again as old lines are trimmed. Returns whether anything
+/// was dropped.
+fn trim_history<T>(term: &mut Term<T>, limit: usize, scrollback_lines: usize) -> bool {
+    if limit == 0 || grid_memory(term) <= limit {
+        return false;
+    }
+
+    let target_rows = limit / 10 * 9 / row_bytes(term.columns());
+    let keep = target_rows.saturating_sub(term.screen_lines()).min(scrollback_lines);
+    // Selections may point into the lines being dropped
+    term.selection = None;
+    let grid = term.grid_mut();
+    grid.update_history(keep);
+    grid.update_history(scrollback_lines);
+    true
+}
+
+/// A line counted from the top of the scrollback, one char per column
+///
+/// Wide character spacers come back as NUL so columns still line up; the
+/// flag says whether the line wraps onto the next.
+fn absolute_row<T>(term: &Term<T>, line: usize) -> Option<(String, bool)> {
+    let line = line as i32 - term.history_size() as i32;
+    if line >= term.screen_lines() as i32 {
+        return None;
+    }
+    let row = &term.grid()[Line(line)];
+    let columns = term.columns();
+    let text = (0..columns)
+        .map(|col| {
+            let cell = &row[Column(col)];
+            match cell.c {
+                _ if cell.flags.contains(Flags::WIDE_CHAR_SPACER) => '\0',
+                '\0' => ' ',
+                c => c,
+            }
+        })
+        .collect();
+    let wrapped = columns > 0 && row[Column(columns - 1)].flags.contains(Flags::WRAPLINE);
+    Some((text, wrapped))
+}
+
+/// A line's text as stored in a command region's anchor
+fn row_anchor<T>(term: &Term<T>, line: usize) -> Option<String> {
+    absolute_row(term, line).map(|(text, _)| text.replace('\0', "").trim_end().to_string())
+}
+
+/// URI of the OSC 8 hyperlink on the cell at `point`, in grid coordinates
+fn hyperlink_at<T>(term: &Term<T>, point: Point) -> Option<String> {
+    let on_grid = point.line >= term.topmost_line()
+        && point.line <= term.bottommost_line()
+        && point.column.0 < term.columns();
+    if !on_grid {
+        return None;
+    }
+    term.grid()[point].hyperlink().map(|link| link.uri().to_string())
+}
+
+/// Bare URL around `point`, in grid coordinates, with the cells it spans
+///
+/// Soft-wrapped rows are joined first, so a long URL that wrapped is found
+/// whole from either row.
+fn url_around<T>(term: &Term<T>, point: Point) -> Option<(String, RangeInclusive<Point>)> {
+    let on_grid = point.line >= term.topmost_line()
+        && point.line <= term.bottommost_line()
+        && point.column.0 < term.columns();
+    if !on_grid {
+        return None;
+    }
+    let columns = term.columns();
+    let history = term.history_size() as i32;
+    let row = |line: Line| absolute_row(term, (line.0 + history) as usize);
+
+    let mut first = point.line;
+    while first > term.topmost_line() && row(first - 1).is_some_and(|(_, wrapped)| wrapped) {
+        first -= 1;
+    }
+    let mut text = String::new();
+    let mut line = first;
+    while let Some((row_text, wrapped)) = row(line) {
+        text.push_str(&row_text);
+        if !wrapped || line == term.bottommost_line() {
+            break;
+        }
+        line += 1;
+    }
+
+    let column = (point.line - first).0 as usize * columns + point.column.0;
+    let (range, url) = urls::url_at(&text, column)?;
+    let cell = |index: usize| Point::new(first + (index / columns) as i32, Column(index % columns));
+    Some((url.replace('\0', ""), cell(range.start)..=cell(range.end - 1)))
+}
+
 /// Terminal operating mode
 pub enum TerminalMode2 {
     /// Local mode - uses PTY for local shell
@@ -145,6 +416,16 @@ pub enum TerminalMode2 {
         resize_tx: tokio::sync::mpsc::UnboundedSender<TerminalSize>,
         tokio_handle: TokioHandle,
     },
+    /// Backend mode - any other `TerminalBackend`, owned by its I/O loop
+    Backend {
+        notifier: Notifier,
+        /// Short name of the connection type
+        kind: &'static str,
+        /// Sender for write data
+        write_tx: tokio::sync::mpsc::UnboundedSender<Vec<u8>>,
+        /// Sender for resize requests
+        resize_tx: tokio::sync::mpsc::UnboundedSender<TerminalSize>,
+    },
     /// SSM mode - AWS Systems Manager Session Manager connection
     Ssm {
         notifier: Notifier,
@@ -157,6 +438,104 @@ pub enum TerminalMode2 {
     },
 }
 
+/// Connection state of a terminal's remote session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionStatus {
+    /// Connection is being established
+    Connecting,
+    /// Session is up (local terminals are always connected)
+    Connected,
+    /// Session ended after being connected
+    Disconnected,
+    /// Connection could not be established
+    Failed,
+}
+
+impl ConnectionStatus {
+    /// Whether the session is gone and can be reconnected
+    pub fn is_dropped(self) -> bool {
+        matches!(self, ConnectionStatus::Disconnected | ConnectionStatus::Failed)
+    }
+}
+
+/// How long an unanswered ZMODEM offer suppresses new prompts
+///
+/// lrzsz gives up well before this, so a later header is a new transfer.
+const ZMODEM_OFFER_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// ZMODEM state of a remote session
+#[derive(Default)]
+struct ZmodemLink {
+    detector: ZmodemDetector,
+    /// A transfer was detected (and when) and the user hasn't answered yet
+    offered: Option<(ZmodemDirection, Instant)>,
+    transfer: Option<ZmodemTransfer>,
+}
+
+/// Stored password offered at `sudo` prompts (sessions that opt in only)
+struct SudoAutofill {
+    password: String,
+    detector: parking_lot::Mutex<SudoPromptDetector>,
+}
+
+/// OSC 133 marks in a terminal's output and the command regions they outline
+///
+/// Remote output is scanned as it is fed to the parser; local output by the
+/// [`MarkedPty`] the event loop reads from, so this is shared with it.
+pub(crate) struct ShellMarks {
+    scanner: parking_lot::Mutex<ShellMarkScanner>,
+    pub(crate) regions: parking_lot::Mutex<CommandRegions>,
+    /// For `CommandFinished` events
+    event_tx: TerminalEventSender,
+}
+
+impl ShellMarks {
+    pub(crate) fn new(event_tx: TerminalEventSender) -> Self {
+        Self {
+            scanner: parking_lot::Mutex::new(ShellMarkScanner::new()),
+            regions: parking_lot::Mutex::new(CommandRegions::default()),
+            event_tx,
+        }
+    }
+
+    /// Raise `CommandFinished` events for OSC 133 `D` marks in output
+    ///
+    /// Returns every mark found, with the offset just past it in `data`.
+    pub(crate) fn scan(&self, data: &[u8]) -> Vec<(usize, ShellMark)> {
+        let mut scanner = self.scanner.lock();
+        let marks = scanner.scan(data);
+        for (_, mark) in &marks {
+            if let ShellMark::CommandFinished(exit_code) = *mark {
+                let duration = scanner.take_command_duration();
+                let _ = self.event_tx.0.send(TerminalEvent::CommandFinished { exit_code, duration });
+            }
+        }
+        marks
+    }
+
+    /// Note where the cursor was when a shell mark arrived
+    ///
+    /// `term` must have parsed the output up to the mark and no further.
+    /// Full-screen apps don't emit marks, and the alternate screen has no
+    /// scrollback to point into, so marks there are ignored.
+    pub(crate) fn record(&self, term: &Term<TerminalEventSender>, mark: ShellMark) {
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return;
+        }
+        let cursor = term.grid().cursor.point;
+        let pos = GridPos {
+            line: (term.history_size() as i32 + cursor.line.0).max(0) as usize,
+            column: cursor.column.0,
+        };
+        let mut regions = self.regions.lock();
+        // Bring older regions into line with `pos` if scrollback was dropped
+        regions.rebase(|line| row_anchor(term, line));
+        if let Some(region) = regions.record(mark, pos) {
+            region.anchor = row_anchor(term, region.command.line).unwrap_or_default();
+        }
+    }
+}
+
 /// A terminal instance wrapping alacritty_terminal
 pub struct Terminal {
     /// Unique identifier
@@ -167,6 +546,8 @@ pub struct Terminal {
     mode: TerminalMode2,
     /// Event receiver for terminal events
     event_rx: Receiver<TerminalEvent>,
+    /// Sender for events the terminal raises itself (e.g. command completion)
+    event_tx: TerminalEventSender,
     /// Terminal configuration
     config: TerminalConfig,
     /// Current title (updated from events)
@@ -174,6 +555,32 @@ pub struct Terminal {
     /// Flag indicating new content has been written (for SSH mode)
     /// This allows the UI to know when to redraw without polling events
     dirty: Arc<AtomicBool>,
+    /// Connection state, updated by the I/O tasks
+    status: ConnectionStatus,
+    /// Transcript recorder (remote sessions only)
+    recorder: Option<parking_lot::Mutex<SessionRecorder>>,
+    /// Input is answering a login prompt, so is kept out of the transcript
+    auth_input: bool,
+    /// When the session last became connected
+    connected_at: Option<Instant>,
+    /// Whether the local shell has exited (and not been restarted since)
+    shell_exited: bool,
+    /// Bytes sent to the remote end (keyboard input)
+    bytes_sent: AtomicU64,
+    /// When input was last written, for idle keep-alives
+    last_input: parking_lot::Mutex<Instant>,
+    /// Bytes received from the remote end (remote sessions only)
+    bytes_received: AtomicU64,
+    /// OSC 133 command marks and the command regions they outline
+    shell_marks: Arc<ShellMarks>,
+    /// ZMODEM detection and any accepted transfer (remote sessions only)
+    zmodem: parking_lot::Mutex<ZmodemLink>,
+    /// Password to offer at `sudo` prompts, if the session opted in
+    sudo_autofill: Option<SudoAutofill>,
+    /// Host key question waiting for the UI to show it
+    host_key_request: Option<HostKeyRequest>,
+    /// The SSH session's port forwards (SSH sessions only)
+    port_forwards: Option<PortForwards>,
     /// Cached content for lock-free rendering (like Zed's last_content)
     pub last_content: TerminalContent,
 }
@@ -184,11 +591,7 @@ impl Terminal {
         let id = Uuid::new_v4();
         let (event_tx, event_rx) = event_channel();
 
-        // Create terminal config with scrollback history
-        let term_config = TermConfig {
-            scrolling_history: config.scrollback_lines,
-            ..TermConfig::default()
-        };
+        let term_config = term_config(config.scrollback_lines);
 
         // Create terminal size (implements Dimensions)
         let term_size = SizeInfo::new(config.size.cols, config.size.rows);
@@ -202,55 +605,65 @@ impl Terminal {
         };
 
         // Create the terminal
-        let term = Term::new(term_config, &term_size, event_tx.clone());
+        let mut term = Term::new(term_config, &term_size, event_tx.clone());
+        apply_tab_width(&mut term, config.tab_width, 0);
         let term = Arc::new(FairMutex::new(term));
+        let shell_marks = Arc::new(ShellMarks::new(event_tx.clone()));
 
-        // Create PTY options with proper TERM environment variable
-        let mut env = HashMap::new();
-        env.insert("TERM".to_string(), "xterm-256color".to_string());
-        env.insert("COLORTERM".to_string(), "truecolor".to_string());
-
-        let pty_config = PtyOptions {
-            shell: None, // Use default shell
-            working_directory: None,
-            drain_on_exit: false,
-            env,
-        };
-
-        // Create PTY
-        let pty = tty::new(&pty_config, window_size, id.as_u128() as u64)?;
-
-        // Create event loop (uses cloned event sender)
-        let event_loop = EventLoop::new(term.clone(), event_tx, pty, pty_config.drain_on_exit, false)?;
-
-        // Get notifier before starting the loop
-        let notifier = Notifier(event_loop.channel());
-
-        // Spawn the event loop
-        let _join_handle = event_loop.spawn();
+        let notifier = spawn_local_shell(&term, &event_tx, window_size, id, &config, &shell_marks)?;
 
         Ok(Self {
             id,
             term,
             mode: TerminalMode2::Local { notifier },
             event_rx,
+            event_tx,
             config,
             title: "Terminal".to_string(),
             dirty: Arc::new(AtomicBool::new(false)),
+            status: ConnectionStatus::Connected,
+            recorder: None,
+            auth_input: false,
+            shell_exited: false,
+            connected_at: Some(Instant::now()),
+            bytes_sent: AtomicU64::new(0),
+            last_input: parking_lot::Mutex::new(Instant::now()),
+            bytes_received: AtomicU64::new(0),
+            shell_marks,
+            zmodem: parking_lot::Mutex::new(ZmodemLink::default()),
+            sudo_autofill: None,
+            host_key_request: None,
+            port_forwards: None,
             last_content: TerminalContent::default(),
         })
     }
 
+    /// Start a new shell in a local terminal whose shell has exited
+    ///
+    /// The screen and scrollback are kept; the new shell starts below them.
+    pub fn restart_shell(&mut self) -> io::Result<()> {
+        let TerminalMode2::Local { notifier } = &mut self.mode else {
+            return Err(io::Error::new(io::ErrorKind::Unsupported, "not a local terminal"));
+        };
+        let size = self.config.size;
+        let window_size = WindowSize {
+            num_cols: size.cols,
+            num_lines: size.rows,
+            cell_width: 1,
+            cell_height: 1,
+        };
+        *notifier = spawn_local_shell(&self.term, &self.event_tx, window_size, self.id, &self.config, &self.shell_marks)?;
+        self.shell_exited = false;
+        self.connected_at = Some(Instant::now());
+        Ok(())
+    }
+
     /// Create an SSH terminal (display-only mode)
     pub fn new_ssh(config: TerminalConfig, backend: SshBackend, tokio_handle: TokioHandle) -> io::Result<Self> {
         let id = Uuid::new_v4();
         let (event_tx, event_rx) = event_channel();
 
-        // Create terminal config with scrollback history
-        let term_config = TermConfig {
-            scrolling_history: config.scrollback_lines,
-            ..TermConfig::default()
-        };
+        let term_config = term_config(config.scrollback_lines);
 
         // Create terminal size
         let term_size = SizeInfo::new(config.size.cols, config.size.rows);
@@ -264,8 +677,10 @@ impl Terminal {
         };
 
         // Create the terminal
-        let term = Term::new(term_config, &term_size, event_tx.clone());
+        let mut term = Term::new(term_config, &term_size, event_tx.clone());
+        apply_tab_width(&mut term, config.tab_width, 0);
         let term = Arc::new(FairMutex::new(term));
+        let shell_marks = Arc::new(ShellMarks::new(event_tx.clone()));
 
         // Create PTY options (we still need a PTY for the EventLoop, but it won't be used for SSH data)
         // Use a null placeholder that blocks waiting for stdin and consumes no resources.
@@ -286,7 +701,7 @@ impl Terminal {
         let pty = tty::new(&pty_config, window_size, id.as_u128() as u64)?;
 
         // Create event loop
-        let event_loop = EventLoop::new(term.clone(), event_tx, pty, false, false)?;
+        let event_loop = EventLoop::new(term.clone(), event_tx.clone(), pty, false, false)?;
 
         // Get notifier before starting the loop
         let notifier = Notifier(event_loop.channel());
@@ -299,6 +714,7 @@ impl Terminal {
         let (write_tx, _) = tokio::sync::mpsc::unbounded_channel();
         let (resize_tx, _) = tokio::sync::mpsc::unbounded_channel();
 
+        let port_forwards = Some(backend.port_forwards());
         let backend_arc = Arc::new(TokioMutex::new(backend));
 
         Ok(Self {
@@ -312,9 +728,23 @@ impl Terminal {
                 tokio_handle,
             },
             event_rx,
+            event_tx,
             config,
             title: "SSH".to_string(),
             dirty: Arc::new(AtomicBool::new(false)),
+            status: ConnectionStatus::Connecting,
+            recorder: None,
+            auth_input: false,
+            shell_exited: false,
+            connected_at: None,
+            bytes_sent: AtomicU64::new(0),
+            last_input: parking_lot::Mutex::new(Instant::now()),
+            bytes_received: AtomicU64::new(0),
+            shell_marks,
+            zmodem: parking_lot::Mutex::new(ZmodemLink::default()),
+            sudo_autofill: None,
+            host_key_request: None,
+            port_forwards,
             last_content: TerminalContent::default(),
         })
     }
@@ -324,11 +754,7 @@ impl Terminal {
         let id = Uuid::new_v4();
         let (event_tx, event_rx) = event_channel();
 
-        // Create terminal config with scrollback history
-        let term_config = TermConfig {
-            scrolling_history: config.scrollback_lines,
-            ..TermConfig::default()
-        };
+        let term_config = term_config(config.scrollback_lines);
 
         // Create terminal size
         let term_size = SizeInfo::new(config.size.cols, config.size.rows);
@@ -342,8 +768,10 @@ impl Terminal {
         };
 
         // Create the terminal
-        let term = Term::new(term_config, &term_size, event_tx.clone());
+        let mut term = Term::new(term_config, &term_size, event_tx.clone());
+        apply_tab_width(&mut term, config.tab_width, 0);
         let term = Arc::new(FairMutex::new(term));
+        let shell_marks = Arc::new(ShellMarks::new(event_tx.clone()));
 
         // Create PTY options (we still need a PTY for the EventLoop, but it won't be used for SSM data)
         // Use a null placeholder that blocks waiting for stdin and consumes no resources.
@@ -364,7 +792,7 @@ impl Terminal {
         let pty = tty::new(&pty_config, window_size, id.as_u128() as u64)?;
 
         // Create event loop
-        let event_loop = EventLoop::new(term.clone(), event_tx, pty, false, false)?;
+        let event_loop = EventLoop::new(term.clone(), event_tx.clone(), pty, false, false)?;
 
         // Get notifier before starting the loop
         let notifier = Notifier(event_loop.channel());
@@ -390,9 +818,23 @@ impl Terminal {
                 tokio_handle,
             },
             event_rx,
+            event_tx,
             config,
             title: "SSM".to_string(),
             dirty: Arc::new(AtomicBool::new(false)),
+            status: ConnectionStatus::Connecting,
+            recorder: None,
+            auth_input: false,
+            shell_exited: false,
+            connected_at: None,
+            bytes_sent: AtomicU64::new(0),
+            last_input: parking_lot::Mutex::new(Instant::now()),
+            bytes_received: AtomicU64::new(0),
+            shell_marks,
+            zmodem: parking_lot::Mutex::new(ZmodemLink::default()),
+            sudo_autofill: None,
+            host_key_request: None,
+            port_forwards: None,
             last_content: TerminalContent::default(),
         })
     }
@@ -402,11 +844,7 @@ impl Terminal {
         let id = Uuid::new_v4();
         let (event_tx, event_rx) = event_channel();
 
-        // Create terminal config with scrollback history
-        let term_config = TermConfig {
-            scrolling_history: config.scrollback_lines,
-            ..TermConfig::default()
-        };
+        let term_config = term_config(config.scrollback_lines);
 
         // Create terminal size
         let term_size = SizeInfo::new(config.size.cols, config.size.rows);
@@ -420,8 +858,10 @@ impl Terminal {
         };
 
         // Create the terminal
-        let term = Term::new(term_config, &term_size, event_tx.clone());
+        let mut term = Term::new(term_config, &term_size, event_tx.clone());
+        apply_tab_width(&mut term, config.tab_width, 0);
         let term = Arc::new(FairMutex::new(term));
+        let shell_marks = Arc::new(ShellMarks::new(event_tx.clone()));
 
         // Create PTY options - use a null placeholder that blocks
         #[cfg(windows)]
@@ -440,7 +880,7 @@ impl Terminal {
         let pty = tty::new(&pty_config, window_size, id.as_u128() as u64)?;
 
         // Create event loop
-        let event_loop = EventLoop::new(term.clone(), event_tx, pty, false, false)?;
+        let event_loop = EventLoop::new(term.clone(), event_tx.clone(), pty, false, false)?;
 
         // Get notifier before starting the loop
         let notifier = Notifier(event_loop.channel());
@@ -465,29 +905,142 @@ impl Terminal {
                 tokio_handle,
             },
             event_rx,
+            event_tx,
             config,
             title: "K8s".to_string(),
             dirty: Arc::new(AtomicBool::new(false)),
+            status: ConnectionStatus::Connecting,
+            recorder: None,
+            auth_input: false,
+            shell_exited: false,
+            connected_at: None,
+            bytes_sent: AtomicU64::new(0),
+            last_input: parking_lot::Mutex::new(Instant::now()),
+            bytes_received: AtomicU64::new(0),
+            shell_marks,
+            zmodem: parking_lot::Mutex::new(ZmodemLink::default()),
+            sudo_autofill: None,
+            host_key_request: None,
+            port_forwards: None,
+            last_content: TerminalContent::default(),
+        })
+    }
+
+    /// Create a display-only terminal for a backend driven by the shared I/O loop
+    ///
+    /// The caller keeps the backend and hands it to the loop, which installs
+    /// the real write/resize senders once connected.
+    pub fn new_backend(config: TerminalConfig, kind: &'static str) -> io::Result<Self> {
+        let id = Uuid::new_v4();
+        let (event_tx, event_rx) = event_channel();
+
+        let term_config = term_config(config.scrollback_lines);
+
+        // Create terminal size
+        let term_size = SizeInfo::new(config.size.cols, config.size.rows);
+
+        // Create window size (for PTY)
+        let window_size = WindowSize {
+            num_cols: config.size.cols,
+            num_lines: config.size.rows,
+            cell_width: 1,
+            cell_height: 1,
+        };
+
+        // Create the terminal
+        let mut term = Term::new(term_config, &term_size, event_tx.clone());
+        apply_tab_width(&mut term, config.tab_width, 0);
+        let term = Arc::new(FairMutex::new(term));
+        let shell_marks = Arc::new(ShellMarks::new(event_tx.clone()));
+
+        // Create PTY options - use a null placeholder that blocks
+        #[cfg(windows)]
+        let dummy_shell = tty::Shell::new("cmd.exe".to_string(), vec!["/c".to_string(), "pause>nul".to_string()]);
+        #[cfg(not(windows))]
+        let dummy_shell = tty::Shell::new("/bin/cat".to_string(), vec![]);
+
+        let pty_config = PtyOptions {
+            shell: Some(dummy_shell),
+            working_directory: None,
+            drain_on_exit: false,
+            env: HashMap::new(),
+        };
+
+        // Create a dummy PTY
+        let pty = tty::new(&pty_config, window_size, id.as_u128() as u64)?;
+
+        // Create event loop
+        let event_loop = EventLoop::new(term.clone(), event_tx.clone(), pty, false, false)?;
+
+        // Get notifier before starting the loop
+        let notifier = Notifier(event_loop.channel());
+
+        // Spawn the event loop
+        let _join_handle = event_loop.spawn();
+
+        // Set up write and resize channels
+        let (write_tx, _) = tokio::sync::mpsc::unbounded_channel();
+        let (resize_tx, _) = tokio::sync::mpsc::unbounded_channel();
+
+        Ok(Self {
+            id,
+            term,
+            mode: TerminalMode2::Backend {
+                notifier,
+                kind,
+                write_tx,
+                resize_tx,
+            },
+            event_rx,
+            event_tx,
+            config,
+            title: kind.to_string(),
+            dirty: Arc::new(AtomicBool::new(false)),
+            status: ConnectionStatus::Connecting,
+            recorder: None,
+            auth_input: false,
+            shell_exited: false,
+            connected_at: None,
+            bytes_sent: AtomicU64::new(0),
+            last_input: parking_lot::Mutex::new(Instant::now()),
+            bytes_received: AtomicU64::new(0),
+            shell_marks,
+            zmodem: parking_lot::Mutex::new(ZmodemLink::default()),
+            sudo_autofill: None,
+            host_key_request: None,
+            port_forwards: None,
             last_content: TerminalContent::default(),
         })
     }
 
     /// Update the write sender after I/O setup
     pub fn set_write_tx(&mut self, tx: tokio::sync::mpsc::UnboundedSender<Vec<u8>>) {
+        self.auth_input = false;
         match &mut self.mode {
             TerminalMode2::Remote { write_tx, .. } => *write_tx = tx,
             TerminalMode2::Ssm { write_tx, .. } => *write_tx = tx,
             TerminalMode2::K8s { write_tx, .. } => *write_tx = tx,
+            TerminalMode2::Backend { write_tx, .. } => *write_tx = tx,
             _ => {}
         }
     }
 
+    /// Send input to `tx` to answer a login prompt while connecting
+    ///
+    /// The input isn't recorded in the transcript. The session's writer
+    /// replaces `tx` through [`Self::set_write_tx`] once connected.
+    pub fn capture_auth_input(&mut self, tx: tokio::sync::mpsc::UnboundedSender<Vec<u8>>) {
+        self.set_write_tx(tx);
+        self.auth_input = true;
+    }
+
     /// Update the resize sender after I/O setup
     pub fn set_resize_tx(&mut self, tx: tokio::sync::mpsc::UnboundedSender<TerminalSize>) {
         match &mut self.mode {
             TerminalMode2::Remote { resize_tx, .. } => *resize_tx = tx,
             TerminalMode2::Ssm { resize_tx, .. } => *resize_tx = tx,
             TerminalMode2::K8s { resize_tx, .. } => *resize_tx = tx,
+            TerminalMode2::Backend { resize_tx, .. } => *resize_tx = tx,
             _ => {}
         }
     }
@@ -516,6 +1069,78 @@ impl Terminal {
         }
     }
 
+    /// Current connection state
+    pub fn status(&self) -> ConnectionStatus {
+        self.status
+    }
+
+    /// Update the connection state
+    ///
+    /// Becoming connected writes the connection marker, if configured.
+    pub fn set_status(&mut self, status: ConnectionStatus) {
+        if status == ConnectionStatus::Connected && self.status != ConnectionStatus::Connected {
+            let reconnect = self.connected_at.is_some();
+            self.connected_at = Some(Instant::now());
+            if let Some(format) = &self.config.connection_marker {
+                let at_line_start = self.term.lock().grid().cursor.point.column.0 == 0;
+                let line = connection_marker_line(format, reconnect, SystemTime::now());
+                let prefix = if at_line_start { "" } else { "\r\n" };
+                self.write_to_pty(format!("{}{}", prefix, line).as_bytes());
+            }
+        }
+        self.status = status;
+    }
+
+    /// How long the session has been connected, if it currently is
+    pub fn uptime(&self) -> Option<Duration> {
+        match self.status {
+            ConnectionStatus::Connected => self.connected_at.map(|t| t.elapsed()),
+            _ => None,
+        }
+    }
+
+    /// Total bytes of input sent
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent.load(Ordering::Relaxed)
+    }
+
+    /// Total bytes of output received (always 0 for local terminals, whose
+    /// output goes straight from the PTY to the parser)
+    pub fn bytes_received(&self) -> u64 {
+        self.bytes_received.load(Ordering::Relaxed)
+    }
+
+    /// Short name of the connection type
+    pub fn kind_label(&self) -> &'static str {
+        match &self.mode {
+            TerminalMode2::Local { .. } => "Local",
+            TerminalMode2::Remote { .. } => "SSH",
+            TerminalMode2::Ssm { .. } => "SSM",
+            TerminalMode2::K8s { .. } => "K8s",
+            TerminalMode2::Backend { kind, .. } => *kind,
+        }
+    }
+
+    /// What the Enter key sends in this terminal
+    pub fn newline_mode(&self) -> NewlineMode {
+        self.config.newline_mode
+    }
+
+    /// Whether this is a local shell rather than a remote session
+    pub fn is_local(&self) -> bool {
+        matches!(self.mode, TerminalMode2::Local { .. })
+    }
+
+    /// Start teeing session I/O into a transcript
+    pub fn set_recorder(&mut self, recorder: SessionRecorder) {
+        self.recorder = Some(parking_lot::Mutex::new(recorder));
+    }
+
+    /// Path of the active transcript, if this session is being recorded
+    pub fn recording_path(&self) -> Option<std::path::PathBuf> {
+        self.recorder.as_ref().map(|r| r.lock().path().to_path_buf())
+    }
+
     /// Get the terminal ID
     pub fn id(&self) -> Uuid {
         self.id
@@ -536,22 +1161,267 @@ impl Terminal {
                 // For local terminals, send through the PTY event loop
                 notifier.notify(data.to_vec());
             }
-            TerminalMode2::Remote { .. } | TerminalMode2::Ssm { .. } | TerminalMode2::K8s { .. } => {
-                // For SSH/SSM/K8s terminals, directly process data through the VT parser
+            TerminalMode2::Remote { .. }
+            | TerminalMode2::Ssm { .. }
+            | TerminalMode2::K8s { .. }
+            | TerminalMode2::Backend { .. } => {
+                // For remote terminals, directly process data through the VT parser
                 // This ensures escape sequences (like mouse mode) are handled correctly
+                self.bytes_received.fetch_add(data.len() as u64, Ordering::Relaxed);
+                let data = self.route_zmodem(data);
+                let marks = self.shell_marks.scan(&data);
+                self.scan_sudo_prompt(&data);
+                if let Some(recorder) = &self.recorder {
+                    recorder.lock().record_output(&data);
+                }
                 let mut processor = Processor::<StdSyncHandler>::new();
                 let mut term = self.term.lock();
-                processor.advance(&mut *term, data);
+                // Stop at each mark to see where the cursor got to
+                let mut start = 0;
+                for (end, mark) in marks {
+                    self.advance_parser(&mut processor, &mut term, &data[start..end]);
+                    self.shell_marks.record(&term, mark);
+                    start = end;
+                }
+                self.advance_parser(&mut processor, &mut term, &data[start..]);
                 // Signal that new content is available for rendering
                 self.dirty.store(true, Ordering::Release);
             }
         }
     }
 
+    /// Show a message from the app itself, e.g. that the shell exited
+    ///
+    /// Goes straight to the screen, bypassing the PTY, shell marks and any
+    /// transcript.
+    pub fn print_notice(&self, text: &str) {
+        let mut processor = Processor::<StdSyncHandler>::new();
+        let mut term = self.term.lock();
+        self.advance_parser(&mut processor, &mut term, text.as_bytes());
+        self.dirty.store(true, Ordering::Release);
+    }
+
+    /// Pass remote output through ZMODEM detection or the running transfer
+    ///
+    /// Returns the bytes that still belong on screen: everything when no
+    /// transfer is involved, status lines and trailing output otherwise.
+    fn route_zmodem<'a>(&self, data: &'a [u8]) -> Cow<'a, [u8]> {
+        if !self.config.zmodem_enabled {
+            return Cow::Borrowed(data);
+        }
+        let mut link = self.zmodem.lock();
+        let Some(transfer) = link.transfer.as_mut() else {
+            let Some((direction, offset)) = link.detector.scan(data) else {
+                return Cow::Borrowed(data);
+            };
+            // The remote repeats its header while waiting; only ask once per offer
+            let pending = link.offered.is_some_and(|(_, at)| at.elapsed() < ZMODEM_OFFER_TIMEOUT);
+            if !pending {
+                link.offered = Some((direction, Instant::now()));
+                let _ = self.event_tx.0.send(TerminalEvent::ZmodemDetected(direction));
+            }
+            return Cow::Owned(data[..offset].to_vec());
+        };
+
+        let output = transfer.feed(data);
+        if output.finished {
+            link.transfer = None;
+        }
+        drop(link);
+        Cow::Owned(self.apply_zmodem_output(output))
+    }
+
+    /// Send a transfer's reply and render its events as status lines
+    fn apply_zmodem_output(&self, output: ZmodemOutput) -> Vec<u8> {
+        if !output.reply.is_empty() {
+            self.send_raw(&output.reply);
+        }
+        let mut display = Vec::new();
+        for event in &output.events {
+            // Progress overwrites itself; everything else gets its own line
+            let newline = if matches!(event, ZmodemEvent::Progress { .. }) { "" } else { "\r\n" };
+            display.extend_from_slice(format!("\r\x1b[2K\x1b[2m{}\x1b[0m{}", event, newline).as_bytes());
+        }
+        display.extend(output.passthrough);
+        display
+    }
+
+    /// Accept an offered ZMODEM download, saving files into `dir`
+    pub fn accept_zmodem_receive(&self, dir: &Path) -> io::Result<()> {
+        let (receiver, start) = ZmodemReceiver::new(dir)?;
+        self.start_zmodem(ZmodemTransfer::Receive(receiver), &start);
+        Ok(())
+    }
+
+    /// Accept an offered ZMODEM upload by sending `path`
+    pub fn accept_zmodem_send(&self, path: &Path) -> io::Result<()> {
+        let (sender, start) = ZmodemSender::new(path)?;
+        self.start_zmodem(ZmodemTransfer::Send(sender), &start);
+        Ok(())
+    }
+
+    fn start_zmodem(&self, transfer: ZmodemTransfer, start: &[u8]) {
+        {
+            let mut link = self.zmodem.lock();
+            link.offered = None;
+            link.transfer = Some(transfer);
+        }
+        self.send_raw(start);
+    }
+
+    /// Decline an offered transfer or abort the running one
+    ///
+    /// Sends the cancel sequence so the remote `sz`/`rz` exits instead of
+    /// waiting for its timeout.
+    pub fn cancel_zmodem(&self) {
+        let was_running = {
+            let mut link = self.zmodem.lock();
+            link.offered = None;
+            link.transfer.take().is_some()
+        };
+        self.send_raw(ABORT_SEQUENCE);
+        if was_running {
+            let mut processor = Processor::<StdSyncHandler>::new();
+            let mut term = self.term.lock();
+            processor.advance(&mut *term, b"\r\n\x1b[2mZMODEM: transfer cancelled\x1b[0m\r\n");
+            self.dirty.store(true, Ordering::Release);
+        }
+    }
+
+    /// Whether a ZMODEM transfer currently owns the session
+    pub fn zmodem_active(&self) -> bool {
+        self.zmodem.lock().transfer.is_some()
+    }
+
+    fn advance_parser(
+        &self,
+        processor: &mut Processor<StdSyncHandler>,
+        term: &mut Term<TerminalEventSender>,
+        data: &[u8],
+    ) {
+        if self.config.ambiguous_wide {
+            processor.advance(&mut AmbiguousWide::new(term), data);
+        } else {
+            processor.advance(term, data);
+        }
+    }
+
+    /// Whether any command has finished since the session started
+    ///
+    /// Cheap; [`Self::command_outputs`] may still come back empty if the
+    /// commands have since left the scrollback.
+    pub fn has_command_outputs(&self) -> bool {
+        self.shell_marks.regions.lock().finished().next().is_some()
+    }
+
+    /// Commands that finished and are still in the scrollback, oldest first
+    ///
+    /// Only sessions whose shell emits OSC 133 marks have any.
+    pub fn command_outputs(&self) -> Vec<CommandOutput> {
+        let term = self.term.lock();
+        let mut regions = self.shell_marks.regions.lock();
+        regions.rebase(|line| row_anchor(&term, line));
+        let row = |line| absolute_row(&term, line);
+        regions
+            .finished()
+            .filter_map(|region| {
+                let (output, end) = (region.output?, region.end?);
+                Some(CommandOutput {
+                    command: text_between(region.command, output, row),
+                    output: text_between(output, end, row),
+                    exit_code: region.exit_code,
+                })
+            })
+            .collect()
+    }
+
+    /// Where finished commands start, with their exit codes, oldest first
+    ///
+    /// Lines are counted from the top of the scrollback.
+    pub fn command_marks(&self) -> Vec<(usize, Option<i32>)> {
+        let term = self.term.lock();
+        let mut regions = self.shell_marks.regions.lock();
+        regions.rebase(|line| row_anchor(&term, line));
+        regions.finished().map(|region| (region.command.line, region.exit_code)).collect()
+    }
+
+    /// Raise `SudoPrompt` when output stops at a `sudo` password prompt
+    fn scan_sudo_prompt(&self, data: &[u8]) {
+        if let Some(autofill) = &self.sudo_autofill {
+            if autofill.detector.lock().scan(data) {
+                let _ = self.event_tx.0.send(TerminalEvent::SudoPrompt);
+            }
+        }
+    }
+
+    /// Hold a host key question for the UI and raise `HostKeyPrompt`
+    ///
+    /// A question still pending is dropped, which rejects its key.
+    pub fn ask_host_key(&mut self, request: HostKeyRequest) {
+        self.host_key_request = Some(request);
+        let _ = self.event_tx.0.send(TerminalEvent::HostKeyPrompt);
+    }
+
+    /// The SSH session's port forwards, if this is an SSH terminal
+    pub fn port_forwards(&self) -> Option<&PortForwards> {
+        self.port_forwards.as_ref()
+    }
+
+    /// Take the pending host key question to show it
+    pub fn take_host_key_request(&mut self) -> Option<HostKeyRequest> {
+        self.host_key_request.take()
+    }
+
+    /// Offer `password` whenever the remote asks for a `sudo` password
+    pub fn set_sudo_autofill(&mut self, password: String) {
+        self.sudo_autofill = Some(SudoAutofill {
+            password,
+            detector: parking_lot::Mutex::new(SudoPromptDetector::default()),
+        });
+    }
+
+    /// Answer a `sudo` prompt with the stored password followed by Enter
+    ///
+    /// The password bypasses the transcript recorder and is never logged.
+    /// Returns false if the session has no password to offer.
+    pub fn send_sudo_password(&self) -> bool {
+        let Some(autofill) = &self.sudo_autofill else {
+            return false;
+        };
+        let mut bytes = Vec::with_capacity(autofill.password.len() + 1);
+        bytes.extend_from_slice(autofill.password.as_bytes());
+        bytes.push(b'\r');
+        *self.last_input.lock() = Instant::now();
+        self.send_raw(&bytes);
+        true
+    }
+
     /// Write keyboard input (goes to PTY for local, SSH/SSM for remote)
     ///
     /// This sends user keyboard input to the shell/remote process.
     pub fn write(&self, data: &[u8]) {
+        // Typing would corrupt a running transfer; Ctrl+C cancels it instead
+        if self.zmodem_active() {
+            if data.contains(&0x03) {
+                self.cancel_zmodem();
+            }
+            return;
+        }
+        if let Some(recorder) = self.recorder.as_ref().filter(|_| !self.auth_input) {
+            recorder.lock().record_input(data);
+        }
+        *self.last_input.lock() = Instant::now();
+        self.send_raw(data);
+    }
+
+    /// Time since input was last written to the session
+    pub fn idle_time(&self) -> Duration {
+        self.last_input.lock().elapsed()
+    }
+
+    /// Send bytes to the shell/remote process without recording them
+    fn send_raw(&self, data: &[u8]) {
+        self.bytes_sent.fetch_add(data.len() as u64, Ordering::Relaxed);
         match &self.mode {
             TerminalMode2::Local { notifier } => {
                 notifier.notify(data.to_vec());
@@ -577,6 +1447,12 @@ impl Terminal {
                     tracing::error!("K8s write send error: {}", e);
                 }
             }
+            TerminalMode2::Backend { kind, write_tx, .. } => {
+                tracing::debug!("{} write: queuing {} bytes", kind, data.len());
+                if let Err(e) = write_tx.send(data.to_vec()) {
+                    tracing::error!("{} write send error: {}", kind, e);
+                }
+            }
         }
     }
 
@@ -591,11 +1467,37 @@ impl Terminal {
             if let TerminalEvent::TitleChanged(ref new_title) = event {
                 self.title = new_title.clone();
             }
+            if let TerminalEvent::Exit(_) = event {
+                // Remote terminals run a placeholder PTY whose exit means nothing
+                if !self.is_local() || self.shell_exited {
+                    continue;
+                }
+                self.shell_exited = true;
+            }
             events.push(event);
         }
         events
     }
 
+    /// Approximate memory held by this terminal's screen and scrollback, in bytes
+    pub fn memory_usage(&self) -> usize {
+        grid_memory(&*self.term.lock_unfair())
+    }
+
+    /// Trim the oldest scrollback if the buffer exceeds the configured memory cap
+    ///
+    /// Cheap when under the cap, so it can be called whenever new output arrives.
+    pub fn enforce_memory_limit(&self) {
+        let limit = self.config.scrollback_memory_limit;
+        if limit == 0 {
+            return;
+        }
+        let mut term = self.term.lock();
+        if trim_history(&mut term, limit, self.config.scrollback_lines) {
+            tracing::debug!("Trimmed scrollback of terminal {} to {} lines", self.id, term.history_size());
+        }
+    }
+
     /// Check if new content has been written (for SSH mode)
     /// Returns true if dirty and clears the flag
     #[must_use]
@@ -610,6 +1512,10 @@ impl Terminal {
 
     /// Resize the terminal
     pub fn resize(&mut self, size: TerminalSize) {
+        // Reflowing to a new width moves text between lines
+        if size.cols != self.config.size.cols {
+            self.shell_marks.regions.lock().clear();
+        }
         self.config.size = size;
 
         // cell_width/cell_height are per-cell dimensions, not total window size
@@ -637,7 +1543,9 @@ impl Terminal {
         // Resize the terminal grid
         {
             let mut term = self.term.lock();
+            let old_columns = term.columns();
             term.resize(size_info);
+            apply_tab_width(&mut term, self.config.tab_width, old_columns);
         }
 
         // Notify the PTY / SSH / SSM backend
@@ -675,6 +1583,16 @@ impl Terminal {
                     tracing::error!("K8s resize send error: {}", e);
                 }
             }
+            TerminalMode2::Backend { notifier, kind, resize_tx, .. } => {
+                // Notify the event loop
+                let _ = notifier.0.send(Msg::Resize(window_size));
+
+                // Send resize through channel (handled by I/O loop)
+                tracing::debug!("{} resize: queuing {}x{}", kind, size.cols, size.rows);
+                if let Err(e) = resize_tx.send(size) {
+                    tracing::error!("{} resize send error: {}", kind, e);
+                }
+            }
         }
     }
 
@@ -767,6 +1685,19 @@ impl Terminal {
         term.scroll_display(alacritty_terminal::grid::Scroll::Delta(lines));
     }
 
+    /// Scroll so the view sits `offset` lines back from the live screen
+    pub fn scroll_to_offset(&self, offset: usize) {
+        let mut term = self.term.lock();
+        let delta = offset as i32 - term.grid().display_offset() as i32;
+        term.scroll_display(alacritty_terminal::grid::Scroll::Delta(delta));
+    }
+
+    /// Jump back to the live screen
+    pub fn scroll_to_bottom(&self) {
+        let mut term = self.term.lock();
+        term.scroll_display(alacritty_terminal::grid::Scroll::Bottom);
+    }
+
     // --- Selection methods using alacritty's built-in selection ---
 
     /// Start a selection at the given point
@@ -826,9 +1757,27 @@ impl Terminal {
             selection: content.selection,
             cursor_point: grid.cursor.point,
             colors: *term.colors(),
+            cursor_shape: Some(term.cursor_style().shape).filter(|shape| *shape != CursorShape::Hidden),
+            cursor_steady: !term.cursor_style().blinking,
         };
     }
 
+    /// Rebuild the render cache from the current terminal state
+    ///
+    /// Used when a tab comes back into view. A scrollback position left in
+    /// the alternate screen, which has no history of its own, is reset so
+    /// full-screen apps are shown as they are now.
+    pub fn refresh_display(&mut self) {
+        {
+            let mut term = self.term.lock();
+            if term.mode().contains(TermMode::ALT_SCREEN) && term.grid().display_offset() != 0 {
+                term.scroll_display(alacritty_terminal::grid::Scroll::Bottom);
+            }
+        }
+        self.dirty.store(true, Ordering::Release);
+        self.sync();
+    }
+
     /// Extract the last N lines of terminal content as text
     pub fn extract_last_lines(&self, line_count: usize) -> String {
         self.with_term(|term| {
@@ -934,6 +1883,16 @@ impl Terminal {
         })
     }
 
+    /// URI of the OSC 8 hyperlink at `point`, in grid coordinates, if any
+    pub fn hyperlink_at(&self, point: Point) -> Option<String> {
+        self.with_term(|term| hyperlink_at(term, point))
+    }
+
+    /// Bare URL at `point`, in grid coordinates, with the cells it spans
+    pub fn url_under_point(&self, point: Point) -> Option<(String, RangeInclusive<Point>)> {
+        self.with_term(|term| url_around(term, point))
+    }
+
     /// Get display offset for scrolling
     pub fn display_offset(&self) -> usize {
         self.with_term(|term| term.grid().display_offset())
@@ -982,6 +1941,7 @@ impl Drop for Terminal {
             TerminalMode2::Remote { notifier, .. } => notifier,
             TerminalMode2::Ssm { notifier, .. } => notifier,
             TerminalMode2::K8s { notifier, .. } => notifier,
+            TerminalMode2::Backend { notifier, .. } => notifier,
         };
         let _ = notifier.0.send(Msg::Shutdown);
     }
@@ -1032,6 +1992,22 @@ pub fn color_to_rgb_with_scheme(color: Color, colors: &Colors, scheme: &ColorSch
     }
 }
 
+/// Resolve the cursor color for the cell under the cursor
+///
+/// Prefers a color set via OSC 12 and falls back to the scheme cursor color.
+/// Without OSC 12, a cell in reverse video has its background drawn with the
+/// cell's foreground, so the cursor takes the cell's own background color
+/// instead to stay visible.
+pub fn cursor_color_with_scheme(colors: &Colors, scheme: &ColorScheme, cell: Option<&Cell>) -> Rgb {
+    if let Some(color) = colors[NamedColor::Cursor] {
+        return color;
+    }
+    if let Some(cell) = cell.filter(|c| c.flags.contains(Flags::INVERSE)) {
+        return color_to_rgb_with_scheme(cell.bg, colors, scheme);
+    }
+    hex_to_rgb(scheme.cursor)
+}
+
 /// Convert a named color to RGB using a color scheme
 fn named_color_to_rgb_with_scheme(named: NamedColor, colors: &Colors, scheme: &ColorScheme) -> Rgb {
     match colors[named] {
@@ -1227,6 +2203,169 @@ mod tests {
         assert_eq!(config.scrollback_lines, 10000);
         assert_eq!(config.size.cols, 80);
         assert_eq!(config.size.rows, 24);
+        assert_eq!(config.tab_width, DEFAULT_TAB_WIDTH);
+        assert!(config.shell.is_none());
+    }
+
+    #[test]
+    fn test_shell_command() {
+        assert_eq!(shell_command("fish"), Some(("fish".to_string(), vec![])));
+        assert_eq!(
+            shell_command("  /bin/zsh -l  -i "),
+            Some(("/bin/zsh".to_string(), vec!["-l".to_string(), "-i".to_string()]))
+        );
+        assert_eq!(shell_command("   "), None);
+    }
+
+    #[test]
+    fn test_trim_history_to_memory_limit() {
+        let (event_tx, _event_rx) = event_channel();
+        let config = TermConfig {
+            scrolling_history: 10000,
+            ..TermConfig::default()
+        };
+        let mut term = Term::new(config, &SizeInfo::new(80, 24), event_tx);
+        let mut processor = Processor::<StdSyncHandler>::new();
+        for i in 0..2000 {
+            processor.advance(&mut term, format!("line {}\r\n", i).as_bytes());
+        }
+        assert!(term.history_size() > 1900);
+
+        let limit = 500 * row_bytes(80);
+        assert!(!trim_history(&mut term, 0, 10000));
+        assert!(trim_history(&mut term, limit, 10000));
+        assert!(grid_memory(&term) <= limit);
+        assert!(term.history_size() < 500);
+        assert!(!trim_history(&mut term, limit, 10000));
+
+        // The line limit is restored, so history keeps growing after a trim
+        let trimmed = term.history_size();
+        processor.advance(&mut term, b"more\r\n".repeat(100).as_slice());
+        assert_eq!(term.history_size(), trimmed + 100);
+    }
+
+    /// Columns at which each tab-separated field starts
+    fn tab_field_columns(tab_width: usize) -> Vec<usize> {
+        let (event_tx, _event_rx) = event_channel();
+        let mut term = Term::new(TermConfig::default(), &SizeInfo::new(80, 24), event_tx);
+        apply_tab_width(&mut term, tab_width, 0);
+
+        let mut processor = Processor::<StdSyncHandler>::new();
+        processor.advance(&mut term, b"a\tbb\tccc\td");
+
+        let row = &term.grid()[Line(0)];
+        (0..80).filter(|&col| !matches!(row[Column(col)].c, ' ' | '\t')).collect()
+    }
+
+    #[test]
+    fn test_connection_marker_line() {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        assert_eq!(
+            connection_marker_line("── {event} {time} ──", false, time),
+            "\x1b[2m── Connected 2023-11-14 22:13:20 UTC ──\x1b[0m\r\n"
+        );
+        assert_eq!(connection_marker_line("[{event}]", true, time), "\x1b[2m[Reconnected]\x1b[0m\r\n");
+    }
+
+    #[test]
+    fn test_default_tab_stops() {
+        assert_eq!(tab_field_columns(8), vec![0, 8, 9, 16, 17, 18, 24]);
+    }
+
+    #[test]
+    fn test_configured_tab_width() {
+        assert_eq!(tab_field_columns(4), vec![0, 4, 5, 8, 9, 10, 12]);
+    }
+
+    #[test]
+    fn test_tab_width_survives_resize() {
+        let (event_tx, _event_rx) = event_channel();
+        let mut term = Term::new(TermConfig::default(), &SizeInfo::new(20, 24), event_tx);
+        apply_tab_width(&mut term, 4, 0);
+        term.resize(SizeInfo::new(40, 24));
+        apply_tab_width(&mut term, 4, 20);
+
+        let mut processor = Processor::<StdSyncHandler>::new();
+        processor.advance(&mut term, b"\t\t\t\t\t\tx");
+        assert_eq!(term.grid()[Line(0)][Column(24)].c, 'x');
+    }
+
+    #[test]
+    fn test_set_tab_stops_survive_resize() {
+        let (event_tx, _event_rx) = event_channel();
+        let mut term = Term::new(TermConfig::default(), &SizeInfo::new(20, 24), event_tx);
+        apply_tab_width(&mut term, 4, 0);
+        let mut processor = Processor::<StdSyncHandler>::new();
+        // Clear every stop (TBC 3), then set one at column 6 (HTS)
+        processor.advance(&mut term, b"\x1b[3g\x1b[7G\x1bH\r");
+        term.resize(SizeInfo::new(40, 24));
+        apply_tab_width(&mut term, 4, 20);
+
+        processor.advance(&mut term, b"\tx\ty\tz");
+        let row = &term.grid()[Line(0)];
+        assert_eq!(row[Column(6)].c, 'x');
+        // Columns added by the resize get the configured stops
+        assert_eq!(row[Column(20)].c, 'y');
+        assert_eq!(row[Column(24)].c, 'z');
+    }
+
+    #[test]
+    fn test_application_cursor_shape() {
+        let (event_tx, _event_rx) = event_channel();
+        let mut term = Term::new(term_config(100), &SizeInfo::new(80, 24), event_tx);
+        let mut processor = Processor::<StdSyncHandler>::new();
+        // Nothing chosen yet, even after the blink mode is toggled
+        processor.advance(&mut term, b"\x1b[?12h");
+        assert_eq!(term.cursor_style().shape, CursorShape::Hidden);
+
+        processor.advance(&mut term, b"\x1b[6 q");
+        assert_eq!(term.cursor_style().shape, CursorShape::Beam);
+        processor.advance(&mut term, b"\x1b[2 q");
+        assert_eq!(term.cursor_style().shape, CursorShape::Block);
+        // DECSCUSR 0 goes back to the user's choice
+        processor.advance(&mut term, b"\x1b[0 q");
+        assert_eq!(term.cursor_style().shape, CursorShape::Hidden);
+        assert!(term.cursor_style().blinking);
+
+        // Steady styles, by DECSCUSR or by resetting mode 12
+        processor.advance(&mut term, b"\x1b[4 q");
+        assert!(!term.cursor_style().blinking);
+        processor.advance(&mut term, b"\x1b[0 q\x1b[?12l");
+        assert!(!term.cursor_style().blinking);
+        assert_eq!(term.cursor_style().shape, CursorShape::Hidden);
+    }
+
+    #[test]
+    fn test_hyperlink_at() {
+        let (event_tx, _event_rx) = event_channel();
+        let mut term = Term::new(TermConfig::default(), &SizeInfo::new(80, 24), event_tx);
+        let mut processor = Processor::<StdSyncHandler>::new();
+        processor.advance(&mut term, b"see \x1b]8;;https://example.com/run/1\x1b\\logs\x1b]8;;\x1b\\ here");
+
+        let link = Some("https://example.com/run/1".to_string());
+        assert_eq!(hyperlink_at(&term, Point::new(Line(0), Column(4))), link);
+        assert_eq!(hyperlink_at(&term, Point::new(Line(0), Column(7))), link);
+        assert_eq!(hyperlink_at(&term, Point::new(Line(0), Column(8))), None);
+        assert_eq!(hyperlink_at(&term, Point::new(Line(0), Column(3))), None);
+        // Off the grid
+        assert_eq!(hyperlink_at(&term, Point::new(Line(-1), Column(4))), None);
+        assert_eq!(hyperlink_at(&term, Point::new(Line(0), Column(80))), None);
+    }
+
+    #[test]
+    fn test_url_around() {
+        let (event_tx, _event_rx) = event_channel();
+        let mut term = Term::new(TermConfig::default(), &SizeInfo::new(20, 24), event_tx);
+        let mut processor = Processor::<StdSyncHandler>::new();
+        // Wraps after "https://example" onto the next row
+        processor.advance(&mut term, b"logs: https://example.com/x.\r\nnone here");
+
+        let url = "https://example.com/x".to_string();
+        let span = Point::new(Line(0), Column(6))..=Point::new(Line(1), Column(6));
+        assert_eq!(url_around(&term, Point::new(Line(0), Column(10))), Some((url.clone(), span.clone())));
+        assert_eq!(url_around(&term, Point::new(Line(1), Column(2))), Some((url, span)));
+        assert_eq!(url_around(&term, Point::new(Line(1), Column(7))), None);
+        assert_eq!(url_around(&term, Point::new(Line(2), Column(0))), None);
     }
 
     #[test]
@@ -1243,4 +2382,43 @@ mod tests {
         assert_eq!(rgb.g, 0);
         assert_eq!(rgb.b, 0);
     }
+
+    #[test]
+    fn test_osc12_cursor_color() {
+        let (event_tx, _event_rx) = event_channel();
+        let mut term = Term::new(TermConfig::default(), &SizeInfo::new(80, 24), event_tx);
+        let mut processor = Processor::<StdSyncHandler>::new();
+        let scheme = ColorScheme::default_dark();
+
+        // No OSC 12 yet: scheme cursor color
+        assert_eq!(cursor_color_with_scheme(term.colors(), &scheme, None), hex_to_rgb(scheme.cursor));
+
+        processor.advance(&mut term, b"\x1b]12;#ff8000\x07");
+        let expected = Rgb { r: 0xff, g: 0x80, b: 0x00 };
+        assert_eq!(term.colors()[NamedColor::Cursor], Some(expected));
+        assert_eq!(cursor_color_with_scheme(term.colors(), &scheme, None), expected);
+
+        // OSC 112 resets back to the scheme color
+        processor.advance(&mut term, b"\x1b]112\x07");
+        assert_eq!(term.colors()[NamedColor::Cursor], None);
+        assert_eq!(cursor_color_with_scheme(term.colors(), &scheme, None), hex_to_rgb(scheme.cursor));
+    }
+
+    #[test]
+    fn test_cursor_color_on_inverse_cell() {
+        let mut colors = Colors::default();
+        let scheme = ColorScheme::default_dark();
+        let mut cell = Cell::default();
+        cell.flags.insert(Flags::INVERSE);
+
+        // Inverse cell background is painted with the foreground, so the
+        // cursor uses the background color to contrast with it
+        let color = cursor_color_with_scheme(&colors, &scheme, Some(&cell));
+        assert_eq!(color, hex_to_rgb(scheme.background));
+
+        // A color set with OSC 12 still wins
+        let osc12 = Rgb { r: 0xff, g: 0x80, b: 0x00 };
+        colors[NamedColor::Cursor] = Some(osc12);
+        assert_eq!(cursor_color_with_scheme(&colors, &scheme, Some(&cell)), osc12);
+    }
 }
@@ -0,0 +1,87 @@
+//! Bare URLs in terminal output
+//!
+//! Programs that don't emit OSC 8 hyperlinks still print plain `https://`
+//! links. These are picked out of a line's text so they can be opened with a
+//! Cmd/Ctrl-click like a real hyperlink.
+
+use regex_lite::Regex;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+/// A scheme we open, then everything up to whitespace or a character that
+/// can't appear unescaped in a URL
+fn url_regex() -> &'static Regex {
+    static URL: OnceLock<Regex> = OnceLock::new();
+    URL.get_or_init(|| Regex::new(r#"(?:https?|file)://[^\s<>"'`{}|\\^\x00]+"#).unwrap())
+}
+
+/// The URL covering character `column` of `line`, with its character range
+///
+/// Sentence punctuation right after a URL is left out, as is a closing
+/// bracket the URL didn't open, so `(see https://example.com/a).` gives
+/// `https://example.com/a`.
+pub fn url_at(line: &str, column: usize) -> Option<(Range<usize>, String)> {
+    url_regex().find_iter(line).find_map(|m| {
+        let url = trim_url(m.as_str());
+        if url.ends_with("://") {
+            return None;
+        }
+        let start = line[..m.start()].chars().count();
+        let range = start..start + url.chars().count();
+        range.contains(&column).then(|| (range, url.to_string()))
+    })
+}
+
+/// Drop trailing characters that more likely end the sentence than the URL
+fn trim_url(mut url: &str) -> &str {
+    while let Some(last) = url.chars().last() {
+        let unbalanced = |open: char| url.matches(open).count() < url.matches(last).count();
+        let trim = match last {
+            '.' | ',' | ':' | ';' | '!' | '?' => true,
+            ')' => unbalanced('('),
+            ']' => unbalanced('['),
+            _ => false,
+        };
+        if !trim {
+            break;
+        }
+        url = &url[..url.len() - last.len_utf8()];
+    }
+    url
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(line: &str, column: usize) -> Option<String> {
+        url_at(line, column).map(|(_, url)| url)
+    }
+
+    #[test]
+    fn test_url_at() {
+        let line = "see https://example.com/build/42 for details";
+        assert_eq!(url_at(line, 4), Some((4..32, "https://example.com/build/42".to_string())));
+        assert_eq!(url(line, 31).as_deref(), Some("https://example.com/build/42"));
+        assert_eq!(url(line, 3), None);
+        assert_eq!(url(line, 32), None);
+
+        // Trailing punctuation and unmatched brackets belong to the sentence
+        assert_eq!(url("Docs: https://example.com/docs.", 10).as_deref(), Some("https://example.com/docs"));
+        assert_eq!(url("(at https://example.com/a).", 10).as_deref(), Some("https://example.com/a"));
+        assert_eq!(url("https://example.com/a?b=1,", 0).as_deref(), Some("https://example.com/a?b=1"));
+        assert_eq!(
+            url("https://en.wikipedia.org/wiki/Rust_(programming_language)", 0).as_deref(),
+            Some("https://en.wikipedia.org/wiki/Rust_(programming_language)")
+        );
+        // The dot after the URL isn't part of it
+        assert_eq!(url("go to http://example.com.", 24), None);
+
+        assert_eq!(url("open file:///tmp/report.html now", 8).as_deref(), Some("file:///tmp/report.html"));
+        assert_eq!(url("<https://example.com/x>", 5).as_deref(), Some("https://example.com/x"));
+        assert_eq!(url("ftp://example.com/x", 0), None);
+        assert_eq!(url("https://.", 0), None);
+        // Columns count characters, not bytes
+        assert_eq!(url_at("→ https://example.com", 2).map(|(range, _)| range), Some(2..21));
+    }
+}
@@ -0,0 +1,1087 @@
+//! ZMODEM file transfer over the shell stream
+//!
+//! Devices without SFTP can still move files with lrzsz: `sz` on the remote
+//! end sends a file to us, `rz` receives one from us. Both announce themselves
+//! with a ZMODEM hex header in the terminal output, which [`ZmodemDetector`]
+//! spots. Once the user accepts, a [`ZmodemTransfer`] takes over the stream
+//! until the session ends.
+//!
+//! The receiver and sender are plain state machines: they are fed the remote
+//! output and return the bytes to send back, so they don't know about SSH.
+
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use thiserror::Error;
+
+use crate::format::format_size;
+
+const ZPAD: u8 = b'*';
+const ZDLE: u8 = 0x18;
+const ZBIN: u8 = b'A';
+const ZHEX: u8 = b'B';
+const ZBIN32: u8 = b'C';
+
+// Frame types
+const ZRQINIT: u8 = 0;
+const ZRINIT: u8 = 1;
+const ZSINIT: u8 = 2;
+const ZACK: u8 = 3;
+const ZFILE: u8 = 4;
+const ZSKIP: u8 = 5;
+const ZNAK: u8 = 6;
+const ZABORT: u8 = 7;
+const ZFIN: u8 = 8;
+const ZRPOS: u8 = 9;
+const ZDATA: u8 = 10;
+const ZEOF: u8 = 11;
+const ZFERR: u8 = 12;
+const ZCAN: u8 = 16;
+
+// Data subpacket terminators
+const ZCRCE: u8 = b'h';
+const ZCRCG: u8 = b'i';
+const ZCRCQ: u8 = b'j';
+const ZCRCW: u8 = b'k';
+const ZRUB0: u8 = b'l';
+const ZRUB1: u8 = b'm';
+
+// ZRINIT capability flags
+const CANFDX: u8 = 0x01;
+const CANOVIO: u8 = 0x02;
+const CANFC32: u8 = 0x20;
+
+/// Payload bytes per data subpacket we send
+const SUBPACKET_SIZE: usize = 1024;
+/// Bytes sent before waiting for the receiver to acknowledge
+const WINDOW_SIZE: usize = 16 * 1024;
+/// Longest subpacket accepted before assuming the stream is corrupt
+const MAX_SUBPACKET: usize = 8 * 1024;
+/// Report progress roughly this often
+const PROGRESS_STEP: u64 = 64 * 1024;
+
+/// Cancels a transfer on the remote end (eight CANs, then backspaces to erase them)
+pub const ABORT_SEQUENCE: &[u8] = b"\x18\x18\x18\x18\x18\x18\x18\x18\x08\x08\x08\x08\x08\x08\x08\x08\x08\x08";
+
+/// Errors that can occur while decoding the ZMODEM stream
+#[derive(Debug, Error)]
+pub enum ZmodemError {
+    #[error("File error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("CRC mismatch")]
+    Crc,
+
+    #[error("Protocol error: {0}")]
+    Protocol(String),
+}
+
+/// Which side of the transfer the remote program wants us to take
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZmodemDirection {
+    /// The remote ran `sz`; we receive
+    Receive,
+    /// The remote ran `rz`; we send
+    Send,
+}
+
+/// Spots the start of a ZMODEM session in terminal output
+///
+/// `sz` opens with a ZRQINIT hex header (`**\x18B00`), `rz` with ZRINIT
+/// (`**\x18B01`). The header may be split across reads.
+#[derive(Debug, Default)]
+pub struct ZmodemDetector {
+    tail: Vec<u8>,
+}
+
+impl ZmodemDetector {
+    const MARKER: &'static [u8] = b"**\x18B0";
+
+    /// Scan a chunk of output
+    ///
+    /// Returns the requested direction and the offset in `data` where the
+    /// header starts; everything before it is ordinary output.
+    pub fn scan(&mut self, data: &[u8]) -> Option<(ZmodemDirection, usize)> {
+        let mut window = std::mem::take(&mut self.tail);
+        let carried = window.len();
+        window.extend_from_slice(data);
+
+        let found = window
+            .windows(Self::MARKER.len() + 1)
+            .position(|w| w.starts_with(Self::MARKER) && matches!(w[Self::MARKER.len()], b'0' | b'1'));
+        match found {
+            Some(pos) => {
+                let direction = if window[pos + Self::MARKER.len()] == b'0' {
+                    ZmodemDirection::Receive
+                } else {
+                    ZmodemDirection::Send
+                };
+                Some((direction, pos.saturating_sub(carried)))
+            }
+            None => {
+                self.tail = window[window.len().saturating_sub(Self::MARKER.len())..].to_vec();
+                None
+            }
+        }
+    }
+}
+
+/// Something worth telling the user about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ZmodemEvent {
+    FileStarted { name: String, size: Option<u64> },
+    Progress { name: String, bytes: u64, size: Option<u64> },
+    FileCompleted { name: String, path: PathBuf, bytes: u64 },
+    FileSkipped { name: String },
+    Aborted(String),
+}
+
+impl fmt::Display for ZmodemEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ZmodemEvent::FileStarted { name, size: Some(size) } => {
+                write!(f, "ZMODEM: transferring {} ({})", name, format_size(*size))
+            }
+            ZmodemEvent::FileStarted { name, size: None } => write!(f, "ZMODEM: transferring {}", name),
+            ZmodemEvent::Progress { name, bytes, size: Some(size) } => {
+                write!(f, "ZMODEM: {} {} / {}", name, format_size(*bytes), format_size(*size))
+            }
+            ZmodemEvent::Progress { name, bytes, size: None } => {
+                write!(f, "ZMODEM: {} {}", name, format_size(*bytes))
+            }
+            ZmodemEvent::FileCompleted { name, path, bytes } => {
+                write!(f, "ZMODEM: {} done ({}) -> {}", name, format_size(*bytes), path.display())
+            }
+            ZmodemEvent::FileSkipped { name } => write!(f, "ZMODEM: {} skipped by receiver", name),
+            ZmodemEvent::Aborted(reason) => write!(f, "ZMODEM: transfer aborted: {}", reason),
+        }
+    }
+}
+
+/// Result of feeding remote output to a transfer
+#[derive(Debug, Default)]
+pub struct ZmodemOutput {
+    /// Bytes to send to the remote end
+    pub reply: Vec<u8>,
+    /// Progress to report
+    pub events: Vec<ZmodemEvent>,
+    /// Output after the end of the session, which belongs to the terminal again
+    pub passthrough: Vec<u8>,
+    /// The session is over (completed or aborted)
+    pub finished: bool,
+}
+
+impl ZmodemOutput {
+    fn abort(&mut self, reason: impl Into<String>) {
+        self.reply.extend_from_slice(ABORT_SEQUENCE);
+        self.events.push(ZmodemEvent::Aborted(reason.into()));
+        self.finished = true;
+    }
+}
+
+/// An accepted transfer in either direction
+pub enum ZmodemTransfer {
+    Receive(ZmodemReceiver),
+    Send(ZmodemSender),
+}
+
+impl ZmodemTransfer {
+    /// Process output from the remote end
+    pub fn feed(&mut self, data: &[u8]) -> ZmodemOutput {
+        match self {
+            ZmodemTransfer::Receive(receiver) => receiver.feed(data),
+            ZmodemTransfer::Send(sender) => sender.feed(data),
+        }
+    }
+}
+
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc: u32 = !0;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// ZDLE-escape `data` onto `out`
+///
+/// Besides ZDLE itself, the flow-control characters and CR are escaped so
+/// nothing in between (ssh, stty, a serial console) can eat them.
+fn escape_into(out: &mut Vec<u8>, data: &[u8]) {
+    for &byte in data {
+        match byte {
+            ZDLE | 0x10 | 0x90 | 0x11 | 0x91 | 0x13 | 0x93 | 0x0d | 0x8d => {
+                out.push(ZDLE);
+                out.push(byte ^ 0x40);
+            }
+            _ => out.push(byte),
+        }
+    }
+}
+
+/// A frame header: type plus four bytes of position or flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Header {
+    frame_type: u8,
+    data: [u8; 4],
+}
+
+impl Header {
+    fn new(frame_type: u8) -> Self {
+        Self { frame_type, data: [0; 4] }
+    }
+
+    fn with_position(frame_type: u8, position: u64) -> Self {
+        Self {
+            frame_type,
+            data: (position as u32).to_le_bytes(),
+        }
+    }
+
+    fn position(&self) -> u64 {
+        u32::from_le_bytes(self.data) as u64
+    }
+
+    fn bytes(&self) -> [u8; 5] {
+        [self.frame_type, self.data[0], self.data[1], self.data[2], self.data[3]]
+    }
+
+    /// Hex header, used for frames not followed by data
+    fn encode_hex(&self) -> Vec<u8> {
+        let bytes = self.bytes();
+        let mut out = vec![ZPAD, ZPAD, ZDLE, ZHEX];
+        for byte in bytes.iter().chain(crc16(&bytes).to_be_bytes().iter()) {
+            out.extend_from_slice(format!("{:02x}", byte).as_bytes());
+        }
+        out.extend_from_slice(b"\r\x8a");
+        if self.frame_type != ZFIN && self.frame_type != ZACK {
+            out.push(0x11);
+        }
+        out
+    }
+
+    /// Binary header with a 16-bit CRC, used before data subpackets
+    fn encode_bin(&self) -> Vec<u8> {
+        let bytes = self.bytes();
+        let mut out = vec![ZPAD, ZDLE, ZBIN];
+        escape_into(&mut out, &bytes);
+        escape_into(&mut out, &crc16(&bytes).to_be_bytes());
+        out
+    }
+}
+
+/// Data subpacket with a 16-bit CRC over the payload and terminator
+fn encode_subpacket(out: &mut Vec<u8>, data: &[u8], end: u8) {
+    escape_into(out, data);
+    out.push(ZDLE);
+    out.push(end);
+    let mut crc_input = data.to_vec();
+    crc_input.push(end);
+    escape_into(out, &crc16(&crc_input).to_be_bytes());
+}
+
+/// One ZDLE-decoded item
+enum Unescaped {
+    Byte(u8),
+    /// A subpacket terminator (ZCRCE/G/Q/W)
+    End(u8),
+}
+
+/// Decode the item at `pos`, skipping stray flow-control bytes
+///
+/// Returns the item and the position after it, or `None` if more input is needed.
+fn read_escaped(buf: &[u8], mut pos: usize) -> Result<Option<(Unescaped, usize)>, ZmodemError> {
+    loop {
+        let Some(&byte) = buf.get(pos) else {
+            return Ok(None);
+        };
+        match byte {
+            0x11 | 0x13 | 0x91 | 0x93 => pos += 1,
+            ZDLE => {
+                let Some(&next) = buf.get(pos + 1) else {
+                    return Ok(None);
+                };
+                let item = match next {
+                    ZCRCE | ZCRCG | ZCRCQ | ZCRCW => Unescaped::End(next),
+                    ZRUB0 => Unescaped::Byte(0x7f),
+                    ZRUB1 => Unescaped::Byte(0xff),
+                    c if c & 0x60 == 0x40 => Unescaped::Byte(c ^ 0x40),
+                    c => return Err(ZmodemError::Protocol(format!("bad escape 0x{:02x}", c))),
+                };
+                return Ok(Some((item, pos + 2)));
+            }
+            _ => return Ok(Some((Unescaped::Byte(byte), pos + 1))),
+        }
+    }
+}
+
+/// Read `count` escaped bytes starting at `pos`
+fn read_escaped_bytes(buf: &[u8], mut pos: usize, count: usize) -> Result<Option<(Vec<u8>, usize)>, ZmodemError> {
+    let mut bytes = Vec::with_capacity(count);
+    while bytes.len() < count {
+        match read_escaped(buf, pos)? {
+            None => return Ok(None),
+            Some((Unescaped::Byte(b), next)) => {
+                bytes.push(b);
+                pos = next;
+            }
+            Some((Unescaped::End(_), _)) => return Err(ZmodemError::Protocol("unexpected subpacket end".into())),
+        }
+    }
+    Ok(Some((bytes, pos)))
+}
+
+/// A decoded piece of the incoming stream
+#[derive(Debug, PartialEq, Eq)]
+enum Packet {
+    Header(Header),
+    Data { data: Vec<u8>, end: u8 },
+    /// The other side sent a cancel sequence
+    Cancelled,
+}
+
+/// Splits the incoming stream into headers and data subpackets
+#[derive(Debug, Default)]
+struct Decoder {
+    buf: Vec<u8>,
+    /// The last header is followed by data subpackets
+    expect_data: bool,
+    /// Those subpackets carry 32-bit CRCs
+    data_crc32: bool,
+}
+
+impl Decoder {
+    fn push(&mut self, data: &[u8]) {
+        self.buf.extend_from_slice(data);
+    }
+
+    /// Stop expecting data, e.g. after rejecting a header
+    fn skip_data(&mut self) {
+        self.expect_data = false;
+    }
+
+    fn next(&mut self) -> Result<Option<Packet>, ZmodemError> {
+        // Five CANs in a row can't occur in escaped data, so they always mean cancel
+        if self.buf.windows(5).any(|w| w == [ZDLE; 5]) {
+            self.buf.clear();
+            return Ok(Some(Packet::Cancelled));
+        }
+        if self.expect_data {
+            self.next_data()
+        } else {
+            self.next_header()
+        }
+    }
+
+    fn next_data(&mut self) -> Result<Option<Packet>, ZmodemError> {
+        let mut data = Vec::new();
+        let mut pos = 0;
+        let end = loop {
+            match read_escaped(&self.buf, pos) {
+                Ok(None) => return Ok(None),
+                Ok(Some((Unescaped::Byte(b), next))) => {
+                    data.push(b);
+                    pos = next;
+                }
+                Ok(Some((Unescaped::End(end), next))) => {
+                    pos = next;
+                    break end;
+                }
+                Err(e) => return Err(self.fail(pos + 2, e)),
+            }
+            if data.len() > MAX_SUBPACKET {
+                return Err(self.fail(pos, ZmodemError::Protocol("subpacket too long".into())));
+            }
+        };
+
+        let crc_len = if self.data_crc32 { 4 } else { 2 };
+        let (crc, pos) = match read_escaped_bytes(&self.buf, pos, crc_len) {
+            Ok(None) => return Ok(None),
+            Ok(Some(found)) => found,
+            Err(e) => return Err(self.fail(pos, e)),
+        };
+        self.buf.drain(..pos);
+
+        let mut crc_input = data.clone();
+        crc_input.push(end);
+        let valid = if self.data_crc32 {
+            crc32(&crc_input).to_le_bytes()[..] == crc[..]
+        } else {
+            crc16(&crc_input).to_be_bytes()[..] == crc[..]
+        };
+        if !valid {
+            self.expect_data = false;
+            return Err(ZmodemError::Crc);
+        }
+
+        // ZCRCG/ZCRCQ continue the frame; ZCRCE/ZCRCW end it
+        self.expect_data = matches!(end, ZCRCG | ZCRCQ);
+        Ok(Some(Packet::Data { data, end }))
+    }
+
+    fn next_header(&mut self) -> Result<Option<Packet>, ZmodemError> {
+        loop {
+            let Some(start) = self.buf.iter().position(|&b| b == ZPAD) else {
+                self.buf.clear();
+                return Ok(None);
+            };
+            self.buf.drain(..start);
+
+            let mut pos = 0;
+            while self.buf.get(pos) == Some(&ZPAD) {
+                pos += 1;
+            }
+            match self.buf.get(pos) {
+                None => return Ok(None),
+                Some(&ZDLE) => {}
+                Some(_) => {
+                    self.buf.drain(..pos);
+                    continue;
+                }
+            }
+            let Some(&kind) = self.buf.get(pos + 1) else {
+                return Ok(None);
+            };
+            pos += 2;
+
+            let parsed = match kind {
+                ZHEX => self.parse_hex_header(pos),
+                ZBIN => self.parse_bin_header(pos, false),
+                ZBIN32 => self.parse_bin_header(pos, true),
+                _ => {
+                    self.buf.drain(..pos);
+                    continue;
+                }
+            };
+            return match parsed {
+                Ok(None) => Ok(None),
+                Ok(Some((header, end))) => {
+                    self.buf.drain(..end);
+                    self.expect_data = matches!(header.frame_type, ZFILE | ZDATA | ZSINIT);
+                    self.data_crc32 = kind == ZBIN32;
+                    Ok(Some(Packet::Header(header)))
+                }
+                Err(e) => Err(self.fail(pos, e)),
+            };
+        }
+    }
+
+    fn parse_hex_header(&self, pos: usize) -> Result<Option<(Header, usize)>, ZmodemError> {
+        let Some(hex) = self.buf.get(pos..pos + 14) else {
+            return Ok(None);
+        };
+        let mut bytes = [0u8; 7];
+        for (i, pair) in hex.chunks(2).enumerate() {
+            let text = std::str::from_utf8(pair).map_err(|_| ZmodemError::Protocol("bad hex header".into()))?;
+            bytes[i] = u8::from_str_radix(text, 16).map_err(|_| ZmodemError::Protocol("bad hex header".into()))?;
+        }
+        if crc16(&bytes[..5]).to_be_bytes() != bytes[5..7] {
+            return Err(ZmodemError::Crc);
+        }
+        let header = Header {
+            frame_type: bytes[0],
+            data: [bytes[1], bytes[2], bytes[3], bytes[4]],
+        };
+        Ok(Some((header, pos + 14)))
+    }
+
+    fn parse_bin_header(&self, pos: usize, use_crc32: bool) -> Result<Option<(Header, usize)>, ZmodemError> {
+        let crc_len = if use_crc32 { 4 } else { 2 };
+        let Some((bytes, end)) = read_escaped_bytes(&self.buf, pos, 5 + crc_len)? else {
+            return Ok(None);
+        };
+        let valid = if use_crc32 {
+            crc32(&bytes[..5]).to_le_bytes()[..] == bytes[5..]
+        } else {
+            crc16(&bytes[..5]).to_be_bytes()[..] == bytes[5..]
+        };
+        if !valid {
+            return Err(ZmodemError::Crc);
+        }
+        let header = Header {
+            frame_type: bytes[0],
+            data: [bytes[1], bytes[2], bytes[3], bytes[4]],
+        };
+        Ok(Some((header, end)))
+    }
+
+    /// Drop the bad input up to `pos` and report `error`
+    fn fail(&mut self, pos: usize, error: ZmodemError) -> ZmodemError {
+        let pos = pos.min(self.buf.len());
+        self.buf.drain(..pos);
+        self.expect_data = false;
+        error
+    }
+
+    /// Take whatever is left in the buffer
+    fn take_rest(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.buf)
+    }
+}
+
+/// Strip the "OO" (over and out) that ends a session from the leftover output
+fn strip_over_and_out(mut rest: Vec<u8>) -> Vec<u8> {
+    let skip = rest.iter().take(2).take_while(|&&b| b == b'O').count();
+    rest.drain(..skip);
+    rest
+}
+
+/// File being written by the receiver
+struct IncomingFile {
+    name: String,
+    path: PathBuf,
+    file: File,
+    size: Option<u64>,
+    offset: u64,
+    last_progress: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReceiveState {
+    /// Waiting for a ZFILE (or ZFIN)
+    Idle,
+    /// Got ZSINIT, waiting for its data subpacket
+    SessionInfo,
+    /// Got ZFILE, waiting for the file info subpacket
+    FileInfo,
+    /// Receiving file data
+    Data,
+    /// Answered ZFIN, waiting for the sender's "OO"
+    Closing,
+}
+
+/// Receives files sent by a remote `sz` into a directory
+pub struct ZmodemReceiver {
+    decoder: Decoder,
+    dir: PathBuf,
+    state: ReceiveState,
+    file: Option<IncomingFile>,
+}
+
+impl ZmodemReceiver {
+    /// Prepare to save files into `dir`
+    ///
+    /// Returns the receiver and the ZRINIT that starts the session.
+    pub fn new(dir: &Path) -> io::Result<(Self, Vec<u8>)> {
+        fs::create_dir_all(dir)?;
+        let receiver = Self {
+            decoder: Decoder::default(),
+            dir: dir.to_path_buf(),
+            state: ReceiveState::Idle,
+            file: None,
+        };
+        Ok((receiver, Self::zrinit()))
+    }
+
+    fn zrinit() -> Vec<u8> {
+        let mut header = Header::new(ZRINIT);
+        header.data[3] = CANFDX | CANOVIO | CANFC32;
+        header.encode_hex()
+    }
+
+    /// Process output from the remote `sz`
+    pub fn feed(&mut self, data: &[u8]) -> ZmodemOutput {
+        let mut out = ZmodemOutput::default();
+        self.decoder.push(data);
+
+        while !out.finished {
+            if self.state == ReceiveState::Closing {
+                let rest = self.decoder.take_rest();
+                if !rest.is_empty() {
+                    out.passthrough = strip_over_and_out(rest);
+                    out.finished = true;
+                }
+                break;
+            }
+            match self.decoder.next() {
+                Ok(None) => break,
+                Ok(Some(Packet::Cancelled)) => {
+                    self.close_file();
+                    out.events.push(ZmodemEvent::Aborted("cancelled by remote".to_string()));
+                    out.finished = true;
+                }
+                Ok(Some(Packet::Header(header))) => self.handle_header(header, &mut out),
+                Ok(Some(Packet::Data { data, end })) => self.handle_data(&data, end, &mut out),
+                Err(e) => {
+                    tracing::debug!("ZMODEM receive error: {}", e);
+                    // Ask for the data again from where we are
+                    if let Some(file) = &self.file {
+                        out.reply.extend(Header::with_position(ZRPOS, file.offset).encode_hex());
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    fn handle_header(&mut self, header: Header, out: &mut ZmodemOutput) {
+        match header.frame_type {
+            ZRQINIT => out.reply.extend(Self::zrinit()),
+            ZSINIT => self.state = ReceiveState::SessionInfo,
+            ZFILE => self.state = ReceiveState::FileInfo,
+            ZDATA => match &self.file {
+                Some(file) if header.position() == file.offset => self.state = ReceiveState::Data,
+                Some(file) => {
+                    self.decoder.skip_data();
+                    out.reply.extend(Header::with_position(ZRPOS, file.offset).encode_hex());
+                }
+                None => {
+                    self.decoder.skip_data();
+                    out.reply.extend(Self::zrinit());
+                }
+            },
+            // A ZEOF at the wrong offset is stale and ignored
+            ZEOF if self.file.as_ref().is_some_and(|f| f.offset == header.position()) => {
+                if let Some(file) = self.file.take() {
+                    out.events.push(ZmodemEvent::FileCompleted {
+                        name: file.name,
+                        path: file.path,
+                        bytes: file.offset,
+                    });
+                }
+                self.state = ReceiveState::Idle;
+                out.reply.extend(Self::zrinit());
+            }
+            ZFIN => {
+                out.reply.extend(Header::new(ZFIN).encode_hex());
+                self.state = ReceiveState::Closing;
+            }
+            ZABORT | ZFERR | ZCAN => {
+                self.close_file();
+                out.reply.extend(Header::new(ZFIN).encode_hex());
+                out.events.push(ZmodemEvent::Aborted("sender aborted".to_string()));
+                out.finished = true;
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_data(&mut self, data: &[u8], end: u8, out: &mut ZmodemOutput) {
+        match self.state {
+            ReceiveState::SessionInfo => {
+                self.state = ReceiveState::Idle;
+                out.reply.extend(Header::new(ZACK).encode_hex());
+            }
+            ReceiveState::FileInfo => match self.open_file(data) {
+                Ok(file) => {
+                    out.events.push(ZmodemEvent::FileStarted {
+                        name: file.name.clone(),
+                        size: file.size,
+                    });
+                    self.file = Some(file);
+                    self.state = ReceiveState::Data;
+                    out.reply.extend(Header::with_position(ZRPOS, 0).encode_hex());
+                }
+                Err(e) => out.abort(format!("cannot create file: {}", e)),
+            },
+            ReceiveState::Data => {
+                let Some(file) = self.file.as_mut() else {
+                    return;
+                };
+                if let Err(e) = file.file.write_all(data) {
+                    out.abort(format!("cannot write {}: {}", file.name, e));
+                    return;
+                }
+                file.offset += data.len() as u64;
+                if file.offset - file.last_progress >= PROGRESS_STEP {
+                    file.last_progress = file.offset;
+                    out.events.push(ZmodemEvent::Progress {
+                        name: file.name.clone(),
+                        bytes: file.offset,
+                        size: file.size,
+                    });
+                }
+                if matches!(end, ZCRCQ | ZCRCW) {
+                    out.reply.extend(Header::with_position(ZACK, file.offset).encode_hex());
+                }
+            }
+            ReceiveState::Idle | ReceiveState::Closing => {}
+        }
+    }
+
+    /// Create the local file described by a ZFILE info subpacket
+    fn open_file(&self, info: &[u8]) -> io::Result<IncomingFile> {
+        let mut fields = info.split(|&b| b == 0);
+        let raw_name = String::from_utf8_lossy(fields.next().unwrap_or_default()).into_owned();
+        let size = fields
+            .next()
+            .and_then(|meta| std::str::from_utf8(meta).ok())
+            .and_then(|meta| meta.split_whitespace().next())
+            .and_then(|size| size.parse().ok());
+
+        // Never trust the remote path; keep only the file name
+        let name = Path::new(&raw_name)
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| "zmodem-download".to_string());
+        let path = unique_path(&self.dir, &name);
+        let file = File::create(&path)?;
+        Ok(IncomingFile {
+            name,
+            path,
+            file,
+            size,
+            offset: 0,
+            last_progress: 0,
+        })
+    }
+
+    /// Close a partly received file, keeping what arrived
+    fn close_file(&mut self) {
+        if let Some(file) = self.file.take() {
+            tracing::warn!("ZMODEM transfer of {} stopped at {} bytes", file.name, file.offset);
+        }
+    }
+}
+
+/// `dir/name`, or `dir/name (n)` if that already exists
+fn unique_path(dir: &Path, name: &str) -> PathBuf {
+    let candidate = dir.join(name);
+    if !candidate.exists() {
+        return candidate;
+    }
+    let (stem, ext) = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{}", ext)),
+        _ => (name, String::new()),
+    };
+    (1..)
+        .map(|n| dir.join(format!("{} ({}){}", stem, n, ext)))
+        .find(|p| !p.exists())
+        .unwrap_or(candidate)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SendState {
+    /// Sent ZRQINIT, waiting for the receiver's ZRINIT
+    Starting,
+    /// Sent ZFILE, waiting for ZRPOS
+    Offered,
+    /// Sent a window ending in ZCRCW, waiting for ZACK
+    Windowed,
+    /// Sent ZEOF, waiting for ZRINIT
+    AtEof,
+    /// Sent ZFIN, waiting for the receiver's ZFIN
+    Closing,
+}
+
+/// Sends one file to a remote `rz`
+pub struct ZmodemSender {
+    decoder: Decoder,
+    name: String,
+    path: PathBuf,
+    file: File,
+    size: u64,
+    mtime: u64,
+    offset: u64,
+    last_progress: u64,
+    state: SendState,
+}
+
+impl ZmodemSender {
+    /// Open the file to send
+    ///
+    /// Returns the sender and the ZRQINIT that starts the session.
+    pub fn new(path: &Path) -> io::Result<(Self, Vec<u8>)> {
+        let file = File::open(path)?;
+        let metadata = file.metadata()?;
+        if !metadata.is_file() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "not a regular file"));
+        }
+        let mtime = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "file".to_string());
+
+        let sender = Self {
+            decoder: Decoder::default(),
+            name,
+            path: path.to_path_buf(),
+            file,
+            size: metadata.len(),
+            mtime,
+            offset: 0,
+            last_progress: 0,
+            state: SendState::Starting,
+        };
+        Ok((sender, Header::new(ZRQINIT).encode_hex()))
+    }
+
+    /// Process output from the remote `rz`
+    pub fn feed(&mut self, data: &[u8]) -> ZmodemOutput {
+        let mut out = ZmodemOutput::default();
+        self.decoder.push(data);
+
+        while !out.finished {
+            match self.decoder.next() {
+                Ok(None) => break,
+                Ok(Some(Packet::Cancelled)) => {
+                    out.events.push(ZmodemEvent::Aborted("cancelled by remote".to_string()));
+                    out.finished = true;
+                }
+                Ok(Some(Packet::Header(header))) => self.handle_header(header, &mut out),
+                // The receiver never sends data subpackets we care about
+                Ok(Some(Packet::Data { .. })) => {}
+                Err(e) => tracing::debug!("ZMODEM send error: {}", e),
+            }
+        }
+        out
+    }
+
+    fn handle_header(&mut self, header: Header, out: &mut ZmodemOutput) {
+        match (header.frame_type, self.state) {
+            (ZRINIT, SendState::Starting) => {
+                out.reply.extend(Header::new(ZFILE).encode_bin());
+                let info = format!("{}\0{} {:o} 100644 0 1 {}\0", self.name, self.size, self.mtime, self.size);
+                encode_subpacket(&mut out.reply, info.as_bytes(), ZCRCW);
+                out.events.push(ZmodemEvent::FileStarted {
+                    name: self.name.clone(),
+                    size: Some(self.size),
+                });
+                self.state = SendState::Offered;
+            }
+            (ZRINIT, SendState::AtEof) => {
+                out.events.push(ZmodemEvent::FileCompleted {
+                    name: self.name.clone(),
+                    path: self.path.clone(),
+                    bytes: self.size,
+                });
+                self.finish(out);
+            }
+            (ZRPOS, _) => {
+                let position = header.position().min(self.size);
+                if let Err(e) = self.file.seek(SeekFrom::Start(position)) {
+                    out.abort(format!("cannot read {}: {}", self.name, e));
+                    return;
+                }
+                self.offset = position;
+                self.send_window(out);
+            }
+            (ZACK, SendState::Windowed) => self.send_window(out),
+            (ZSKIP, _) => {
+                out.events.push(ZmodemEvent::FileSkipped { name: self.name.clone() });
+                self.finish(out);
+            }
+            (ZFIN, SendState::Closing) => {
+                out.reply.extend_from_slice(b"OO");
+                out.passthrough = self.decoder.take_rest();
+                out.finished = true;
+            }
+            (ZABORT | ZFERR | ZCAN, _) => {
+                out.events.push(ZmodemEvent::Aborted("receiver aborted".to_string()));
+                out.finished = true;
+            }
+            (ZNAK, SendState::Starting) => out.reply.extend(Header::new(ZRQINIT).encode_hex()),
+            _ => {}
+        }
+    }
+
+    /// Send the next window of file data from `offset`
+    fn send_window(&mut self, out: &mut ZmodemOutput) {
+        if self.offset >= self.size {
+            out.reply.extend(Header::with_position(ZEOF, self.offset).encode_hex());
+            self.state = SendState::AtEof;
+            return;
+        }
+
+        out.reply.extend(Header::with_position(ZDATA, self.offset).encode_bin());
+        let mut sent = 0;
+        let mut chunk = vec![0u8; SUBPACKET_SIZE];
+        loop {
+            let want = SUBPACKET_SIZE.min((self.size - self.offset) as usize);
+            if let Err(e) = self.file.read_exact(&mut chunk[..want]) {
+                out.abort(format!("cannot read {}: {}", self.name, e));
+                return;
+            }
+            self.offset += want as u64;
+            sent += want;
+
+            if self.offset >= self.size {
+                encode_subpacket(&mut out.reply, &chunk[..want], ZCRCE);
+                out.reply.extend(Header::with_position(ZEOF, self.offset).encode_hex());
+                self.state = SendState::AtEof;
+                break;
+            }
+            if sent >= WINDOW_SIZE {
+                encode_subpacket(&mut out.reply, &chunk[..want], ZCRCW);
+                self.state = SendState::Windowed;
+                break;
+            }
+            encode_subpacket(&mut out.reply, &chunk[..want], ZCRCG);
+        }
+
+        if self.offset - self.last_progress >= PROGRESS_STEP {
+            self.last_progress = self.offset;
+            out.events.push(ZmodemEvent::Progress {
+                name: self.name.clone(),
+                bytes: self.offset,
+                size: Some(self.size),
+            });
+        }
+    }
+
+    fn finish(&mut self, out: &mut ZmodemOutput) {
+        out.reply.extend(Header::new(ZFIN).encode_hex());
+        self.state = SendState::Closing;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detector_finds_split_header() {
+        let mut detector = ZmodemDetector::default();
+        assert!(detector.scan(b"$ sz notes.txt\r\nrz\r**").is_none());
+        assert_eq!(detector.scan(b"\x18B00000000000000\r\x8a\x11"), Some((ZmodemDirection::Receive, 0)));
+
+        let mut detector = ZmodemDetector::default();
+        let (direction, offset) = detector.scan(b"rz waiting to receive.**\x18B0100000023be50\r\x8a\x11").unwrap();
+        assert_eq!(direction, ZmodemDirection::Send);
+        assert_eq!(offset, "rz waiting to receive.".len());
+
+        assert!(ZmodemDetector::default().scan(b"**\x18B09 not zmodem").is_none());
+    }
+
+    #[test]
+    fn test_hex_header_matches_lrzsz() {
+        // The exact ZRINIT `rz` prints with no capability flags
+        let mut header = Header::new(ZRINIT);
+        header.data[3] = 0x23;
+        assert_eq!(Header::new(ZRQINIT).encode_hex(), b"**\x18B00000000000000\r\x8a\x11");
+        assert!(header.encode_hex().starts_with(b"**\x18B0100000023be50"));
+    }
+
+    #[test]
+    fn test_escape_roundtrip() {
+        let data: Vec<u8> = (0..=255).collect();
+        let mut encoded = Vec::new();
+        encode_subpacket(&mut encoded, &data, ZCRCW);
+        assert!(!encoded[..encoded.len() - 4].contains(&0x11));
+
+        let mut decoder = Decoder {
+            expect_data: true,
+            ..Default::default()
+        };
+        // Feed byte by byte to exercise the incomplete-input paths
+        for (i, byte) in encoded.iter().enumerate() {
+            decoder.push(&[*byte]);
+            let packet = decoder.next().unwrap();
+            if i + 1 < encoded.len() {
+                assert!(packet.is_none());
+            } else {
+                assert_eq!(packet, Some(Packet::Data { data, end: ZCRCW }));
+                break;
+            }
+        }
+        assert!(!decoder.expect_data);
+    }
+
+    #[test]
+    fn test_decoder_rejects_bad_crc_and_sees_cancel() {
+        let mut decoder = Decoder::default();
+        let mut frame = Header::with_position(ZRPOS, 1234).encode_hex();
+        frame[10] = b'f';
+        decoder.push(&frame);
+        assert!(matches!(decoder.next(), Err(ZmodemError::Crc)));
+
+        decoder.push(&Header::with_position(ZRPOS, 1234).encode_hex());
+        let Some(Packet::Header(header)) = decoder.next().unwrap() else {
+            panic!("expected a header");
+        };
+        assert_eq!((header.frame_type, header.position()), (ZRPOS, 1234));
+
+        decoder.push(ABORT_SEQUENCE);
+        assert_eq!(decoder.next().unwrap(), Some(Packet::Cancelled));
+    }
+
+    /// Run a sender and receiver against each other until both finish
+    #[test]
+    fn test_send_and_receive_file() {
+        let src_dir = tempfile::tempdir().unwrap();
+        let dst_dir = tempfile::tempdir().unwrap();
+        let content: Vec<u8> = (0..100_000u32).map(|i| (i * 7 % 256) as u8).collect();
+        let src = src_dir.path().join("firmware.bin");
+        fs::write(&src, &content).unwrap();
+        // An existing file must not be overwritten
+        fs::write(dst_dir.path().join("firmware.bin"), b"old").unwrap();
+
+        let (mut sender, mut to_receiver) = ZmodemSender::new(&src).unwrap();
+        let (mut receiver, mut to_sender) = ZmodemReceiver::new(dst_dir.path()).unwrap();
+        let mut events = Vec::new();
+        let (mut sender_done, mut receiver_done) = (false, false);
+
+        for _ in 0..1000 {
+            if !receiver_done {
+                let out = receiver.feed(&std::mem::take(&mut to_receiver));
+                to_sender.extend(out.reply);
+                events.extend(out.events);
+                receiver_done = out.finished;
+            }
+            if !sender_done {
+                let out = sender.feed(&std::mem::take(&mut to_sender));
+                to_receiver.extend(out.reply);
+                sender_done = out.finished;
+            }
+            if sender_done && receiver_done {
+                break;
+            }
+        }
+
+        assert!(sender_done && receiver_done);
+        let received = dst_dir.path().join("firmware (1).bin");
+        assert_eq!(fs::read(&received).unwrap(), content);
+        assert_eq!(fs::read(dst_dir.path().join("firmware.bin")).unwrap(), b"old");
+        assert!(events.contains(&ZmodemEvent::FileCompleted {
+            name: "firmware.bin".to_string(),
+            path: received,
+            bytes: content.len() as u64,
+        }));
+    }
+
+    #[test]
+    fn test_receiver_strips_remote_path_and_handles_cancel() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut receiver, _) = ZmodemReceiver::new(dir.path()).unwrap();
+
+        let mut stream = Header::new(ZFILE).encode_bin();
+        encode_subpacket(&mut stream, b"../../etc/passwd\x0012 0 100644\x00", ZCRCW);
+        let out = receiver.feed(&stream);
+        assert_eq!(
+            out.events,
+            vec![ZmodemEvent::FileStarted {
+                name: "passwd".to_string(),
+                size: Some(12)
+            }]
+        );
+        assert!(dir.path().join("passwd").exists());
+
+        let out = receiver.feed(ABORT_SEQUENCE);
+        assert!(out.finished);
+        assert!(matches!(out.events.as_slice(), [ZmodemEvent::Aborted(_)]));
+    }
+}
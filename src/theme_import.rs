@@ -0,0 +1,302 @@
+//! Color scheme import from other terminals
+//!
+//! Reads Alacritty color tables (YAML, or the TOML used since Alacritty 0.13)
+//! and iTerm2 `.itermcolors` presets into a [`ColorScheme`] named after the
+//! file.
+
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::config::ColorScheme;
+
+/// Errors that can occur while reading a theme file
+#[derive(Debug, Error)]
+pub enum ThemeImportError {
+    #[error("Failed to read theme file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to parse YAML: {0}")]
+    Yaml(#[from] serde_yaml_ng::Error),
+
+    #[error("Failed to parse TOML: {0}")]
+    Toml(#[from] toml::de::Error),
+
+    #[error("Failed to parse property list: {0}")]
+    Plist(#[from] plist::Error),
+
+    #[error("Unrecognized theme: {0}")]
+    Format(String),
+}
+
+/// Read a theme file, picking the format from its extension
+pub fn import_theme(path: &Path) -> Result<ColorScheme, ThemeImportError> {
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "itermcolors" => parse_itermcolors(&name, &fs::read(path)?),
+        "yml" | "yaml" => parse_alacritty_yaml(&name, &fs::read_to_string(path)?),
+        "toml" => parse_alacritty_toml(&name, &fs::read_to_string(path)?),
+        _ => Err(ThemeImportError::Format(
+            "expected an .itermcolors, .yml or .toml file".into(),
+        )),
+    }
+}
+
+/// Top level of an Alacritty config or theme file
+#[derive(Deserialize)]
+struct AlacrittyFile {
+    colors: AlacrittyColors,
+}
+
+#[derive(Deserialize)]
+struct AlacrittyColors {
+    primary: AlacrittyPrimary,
+    #[serde(default)]
+    cursor: Option<AlacrittyCursor>,
+    normal: AlacrittyPalette,
+    bright: AlacrittyPalette,
+}
+
+#[derive(Deserialize)]
+struct AlacrittyPrimary {
+    background: String,
+    foreground: String,
+}
+
+#[derive(Deserialize)]
+struct AlacrittyCursor {
+    /// A color, or a keyword such as `CellForeground`
+    #[serde(default)]
+    cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AlacrittyPalette {
+    black: String,
+    red: String,
+    green: String,
+    yellow: String,
+    blue: String,
+    magenta: String,
+    cyan: String,
+    white: String,
+}
+
+impl AlacrittyPalette {
+    fn colors(&self) -> [&str; 8] {
+        [
+            &self.black,
+            &self.red,
+            &self.green,
+            &self.yellow,
+            &self.blue,
+            &self.magenta,
+            &self.cyan,
+            &self.white,
+        ]
+    }
+}
+
+/// Parse an Alacritty YAML theme (`colors:` with `primary`, `normal`, `bright`)
+pub fn parse_alacritty_yaml(name: &str, text: &str) -> Result<ColorScheme, ThemeImportError> {
+    let file: AlacrittyFile = serde_yaml_ng::from_str(text)?;
+    scheme_from_alacritty(name, &file.colors)
+}
+
+/// Parse an Alacritty TOML theme (`[colors.primary]`, `[colors.normal]`, ...)
+pub fn parse_alacritty_toml(name: &str, text: &str) -> Result<ColorScheme, ThemeImportError> {
+    let file: AlacrittyFile = toml::from_str(text)?;
+    scheme_from_alacritty(name, &file.colors)
+}
+
+fn scheme_from_alacritty(name: &str, colors: &AlacrittyColors) -> Result<ColorScheme, ThemeImportError> {
+    // Alacritty writes colors as "#rrggbb" or "0xrrggbb"
+    let parse = |text: &str| {
+        let trimmed = text.trim();
+        let hex = trimmed.strip_prefix("0x").or_else(|| trimmed.strip_prefix("0X")).unwrap_or(trimmed);
+        ColorScheme::parse_hex(hex)
+            .ok_or_else(|| ThemeImportError::Format(format!("\"{}\" is not a color", text)))
+    };
+
+    let foreground = parse(&colors.primary.foreground)?;
+    // Keyword cursors like "CellForeground" follow the text color
+    let cursor = colors
+        .cursor
+        .as_ref()
+        .and_then(|c| c.cursor.as_deref())
+        .and_then(|c| parse(c).ok())
+        .unwrap_or(foreground);
+
+    let mut scheme = ColorScheme::default_dark();
+    scheme.name = name.to_string();
+    scheme.set_color(0, foreground);
+    scheme.set_color(1, parse(&colors.primary.background)?);
+    scheme.set_color(2, cursor);
+    for (index, color) in colors.normal.colors().into_iter().chain(colors.bright.colors()).enumerate() {
+        scheme.set_color(3 + index, parse(color)?);
+    }
+    Ok(scheme)
+}
+
+/// Parse an iTerm2 color preset (a plist of `Ansi 0 Color` .. `Ansi 15 Color`,
+/// `Foreground Color`, `Background Color` and `Cursor Color`)
+pub fn parse_itermcolors(name: &str, data: &[u8]) -> Result<ColorScheme, ThemeImportError> {
+    let value = plist::Value::from_reader(Cursor::new(data))?;
+    let dict = value
+        .as_dictionary()
+        .ok_or_else(|| ThemeImportError::Format("expected a dictionary of colors".into()))?;
+
+    let color = |key: &str| -> Result<u32, ThemeImportError> {
+        let entry = dict
+            .get(key)
+            .and_then(plist::Value::as_dictionary)
+            .ok_or_else(|| ThemeImportError::Format(format!("missing \"{}\"", key)))?;
+        // Components are reals from 0 to 1
+        let component = |name: &str| {
+            entry
+                .get(name)
+                .and_then(plist::Value::as_real)
+                .map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u32)
+                .ok_or_else(|| ThemeImportError::Format(format!("\"{}\" has no {}", key, name)))
+        };
+        Ok(component("Red Component")? << 16 | component("Green Component")? << 8 | component("Blue Component")?)
+    };
+
+    let foreground = color("Foreground Color")?;
+    let mut scheme = ColorScheme::default_dark();
+    scheme.name = name.to_string();
+    scheme.set_color(0, foreground);
+    scheme.set_color(1, color("Background Color")?);
+    scheme.set_color(2, color("Cursor Color").unwrap_or(foreground));
+    for index in 0..16 {
+        scheme.set_color(3 + index, color(&format!("Ansi {} Color", index))?);
+    }
+    Ok(scheme)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_alacritty_yaml() {
+        let yaml = r##"
+colors:
+  primary:
+    background: '0x1d1f21'
+    foreground: '#C5C8C6'
+  cursor:
+    text: CellBackground
+    cursor: CellForeground
+  normal:
+    black: '#1d1f21'
+    red: '#cc6666'
+    green: '#b5bd68'
+    yellow: '#f0c674'
+    blue: '#81a2be'
+    magenta: '#b294bb'
+    cyan: '#8abeb7'
+    white: '#c5c8c6'
+  bright:
+    black: '#666666'
+    red: '#d54e53'
+    green: '#b9ca4a'
+    yellow: '#e7c547'
+    blue: '#7aa6da'
+    magenta: '#c397d8'
+    cyan: '#70c0b1'
+    white: '#eaeaea'
+"##;
+        let scheme = parse_alacritty_yaml("Tomorrow Night", yaml).unwrap();
+        assert_eq!(scheme.name, "Tomorrow Night");
+        assert_eq!(scheme.background, 0x1d1f21);
+        assert_eq!(scheme.foreground, 0xc5c8c6);
+        assert_eq!(scheme.cursor, 0xc5c8c6);
+        assert_eq!(scheme.red, 0xcc6666);
+        assert_eq!(scheme.white, 0xc5c8c6);
+        assert_eq!(scheme.bright_black, 0x666666);
+    }
+
+    #[test]
+    fn test_parse_alacritty_toml() {
+        let toml = r##"
+[colors.primary]
+background = "#282828"
+foreground = "#ebdbb2"
+
+[colors.cursor]
+cursor = "#fe8019"
+
+[colors.normal]
+black = "#282828"
+red = "#cc241d"
+green = "#98971a"
+yellow = "#d79921"
+blue = "#458588"
+magenta = "#b16286"
+cyan = "#689d6a"
+white = "#a89984"
+
+[colors.bright]
+black = "#928374"
+red = "#fb4934"
+green = "#b8bb26"
+yellow = "#fabd2f"
+blue = "#83a598"
+magenta = "#d3869b"
+cyan = "#8ec07c"
+white = "#ebdbb2"
+"##;
+        let scheme = parse_alacritty_toml("gruvbox", toml).unwrap();
+        assert_eq!(scheme.cursor, 0xfe8019);
+        assert_eq!(scheme.blue, 0x458588);
+        assert_eq!(scheme.bright_white, 0xebdbb2);
+
+        let broken = toml.replace("\"#cc241d\"", "\"red\"");
+        assert!(matches!(parse_alacritty_toml("gruvbox", &broken), Err(ThemeImportError::Format(_))));
+    }
+
+    #[test]
+    fn test_parse_itermcolors() {
+        let entry = |key: &str, r: f64, g: f64, b: f64| {
+            format!(
+                "<key>{}</key><dict><key>Color Space</key><string>sRGB</string>\
+                 <key>Red Component</key><real>{}</real>\
+                 <key>Green Component</key><real>{}</real>\
+                 <key>Blue Component</key><real>{}</real></dict>",
+                key, r, g, b
+            )
+        };
+        let mut body = entry("Background Color", 0.0, 0.0, 0.0);
+        body += &entry("Foreground Color", 1.0, 1.0, 1.0);
+        for index in 0..16 {
+            body += &entry(&format!("Ansi {} Color", index), 1.0, 0.5, 0.0);
+        }
+        let plist = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+             <plist version=\"1.0\"><dict>{}</dict></plist>",
+            body
+        );
+
+        let scheme = parse_itermcolors("Solarized", plist.as_bytes()).unwrap();
+        assert_eq!(scheme.background, 0x000000);
+        assert_eq!(scheme.foreground, 0xffffff);
+        // No cursor color: it follows the foreground
+        assert_eq!(scheme.cursor, 0xffffff);
+        assert_eq!(scheme.bright_white, 0xff8000);
+
+        let missing = plist.replace("Ansi 7 Color", "Ansi 77 Color");
+        assert!(matches!(parse_itermcolors("Solarized", missing.as_bytes()), Err(ThemeImportError::Format(_))));
+    }
+}
@@ -0,0 +1,214 @@
+//! Broadcast input: typing once into several tabs
+//!
+//! The set of tabs lives in `RedPillApp`; terminal views ask it for their
+//! peers on each keystroke. This module turns the mode on and off and holds
+//! the confirmation shown before input fans out to many sessions.
+
+use gpui::*;
+use gpui::prelude::*;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::app::{flash_notice, AppState};
+use crate::terminal::Terminal;
+
+/// Input waiting to be written to each receiving terminal
+pub type BroadcastWrites = Vec<(Arc<Mutex<Terminal>>, Vec<u8>)>;
+
+/// What the user is asked to confirm
+enum BroadcastConfirm {
+    /// Turn broadcast on for these tabs
+    Enable { tab_ids: Vec<Uuid> },
+    /// Send a control key, already typed into the focused tab, to the others
+    ControlKey { key: &'static str, writes: BroadcastWrites },
+}
+
+/// Turn broadcast input off, or on for the tabs around the active one
+///
+/// Covering more than `confirm_broadcast_above` tabs asks first.
+pub fn toggle_broadcast(cx: &mut App) {
+    let Some(state) = cx.try_global::<AppState>() else {
+        return;
+    };
+    let (was_on, tab_ids, confirm_above) = {
+        let mut app = state.app.lock();
+        let was_on = app.broadcast_tab_count() > 0;
+        app.stop_broadcast();
+        (was_on, app.broadcast_candidates(), app.config.confirm_broadcast_above)
+    };
+
+    if was_on {
+        flash_notice("Broadcast input off".to_string(), cx);
+    } else if tab_ids.len() < 2 {
+        flash_notice("Broadcast input needs at least two tabs".to_string(), cx);
+    } else if tab_ids.len() > confirm_above {
+        BroadcastConfirmDialog::open(BroadcastConfirm::Enable { tab_ids }, cx);
+    } else {
+        start_broadcast(tab_ids, cx);
+    }
+}
+
+/// Turn broadcast on and say how many tabs it covers
+fn start_broadcast(tab_ids: Vec<Uuid>, cx: &mut App) {
+    let Some(state) = cx.try_global::<AppState>() else {
+        return;
+    };
+    let count = {
+        let mut app = state.app.lock();
+        app.start_broadcast(tab_ids);
+        app.broadcast_tab_count()
+    };
+    flash_notice(format!("Broadcasting input to {} tabs", count), cx);
+}
+
+/// Write broadcast input to each receiving terminal
+pub fn send_broadcast(writes: &BroadcastWrites) {
+    for (terminal, bytes) in writes {
+        terminal.lock().write(bytes);
+    }
+}
+
+/// Confirmation before broadcast input reaches several sessions
+pub struct BroadcastConfirmDialog {
+    confirm: BroadcastConfirm,
+}
+
+impl BroadcastConfirmDialog {
+    /// Ask before a Ctrl-C, Ctrl-D or Ctrl-Z goes to the other broadcast tabs
+    pub fn open_for_control_key(key: &'static str, writes: BroadcastWrites, cx: &mut App) {
+        Self::open(BroadcastConfirm::ControlKey { key, writes }, cx);
+    }
+
+    fn open(confirm: BroadcastConfirm, cx: &mut App) {
+        let title = match &confirm {
+            BroadcastConfirm::Enable { .. } => "Broadcast Input",
+            BroadcastConfirm::ControlKey { key, .. } => *key,
+        };
+        let window_options = WindowOptions {
+            window_bounds: Some(WindowBounds::Windowed(Bounds::centered(
+                None,
+                size(px(420.0), px(200.0)),
+                cx,
+            ))),
+            titlebar: Some(TitlebarOptions {
+                title: Some(title.into()),
+                appears_transparent: false,
+                ..Default::default()
+            }),
+            kind: WindowKind::Normal,
+            ..Default::default()
+        };
+
+        let _ = cx.open_window(window_options, |_window, cx| cx.new(|_cx| Self { confirm }));
+    }
+
+    fn handle_confirm(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        match &self.confirm {
+            BroadcastConfirm::Enable { tab_ids } => start_broadcast(tab_ids.clone(), cx),
+            BroadcastConfirm::ControlKey { writes, .. } => send_broadcast(writes),
+        }
+        window.remove_window();
+    }
+
+    fn handle_cancel(&mut self, window: &mut Window, _cx: &mut Context<Self>) {
+        window.remove_window();
+    }
+}
+
+impl Render for BroadcastConfirmDialog {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let (title, message, confirm_label) = match &self.confirm {
+            BroadcastConfirm::Enable { tab_ids } => (
+                "Broadcast input?".to_string(),
+                format!("Everything you type will be sent to all {} tabs.", tab_ids.len()),
+                "Broadcast",
+            ),
+            BroadcastConfirm::ControlKey { key, writes } => (
+                format!("Send {} to all tabs?", key),
+                format!(
+                    "{} went to this tab. Send it to the other {} broadcast tab{} too?",
+                    key,
+                    writes.len(),
+                    if writes.len() == 1 { "" } else { "s" }
+                ),
+                "Send",
+            ),
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .size_full()
+            .bg(rgb(0x1e1e2e))
+            // Header
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .px_4()
+                    .py_3()
+                    .border_b_1()
+                    .border_color(rgb(0x313244))
+                    .child(
+                        div()
+                            .text_lg()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(rgb(0xfab387))
+                            .child(title),
+                    ),
+            )
+            // Content
+            .child(
+                div()
+                    .flex_1()
+                    .p_4()
+                    .child(div().text_sm().text_color(rgb(0xcdd6f4)).child(message)),
+            )
+            // Footer with buttons
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_end()
+                    .gap_2()
+                    .px_4()
+                    .py_3()
+                    .border_t_1()
+                    .border_color(rgb(0x313244))
+                    .child(
+                        div()
+                            .id("cancel-btn")
+                            .px_4()
+                            .py_2()
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|style| style.bg(rgb(0x313244)))
+                            .on_click(cx.listener(|this, _event, window, cx| {
+                                this.handle_cancel(window, cx);
+                            }))
+                            .child(div().text_sm().text_color(rgb(0x6c7086)).child("Cancel")),
+                    )
+                    .child(
+                        div()
+                            .id("confirm-btn")
+                            .px_4()
+                            .py_2()
+                            .bg(rgb(0xfab387))
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|style| style.bg(rgb(0xf9e2af)))
+                            .on_click(cx.listener(|this, _event, window, cx| {
+                                this.handle_confirm(window, cx);
+                            }))
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .text_color(rgb(0x1e1e2e))
+                                    .font_weight(FontWeight::SEMIBOLD)
+                                    .child(confirm_label),
+                            ),
+                    ),
+            )
+    }
+}
@@ -0,0 +1,485 @@
+//! Editor for custom color schemes
+//!
+//! Custom schemes are saved in `appearance.custom_schemes` in the config,
+//! where the View menu, theme cycling and the session dialogs pick them up.
+//! Built-in schemes can't be changed, so editing one starts a copy.
+
+use gpui::*;
+use gpui::prelude::*;
+
+use crate::app::AppState;
+use crate::config::ColorScheme;
+use super::text_field::{TextField, TextFieldEvent};
+
+/// Window for creating, changing and deleting custom color schemes
+pub struct ColorSchemeEditor {
+    /// Saved name of the custom scheme being edited (None = not saved yet)
+    original: Option<String>,
+    /// Colors as edited so far, shown in the preview
+    scheme: ColorScheme,
+    /// Name to save the scheme under
+    name_field: Entity<TextField>,
+    /// One `#rrggbb` field per color, in `ColorScheme::colors` order
+    color_fields: Vec<Entity<TextField>>,
+    /// Why the last save failed
+    error: Option<String>,
+    /// Result of the last save or delete
+    status: Option<String>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ColorSchemeEditor {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        let name_field = cx.new(|cx| TextField::new(cx, "Scheme name"));
+        let color_fields: Vec<Entity<TextField>> = ColorScheme::COLOR_LABELS
+            .iter()
+            .map(|_| cx.new(|cx| TextField::new(cx, "#rrggbb")))
+            .collect();
+
+        // Valid colors show up in the preview as they are typed
+        let subscriptions = color_fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                cx.subscribe(field, move |this, _field, event: &TextFieldEvent, cx| {
+                    if let TextFieldEvent::Changed(text) = event {
+                        if let Some(color) = ColorScheme::parse_hex(text) {
+                            this.scheme.set_color(index, color);
+                            cx.notify();
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        let mut editor = Self {
+            original: None,
+            scheme: ColorScheme::default_dark(),
+            name_field,
+            color_fields,
+            error: None,
+            status: None,
+            _subscriptions: subscriptions,
+        };
+
+        let theme = cx
+            .try_global::<AppState>()
+            .map(|state| state.app.lock().config.appearance.theme.clone())
+            .unwrap_or_else(|| "default".to_string());
+        editor.edit(&theme, cx);
+        editor
+    }
+
+    /// Open as a modal window
+    pub fn open(cx: &mut App) {
+        let window_options = WindowOptions {
+            window_bounds: Some(WindowBounds::Windowed(Bounds::centered(
+                None,
+                size(px(760.0), px(640.0)),
+                cx,
+            ))),
+            titlebar: Some(TitlebarOptions {
+                title: Some("Color Schemes".into()),
+                appears_transparent: false,
+                ..Default::default()
+            }),
+            kind: WindowKind::Normal,
+            ..Default::default()
+        };
+
+        let _ = cx.open_window(window_options, |_window, cx| cx.new(ColorSchemeEditor::new));
+    }
+
+    /// Start editing a scheme; a built-in one becomes a new copy
+    fn edit(&mut self, name: &str, cx: &mut Context<Self>) {
+        let Some(mut scheme) = cx
+            .try_global::<AppState>()
+            .and_then(|state| state.app.lock().config.appearance.find_scheme(name))
+        else {
+            return;
+        };
+
+        self.original = if ColorScheme::builtin(name).is_some() {
+            scheme.name = format!("{} copy", name);
+            None
+        } else {
+            Some(name.to_string())
+        };
+        self.load(scheme, cx);
+    }
+
+    /// Fill the fields from a scheme
+    fn load(&mut self, scheme: ColorScheme, cx: &mut Context<Self>) {
+        self.name_field.update(cx, |field, cx| {
+            field.set_content(scheme.name.clone());
+            cx.notify();
+        });
+        for (field, color) in self.color_fields.iter().zip(scheme.colors()) {
+            field.update(cx, |field, cx| {
+                field.set_content(ColorScheme::hex(color));
+                cx.notify();
+            });
+        }
+        self.scheme = scheme;
+        self.error = None;
+        self.status = None;
+        cx.notify();
+    }
+
+    /// Save the scheme, and with `apply` make it the theme
+    fn handle_save(&mut self, apply: bool, cx: &mut Context<Self>) {
+        self.error = None;
+        self.status = None;
+
+        let invalid: Vec<&str> = self
+            .color_fields
+            .iter()
+            .zip(ColorScheme::COLOR_LABELS)
+            .filter(|(field, _)| ColorScheme::parse_hex(field.read(cx).content()).is_none())
+            .map(|(_, label)| label)
+            .collect();
+        if !invalid.is_empty() {
+            self.error = Some(format!("Not a #rrggbb color: {}", invalid.join(", ")));
+            cx.notify();
+            return;
+        }
+
+        let mut scheme = self.scheme.clone();
+        scheme.name = self.name_field.read(cx).content().trim().to_string();
+        let name = scheme.name.clone();
+
+        if let Some(app_state) = cx.try_global::<AppState>() {
+            let mut app = app_state.app.lock();
+            match app.config.appearance.save_custom_scheme(self.original.as_deref(), scheme) {
+                Ok(()) => {
+                    if apply {
+                        app.config.appearance.set_scheme(&name);
+                    }
+                    match app.config.save() {
+                        Ok(()) => self.status = Some(format!("Saved \"{}\"", name)),
+                        Err(e) => self.error = Some(format!("Failed to save config: {}", e)),
+                    }
+                    self.original = Some(name);
+                }
+                Err(e) => self.error = Some(e.to_string()),
+            }
+        }
+        cx.refresh_windows();
+        cx.notify();
+    }
+
+    /// Delete the custom scheme being edited and go back to the theme
+    fn handle_delete(&mut self, cx: &mut Context<Self>) {
+        let Some(name) = self.original.take() else {
+            return;
+        };
+
+        let mut theme = "default".to_string();
+        let mut error = None;
+        if let Some(app_state) = cx.try_global::<AppState>() {
+            let mut app = app_state.app.lock();
+            app.config.appearance.remove_custom_scheme(&name);
+            if let Err(e) = app.config.save() {
+                error = Some(format!("Failed to save config: {}", e));
+            }
+            theme = app.config.appearance.theme.clone();
+        }
+
+        self.edit(&theme, cx);
+        self.error = error;
+        self.status = Some(format!("Deleted \"{}\"", name));
+        cx.refresh_windows();
+    }
+
+    fn render_scheme_list(&self, cx: &Context<Self>) -> impl IntoElement {
+        let names = cx
+            .try_global::<AppState>()
+            .map(|state| state.app.lock().config.appearance.scheme_names())
+            .unwrap_or_default();
+
+        div()
+            .id("scheme-list")
+            .flex()
+            .flex_col()
+            .w(px(170.0))
+            .flex_shrink_0()
+            .gap_1()
+            .p_2()
+            .overflow_y_scroll()
+            .border_r_1()
+            .border_color(rgb(0x313244))
+            .children(names.into_iter().map(|name| {
+                let is_selected = self.original.as_deref() == Some(name.as_str());
+                let is_builtin = ColorScheme::builtin(&name).is_some();
+                let label = ColorScheme::display_name(&name);
+
+                div()
+                    .id(ElementId::Name(format!("scheme-list-{}", name).into()))
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .px_2()
+                    .py_1()
+                    .rounded_md()
+                    .cursor_pointer()
+                    .text_sm()
+                    .when(is_selected, |this| this.bg(rgb(0x89b4fa)).text_color(rgb(0x1e1e2e)))
+                    .when(!is_selected, |this| {
+                        this.text_color(rgb(0xcdd6f4)).hover(|style| style.bg(rgb(0x313244)))
+                    })
+                    .on_click(cx.listener(move |this, _event, _window, cx| {
+                        this.edit(&name, cx);
+                    }))
+                    .child(label)
+                    .when(is_builtin, |this| {
+                        this.child(div().text_xs().text_color(rgb(0x6c7086)).child("built-in"))
+                    })
+            }))
+    }
+
+    /// A terminal-like sample drawn in the scheme being edited
+    fn render_preview(&self) -> impl IntoElement {
+        let scheme = &self.scheme;
+        let swatches = |colors: &[u32]| {
+            div().flex().gap_1().children(
+                colors
+                    .iter()
+                    .map(|&color| div().w(px(28.0)).h(px(14.0)).rounded_sm().bg(rgb(color))),
+            )
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .p_3()
+            .rounded_md()
+            .border_1()
+            .border_color(rgb(0x313244))
+            .bg(rgb(scheme.background))
+            .text_sm()
+            .text_color(rgb(scheme.foreground))
+            .child(
+                div()
+                    .flex()
+                    .child(div().text_color(rgb(scheme.green)).child("user@host"))
+                    .child(":")
+                    .child(div().text_color(rgb(scheme.blue)).child("~/projects"))
+                    .child("$ ls"),
+            )
+            .child(
+                div()
+                    .flex()
+                    .gap_3()
+                    .child(div().text_color(rgb(scheme.blue)).child("src"))
+                    .child(div().text_color(rgb(scheme.green)).child("build.sh"))
+                    .child(div().text_color(rgb(scheme.cyan)).child("latest"))
+                    .child(div().text_color(rgb(scheme.magenta)).child("logo.png"))
+                    .child("README.md"),
+            )
+            .child(
+                div()
+                    .flex()
+                    .gap_1()
+                    .child(div().text_color(rgb(scheme.red)).child("error:"))
+                    .child(div().text_color(rgb(scheme.yellow)).child("warning:"))
+                    .child(div().text_color(rgb(scheme.bright_black)).child("# comment")),
+            )
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .child(div().text_color(rgb(scheme.green)).child("user@host"))
+                    .child("$ ")
+                    .child(div().w(px(8.0)).h(px(16.0)).bg(rgb(scheme.cursor))),
+            )
+            .child(swatches(&scheme.colors()[3..11]))
+            .child(swatches(&scheme.colors()[11..]))
+    }
+
+    fn render_color_fields(&self) -> impl IntoElement {
+        let colors = self.scheme.colors();
+
+        div()
+            .flex()
+            .flex_wrap()
+            .gap_y_2()
+            .children(ColorScheme::COLOR_LABELS.iter().zip(&self.color_fields).zip(colors).map(
+                |((label, field), color)| {
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap_2()
+                        .w_1_2()
+                        .pr_3()
+                        .child(
+                            div()
+                                .w(px(16.0))
+                                .h(px(16.0))
+                                .rounded_sm()
+                                .border_1()
+                                .border_color(rgb(0x45475a))
+                                .bg(rgb(color)),
+                        )
+                        .child(div().w(px(100.0)).text_sm().text_color(rgb(0xcdd6f4)).child(*label))
+                        .child(div().flex_1().child(field.clone()))
+                },
+            ))
+    }
+}
+
+impl Render for ColorSchemeEditor {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let can_delete = self.original.is_some();
+
+        div()
+            .flex()
+            .flex_col()
+            .size_full()
+            .bg(rgb(0x1e1e2e))
+            // Header
+            .child(
+                div()
+                    .px_4()
+                    .py_3()
+                    .border_b_1()
+                    .border_color(rgb(0x313244))
+                    .child(
+                        div()
+                            .text_lg()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(rgb(0xcdd6f4))
+                            .child("Color Schemes"),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_1()
+                    .overflow_hidden()
+                    .child(self.render_scheme_list(cx))
+                    // Editor
+                    .child(
+                        div()
+                            .id("scheme-editor")
+                            .flex()
+                            .flex_col()
+                            .flex_1()
+                            .gap_4()
+                            .p_4()
+                            .overflow_y_scroll()
+                            .when_some(self.error.clone(), |this, error| {
+                                this.child(
+                                    div()
+                                        .p_2()
+                                        .bg(rgba(0xf38ba833))
+                                        .rounded_md()
+                                        .text_sm()
+                                        .text_color(rgb(0xf38ba8))
+                                        .child(error),
+                                )
+                            })
+                            .when_some(self.status.clone(), |this, status| {
+                                this.child(div().text_sm().text_color(rgb(0xa6e3a1)).child(status))
+                            })
+                            .child(
+                                div()
+                                    .flex()
+                                    .flex_col()
+                                    .gap_1()
+                                    .child(div().text_sm().text_color(rgb(0xcdd6f4)).child("Name"))
+                                    .child(self.name_field.clone()),
+                            )
+                            .child(self.render_preview())
+                            .child(self.render_color_fields()),
+                    ),
+            )
+            // Footer with buttons
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .px_4()
+                    .py_3()
+                    .border_t_1()
+                    .border_color(rgb(0x313244))
+                    .when(can_delete, |this| {
+                        this.child(
+                            div()
+                                .id("delete-scheme-btn")
+                                .px_4()
+                                .py_2()
+                                .rounded_md()
+                                .cursor_pointer()
+                                .hover(|style| style.bg(rgb(0x313244)))
+                                .on_click(cx.listener(|this, _event, _window, cx| {
+                                    this.handle_delete(cx);
+                                }))
+                                .child(div().text_sm().text_color(rgb(0xf38ba8)).child("Delete")),
+                        )
+                    })
+                    .child(
+                        div()
+                            .id("import-theme-btn")
+                            .px_4()
+                            .py_2()
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|style| style.bg(rgb(0x313244)))
+                            .on_click(cx.listener(|_this, _event, _window, cx| {
+                                crate::app::import_theme_file(cx);
+                            }))
+                            .child(div().text_sm().text_color(rgb(0xcdd6f4)).child("Import...")),
+                    )
+                    .child(div().flex_1())
+                    .child(
+                        div()
+                            .id("close-btn")
+                            .px_4()
+                            .py_2()
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|style| style.bg(rgb(0x313244)))
+                            .on_click(cx.listener(|_this, _event, window, _cx| {
+                                window.remove_window();
+                            }))
+                            .child(div().text_sm().text_color(rgb(0x6c7086)).child("Close")),
+                    )
+                    .child(
+                        div()
+                            .id("save-btn")
+                            .px_4()
+                            .py_2()
+                            .rounded_md()
+                            .cursor_pointer()
+                            .bg(rgb(0x313244))
+                            .hover(|style| style.bg(rgb(0x45475a)))
+                            .on_click(cx.listener(|this, _event, _window, cx| {
+                                this.handle_save(false, cx);
+                            }))
+                            .child(div().text_sm().text_color(rgb(0xcdd6f4)).child("Save")),
+                    )
+                    .child(
+                        div()
+                            .id("save-apply-btn")
+                            .px_4()
+                            .py_2()
+                            .bg(rgb(0x89b4fa))
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|style| style.bg(rgb(0x74c7ec)))
+                            .on_click(cx.listener(|this, _event, _window, cx| {
+                                this.handle_save(true, cx);
+                            }))
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .text_color(rgb(0x1e1e2e))
+                                    .font_weight(FontWeight::SEMIBOLD)
+                                    .child("Save & Use"),
+                            ),
+                    ),
+            )
+    }
+}
@@ -0,0 +1,286 @@
+use std::time::Duration;
+
+use gpui::*;
+use gpui::prelude::*;
+use uuid::Uuid;
+
+use crate::app::AppState;
+use crate::format::{format_duration, format_size};
+use crate::session::Session;
+use crate::terminal::ConnectionStatus;
+
+/// How often the dashboard refreshes uptime, traffic and memory counters
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Snapshot of one open tab for display
+struct ConnectionRow {
+    tab_id: Uuid,
+    title: String,
+    target: String,
+    kind: &'static str,
+    status: ConnectionStatus,
+    uptime: Option<Duration>,
+    bytes_sent: u64,
+    bytes_received: Option<u64>,
+    memory: usize,
+    is_active: bool,
+}
+
+/// Window listing every open connection with focus/disconnect controls
+pub struct ConnectionsDashboard {
+    _refresh_task: Task<()>,
+}
+
+impl ConnectionsDashboard {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        // Re-render periodically so uptime and byte counts stay current
+        let refresh_task = cx.spawn(async move |this: WeakEntity<ConnectionsDashboard>, cx: &mut AsyncApp| {
+            loop {
+                cx.background_executor().timer(REFRESH_INTERVAL).await;
+                if this.update(cx, |_, cx| cx.notify()).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            _refresh_task: refresh_task,
+        }
+    }
+
+    /// Open the dashboard in its own window
+    pub fn open(cx: &mut App) {
+        let window_options = WindowOptions {
+            window_bounds: Some(WindowBounds::Windowed(Bounds::centered(
+                None,
+                size(px(840.0), px(420.0)),
+                cx,
+            ))),
+            titlebar: Some(TitlebarOptions {
+                title: Some("Connections".into()),
+                appears_transparent: false,
+                ..Default::default()
+            }),
+            kind: WindowKind::Normal,
+            ..Default::default()
+        };
+
+        let _ = cx.open_window(window_options, |_window, cx| cx.new(ConnectionsDashboard::new));
+    }
+
+    fn collect_rows(cx: &App) -> Vec<ConnectionRow> {
+        let Some(state) = cx.try_global::<AppState>() else {
+            return Vec::new();
+        };
+        let app = state.app.lock();
+        let active_id = app.active_tab().map(|t| t.id);
+
+        app.tabs
+            .iter()
+            .map(|tab| {
+                let target = tab
+                    .session_id
+                    .and_then(|id| app.session_manager.get_session(id))
+                    .map(session_target)
+                    .unwrap_or_else(|| "localhost".to_string());
+                let terminal = tab.terminal.lock();
+                ConnectionRow {
+                    tab_id: tab.id,
+                    title: tab.display_title().to_string(),
+                    target,
+                    kind: terminal.kind_label(),
+                    status: terminal.status(),
+                    uptime: terminal.uptime(),
+                    bytes_sent: terminal.bytes_sent(),
+                    bytes_received: (!terminal.is_local()).then(|| terminal.bytes_received()),
+                    memory: terminal.memory_usage(),
+                    is_active: active_id == Some(tab.id),
+                }
+            })
+            .collect()
+    }
+
+    fn focus_tab(tab_id: Uuid, cx: &mut App) {
+        if let Some(state) = cx.try_global::<AppState>() {
+            state.app.lock().set_active_tab_by_id(tab_id);
+        }
+        cx.refresh_windows();
+    }
+
+    fn disconnect_tab(tab_id: Uuid, cx: &mut App) {
+        if let Some(state) = cx.try_global::<AppState>() {
+            state.app.lock().close_tab(tab_id);
+        }
+        cx.refresh_windows();
+    }
+
+    fn render_row(row: ConnectionRow) -> impl IntoElement {
+        let tab_id = row.tab_id;
+        let (status_label, status_color) = status_style(row.status);
+
+        div()
+            .id(ElementId::Name(format!("connection-{}", tab_id).into()))
+            .flex()
+            .items_center()
+            .gap_3()
+            .px_3()
+            .py_2()
+            .border_b_1()
+            .border_color(rgb(0x313244))
+            .when(row.is_active, |this| this.bg(rgb(0x313244)))
+            .child(
+                div()
+                    .w(px(180.0))
+                    .flex()
+                    .flex_col()
+                    .overflow_hidden()
+                    .child(div().text_sm().text_color(rgb(0xcdd6f4)).child(row.title))
+                    .child(div().text_xs().text_color(rgb(0x6c7086)).child(row.target)),
+            )
+            .child(div().w(px(48.0)).text_xs().text_color(rgb(0xcdd6f4)).child(row.kind))
+            .child(
+                div()
+                    .w(px(100.0))
+                    .flex()
+                    .items_center()
+                    .gap_1()
+                    .child(div().w(px(8.0)).h(px(8.0)).rounded_full().bg(rgb(status_color)))
+                    .child(div().text_xs().text_color(rgb(0xcdd6f4)).child(status_label)),
+            )
+            .child(
+                div()
+                    .w(px(72.0))
+                    .text_xs()
+                    .text_color(rgb(0xcdd6f4))
+                    .child(row.uptime.map(format_duration).unwrap_or_else(|| "—".to_string())),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .text_xs()
+                    .text_color(rgb(0x6c7086))
+                    .child(format!(
+                        "↑ {}  ↓ {}",
+                        format_size(row.bytes_sent),
+                        row.bytes_received.map(format_size).unwrap_or_else(|| "—".to_string())
+                    )),
+            )
+            .child(
+                div()
+                    .w(px(72.0))
+                    .text_xs()
+                    .text_color(rgb(0x6c7086))
+                    .child(format_size(row.memory as u64)),
+            )
+            .child(
+                div()
+                    .flex()
+                    .gap_1()
+                    .child(
+                        div()
+                            .id(ElementId::Name(format!("connection-focus-{}", tab_id).into()))
+                            .px_2()
+                            .py_1()
+                            .rounded_md()
+                            .cursor_pointer()
+                            .bg(rgb(0x313244))
+                            .hover(|style| style.bg(rgb(0x45475a)))
+                            .text_xs()
+                            .text_color(rgb(0xcdd6f4))
+                            .on_click(move |_event, _window, cx| Self::focus_tab(tab_id, cx))
+                            .child("Focus"),
+                    )
+                    .child(
+                        div()
+                            .id(ElementId::Name(format!("connection-close-{}", tab_id).into()))
+                            .px_2()
+                            .py_1()
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|style| style.bg(rgba(0xf38ba833)))
+                            .text_xs()
+                            .text_color(rgb(0xf38ba8))
+                            .on_click(move |_event, _window, cx| Self::disconnect_tab(tab_id, cx))
+                            .child("Disconnect"),
+                    ),
+            )
+    }
+}
+
+impl Render for ConnectionsDashboard {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let rows = Self::collect_rows(cx);
+        let connected = rows.iter().filter(|r| r.status == ConnectionStatus::Connected).count();
+        let total = rows.len();
+
+        div()
+            .flex()
+            .flex_col()
+            .size_full()
+            .bg(rgb(0x1e1e2e))
+            // Header
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .px_4()
+                    .py_3()
+                    .border_b_1()
+                    .border_color(rgb(0x313244))
+                    .child(
+                        div()
+                            .text_lg()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(rgb(0xcdd6f4))
+                            .child("Connections"),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(rgb(0x6c7086))
+                            .child(format!("{} of {} connected", connected, total)),
+                    ),
+            )
+            .child(
+                div()
+                    .id("connections-list")
+                    .flex()
+                    .flex_col()
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .when(rows.is_empty(), |this| {
+                        this.child(
+                            div()
+                                .p_4()
+                                .text_sm()
+                                .text_color(rgb(0x6c7086))
+                                .child("No open connections"),
+                        )
+                    })
+                    .children(rows.into_iter().map(Self::render_row)),
+            )
+    }
+}
+
+/// Host (or instance/pod) a session connects to
+fn session_target(session: &Session) -> String {
+    match session {
+        Session::Ssh(s) if s.username.is_empty() => format!("{}:{}", s.host, s.port),
+        Session::Ssh(s) => format!("{}@{}:{}", s.username, s.host, s.port),
+        Session::Local(_) => "localhost".to_string(),
+        Session::Ssm(s) => s.instance_id.clone(),
+        Session::K8s(s) => format!("{}/{}/{}", s.context, s.namespace, s.pod),
+        Session::Serial(s) => format!("{} @ {}", s.device, s.line_settings()),
+        Session::Telnet(s) => s.address(),
+    }
+}
+
+fn status_style(status: ConnectionStatus) -> (&'static str, u32) {
+    match status {
+        ConnectionStatus::Connecting => ("Connecting", 0xf9e2af),
+        ConnectionStatus::Connected => ("Connected", 0xa6e3a1),
+        ConnectionStatus::Disconnected => ("Disconnected", 0x6c7086),
+        ConnectionStatus::Failed => ("Failed", 0xf38ba8),
+    }
+}
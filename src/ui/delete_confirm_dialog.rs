@@ -3,12 +3,17 @@ use gpui::prelude::*;
 use uuid::Uuid;
 
 use crate::app::AppState;
+use crate::terminal::remove_host_key;
 
 /// Target for deletion
 #[derive(Clone, Debug)]
 pub enum DeleteTarget {
     Session { id: Uuid, name: String },
+    /// Several sessions selected together in the tree
+    Sessions { ids: Vec<Uuid> },
     Group { id: Uuid, name: String },
+    /// A host's saved keys in known_hosts
+    HostKey { host: String },
 }
 
 /// Events emitted by the delete confirmation dialog
@@ -57,6 +62,28 @@ impl DeleteConfirmDialog {
         });
     }
 
+    /// Open as a modal window for deleting several sessions at once
+    pub fn open_for_sessions(ids: Vec<Uuid>, cx: &mut App) {
+        let window_options = WindowOptions {
+            window_bounds: Some(WindowBounds::Windowed(Bounds::centered(
+                None,
+                size(px(380.0), px(200.0)),
+                cx,
+            ))),
+            titlebar: Some(TitlebarOptions {
+                title: Some("Delete Sessions".into()),
+                appears_transparent: false,
+                ..Default::default()
+            }),
+            kind: WindowKind::Normal,
+            ..Default::default()
+        };
+
+        let _ = cx.open_window(window_options, |_window, cx| {
+            cx.new(|_cx| DeleteConfirmDialog::new(DeleteTarget::Sessions { ids }))
+        });
+    }
+
     /// Open as a modal window for group deletion
     pub fn open_for_group(id: Uuid, name: String, cx: &mut App) {
         let window_options = WindowOptions {
@@ -79,9 +106,37 @@ impl DeleteConfirmDialog {
         });
     }
 
+    /// Open as a modal window for forgetting a host's known_hosts entries
+    pub fn open_for_host_key(host: String, cx: &mut App) {
+        let window_options = WindowOptions {
+            window_bounds: Some(WindowBounds::Windowed(Bounds::centered(
+                None,
+                size(px(420.0), px(220.0)),
+                cx,
+            ))),
+            titlebar: Some(TitlebarOptions {
+                title: Some("Forget Host Key".into()),
+                appears_transparent: false,
+                ..Default::default()
+            }),
+            kind: WindowKind::Normal,
+            ..Default::default()
+        };
+
+        let _ = cx.open_window(window_options, |_window, cx| {
+            cx.new(|_cx| DeleteConfirmDialog::new(DeleteTarget::HostKey { host }))
+        });
+    }
+
     /// Handle delete confirmation
     fn handle_delete(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        if let Some(app_state) = cx.try_global::<AppState>() {
+        if let DeleteTarget::HostKey { host } = &self.target {
+            match remove_host_key(host) {
+                Ok(0) => tracing::info!("No known_hosts entries for {}", host),
+                Ok(_) => {}
+                Err(e) => tracing::error!("Failed to forget host key for {}: {}", host, e),
+            }
+        } else if let Some(app_state) = cx.try_global::<AppState>() {
             let mut app = app_state.app.lock();
             match &self.target {
                 DeleteTarget::Session { id, .. } => {
@@ -89,11 +144,19 @@ impl DeleteConfirmDialog {
                         tracing::error!("Failed to delete session: {}", e);
                     }
                 }
+                DeleteTarget::Sessions { ids } => {
+                    for id in ids {
+                        if let Err(e) = app.delete_session(*id) {
+                            tracing::error!("Failed to delete session: {}", e);
+                        }
+                    }
+                }
                 DeleteTarget::Group { id, .. } => {
                     if let Err(e) = app.delete_group(*id, self.recursive) {
                         tracing::error!("Failed to delete group: {}", e);
                     }
                 }
+                DeleteTarget::HostKey { .. } => {}
             }
             let _ = app.save();
         }
@@ -111,10 +174,25 @@ impl DeleteConfirmDialog {
 
 impl Render for DeleteConfirmDialog {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let (title, name, is_group) = match &self.target {
-            DeleteTarget::Session { name, .. } => ("Delete Session?", name.clone(), false),
-            DeleteTarget::Group { name, .. } => ("Delete Group?", name.clone(), true),
+        let (title, message, is_group) = match &self.target {
+            DeleteTarget::Session { name, .. } => {
+                ("Delete Session?", format!("Are you sure you want to delete '{}'?", name), false)
+            }
+            DeleteTarget::Sessions { ids } => (
+                "Delete Sessions?",
+                format!("Are you sure you want to delete {} sessions?", ids.len()),
+                false,
+            ),
+            DeleteTarget::Group { name, .. } => {
+                ("Delete Group?", format!("Are you sure you want to delete '{}'?", name), true)
+            }
+            DeleteTarget::HostKey { host } => (
+                "Forget Host Key?",
+                format!("Remove the saved keys for '{}' from known_hosts?", host),
+                false,
+            ),
         };
+        let is_host_key = matches!(self.target, DeleteTarget::HostKey { .. });
 
         let recursive = self.recursive;
 
@@ -152,8 +230,16 @@ impl Render for DeleteConfirmDialog {
                         div()
                             .text_sm()
                             .text_color(rgb(0xcdd6f4))
-                            .child(format!("Are you sure you want to delete '{}'?", name)),
+                            .child(message),
                     )
+                    .when(is_host_key, |this| {
+                        this.child(
+                            div()
+                                .text_xs()
+                                .text_color(rgb(0x6c7086))
+                                .child("The next connection will ask you to accept whatever key the server presents. Only do this if you know the server was rebuilt or its key rotated."),
+                        )
+                    })
                     // Show recursive checkbox only for groups
                     .when(is_group, |this| {
                         this.child(
@@ -251,7 +337,7 @@ impl Render for DeleteConfirmDialog {
                                     .text_sm()
                                     .text_color(rgb(0x1e1e2e))
                                     .font_weight(FontWeight::SEMIBOLD)
-                                    .child("Delete"),
+                                    .child(if is_host_key { "Forget" } else { "Delete" }),
                             ),
                     ),
             )
@@ -1,9 +1,11 @@
 use gpui::*;
 use gpui::prelude::*;
+use std::path::PathBuf;
 use uuid::Uuid;
 
 use crate::app::AppState;
-use crate::session::SessionGroup;
+use crate::config::ColorScheme;
+use crate::session::{AuthMethod, GroupDefaults, SessionGroup};
 use super::text_field::TextField;
 
 /// Result of the group dialog
@@ -23,6 +25,37 @@ pub enum GroupDialogEvent {
 
 impl EventEmitter<GroupDialogEvent> for GroupDialog {}
 
+/// Auth method new sessions in the group start with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DefaultAuth {
+    /// No default; taken from the parent group, if any
+    Unset,
+    Password,
+    PrivateKey,
+    Agent,
+    KeyboardInteractive,
+}
+
+impl DefaultAuth {
+    const ALL: [DefaultAuth; 5] = [
+        DefaultAuth::Unset,
+        DefaultAuth::Password,
+        DefaultAuth::PrivateKey,
+        DefaultAuth::Agent,
+        DefaultAuth::KeyboardInteractive,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            DefaultAuth::Unset => "Not set",
+            DefaultAuth::Password => "Password",
+            DefaultAuth::PrivateKey => "Private Key",
+            DefaultAuth::Agent => "SSH Agent",
+            DefaultAuth::KeyboardInteractive => "Keyboard-Interactive",
+        }
+    }
+}
+
 /// Group dialog for creating/editing session groups
 pub struct GroupDialog {
     /// Group ID if editing (None for new group)
@@ -33,6 +66,14 @@ pub struct GroupDialog {
     name_field: Entity<TextField>,
     /// Selected color
     color: Option<String>,
+    /// Defaults for new SSH sessions in the group
+    username_field: Entity<TextField>,
+    port_field: Entity<TextField>,
+    key_path_field: Entity<TextField>,
+    default_auth: DefaultAuth,
+    color_scheme: Option<String>,
+    /// The auth default being edited, so key options the form doesn't show survive
+    original_auth: Option<AuthMethod>,
     /// Validation errors
     errors: Vec<String>,
     /// Available colors
@@ -47,6 +88,12 @@ impl GroupDialog {
             parent_id,
             name_field: cx.new(|cx| TextField::new(cx, "Group Name")),
             color: None,
+            username_field: cx.new(|cx| TextField::new(cx, "not set")),
+            port_field: cx.new(|cx| TextField::new(cx, "not set")),
+            key_path_field: cx.new(|cx| TextField::new(cx, "~/.ssh/id_rsa")),
+            default_auth: DefaultAuth::Unset,
+            color_scheme: None,
+            original_auth: None,
             errors: Vec::new(),
             available_colors: vec![
                 ("Red", "#f38ba8"),
@@ -63,11 +110,32 @@ impl GroupDialog {
 
     /// Create a dialog for editing an existing group
     pub fn edit(group: &SessionGroup, cx: &mut Context<Self>) -> Self {
+        let defaults = &group.defaults;
+        let default_auth = match &defaults.auth {
+            None => DefaultAuth::Unset,
+            Some(AuthMethod::Password { .. }) => DefaultAuth::Password,
+            Some(AuthMethod::PrivateKey { .. }) => DefaultAuth::PrivateKey,
+            Some(AuthMethod::Agent) => DefaultAuth::Agent,
+            Some(AuthMethod::KeyboardInteractive) => DefaultAuth::KeyboardInteractive,
+        };
+        let key_path = match &defaults.auth {
+            Some(AuthMethod::PrivateKey { path, .. }) => path.to_string_lossy().into_owned(),
+            _ => String::new(),
+        };
+        let username = defaults.username.clone().unwrap_or_default();
+        let port = defaults.port.map(|p| p.to_string()).unwrap_or_default();
+
         Self {
             group_id: Some(group.id),
             parent_id: group.parent_id,
             name_field: cx.new(|cx| TextField::with_content(cx, "Group Name", group.name.clone())),
             color: group.color.clone(),
+            username_field: cx.new(|cx| TextField::with_content(cx, "not set", username)),
+            port_field: cx.new(|cx| TextField::with_content(cx, "not set", port)),
+            key_path_field: cx.new(|cx| TextField::with_content(cx, "~/.ssh/id_rsa", key_path)),
+            default_auth,
+            color_scheme: defaults.color_scheme.clone(),
+            original_auth: defaults.auth.clone(),
             errors: Vec::new(),
             available_colors: vec![
                 ("Red", "#f38ba8"),
@@ -87,7 +155,7 @@ impl GroupDialog {
         let window_options = WindowOptions {
             window_bounds: Some(WindowBounds::Windowed(Bounds::centered(
                 None,
-                size(px(400.0), px(300.0)),
+                size(px(450.0), px(620.0)),
                 cx,
             ))),
             titlebar: Some(TitlebarOptions {
@@ -110,7 +178,7 @@ impl GroupDialog {
         let window_options = WindowOptions {
             window_bounds: Some(WindowBounds::Windowed(Bounds::centered(
                 None,
-                size(px(400.0), px(300.0)),
+                size(px(450.0), px(620.0)),
                 cx,
             ))),
             titlebar: Some(TitlebarOptions {
@@ -137,6 +205,14 @@ impl GroupDialog {
             self.errors.push("Name is required".to_string());
         }
 
+        let port = self.port_field.read(cx).content().trim().to_string();
+        if !port.is_empty() && port.parse::<u16>().is_err() {
+            self.errors.push("Default port must be a number between 1 and 65535".to_string());
+        }
+        if self.default_auth == DefaultAuth::PrivateKey && self.key_path_field.read(cx).content().trim().is_empty() {
+            self.errors.push("Default key path is required for private key auth".to_string());
+        }
+
         self.errors.is_empty()
     }
 
@@ -151,6 +227,7 @@ impl GroupDialog {
         };
 
         group.color = self.color.clone();
+        group.defaults = self.build_defaults(cx);
 
         // Preserve ID if editing
         if let Some(id) = self.group_id {
@@ -160,6 +237,47 @@ impl GroupDialog {
         group
     }
 
+    /// Build the session defaults from form fields
+    fn build_defaults(&self, cx: &Context<Self>) -> GroupDefaults {
+        let username = self.username_field.read(cx).content().trim().to_string();
+        let port = self.port_field.read(cx).content().trim().parse().ok();
+        let key_path = PathBuf::from(self.key_path_field.read(cx).content().trim());
+
+        let auth = match (self.default_auth, self.original_auth.clone()) {
+            (DefaultAuth::Unset, _) => None,
+            (DefaultAuth::Password, Some(auth @ AuthMethod::Password { .. })) => Some(auth),
+            (DefaultAuth::Password, _) => Some(AuthMethod::Password {
+                password: None,
+                use_keychain: false,
+            }),
+            (DefaultAuth::PrivateKey, Some(AuthMethod::PrivateKey { extra_keys, identities_only, use_keychain, .. })) => {
+                Some(AuthMethod::PrivateKey {
+                    path: key_path,
+                    passphrase: None,
+                    use_keychain,
+                    extra_keys,
+                    identities_only,
+                })
+            }
+            (DefaultAuth::PrivateKey, _) => Some(AuthMethod::PrivateKey {
+                path: key_path,
+                passphrase: None,
+                use_keychain: false,
+                extra_keys: Vec::new(),
+                identities_only: false,
+            }),
+            (DefaultAuth::Agent, _) => Some(AuthMethod::Agent),
+            (DefaultAuth::KeyboardInteractive, _) => Some(AuthMethod::KeyboardInteractive),
+        };
+
+        GroupDefaults {
+            username: Some(username).filter(|u| !u.is_empty()),
+            port,
+            auth,
+            color_scheme: self.color_scheme.clone(),
+        }
+    }
+
     /// Get the built group if valid
     pub fn get_group(&self, cx: &Context<Self>) -> Option<SessionGroup> {
         if self.errors.is_empty() {
@@ -181,12 +299,18 @@ impl GroupDialog {
         // Save to app state
         if let Some(app_state) = cx.try_global::<AppState>() {
             let mut app = app_state.app.lock();
-            if self.group_id.is_some() {
+            let result = if self.group_id.is_some() {
                 // Update existing group
-                let _ = app.session_manager.update_group(group.id, group.clone());
+                app.session_manager.update_group(group.id, group.clone())
             } else {
                 // Add new group
-                app.session_manager.add_group(group.clone());
+                app.session_manager.add_group(group.clone()).map(|_| ())
+            };
+            if let Err(e) = result {
+                drop(app);
+                self.errors = vec![e.to_string()];
+                cx.notify();
+                return;
             }
             let _ = app.save();
         }
@@ -202,6 +326,136 @@ impl GroupDialog {
         cx.emit(GroupDialogEvent::Canceled);
         window.remove_window();
     }
+
+    fn render_label(&self, text: &'static str) -> impl IntoElement {
+        div().text_sm().text_color(rgb(0xcdd6f4)).child(text)
+    }
+
+    fn render_option(
+        &self,
+        id: String,
+        label: impl Into<SharedString>,
+        is_selected: bool,
+        on_click: impl Fn(&mut Self) + 'static,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        div()
+            .id(ElementId::Name(id.into()))
+            .px_3()
+            .py_1()
+            .rounded_md()
+            .cursor_pointer()
+            .when(is_selected, |this| this.bg(rgb(0x89b4fa)).text_color(rgb(0x1e1e2e)))
+            .when(!is_selected, |this| {
+                this.bg(rgb(0x313244))
+                    .text_color(rgb(0xcdd6f4))
+                    .hover(|style| style.bg(rgb(0x45475a)))
+            })
+            .on_click(cx.listener(move |this, _event, _window, cx| {
+                on_click(this);
+                cx.notify();
+            }))
+            .child(div().text_sm().child(label.into()))
+    }
+
+    /// Defaults new SSH sessions in the group start with, and keep following
+    /// as long as they aren't changed
+    fn render_session_defaults(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut scheme_choices: Vec<String> = cx
+            .try_global::<AppState>()
+            .map(|state| state.app.lock().config.appearance.visible_scheme_names())
+            .unwrap_or_else(|| ColorScheme::builtin_names().iter().map(|n| n.to_string()).collect());
+        scheme_choices.retain(|name| name != "default");
+        if let Some(current) = self.color_scheme.as_ref().filter(|c| !scheme_choices.contains(c)) {
+            scheme_choices.push(current.clone());
+        }
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_3()
+            .pt_3()
+            .border_t_1()
+            .border_color(rgb(0x313244))
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(rgb(0x6c7086))
+                    .child("Session defaults. New SSH sessions in this group start with these and follow later changes unless edited."),
+            )
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .child(
+                        div()
+                            .flex()
+                            .flex_col()
+                            .flex_1()
+                            .gap_1()
+                            .child(self.render_label("Username"))
+                            .child(self.username_field.clone()),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .flex_col()
+                            .w(px(100.0))
+                            .gap_1()
+                            .child(self.render_label("Port"))
+                            .child(self.port_field.clone()),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .child(self.render_label("Authentication"))
+                    .child(div().flex().flex_wrap().gap_2().children(DefaultAuth::ALL.into_iter().map(|auth| {
+                        self.render_option(
+                            format!("default-auth-{:?}", auth),
+                            auth.label(),
+                            self.default_auth == auth,
+                            move |this| this.default_auth = auth,
+                            cx,
+                        )
+                    })))
+                    .when(self.default_auth == DefaultAuth::PrivateKey, |this| {
+                        this.child(self.key_path_field.clone())
+                    }),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .child(self.render_label("Color Scheme"))
+                    .child(
+                        div()
+                            .flex()
+                            .flex_wrap()
+                            .gap_2()
+                            .child(self.render_option(
+                                "default-scheme-none".to_string(),
+                                "Not set",
+                                self.color_scheme.is_none(),
+                                |this| this.color_scheme = None,
+                                cx,
+                            ))
+                            .children(scheme_choices.into_iter().map(|name| {
+                                let value = Some(name.clone());
+                                self.render_option(
+                                    format!("default-scheme-{}", name),
+                                    ColorScheme::display_name(&name),
+                                    self.color_scheme.as_deref() == Some(name.as_str()),
+                                    move |this| this.color_scheme = value.clone(),
+                                    cx,
+                                )
+                            })),
+                    ),
+            )
+    }
 }
 
 impl Render for GroupDialog {
@@ -325,7 +579,8 @@ impl Render for GroupDialog {
                                             }))
                                     })),
                             ),
-                    ),
+                    )
+                    .child(self.render_session_defaults(cx)),
             )
             // Footer with buttons
             .child(
@@ -0,0 +1,209 @@
+use gpui::*;
+use gpui::prelude::*;
+
+use crate::terminal::HostKeyRequest;
+
+/// Asks whether to trust a server host key that known_hosts doesn't vouch for
+///
+/// Accepting saves the key to known_hosts and lets the connection go ahead.
+/// Rejecting, or closing the window, aborts the connection. A changed key
+/// can only be accepted after ticking a confirmation box.
+pub struct HostKeyDialog {
+    /// The pending question; taken when answered
+    request: Option<HostKeyRequest>,
+    host: String,
+    key_type: String,
+    fingerprint: String,
+    changed: bool,
+    /// The user confirmed they expect the key to have changed
+    confirmed: bool,
+}
+
+impl HostKeyDialog {
+    pub fn new(request: HostKeyRequest) -> Self {
+        Self {
+            host: request.host.clone(),
+            key_type: request.key_type.clone(),
+            fingerprint: request.fingerprint.clone(),
+            changed: request.changed,
+            request: Some(request),
+            confirmed: false,
+        }
+    }
+
+    /// Open as a modal window
+    pub fn open(request: HostKeyRequest, cx: &mut App) {
+        let title = if request.changed { "Host Key Changed" } else { "Unknown Host Key" };
+        let window_options = WindowOptions {
+            window_bounds: Some(WindowBounds::Windowed(Bounds::centered(
+                None,
+                size(px(500.0), px(if request.changed { 340.0 } else { 280.0 })),
+                cx,
+            ))),
+            titlebar: Some(TitlebarOptions {
+                title: Some(title.into()),
+                appears_transparent: false,
+                ..Default::default()
+            }),
+            kind: WindowKind::Normal,
+            ..Default::default()
+        };
+
+        let _ = cx.open_window(window_options, |_window, cx| cx.new(|_cx| HostKeyDialog::new(request)));
+    }
+
+    fn respond(&mut self, accept: bool, window: &mut Window) {
+        if let Some(request) = self.request.take() {
+            request.respond(accept);
+        }
+        window.remove_window();
+    }
+}
+
+impl Render for HostKeyDialog {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let (title, message) = if self.changed {
+            (
+                "Host key has changed!",
+                format!(
+                    "The key {} presented doesn't match the one saved in known_hosts. Someone could be intercepting the connection, or the server was rebuilt or its key rotated.",
+                    self.host
+                ),
+            )
+        } else {
+            (
+                "Unknown host key",
+                format!(
+                    "The authenticity of {} can't be established; its key isn't in known_hosts yet. Check the fingerprint with the server's administrator before connecting.",
+                    self.host
+                ),
+            )
+        };
+        let can_accept = !self.changed || self.confirmed;
+        let confirmed = self.confirmed;
+
+        div()
+            .flex()
+            .flex_col()
+            .size_full()
+            .bg(rgb(0x1e1e2e))
+            // Header
+            .child(
+                div()
+                    .px_4()
+                    .py_3()
+                    .border_b_1()
+                    .border_color(rgb(0x313244))
+                    .child(
+                        div()
+                            .text_lg()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(rgb(if self.changed { 0xf38ba8 } else { 0xcdd6f4 }))
+                            .child(title),
+                    ),
+            )
+            // Content
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .flex_1()
+                    .gap_3()
+                    .p_4()
+                    .child(div().text_sm().text_color(rgb(0xcdd6f4)).child(message))
+                    .child(
+                        div()
+                            .flex()
+                            .flex_col()
+                            .gap_1()
+                            .p_2()
+                            .bg(rgb(0x313244))
+                            .rounded_md()
+                            .child(div().text_xs().text_color(rgb(0x6c7086)).child(format!("{} key fingerprint", self.key_type)))
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .font_family("monospace")
+                                    .text_color(rgb(0xcdd6f4))
+                                    .child(self.fingerprint.clone()),
+                            ),
+                    )
+                    .when(self.changed, |this| {
+                        this.child(
+                            div()
+                                .id("confirm-changed-key")
+                                .flex()
+                                .items_center()
+                                .gap_2()
+                                .cursor_pointer()
+                                .on_click(cx.listener(|this, _event, _window, cx| {
+                                    this.confirmed = !this.confirmed;
+                                    cx.notify();
+                                }))
+                                .child(
+                                    div()
+                                        .text_sm()
+                                        .text_color(rgb(0xf38ba8))
+                                        .child(if confirmed { "☑" } else { "☐" }),
+                                )
+                                .child(
+                                    div()
+                                        .text_sm()
+                                        .text_color(rgb(0xcdd6f4))
+                                        .child("I know why the key changed; replace the saved key"),
+                                ),
+                        )
+                    }),
+            )
+            // Footer with buttons
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_end()
+                    .gap_2()
+                    .px_4()
+                    .py_3()
+                    .border_t_1()
+                    .border_color(rgb(0x313244))
+                    .child(
+                        div()
+                            .id("reject-btn")
+                            .px_4()
+                            .py_2()
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|style| style.bg(rgb(0x313244)))
+                            .on_click(cx.listener(|this, _event, window, _cx| {
+                                this.respond(false, window);
+                            }))
+                            .child(div().text_sm().text_color(rgb(0x6c7086)).child("Reject")),
+                    )
+                    .child(
+                        div()
+                            .id("accept-btn")
+                            .px_4()
+                            .py_2()
+                            .bg(rgb(match (can_accept, self.changed) {
+                                (false, _) => 0x45475a,
+                                (true, true) => 0xf38ba8,
+                                (true, false) => 0x89b4fa,
+                            }))
+                            .rounded_md()
+                            .when(can_accept, |this| {
+                                this.cursor_pointer()
+                                    .on_click(cx.listener(|this, _event, window, _cx| {
+                                        this.respond(true, window);
+                                    }))
+                            })
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .text_color(rgb(0x1e1e2e))
+                                    .font_weight(FontWeight::SEMIBOLD)
+                                    .child("Accept and connect"),
+                            ),
+                    ),
+            )
+    }
+}
@@ -0,0 +1,462 @@
+use std::path::PathBuf;
+
+use gpui::*;
+use gpui::prelude::*;
+
+use crate::app::AppState;
+use crate::session::import::{import_sessions, ImportResult, ImportSource};
+use crate::session::SshSession;
+use super::text_field::TextField;
+
+/// A parsed host the user can choose to import
+struct Candidate {
+    session: SshSession,
+    selected: bool,
+}
+
+/// Dialog for importing sessions from PuTTY, Termius, iTerm2 or an ssh config
+pub struct ImportDialog {
+    /// Application to import from
+    source: ImportSource,
+    /// Path to the export file or directory
+    path_field: Entity<TextField>,
+    /// Name of the group the sessions are placed in
+    group_field: Entity<TextField>,
+    /// Hosts found by the last scan
+    candidates: Vec<Candidate>,
+    /// Parse or import errors
+    errors: Vec<String>,
+    /// Result of the last import, shown instead of the host list
+    result: Option<ImportResult>,
+}
+
+impl ImportDialog {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        let source = ImportSource::Putty;
+        Self {
+            source,
+            path_field: cx.new(|cx| TextField::with_content(cx, "Path to export file or folder", Self::default_path(source))),
+            group_field: cx.new(|cx| TextField::with_content(cx, "Group Name", Self::default_group(source))),
+            candidates: Vec::new(),
+            errors: Vec::new(),
+            result: None,
+        }
+    }
+
+    /// Open as a modal window
+    pub fn open(cx: &mut App) {
+        let window_options = WindowOptions {
+            window_bounds: Some(WindowBounds::Windowed(Bounds::centered(
+                None,
+                size(px(520.0), px(560.0)),
+                cx,
+            ))),
+            titlebar: Some(TitlebarOptions {
+                title: Some("Import Sessions".into()),
+                appears_transparent: false,
+                ..Default::default()
+            }),
+            kind: WindowKind::Normal,
+            ..Default::default()
+        };
+
+        let _ = cx.open_window(window_options, |_window, cx| cx.new(ImportDialog::new));
+    }
+
+    fn default_path(source: ImportSource) -> String {
+        source
+            .default_path()
+            .filter(|p| p.exists())
+            .map(|p| p.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    fn default_group(source: ImportSource) -> String {
+        format!("Imported from {}", source.label())
+    }
+
+    fn select_source(&mut self, source: ImportSource, cx: &mut Context<Self>) {
+        if self.source == source {
+            return;
+        }
+        self.source = source;
+        self.candidates.clear();
+        self.errors.clear();
+        self.result = None;
+        self.path_field.update(cx, |field, cx| {
+            field.set_content(Self::default_path(source));
+            cx.notify();
+        });
+        self.group_field.update(cx, |field, cx| {
+            field.set_content(Self::default_group(source));
+            cx.notify();
+        });
+        cx.notify();
+    }
+
+    /// Parse the selected file and list the hosts found
+    fn scan(&mut self, cx: &mut Context<Self>) {
+        self.errors.clear();
+        self.result = None;
+        self.candidates.clear();
+
+        let path = self.path_field.read(cx).content().trim().to_string();
+        if path.is_empty() {
+            self.errors.push("Path is required".to_string());
+        } else {
+            let path = match path.strip_prefix("~/") {
+                Some(rest) => dirs::home_dir().map(|h| h.join(rest)).unwrap_or_else(|| PathBuf::from(&path)),
+                None => PathBuf::from(&path),
+            };
+            match self.source.parse(&path) {
+                Ok(sessions) if sessions.is_empty() => {
+                    self.errors.push(format!("No SSH hosts found in {}", path.display()));
+                }
+                Ok(sessions) => {
+                    self.candidates = sessions
+                        .into_iter()
+                        .map(|session| Candidate { session, selected: true })
+                        .collect();
+                }
+                Err(e) => self.errors.push(e.to_string()),
+            }
+        }
+        cx.notify();
+    }
+
+    fn set_all_selected(&mut self, selected: bool, cx: &mut Context<Self>) {
+        for candidate in &mut self.candidates {
+            candidate.selected = selected;
+        }
+        cx.notify();
+    }
+
+    /// Import the selected hosts into a new group
+    fn handle_import(&mut self, cx: &mut Context<Self>) {
+        self.errors.clear();
+        let group_name = self.group_field.read(cx).content().trim().to_string();
+        if group_name.is_empty() {
+            self.errors.push("Group name is required".to_string());
+        }
+        let selected: Vec<SshSession> = self
+            .candidates
+            .iter()
+            .filter(|c| c.selected)
+            .map(|c| c.session.clone())
+            .collect();
+        if selected.is_empty() {
+            self.errors.push("Select at least one host".to_string());
+        }
+        if !self.errors.is_empty() {
+            cx.notify();
+            return;
+        }
+
+        if let Some(app_state) = cx.try_global::<AppState>() {
+            let mut app = app_state.app.lock();
+            let result = import_sessions(&mut app.session_manager, &group_name, selected);
+            if !result.created.is_empty() {
+                if let Err(e) = app.save() {
+                    self.errors.push(format!("Failed to save sessions: {}", e));
+                }
+            }
+            self.result = Some(result);
+        }
+        self.candidates.clear();
+        cx.refresh_windows();
+        cx.notify();
+    }
+
+    fn render_source_selector(&self, cx: &Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .gap_2()
+            .children(ImportSource::ALL.into_iter().map(|source| {
+                let is_selected = self.source == source;
+                div()
+                    .id(ElementId::Name(format!("import-source-{}", source.label()).into()))
+                    .px_3()
+                    .py_1()
+                    .rounded_md()
+                    .cursor_pointer()
+                    .text_sm()
+                    .when(is_selected, |this| this.bg(rgb(0x89b4fa)).text_color(rgb(0x1e1e2e)))
+                    .when(!is_selected, |this| {
+                        this.bg(rgb(0x313244))
+                            .text_color(rgb(0xcdd6f4))
+                            .hover(|style| style.bg(rgb(0x45475a)))
+                    })
+                    .on_click(cx.listener(move |this, _event, _window, cx| {
+                        this.select_source(source, cx);
+                    }))
+                    .child(source.label())
+            }))
+    }
+
+    fn render_candidates(&self, cx: &Context<Self>) -> impl IntoElement {
+        let selected_count = self.candidates.iter().filter(|c| c.selected).count();
+
+        div()
+            .flex()
+            .flex_col()
+            .flex_1()
+            .gap_2()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(rgb(0xcdd6f4))
+                            .child(format!("{} of {} hosts selected", selected_count, self.candidates.len())),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .gap_2()
+                            .text_xs()
+                            .text_color(rgb(0x89b4fa))
+                            .child(
+                                div()
+                                    .id("import-select-all")
+                                    .cursor_pointer()
+                                    .on_click(cx.listener(|this, _event, _window, cx| this.set_all_selected(true, cx)))
+                                    .child("All"),
+                            )
+                            .child(
+                                div()
+                                    .id("import-select-none")
+                                    .cursor_pointer()
+                                    .on_click(cx.listener(|this, _event, _window, cx| this.set_all_selected(false, cx)))
+                                    .child("None"),
+                            ),
+                    ),
+            )
+            .child(
+                div()
+                    .id("import-candidates")
+                    .flex()
+                    .flex_col()
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .rounded_md()
+                    .border_1()
+                    .border_color(rgb(0x313244))
+                    .children(self.candidates.iter().enumerate().map(|(index, candidate)| {
+                        let session = &candidate.session;
+                        let target = if session.username.is_empty() {
+                            format!("{}:{}", session.host, session.port)
+                        } else {
+                            format!("{}@{}:{}", session.username, session.host, session.port)
+                        };
+
+                        div()
+                            .id(ElementId::Name(format!("import-candidate-{}", index).into()))
+                            .flex()
+                            .items_center()
+                            .gap_2()
+                            .px_2()
+                            .py_1()
+                            .cursor_pointer()
+                            .hover(|style| style.bg(rgb(0x313244)))
+                            .on_click(cx.listener(move |this, _event, _window, cx| {
+                                if let Some(candidate) = this.candidates.get_mut(index) {
+                                    candidate.selected = !candidate.selected;
+                                }
+                                cx.notify();
+                            }))
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .text_color(rgb(0x89b4fa))
+                                    .child(if candidate.selected { "☑" } else { "☐" }),
+                            )
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .text_sm()
+                                    .text_color(rgb(0xcdd6f4))
+                                    .child(session.name.clone()),
+                            )
+                            .child(div().text_xs().text_color(rgb(0x6c7086)).child(target))
+                    })),
+            )
+    }
+
+    fn render_result(result: &ImportResult) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .p_2()
+            .bg(rgb(0x313244))
+            .rounded_md()
+            .child(div().text_sm().text_color(rgb(0xa6e3a1)).child(result.summary()))
+            .when(!result.skipped.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(rgb(0x6c7086))
+                        .child(format!("Already present: {}", result.skipped.join(", "))),
+                )
+            })
+            .children(result.errors.iter().map(|e| div().text_xs().text_color(rgb(0xf38ba8)).child(e.clone())))
+    }
+}
+
+impl Render for ImportDialog {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let can_import = self.candidates.iter().any(|c| c.selected);
+
+        div()
+            .flex()
+            .flex_col()
+            .size_full()
+            .bg(rgb(0x1e1e2e))
+            // Header
+            .child(
+                div()
+                    .px_4()
+                    .py_3()
+                    .border_b_1()
+                    .border_color(rgb(0x313244))
+                    .child(
+                        div()
+                            .text_lg()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(rgb(0xcdd6f4))
+                            .child("Import Sessions"),
+                    ),
+            )
+            // Form content
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .flex_1()
+                    .gap_4()
+                    .p_4()
+                    .when(!self.errors.is_empty(), |this| {
+                        this.child(
+                            div()
+                                .flex()
+                                .flex_col()
+                                .gap_1()
+                                .p_2()
+                                .bg(rgba(0xf38ba833))
+                                .rounded_md()
+                                .children(self.errors.iter().map(|e| {
+                                    div().text_sm().text_color(rgb(0xf38ba8)).child(e.clone())
+                                })),
+                        )
+                    })
+                    .child(
+                        div()
+                            .flex()
+                            .flex_col()
+                            .gap_2()
+                            .child(div().text_sm().text_color(rgb(0xcdd6f4)).child("Import from"))
+                            .child(self.render_source_selector(cx)),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .flex_col()
+                            .gap_1()
+                            .child(div().text_sm().text_color(rgb(0xcdd6f4)).child("File or folder"))
+                            .child(
+                                div()
+                                    .flex()
+                                    .gap_2()
+                                    .child(div().flex_1().child(self.path_field.clone()))
+                                    .child(
+                                        div()
+                                            .id("import-scan-btn")
+                                            .px_3()
+                                            .py_1()
+                                            .rounded_md()
+                                            .cursor_pointer()
+                                            .bg(rgb(0x313244))
+                                            .hover(|style| style.bg(rgb(0x45475a)))
+                                            .text_sm()
+                                            .text_color(rgb(0xcdd6f4))
+                                            .on_click(cx.listener(|this, _event, _window, cx| this.scan(cx)))
+                                            .child("Scan"),
+                                    ),
+                            )
+                            .child(div().text_xs().text_color(rgb(0x6c7086)).child(match self.source {
+                                ImportSource::Putty => "A .reg export of the PuTTY Sessions key, or ~/.putty/sessions",
+                                ImportSource::Termius => "A Termius JSON or CSV host export",
+                                ImportSource::Iterm2 => "Exported profiles JSON, or the DynamicProfiles folder",
+                                ImportSource::SshConfig => "An OpenSSH client config; Include files are read too",
+                            })),
+                    )
+                    .when(!self.candidates.is_empty(), |this| {
+                        this.child(
+                            div()
+                                .flex()
+                                .flex_col()
+                                .gap_1()
+                                .child(div().text_sm().text_color(rgb(0xcdd6f4)).child("Group"))
+                                .child(self.group_field.clone()),
+                        )
+                        .child(self.render_candidates(cx))
+                    })
+                    .when_some(self.result.as_ref(), |this, result| this.child(Self::render_result(result))),
+            )
+            // Footer with buttons
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_end()
+                    .gap_2()
+                    .px_4()
+                    .py_3()
+                    .border_t_1()
+                    .border_color(rgb(0x313244))
+                    .child(
+                        div()
+                            .id("cancel-btn")
+                            .px_4()
+                            .py_2()
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|style| style.bg(rgb(0x313244)))
+                            .on_click(cx.listener(|_this, _event, window, _cx| {
+                                window.remove_window();
+                            }))
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .text_color(rgb(0x6c7086))
+                                    .child(if self.result.is_some() { "Close" } else { "Cancel" }),
+                            ),
+                    )
+                    .when(can_import, |this| {
+                        this.child(
+                            div()
+                                .id("import-btn")
+                                .px_4()
+                                .py_2()
+                                .bg(rgb(0x89b4fa))
+                                .rounded_md()
+                                .cursor_pointer()
+                                .hover(|style| style.bg(rgb(0x74c7ec)))
+                                .on_click(cx.listener(|this, _event, _window, cx| {
+                                    this.handle_import(cx);
+                                }))
+                                .child(
+                                    div()
+                                        .text_sm()
+                                        .text_color(rgb(0x1e1e2e))
+                                        .font_weight(FontWeight::SEMIBOLD)
+                                        .child("Import"),
+                                ),
+                        )
+                    }),
+            )
+    }
+}
@@ -6,15 +6,22 @@ use tokio::sync::Mutex as TokioMutex;
 use uuid::Uuid;
 
 use crate::app::AppState;
+use crate::config::{AppConfig, WindowState};
 use crate::sftp::SftpBrowser;
-use crate::terminal::Terminal;
+use crate::terminal::{markdown_code_block, Terminal};
 
 use super::agent_panel::{AgentPanel, AgentPanelEvent};
+use super::broadcast_dialog::toggle_broadcast;
+use super::connections_dashboard::ConnectionsDashboard;
 use super::quit_confirm_dialog::QuitConfirmDialog;
 use super::session_tree::SessionTree;
 use super::sftp_panel::{SftpPanel, SftpPanelEvent};
+use super::pane_layout::Direction;
 use super::split_container::SplitContainer;
 use super::terminal_tabs::{TabContextMenuState, TabInfo, TerminalTabs};
+use super::text_viewer::TextViewer;
+
+actions!(redpill, [SplitHorizontal, SplitVertical, ClosePane]);
 
 /// Minimum session tree width in pixels
 const MIN_TREE_WIDTH: f32 = 150.0;
@@ -182,6 +189,18 @@ impl MainWindow {
         let has_other_tabs = tab_count > 1;
 
         let tabs_view = self.tabs_view.clone();
+        let (has_dropped_tabs, has_command_outputs, has_selection) = cx
+            .try_global::<AppState>()
+            .map(|state| {
+                let app = state.app.lock();
+                let (has_outputs, has_selection) = app.get_tab(tab_id).map_or((false, false), |tab| {
+                    let terminal = tab.terminal.lock();
+                    (terminal.has_command_outputs(), terminal.selected_text().is_some_and(|text| !text.is_empty()))
+                });
+                (!app.dropped_tabs().is_empty(), has_outputs, has_selection)
+            })
+            .unwrap_or((false, false, false));
+        let can_copy_markdown = has_selection || has_command_outputs;
 
         div()
             .absolute()
@@ -217,6 +236,166 @@ impl MainWindow {
                             .child("Close Tab"),
                     ),
             )
+            // Rename Tab
+            .child(
+                div()
+                    .id("ctx-rename-tab")
+                    .px_3()
+                    .py_1()
+                    .cursor_pointer()
+                    .hover(|s| s.bg(rgb(0x45475a)))
+                    .on_click({
+                        let tabs_view = tabs_view.clone();
+                        cx.listener(move |_this, _event, window, cx| {
+                            tabs_view.update(cx, |view, cx| {
+                                view.start_rename(tab_id, window, cx);
+                            });
+                        })
+                    })
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(rgb(0xcdd6f4))
+                            .child("Rename Tab..."),
+                    ),
+            )
+            // Separator
+            .child(
+                div()
+                    .h(px(1.0))
+                    .mx_2()
+                    .my_1()
+                    .bg(rgb(0x45475a)),
+            )
+            // View Scrollback
+            .child(
+                div()
+                    .id("ctx-view-scrollback")
+                    .px_3()
+                    .py_1()
+                    .cursor_pointer()
+                    .hover(|s| s.bg(rgb(0x45475a)))
+                    .on_click({
+                        let tabs_view = tabs_view.clone();
+                        cx.listener(move |_this, _event, _window, cx| {
+                            tabs_view.update(cx, |view, cx| view.dismiss_context_menu(cx));
+                            let scrollback = cx.try_global::<AppState>().and_then(|state| {
+                                let app = state.app.lock();
+                                let tab = app.get_tab(tab_id)?;
+                                let text = tab.terminal.lock().extract_last_lines(usize::MAX);
+                                Some((format!("{} — Scrollback", tab.display_title()), text))
+                            });
+                            if let Some((title, text)) = scrollback {
+                                TextViewer::open(title, text, cx);
+                            }
+                        })
+                    })
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(rgb(0xcdd6f4))
+                            .child("View Scrollback"),
+                    ),
+            )
+            // View Command Output (needs shell integration marks)
+            .child(
+                div()
+                    .id("ctx-view-command-output")
+                    .px_3()
+                    .py_1()
+                    .when(has_command_outputs, |this| {
+                        let tabs_view = tabs_view.clone();
+                        this.cursor_pointer()
+                            .hover(|s| s.bg(rgb(0x45475a)))
+                            .on_click(cx.listener(move |_this, _event, _window, cx| {
+                                tabs_view.update(cx, |view, cx| view.dismiss_context_menu(cx));
+                                let outputs = cx.try_global::<AppState>().and_then(|state| {
+                                    let app = state.app.lock();
+                                    let tab = app.get_tab(tab_id)?;
+                                    let outputs = tab.terminal.lock().command_outputs();
+                                    Some((format!("{} — Command Output", tab.display_title()), outputs))
+                                });
+                                let Some((title, outputs)) = outputs.filter(|(_, outputs)| !outputs.is_empty()) else {
+                                    return;
+                                };
+                                let last = outputs.len() - 1;
+                                let pages = outputs
+                                    .into_iter()
+                                    .map(|command| {
+                                        let heading = match command.exit_code {
+                                            Some(code) if code != 0 => format!("$ {}  (exit {})", command.command, code),
+                                            _ => format!("$ {}", command.command),
+                                        };
+                                        (heading.into(), command.output)
+                                    })
+                                    .collect();
+                                TextViewer::open_pages(title, pages, last, cx);
+                            }))
+                    })
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(if has_command_outputs { rgb(0xcdd6f4) } else { rgb(0x6c7086) })
+                            .child("View Command Output"),
+                    ),
+            )
+            // Copy as Markdown: the selection, or else the last command's output
+            .child(
+                div()
+                    .id("ctx-copy-markdown")
+                    .px_3()
+                    .py_1()
+                    .when(can_copy_markdown, |this| {
+                        let tabs_view = tabs_view.clone();
+                        this.cursor_pointer()
+                            .hover(|s| s.bg(rgb(0x45475a)))
+                            .on_click(cx.listener(move |_this, _event, _window, cx| {
+                                tabs_view.update(cx, |view, cx| view.dismiss_context_menu(cx));
+                                let markdown = cx.try_global::<AppState>().and_then(|state| {
+                                    let app = state.app.lock();
+                                    let tab = app.get_tab(tab_id)?;
+                                    let terminal = tab.terminal.lock();
+                                    if let Some(text) = terminal.selected_text().filter(|text| !text.is_empty()) {
+                                        return Some(markdown_code_block(&text, None));
+                                    }
+                                    let last = terminal.command_outputs().pop()?;
+                                    let header = format!("{} $ {}", tab.display_title(), last.command);
+                                    Some(markdown_code_block(&last.output, Some(&header)))
+                                });
+                                if let Some(markdown) = markdown {
+                                    cx.write_to_clipboard(ClipboardItem::new_string(markdown));
+                                }
+                            }))
+                    })
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(if can_copy_markdown { rgb(0xcdd6f4) } else { rgb(0x6c7086) })
+                            .child("Copy as Markdown"),
+                    ),
+            )
+            // Reconnect Dropped Sessions
+            .child(
+                div()
+                    .id("ctx-reconnect-dropped")
+                    .px_3()
+                    .py_1()
+                    .when(has_dropped_tabs, |this| {
+                        let tabs_view = tabs_view.clone();
+                        this.cursor_pointer()
+                            .hover(|s| s.bg(rgb(0x45475a)))
+                            .on_click(cx.listener(move |_this, _event, _window, cx| {
+                                tabs_view.update(cx, |view, cx| view.dismiss_context_menu(cx));
+                                crate::app::reconnect_dropped_sessions(cx);
+                            }))
+                    })
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(if has_dropped_tabs { rgb(0xcdd6f4) } else { rgb(0x6c7086) })
+                            .child("Reconnect Dropped"),
+                    ),
+            )
             // Separator
             .child(
                 div()
@@ -358,6 +537,9 @@ impl MainWindow {
     /// Split the active pane horizontally (left/right)
     fn split_horizontal(&mut self, cx: &mut Context<Self>) {
         if let Some(container) = self.active_split_container().cloned() {
+            if !container.read(cx).can_split() {
+                return;
+            }
             // Create a new local terminal for the split
             if let Some(new_terminal) = self.create_local_terminal(cx) {
                 container.update(cx, |split, cx| {
@@ -370,6 +552,9 @@ impl MainWindow {
     /// Split the active pane vertically (top/bottom)
     fn split_vertical(&mut self, cx: &mut Context<Self>) {
         if let Some(container) = self.active_split_container().cloned() {
+            if !container.read(cx).can_split() {
+                return;
+            }
             // Create a new local terminal for the split
             if let Some(new_terminal) = self.create_local_terminal(cx) {
                 container.update(cx, |split, cx| {
@@ -380,10 +565,14 @@ impl MainWindow {
     }
 
     /// Close the active pane (or tab if single pane)
-    fn close_active_pane(&mut self, cx: &mut Context<Self>) {
+    fn close_active_pane(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         if let Some(container) = self.active_split_container().cloned() {
             let closed = container.update(cx, |split, cx| {
-                split.close_active_pane(cx)
+                let closed = split.close_active_pane(cx);
+                if closed {
+                    split.focus_active_pane(window, cx);
+                }
+                closed
             });
             if !closed {
                 // Single pane - close the tab instead
@@ -416,17 +605,21 @@ impl MainWindow {
         }
     }
 
+    /// Focus the pane beside the active one in the active split container
+    fn focus_pane_toward(&mut self, direction: Direction, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(container) = self.active_split_container().cloned() {
+            container.update(cx, |split, cx| {
+                split.focus_pane_toward(direction, window, cx);
+            });
+        }
+    }
+
     /// Create a new local terminal
     fn create_local_terminal(&self, cx: &Context<Self>) -> Option<Arc<Mutex<Terminal>>> {
-        let scrollback = cx
+        let config = cx
             .try_global::<AppState>()
-            .map(|state| state.app.lock().config.scrollback_lines)
-            .unwrap_or(10000);
-
-        let config = crate::terminal::TerminalConfig {
-            scrollback_lines: scrollback,
-            ..Default::default()
-        };
+            .map(|state| state.app.lock().terminal_config())
+            .unwrap_or_default();
 
         match Terminal::new_local(config) {
             Ok(terminal) => Some(Arc::new(Mutex::new(terminal))),
@@ -442,7 +635,7 @@ impl MainWindow {
         let keystroke = &event.keystroke;
 
         // Split horizontal: Cmd+D (Mac) or Ctrl+Shift+D
-        if (keystroke.modifiers.platform && keystroke.key == "d")
+        if (keystroke.modifiers.platform && !keystroke.modifiers.shift && keystroke.key == "d")
             || (keystroke.modifiers.control && keystroke.modifiers.shift && keystroke.key == "d")
         {
             self.split_horizontal(cx);
@@ -459,6 +652,24 @@ impl MainWindow {
             return;
         }
 
+        // Close pane: Cmd+Shift+W (Mac) or Ctrl+Shift+W - closes the tab
+        // when it has a single pane
+        if keystroke.modifiers.shift
+            && (keystroke.modifiers.platform || keystroke.modifiers.control)
+            && keystroke.key == "w"
+        {
+            self.close_active_pane(window, cx);
+            cx.stop_propagation();
+            return;
+        }
+
+        // Focus the pane beside this one: Cmd+Alt+Arrow (Mac) or Ctrl+Alt+Arrow
+        if let Some(direction) = Direction::from_keystroke(keystroke) {
+            self.focus_pane_toward(direction, window, cx);
+            cx.stop_propagation();
+            return;
+        }
+
         // Focus next pane: Cmd+] or Ctrl+]
         if (keystroke.modifiers.platform || keystroke.modifiers.control) && keystroke.key == "]" {
             self.focus_next_pane(window, cx);
@@ -473,6 +684,16 @@ impl MainWindow {
             return;
         }
 
+        // Toggle broadcast input: Cmd+Shift+I (Mac) or Ctrl+Shift+I
+        if keystroke.modifiers.shift
+            && (keystroke.modifiers.platform || keystroke.modifiers.control)
+            && keystroke.key == "i"
+        {
+            toggle_broadcast(cx);
+            cx.stop_propagation();
+            return;
+        }
+
         // Toggle SFTP panel: Cmd+Shift+B (Mac) or Ctrl+Shift+B
         if keystroke.modifiers.shift
             && (keystroke.modifiers.platform || keystroke.modifiers.control)
@@ -628,6 +849,15 @@ impl Render for MainWindow {
         // Get tab context menu state
         let tab_context_menu = self.tabs_view.read(cx).context_menu_state();
 
+        // Current theme name (for the status bar) and any transient notice
+        let (theme_name, notice) = cx
+            .try_global::<AppState>()
+            .map(|state| {
+                let app = state.app.lock();
+                (app.config.appearance.theme.clone(), app.notice().map(str::to_string))
+            })
+            .unwrap_or_default();
+
         // Get window width for agent panel resize calculation
         let window_bounds = window.bounds();
         let window_width: f32 = window_bounds.size.width.into();
@@ -642,6 +872,10 @@ impl Render for MainWindow {
             .bg(rgb(0x1e1e2e))
             // Handle split keyboard shortcuts
             .on_key_down(cx.listener(Self::handle_key_input))
+            // Split actions from the View menu
+            .on_action(cx.listener(|this, _: &SplitHorizontal, _window, cx| this.split_horizontal(cx)))
+            .on_action(cx.listener(|this, _: &SplitVertical, _window, cx| this.split_vertical(cx)))
+            .on_action(cx.listener(|this, _: &ClosePane, window, cx| this.close_active_pane(window, cx)))
             // Window-level mouse move handler for resize dragging
             .on_mouse_move(cx.listener(move |this, event: &MouseMoveEvent, _window, cx| {
                 if this.is_resizing {
@@ -867,16 +1101,72 @@ impl Render for MainWindow {
                     )
                     .child(
                         div()
-                            .text_xs()
-                            .text_color(rgb(0x6c7086))
-                            .child(format!(
-                                "{} tab{}",
-                                self.split_containers.len(),
-                                if self.split_containers.len() == 1 { "" } else { "s" }
-                            )),
+                            .flex()
+                            .items_center()
+                            .gap_3()
+                            .child(
+                                div()
+                                    .id("status-connections")
+                                    .text_xs()
+                                    .text_color(rgb(0x6c7086))
+                                    .cursor_pointer()
+                                    .hover(|s| s.text_color(rgb(0xcdd6f4)))
+                                    .on_click(|_event, _window, cx| {
+                                        cx.defer(ConnectionsDashboard::open);
+                                    })
+                                    .child("Connections"),
+                            )
+                            .child(
+                                div()
+                                    .id("status-theme")
+                                    .text_xs()
+                                    .text_color(rgb(0x6c7086))
+                                    .cursor_pointer()
+                                    .hover(|s| s.text_color(rgb(0xcdd6f4)))
+                                    .on_click(|_event, _window, cx| {
+                                        crate::app::cycle_theme(cx);
+                                    })
+                                    .child(format!("Theme: {}", theme_name)),
+                            )
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(rgb(0x6c7086))
+                                    .child(format!(
+                                        "{} tab{}",
+                                        self.split_containers.len(),
+                                        if self.split_containers.len() == 1 { "" } else { "s" }
+                                    )),
+                            ),
                     ),
             );
 
+        // Transient notice (e.g. theme name after switching)
+        if let Some(notice) = notice {
+            root = root.child(
+                div()
+                    .absolute()
+                    .top(px(48.0))
+                    .left_0()
+                    .right_0()
+                    .flex()
+                    .justify_center()
+                    .child(
+                        div()
+                            .px_4()
+                            .py_2()
+                            .bg(rgb(0x313244))
+                            .border_1()
+                            .border_color(rgb(0x45475a))
+                            .rounded_md()
+                            .shadow_lg()
+                            .text_sm()
+                            .text_color(rgb(0xcdd6f4))
+                            .child(notice),
+                    ),
+            );
+        }
+
         // Add tab context menu if open (rendered at window level to avoid clipping)
         if let Some(menu) = tab_context_menu {
             let tabs_view = self.tabs_view.clone();
@@ -912,18 +1202,71 @@ impl Render for MainWindow {
 }
 
 /// Create the main window
-pub fn main_window(_window: &mut Window, cx: &mut App) -> Entity<MainWindow> {
-    cx.new(|cx| MainWindow::new(cx))
+pub fn main_window(window: &mut Window, cx: &mut App) -> Entity<MainWindow> {
+    cx.new(|cx| {
+        // Keep the saved geometry current so it can be written out at quit
+        cx.observe_window_bounds(window, |_this, window, cx| {
+            if let Some(state) = cx.try_global::<AppState>() {
+                state.app.lock().config.window = window_state(window.window_bounds());
+            }
+        })
+        .detach();
+        MainWindow::new(cx)
+    })
+}
+
+/// Where to put the window: where it was last time with `restore_sessions`,
+/// otherwise centered at the default size
+fn initial_window_bounds(config: &AppConfig, cx: &App) -> WindowBounds {
+    if !config.restore_sessions {
+        return WindowBounds::Windowed(Bounds::centered(None, size(px(1200.0), px(800.0)), cx));
+    }
+
+    let saved = &config.window;
+    let window_size = size(px(saved.width as f32), px(saved.height as f32));
+    let bounds = match (saved.x, saved.y) {
+        (Some(x), Some(y)) => Bounds::new(point(px(x as f32), px(y as f32)), window_size),
+        _ => Bounds::centered(None, window_size, cx),
+    };
+    if saved.maximized {
+        WindowBounds::Maximized(bounds)
+    } else {
+        WindowBounds::Windowed(bounds)
+    }
+}
+
+/// Window geometry to save for the next launch
+fn window_state(bounds: WindowBounds) -> WindowState {
+    let (restore_bounds, maximized) = match bounds {
+        WindowBounds::Windowed(bounds) | WindowBounds::Fullscreen(bounds) => (bounds, false),
+        WindowBounds::Maximized(bounds) => (bounds, true),
+    };
+    WindowState {
+        width: f32::from(restore_bounds.size.width).round() as u32,
+        height: f32::from(restore_bounds.size.height).round() as u32,
+        x: Some(f32::from(restore_bounds.origin.x).round() as i32),
+        y: Some(f32::from(restore_bounds.origin.y).round() as i32),
+        maximized,
+    }
+}
+
+/// Write the workspace out for `restore_sessions`
+fn save_workspace(cx: &mut App) {
+    if let Some(state) = cx.try_global::<AppState>() {
+        state.app.lock().save_workspace();
+    }
 }
 
 /// Open the main application window
 pub fn open_main_window(cx: &mut App) -> WindowHandle<MainWindow> {
+    // Initialize app state
+    let app_state = AppState::new();
+    let window_bounds = initial_window_bounds(&app_state.app.lock().config, cx);
+    cx.set_global(app_state);
+    crate::app::watch_config(cx);
+
     let window_options = WindowOptions {
-        window_bounds: Some(WindowBounds::Windowed(Bounds::centered(
-            None,
-            size(px(1200.0), px(800.0)),
-            cx,
-        ))),
+        window_bounds: Some(window_bounds),
         titlebar: Some(TitlebarOptions {
             title: Some("RedPill".into()),
             appears_transparent: false,
@@ -932,11 +1275,22 @@ pub fn open_main_window(cx: &mut App) -> WindowHandle<MainWindow> {
         ..Default::default()
     };
 
-    cx.open_window(window_options, |window, cx| {
-        // Initialize app state
-        let app_state = AppState::new();
-        cx.set_global(app_state);
+    // Reopen last time's tabs
+    if let Some(state) = cx.try_global::<AppState>() {
+        let mut app = state.app.lock();
+        if app.config.restore_sessions {
+            app.restore_workspace(&state.tokio_runtime);
+        }
+    }
 
+    // Save the workspace however the app quits
+    cx.on_app_quit(|cx| {
+        save_workspace(cx);
+        async {}
+    })
+    .detach();
+
+    cx.open_window(window_options, |window, cx| {
         // Register window close handler to check for active SSH connections
         window.on_window_should_close(cx, |_window, cx| {
             // Check for active SSH connections
@@ -951,6 +1305,7 @@ pub fn open_main_window(cx: &mut App) -> WindowHandle<MainWindow> {
                 QuitConfirmDialog::open(ssh_count, cx);
                 false // Don't close the window yet
             } else {
+                save_workspace(cx);
                 true // Allow the window to close
             }
         });
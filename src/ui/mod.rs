@@ -1,8 +1,15 @@
 pub mod agent_panel;
+pub mod broadcast_dialog;
+pub mod color_scheme_editor;
+pub mod connections_dashboard;
 pub mod delete_confirm_dialog;
 pub mod group_dialog;
+pub mod host_key_dialog;
+pub mod import_dialog;
 pub mod main_window;
+pub mod pane_layout;
 pub mod quit_confirm_dialog;
+pub mod run_command_dialog;
 pub mod search_bar;
 pub mod session_dialog;
 pub mod session_tree;
@@ -12,18 +19,29 @@ pub mod ssm_session_dialog;
 pub mod terminal_tabs;
 pub mod terminal_view;
 pub mod text_field;
+pub mod text_viewer;
+pub mod zmodem_dialog;
 
 pub use agent_panel::{agent_panel, AgentPanel};
+pub use broadcast_dialog::{toggle_broadcast, BroadcastConfirmDialog};
+pub use color_scheme_editor::ColorSchemeEditor;
+pub use connections_dashboard::ConnectionsDashboard;
 pub use delete_confirm_dialog::{DeleteConfirmDialog, DeleteTarget};
 pub use group_dialog::{group_dialog, edit_group_dialog, GroupDialog, GroupDialogResult};
+pub use host_key_dialog::HostKeyDialog;
+pub use import_dialog::ImportDialog;
 pub use quit_confirm_dialog::QuitConfirmDialog;
-pub use main_window::{main_window, open_main_window, MainWindow};
+pub use run_command_dialog::RunCommandDialog;
+pub use main_window::{main_window, open_main_window, ClosePane, MainWindow, SplitHorizontal, SplitVertical};
 pub use search_bar::{SearchBar, SearchBarEvent};
 pub use session_dialog::{session_dialog, edit_session_dialog, SessionDialog, SessionDialogResult};
 pub use session_tree::{session_tree, SessionTree, SessionTreeAction};
 pub use sftp_panel::{SftpPanel, SftpPanelEvent};
+pub use pane_layout::{Direction, PaneId, PaneNode};
 pub use split_container::{SplitContainer, SplitContainerEvent, SplitOrientation};
 pub use ssm_session_dialog::{ssm_session_dialog, edit_ssm_session_dialog, SsmSessionDialog, SsmSessionDialogResult};
 pub use terminal_tabs::{terminal_tabs, TabAction, TabContextMenuState, TabInfo, TerminalTabs};
 pub use terminal_view::{terminal_view, TerminalView};
-pub use text_field::{text_field, text_field_with_content, TextField, TextFieldEvent};
+pub use text_field::{text_field, text_field_with_content, Suggestion, TextField, TextFieldEvent};
+pub use text_viewer::TextViewer;
+pub use zmodem_dialog::ZmodemDialog;
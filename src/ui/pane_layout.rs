@@ -0,0 +1,262 @@
+//! Arrangement of the panes in a tab
+//!
+//! A tab's panes form a binary tree: every split divides its area between
+//! two children, side by side or stacked, and the leaves are panes. Splitting
+//! a pane replaces its leaf with a split holding the old and the new pane;
+//! closing one replaces its parent split with the sibling.
+
+use gpui::Keystroke;
+
+use super::split_container::SplitOrientation;
+
+/// Identifies a pane within its tab
+pub type PaneId = usize;
+
+/// Identifies a split within its tab, for resizing
+pub type SplitId = usize;
+
+/// Smallest share of a split either side can be dragged down to
+const MIN_RATIO: f32 = 0.1;
+
+/// A direction to move focus between panes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Direction {
+    /// Direction for a pane focus key: Cmd+Alt+Arrow (macOS) or Ctrl+Alt+Arrow
+    pub fn from_keystroke(keystroke: &Keystroke) -> Option<Self> {
+        let modifiers = &keystroke.modifiers;
+        if !modifiers.alt || !(modifiers.platform || modifiers.control) || modifiers.shift {
+            return None;
+        }
+        match keystroke.key.as_str() {
+            "left" => Some(Direction::Left),
+            "right" => Some(Direction::Right),
+            "up" => Some(Direction::Up),
+            "down" => Some(Direction::Down),
+            _ => None,
+        }
+    }
+}
+
+/// Area of a pane as fractions of the tab's area
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PaneRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// A node in the pane tree
+#[derive(Clone, Debug, PartialEq)]
+pub enum PaneNode {
+    Pane(PaneId),
+    Split {
+        id: SplitId,
+        orientation: SplitOrientation,
+        /// Share of the area taken by `first` (0.0 to 1.0)
+        ratio: f32,
+        first: Box<PaneNode>,
+        second: Box<PaneNode>,
+    },
+}
+
+impl PaneNode {
+    /// Panes in reading order: left before right, top before bottom
+    pub fn panes(&self) -> Vec<PaneId> {
+        match self {
+            PaneNode::Pane(id) => vec![*id],
+            PaneNode::Split { first, second, .. } => {
+                let mut panes = first.panes();
+                panes.extend(second.panes());
+                panes
+            }
+        }
+    }
+
+    /// Split `target` in two, putting `new_pane` after it
+    ///
+    /// Returns false if `target` isn't in the tree.
+    pub fn split(&mut self, target: PaneId, new_pane: PaneId, orientation: SplitOrientation, id: SplitId) -> bool {
+        match self {
+            PaneNode::Pane(pane) if *pane == target => {
+                *self = PaneNode::Split {
+                    id,
+                    orientation,
+                    ratio: 0.5,
+                    first: Box::new(PaneNode::Pane(target)),
+                    second: Box::new(PaneNode::Pane(new_pane)),
+                };
+                true
+            }
+            PaneNode::Pane(_) => false,
+            PaneNode::Split { first, second, .. } => {
+                first.split(target, new_pane, orientation, id) || second.split(target, new_pane, orientation, id)
+            }
+        }
+    }
+
+    /// Remove `target`, letting its sibling take the space
+    ///
+    /// Returns the first pane of that sibling, which is the natural one to
+    /// focus next, or `None` if `target` is missing or the only pane.
+    pub fn remove(&mut self, target: PaneId) -> Option<PaneId> {
+        let PaneNode::Split { first, second, .. } = self else {
+            return None;
+        };
+        let sibling = if **first == PaneNode::Pane(target) {
+            std::mem::replace(&mut **second, PaneNode::Pane(target))
+        } else if **second == PaneNode::Pane(target) {
+            std::mem::replace(&mut **first, PaneNode::Pane(target))
+        } else {
+            return first.remove(target).or_else(|| second.remove(target));
+        };
+        let focus = sibling.panes()[0];
+        *self = sibling;
+        Some(focus)
+    }
+
+    /// Orientation of the split with this ID
+    pub fn split_orientation(&self, split: SplitId) -> Option<SplitOrientation> {
+        match self {
+            PaneNode::Pane(_) => None,
+            PaneNode::Split { id, orientation, first, second, .. } => {
+                if *id == split {
+                    Some(*orientation)
+                } else {
+                    first.split_orientation(split).or_else(|| second.split_orientation(split))
+                }
+            }
+        }
+    }
+
+    /// Move the divider of a split, keeping both sides usable
+    pub fn set_ratio(&mut self, split: SplitId, new_ratio: f32) {
+        if let PaneNode::Split { id, ratio, first, second, .. } = self {
+            if *id == split {
+                *ratio = new_ratio.clamp(MIN_RATIO, 1.0 - MIN_RATIO);
+            } else {
+                first.set_ratio(split, new_ratio);
+                second.set_ratio(split, new_ratio);
+            }
+        }
+    }
+
+    /// Where every pane sits, as fractions of the whole area
+    pub fn rects(&self) -> Vec<(PaneId, PaneRect)> {
+        let mut rects = Vec::new();
+        self.collect_rects(PaneRect { x: 0.0, y: 0.0, width: 1.0, height: 1.0 }, &mut rects);
+        rects
+    }
+
+    fn collect_rects(&self, area: PaneRect, rects: &mut Vec<(PaneId, PaneRect)>) {
+        match self {
+            PaneNode::Pane(id) => rects.push((*id, area)),
+            PaneNode::Split { orientation, ratio, first, second, .. } => {
+                let (a, b) = match orientation {
+                    SplitOrientation::Horizontal => {
+                        let width = area.width * ratio;
+                        (
+                            PaneRect { width, ..area },
+                            PaneRect { x: area.x + width, width: area.width - width, ..area },
+                        )
+                    }
+                    SplitOrientation::Vertical => {
+                        let height = area.height * ratio;
+                        (
+                            PaneRect { height, ..area },
+                            PaneRect { y: area.y + height, height: area.height - height, ..area },
+                        )
+                    }
+                };
+                first.collect_rects(a, rects);
+                second.collect_rects(b, rects);
+            }
+        }
+    }
+
+    /// The pane next to `from` in a direction, if any
+    ///
+    /// Of the panes beyond that edge of `from` that line up with it, the
+    /// nearest wins, then the one sharing the longest stretch of edge.
+    pub fn neighbor(&self, from: PaneId, direction: Direction) -> Option<PaneId> {
+        const EPSILON: f32 = 1e-4;
+        let rects = self.rects();
+        let (_, origin) = rects.iter().find(|(id, _)| *id == from)?;
+
+        let overlap = |a0: f32, a1: f32, b0: f32, b1: f32| a1.min(b1) - a0.max(b0);
+        rects
+            .iter()
+            .filter(|(id, _)| *id != from)
+            .filter_map(|(id, rect)| {
+                let (gap, shared) = match direction {
+                    Direction::Left => (origin.x - (rect.x + rect.width), overlap(origin.y, origin.y + origin.height, rect.y, rect.y + rect.height)),
+                    Direction::Right => (rect.x - (origin.x + origin.width), overlap(origin.y, origin.y + origin.height, rect.y, rect.y + rect.height)),
+                    Direction::Up => (origin.y - (rect.y + rect.height), overlap(origin.x, origin.x + origin.width, rect.x, rect.x + rect.width)),
+                    Direction::Down => (rect.y - (origin.y + origin.height), overlap(origin.x, origin.x + origin.width, rect.x, rect.x + rect.width)),
+                };
+                (gap > -EPSILON && shared > EPSILON).then_some((*id, gap, shared))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1).then(b.2.total_cmp(&a.2)))
+            .map(|(id, _, _)| id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 1 on the left; 2 over 3 on the right
+    fn three_panes() -> PaneNode {
+        let mut layout = PaneNode::Pane(1);
+        assert!(layout.split(1, 2, SplitOrientation::Horizontal, 10));
+        assert!(layout.split(2, 3, SplitOrientation::Vertical, 11));
+        layout
+    }
+
+    #[test]
+    fn test_split_and_remove() {
+        let mut layout = three_panes();
+        assert_eq!(layout.panes(), vec![1, 2, 3]);
+        assert!(!layout.split(9, 4, SplitOrientation::Vertical, 12));
+
+        // Closing 2 lets 3 take the whole right side
+        assert_eq!(layout.remove(2), Some(3));
+        assert_eq!(layout.panes(), vec![1, 3]);
+        assert_eq!(layout.split_orientation(10), Some(SplitOrientation::Horizontal));
+        assert_eq!(layout.split_orientation(11), None);
+
+        assert_eq!(layout.remove(1), Some(3));
+        assert_eq!(layout, PaneNode::Pane(3));
+        assert_eq!(layout.remove(3), None);
+    }
+
+    #[test]
+    fn test_rects_and_ratio() {
+        let mut layout = three_panes();
+        layout.set_ratio(10, 0.25);
+        let rects = layout.rects();
+        assert_eq!(rects[0], (1, PaneRect { x: 0.0, y: 0.0, width: 0.25, height: 1.0 }));
+        assert_eq!(rects[2], (3, PaneRect { x: 0.25, y: 0.5, width: 0.75, height: 0.5 }));
+
+        layout.set_ratio(11, 0.99);
+        assert!(matches!(layout, PaneNode::Split { ref second, .. } if matches!(**second, PaneNode::Split { ratio, .. } if ratio == 0.9)));
+    }
+
+    #[test]
+    fn test_neighbor() {
+        let layout = three_panes();
+        assert_eq!(layout.neighbor(1, Direction::Right), Some(2));
+        assert_eq!(layout.neighbor(1, Direction::Left), None);
+        assert_eq!(layout.neighbor(2, Direction::Down), Some(3));
+        assert_eq!(layout.neighbor(3, Direction::Up), Some(2));
+        assert_eq!(layout.neighbor(3, Direction::Left), Some(1));
+        assert_eq!(layout.neighbor(2, Direction::Up), None);
+    }
+}
@@ -0,0 +1,246 @@
+use gpui::*;
+use gpui::prelude::*;
+use uuid::Uuid;
+
+use crate::app::AppState;
+use crate::terminal::exec_results_table;
+use super::text_field::{TextField, TextFieldEvent};
+use super::text_viewer::TextViewer;
+
+/// Dialog that runs one command on SSH sessions without opening tabs
+///
+/// The command runs without a PTY on every target at once. The output opens
+/// in a text viewer: a single host's output directly, or for several hosts a
+/// results table followed by one page per host.
+pub struct RunCommandDialog {
+    /// Target sessions as (id, name)
+    targets: Vec<(Uuid, String)>,
+    command_field: Entity<TextField>,
+    running: bool,
+    /// Results were shown; the window closes on the next render
+    finished: bool,
+    error: Option<String>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl RunCommandDialog {
+    pub fn new(targets: Vec<(Uuid, String)>, cx: &mut Context<Self>) -> Self {
+        let command_field = cx.new(|cx| TextField::new(cx, "uptime"));
+        let subscription = cx.subscribe(&command_field, |this: &mut Self, _field, event, cx| {
+            if let TextFieldEvent::Submit = event {
+                this.run(cx);
+            }
+        });
+
+        Self {
+            targets,
+            command_field,
+            running: false,
+            finished: false,
+            error: None,
+            _subscriptions: vec![subscription],
+        }
+    }
+
+    /// Open as a modal window for `targets`, given as (session id, name)
+    pub fn open(targets: Vec<(Uuid, String)>, cx: &mut App) {
+        let window_options = WindowOptions {
+            window_bounds: Some(WindowBounds::Windowed(Bounds::centered(
+                None,
+                size(px(460.0), px(260.0)),
+                cx,
+            ))),
+            titlebar: Some(TitlebarOptions {
+                title: Some("Run Command".into()),
+                appears_transparent: false,
+                ..Default::default()
+            }),
+            kind: WindowKind::Normal,
+            ..Default::default()
+        };
+
+        let _ = cx.open_window(window_options, |window, cx| {
+            let dialog = cx.new(|cx| RunCommandDialog::new(targets, cx));
+            let field_focus = dialog.read(cx).command_field.read(cx).focus_handle().clone();
+            window.focus(&field_focus, cx);
+            dialog
+        });
+    }
+
+    /// Start the command on every target
+    fn run(&mut self, cx: &mut Context<Self>) {
+        let command = self.command_field.read(cx).content().trim().to_string();
+        if self.running || command.is_empty() {
+            return;
+        }
+        let Some(app_state) = cx.try_global::<AppState>() else {
+            return;
+        };
+        let ids: Vec<Uuid> = self.targets.iter().map(|(id, _)| *id).collect();
+        let results = app_state
+            .app
+            .lock()
+            .exec_on_sessions(&ids, &command, &app_state.tokio_runtime);
+        self.running = true;
+        self.error = None;
+        cx.notify();
+
+        cx.spawn(async move |entity, cx| {
+            let results = results.await;
+
+            entity.update(cx, |this, cx| {
+                this.running = false;
+                if results.is_empty() {
+                    this.error = Some("None of the selected sessions are SSH sessions".to_string());
+                    cx.notify();
+                    return;
+                }
+
+                let title = format!("$ {}", command);
+                if let [(name, result)] = results.as_slice() {
+                    let text = match result {
+                        Ok(output) => output.text(),
+                        Err(e) => e.clone(),
+                    };
+                    TextViewer::open(format!("{} — {}", name, title), text, cx);
+                } else {
+                    let mut pages = vec![(SharedString::from("Results"), exec_results_table(&results))];
+                    pages.extend(results.into_iter().map(|(name, result)| {
+                        let (heading, text) = match result {
+                            Ok(output) => match output.exit_status {
+                                Some(code) if code != 0 => (format!("{}  (exit {})", name, code), output.text()),
+                                _ => (name, output.text()),
+                            },
+                            Err(e) => (format!("{}  (failed)", name), e),
+                        };
+                        (heading.into(), text)
+                    }));
+                    TextViewer::open_pages(title, pages, 0, cx);
+                }
+                this.finished = true;
+                cx.notify();
+            }).ok();
+        }).detach();
+    }
+}
+
+impl Render for RunCommandDialog {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if self.finished {
+            window.remove_window();
+        }
+
+        let names: Vec<&str> = self.targets.iter().map(|(_, name)| name.as_str()).collect();
+        let target_text = if names.len() <= 3 {
+            format!("On {}", names.join(", "))
+        } else {
+            format!("On {}, {} and {} more", names[0], names[1], names.len() - 2)
+        };
+        let running = self.running;
+
+        div()
+            .flex()
+            .flex_col()
+            .size_full()
+            .bg(rgb(0x1e1e2e))
+            // Header
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .px_4()
+                    .py_3()
+                    .border_b_1()
+                    .border_color(rgb(0x313244))
+                    .child(
+                        div()
+                            .text_lg()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(rgb(0xcdd6f4))
+                            .child("Run Command"),
+                    ),
+            )
+            // Content
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .flex_1()
+                    .gap_2()
+                    .p_4()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(rgb(0x6c7086))
+                            .child(target_text),
+                    )
+                    .child(self.command_field.clone())
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(rgb(0x6c7086))
+                            .child("Runs without a terminal; interactive commands won't work."),
+                    )
+                    .when_some(self.error.clone(), |this, error| {
+                        this.child(
+                            div()
+                                .text_sm()
+                                .text_color(rgb(0xf38ba8))
+                                .child(error),
+                        )
+                    }),
+            )
+            // Footer with buttons
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_end()
+                    .gap_2()
+                    .px_4()
+                    .py_3()
+                    .border_t_1()
+                    .border_color(rgb(0x313244))
+                    .child(
+                        div()
+                            .id("cancel-btn")
+                            .px_4()
+                            .py_2()
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|style| style.bg(rgb(0x313244)))
+                            .on_click(cx.listener(|_this, _event, window, _cx| {
+                                window.remove_window();
+                            }))
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .text_color(rgb(0x6c7086))
+                                    .child("Cancel"),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .id("run-btn")
+                            .px_4()
+                            .py_2()
+                            .bg(rgb(if running { 0x45475a } else { 0x89b4fa }))
+                            .rounded_md()
+                            .when(!running, |this| {
+                                this.cursor_pointer()
+                                    .hover(|style| style.bg(rgb(0x74c7ec)))
+                                    .on_click(cx.listener(|this, _event, _window, cx| {
+                                        this.run(cx);
+                                    }))
+                            })
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .text_color(rgb(0x1e1e2e))
+                                    .font_weight(FontWeight::SEMIBOLD)
+                                    .child(if running { "Running…" } else { "Run" }),
+                            ),
+                    ),
+            )
+    }
+}
@@ -1,11 +1,18 @@
 use gpui::*;
 use gpui::prelude::*;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use uuid::Uuid;
 
 use crate::app::AppState;
-use crate::session::{AuthMethod, SshSession, SsmSession};
-use super::text_field::TextField;
+use crate::config::ColorScheme;
+use crate::session::host_suggestions::{collect_host_suggestions, rank_suggestions};
+use crate::session::{
+    AuthMethod, ForwardDirection, GroupDefaults, HostSuggestion, JumpHostConfig, K8sSession, KeepAliveCommand, LocalSession, NewlineMode, PortForward, SerialFlowControl, SerialParity, SerialSession, SshSession,
+    SsmPortForward, SsmSession, TelnetSession,
+};
+use super::color_scheme_editor::ColorSchemeEditor;
+use super::text_field::{Suggestion, TextField, TextFieldEvent};
 
 /// Result of the session dialog
 #[derive(Clone, Debug)]
@@ -20,6 +27,10 @@ pub enum SessionDialogResult {
 pub enum SessionDialogEvent {
     Saved(SshSession),
     SavedSsm(SsmSession),
+    SavedSerial(SerialSession),
+    SavedTelnet(TelnetSession),
+    SavedK8s(K8sSession),
+    SavedLocal(LocalSession),
     Canceled,
 }
 
@@ -31,6 +42,12 @@ enum SessionType {
     #[default]
     Ssh,
     Ssm,
+    Serial,
+    Telnet,
+    /// Only reachable by editing; new K8s sessions come from the cluster tree
+    K8s,
+    /// Only reachable by editing
+    Local,
 }
 
 #[derive(Clone, Copy, PartialEq, Default, Debug)]
@@ -39,15 +56,114 @@ enum AuthType {
     Password,
     PrivateKey,
     Agent,
+    KeyboardInteractive,
 }
 
-/// Session dialog for creating/editing SSH and SSM sessions
+/// Placeholder for the additional keys field
+const EXTRA_KEYS_PLACEHOLDER: &str = "~/.ssh/id_ed25519, ~/.ssh/deploy (optional)";
+
+/// Most host suggestions shown at once
+const MAX_HOST_SUGGESTIONS: usize = 6;
+
+/// Placeholder for the keep-alive command field
+const KEEP_ALIVE_PLACEHOLDER: &str = "e.g. \\x20\\b (optional)";
+
+/// Placeholder for the pre-connect hook field
+const PRE_CONNECT_PLACEHOLDER: &str = "e.g. aws sso login (optional)";
+
+/// Placeholder for the post-disconnect hook field
+const POST_DISCONNECT_PLACEHOLDER: &str = "e.g. notify-send {name} {status} (optional)";
+
+/// Placeholder for the jump host field
+const JUMP_HOST_PLACEHOLDER: &str = "user@bastion:22 (optional)";
+
+/// Placeholder for the jump host key field
+const JUMP_KEY_PLACEHOLDER: &str = "~/.ssh/id_ed25519 (empty = agent)";
+
+/// Placeholders for the port forward fields
+const LOCAL_FORWARDS_PLACEHOLDER: &str = "local: 8080:localhost:80, ... (optional)";
+const REMOTE_FORWARDS_PLACEHOLDER: &str = "remote: 9000:localhost:3000, ... (optional)";
+
+/// Placeholder for the serial device field
+#[cfg(windows)]
+const DEVICE_PLACEHOLDER: &str = "COM3";
+#[cfg(not(windows))]
+const DEVICE_PLACEHOLDER: &str = "/dev/ttyUSB0";
+
+/// Placeholders for the K8s fields
+const K8S_CONTEXT_PLACEHOLDER: &str = "context (empty = current)";
+const K8S_CONTAINER_PLACEHOLDER: &str = "container (empty = first)";
+const K8S_COMMAND_PLACEHOLDER: &str = "e.g. /bin/zsh (empty = shell)";
+
+/// Placeholders for the local session fields
+#[cfg(windows)]
+const SHELL_PLACEHOLDER: &str = "powershell.exe (empty = default shell)";
+#[cfg(not(windows))]
+const SHELL_PLACEHOLDER: &str = "/bin/zsh (empty = default shell)";
+const WORKING_DIR_PLACEHOLDER: &str = "~/projects (empty = home)";
+
+/// Serial ports present on this machine, as (device, description)
+#[cfg(feature = "serial")]
+fn detect_serial_ports() -> Vec<(String, Option<String>)> {
+    crate::terminal::serial_backend::available_ports()
+        .into_iter()
+        .map(|port| (port.device, port.description))
+        .collect()
+}
+
+#[cfg(not(feature = "serial"))]
+fn detect_serial_ports() -> Vec<(String, Option<String>)> {
+    Vec::new()
+}
+
+/// Comma-separated forward specs for one direction, for editing
+fn forward_specs(forwards: &[PortForward], direction: ForwardDirection) -> String {
+    forwards
+        .iter()
+        .filter(|forward| forward.direction == direction)
+        .map(PortForward::spec)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Parse comma-separated forward specs, or `None` if any of them is invalid
+fn parse_forwards(direction: ForwardDirection, text: &str) -> Option<Vec<PortForward>> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|spec| !spec.is_empty())
+        .map(|spec| PortForward::parse(direction, spec))
+        .collect()
+}
+
+/// Parse the SSM port forward fields: `Some(None)` if both are empty, `None`
+/// if either isn't a port
+fn parse_ssm_forward(local: &str, remote: &str) -> Option<Option<SsmPortForward>> {
+    let (local, remote) = (local.trim(), remote.trim());
+    if local.is_empty() && remote.is_empty() {
+        return Some(None);
+    }
+    let port = |text: &str| text.parse::<u16>().ok().filter(|&port| port > 0);
+    Some(Some(SsmPortForward {
+        local_port: port(local)?,
+        remote_port: port(remote)?,
+    }))
+}
+
+/// Some(trimmed text), or None if it's empty
+fn non_empty(text: &str) -> Option<String> {
+    Some(text.trim().to_string()).filter(|text| !text.is_empty())
+}
+
+/// Session dialog for creating/editing SSH, SSM, serial and telnet sessions,
+/// and editing K8s and local ones
 pub struct SessionDialog {
     /// Session ID if editing (None for new session)
     session_id: Option<Uuid>,
     /// Group ID if adding to a group
     group_id: Option<Uuid>,
-    /// Session type (SSH or SSM)
+    /// The group's defaults, including those inherited from parent groups
+    group_defaults: GroupDefaults,
+    /// Session type (SSH, SSM, serial, telnet, K8s or local)
     session_type: SessionType,
     /// Whether we're editing (locks session type)
     is_editing: bool,
@@ -60,26 +176,83 @@ pub struct SessionDialog {
     password_field: Entity<TextField>,
     key_path_field: Entity<TextField>,
     key_passphrase_field: Entity<TextField>,
+    /// Further key paths to try, comma-separated
+    extra_keys_field: Entity<TextField>,
+    /// Keep-alive command and its idle interval in seconds
+    keep_alive_field: Entity<TextField>,
+    keep_alive_interval_field: Entity<TextField>,
+    /// Local commands run before connecting and when the connection ends
+    pre_connect_field: Entity<TextField>,
+    post_disconnect_field: Entity<TextField>,
+    /// Jump host as `[user@]host[:port]` and the key to log in to it with
+    jump_host_field: Entity<TextField>,
+    jump_key_field: Entity<TextField>,
+    /// Comma-separated `ssh -L` and `-R` forward specs
+    local_forwards_field: Entity<TextField>,
+    remote_forwards_field: Entity<TextField>,
     /// SSM-specific fields
     instance_id_field: Entity<TextField>,
     region_field: Entity<TextField>,
     profile_field: Entity<TextField>,
+    /// SSM port forward; both empty for a shell
+    ssm_local_port_field: Entity<TextField>,
+    ssm_remote_port_field: Entity<TextField>,
+    /// Serial-specific fields
+    device_field: Entity<TextField>,
+    baud_field: Entity<TextField>,
+    data_bits: u8,
+    stop_bits: u8,
+    parity: SerialParity,
+    flow_control: SerialFlowControl,
+    /// Ports found on this machine, as (device, description)
+    serial_ports: Vec<(String, Option<String>)>,
+    /// K8s-specific fields; the command is split on whitespace
+    k8s_context_field: Entity<TextField>,
+    k8s_namespace_field: Entity<TextField>,
+    k8s_pod_field: Entity<TextField>,
+    k8s_container_field: Entity<TextField>,
+    k8s_command_field: Entity<TextField>,
+    /// Allocate a TTY for the pod exec
+    k8s_tty: bool,
+    /// Local-specific fields
+    shell_field: Entity<TextField>,
+    working_dir_field: Entity<TextField>,
+    /// Environment of the local session being edited, kept as is
+    local_env: HashMap<String, String>,
     /// Auth settings (SSH only)
     auth_type: AuthType,
     save_password: bool,
     save_passphrase: bool,
+    /// Don't fall back to agent keys when key auth fails
+    identities_only: bool,
     /// Color scheme override (None = use default)
     color_scheme: Option<String>,
+    /// Transcript recording override (None = use the global setting)
+    record_session: Option<bool>,
+    /// Whether the advanced SSH options are expanded
+    show_advanced: bool,
+    /// What the Enter key sends (SSH only)
+    newline_mode: NewlineMode,
+    /// Offer the saved password at `sudo` prompts (SSH password auth only)
+    sudo_autofill: bool,
+    /// Forward the local SSH agent to the server
+    agent_forward: bool,
     /// Validation errors
     errors: Vec<String>,
+    /// Known hosts offered while typing in the host field
+    host_suggestions: Vec<HostSuggestion>,
+    /// Suggestions currently shown under the host field, in order
+    host_matches: Vec<HostSuggestion>,
+    _subscriptions: Vec<Subscription>,
 }
 
 impl SessionDialog {
     /// Create a new session dialog
     pub fn new(cx: &mut Context<Self>) -> Self {
-        Self {
+        let mut dialog = Self {
             session_id: None,
             group_id: None,
+            group_defaults: GroupDefaults::default(),
             session_type: SessionType::Ssh,
             is_editing: false,
             name_field: cx.new(|cx| TextField::new(cx, "My Server")),
@@ -97,24 +270,116 @@ impl SessionDialog {
                 field.set_password(true);
                 field
             }),
+            extra_keys_field: cx.new(|cx| TextField::new(cx, EXTRA_KEYS_PLACEHOLDER)),
+            keep_alive_field: cx.new(|cx| TextField::new(cx, KEEP_ALIVE_PLACEHOLDER)),
+            keep_alive_interval_field: cx.new(|cx| TextField::with_content(cx, "240", "240".to_string())),
+            pre_connect_field: cx.new(|cx| TextField::new(cx, PRE_CONNECT_PLACEHOLDER)),
+            post_disconnect_field: cx.new(|cx| TextField::new(cx, POST_DISCONNECT_PLACEHOLDER)),
+            jump_host_field: cx.new(|cx| TextField::new(cx, JUMP_HOST_PLACEHOLDER)),
+            jump_key_field: cx.new(|cx| TextField::new(cx, JUMP_KEY_PLACEHOLDER)),
+            local_forwards_field: cx.new(|cx| TextField::new(cx, LOCAL_FORWARDS_PLACEHOLDER)),
+            remote_forwards_field: cx.new(|cx| TextField::new(cx, REMOTE_FORWARDS_PLACEHOLDER)),
             instance_id_field: cx.new(|cx| TextField::new(cx, "i-0123456789abcdef0")),
             region_field: cx.new(|cx| TextField::new(cx, "us-east-1 (optional)")),
             profile_field: cx.new(|cx| TextField::new(cx, "default (optional)")),
+            ssm_local_port_field: cx.new(|cx| TextField::new(cx, "local port")),
+            ssm_remote_port_field: cx.new(|cx| TextField::new(cx, "instance port")),
+            device_field: cx.new(|cx| TextField::new(cx, DEVICE_PLACEHOLDER)),
+            baud_field: cx.new(|cx| TextField::with_content(cx, "115200", "115200".to_string())),
+            data_bits: 8,
+            stop_bits: 1,
+            parity: SerialParity::None,
+            flow_control: SerialFlowControl::None,
+            serial_ports: Vec::new(),
+            k8s_context_field: cx.new(|cx| TextField::new(cx, K8S_CONTEXT_PLACEHOLDER)),
+            k8s_namespace_field: cx.new(|cx| TextField::new(cx, "default")),
+            k8s_pod_field: cx.new(|cx| TextField::new(cx, "pod name")),
+            k8s_container_field: cx.new(|cx| TextField::new(cx, K8S_CONTAINER_PLACEHOLDER)),
+            k8s_command_field: cx.new(|cx| TextField::new(cx, K8S_COMMAND_PLACEHOLDER)),
+            k8s_tty: true,
+            shell_field: cx.new(|cx| TextField::new(cx, SHELL_PLACEHOLDER)),
+            working_dir_field: cx.new(|cx| TextField::new(cx, WORKING_DIR_PLACEHOLDER)),
+            local_env: HashMap::new(),
             auth_type: AuthType::Password,
             save_password: false,
             save_passphrase: false,
+            identities_only: false,
             color_scheme: None,
+            record_session: None,
+            show_advanced: false,
+            newline_mode: NewlineMode::Auto,
+            sudo_autofill: false,
+            agent_forward: false,
             errors: Vec::new(),
-        }
+            host_suggestions: Vec::new(),
+            host_matches: Vec::new(),
+            _subscriptions: Vec::new(),
+        };
+        dialog.watch_host_field(cx);
+        dialog
     }
 
     /// Create a new session dialog for a specific group
     pub fn new_for_group(group_id: Option<Uuid>, cx: &mut Context<Self>) -> Self {
         let mut dialog = Self::new(cx);
         dialog.group_id = group_id;
+        dialog.group_defaults = Self::group_defaults(group_id, cx);
+        dialog.apply_group_defaults(cx);
         dialog
     }
 
+    /// Effective defaults of a group, empty without one
+    fn group_defaults(group_id: Option<Uuid>, cx: &mut Context<Self>) -> GroupDefaults {
+        match (group_id, cx.try_global::<AppState>()) {
+            (Some(group_id), Some(state)) => state.app.lock().session_manager.group_defaults(group_id),
+            _ => GroupDefaults::default(),
+        }
+    }
+
+    /// Fill the SSH fields a new session takes from its group
+    fn apply_group_defaults(&mut self, cx: &mut Context<Self>) {
+        let set = |field: &Entity<TextField>, value: String, cx: &mut Context<Self>| {
+            field.update(cx, |field, cx| {
+                field.set_content(value);
+                cx.notify();
+            });
+        };
+
+        let defaults = self.group_defaults.clone();
+        if let Some(username) = defaults.username {
+            set(&self.username_field, username, cx);
+        }
+        if let Some(port) = defaults.port {
+            set(&self.port_field, port.to_string(), cx);
+        }
+        match defaults.auth {
+            Some(AuthMethod::Password { use_keychain, .. }) => {
+                self.auth_type = AuthType::Password;
+                self.save_password = use_keychain;
+            }
+            Some(AuthMethod::PrivateKey {
+                path,
+                use_keychain,
+                extra_keys,
+                identities_only,
+                ..
+            }) => {
+                self.auth_type = AuthType::PrivateKey;
+                self.save_passphrase = use_keychain;
+                self.identities_only = identities_only;
+                set(&self.key_path_field, path.to_string_lossy().into_owned(), cx);
+                let extra_keys: Vec<_> = extra_keys.iter().map(|p| p.to_string_lossy().into_owned()).collect();
+                set(&self.extra_keys_field, extra_keys.join(", "), cx);
+            }
+            Some(AuthMethod::Agent) => self.auth_type = AuthType::Agent,
+            Some(AuthMethod::KeyboardInteractive) => self.auth_type = AuthType::KeyboardInteractive,
+            None => {}
+        }
+        if defaults.color_scheme.is_some() {
+            self.color_scheme = defaults.color_scheme;
+        }
+    }
+
     /// Create a dialog for editing an existing SSH session
     pub fn edit(session: &SshSession, cx: &mut Context<Self>) -> Self {
         let (auth_type, password, save_password, key_path, key_passphrase, save_passphrase) =
@@ -134,6 +399,7 @@ impl SessionDialog {
                     path,
                     passphrase,
                     use_keychain,
+                    ..
                 } => (
                     AuthType::PrivateKey,
                     String::new(),
@@ -150,11 +416,49 @@ impl SessionDialog {
                     String::new(),
                     false,
                 ),
+                AuthMethod::KeyboardInteractive => (
+                    AuthType::KeyboardInteractive,
+                    String::new(),
+                    false,
+                    String::new(),
+                    String::new(),
+                    false,
+                ),
             };
+        let (extra_keys, identities_only) = match &session.auth {
+            AuthMethod::PrivateKey {
+                extra_keys,
+                identities_only,
+                ..
+            } => (
+                extra_keys
+                    .iter()
+                    .map(|p| p.to_string_lossy().into_owned())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                *identities_only,
+            ),
+            _ => (String::new(), false),
+        };
+        let (keep_alive_command, keep_alive_interval) = match &session.keep_alive {
+            Some(keep_alive) => (keep_alive.command.clone(), keep_alive.interval_secs.to_string()),
+            None => (String::new(), "240".to_string()),
+        };
+        let (jump_host, jump_key) = match &session.jump_host {
+            Some(jump) => (
+                jump.spec(),
+                match &jump.auth {
+                    AuthMethod::PrivateKey { path, .. } => path.to_string_lossy().into_owned(),
+                    _ => String::new(),
+                },
+            ),
+            None => (String::new(), String::new()),
+        };
 
-        Self {
+        let mut dialog = Self {
             session_id: Some(session.id),
             group_id: session.group_id,
+            group_defaults: GroupDefaults::default(),
             session_type: SessionType::Ssh,
             is_editing: true,
             name_field: cx.new(|cx| TextField::with_content(cx, "My Server", session.name.clone())),
@@ -172,22 +476,77 @@ impl SessionDialog {
                 field.set_password(true);
                 field
             }),
+            extra_keys_field: cx.new(|cx| TextField::with_content(cx, EXTRA_KEYS_PLACEHOLDER, extra_keys)),
+            keep_alive_field: cx.new(|cx| TextField::with_content(cx, KEEP_ALIVE_PLACEHOLDER, keep_alive_command)),
+            keep_alive_interval_field: cx.new(|cx| TextField::with_content(cx, "240", keep_alive_interval)),
+            pre_connect_field: cx.new(|cx| {
+                TextField::with_content(cx, PRE_CONNECT_PLACEHOLDER, session.pre_connect_command.clone().unwrap_or_default())
+            }),
+            post_disconnect_field: cx.new(|cx| {
+                TextField::with_content(cx, POST_DISCONNECT_PLACEHOLDER, session.post_disconnect_command.clone().unwrap_or_default())
+            }),
+            jump_host_field: cx.new(|cx| TextField::with_content(cx, JUMP_HOST_PLACEHOLDER, jump_host)),
+            jump_key_field: cx.new(|cx| TextField::with_content(cx, JUMP_KEY_PLACEHOLDER, jump_key)),
+            local_forwards_field: cx.new(|cx| {
+                TextField::with_content(cx, LOCAL_FORWARDS_PLACEHOLDER, forward_specs(&session.port_forwards, ForwardDirection::Local))
+            }),
+            remote_forwards_field: cx.new(|cx| {
+                TextField::with_content(cx, REMOTE_FORWARDS_PLACEHOLDER, forward_specs(&session.port_forwards, ForwardDirection::Remote))
+            }),
             instance_id_field: cx.new(|cx| TextField::new(cx, "i-0123456789abcdef0")),
             region_field: cx.new(|cx| TextField::new(cx, "us-east-1 (optional)")),
             profile_field: cx.new(|cx| TextField::new(cx, "default (optional)")),
+            ssm_local_port_field: cx.new(|cx| TextField::new(cx, "local port")),
+            ssm_remote_port_field: cx.new(|cx| TextField::new(cx, "instance port")),
+            device_field: cx.new(|cx| TextField::new(cx, DEVICE_PLACEHOLDER)),
+            baud_field: cx.new(|cx| TextField::with_content(cx, "115200", "115200".to_string())),
+            data_bits: 8,
+            stop_bits: 1,
+            parity: SerialParity::None,
+            flow_control: SerialFlowControl::None,
+            serial_ports: Vec::new(),
+            k8s_context_field: cx.new(|cx| TextField::new(cx, K8S_CONTEXT_PLACEHOLDER)),
+            k8s_namespace_field: cx.new(|cx| TextField::new(cx, "default")),
+            k8s_pod_field: cx.new(|cx| TextField::new(cx, "pod name")),
+            k8s_container_field: cx.new(|cx| TextField::new(cx, K8S_CONTAINER_PLACEHOLDER)),
+            k8s_command_field: cx.new(|cx| TextField::new(cx, K8S_COMMAND_PLACEHOLDER)),
+            k8s_tty: true,
+            shell_field: cx.new(|cx| TextField::new(cx, SHELL_PLACEHOLDER)),
+            working_dir_field: cx.new(|cx| TextField::new(cx, WORKING_DIR_PLACEHOLDER)),
+            local_env: HashMap::new(),
             auth_type,
             save_password,
             save_passphrase,
+            identities_only,
             color_scheme: session.color_scheme.clone(),
+            record_session: session.record_session,
+            show_advanced: !session.newline_mode.is_auto()
+                || session.keep_alive.is_some()
+                || session.sudo_autofill
+                || session.agent_forward
+                || session.pre_connect_command.is_some()
+                || session.post_disconnect_command.is_some()
+                || session.jump_host.is_some()
+                || !session.port_forwards.is_empty(),
+            newline_mode: session.newline_mode,
+            sudo_autofill: session.sudo_autofill,
+            agent_forward: session.agent_forward,
             errors: Vec::new(),
-        }
+            host_suggestions: Vec::new(),
+            host_matches: Vec::new(),
+            _subscriptions: Vec::new(),
+        };
+        dialog.group_defaults = Self::group_defaults(session.group_id, cx);
+        dialog.watch_host_field(cx);
+        dialog
     }
 
     /// Create a dialog for editing an existing SSM session
     pub fn edit_ssm(session: &SsmSession, cx: &mut Context<Self>) -> Self {
-        Self {
+        let mut dialog = Self {
             session_id: Some(session.id),
             group_id: session.group_id,
+            group_defaults: GroupDefaults::default(),
             session_type: SessionType::Ssm,
             is_editing: true,
             name_field: cx.new(|cx| TextField::with_content(cx, "My EC2 Instance", session.name.clone())),
@@ -205,15 +564,143 @@ impl SessionDialog {
                 field.set_password(true);
                 field
             }),
+            extra_keys_field: cx.new(|cx| TextField::new(cx, EXTRA_KEYS_PLACEHOLDER)),
+            keep_alive_field: cx.new(|cx| TextField::new(cx, KEEP_ALIVE_PLACEHOLDER)),
+            keep_alive_interval_field: cx.new(|cx| TextField::with_content(cx, "240", "240".to_string())),
+            pre_connect_field: cx.new(|cx| TextField::new(cx, PRE_CONNECT_PLACEHOLDER)),
+            post_disconnect_field: cx.new(|cx| TextField::new(cx, POST_DISCONNECT_PLACEHOLDER)),
+            jump_host_field: cx.new(|cx| TextField::new(cx, JUMP_HOST_PLACEHOLDER)),
+            jump_key_field: cx.new(|cx| TextField::new(cx, JUMP_KEY_PLACEHOLDER)),
+            local_forwards_field: cx.new(|cx| TextField::new(cx, LOCAL_FORWARDS_PLACEHOLDER)),
+            remote_forwards_field: cx.new(|cx| TextField::new(cx, REMOTE_FORWARDS_PLACEHOLDER)),
             instance_id_field: cx.new(|cx| TextField::with_content(cx, "i-0123456789abcdef0", session.instance_id.clone())),
             region_field: cx.new(|cx| TextField::with_content(cx, "us-east-1 (optional)", session.region.clone().unwrap_or_default())),
             profile_field: cx.new(|cx| TextField::with_content(cx, "default (optional)", session.profile.clone().unwrap_or_default())),
+            ssm_local_port_field: cx.new(|cx| {
+                let port = session.port_forward.map(|forward| forward.local_port.to_string());
+                TextField::with_content(cx, "local port", port.unwrap_or_default())
+            }),
+            ssm_remote_port_field: cx.new(|cx| {
+                let port = session.port_forward.map(|forward| forward.remote_port.to_string());
+                TextField::with_content(cx, "instance port", port.unwrap_or_default())
+            }),
+            device_field: cx.new(|cx| TextField::new(cx, DEVICE_PLACEHOLDER)),
+            baud_field: cx.new(|cx| TextField::with_content(cx, "115200", "115200".to_string())),
+            data_bits: 8,
+            stop_bits: 1,
+            parity: SerialParity::None,
+            flow_control: SerialFlowControl::None,
+            serial_ports: Vec::new(),
+            k8s_context_field: cx.new(|cx| TextField::new(cx, K8S_CONTEXT_PLACEHOLDER)),
+            k8s_namespace_field: cx.new(|cx| TextField::new(cx, "default")),
+            k8s_pod_field: cx.new(|cx| TextField::new(cx, "pod name")),
+            k8s_container_field: cx.new(|cx| TextField::new(cx, K8S_CONTAINER_PLACEHOLDER)),
+            k8s_command_field: cx.new(|cx| TextField::new(cx, K8S_COMMAND_PLACEHOLDER)),
+            k8s_tty: true,
+            shell_field: cx.new(|cx| TextField::new(cx, SHELL_PLACEHOLDER)),
+            working_dir_field: cx.new(|cx| TextField::new(cx, WORKING_DIR_PLACEHOLDER)),
+            local_env: HashMap::new(),
             auth_type: AuthType::Password,
             save_password: false,
             save_passphrase: false,
+            identities_only: false,
             color_scheme: session.color_scheme.clone(),
+            record_session: session.record_session,
+            show_advanced: false,
+            newline_mode: NewlineMode::Auto,
+            sudo_autofill: false,
+            agent_forward: false,
             errors: Vec::new(),
+            host_suggestions: Vec::new(),
+            host_matches: Vec::new(),
+            _subscriptions: Vec::new(),
+        };
+        dialog.watch_host_field(cx);
+        dialog
+    }
+
+    /// Offer saved and ssh config hosts while the host field is edited
+    fn watch_host_field(&mut self, cx: &mut Context<Self>) {
+        self.host_suggestions = cx
+            .try_global::<AppState>()
+            .map(|state| collect_host_suggestions(&state.app.lock().session_manager))
+            .unwrap_or_default();
+
+        let subscription = cx.subscribe(&self.host_field, |this, field, event, cx| match event {
+            TextFieldEvent::Changed(text) => {
+                // Only SSH sessions carry a user and port to fill in
+                let ranked = if this.session_type == SessionType::Ssh {
+                    rank_suggestions(text, &this.host_suggestions, MAX_HOST_SUGGESTIONS)
+                } else {
+                    Vec::new()
+                };
+                let suggestions = ranked
+                    .iter()
+                    .map(|r| Suggestion {
+                        label: r.suggestion.match_text().to_string(),
+                        detail: Some(r.suggestion.detail()).filter(|detail| detail != r.suggestion.match_text()),
+                        highlights: r.positions.clone(),
+                    })
+                    .collect();
+                this.host_matches = ranked.into_iter().map(|r| r.suggestion).collect();
+                field.update(cx, |field, cx| {
+                    field.set_suggestions(suggestions);
+                    cx.notify();
+                });
+            }
+            TextFieldEvent::SuggestionAccepted(index) => {
+                if let Some(suggestion) = this.host_matches.get(*index).cloned() {
+                    this.apply_host_suggestion(suggestion, cx);
+                }
+                this.host_matches.clear();
+            }
+            TextFieldEvent::Submit => {}
+        });
+        self._subscriptions.push(subscription);
+    }
+
+    /// Fill host, user and port (and an empty name) from a picked suggestion
+    fn apply_host_suggestion(&mut self, suggestion: HostSuggestion, cx: &mut Context<Self>) {
+        let set = |field: &Entity<TextField>, value: String, cx: &mut Context<Self>| {
+            field.update(cx, |field, cx| {
+                field.set_content(value);
+                cx.notify();
+            });
+        };
+
+        set(&self.host_field, suggestion.host, cx);
+        if let Some(username) = suggestion.username {
+            set(&self.username_field, username, cx);
+        }
+        if let Some(port) = suggestion.port {
+            set(&self.port_field, port.to_string(), cx);
         }
+        if let Some(alias) = suggestion.alias {
+            if self.name_field.read(cx).content().trim().is_empty() {
+                set(&self.name_field, alias, cx);
+            }
+        }
+        cx.notify();
+    }
+
+    /// Create a dialog for editing an existing serial session
+    pub fn edit_serial(session: &SerialSession, cx: &mut Context<Self>) -> Self {
+        let mut dialog = Self::new(cx);
+        dialog.session_id = Some(session.id);
+        dialog.group_id = session.group_id;
+        dialog.session_type = SessionType::Serial;
+        dialog.is_editing = true;
+        dialog.name_field = cx.new(|cx| TextField::with_content(cx, "My Router Console", session.name.clone()));
+        dialog.device_field = cx.new(|cx| TextField::with_content(cx, DEVICE_PLACEHOLDER, session.device.clone()));
+        dialog.baud_field = cx.new(|cx| TextField::with_content(cx, "115200", session.baud_rate.to_string()));
+        dialog.data_bits = session.data_bits;
+        dialog.stop_bits = session.stop_bits;
+        dialog.parity = session.parity;
+        dialog.flow_control = session.flow_control;
+        dialog.serial_ports = detect_serial_ports();
+        dialog.color_scheme = session.color_scheme.clone();
+        dialog.record_session = session.record_session;
+        dialog
     }
 
     /// Open as a modal window
@@ -265,6 +752,33 @@ impl SessionDialog {
         });
     }
 
+    /// Open as a modal window for a new SSH session pre-filled from `session`
+    pub fn open_prefilled(session: SshSession, cx: &mut App) {
+        let window_options = WindowOptions {
+            window_bounds: Some(WindowBounds::Windowed(Bounds::centered(
+                None,
+                size(px(450.0), px(720.0)),
+                cx,
+            ))),
+            titlebar: Some(TitlebarOptions {
+                title: Some("New Session".into()),
+                appears_transparent: false,
+                ..Default::default()
+            }),
+            kind: WindowKind::Normal,
+            ..Default::default()
+        };
+
+        let _ = cx.open_window(window_options, |_window, cx| {
+            cx.new(|cx| {
+                let mut dialog = SessionDialog::edit(&session, cx);
+                dialog.session_id = None;
+                dialog.is_editing = false;
+                dialog
+            })
+        });
+    }
+
     /// Open as a modal window for editing an SSM session
     pub fn open_edit_ssm(session: SsmSession, cx: &mut App) {
         let window_options = WindowOptions {
@@ -287,6 +801,153 @@ impl SessionDialog {
         });
     }
 
+    /// Create a dialog for editing an existing telnet session
+    pub fn edit_telnet(session: &TelnetSession, cx: &mut Context<Self>) -> Self {
+        let mut dialog = Self::new(cx);
+        dialog.session_id = Some(session.id);
+        dialog.group_id = session.group_id;
+        dialog.session_type = SessionType::Telnet;
+        dialog.is_editing = true;
+        dialog.name_field = cx.new(|cx| TextField::with_content(cx, "My Switch", session.name.clone()));
+        dialog.host_field = cx.new(|cx| TextField::with_content(cx, "192.168.1.1", session.host.clone()));
+        dialog.port_field = cx.new(|cx| TextField::with_content(cx, "23", session.port.to_string()));
+        dialog.color_scheme = session.color_scheme.clone();
+        dialog.record_session = session.record_session;
+        dialog
+    }
+
+    /// Create a dialog for editing an existing K8s session
+    pub fn edit_k8s(session: &K8sSession, cx: &mut Context<Self>) -> Self {
+        let command = session.command.as_ref().map(|command| command.join(" ")).unwrap_or_default();
+
+        let mut dialog = Self::new(cx);
+        dialog.session_id = Some(session.id);
+        dialog.group_id = session.group_id;
+        dialog.session_type = SessionType::K8s;
+        dialog.is_editing = true;
+        dialog.name_field = cx.new(|cx| TextField::with_content(cx, "My Pod", session.name.clone()));
+        dialog.k8s_context_field = cx.new(|cx| TextField::with_content(cx, K8S_CONTEXT_PLACEHOLDER, session.context.clone()));
+        dialog.k8s_namespace_field = cx.new(|cx| TextField::with_content(cx, "default", session.namespace.clone()));
+        dialog.k8s_pod_field = cx.new(|cx| TextField::with_content(cx, "pod name", session.pod.clone()));
+        dialog.k8s_container_field = cx.new(|cx| {
+            TextField::with_content(cx, K8S_CONTAINER_PLACEHOLDER, session.container.clone().unwrap_or_default())
+        });
+        dialog.k8s_command_field = cx.new(|cx| TextField::with_content(cx, K8S_COMMAND_PLACEHOLDER, command));
+        dialog.k8s_tty = session.tty;
+        dialog.color_scheme = session.color_scheme.clone();
+        dialog.record_session = session.record_session;
+        dialog
+    }
+
+    /// Create a dialog for editing an existing local session
+    pub fn edit_local(session: &LocalSession, cx: &mut Context<Self>) -> Self {
+        let working_dir = session
+            .working_dir
+            .as_ref()
+            .map(|dir| dir.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let mut dialog = Self::new(cx);
+        dialog.session_id = Some(session.id);
+        dialog.group_id = session.group_id;
+        dialog.session_type = SessionType::Local;
+        dialog.is_editing = true;
+        dialog.name_field = cx.new(|cx| TextField::with_content(cx, "Local Terminal", session.name.clone()));
+        dialog.shell_field = cx.new(|cx| TextField::with_content(cx, SHELL_PLACEHOLDER, session.shell.clone().unwrap_or_default()));
+        dialog.working_dir_field = cx.new(|cx| TextField::with_content(cx, WORKING_DIR_PLACEHOLDER, working_dir));
+        dialog.local_env = session.env.clone();
+        dialog.color_scheme = session.color_scheme.clone();
+        dialog
+    }
+
+    /// Open as a modal window for editing a serial session
+    pub fn open_edit_serial(session: SerialSession, cx: &mut App) {
+        let window_options = WindowOptions {
+            window_bounds: Some(WindowBounds::Windowed(Bounds::centered(
+                None,
+                size(px(450.0), px(720.0)),
+                cx,
+            ))),
+            titlebar: Some(TitlebarOptions {
+                title: Some("Edit Serial Session".into()),
+                appears_transparent: false,
+                ..Default::default()
+            }),
+            kind: WindowKind::Normal,
+            ..Default::default()
+        };
+
+        let _ = cx.open_window(window_options, |_window, cx| {
+            cx.new(|cx| SessionDialog::edit_serial(&session, cx))
+        });
+    }
+
+    /// Open as a modal window for editing a telnet session
+    pub fn open_edit_telnet(session: TelnetSession, cx: &mut App) {
+        let window_options = WindowOptions {
+            window_bounds: Some(WindowBounds::Windowed(Bounds::centered(
+                None,
+                size(px(450.0), px(520.0)),
+                cx,
+            ))),
+            titlebar: Some(TitlebarOptions {
+                title: Some("Edit Telnet Session".into()),
+                appears_transparent: false,
+                ..Default::default()
+            }),
+            kind: WindowKind::Normal,
+            ..Default::default()
+        };
+
+        let _ = cx.open_window(window_options, |_window, cx| {
+            cx.new(|cx| SessionDialog::edit_telnet(&session, cx))
+        });
+    }
+
+    /// Open as a modal window for editing a K8s session
+    pub fn open_edit_k8s(session: K8sSession, cx: &mut App) {
+        let window_options = WindowOptions {
+            window_bounds: Some(WindowBounds::Windowed(Bounds::centered(
+                None,
+                size(px(450.0), px(620.0)),
+                cx,
+            ))),
+            titlebar: Some(TitlebarOptions {
+                title: Some("Edit K8s Session".into()),
+                appears_transparent: false,
+                ..Default::default()
+            }),
+            kind: WindowKind::Normal,
+            ..Default::default()
+        };
+
+        let _ = cx.open_window(window_options, |_window, cx| {
+            cx.new(|cx| SessionDialog::edit_k8s(&session, cx))
+        });
+    }
+
+    /// Open as a modal window for editing a local session
+    pub fn open_edit_local(session: LocalSession, cx: &mut App) {
+        let window_options = WindowOptions {
+            window_bounds: Some(WindowBounds::Windowed(Bounds::centered(
+                None,
+                size(px(450.0), px(480.0)),
+                cx,
+            ))),
+            titlebar: Some(TitlebarOptions {
+                title: Some("Edit Local Session".into()),
+                appears_transparent: false,
+                ..Default::default()
+            }),
+            kind: WindowKind::Normal,
+            ..Default::default()
+        };
+
+        let _ = cx.open_window(window_options, |_window, cx| {
+            cx.new(|cx| SessionDialog::edit_local(&session, cx))
+        });
+    }
+
     /// Validate the form
     fn validate(&mut self, cx: &mut Context<Self>) -> bool {
         self.errors.clear();
@@ -318,6 +979,31 @@ impl SessionDialog {
                 if self.auth_type == AuthType::PrivateKey && key_path.trim().is_empty() {
                     self.errors.push("Private key path is required".into());
                 }
+
+                if !self.keep_alive_field.read(cx).content().is_empty() {
+                    let interval = self.keep_alive_interval_field.read(cx).content().trim().parse::<u64>();
+                    if !matches!(interval, Ok(secs) if secs >= KeepAliveCommand::MIN_INTERVAL_SECS) {
+                        self.errors.push(format!(
+                            "Keep-alive interval must be at least {} seconds",
+                            KeepAliveCommand::MIN_INTERVAL_SECS
+                        ));
+                    }
+                }
+
+                let jump_host = self.jump_host_field.read(cx).content().trim();
+                if !jump_host.is_empty() && JumpHostConfig::parse(jump_host).is_none() {
+                    self.errors.push("Jump host must look like user@host:port".into());
+                }
+
+                for (direction, field) in [
+                    (ForwardDirection::Local, &self.local_forwards_field),
+                    (ForwardDirection::Remote, &self.remote_forwards_field),
+                ] {
+                    if parse_forwards(direction, field.read(cx).content()).is_none() {
+                        self.errors.push("Port forwards must look like [address:]port:host:hostport".into());
+                        break;
+                    }
+                }
             }
             SessionType::Ssm => {
                 let instance_id = self.instance_id_field.read(cx).content();
@@ -330,7 +1016,41 @@ impl SessionDialog {
                         self.errors.push("Instance ID must start with 'i-' (EC2) or 'mi-' (on-prem)".into());
                     }
                 }
+
+                let local = self.ssm_local_port_field.read(cx).content();
+                let remote = self.ssm_remote_port_field.read(cx).content();
+                if parse_ssm_forward(local, remote).is_none() {
+                    self.errors.push("Port forward needs both a local and an instance port (1-65535)".into());
+                }
+            }
+            SessionType::Serial => {
+                if self.device_field.read(cx).content().trim().is_empty() {
+                    self.errors.push("Device is required".into());
+                }
+
+                if !matches!(self.baud_field.read(cx).content().trim().parse::<u32>(), Ok(baud) if baud > 0) {
+                    self.errors.push("Baud rate must be a positive number".into());
+                }
+            }
+            SessionType::Telnet => {
+                if self.host_field.read(cx).content().trim().is_empty() {
+                    self.errors.push("Host is required".into());
+                }
+
+                if self.port_field.read(cx).content().trim().parse::<u16>().is_err() {
+                    self.errors.push("Port must be a valid number (1-65535)".into());
+                }
+            }
+            SessionType::K8s => {
+                if self.k8s_namespace_field.read(cx).content().trim().is_empty() {
+                    self.errors.push("Namespace is required".into());
+                }
+
+                if self.k8s_pod_field.read(cx).content().trim().is_empty() {
+                    self.errors.push("Pod is required".into());
+                }
             }
+            SessionType::Local => {}
         }
 
         self.errors.is_empty()
@@ -346,6 +1066,15 @@ impl SessionDialog {
         let password = self.password_field.read(cx).content();
         let key_path = self.key_path_field.read(cx).content();
         let key_passphrase = self.key_passphrase_field.read(cx).content();
+        let extra_keys = self
+            .extra_keys_field
+            .read(cx)
+            .content()
+            .split(',')
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from)
+            .collect();
 
         let auth = match self.auth_type {
             AuthType::Password => AuthMethod::Password {
@@ -364,8 +1093,11 @@ impl SessionDialog {
                     Some(key_passphrase.to_string())
                 },
                 use_keychain: self.save_passphrase,
+                extra_keys,
+                identities_only: self.identities_only,
             },
             AuthType::Agent => AuthMethod::Agent,
+            AuthType::KeyboardInteractive => AuthMethod::KeyboardInteractive,
         };
 
         let mut session = SshSession::new(name, host, username);
@@ -373,6 +1105,43 @@ impl SessionDialog {
         session.auth = auth;
         session.group_id = self.group_id;
         session.color_scheme = self.color_scheme.clone();
+        session.record_session = self.record_session;
+        session.newline_mode = self.newline_mode;
+        session.sudo_autofill = self.sudo_autofill && self.auth_type == AuthType::Password;
+        session.agent_forward = self.agent_forward;
+
+        let keep_alive_command = self.keep_alive_field.read(cx).content();
+        if !keep_alive_command.is_empty() {
+            let interval = self.keep_alive_interval_field.read(cx).content().trim().parse().unwrap_or(240);
+            session.keep_alive = Some(KeepAliveCommand::new(keep_alive_command, interval));
+        }
+        let pre_connect_command = self.pre_connect_field.read(cx).content().trim().to_string();
+        session.pre_connect_command = Some(pre_connect_command).filter(|command| !command.is_empty());
+        let post_disconnect_command = self.post_disconnect_field.read(cx).content().trim().to_string();
+        session.post_disconnect_command = Some(post_disconnect_command).filter(|command| !command.is_empty());
+        session.jump_host = JumpHostConfig::parse(self.jump_host_field.read(cx).content().trim()).map(|mut jump| {
+            let jump_key = self.jump_key_field.read(cx).content().trim();
+            if !jump_key.is_empty() {
+                jump.auth = AuthMethod::PrivateKey {
+                    path: PathBuf::from(jump_key),
+                    passphrase: None,
+                    use_keychain: false,
+                    extra_keys: Vec::new(),
+                    identities_only: false,
+                };
+            }
+            jump
+        });
+        session.port_forwards = [
+            (ForwardDirection::Local, &self.local_forwards_field),
+            (ForwardDirection::Remote, &self.remote_forwards_field),
+        ]
+        .into_iter()
+        .flat_map(|(direction, field)| parse_forwards(direction, field.read(cx).content()).unwrap_or_default())
+        .collect();
+
+        // Settings left at the group's default keep following it
+        session.inherited = session.matching_defaults(&self.group_defaults);
 
         // Preserve ID if editing
         if let Some(id) = self.session_id {
@@ -398,6 +1167,12 @@ impl SessionDialog {
         let mut session = SsmSession::with_config(name, instance_id, region, profile);
         session.group_id = self.group_id;
         session.color_scheme = self.color_scheme.clone();
+        session.record_session = self.record_session;
+        session.port_forward = parse_ssm_forward(
+            self.ssm_local_port_field.read(cx).content(),
+            self.ssm_remote_port_field.read(cx).content(),
+        )
+        .flatten();
 
         // Preserve ID if editing
         if let Some(id) = self.session_id {
@@ -407,41 +1182,124 @@ impl SessionDialog {
         session
     }
 
-    /// Get the built session if valid
-    pub fn get_session(&self, cx: &Context<Self>) -> Option<SshSession> {
-        if self.errors.is_empty() && self.session_type == SessionType::Ssh {
-            Some(self.build_session(cx))
-        } else {
-            None
-        }
-    }
+    /// Build a serial session from form fields
+    fn build_serial_session(&self, cx: &Context<Self>) -> SerialSession {
+        let name = self.name_field.read(cx).content().trim();
+        let device = self.device_field.read(cx).content().trim();
+
+        let mut session = SerialSession::new(name, device);
+        session.baud_rate = self.baud_field.read(cx).content().trim().parse().unwrap_or(session.baud_rate);
+        session.data_bits = self.data_bits;
+        session.stop_bits = self.stop_bits;
+        session.parity = self.parity;
+        session.flow_control = self.flow_control;
+        session.group_id = self.group_id;
+        session.color_scheme = self.color_scheme.clone();
+        session.record_session = self.record_session;
 
-    /// Handle save button click
-    fn handle_save(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        if !self.validate(cx) {
-            cx.notify();
-            return;
+        // Preserve ID if editing
+        if let Some(id) = self.session_id {
+            session.id = id;
         }
 
-        match self.session_type {
-            SessionType::Ssh => {
-                let session = self.build_session(cx);
+        session
+    }
 
-                // Save to app state
-                if let Some(app_state) = cx.try_global::<AppState>() {
-                    let mut app = app_state.app.lock();
-                    if self.session_id.is_some() {
-                        let _ = app.session_manager.update_ssh_session(session.id, session.clone());
-                    } else {
-                        app.add_ssh_session(session.clone());
-                    }
-                    let _ = app.save();
-                }
+    /// Build a telnet session from form fields
+    fn build_telnet_session(&self, cx: &Context<Self>) -> TelnetSession {
+        let name = self.name_field.read(cx).content().trim();
+        let host = self.host_field.read(cx).content().trim();
 
-                cx.emit(SessionDialogEvent::Saved(session));
-            }
-            SessionType::Ssm => {
-                let session = self.build_ssm_session(cx);
+        let mut session = TelnetSession::new(name, host);
+        session.port = self.port_field.read(cx).content().trim().parse().unwrap_or(session.port);
+        session.group_id = self.group_id;
+        session.color_scheme = self.color_scheme.clone();
+        session.record_session = self.record_session;
+
+        // Preserve ID if editing
+        if let Some(id) = self.session_id {
+            session.id = id;
+        }
+
+        session
+    }
+
+    /// Build a K8s session from form fields
+    fn build_k8s_session(&self, cx: &Context<Self>) -> K8sSession {
+        let name = self.name_field.read(cx).content().trim();
+        let context = self.k8s_context_field.read(cx).content().trim();
+        let namespace = self.k8s_namespace_field.read(cx).content().trim();
+        let pod = self.k8s_pod_field.read(cx).content().trim();
+
+        let mut session = K8sSession::new(name, context, namespace, pod);
+        session.container = non_empty(self.k8s_container_field.read(cx).content());
+        let command: Vec<String> = self.k8s_command_field.read(cx).content().split_whitespace().map(String::from).collect();
+        session.command = Some(command).filter(|command| !command.is_empty());
+        session.tty = self.k8s_tty;
+        session.group_id = self.group_id;
+        session.color_scheme = self.color_scheme.clone();
+        session.record_session = self.record_session;
+
+        // Preserve ID if editing
+        if let Some(id) = self.session_id {
+            session.id = id;
+        }
+
+        session
+    }
+
+    /// Build a local session from form fields
+    fn build_local_session(&self, cx: &Context<Self>) -> LocalSession {
+        let mut session = LocalSession::new(self.name_field.read(cx).content().trim());
+        session.shell = non_empty(self.shell_field.read(cx).content());
+        session.working_dir = non_empty(self.working_dir_field.read(cx).content()).map(PathBuf::from);
+        session.env = self.local_env.clone();
+        session.group_id = self.group_id;
+        session.color_scheme = self.color_scheme.clone();
+
+        // Preserve ID if editing
+        if let Some(id) = self.session_id {
+            session.id = id;
+        }
+
+        session
+    }
+
+    /// Get the built session if valid
+    pub fn get_session(&self, cx: &Context<Self>) -> Option<SshSession> {
+        if self.errors.is_empty() && self.session_type == SessionType::Ssh {
+            Some(self.build_session(cx))
+        } else {
+            None
+        }
+    }
+
+    /// Handle save button click
+    fn handle_save(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.validate(cx) {
+            cx.notify();
+            return;
+        }
+
+        match self.session_type {
+            SessionType::Ssh => {
+                let session = self.build_session(cx);
+
+                // Save to app state
+                if let Some(app_state) = cx.try_global::<AppState>() {
+                    let mut app = app_state.app.lock();
+                    if self.session_id.is_some() {
+                        let _ = app.session_manager.update_ssh_session(session.id, session.clone());
+                    } else {
+                        app.add_ssh_session(session.clone());
+                    }
+                    let _ = app.save();
+                }
+
+                cx.emit(SessionDialogEvent::Saved(session));
+            }
+            SessionType::Ssm => {
+                let session = self.build_ssm_session(cx);
 
                 // Save to app state
                 if let Some(app_state) = cx.try_global::<AppState>() {
@@ -456,6 +1314,70 @@ impl SessionDialog {
 
                 cx.emit(SessionDialogEvent::SavedSsm(session));
             }
+            SessionType::Serial => {
+                let session = self.build_serial_session(cx);
+
+                // Save to app state
+                if let Some(app_state) = cx.try_global::<AppState>() {
+                    let mut app = app_state.app.lock();
+                    if self.session_id.is_some() {
+                        let _ = app.session_manager.update_serial_session(session.id, session.clone());
+                    } else {
+                        app.add_serial_session(session.clone());
+                    }
+                    let _ = app.save();
+                }
+
+                cx.emit(SessionDialogEvent::SavedSerial(session));
+            }
+            SessionType::Telnet => {
+                let session = self.build_telnet_session(cx);
+
+                // Save to app state
+                if let Some(app_state) = cx.try_global::<AppState>() {
+                    let mut app = app_state.app.lock();
+                    if self.session_id.is_some() {
+                        let _ = app.session_manager.update_telnet_session(session.id, session.clone());
+                    } else {
+                        app.add_telnet_session(session.clone());
+                    }
+                    let _ = app.save();
+                }
+
+                cx.emit(SessionDialogEvent::SavedTelnet(session));
+            }
+            SessionType::K8s => {
+                let session = self.build_k8s_session(cx);
+
+                // Save to app state
+                if let Some(app_state) = cx.try_global::<AppState>() {
+                    let mut app = app_state.app.lock();
+                    if self.session_id.is_some() {
+                        let _ = app.session_manager.update_k8s_session(session.id, session.clone());
+                    } else {
+                        app.session_manager.add_k8s_session(session.clone());
+                    }
+                    let _ = app.save();
+                }
+
+                cx.emit(SessionDialogEvent::SavedK8s(session));
+            }
+            SessionType::Local => {
+                let session = self.build_local_session(cx);
+
+                // Save to app state
+                if let Some(app_state) = cx.try_global::<AppState>() {
+                    let mut app = app_state.app.lock();
+                    if self.session_id.is_some() {
+                        let _ = app.session_manager.update_local_session(session.id, session.clone());
+                    } else {
+                        app.session_manager.add_local_session(session.clone());
+                    }
+                    let _ = app.save();
+                }
+
+                cx.emit(SessionDialogEvent::SavedLocal(session));
+            }
         }
 
         // Close the window
@@ -537,6 +1459,17 @@ impl SessionDialog {
     }
 
     fn render_color_scheme_selector(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        // "Default" (no override) stands in for the default scheme; a hidden
+        // scheme the session already uses stays selectable
+        let mut scheme_choices: Vec<String> = cx
+            .try_global::<AppState>()
+            .map(|state| state.app.lock().config.appearance.visible_scheme_names())
+            .unwrap_or_else(|| ColorScheme::builtin_names().iter().map(|n| n.to_string()).collect());
+        scheme_choices.retain(|name| name != "default");
+        if let Some(current) = self.color_scheme.as_ref().filter(|c| !scheme_choices.contains(c)) {
+            scheme_choices.push(current.clone());
+        }
+
         div()
             .flex()
             .flex_col()
@@ -548,12 +1481,83 @@ impl SessionDialog {
                     .flex_wrap()
                     .gap_2()
                     .child(self.render_color_scheme_option("Default", None, cx))
-                    .child(self.render_color_scheme_option("Light", Some("light".to_string()), cx))
-                    .child(self.render_color_scheme_option("Matrix", Some("matrix".to_string()), cx))
-                    .child(self.render_color_scheme_option("Red", Some("red".to_string()), cx)),
+                    .children(
+                        scheme_choices
+                            .into_iter()
+                            .map(|name| self.render_color_scheme_option(ColorScheme::display_name(&name), Some(name), cx)),
+                    )
+                    .child(
+                        div()
+                            .id("edit-schemes")
+                            .px_3()
+                            .py_1()
+                            .rounded_md()
+                            .cursor_pointer()
+                            .text_color(rgb(0x89b4fa))
+                            .hover(|style| style.bg(rgb(0x313244)))
+                            .on_click(cx.listener(|_this, _event, _window, cx| {
+                                ColorSchemeEditor::open(cx);
+                            }))
+                            .child(div().text_sm().child("Edit...")),
+                    ),
             )
     }
 
+    fn render_recording_option(
+        &self,
+        label: impl Into<SharedString>,
+        value: Option<bool>,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let label = label.into();
+        let is_selected = self.record_session == value;
+
+        div()
+            .id(ElementId::Name(format!("record-{:?}", value).into()))
+            .px_3()
+            .py_1()
+            .rounded_md()
+            .cursor_pointer()
+            .when(is_selected, |this| {
+                this.bg(rgb(0x89b4fa)).text_color(rgb(0x1e1e2e))
+            })
+            .when(!is_selected, |this| {
+                this.bg(rgb(0x313244))
+                    .text_color(rgb(0xcdd6f4))
+                    .hover(|style| style.bg(rgb(0x45475a)))
+            })
+            .on_click(cx.listener(move |this, _event, _window, cx| {
+                this.record_session = value;
+                cx.notify();
+            }))
+            .child(div().text_sm().child(label))
+    }
+
+    fn render_recording_selector(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(self.render_label("Record Transcript"))
+            .child(
+                div()
+                    .flex()
+                    .flex_wrap()
+                    .gap_2()
+                    .child(self.render_recording_option("Default", None, cx))
+                    .child(self.render_recording_option("On", Some(true), cx))
+                    .child(self.render_recording_option("Off", Some(false), cx)),
+            )
+            .when(self.record_session == Some(true), |this| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(rgb(0xf9e2af))
+                        .child("Everything shown in the terminal is written to disk, including secrets echoed by the remote side."),
+                )
+            })
+    }
+
     fn render_errors(&self) -> impl IntoElement {
         div()
             .flex()
@@ -579,7 +1583,7 @@ impl SessionDialog {
             .child(self.password_field.clone())
     }
 
-    fn render_key_fields(&self) -> impl IntoElement {
+    fn render_key_fields(&self, cx: &mut Context<Self>) -> impl IntoElement {
         div()
             .flex()
             .flex_col()
@@ -600,6 +1604,38 @@ impl SessionDialog {
                     .child(self.render_label("Key Passphrase"))
                     .child(self.key_passphrase_field.clone()),
             )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(self.render_label("Additional Keys"))
+                    .child(self.extra_keys_field.clone()),
+            )
+            .child(
+                div()
+                    .id("identities-only")
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .cursor_pointer()
+                    .on_click(cx.listener(|this, _event, _window, cx| {
+                        this.identities_only = !this.identities_only;
+                        cx.notify();
+                    }))
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(rgb(0x89b4fa))
+                            .child(if self.identities_only { "☑" } else { "☐" }),
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(rgb(0xcdd6f4))
+                            .child("Only use these keys (don't try agent keys)"),
+                    ),
+            )
     }
 
     fn render_session_type_option(
@@ -635,7 +1671,25 @@ impl SessionDialog {
             })
             .when(!is_disabled, |this| {
                 this.on_click(cx.listener(move |this, _event, _window, cx| {
+                    // Swap the port between the SSH and telnet defaults unless the user changed it
+                    let default_port = match session_type {
+                        SessionType::Telnet => Some("23"),
+                        SessionType::Ssh => Some("22"),
+                        _ => None,
+                    };
+                    if let Some(default_port) = default_port {
+                        let port = this.port_field.read(cx).content().trim();
+                        if port.is_empty() || port == "22" || port == "23" {
+                            this.port_field.update(cx, |field, cx| {
+                                field.set_content(default_port);
+                                cx.notify();
+                            });
+                        }
+                    }
                     this.session_type = session_type;
+                    if session_type == SessionType::Serial && this.serial_ports.is_empty() {
+                        this.serial_ports = detect_serial_ports();
+                    }
                     cx.notify();
                 }))
             })
@@ -654,7 +1708,9 @@ impl SessionDialog {
                     .flex()
                     .gap_2()
                     .child(self.render_session_type_option("SSH", "🖥️", SessionType::Ssh, cx))
-                    .child(self.render_session_type_option("AWS SSM", "☁️", SessionType::Ssm, cx)),
+                    .child(self.render_session_type_option("AWS SSM", "☁️", SessionType::Ssm, cx))
+                    .child(self.render_session_type_option("Serial", "🔌", SessionType::Serial, cx))
+                    .child(self.render_session_type_option("Telnet", "📟", SessionType::Telnet, cx)),
             )
     }
 
@@ -701,17 +1757,225 @@ impl SessionDialog {
                             .gap_2()
                             .child(self.render_auth_option("Password", AuthType::Password, cx))
                             .child(self.render_auth_option("Key", AuthType::PrivateKey, cx))
-                            .child(self.render_auth_option("Agent", AuthType::Agent, cx)),
+                            .child(self.render_auth_option("Agent", AuthType::Agent, cx))
+                            .child(self.render_auth_option("2FA / Interactive", AuthType::KeyboardInteractive, cx)),
                     ),
             );
 
         if auth_type == AuthType::Password {
             fields = fields.child(self.render_password_field());
         } else if auth_type == AuthType::PrivateKey {
-            fields = fields.child(self.render_key_fields());
+            fields = fields.child(self.render_key_fields(cx));
+        } else if auth_type == AuthType::KeyboardInteractive {
+            fields = fields.child(
+                div()
+                    .text_xs()
+                    .text_color(rgb(0x6c7086))
+                    .child("The server's questions, such as a password and a verification code, are asked in the tab when connecting. Answers are hidden unless the server says otherwise."),
+            );
         }
 
-        fields
+        fields.child(self.render_advanced_options(cx))
+    }
+
+    /// Collapsible section for rarely needed SSH settings
+    fn render_advanced_options(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(
+                div()
+                    .id("advanced-toggle")
+                    .cursor_pointer()
+                    .text_sm()
+                    .text_color(rgb(0x89b4fa))
+                    .on_click(cx.listener(|this, _event, _window, cx| {
+                        this.show_advanced = !this.show_advanced;
+                        cx.notify();
+                    }))
+                    .child(if self.show_advanced { "▾ Advanced" } else { "▸ Advanced" }),
+            )
+            .when(self.show_advanced, |this| {
+                this.child(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .gap_2()
+                        .child(self.render_label("Enter Key Sends"))
+                        .child(
+                            div()
+                                .flex()
+                                .flex_wrap()
+                                .gap_2()
+                                .children(NewlineMode::ALL.into_iter().map(|mode| self.render_newline_option(mode, cx))),
+                        )
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(rgb(0x6c7086))
+                                .child("Auto sends CR, or CR+LF when the host asks for it. Change this if lines come out double-spaced or never submit."),
+                        )
+                        .child(self.render_label("Keep-Alive Command"))
+                        .child(
+                            div()
+                                .flex()
+                                .gap_2()
+                                .child(div().flex_1().child(self.keep_alive_field.clone()))
+                                .child(div().w(px(80.0)).child(self.keep_alive_interval_field.clone())),
+                        )
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(rgb(0x6c7086))
+                                .child("Typed after this many seconds without input, for servers that drop idle shells. Supports \\r, \\n, \\b and \\xNN."),
+                        )
+                        .child(self.render_label("Pre-Connect Command"))
+                        .child(self.pre_connect_field.clone())
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(rgb(0x6c7086))
+                                .child("Run on this machine before connecting; the connection waits for it to succeed."),
+                        )
+                        .child(self.render_label("Post-Disconnect Command"))
+                        .child(self.post_disconnect_field.clone())
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(rgb(0x6c7086))
+                                .child("Run on this machine when the connection ends, with {name}, {host}, {status} and {exit} filled in. Both commands only run when allow_session_hooks is enabled in the config."),
+                        )
+                        .child(self.render_label("Jump Host"))
+                        .child(
+                            div()
+                                .flex()
+                                .gap_2()
+                                .child(div().flex_1().child(self.jump_host_field.clone()))
+                                .child(div().flex_1().child(self.jump_key_field.clone())),
+                        )
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(rgb(0x6c7086))
+                                .child("Connect through this bastion host (like ssh -J). Without a user, the session's username is used; without a key, the SSH agent."),
+                        )
+                        .child(self.render_label("Port Forwards"))
+                        .child(
+                            div()
+                                .flex()
+                                .gap_2()
+                                .child(div().flex_1().child(self.local_forwards_field.clone()))
+                                .child(div().flex_1().child(self.remote_forwards_field.clone())),
+                        )
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(rgb(0x6c7086))
+                                .child("Local forwards listen here and connect from the server (ssh -L); remote forwards listen on the server and connect from here (ssh -R). The address defaults to 127.0.0.1."),
+                        )
+                        .child(self.render_agent_forward(cx))
+                        .when(self.auth_type == AuthType::Password, |this| this.child(self.render_sudo_autofill(cx))),
+                )
+            })
+    }
+
+    fn render_sudo_autofill(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .child(
+                div()
+                    .id("sudo-autofill")
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .cursor_pointer()
+                    .on_click(cx.listener(|this, _event, _window, cx| {
+                        this.sudo_autofill = !this.sudo_autofill;
+                        cx.notify();
+                    }))
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(rgb(0x89b4fa))
+                            .child(if self.sudo_autofill { "☑" } else { "☐" }),
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(rgb(0xcdd6f4))
+                            .child("Offer the saved password at sudo prompts"),
+                    ),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(rgb(0x6c7086))
+                    .child("Shows a button when the server asks \"[sudo] password for ...\". Nothing is sent until you click it."),
+            )
+    }
+
+    fn render_agent_forward(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .child(
+                div()
+                    .id("agent-forward")
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .cursor_pointer()
+                    .on_click(cx.listener(|this, _event, _window, cx| {
+                        this.agent_forward = !this.agent_forward;
+                        cx.notify();
+                    }))
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(rgb(0x89b4fa))
+                            .child(if self.agent_forward { "☑" } else { "☐" }),
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(rgb(0xcdd6f4))
+                            .child("Forward the SSH agent"),
+                    ),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(rgb(0x6c7086))
+                    .child("Lets you use your local keys from the server (like ssh -A). Anyone with root there can use them while you're connected."),
+            )
+    }
+
+    fn render_newline_option(&self, mode: NewlineMode, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_selected = self.newline_mode == mode;
+
+        div()
+            .id(ElementId::Name(format!("newline-{:?}", mode).into()))
+            .px_3()
+            .py_1()
+            .rounded_md()
+            .cursor_pointer()
+            .when(is_selected, |this| {
+                this.bg(rgb(0x89b4fa)).text_color(rgb(0x1e1e2e))
+            })
+            .when(!is_selected, |this| {
+                this.bg(rgb(0x313244))
+                    .text_color(rgb(0xcdd6f4))
+                    .hover(|style| style.bg(rgb(0x45475a)))
+            })
+            .on_click(cx.listener(move |this, _event, _window, cx| {
+                this.newline_mode = mode;
+                cx.notify();
+            }))
+            .child(div().text_sm().child(mode.label()))
     }
 
     fn render_ssm_fields(&self) -> impl IntoElement {
@@ -768,6 +2032,397 @@ impl SessionDialog {
                     .child(self.render_label("AWS Profile (optional)"))
                     .child(self.profile_field.clone()),
             )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(self.render_label("Port Forward (optional)"))
+                    .child(
+                        div()
+                            .flex()
+                            .gap_2()
+                            .child(div().flex_1().child(self.ssm_local_port_field.clone()))
+                            .child(div().flex_1().child(self.ssm_remote_port_field.clone())),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(rgb(0x6c7086))
+                            .child("Tunnels a port on the instance to 127.0.0.1 instead of opening a shell, e.g. to reach a database in a private subnet."),
+                    ),
+            )
+    }
+
+    /// Small selectable pill used for the serial line settings
+    fn render_serial_pill(
+        &self,
+        id: String,
+        label: impl Into<SharedString>,
+        is_selected: bool,
+        on_select: impl Fn(&mut Self, &mut Context<Self>) + 'static,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        div()
+            .id(ElementId::Name(id.into()))
+            .px_3()
+            .py_1()
+            .rounded_md()
+            .cursor_pointer()
+            .when(is_selected, |this| {
+                this.bg(rgb(0x89b4fa)).text_color(rgb(0x1e1e2e))
+            })
+            .when(!is_selected, |this| {
+                this.bg(rgb(0x313244))
+                    .text_color(rgb(0xcdd6f4))
+                    .hover(|style| style.bg(rgb(0x45475a)))
+            })
+            .on_click(cx.listener(move |this, _event, _window, cx| {
+                on_select(this, cx);
+                cx.notify();
+            }))
+            .child(div().text_sm().child(label.into()))
+    }
+
+    fn render_serial_ports(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let device = self.device_field.read(cx).content().trim().to_string();
+
+        div()
+            .flex()
+            .flex_wrap()
+            .items_center()
+            .gap_2()
+            .when(self.serial_ports.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(rgb(0x6c7086))
+                        .child("No serial ports detected"),
+                )
+            })
+            .children(self.serial_ports.iter().map(|(port, description)| {
+                let label = match description {
+                    Some(description) => format!("{} ({})", port, description),
+                    None => port.clone(),
+                };
+                let port = port.clone();
+                self.render_serial_pill(
+                    format!("port-{}", port),
+                    label,
+                    device == port,
+                    move |this, cx| {
+                        let port = port.clone();
+                        this.device_field.update(cx, |field, cx| {
+                            field.set_content(port);
+                            cx.notify();
+                        });
+                    },
+                    cx,
+                )
+            }))
+            .child(
+                div()
+                    .id("refresh-ports")
+                    .cursor_pointer()
+                    .text_xs()
+                    .text_color(rgb(0x89b4fa))
+                    .on_click(cx.listener(|this, _event, _window, cx| {
+                        this.serial_ports = detect_serial_ports();
+                        cx.notify();
+                    }))
+                    .child("↻ Refresh"),
+            )
+    }
+
+    fn render_telnet_fields(&self) -> impl IntoElement {
+        let (note, note_color) = if cfg!(feature = "telnet") {
+            ("Unencrypted; meant for legacy switches and routers", 0x6c7086)
+        } else {
+            ("This build was compiled without telnet support", 0xf9e2af)
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_3()
+            .child(
+                div()
+                    .p_3()
+                    .bg(rgb(0x313244))
+                    .rounded_md()
+                    .child(
+                        div()
+                            .flex()
+                            .flex_col()
+                            .gap_1()
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .text_color(rgb(0xcba6f7))
+                                    .font_weight(FontWeight::MEDIUM)
+                                    .child("Telnet"),
+                            )
+                            .child(div().text_xs().text_color(rgb(note_color)).child(note)),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(self.render_label("Host"))
+                    .child(self.host_field.clone()),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(self.render_label("Port"))
+                    .child(self.port_field.clone()),
+            )
+    }
+
+    fn render_k8s_fields(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_3()
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(self.render_label("Context"))
+                    .child(self.k8s_context_field.clone()),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(self.render_label("Namespace"))
+                    .child(self.k8s_namespace_field.clone()),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(self.render_label("Pod"))
+                    .child(self.k8s_pod_field.clone()),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(self.render_label("Container (optional)"))
+                    .child(self.k8s_container_field.clone()),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(self.render_label("Command (optional)"))
+                    .child(self.k8s_command_field.clone())
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(rgb(0x6c7086))
+                            .child("Run instead of a shell; arguments are split on spaces"),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(
+                        div()
+                            .id("k8s-tty")
+                            .flex()
+                            .items_center()
+                            .gap_2()
+                            .cursor_pointer()
+                            .on_click(cx.listener(|this, _event, _window, cx| {
+                                this.k8s_tty = !this.k8s_tty;
+                                cx.notify();
+                            }))
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .text_color(rgb(0x89b4fa))
+                                    .child(if self.k8s_tty { "☑" } else { "☐" }),
+                            )
+                            .child(div().text_sm().text_color(rgb(0xcdd6f4)).child("Allocate a TTY")),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(rgb(0x6c7086))
+                            .child("Turn off for commands that fail or print control codes under a terminal"),
+                    ),
+            )
+    }
+
+    fn render_local_fields(&self) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_3()
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(self.render_label("Shell (optional)"))
+                    .child(self.shell_field.clone()),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(self.render_label("Working Directory (optional)"))
+                    .child(self.working_dir_field.clone()),
+            )
+    }
+
+    fn render_serial_fields(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let baud = self.baud_field.read(cx).content().trim().parse::<u32>().ok();
+        let (note, note_color) = if cfg!(feature = "serial") {
+            ("Opens a local serial port, e.g. a USB console cable", 0x6c7086)
+        } else {
+            ("This build was compiled without serial support", 0xf9e2af)
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_3()
+            .child(
+                div()
+                    .p_3()
+                    .bg(rgb(0x313244))
+                    .rounded_md()
+                    .child(
+                        div()
+                            .flex()
+                            .flex_col()
+                            .gap_1()
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .text_color(rgb(0xa6e3a1))
+                                    .font_weight(FontWeight::MEDIUM)
+                                    .child("Serial Console"),
+                            )
+                            .child(div().text_xs().text_color(rgb(note_color)).child(note)),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(self.render_label("Device"))
+                    .child(self.device_field.clone())
+                    .child(self.render_serial_ports(cx)),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(self.render_label("Baud Rate"))
+                    .child(self.baud_field.clone())
+                    .child(
+                        div()
+                            .flex()
+                            .flex_wrap()
+                            .gap_2()
+                            .children(SerialSession::BAUD_RATES.into_iter().map(|rate| {
+                                self.render_serial_pill(
+                                    format!("baud-{}", rate),
+                                    rate.to_string(),
+                                    baud == Some(rate),
+                                    move |this, cx| {
+                                        this.baud_field.update(cx, |field, cx| {
+                                            field.set_content(rate.to_string());
+                                            cx.notify();
+                                        });
+                                    },
+                                    cx,
+                                )
+                            })),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .child(self.render_label("Data Bits"))
+                    .child(div().flex().gap_2().children((5u8..=8).map(|bits| {
+                        self.render_serial_pill(
+                            format!("data-bits-{}", bits),
+                            bits.to_string(),
+                            self.data_bits == bits,
+                            move |this, _cx| this.data_bits = bits,
+                            cx,
+                        )
+                    }))),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .child(self.render_label("Parity"))
+                    .child(div().flex().gap_2().children(SerialParity::ALL.into_iter().map(|parity| {
+                        self.render_serial_pill(
+                            format!("parity-{:?}", parity),
+                            parity.label(),
+                            self.parity == parity,
+                            move |this, _cx| this.parity = parity,
+                            cx,
+                        )
+                    }))),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .child(self.render_label("Stop Bits"))
+                    .child(div().flex().gap_2().children([1u8, 2].into_iter().map(|bits| {
+                        self.render_serial_pill(
+                            format!("stop-bits-{}", bits),
+                            bits.to_string(),
+                            self.stop_bits == bits,
+                            move |this, _cx| this.stop_bits = bits,
+                            cx,
+                        )
+                    }))),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .child(self.render_label("Flow Control"))
+                    .child(div().flex().gap_2().children(SerialFlowControl::ALL.into_iter().map(|flow| {
+                        self.render_serial_pill(
+                            format!("flow-{:?}", flow),
+                            flow.label(),
+                            self.flow_control == flow,
+                            move |this, _cx| this.flow_control = flow,
+                            cx,
+                        )
+                    }))),
+            )
     }
 }
 
@@ -777,6 +2432,10 @@ impl Render for SessionDialog {
             match self.session_type {
                 SessionType::Ssh => "Edit SSH Session",
                 SessionType::Ssm => "Edit SSM Session",
+                SessionType::Serial => "Edit Serial Session",
+                SessionType::Telnet => "Edit Telnet Session",
+                SessionType::K8s => "Edit K8s Session",
+                SessionType::Local => "Edit Local Session",
             }
         } else {
             "New Session"
@@ -789,10 +2448,18 @@ impl Render for SessionDialog {
         let button_bg = match session_type {
             SessionType::Ssh => rgb(0x89b4fa),  // Blue for SSH
             SessionType::Ssm => rgb(0xfab387),  // Orange for AWS
+            SessionType::Serial => rgb(0xa6e3a1),  // Green for serial
+            SessionType::Telnet => rgb(0xcba6f7),  // Mauve for telnet
+            SessionType::K8s => rgb(0x89dceb),  // Sky for K8s
+            SessionType::Local => rgb(0xb4befe),  // Lavender for local
         };
         let button_hover = match session_type {
             SessionType::Ssh => rgb(0x74c7ec),
             SessionType::Ssm => rgb(0xf9e2af),
+            SessionType::Serial => rgb(0x94e2d5),
+            SessionType::Telnet => rgb(0xf5c2e7),
+            SessionType::K8s => rgb(0x94e2d5),
+            SessionType::Local => rgb(0xcba6f7),
         };
 
         div()
@@ -833,8 +2500,10 @@ impl Render for SessionDialog {
                     form = form.child(self.render_errors());
                 }
 
-                // Session type selector (only for new sessions)
-                form = form.child(self.render_session_type_selector(cx));
+                // Session type selector (K8s and local sessions can't switch type)
+                if !matches!(session_type, SessionType::K8s | SessionType::Local) {
+                    form = form.child(self.render_session_type_selector(cx));
+                }
 
                 // Name field (common to both)
                 form = form.child(
@@ -854,11 +2523,28 @@ impl Render for SessionDialog {
                     SessionType::Ssm => {
                         form = form.child(self.render_ssm_fields());
                     }
+                    SessionType::Serial => {
+                        form = form.child(self.render_serial_fields(cx));
+                    }
+                    SessionType::Telnet => {
+                        form = form.child(self.render_telnet_fields());
+                    }
+                    SessionType::K8s => {
+                        form = form.child(self.render_k8s_fields(cx));
+                    }
+                    SessionType::Local => {
+                        form = form.child(self.render_local_fields());
+                    }
                 }
 
                 // Color scheme selector (common to both)
                 form = form.child(self.render_color_scheme_selector(cx));
 
+                // Transcript recording (local shells aren't recorded)
+                if session_type != SessionType::Local {
+                    form = form.child(self.render_recording_selector(cx));
+                }
+
                 form
             })
             // Footer with buttons
@@ -1,14 +1,20 @@
 use gpui::*;
 use gpui::prelude::*;
 use std::collections::{HashMap, HashSet};
+use std::time::SystemTime;
 use uuid::Uuid;
 
 use crate::app::AppState;
+use crate::config::SessionSort;
+use crate::format::relative_time;
 use crate::kubernetes::{KubeConfig, KubeContext, KubeClient, KubeNamespace, KubePod, NamespaceWatchEvent, PodWatchEvent};
-use crate::session::{Session, SessionGroup, SshSession, SsmSession};
+use crate::session::{K8sSession, LocalSession, SerialSession, Session, SessionGroup, SshSession, SsmSession, TelnetSession};
 use super::session_dialog::SessionDialog;
 use super::group_dialog::GroupDialog;
+use super::import_dialog::ImportDialog;
 use super::delete_confirm_dialog::DeleteConfirmDialog;
+use super::run_command_dialog::RunCommandDialog;
+use super::text_field::{TextField, TextFieldEvent};
 
 /// Actions for the session tree
 #[derive(Clone, Debug)]
@@ -39,14 +45,17 @@ impl EventEmitter<SessionTreeEvent> for SessionTree {}
 /// State for expanded groups
 pub struct SessionTreeState {
     expanded_groups: HashSet<Uuid>,
-    selected_item: Option<TreeItem>,
+    selected_items: HashSet<TreeItem>,
+    /// Where a shift-click range starts
+    selection_anchor: Option<TreeItem>,
 }
 
 impl SessionTreeState {
     pub fn new() -> Self {
         Self {
             expanded_groups: HashSet::new(),
-            selected_item: None,
+            selected_items: HashSet::new(),
+            selection_anchor: None,
         }
     }
 
@@ -65,9 +74,49 @@ impl SessionTreeState {
     pub fn expand(&mut self, group_id: Uuid) {
         self.expanded_groups.insert(group_id);
     }
+
+    pub fn is_selected(&self, item: &TreeItem) -> bool {
+        self.selected_items.contains(item)
+    }
+
+    /// Replace the selection with a single item
+    pub fn select_only(&mut self, item: TreeItem) {
+        self.selected_items.clear();
+        self.selected_items.insert(item.clone());
+        self.selection_anchor = Some(item);
+    }
+
+    /// Add or remove an item, keeping the rest of the selection
+    pub fn toggle_selected(&mut self, item: TreeItem) {
+        if !self.selected_items.remove(&item) {
+            self.selected_items.insert(item.clone());
+        }
+        self.selection_anchor = Some(item);
+    }
+
+    /// Select everything in `order` between the anchor and `item`
+    ///
+    /// Without a visible anchor this behaves like a plain click.
+    pub fn select_range(&mut self, item: TreeItem, order: &[TreeItem]) {
+        let anchor = self
+            .selection_anchor
+            .as_ref()
+            .and_then(|anchor| order.iter().position(|i| i == anchor));
+        let (Some(start), Some(end)) = (anchor, order.iter().position(|i| *i == item)) else {
+            self.select_only(item);
+            return;
+        };
+        let (start, end) = if start <= end { (start, end) } else { (end, start) };
+        self.selected_items = order[start..=end].iter().cloned().collect();
+    }
+
+    /// Drop selected items that are no longer in `visible`
+    pub fn retain_visible(&mut self, visible: &[TreeItem]) {
+        self.selected_items.retain(|item| visible.contains(item));
+    }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum TreeItem {
     Group(Uuid),
     Session(Uuid),
@@ -78,12 +127,43 @@ pub enum TreeItem {
 enum ContextMenuTarget {
     Group { id: Uuid, name: String },
     Session { id: Uuid, name: String },
+    /// Several selected sessions, in tree order
+    Selection { ids: Vec<Uuid> },
+    /// The containers of a pod, to pick one to exec into
+    Containers { context: String, namespace: String, pod: String, containers: Vec<String> },
+    /// A pod, for its logs
+    Pod { context: String, namespace: String, pod: String, containers: Vec<String> },
+}
+
+/// Sessions being dragged onto a group
+#[derive(Clone)]
+struct DraggedSessions {
+    ids: Vec<Uuid>,
+    /// Shown next to the cursor while dragging
+    label: String,
+}
+
+impl Render for DraggedSessions {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .px_2()
+            .py_1()
+            .rounded_sm()
+            .bg(rgb(0x313244))
+            .border_1()
+            .border_color(rgb(0x89b4fa))
+            .text_sm()
+            .text_color(rgb(0xcdd6f4))
+            .child(self.label.clone())
+    }
 }
 
 /// State for an open context menu
 struct ContextMenuState {
     position: Point<Pixels>,
     target: ContextMenuTarget,
+    /// Whether the "Move to Group" list is expanded
+    show_move_targets: bool,
 }
 
 /// Cached data for rendering the tree
@@ -108,6 +188,51 @@ impl TreeRenderData {
     fn ungrouped_sessions(&self) -> impl Iterator<Item = &Session> {
         self.sessions.iter().filter(|s| s.group_id().is_none())
     }
+
+    /// Keep only what matches a lowercased search query
+    ///
+    /// A session stays if its name matches; a group stays if its name matches
+    /// or anything below it does. Everything inside a matching group is kept,
+    /// so searching for a group name shows its whole branch.
+    fn filtered(self, query: &str) -> Self {
+        let matches = |name: &str| name.to_lowercase().contains(query);
+        let parents: HashMap<Uuid, Option<Uuid>> = self.groups.iter().map(|g| (g.id, g.parent_id)).collect();
+        // A group followed by its ancestors; bounded in case of a parent cycle
+        let lineage = |start: Option<Uuid>| {
+            let parents = &parents;
+            std::iter::successors(start, move |id| parents.get(id).copied().flatten()).take(parents.len())
+        };
+
+        let matched: HashSet<Uuid> = self.groups.iter().filter(|g| matches(&g.name)).map(|g| g.id).collect();
+        let in_matched = |group_id: Option<Uuid>| lineage(group_id).any(|id| matched.contains(&id));
+
+        let sessions: Vec<Session> = self
+            .sessions
+            .into_iter()
+            .filter(|s| matches(s.name()) || in_matched(s.group_id()))
+            .collect();
+
+        // Matching branches, plus the path down to every match
+        let mut keep: HashSet<Uuid> = self.groups.iter().map(|g| g.id).filter(|&id| in_matched(Some(id))).collect();
+        for id in matched.iter().copied().map(Some).chain(sessions.iter().map(|s| s.group_id())) {
+            keep.extend(lineage(id));
+        }
+
+        Self {
+            groups: self.groups.into_iter().filter(|g| keep.contains(&g.id)).collect(),
+            sessions,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.groups.is_empty() && self.sessions.is_empty()
+    }
+
+    /// Put the most recently connected sessions first, keeping the saved
+    /// order among sessions never connected
+    fn sort_by_recent(&mut self) {
+        self.sessions.sort_by_key(|s| std::cmp::Reverse(s.last_connected()));
+    }
 }
 
 /// Message for async K8s data updates
@@ -117,6 +242,8 @@ pub enum K8sUpdate {
     NamespacesError { context: String, error: String },
     Pods { context: String, namespace: String, pods: Vec<KubePod> },
     PodsError { context: String, namespace: String, error: String },
+    /// The pod watcher has delivered its initial list
+    PodsWatchReady { context: String, namespace: String },
     // Watch events for real-time updates
     NamespaceAdded { context: String, namespace: KubeNamespace },
     NamespaceDeleted { context: String, name: String },
@@ -130,10 +257,18 @@ pub struct SessionTree {
     pending_new_session_group: Option<Uuid>,
     pending_new_group_parent: Option<Uuid>,
     pending_edit_session: Option<Uuid>,
+    pending_ssh_from_ssm: Option<Uuid>,
     pending_edit_group: Option<Uuid>,
     pending_delete_session: Option<(Uuid, String)>,
     pending_delete_group: Option<(Uuid, String)>,
+    pending_delete_sessions: Option<Vec<Uuid>>,
+    /// SSH sessions, as (id, name), to run a one-off command on
+    pending_run_command: Option<Vec<(Uuid, String)>>,
+    /// Host whose known_hosts entries to forget, once confirmed
+    pending_forget_host_key: Option<String>,
     context_menu: Option<ContextMenuState>,
+    /// Sessions in the order they're drawn, for shift-click ranges
+    visible_sessions: Vec<TreeItem>,
     /// Kubernetes config loaded from kubeconfig
     kube_config: Option<KubeConfig>,
     /// Expanded K8s contexts
@@ -150,12 +285,25 @@ pub struct SessionTree {
     loading_contexts: HashSet<String>,
     /// Namespaces currently loading pods (key: "context:namespace")
     loading_namespaces: HashSet<String>,
+    /// Whether pod logs opened from the menu keep streaming new lines
+    follow_logs: bool,
     /// Channel sender for K8s data updates (cloned for async tasks)
     k8s_update_tx: async_channel::Sender<K8sUpdate>,
     /// Active namespace watchers per context (for cleanup)
     active_namespace_watchers: HashSet<String>,
     /// Active pod watchers per context:namespace (for cleanup)
     active_pod_watchers: HashSet<String>,
+    /// Periodic pod list refreshes per expanded context:namespace
+    pod_refreshers: HashMap<String, tokio::task::JoinHandle<()>>,
+    /// Search box for session and group names
+    session_filter_field: Entity<TextField>,
+    /// Lowercased search text; empty shows the tree as it is
+    session_filter: String,
+    /// Filter box for namespace and pod names
+    k8s_filter_field: Entity<TextField>,
+    /// Lowercased filter text; empty shows everything
+    k8s_filter: String,
+    _subscriptions: Vec<Subscription>,
 }
 
 impl SessionTree {
@@ -185,15 +333,36 @@ impl SessionTree {
             }
         }).detach();
 
+        let session_filter_field = cx.new(|cx| TextField::new(cx, "Search sessions"));
+        let session_filter_sub = cx.subscribe(&session_filter_field, |this, _field, event, cx| {
+            if let TextFieldEvent::Changed(text) = event {
+                this.session_filter = text.trim().to_lowercase();
+                cx.notify();
+            }
+        });
+
+        let k8s_filter_field = cx.new(|cx| TextField::new(cx, "Filter namespaces and pods"));
+        let filter_sub = cx.subscribe(&k8s_filter_field, |this, _field, event, cx| {
+            if let TextFieldEvent::Changed(text) = event {
+                this.k8s_filter = text.trim().to_lowercase();
+                cx.notify();
+            }
+        });
+
         Self {
             state: SessionTreeState::new(),
             pending_new_session_group: None,
             pending_new_group_parent: None,
             pending_edit_session: None,
+            pending_ssh_from_ssm: None,
             pending_edit_group: None,
             pending_delete_session: None,
             pending_delete_group: None,
+            pending_delete_sessions: None,
+            pending_run_command: None,
+            pending_forget_host_key: None,
             context_menu: None,
+            visible_sessions: Vec::new(),
             kube_config,
             expanded_k8s_contexts: HashSet::new(),
             k8s_expanded: false,
@@ -202,9 +371,16 @@ impl SessionTree {
             expanded_k8s_namespaces: HashSet::new(),
             loading_contexts: HashSet::new(),
             loading_namespaces: HashSet::new(),
+            follow_logs: true,
             k8s_update_tx,
             active_namespace_watchers: HashSet::new(),
             active_pod_watchers: HashSet::new(),
+            pod_refreshers: HashMap::new(),
+            session_filter_field,
+            session_filter: String::new(),
+            k8s_filter_field,
+            k8s_filter: String::new(),
+            _subscriptions: vec![session_filter_sub, filter_sub],
         }
     }
 
@@ -230,18 +406,17 @@ impl SessionTree {
                 self.k8s_namespaces.insert(context, vec![]);
             }
             K8sUpdate::Pods { context, namespace, pods } => {
-                // This is now just a marker that initial load is complete (pods vec is empty)
-                // Real data comes through PodAddedOrModified events
+                // A full re-list from the periodic refresh replaces whatever
+                // the watcher has built up, in case it missed an event
+                tracing::debug!("Refreshed {} pods for {}:{}", pods.len(), context, namespace);
                 let key = format!("{}:{}", context, namespace);
-                if pods.is_empty() {
-                    tracing::debug!("Pod watcher init complete for {}:{}", context, namespace);
-                    self.loading_namespaces.remove(&key);
-                } else {
-                    // Fallback for non-watcher path (shouldn't happen normally)
-                    tracing::info!("Loaded {} pods for {}:{}", pods.len(), context, namespace);
-                    self.loading_namespaces.remove(&key);
-                    self.k8s_pods.insert(key, pods);
-                }
+                self.loading_namespaces.remove(&key);
+                self.k8s_pods.insert(key, pods);
+            }
+            K8sUpdate::PodsWatchReady { context, namespace } => {
+                // Real data comes through PodAddedOrModified events
+                tracing::debug!("Pod watcher init complete for {}:{}", context, namespace);
+                self.loading_namespaces.remove(&format!("{}:{}", context, namespace));
             }
             K8sUpdate::PodsError { context, namespace, error } => {
                 tracing::warn!("Failed to load pods for {}:{}: {}", context, namespace, error);
@@ -293,6 +468,20 @@ impl SessionTree {
         }
     }
 
+    /// Whether a namespace or pod name passes the K8s filter box
+    fn k8s_filter_matches(&self, name: &str) -> bool {
+        self.k8s_filter.is_empty() || name.to_lowercase().contains(&self.k8s_filter)
+    }
+
+    /// Whether a namespace should be listed: its own name matches, or one of
+    /// its already-loaded pods does
+    fn k8s_namespace_visible(&self, context_name: &str, namespace: &str) -> bool {
+        self.k8s_filter_matches(namespace)
+            || self.k8s_pods
+                .get(&format!("{}:{}", context_name, namespace))
+                .is_some_and(|pods| pods.iter().any(|pod| self.k8s_filter_matches(&pod.name)))
+    }
+
     /// Toggle K8s root group expansion
     fn toggle_k8s_expanded(&mut self, _cx: &mut Context<Self>) {
         self.k8s_expanded = !self.k8s_expanded;
@@ -316,15 +505,67 @@ impl SessionTree {
         let key = format!("{}:{}", context_name, namespace);
         if self.expanded_k8s_namespaces.contains(&key) {
             self.expanded_k8s_namespaces.remove(&key);
+            if let Some(refresher) = self.pod_refreshers.remove(&key) {
+                refresher.abort();
+            }
         } else {
             self.expanded_k8s_namespaces.insert(key.clone());
             // Load pods if not already loaded/loading
             if !self.k8s_pods.contains_key(&key) && !self.loading_namespaces.contains(&key) {
-                self.load_pods(context_name, namespace, cx);
+                self.load_pods(context_name.clone(), namespace.clone(), cx);
             }
+            self.start_pod_refresh(context_name, namespace, cx);
         }
     }
 
+    /// Re-list a namespace's pods every few seconds while it's expanded
+    ///
+    /// The watcher normally keeps statuses current, but it can stall quietly
+    /// (expired credentials, a dropped connection); this keeps the dots honest.
+    fn start_pod_refresh(&mut self, context_name: String, namespace: String, cx: &mut Context<Self>) {
+        let key = format!("{}:{}", context_name, namespace);
+        let Some(app_state) = cx.try_global::<AppState>() else {
+            return;
+        };
+        let interval_secs = app_state.app.lock().config.session_tree.pod_refresh_secs;
+        if interval_secs == 0 || self.pod_refreshers.contains_key(&key) {
+            return;
+        }
+
+        let tx = self.k8s_update_tx.clone();
+        let refresher = app_state.tokio_runtime.spawn(async move {
+            let client = match KubeClient::for_context(&context_name).await {
+                Ok(client) => client,
+                Err(e) => {
+                    tracing::warn!("Pod refresh for {}:{} disabled: {}", context_name, namespace, e);
+                    return;
+                }
+            };
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+            // The first tick is immediate and the watcher has just loaded the list
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                match client.list_pods(&namespace).await {
+                    Ok(mut pods) => {
+                        pods.sort_by(|a, b| a.name.cmp(&b.name));
+                        let update = K8sUpdate::Pods {
+                            context: context_name.clone(),
+                            namespace: namespace.clone(),
+                            pods,
+                        };
+                        if tx.send(update).await.is_err() {
+                            break;
+                        }
+                    }
+                    // Keep the last known list; the next tick may succeed
+                    Err(e) => tracing::debug!("Pod refresh for {}:{} failed: {}", context_name, namespace, e),
+                }
+            }
+        });
+        self.pod_refreshers.insert(key, refresher);
+    }
+
     /// Load namespaces for a K8s context (starts a watcher for real-time updates)
     fn load_namespaces(&mut self, context_name: String, cx: &mut Context<Self>) {
         // Don't start duplicate watchers
@@ -438,10 +679,9 @@ impl SessionTree {
                                 }
                                 PodWatchEvent::InitDone => {
                                     // Signal that initial load is complete
-                                    let _ = tx.send_blocking(K8sUpdate::Pods {
+                                    let _ = tx.send_blocking(K8sUpdate::PodsWatchReady {
                                         context: ctx,
                                         namespace,
-                                        pods: vec![], // Marker that init is done
                                     });
                                 }
                             }
@@ -469,8 +709,6 @@ impl SessionTree {
     fn handle_pod_exec(&mut self, context: String, namespace: String, pod: String, container: Option<String>, cx: &mut Context<Self>) {
         tracing::info!("Exec into pod: {}:{}:{}", context, namespace, pod);
         // Create a K8s session and open it
-        use crate::session::K8sSession;
-
         let session = if let Some(container) = container {
             K8sSession::with_container(&pod, &context, &namespace, &pod, container)
         } else {
@@ -483,13 +721,26 @@ impl SessionTree {
             // Add session temporarily (or we could have a transient exec)
             let session_id = session.id;
             app.session_manager.add_k8s_session(session);
-            if let Err(e) = app.open_k8s_session(session_id, &runtime) {
+            if let Err(e) = app.open_session(session_id, &runtime, false) {
                 tracing::error!("Failed to exec into pod: {}", e);
             }
         }
         cx.notify();
     }
 
+    /// Open a tab with the log of a pod's container
+    fn handle_pod_logs(&mut self, context: String, namespace: String, pod: String, container: Option<String>, cx: &mut Context<Self>) {
+        tracing::info!("View logs of pod: {}:{}:{} ({:?})", context, namespace, pod, container);
+        if let Some(app_state) = cx.try_global::<AppState>() {
+            let runtime = app_state.tokio_runtime.clone();
+            let mut app = app_state.app.lock();
+            if let Err(e) = app.open_pod_logs(&context, &namespace, &pod, container, self.follow_logs, &runtime) {
+                tracing::error!("Failed to open pod logs: {}", e);
+            }
+        }
+        cx.notify();
+    }
+
     /// Handle clicking on a group header
     fn handle_toggle_group(&mut self, group_id: Uuid, cx: &mut Context<Self>) {
         self.state.toggle_expanded(group_id);
@@ -497,21 +748,14 @@ impl SessionTree {
     }
 
     /// Handle clicking on a session
-    fn handle_open_session(&mut self, session_id: Uuid, cx: &mut Context<Self>) {
+    ///
+    /// With `force_prompt`, an SSH session's saved password or passphrase is
+    /// ignored and asked for instead.
+    fn handle_open_session(&mut self, session_id: Uuid, force_prompt: bool, cx: &mut Context<Self>) {
         if let Some(app_state) = cx.try_global::<AppState>() {
             let runtime = app_state.tokio_runtime.clone();
-            let mut app = app_state.app.lock();
-            // Check session type and call appropriate method
-            if let Some(session) = app.session_manager.get_session(session_id) {
-                let result = match session {
-                    Session::Ssh(_) => app.open_ssh_session(session_id, &runtime),
-                    Session::Ssm(_) => app.open_ssm_session(session_id, &runtime),
-                    Session::Local(_) => app.open_local_terminal(),
-                    Session::K8s(_) => app.open_k8s_session(session_id, &runtime),
-                };
-                if let Err(e) = result {
-                    tracing::error!("Failed to open session: {}", e);
-                }
+            if let Err(e) = app_state.app.lock().open_session(session_id, &runtime, force_prompt) {
+                tracing::error!("Failed to open session: {}", e);
             }
         }
         cx.emit(SessionTreeEvent::OpenSession(session_id));
@@ -532,6 +776,33 @@ impl SessionTree {
         cx.notify();
     }
 
+    /// Open every session in a multi-selection
+    fn handle_connect_sessions(&mut self, ids: Vec<Uuid>, cx: &mut Context<Self>) {
+        for id in ids {
+            self.handle_open_session(id, false, cx);
+        }
+    }
+
+    /// Move sessions into a group, or out of any group with `None`
+    fn handle_move_sessions(&mut self, ids: Vec<Uuid>, group_id: Option<Uuid>, cx: &mut Context<Self>) {
+        if let Some(gid) = group_id {
+            self.state.expand(gid);
+        }
+        if let Some(app_state) = cx.try_global::<AppState>() {
+            let mut app = app_state.app.lock();
+            for id in ids {
+                if let Err(e) = app.session_manager.move_session_to_group(id, group_id) {
+                    tracing::error!("Failed to move session: {}", e);
+                }
+            }
+            if let Err(e) = app.save() {
+                tracing::error!("Failed to save moved sessions: {}", e);
+            }
+        }
+        self.context_menu = None;
+        cx.notify();
+    }
+
     /// Handle clicking the new session button - just set flag for later
     fn request_new_session(&mut self, group_id: Option<Uuid>, cx: &mut Context<Self>) {
         if let Some(gid) = group_id {
@@ -558,6 +829,31 @@ impl SessionTree {
         cx.notify();
     }
 
+    /// Duplicate a session of any type, keeping its group and settings
+    fn handle_duplicate_session(&mut self, session_id: Uuid, cx: &mut Context<Self>) {
+        if let Some(app_state) = cx.try_global::<AppState>() {
+            let mut app = app_state.app.lock();
+            match app.session_manager.duplicate_session(session_id) {
+                Ok(_) => {
+                    if let Err(e) = app.save() {
+                        tracing::error!("Failed to save duplicated session: {}", e);
+                    }
+                }
+                Err(e) => tracing::error!("Failed to duplicate session: {}", e),
+            }
+        }
+        self.context_menu = None;
+        cx.emit(SessionTreeEvent::SessionCreated);
+        cx.notify();
+    }
+
+    /// Request a new SSH session dialog pre-filled from an SSM session
+    fn request_ssh_from_ssm(&mut self, session_id: Uuid, cx: &mut Context<Self>) {
+        self.pending_ssh_from_ssm = Some(session_id);
+        self.context_menu = None;
+        cx.notify();
+    }
+
     /// Request edit group dialog
     fn request_edit_group(&mut self, group_id: Uuid, cx: &mut Context<Self>) {
         tracing::info!("request_edit_group called for: {}", group_id);
@@ -580,13 +876,114 @@ impl SessionTree {
         cx.notify();
     }
 
+    /// Request confirmation to delete several sessions at once
+    fn request_delete_sessions(&mut self, ids: Vec<Uuid>, cx: &mut Context<Self>) {
+        self.pending_delete_sessions = Some(ids);
+        self.context_menu = None;
+        cx.notify();
+    }
+
+    /// Request the run-command dialog for the SSH sessions among `ids`
+    fn request_run_command(&mut self, ids: Vec<Uuid>, cx: &mut Context<Self>) {
+        let targets: Vec<(Uuid, String)> = cx
+            .try_global::<AppState>()
+            .map(|app_state| {
+                let app = app_state.app.lock();
+                ids.iter()
+                    .filter_map(|&id| match app.session_manager.get_session(id) {
+                        Some(Session::Ssh(ssh)) => Some((id, ssh.name.clone())),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !targets.is_empty() {
+            self.pending_run_command = Some(targets);
+        }
+        self.context_menu = None;
+        cx.notify();
+    }
+
+    /// Request the run-command dialog for every SSH session under a group
+    fn request_run_command_in_group(&mut self, group_id: Uuid, cx: &mut Context<Self>) {
+        let ids = cx
+            .try_global::<AppState>()
+            .map(|app_state| app_state.app.lock().session_manager.get_all_sessions_in_group_recursive(group_id))
+            .unwrap_or_default();
+        self.request_run_command(ids, cx);
+    }
+
+    /// Request confirmation to forget an SSH host's known_hosts entries
+    fn request_forget_host_key(&mut self, host: String, cx: &mut Context<Self>) {
+        self.pending_forget_host_key = Some(host);
+        self.context_menu = None;
+        cx.notify();
+    }
+
     /// Show context menu for a target
     fn show_context_menu(&mut self, position: Point<Pixels>, target: ContextMenuTarget, cx: &mut Context<Self>) {
         tracing::info!("show_context_menu called at position: {:?}, target: {:?}", position, target);
-        self.context_menu = Some(ContextMenuState { position, target });
+        self.context_menu = Some(ContextMenuState { position, target, show_move_targets: false });
         cx.notify();
     }
 
+    /// Selected sessions, in tree order
+    fn selected_session_ids(&self) -> Vec<Uuid> {
+        self.visible_sessions
+            .iter()
+            .filter(|item| self.state.is_selected(item))
+            .filter_map(|item| match item {
+                TreeItem::Session(id) => Some(*id),
+                TreeItem::Group(_) => None,
+            })
+            .collect()
+    }
+
+    /// Show the session menu, or the selection menu if the session is part of one
+    fn show_session_context_menu(&mut self, position: Point<Pixels>, id: Uuid, name: String, cx: &mut Context<Self>) {
+        let item = TreeItem::Session(id);
+        if !self.state.is_selected(&item) {
+            self.state.select_only(item);
+        }
+        let ids = self.selected_session_ids();
+        let target = if ids.len() > 1 {
+            ContextMenuTarget::Selection { ids }
+        } else {
+            ContextMenuTarget::Session { id, name }
+        };
+        self.show_context_menu(position, target, cx);
+    }
+
+    /// Expand or collapse the "Move to Group" list in the open menu
+    fn toggle_move_targets(&mut self, cx: &mut Context<Self>) {
+        if let Some(menu) = &mut self.context_menu {
+            menu.show_move_targets = !menu.show_move_targets;
+        }
+        cx.notify();
+    }
+
+    /// Whether a group's contents are drawn; searching opens every group left
+    fn is_group_open(&self, group_id: Uuid) -> bool {
+        !self.session_filter.is_empty() || self.state.is_expanded(group_id)
+    }
+
+    /// Sessions in the order they're drawn, skipping collapsed groups
+    fn collect_visible_sessions(&self, data: &TreeRenderData) -> Vec<TreeItem> {
+        let mut items = Vec::new();
+        let mut stack: Vec<Uuid> = data.top_level_groups().map(|g| g.id).collect();
+        stack.reverse();
+        while let Some(group_id) = stack.pop() {
+            if !self.is_group_open(group_id) {
+                continue;
+            }
+            items.extend(data.sessions_in_group(group_id).map(|s| TreeItem::Session(s.id())));
+            let children: Vec<Uuid> = data.child_groups(group_id).map(|g| g.id).collect();
+            stack.extend(children.into_iter().rev());
+        }
+        items.extend(data.ungrouped_sessions().map(|s| TreeItem::Session(s.id())));
+        items
+    }
+
     /// Close context menu
     fn close_context_menu(&mut self, cx: &mut Context<Self>) {
         self.context_menu = None;
@@ -619,6 +1016,12 @@ impl SessionTree {
             .on_click(cx.listener(move |this, _event, _window, cx| {
                 this.handle_toggle_group(group_id, cx);
             }))
+            .drag_over::<DraggedSessions>(|style, _, _, _| {
+                style.bg(rgb(0x45475a)).border_1().border_color(rgb(0x89b4fa))
+            })
+            .on_drop(cx.listener(move |this, dragged: &DraggedSessions, _window, cx| {
+                this.handle_move_sessions(dragged.ids.clone(), Some(group_id), cx);
+            }))
             .on_mouse_up(MouseButton::Right, cx.listener(move |this, event: &MouseUpEvent, _window, cx| {
                 cx.stop_propagation();
                 let target = ContextMenuTarget::Group { id: group_id, name: group_name_for_menu.clone() };
@@ -660,11 +1063,27 @@ impl SessionTree {
         let session_id = session.id();
         let session_name = session.name().to_string();
         let session_name_for_menu = session.name().to_string();
+        let is_selected = self.state.is_selected(&TreeItem::Session(session_id));
+        let group_id = session.group_id();
+        let last_connected = session.last_connected().map(|time| relative_time(time, SystemTime::now()));
+        // Dragging a selected session takes the rest of the selection with it
+        let dragged = match self.selected_session_ids() {
+            ids if is_selected && ids.len() > 1 => DraggedSessions {
+                label: format!("{} sessions", ids.len()),
+                ids,
+            },
+            _ => DraggedSessions {
+                ids: vec![session_id],
+                label: session_name.clone(),
+            },
+        };
         let icon = match session {
             Session::Ssh(_) => "🖥️",
             Session::Local(_) => "💻",
             Session::Ssm(_) => "☁️",
             Session::K8s(_) => "⎈",
+            Session::Serial(_) => "🔌",
+            Session::Telnet(_) => "📟",
         };
 
         div()
@@ -678,14 +1097,32 @@ impl SessionTree {
             .ml(px(indent))
             .rounded_sm()
             .cursor_pointer()
-            .hover(|style| style.bg(rgb(0x313244)))
-            .on_click(cx.listener(move |this, _event, _window, cx| {
-                this.handle_open_session(session_id, cx);
+            .when(is_selected, |this| this.bg(rgb(0x45475a)))
+            .when(!is_selected, |this| this.hover(|style| style.bg(rgb(0x313244))))
+            // Ctrl/Cmd-click and shift-click build a selection instead of connecting
+            .on_click(cx.listener(move |this, _event, window, cx| {
+                let item = TreeItem::Session(session_id);
+                let modifiers = window.modifiers();
+                if modifiers.shift {
+                    this.state.select_range(item, &this.visible_sessions);
+                    cx.notify();
+                } else if modifiers.secondary() {
+                    this.state.toggle_selected(item);
+                    cx.notify();
+                } else {
+                    this.state.select_only(item);
+                    this.handle_open_session(session_id, false, cx);
+                }
             }))
             .on_mouse_up(MouseButton::Right, cx.listener(move |this, event: &MouseUpEvent, _window, cx| {
                 cx.stop_propagation();
-                let target = ContextMenuTarget::Session { id: session_id, name: session_name_for_menu.clone() };
-                this.show_context_menu(event.position, target, cx);
+                this.show_session_context_menu(event.position, session_id, session_name_for_menu.clone(), cx);
+            }))
+            .on_drag(dragged, |dragged, _offset, _window, cx| cx.new(|_| dragged.clone()))
+            // Dropping onto a session moves into that session's group
+            .drag_over::<DraggedSessions>(|style, _, _, _| style.bg(rgb(0x313244)))
+            .on_drop(cx.listener(move |this, dragged: &DraggedSessions, _window, cx| {
+                this.handle_move_sessions(dragged.ids.clone(), group_id, cx);
             }))
             .child(
                 div()
@@ -700,6 +1137,102 @@ impl SessionTree {
                             .child(session_name),
                     ),
             )
+            .when_some(last_connected, |el, age| {
+                el.child(div().flex_none().text_xs().text_color(rgb(0x6c7086)).child(age))
+            })
+    }
+
+    /// Groups that sessions can be moved into, in tree order with their depth
+    fn move_target_groups(cx: &Context<Self>) -> Vec<(Uuid, String, usize)> {
+        let Some(app_state) = cx.try_global::<AppState>() else {
+            return Vec::new();
+        };
+        let app = app_state.app.lock();
+        let groups = app.session_manager.all_groups();
+        let mut targets = Vec::new();
+        let mut stack: Vec<(&SessionGroup, usize)> = groups
+            .iter()
+            .filter(|g| g.parent_id.is_none())
+            .rev()
+            .map(|g| (g, 0))
+            .collect();
+        while let Some((group, depth)) = stack.pop() {
+            targets.push((group.id, group.name.clone(), depth));
+            stack.extend(
+                groups
+                    .iter()
+                    .filter(|g| g.parent_id == Some(group.id))
+                    .rev()
+                    .map(|g| (g, depth + 1)),
+            );
+        }
+        targets
+    }
+
+    /// "Move to Group" menu entry, with the destination list when expanded
+    fn render_move_to_group(&self, ids: Vec<Uuid>, expanded: bool, cx: &mut Context<Self>) -> Div {
+        let mut section = div().flex().flex_col().child(
+            div()
+                .id("ctx-move-to-group")
+                .px_3()
+                .py_1()
+                .cursor_pointer()
+                .hover(|s| s.bg(rgb(0x45475a)))
+                .on_click(cx.listener(|this, _event, _window, cx| {
+                    this.toggle_move_targets(cx);
+                }))
+                .child(
+                    div()
+                        .text_sm()
+                        .text_color(rgb(0xcdd6f4))
+                        .child(if expanded { "Move to Group ▾" } else { "Move to Group ▸" }),
+                ),
+        );
+        if !expanded {
+            return section;
+        }
+
+        let ungrouped_ids = ids.clone();
+        section = section.child(
+            div()
+                .id("ctx-move-ungrouped")
+                .pl_6()
+                .pr_3()
+                .py_1()
+                .cursor_pointer()
+                .hover(|s| s.bg(rgb(0x45475a)))
+                .on_click(cx.listener(move |this, _event, _window, cx| {
+                    this.handle_move_sessions(ungrouped_ids.clone(), None, cx);
+                }))
+                .child(
+                    div()
+                        .text_sm()
+                        .text_color(rgb(0x6c7086))
+                        .child("Ungrouped"),
+                ),
+        );
+        for (group_id, name, depth) in Self::move_target_groups(cx) {
+            let ids = ids.clone();
+            section = section.child(
+                div()
+                    .id(ElementId::Name(format!("ctx-move-{}", group_id).into()))
+                    .pl(px(24.0 + depth as f32 * 12.0))
+                    .pr_3()
+                    .py_1()
+                    .cursor_pointer()
+                    .hover(|s| s.bg(rgb(0x45475a)))
+                    .on_click(cx.listener(move |this, _event, _window, cx| {
+                        this.handle_move_sessions(ids.clone(), Some(group_id), cx);
+                    }))
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(rgb(0xcdd6f4))
+                            .child(name),
+                    ),
+            );
+        }
+        section
     }
 
     fn render_context_menu(&self, menu: &ContextMenuState, cx: &mut Context<Self>) -> impl IntoElement {
@@ -765,6 +1298,23 @@ impl SessionTree {
                                     .child("Connect All"),
                             ),
                     )
+                    .child(
+                        div()
+                            .id("ctx-run-command-group")
+                            .px_3()
+                            .py_1()
+                            .cursor_pointer()
+                            .hover(|s| s.bg(rgb(0x45475a)))
+                            .on_click(cx.listener(move |this, _event, _window, cx| {
+                                this.request_run_command_in_group(group_id, cx);
+                            }))
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .text_color(rgb(0xcdd6f4))
+                                    .child("Run Command…"),
+                            ),
+                    )
                     .child(
                         div()
                             .id("ctx-add-session")
@@ -830,12 +1380,25 @@ impl SessionTree {
             ContextMenuTarget::Session { id, name } => {
                 let session_id = *id;
                 let session_name_delete = name.clone();
+                let (is_ssm, is_ssh, ssh_host, has_saved_secret) = cx
+                    .try_global::<AppState>()
+                    .map(|app_state| match app_state.app.lock().session_manager.get_session(session_id) {
+                        Some(Session::Ssm(_)) => (true, false, None, false),
+                        Some(Session::Ssh(ssh)) => (
+                            false,
+                            true,
+                            Some(ssh.host.clone()).filter(|host| !host.is_empty()),
+                            ssh.auth.has_saved_secret(),
+                        ),
+                        _ => (false, false, None, false),
+                    })
+                    .unwrap_or((false, false, None, false));
 
                 div()
                     .absolute()
                     .left(x)
                     .top(y)
-                    .w(px(160.0))
+                    .w(px(180.0))
                     .bg(rgb(0x313244))
                     .border_1()
                     .border_color(rgb(0x45475a))
@@ -850,7 +1413,7 @@ impl SessionTree {
                             .cursor_pointer()
                             .hover(|s| s.bg(rgb(0x45475a)))
                             .on_click(cx.listener(move |this, _event, _window, cx| {
-                                this.handle_open_session(session_id, cx);
+                                this.handle_open_session(session_id, false, cx);
                                 this.close_context_menu(cx);
                             }))
                             .child(
@@ -860,6 +1423,47 @@ impl SessionTree {
                                     .child("Connect"),
                             ),
                     )
+                    // Type the secret fresh, e.g. on a machine it shouldn't be used from
+                    .when(has_saved_secret, |menu| {
+                        menu.child(
+                            div()
+                                .id("ctx-connect-prompt")
+                                .px_3()
+                                .py_1()
+                                .cursor_pointer()
+                                .hover(|s| s.bg(rgb(0x45475a)))
+                                .on_click(cx.listener(move |this, _event, _window, cx| {
+                                    this.handle_open_session(session_id, true, cx);
+                                    this.close_context_menu(cx);
+                                }))
+                                .child(
+                                    div()
+                                        .text_sm()
+                                        .text_color(rgb(0xcdd6f4))
+                                        .child("Connect, Ask Password"),
+                                ),
+                        )
+                    })
+                    // One-off command without a PTY or a tab
+                    .when(is_ssh, |menu| {
+                        menu.child(
+                            div()
+                                .id("ctx-run-command")
+                                .px_3()
+                                .py_1()
+                                .cursor_pointer()
+                                .hover(|s| s.bg(rgb(0x45475a)))
+                                .on_click(cx.listener(move |this, _event, _window, cx| {
+                                    this.request_run_command(vec![session_id], cx);
+                                }))
+                                .child(
+                                    div()
+                                        .text_sm()
+                                        .text_color(rgb(0xcdd6f4))
+                                        .child("Run Command…"),
+                                ),
+                        )
+                    })
                     .child(
                         div()
                             .id("ctx-edit-session")
@@ -877,6 +1481,62 @@ impl SessionTree {
                                     .child("Edit Session"),
                             ),
                     )
+                    .child(
+                        div()
+                            .id("ctx-duplicate-session")
+                            .px_3()
+                            .py_1()
+                            .cursor_pointer()
+                            .hover(|s| s.bg(rgb(0x45475a)))
+                            .on_click(cx.listener(move |this, _event, _window, cx| {
+                                this.handle_duplicate_session(session_id, cx);
+                            }))
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .text_color(rgb(0xcdd6f4))
+                                    .child("Duplicate"),
+                            ),
+                    )
+                    .when(is_ssm, |menu| {
+                        menu.child(
+                            div()
+                                .id("ctx-ssh-from-ssm")
+                                .px_3()
+                                .py_1()
+                                .cursor_pointer()
+                                .hover(|s| s.bg(rgb(0x45475a)))
+                                .on_click(cx.listener(move |this, _event, _window, cx| {
+                                    this.request_ssh_from_ssm(session_id, cx);
+                                }))
+                                .child(
+                                    div()
+                                        .text_sm()
+                                        .text_color(rgb(0xcdd6f4))
+                                        .child("Create SSH Session"),
+                                ),
+                        )
+                    })
+                    .when_some(ssh_host, |menu, host| {
+                        menu.child(
+                            div()
+                                .id("ctx-forget-host-key")
+                                .px_3()
+                                .py_1()
+                                .cursor_pointer()
+                                .hover(|s| s.bg(rgb(0x45475a)))
+                                .on_click(cx.listener(move |this, _event, _window, cx| {
+                                    this.request_forget_host_key(host.clone(), cx);
+                                }))
+                                .child(
+                                    div()
+                                        .text_sm()
+                                        .text_color(rgb(0xcdd6f4))
+                                        .child("Forget Host Key"),
+                                ),
+                        )
+                    })
+                    .child(self.render_move_to_group(vec![session_id], menu.show_move_targets, cx))
                     // Separator
                     .child(
                         div()
@@ -903,6 +1563,211 @@ impl SessionTree {
                             ),
                     )
             }
+            ContextMenuTarget::Selection { ids } => {
+                let count = ids.len();
+                let connect_ids = ids.clone();
+                let run_ids = ids.clone();
+                let delete_ids = ids.clone();
+
+                div()
+                    .absolute()
+                    .left(x)
+                    .top(y)
+                    .w(px(180.0))
+                    .bg(rgb(0x313244))
+                    .border_1()
+                    .border_color(rgb(0x45475a))
+                    .rounded_md()
+                    .shadow_lg()
+                    .py_1()
+                    .child(
+                        div()
+                            .px_3()
+                            .py_1()
+                            .text_xs()
+                            .text_color(rgb(0x6c7086))
+                            .child(format!("{} sessions selected", count)),
+                    )
+                    .child(
+                        div()
+                            .id("ctx-connect-selected")
+                            .px_3()
+                            .py_1()
+                            .cursor_pointer()
+                            .hover(|s| s.bg(rgb(0x45475a)))
+                            .on_click(cx.listener(move |this, _event, _window, cx| {
+                                this.handle_connect_sessions(connect_ids.clone(), cx);
+                                this.close_context_menu(cx);
+                            }))
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .text_color(rgb(0xcdd6f4))
+                                    .child("Connect All"),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .id("ctx-run-command-selected")
+                            .px_3()
+                            .py_1()
+                            .cursor_pointer()
+                            .hover(|s| s.bg(rgb(0x45475a)))
+                            .on_click(cx.listener(move |this, _event, _window, cx| {
+                                this.request_run_command(run_ids.clone(), cx);
+                            }))
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .text_color(rgb(0xcdd6f4))
+                                    .child("Run Command…"),
+                            ),
+                    )
+                    .child(self.render_move_to_group(ids.clone(), menu.show_move_targets, cx))
+                    // Separator
+                    .child(
+                        div()
+                            .h(px(1.0))
+                            .mx_2()
+                            .my_1()
+                            .bg(rgb(0x45475a)),
+                    )
+                    .child(
+                        div()
+                            .id("ctx-delete-selected")
+                            .px_3()
+                            .py_1()
+                            .cursor_pointer()
+                            .hover(|s| s.bg(rgb(0x45475a)))
+                            .on_click(cx.listener(move |this, _event, _window, cx| {
+                                this.request_delete_sessions(delete_ids.clone(), cx);
+                            }))
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .text_color(rgb(0xf38ba8))
+                                    .child(format!("Delete {} Sessions", count)),
+                            ),
+                    )
+            }
+            ContextMenuTarget::Containers { context, namespace, pod, containers } => {
+                let mut menu = div()
+                    .absolute()
+                    .left(x)
+                    .top(y)
+                    .w(px(160.0))
+                    .bg(rgb(0x313244))
+                    .border_1()
+                    .border_color(rgb(0x45475a))
+                    .rounded_md()
+                    .shadow_lg()
+                    .py_1()
+                    .child(
+                        div()
+                            .px_3()
+                            .py_1()
+                            .text_xs()
+                            .text_color(rgb(0x6c7086))
+                            .child("Exec into container"),
+                    );
+                for container in containers {
+                    let (ctx, ns, pod_name, name) = (context.clone(), namespace.clone(), pod.clone(), container.clone());
+                    menu = menu.child(
+                        div()
+                            .id(ElementId::Name(format!("ctx-container-{}", container).into()))
+                            .px_3()
+                            .py_1()
+                            .cursor_pointer()
+                            .hover(|s| s.bg(rgb(0x45475a)))
+                            .on_click(cx.listener(move |this, _event, _window, cx| {
+                                this.close_context_menu(cx);
+                                this.handle_pod_exec(ctx.clone(), ns.clone(), pod_name.clone(), Some(name.clone()), cx);
+                            }))
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .text_color(rgb(0xcdd6f4))
+                                    .child(container.clone()),
+                            ),
+                    );
+                }
+                menu
+            }
+            ContextMenuTarget::Pod { context, namespace, pod, containers } => {
+                let follow = self.follow_logs;
+                let mut menu = div()
+                    .absolute()
+                    .left(x)
+                    .top(y)
+                    .w(px(160.0))
+                    .bg(rgb(0x313244))
+                    .border_1()
+                    .border_color(rgb(0x45475a))
+                    .rounded_md()
+                    .shadow_lg()
+                    .py_1();
+                // One entry per container when there's a choice
+                let entries: Vec<(String, Option<String>)> = if containers.len() > 1 {
+                    containers.iter().map(|c| (format!("Logs: {}", c), Some(c.clone()))).collect()
+                } else {
+                    vec![("View Logs".to_string(), containers.first().cloned())]
+                };
+                for (label, container) in entries {
+                    let (ctx, ns, pod_name) = (context.clone(), namespace.clone(), pod.clone());
+                    menu = menu.child(
+                        div()
+                            .id(ElementId::Name(format!("ctx-logs-{}", label).into()))
+                            .px_3()
+                            .py_1()
+                            .cursor_pointer()
+                            .hover(|s| s.bg(rgb(0x45475a)))
+                            .on_click(cx.listener(move |this, _event, _window, cx| {
+                                this.close_context_menu(cx);
+                                this.handle_pod_logs(ctx.clone(), ns.clone(), pod_name.clone(), container.clone(), cx);
+                            }))
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .text_color(rgb(0xcdd6f4))
+                                    .child(label),
+                            ),
+                    );
+                }
+                menu.child(
+                    div()
+                        .h(px(1.0))
+                        .mx_2()
+                        .my_1()
+                        .bg(rgb(0x45475a)),
+                )
+                .child(
+                    div()
+                        .id("ctx-follow-logs")
+                        .flex()
+                        .items_center()
+                        .gap_2()
+                        .px_3()
+                        .py_1()
+                        .cursor_pointer()
+                        .hover(|s| s.bg(rgb(0x45475a)))
+                        .on_click(cx.listener(|this, _event, _window, cx| {
+                            this.follow_logs = !this.follow_logs;
+                            cx.notify();
+                        }))
+                        .child(
+                            div()
+                                .text_sm()
+                                .text_color(rgb(0x89b4fa))
+                                .child(if follow { "☑" } else { "☐" }),
+                        )
+                        .child(
+                            div()
+                                .text_sm()
+                                .text_color(rgb(0xcdd6f4))
+                                .child("Follow new lines"),
+                        ),
+                )
+            }
         }
     }
 
@@ -914,7 +1779,7 @@ impl SessionTree {
         depth: usize,
         cx: &mut Context<Self>,
     ) -> Div {
-        let is_expanded = self.state.is_expanded(group.id);
+        let is_expanded = self.is_group_open(group.id);
         let group_id = group.id;
         let group_indent = (depth as f32) * 12.0;
         let session_indent = group_indent + 16.0;
@@ -946,6 +1811,17 @@ impl SessionTree {
     fn render_tree_content(&self, data: &TreeRenderData, cx: &mut Context<Self>) -> Div {
         let mut content = div().flex().flex_col().gap_1();
 
+        if data.is_empty() && !self.session_filter.is_empty() {
+            content = content.child(
+                div()
+                    .px_2()
+                    .py_1()
+                    .text_xs()
+                    .text_color(rgb(0x6c7086))
+                    .child("No matching sessions"),
+            );
+        }
+
         // Render top-level groups recursively
         for group in data.top_level_groups() {
             content = content.child(self.render_group_recursive(data, group, 0, cx));
@@ -1033,6 +1909,43 @@ impl SessionTree {
 
         // Render contexts if expanded
         if k8s_expanded {
+            let has_filter = !self.k8s_filter.is_empty();
+            section = section.child(
+                div()
+                    .ml(px(12.0))
+                    .px_2()
+                    .py_1()
+                    .flex()
+                    .items_center()
+                    .gap_1()
+                    .child(
+                        div()
+                            .flex_1()
+                            .px_2()
+                            .py_0p5()
+                            .rounded_sm()
+                            .bg(rgb(0x313244))
+                            .text_xs()
+                            .child(self.k8s_filter_field.clone()),
+                    )
+                    .when(has_filter, |el| {
+                        el.child(
+                            div()
+                                .id("k8s-filter-clear")
+                                .px_1()
+                                .text_xs()
+                                .text_color(rgb(0x6c7086))
+                                .cursor_pointer()
+                                .hover(|style| style.text_color(rgb(0xcdd6f4)))
+                                .on_click(cx.listener(|this, _event, _window, cx| {
+                                    this.k8s_filter_field.update(cx, |field, _cx| field.set_content(""));
+                                    this.k8s_filter.clear();
+                                    cx.notify();
+                                }))
+                                .child("✕"),
+                        )
+                    }),
+            );
             for context in &config.contexts {
                 section = section.child(self.render_k8s_context(context, config, cx));
             }
@@ -1095,8 +2008,19 @@ impl SessionTree {
         // Show namespaces if expanded
         if is_expanded {
             if let Some(namespaces) = self.k8s_namespaces.get(&context.name) {
-                for ns in namespaces {
+                let mut shown = 0;
+                for ns in namespaces.iter().filter(|ns| self.k8s_namespace_visible(&context.name, &ns.name)) {
                     container = container.child(self.render_k8s_namespace(&context.name, ns, cx));
+                    shown += 1;
+                }
+                if shown == 0 && !namespaces.is_empty() {
+                    container = container.child(
+                        div()
+                            .ml(px(24.0))
+                            .text_xs()
+                            .text_color(rgb(0x6c7086))
+                            .child("No matching namespaces or pods")
+                    );
                 }
             } else if is_loading {
                 container = container.child(
@@ -1180,7 +2104,9 @@ impl SessionTree {
                             .child("No pods")
                     );
                 } else {
-                    for pod in pods {
+                    // A namespace that matches by name shows all its pods
+                    let show_all = self.k8s_filter_matches(&namespace.name);
+                    for pod in pods.iter().filter(|pod| show_all || self.k8s_filter_matches(&pod.name)) {
                         container = container.child(self.render_k8s_pod(&ctx, &namespace.name, pod, cx));
                     }
                 }
@@ -1203,7 +2129,13 @@ impl SessionTree {
         let ctx = context.to_string();
         let ns = namespace.to_string();
         let pod_name = pod.name.clone();
-        let container = pod.containers.first().cloned();
+        let containers = pod.containers.clone();
+        let menu_target = ContextMenuTarget::Pod {
+            context: context.to_string(),
+            namespace: namespace.to_string(),
+            pod: pod.name.clone(),
+            containers: pod.containers.clone(),
+        };
 
         // Color based on status
         let status_color = match pod.status.as_str() {
@@ -1225,8 +2157,23 @@ impl SessionTree {
             .rounded_sm()
             .cursor_pointer()
             .hover(|style| style.bg(rgb(0x313244)))
-            .on_click(cx.listener(move |this, _event, _window, cx| {
-                this.handle_pod_exec(ctx.clone(), ns.clone(), pod_name.clone(), container.clone(), cx);
+            .on_mouse_up(MouseButton::Left, cx.listener(move |this, event: &MouseUpEvent, _window, cx| {
+                // Side-car pods ask which container; others exec straight in
+                if containers.len() > 1 {
+                    let target = ContextMenuTarget::Containers {
+                        context: ctx.clone(),
+                        namespace: ns.clone(),
+                        pod: pod_name.clone(),
+                        containers: containers.clone(),
+                    };
+                    this.show_context_menu(event.position, target, cx);
+                } else {
+                    this.handle_pod_exec(ctx.clone(), ns.clone(), pod_name.clone(), containers.first().cloned(), cx);
+                }
+            }))
+            .on_mouse_up(MouseButton::Right, cx.listener(move |this, event: &MouseUpEvent, _window, cx| {
+                cx.stop_propagation();
+                this.show_context_menu(event.position, menu_target.clone(), cx);
             }))
             .child(
                 div()
@@ -1271,6 +2218,10 @@ impl Render for SessionTree {
             tracing::info!("Edit session requested for: {}", session_id);
             let mut ssh_session_to_edit: Option<SshSession> = None;
             let mut ssm_session_to_edit: Option<SsmSession> = None;
+            let mut serial_session_to_edit: Option<SerialSession> = None;
+            let mut telnet_session_to_edit: Option<TelnetSession> = None;
+            let mut k8s_session_to_edit: Option<K8sSession> = None;
+            let mut local_session_to_edit: Option<LocalSession> = None;
             if let Some(app_state) = cx.try_global::<AppState>() {
                 let app = app_state.app.lock();
                 if let Some(session) = app.session_manager.get_session(session_id) {
@@ -1282,11 +2233,17 @@ impl Render for SessionTree {
                         Session::Ssm(ssm_session) => {
                             ssm_session_to_edit = Some(ssm_session.clone());
                         }
-                        Session::Local(_) => {
-                            tracing::info!("Local sessions don't have edit dialogs yet");
+                        Session::Local(local_session) => {
+                            local_session_to_edit = Some(local_session.clone());
+                        }
+                        Session::K8s(k8s_session) => {
+                            k8s_session_to_edit = Some(k8s_session.clone());
+                        }
+                        Session::Serial(serial_session) => {
+                            serial_session_to_edit = Some(serial_session.clone());
                         }
-                        Session::K8s(_) => {
-                            tracing::info!("K8s sessions don't have edit dialogs yet");
+                        Session::Telnet(telnet_session) => {
+                            telnet_session_to_edit = Some(telnet_session.clone());
                         }
                     }
                 } else {
@@ -1305,6 +2262,42 @@ impl Render for SessionTree {
                 cx.defer(move |cx| {
                     SessionDialog::open_edit_ssm(session, cx);
                 });
+            } else if let Some(session) = serial_session_to_edit {
+                tracing::info!("Opening edit dialog for serial session");
+                cx.defer(move |cx| {
+                    SessionDialog::open_edit_serial(session, cx);
+                });
+            } else if let Some(session) = telnet_session_to_edit {
+                tracing::info!("Opening edit dialog for telnet session");
+                cx.defer(move |cx| {
+                    SessionDialog::open_edit_telnet(session, cx);
+                });
+            } else if let Some(session) = k8s_session_to_edit {
+                tracing::info!("Opening edit dialog for K8s session");
+                cx.defer(move |cx| {
+                    SessionDialog::open_edit_k8s(session, cx);
+                });
+            } else if let Some(session) = local_session_to_edit {
+                tracing::info!("Opening edit dialog for local session");
+                cx.defer(move |cx| {
+                    SessionDialog::open_edit_local(session, cx);
+                });
+            }
+        }
+
+        // Handle pending SSH-from-SSM request
+        if let Some(session_id) = self.pending_ssh_from_ssm.take() {
+            let ssh_session = cx.try_global::<AppState>().and_then(|app_state| {
+                match app_state.app.lock().session_manager.get_session(session_id) {
+                    // SSM only knows the instance ID; the host is filled in by the user
+                    Some(Session::Ssm(ssm)) => Some(SshSession::from_ssm(ssm, "", "")),
+                    _ => None,
+                }
+            });
+            if let Some(session) = ssh_session {
+                cx.defer(move |cx| {
+                    SessionDialog::open_prefilled(session, cx);
+                });
             }
         }
 
@@ -1338,6 +2331,20 @@ impl Render for SessionTree {
             });
         }
 
+        // Handle pending bulk delete request
+        if let Some(ids) = self.pending_delete_sessions.take() {
+            cx.defer(move |cx| {
+                DeleteConfirmDialog::open_for_sessions(ids, cx);
+            });
+        }
+
+        // Handle pending run command request
+        if let Some(targets) = self.pending_run_command.take() {
+            cx.defer(move |cx| {
+                RunCommandDialog::open(targets, cx);
+            });
+        }
+
         // Handle pending delete group request
         if let Some((id, name)) = self.pending_delete_group.take() {
             cx.defer(move |cx| {
@@ -1345,15 +2352,40 @@ impl Render for SessionTree {
             });
         }
 
+        // Handle pending forget host key request
+        if let Some(host) = self.pending_forget_host_key.take() {
+            cx.defer(move |cx| {
+                DeleteConfirmDialog::open_for_host_key(host, cx);
+            });
+        }
+
+        let sort = cx
+            .try_global::<AppState>()
+            .map(|app_state| app_state.app.lock().config.session_tree.sort)
+            .unwrap_or_default();
+
         // Get data from app state (clone it to avoid borrow conflicts)
         let render_data = cx.try_global::<AppState>().map(|app_state| {
             let app = app_state.app.lock();
-            TreeRenderData {
+            let mut data = TreeRenderData {
                 groups: app.session_manager.all_groups().to_vec(),
                 sessions: app.session_manager.all_sessions().to_vec(),
+            };
+            if sort == SessionSort::Recent {
+                data.sort_by_recent();
+            }
+            if self.session_filter.is_empty() {
+                data
+            } else {
+                data.filtered(&self.session_filter)
             }
         });
 
+        if let Some(data) = &render_data {
+            self.visible_sessions = self.collect_visible_sessions(data);
+            self.state.retain_visible(&self.visible_sessions);
+        }
+
         // Check if context menu is open
         let has_context_menu = self.context_menu.is_some();
         if has_context_menu {
@@ -1413,6 +2445,55 @@ impl Render for SessionTree {
                         div()
                             .flex()
                             .gap_1()
+                            // Sort toggle: saved order or most recently connected first
+                            .child(
+                                div()
+                                    .id("sort-sessions-btn")
+                                    .px_2()
+                                    .py_1()
+                                    .rounded_sm()
+                                    .cursor_pointer()
+                                    .hover(|style| style.bg(rgb(0x313244)))
+                                    .on_click(cx.listener(move |_this, _event, _window, cx| {
+                                        if let Some(app_state) = cx.try_global::<AppState>() {
+                                            let next = match sort {
+                                                SessionSort::Manual => SessionSort::Recent,
+                                                SessionSort::Recent => SessionSort::Manual,
+                                            };
+                                            app_state.app.lock().set_session_sort(next);
+                                        }
+                                        cx.notify();
+                                    }))
+                                    .child(
+                                        div()
+                                            .text_sm()
+                                            .text_color(if sort == SessionSort::Recent {
+                                                rgb(0x89b4fa)
+                                            } else {
+                                                rgb(0x6c7086)
+                                            })
+                                            .child("🕒"),
+                                    ),
+                            )
+                            // Import sessions button
+                            .child(
+                                div()
+                                    .id("import-sessions-btn")
+                                    .px_2()
+                                    .py_1()
+                                    .rounded_sm()
+                                    .cursor_pointer()
+                                    .hover(|style| style.bg(rgb(0x313244)))
+                                    .on_click(cx.listener(|_this, _event, _window, cx| {
+                                        cx.defer(ImportDialog::open);
+                                    }))
+                                    .child(
+                                        div()
+                                            .text_sm()
+                                            .text_color(rgb(0xf9e2af))
+                                            .child("⇣"),
+                                    ),
+                            )
                             // New group button
                             .child(
                                 div()
@@ -1454,11 +2535,52 @@ impl Render for SessionTree {
                     ),
             )
             .child(
-                // Tree content
+                // Search box
+                div()
+                    .px_2()
+                    .py_1()
+                    .flex()
+                    .items_center()
+                    .gap_1()
+                    .border_b_1()
+                    .border_color(rgb(0x313244))
+                    .child(
+                        div()
+                            .flex_1()
+                            .px_2()
+                            .py_0p5()
+                            .rounded_sm()
+                            .bg(rgb(0x313244))
+                            .text_xs()
+                            .child(self.session_filter_field.clone()),
+                    )
+                    .when(!self.session_filter.is_empty(), |el| {
+                        el.child(
+                            div()
+                                .id("session-filter-clear")
+                                .px_1()
+                                .text_xs()
+                                .text_color(rgb(0x6c7086))
+                                .cursor_pointer()
+                                .hover(|style| style.text_color(rgb(0xcdd6f4)))
+                                .on_click(cx.listener(|this, _event, _window, cx| {
+                                    this.session_filter_field.update(cx, |field, _cx| field.set_content(""));
+                                    this.session_filter.clear();
+                                    cx.notify();
+                                }))
+                                .child("✕"),
+                        )
+                    }),
+            )
+            .child(
+                // Tree content; dropping on empty space takes sessions out of their group
                 div()
                     .flex_1()
                     .overflow_y_hidden()
                     .p_2()
+                    .on_drop(cx.listener(|this, dragged: &DraggedSessions, _window, cx| {
+                        this.handle_move_sessions(dragged.ids.clone(), None, cx);
+                    }))
                     .child(
                         if let Some(data) = render_data {
                             self.render_tree_content(&data, cx)
@@ -1502,3 +2624,42 @@ impl Render for SessionTree {
 pub fn session_tree(cx: &mut App) -> Entity<SessionTree> {
     cx.new(|cx| SessionTree::new(cx))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filtered_keeps_matches_and_their_branches() {
+        let prod = SessionGroup::new("Prod".to_string());
+        let mut web = SessionGroup::new("Web".to_string());
+        web.parent_id = Some(prod.id);
+        let lab = SessionGroup::new("Lab".to_string());
+
+        let mut web_1 = SshSession::new("web-1", "10.0.0.1", "deploy");
+        web_1.group_id = Some(web.id);
+        let mut bastion = SshSession::new("bastion", "10.0.0.2", "deploy");
+        bastion.group_id = Some(prod.id);
+        let mut db = SshSession::new("db", "10.0.0.3", "deploy");
+        db.group_id = Some(lab.id);
+
+        let data = || TreeRenderData {
+            groups: vec![prod.clone(), web.clone(), lab.clone()],
+            sessions: vec![Session::Ssh(web_1.clone()), Session::Ssh(bastion.clone()), Session::Ssh(db.clone())],
+        };
+        let names = |data: &TreeRenderData| {
+            let groups: Vec<_> = data.groups.iter().map(|g| g.name.clone()).collect();
+            let sessions: Vec<_> = data.sessions.iter().map(|s| s.name().to_string()).collect();
+            (groups, sessions)
+        };
+
+        // A session match keeps the groups above it
+        assert_eq!(names(&data().filtered("web-")), (vec!["Prod".into(), "Web".into()], vec!["web-1".into()]));
+        // A group match keeps everything inside it
+        assert_eq!(
+            names(&data().filtered("prod")),
+            (vec!["Prod".into(), "Web".into()], vec!["web-1".into(), "bastion".into()])
+        );
+        assert!(data().filtered("nothing").is_empty());
+    }
+}
@@ -6,7 +6,8 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex as TokioMutex;
 
-use crate::sftp::{DirEntry, EntryType, SftpBrowser, SftpError, TransferProgress, format_size};
+use crate::format::format_size;
+use crate::sftp::{DeletePreview, DirEntry, EntryType, SftpBrowser, SftpError, TransferProgress};
 
 /// Events emitted by SftpPanel
 pub enum SftpPanelEvent {
@@ -16,6 +17,15 @@ pub enum SftpPanelEvent {
 
 impl EventEmitter<SftpPanelEvent> for SftpPanel {}
 
+/// A delete waiting for the user to confirm it
+struct PendingDelete {
+    path: PathBuf,
+    name: String,
+    is_dir: bool,
+    /// What deleting a directory would remove; None while it is being counted
+    preview: Option<DeletePreview>,
+}
+
 /// SFTP panel state
 pub struct SftpPanel {
     /// SFTP browser (wrapped for async access)
@@ -34,6 +44,8 @@ pub struct SftpPanel {
     loading: bool,
     /// Error message
     error: Option<String>,
+    /// Delete shown for confirmation
+    pending_delete: Option<PendingDelete>,
 }
 
 impl SftpPanel {
@@ -47,6 +59,7 @@ impl SftpPanel {
             focus_handle: cx.focus_handle(),
             loading: false,
             error: None,
+            pending_delete: None,
         }
     }
 
@@ -121,6 +134,86 @@ impl SftpPanel {
         }
     }
 
+    /// Ask to delete the selected item
+    ///
+    /// For a directory, its contents are counted first so the confirmation
+    /// can say how much would be removed.
+    fn request_delete(&mut self, cx: &mut Context<Self>) {
+        let Some(entry) = self.selected.and_then(|idx| self.entries.get(idx)) else {
+            return;
+        };
+        let path = self.current_path.join(&entry.name);
+        let is_dir = entry.entry_type == EntryType::Directory;
+        self.pending_delete = Some(PendingDelete {
+            path: path.clone(),
+            name: entry.name.clone(),
+            is_dir,
+            preview: None,
+        });
+        cx.notify();
+
+        if !is_dir {
+            return;
+        }
+        let browser = self.browser.clone();
+        cx.spawn(async move |entity, cx| {
+            let result = browser.lock().await.preview_remove(&path).await;
+
+            entity.update(cx, |this, cx| {
+                // The user may have cancelled, or picked something else meanwhile
+                let Some(pending) = this.pending_delete.as_mut().filter(|pending| pending.path == path) else {
+                    return;
+                };
+                match result {
+                    Ok(preview) => pending.preview = Some(preview),
+                    Err(e) => {
+                        this.pending_delete = None;
+                        this.set_error(format!("Could not list {}: {}", path.display(), e), cx);
+                    }
+                }
+                cx.notify();
+            }).ok();
+        }).detach();
+    }
+
+    /// Drop the pending delete
+    fn cancel_delete(&mut self, cx: &mut Context<Self>) {
+        self.pending_delete = None;
+        cx.notify();
+    }
+
+    /// Carry out the pending delete, once a directory's contents were counted
+    fn confirm_delete(&mut self, cx: &mut Context<Self>) {
+        let ready = self.pending_delete.as_ref().is_some_and(|pending| !pending.is_dir || pending.preview.is_some());
+        if !ready {
+            return;
+        }
+        let Some(pending) = self.pending_delete.take() else {
+            return;
+        };
+        self.loading = true;
+        cx.notify();
+
+        let browser = self.browser.clone();
+        cx.spawn(async move |entity, cx| {
+            let result = {
+                let browser = browser.lock().await;
+                if pending.is_dir {
+                    browser.remove_recursive(&pending.path).await
+                } else {
+                    browser.remove_file(&pending.path).await
+                }
+            };
+
+            entity.update(cx, |this, cx| {
+                match result {
+                    Ok(()) => this.refresh(cx),
+                    Err(e) => this.set_error(format!("Could not delete {}: {}", pending.name, e), cx),
+                }
+            }).ok();
+        }).detach();
+    }
+
     /// Select next item
     fn select_next(&mut self, cx: &mut Context<Self>) {
         if self.entries.is_empty() {
@@ -150,9 +243,15 @@ impl SftpPanel {
         let keystroke = &event.keystroke;
 
         match keystroke.key.as_str() {
+            "escape" if self.pending_delete.is_some() => {
+                self.cancel_delete(cx);
+            }
             "escape" => {
                 cx.emit(SftpPanelEvent::Close);
             }
+            "delete" => {
+                self.request_delete(cx);
+            }
             "enter" => {
                 self.open_selected(cx);
             }
@@ -189,6 +288,16 @@ impl Render for SftpPanel {
         let is_empty = self.entries.is_empty();
         let entries = self.entries.clone();
         let transfers = self.transfers.clone();
+        let pending_delete = self.pending_delete.as_ref().map(|pending| {
+            let message = match (pending.is_dir, pending.preview) {
+                (false, _) => format!("Delete '{}'?", pending.name),
+                (true, None) => format!("Counting what is in '{}'...", pending.name),
+                (true, Some(preview)) => {
+                    format!("Delete '{}' and everything in it? {}", pending.name, preview.summary())
+                }
+            };
+            (message, !pending.is_dir || pending.preview.is_some())
+        });
 
         div()
             .track_focus(&self.focus_handle)
@@ -238,6 +347,20 @@ impl Render for SftpPanel {
                             .on_click(cx.listener(|this, _, _, cx| this.refresh(cx)))
                             .child("\u{21BB}") // Refresh symbol
                     )
+                    // Delete button
+                    .child(
+                        div()
+                            .id("sftp-delete")
+                            .px_2()
+                            .py_1()
+                            .cursor_pointer()
+                            .text_sm()
+                            .text_color(rgb(0x9399b2))
+                            .hover(|s| s.text_color(rgb(0xf38ba8)).bg(rgb(0x45475a)))
+                            .rounded_sm()
+                            .on_click(cx.listener(|this, _, _, cx| this.request_delete(cx)))
+                            .child("\u{1F5D1}") // Wastebasket
+                    )
                     // Path
                     .child(
                         div()
@@ -390,6 +513,62 @@ impl Render for SftpPanel {
                         }
                     )
             )
+            // Delete confirmation
+            .when_some(pending_delete, |el, (message, ready)| {
+                el.child(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .gap_2()
+                        .p_2()
+                        .border_t_1()
+                        .border_color(rgb(0xf38ba8))
+                        .bg(rgb(0x313244))
+                        .child(
+                            div()
+                                .text_sm()
+                                .text_color(rgb(0xcdd6f4))
+                                .child(message)
+                        )
+                        .child(
+                            div()
+                                .flex()
+                                .justify_end()
+                                .gap_2()
+                                .child(
+                                    div()
+                                        .id("sftp-delete-cancel")
+                                        .px_3()
+                                        .py_1()
+                                        .rounded_md()
+                                        .cursor_pointer()
+                                        .text_sm()
+                                        .text_color(rgb(0x6c7086))
+                                        .hover(|s| s.bg(rgb(0x45475a)))
+                                        .on_click(cx.listener(|this, _, _, cx| this.cancel_delete(cx)))
+                                        .child("Cancel")
+                                )
+                                .child(
+                                    div()
+                                        .id("sftp-delete-confirm")
+                                        .px_3()
+                                        .py_1()
+                                        .rounded_md()
+                                        .text_sm()
+                                        .font_weight(FontWeight::SEMIBOLD)
+                                        .text_color(rgb(0x1e1e2e))
+                                        .when(ready, |s| {
+                                            s.bg(rgb(0xf38ba8))
+                                                .cursor_pointer()
+                                                .hover(|s| s.bg(rgb(0xeba0ac)))
+                                                .on_click(cx.listener(|this, _, _, cx| this.confirm_delete(cx)))
+                                        })
+                                        .when(!ready, |s| s.bg(rgb(0x6c7086)))
+                                        .child("Delete")
+                                )
+                        )
+                )
+            })
             // Transfers section
             .when(!transfers.is_empty(), |el| {
                 el.child(
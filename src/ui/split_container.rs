@@ -3,11 +3,18 @@
 use gpui::*;
 use gpui::prelude::*;
 use parking_lot::Mutex;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
 use std::sync::Arc;
 
 use crate::terminal::Terminal;
+use super::pane_layout::{Direction, PaneId, PaneNode, SplitId};
 use super::terminal_view::TerminalView;
 
+/// Most panes a single tab can be split into
+const MAX_PANES: usize = 4;
+
 /// Split orientation
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum SplitOrientation {
@@ -25,20 +32,27 @@ pub enum SplitContainerEvent {
 
 impl EventEmitter<SplitContainerEvent> for SplitContainer {}
 
-/// A container that holds multiple terminal panes with resize handles
+/// One terminal pane of a split container
+struct Pane {
+    id: PaneId,
+    view: Entity<TerminalView>,
+    terminal: Arc<Mutex<Terminal>>,
+}
+
+/// A container that holds a tree of terminal panes with resize handles
 pub struct SplitContainer {
-    /// Terminal views in this container
-    panes: Vec<Entity<TerminalView>>,
-    /// Terminal instances corresponding to panes
-    terminals: Vec<Arc<Mutex<Terminal>>>,
-    /// Split positions as percentages (0.0 to 1.0) - one less than panes count
-    split_positions: Vec<f32>,
-    /// Currently active pane index
-    active_pane: usize,
-    /// Split orientation
-    orientation: SplitOrientation,
-    /// Whether currently resizing a divider
-    is_resizing: Option<usize>,
+    /// Terminal panes in this container, in no particular order
+    panes: Vec<Pane>,
+    /// How the panes are arranged
+    layout: PaneNode,
+    /// Currently active pane
+    active_pane: PaneId,
+    /// Next ID to hand out to a pane or split
+    next_id: usize,
+    /// Split whose divider is being dragged
+    is_resizing: Option<SplitId>,
+    /// On-screen bounds of each split, recorded during paint for resizing
+    split_bounds: Rc<RefCell<HashMap<SplitId, Bounds<Pixels>>>>,
     /// Focus handle
     focus_handle: FocusHandle,
     /// Color scheme for new panes
@@ -55,12 +69,12 @@ impl SplitContainer {
         let view = cx.new(|cx| TerminalView::new(terminal.clone(), color_scheme.clone(), cx));
 
         Self {
-            panes: vec![view],
-            terminals: vec![terminal],
-            split_positions: Vec::new(),
+            panes: vec![Pane { id: 0, view, terminal }],
+            layout: PaneNode::Pane(0),
             active_pane: 0,
-            orientation: SplitOrientation::Horizontal,
+            next_id: 1,
             is_resizing: None,
+            split_bounds: Rc::new(RefCell::new(HashMap::new())),
             focus_handle: cx.focus_handle(),
             color_scheme,
         }
@@ -71,14 +85,29 @@ impl SplitContainer {
         self.panes.len()
     }
 
-    /// Get the active pane index
+    /// Get the active pane's position in reading order
     pub fn active_pane(&self) -> usize {
-        self.active_pane
+        self.pane_index(self.active_pane)
     }
 
     /// Get the active terminal
     pub fn active_terminal(&self) -> Option<Arc<Mutex<Terminal>>> {
-        self.terminals.get(self.active_pane).cloned()
+        self.pane(self.active_pane).map(|pane| pane.terminal.clone())
+    }
+
+    fn pane(&self, id: PaneId) -> Option<&Pane> {
+        self.panes.iter().find(|pane| pane.id == id)
+    }
+
+    /// Position of a pane in reading order, as reported in events
+    fn pane_index(&self, id: PaneId) -> usize {
+        self.layout.panes().iter().position(|&pane| pane == id).unwrap_or(0)
+    }
+
+    fn take_id(&mut self) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
     }
 
     /// Split the active pane horizontally (left/right)
@@ -91,144 +120,193 @@ impl SplitContainer {
         self.split(new_terminal, SplitOrientation::Vertical, cx);
     }
 
-    /// Split the active pane
+    /// Whether another pane fits in this tab
+    pub fn can_split(&self) -> bool {
+        self.panes.len() < MAX_PANES
+    }
+
+    /// Split the active pane, putting the new one after it
     fn split(&mut self, new_terminal: Arc<Mutex<Terminal>>, orientation: SplitOrientation, cx: &mut Context<Self>) {
-        // Limit to 4 panes
-        if self.panes.len() >= 4 {
+        if !self.can_split() {
             return;
         }
 
-        // If this is the second pane, set the orientation
-        if self.panes.len() == 1 {
-            self.orientation = orientation;
+        let pane_id = self.take_id();
+        let split_id = self.take_id();
+        if !self.layout.split(self.active_pane, pane_id, orientation, split_id) {
+            return;
         }
 
         let view = cx.new(|cx| TerminalView::new(new_terminal.clone(), self.color_scheme.clone(), cx));
-
-        // Insert after active pane
-        let insert_idx = self.active_pane + 1;
-        self.panes.insert(insert_idx, view);
-        self.terminals.insert(insert_idx, new_terminal);
-
-        // Add a split position for the new divider
-        // Calculate equal spacing
-        let num_panes = self.panes.len();
-        self.split_positions = (1..num_panes)
-            .map(|i| i as f32 / num_panes as f32)
-            .collect();
+        self.panes.push(Pane { id: pane_id, view, terminal: new_terminal });
 
         // Focus the new pane
-        self.active_pane = insert_idx;
-        cx.emit(SplitContainerEvent::ActivePaneChanged(self.active_pane));
+        self.active_pane = pane_id;
+        cx.emit(SplitContainerEvent::ActivePaneChanged(self.active_pane()));
         cx.notify();
     }
 
-    /// Close the active pane
+    /// Close the active pane, letting its neighbour take the space
     pub fn close_active_pane(&mut self, cx: &mut Context<Self>) -> bool {
-        if self.panes.len() <= 1 {
+        let closed_idx = self.active_pane();
+        let Some(next) = self.layout.remove(self.active_pane) else {
             // Can't close the last pane
             return false;
-        }
-
-        let closed_idx = self.active_pane;
-        self.panes.remove(closed_idx);
-        self.terminals.remove(closed_idx);
+        };
 
-        // Recalculate split positions
-        let num_panes = self.panes.len();
-        self.split_positions = (1..num_panes)
-            .map(|i| i as f32 / num_panes as f32)
-            .collect();
-
-        // Adjust active pane
-        if self.active_pane >= self.panes.len() {
-            self.active_pane = self.panes.len() - 1;
-        }
+        let closed = self.active_pane;
+        self.panes.retain(|pane| pane.id != closed);
+        self.active_pane = next;
 
         cx.emit(SplitContainerEvent::PaneClosed(closed_idx));
-        cx.emit(SplitContainerEvent::ActivePaneChanged(self.active_pane));
+        cx.emit(SplitContainerEvent::ActivePaneChanged(self.active_pane()));
         cx.notify();
         true
     }
 
     /// Focus next pane
     pub fn focus_next_pane(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        if self.panes.len() > 1 {
-            self.active_pane = (self.active_pane + 1) % self.panes.len();
-            self.focus_active_pane(window, cx);
-            cx.emit(SplitContainerEvent::ActivePaneChanged(self.active_pane));
-        }
+        let order = self.layout.panes();
+        let next = order[(self.active_pane() + 1) % order.len()];
+        self.set_active_pane(next, window, cx);
     }
 
     /// Focus previous pane
     pub fn focus_prev_pane(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        if self.panes.len() > 1 {
-            if self.active_pane == 0 {
-                self.active_pane = self.panes.len() - 1;
-            } else {
-                self.active_pane -= 1;
-            }
-            self.focus_active_pane(window, cx);
-            cx.emit(SplitContainerEvent::ActivePaneChanged(self.active_pane));
+        let order = self.layout.panes();
+        let prev = order[(self.active_pane() + order.len() - 1) % order.len()];
+        self.set_active_pane(prev, window, cx);
+    }
+
+    /// Focus the pane beside the active one, if there is one that way
+    pub fn focus_pane_toward(&mut self, direction: Direction, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(id) = self.layout.neighbor(self.active_pane, direction) {
+            self.set_active_pane(id, window, cx);
         }
     }
 
     /// Focus the active pane
     pub fn focus_active_pane(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        if let Some(view) = self.panes.get(self.active_pane).cloned() {
+        if let Some(view) = self.pane(self.active_pane).map(|pane| pane.view.clone()) {
             view.update(cx, |v, cx| v.focus(window, cx));
         }
         cx.notify();
     }
 
-    /// Set active pane by index
-    fn set_active_pane(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
-        if index < self.panes.len() && index != self.active_pane {
-            self.active_pane = index;
+    /// Make a pane active and focus it
+    fn set_active_pane(&mut self, id: PaneId, window: &mut Window, cx: &mut Context<Self>) {
+        if id != self.active_pane && self.pane(id).is_some() {
+            self.active_pane = id;
             self.focus_active_pane(window, cx);
-            cx.emit(SplitContainerEvent::ActivePaneChanged(self.active_pane));
+            cx.emit(SplitContainerEvent::ActivePaneChanged(self.active_pane()));
         }
     }
 
-    /// Handle resize drag
-    fn handle_resize_drag(&mut self, divider_index: usize, position: f32, total_size: f32, cx: &mut Context<Self>) {
-        if divider_index < self.split_positions.len() {
-            let ratio = (position / total_size).clamp(0.1, 0.9);
-
-            // Ensure this position doesn't cross adjacent dividers
-            let min_ratio = if divider_index > 0 {
-                self.split_positions[divider_index - 1] + 0.1
-            } else {
-                0.1
-            };
-            let max_ratio = if divider_index + 1 < self.split_positions.len() {
-                self.split_positions[divider_index + 1] - 0.1
-            } else {
-                0.9
-            };
-
-            self.split_positions[divider_index] = ratio.clamp(min_ratio, max_ratio);
+    /// Move the divider being dragged to follow the mouse
+    fn handle_resize_drag(&mut self, position: Point<Pixels>, cx: &mut Context<Self>) {
+        let Some(split) = self.is_resizing else {
+            return;
+        };
+        let Some(orientation) = self.layout.split_orientation(split) else {
+            return;
+        };
+        let Some(bounds) = self.split_bounds.borrow().get(&split).copied() else {
+            return;
+        };
+
+        let (offset, total): (f32, f32) = match orientation {
+            SplitOrientation::Horizontal => ((position.x - bounds.origin.x).into(), bounds.size.width.into()),
+            SplitOrientation::Vertical => ((position.y - bounds.origin.y).into(), bounds.size.height.into()),
+        };
+        if total > 0.0 {
+            self.layout.set_ratio(split, offset / total);
             cx.notify();
         }
     }
 
-    /// Calculate pane bounds based on split positions
-    fn calculate_pane_sizes(&self, total_size: f32) -> Vec<f32> {
-        if self.panes.len() == 1 {
-            return vec![total_size];
-        }
-
-        let mut sizes = Vec::with_capacity(self.panes.len());
-        let mut prev_pos = 0.0;
+    /// Build the element for a node of the layout
+    fn render_node(&self, node: &PaneNode, cx: &mut Context<Self>) -> AnyElement {
+        match node {
+            PaneNode::Pane(id) => {
+                let id = *id;
+                let is_active = id == self.active_pane;
+                let view = self.pane(id).map(|pane| pane.view.clone());
+
+                // Pane wrapper with border highlighting for active pane
+                div()
+                    .size_full()
+                    .overflow_hidden()
+                    .when(is_active, |el| {
+                        el.border_2().border_color(rgb(0x89b4fa))
+                    })
+                    .when(!is_active, |el| {
+                        el.border_1().border_color(rgb(0x313244))
+                    })
+                    .on_mouse_down(MouseButton::Left, cx.listener(move |this, _event, window, cx| {
+                        this.set_active_pane(id, window, cx);
+                    }))
+                    .when_some(view, |el, view| el.child(view))
+                    .into_any_element()
+            }
+            PaneNode::Split { id, orientation, ratio, first, second } => {
+                let split_id = *id;
+                let horizontal = *orientation == SplitOrientation::Horizontal;
+                let is_divider_resizing = self.is_resizing == Some(split_id);
+                let split_bounds = self.split_bounds.clone();
 
-        for &pos in self.split_positions.iter() {
-            sizes.push((pos - prev_pos) * total_size);
-            prev_pos = pos;
+                let divider = div()
+                    .id(ElementId::Name(format!("divider-{}", split_id).into()))
+                    .flex_none()
+                    .when(horizontal, |el| el.w(px(6.0)).h_full().cursor_col_resize())
+                    .when(!horizontal, |el| el.h(px(6.0)).w_full().cursor_row_resize())
+                    .when(is_divider_resizing, |el| el.bg(rgb(0x89b4fa)))
+                    .when(!is_divider_resizing, |el| {
+                        el.bg(rgb(0x313244)).hover(|h| h.bg(rgb(0x45475a)))
+                    })
+                    .on_mouse_down(MouseButton::Left, cx.listener(move |this, _event, _window, cx| {
+                        this.is_resizing = Some(split_id);
+                        cx.notify();
+                    }));
+
+                div()
+                    .relative()
+                    .size_full()
+                    .flex()
+                    .when(horizontal, |el| el.flex_row())
+                    .when(!horizontal, |el| el.flex_col())
+                    // Record where this split is drawn so dragging its divider maps to a ratio
+                    .child(
+                        canvas(
+                            move |bounds, _window, _cx| {
+                                split_bounds.borrow_mut().insert(split_id, bounds);
+                            },
+                            |_, _, _, _| {},
+                        )
+                        .absolute()
+                        .top_0()
+                        .left_0()
+                        .size_full(),
+                    )
+                    .child(
+                        div()
+                            .flex_none()
+                            .overflow_hidden()
+                            .when(horizontal, |el| el.w(relative(*ratio)).h_full())
+                            .when(!horizontal, |el| el.h(relative(*ratio)).w_full())
+                            .child(self.render_node(first, cx)),
+                    )
+                    .child(divider)
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w(px(0.0))
+                            .min_h(px(0.0))
+                            .overflow_hidden()
+                            .child(self.render_node(second, cx)),
+                    )
+                    .into_any_element()
+            }
         }
-        // Last pane
-        sizes.push((1.0 - prev_pos) * total_size);
-
-        sizes
     }
 }
 
@@ -239,123 +317,36 @@ impl Focusable for SplitContainer {
 }
 
 impl Render for SplitContainer {
-    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let pane_count = self.panes.len();
-        let active_pane = self.active_pane;
-        let orientation = self.orientation;
-        let is_resizing = self.is_resizing;
-
-        if pane_count == 1 {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if let PaneNode::Pane(id) = self.layout {
             // Single pane - no split, just render the terminal view directly
             // Focus is delegated to the TerminalView inside
-            return self.panes[0].clone().into_any_element();
-        }
-
-        // Build split layout
-        let mut container = div()
-            .size_full()
-            .flex()
-            .when(orientation == SplitOrientation::Horizontal, |el| el.flex_row())
-            .when(orientation == SplitOrientation::Vertical, |el| el.flex_col());
-
-        // Clone split positions for closures
-        let split_positions = self.split_positions.clone();
-
-        for (idx, view) in self.panes.iter().enumerate() {
-            let is_active = idx == active_pane;
-
-            // Calculate flex basis from split positions
-            let flex_basis = if idx == 0 {
-                split_positions.first().copied().unwrap_or(0.5)
-            } else if idx == pane_count - 1 {
-                1.0 - split_positions.last().copied().unwrap_or(0.5)
-            } else {
-                split_positions.get(idx).copied().unwrap_or(0.5)
-                    - split_positions.get(idx - 1).copied().unwrap_or(0.0)
-            };
-
-            // Pane wrapper with border highlighting for active pane
-            let pane_wrapper = div()
-                .flex_1()
-                .flex_basis(px(flex_basis * 1000.0)) // Use large number for flex calculation
-                .min_w(px(100.0))
-                .min_h(px(50.0))
-                .overflow_hidden()
-                .when(is_active, |el| {
-                    el.border_2().border_color(rgb(0x89b4fa))
-                })
-                .when(!is_active, |el| {
-                    el.border_1().border_color(rgb(0x313244))
-                })
-                .on_mouse_down(MouseButton::Left, {
-                    let idx = idx;
-                    cx.listener(move |this, _event, window, cx| {
-                        this.set_active_pane(idx, window, cx);
-                    })
-                })
-                .child(view.clone());
-
-            container = container.child(pane_wrapper);
-
-            // Add divider between panes (not after the last one)
-            if idx < pane_count - 1 {
-                let divider_idx = idx;
-                let is_divider_resizing = is_resizing == Some(divider_idx);
-
-                let divider = div()
-                    .id(ElementId::Name(format!("divider-{}", divider_idx).into()))
-                    .when(orientation == SplitOrientation::Horizontal, |el| {
-                        el.w(px(6.0)).h_full().cursor_col_resize()
-                    })
-                    .when(orientation == SplitOrientation::Vertical, |el| {
-                        el.h(px(6.0)).w_full().cursor_row_resize()
-                    })
-                    .when(is_divider_resizing, |el| el.bg(rgb(0x89b4fa)))
-                    .when(!is_divider_resizing, |el| {
-                        el.bg(rgb(0x313244)).hover(|h| h.bg(rgb(0x45475a)))
-                    })
-                    .on_mouse_down(MouseButton::Left, {
-                        let divider_idx = divider_idx;
-                        cx.listener(move |this, _event, _window, cx| {
-                            this.is_resizing = Some(divider_idx);
-                            cx.notify();
-                        })
-                    })
-;
-
-                container = container.child(divider);
+            if let Some(pane) = self.pane(id) {
+                return pane.view.clone().into_any_element();
             }
         }
 
-        // Handle mouse move for resizing at container level
-        let orientation_for_move = orientation;
-        container = container.on_mouse_move(cx.listener(move |this, event: &MouseMoveEvent, _window, cx| {
-            if let Some(divider_idx) = this.is_resizing {
-                let position = match orientation_for_move {
-                    SplitOrientation::Horizontal => event.position.x.into(),
-                    SplitOrientation::Vertical => event.position.y.into(),
-                };
-                // Estimate total size (this is approximate, actual bounds would be better)
-                let total_size = 1000.0; // Will be scaled by flex
-                this.handle_resize_drag(divider_idx, position, total_size, cx);
-            }
-        }));
-
-        // Handle mouse up to end resizing
-        container = container.on_mouse_up(MouseButton::Left, cx.listener(|this, _event, _window, cx| {
-            if this.is_resizing.is_some() {
-                this.is_resizing = None;
-                cx.notify();
-            }
-        }));
-
-        container = container.on_mouse_up_out(MouseButton::Left, cx.listener(|this, _event, _window, cx| {
-            if this.is_resizing.is_some() {
-                this.is_resizing = None;
-                cx.notify();
-            }
-        }));
-
-        container.into_any_element()
+        let layout = self.layout.clone();
+        div()
+            .size_full()
+            .child(self.render_node(&layout, cx))
+            // Handle mouse move for resizing at container level
+            .on_mouse_move(cx.listener(|this, event: &MouseMoveEvent, _window, cx| {
+                this.handle_resize_drag(event.position, cx);
+            }))
+            // Handle mouse up to end resizing
+            .on_mouse_up(MouseButton::Left, cx.listener(|this, _event, _window, cx| {
+                if this.is_resizing.is_some() {
+                    this.is_resizing = None;
+                    cx.notify();
+                }
+            }))
+            .on_mouse_up_out(MouseButton::Left, cx.listener(|this, _event, _window, cx| {
+                if this.is_resizing.is_some() {
+                    this.is_resizing = None;
+                    cx.notify();
+                }
+            }))
+            .into_any_element()
     }
 }
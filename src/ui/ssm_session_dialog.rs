@@ -3,7 +3,9 @@ use gpui::prelude::*;
 use uuid::Uuid;
 
 use crate::app::AppState;
+use crate::config::ColorScheme;
 use crate::session::SsmSession;
+use super::color_scheme_editor::ColorSchemeEditor;
 use super::text_field::TextField;
 
 /// Result of the SSM session dialog
@@ -36,6 +38,8 @@ pub struct SsmSessionDialog {
     profile_field: Entity<TextField>,
     /// Color scheme override (None = use default)
     color_scheme: Option<String>,
+    /// Transcript recording override, kept as-is when editing
+    record_session: Option<bool>,
     /// Validation errors
     errors: Vec<String>,
 }
@@ -51,6 +55,7 @@ impl SsmSessionDialog {
             region_field: cx.new(|cx| TextField::new(cx, "us-east-1 (optional)")),
             profile_field: cx.new(|cx| TextField::new(cx, "default (optional)")),
             color_scheme: None,
+            record_session: None,
             errors: Vec::new(),
         }
     }
@@ -72,6 +77,7 @@ impl SsmSessionDialog {
             region_field: cx.new(|cx| TextField::with_content(cx, "us-east-1 (optional)", session.region.clone().unwrap_or_default())),
             profile_field: cx.new(|cx| TextField::with_content(cx, "default (optional)", session.profile.clone().unwrap_or_default())),
             color_scheme: session.color_scheme.clone(),
+            record_session: session.record_session,
             errors: Vec::new(),
         }
     }
@@ -165,6 +171,7 @@ impl SsmSessionDialog {
         let mut session = SsmSession::with_config(name, instance_id, region, profile);
         session.group_id = self.group_id;
         session.color_scheme = self.color_scheme.clone();
+        session.record_session = self.record_session;
 
         // Preserve ID if editing
         if let Some(id) = self.session_id {
@@ -247,6 +254,17 @@ impl SsmSessionDialog {
     }
 
     fn render_color_scheme_selector(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        // "Default" (no override) stands in for the default scheme; a hidden
+        // scheme the session already uses stays selectable
+        let mut scheme_choices: Vec<String> = cx
+            .try_global::<AppState>()
+            .map(|state| state.app.lock().config.appearance.visible_scheme_names())
+            .unwrap_or_else(|| ColorScheme::builtin_names().iter().map(|n| n.to_string()).collect());
+        scheme_choices.retain(|name| name != "default");
+        if let Some(current) = self.color_scheme.as_ref().filter(|c| !scheme_choices.contains(c)) {
+            scheme_choices.push(current.clone());
+        }
+
         div()
             .flex()
             .flex_col()
@@ -258,9 +276,25 @@ impl SsmSessionDialog {
                     .flex_wrap()
                     .gap_2()
                     .child(self.render_color_scheme_option("Default", None, cx))
-                    .child(self.render_color_scheme_option("Light", Some("light".to_string()), cx))
-                    .child(self.render_color_scheme_option("Matrix", Some("matrix".to_string()), cx))
-                    .child(self.render_color_scheme_option("Red", Some("red".to_string()), cx)),
+                    .children(
+                        scheme_choices
+                            .into_iter()
+                            .map(|name| self.render_color_scheme_option(ColorScheme::display_name(&name), Some(name), cx)),
+                    )
+                    .child(
+                        div()
+                            .id("edit-schemes")
+                            .px_3()
+                            .py_1()
+                            .rounded_md()
+                            .cursor_pointer()
+                            .text_color(rgb(0x89b4fa))
+                            .hover(|style| style.bg(rgb(0x313244)))
+                            .on_click(cx.listener(|_this, _event, _window, cx| {
+                                ColorSchemeEditor::open(cx);
+                            }))
+                            .child(div().text_sm().child("Edit...")),
+                    ),
             )
     }
 
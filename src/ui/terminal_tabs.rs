@@ -3,6 +3,7 @@ use gpui::prelude::*;
 use uuid::Uuid;
 
 use crate::app::{AppState, TerminalTab};
+use super::text_field::{TextField, TextFieldEvent};
 
 /// Actions for terminal tabs
 #[derive(Clone, Debug)]
@@ -15,6 +16,8 @@ pub enum TabAction {
     NewTab,
     /// Reorder tabs (from_index, to_index)
     Reorder(usize, usize),
+    /// Give a tab a name of its own (blank restores its original title)
+    Rename(Uuid, String),
 }
 
 /// Events emitted by the terminal tabs
@@ -26,6 +29,29 @@ pub enum TabEvent {
 
 impl EventEmitter<TabEvent> for TerminalTabs {}
 
+/// A tab being dragged to a new position
+#[derive(Clone)]
+struct DraggedTab {
+    index: usize,
+    /// Shown next to the cursor while dragging
+    title: String,
+}
+
+impl Render for DraggedTab {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .px_3()
+            .py_1()
+            .rounded_sm()
+            .bg(rgb(0x313244))
+            .border_1()
+            .border_color(rgb(0x89b4fa))
+            .text_sm()
+            .text_color(rgb(0xcdd6f4))
+            .child(self.title.clone())
+    }
+}
+
 /// State for tab context menu (public for rendering in MainWindow)
 #[derive(Clone)]
 pub struct TabContextMenuState {
@@ -42,6 +68,9 @@ pub struct TerminalTabs {
     scroll_offset: f32,
     prev_tab_count: usize,
     context_menu: Option<TabContextMenuState>,
+    /// Tab whose title is being edited, with its editor
+    renaming: Option<(Uuid, Entity<TextField>)>,
+    _rename_subscription: Option<Subscription>,
 }
 
 /// Information about a tab for display
@@ -50,14 +79,22 @@ pub struct TabInfo {
     pub id: Uuid,
     pub title: String,
     pub dirty: bool,
+    /// Port forwards running and failed, for SSH tabs that have any
+    pub forwards: (usize, usize),
 }
 
 impl From<&TerminalTab> for TabInfo {
     fn from(tab: &TerminalTab) -> Self {
+        let forwards = tab
+            .terminal
+            .lock()
+            .port_forwards()
+            .map_or((0, 0), |forwards| (forwards.running(), forwards.failed()));
         Self {
             id: tab.id,
-            title: tab.title.clone(),
+            title: tab.display_title().to_string(),
             dirty: tab.dirty,
+            forwards,
         }
     }
 }
@@ -71,6 +108,8 @@ impl TerminalTabs {
             scroll_offset: 0.0,
             prev_tab_count: tab_count,
             context_menu: None,
+            renaming: None,
+            _rename_subscription: None,
         }
     }
 
@@ -119,7 +158,68 @@ impl TerminalTabs {
         self.handle_close_tab(tab_id, window, cx);
     }
 
+    /// Start editing a tab's title in place
+    pub fn start_rename(&mut self, tab_id: Uuid, window: &mut Window, cx: &mut Context<Self>) {
+        if self.renaming.as_ref().is_some_and(|(id, _)| *id == tab_id) {
+            return;
+        }
+        let Some(tab) = self.tabs.iter().find(|t| t.id == tab_id) else {
+            return;
+        };
+        self.context_menu = None;
+
+        let title = tab.title.clone();
+        let field = cx.new(|cx| TextField::with_content(cx, "Tab name", title));
+        field.read(cx).focus(window, cx);
+        self._rename_subscription = Some(cx.subscribe(&field, move |this, field, event, cx| {
+            if let TextFieldEvent::Submit = event {
+                let name = field.read(cx).content().to_string();
+                this.handle_action(TabAction::Rename(tab_id, name), cx);
+            }
+        }));
+        self.renaming = Some((tab_id, field));
+        cx.notify();
+    }
+
+    /// Stop editing a tab's title without changing it
+    fn cancel_rename(&mut self, cx: &mut Context<Self>) {
+        if self.renaming.take().is_some() {
+            self._rename_subscription = None;
+            cx.notify();
+        }
+    }
+
+    /// Apply a tab action to the app state
+    pub fn handle_action(&mut self, action: TabAction, cx: &mut Context<Self>) {
+        match action {
+            TabAction::Rename(tab_id, name) => {
+                if let Some(app_state) = cx.try_global::<AppState>() {
+                    app_state.app.lock().rename_tab(tab_id, &name);
+                }
+                if self.renaming.as_ref().is_some_and(|(id, _)| *id == tab_id) {
+                    self.cancel_rename(cx);
+                }
+                cx.notify();
+            }
+            TabAction::Reorder(from, to) => {
+                if from < self.tabs.len() && to < self.tabs.len() && from != to {
+                    let tab = self.tabs.remove(from);
+                    self.tabs.insert(to, tab);
+                    if let Some(app_state) = cx.try_global::<AppState>() {
+                        app_state.app.lock().reorder_tab(from, to);
+                    }
+                    cx.notify();
+                }
+            }
+            // These need the window and go through their own handlers
+            TabAction::Select(_) | TabAction::Close(_) | TabAction::NewTab => {}
+        }
+    }
+
     fn handle_select_tab(&mut self, tab_id: Uuid, window: &mut Window, cx: &mut Context<Self>) {
+        if self.renaming.as_ref().is_some_and(|(id, _)| *id != tab_id) {
+            self.cancel_rename(cx);
+        }
         if let Some(app_state) = cx.try_global::<AppState>() {
             app_state.app.lock().set_active_tab_by_id(tab_id);
         }
@@ -253,6 +353,13 @@ impl TerminalTabs {
         let tab_id = tab.id;
         let title = tab.title.clone();
         let dirty = tab.dirty;
+        let rename_field = self
+            .renaming
+            .as_ref()
+            .filter(|(id, _)| *id == tab_id)
+            .map(|(_, field)| field.clone());
+        let is_renaming = rename_field.is_some();
+        let (forwards_running, forwards_failed) = tab.forwards;
 
         div()
             .id(ElementId::Name(format!("tab-{}", tab_id).into()))
@@ -276,23 +383,71 @@ impl TerminalTabs {
             .on_click(cx.listener(move |this, _event, window, cx| {
                 this.handle_select_tab(tab_id, window, cx);
             }))
+            // Double-click to rename
+            .on_mouse_down(MouseButton::Left, cx.listener(move |this, event: &MouseDownEvent, window, cx| {
+                if event.click_count == 2 {
+                    this.start_rename(tab_id, window, cx);
+                }
+            }))
+            // Drag onto another tab to take its place; the accent edge shows
+            // which side the tab will land on
+            .on_drag(
+                DraggedTab { index: tab_index, title: title.clone() },
+                |dragged, _offset, _window, cx| cx.new(|_| dragged.clone()),
+            )
+            .drag_over::<DraggedTab>(move |style, dragged, _window, _cx| {
+                if dragged.index > tab_index {
+                    style.border_l_2().border_color(rgb(0x89b4fa))
+                } else if dragged.index < tab_index {
+                    style.border_r_2().border_color(rgb(0x89b4fa))
+                } else {
+                    style
+                }
+            })
+            .on_drop(cx.listener(move |this, dragged: &DraggedTab, _window, cx| {
+                this.handle_action(TabAction::Reorder(dragged.index, tab_index), cx);
+            }))
             // Right-click handler for context menu
             .on_mouse_up(MouseButton::Right, cx.listener(move |this, event: &MouseUpEvent, _window, cx| {
                 cx.stop_propagation();
                 this.show_context_menu(event.position, tab_id, tab_index, cx);
             }))
-            .child(
-                // Tab title
-                div()
-                    .text_sm()
-                    .text_color(if is_active {
-                        rgb(0xcdd6f4)
-                    } else {
-                        rgb(0x6c7086)
-                    })
-                    .when(dirty, |this| this.child(format!("● {}", title)))
-                    .when(!dirty, |this| this.child(title)),
-            )
+            // Tab title, or its editor while renaming (Enter saves, Escape cancels)
+            .when_some(rename_field, |this, field| {
+                this.child(
+                    div()
+                        .w(px(140.0))
+                        .on_key_down(cx.listener(|this, event: &KeyDownEvent, _window, cx| {
+                            if event.keystroke.key == "escape" {
+                                this.cancel_rename(cx);
+                                cx.stop_propagation();
+                            }
+                        }))
+                        .child(field),
+                )
+            })
+            .when(!is_renaming, |this| {
+                this.child(
+                    div()
+                        .text_sm()
+                        .text_color(if is_active {
+                            rgb(0xcdd6f4)
+                        } else {
+                            rgb(0x6c7086)
+                        })
+                        .when(dirty, |this| this.child(format!("● {}", title)))
+                        .when(!dirty, |this| this.child(title)),
+                )
+            })
+            // Port forwards, red when some failed to start
+            .when(forwards_running + forwards_failed > 0, |this| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(rgb(if forwards_failed > 0 { 0xf38ba8 } else { 0xa6e3a1 }))
+                        .child(format!("⇄{}", forwards_running)),
+                )
+            })
             .child(
                 // Close button
                 div()
@@ -1,20 +1,41 @@
 use alacritty_terminal::index::{Column, Line, Point as TermPoint, Side};
 use alacritty_terminal::selection::SelectionType;
-use alacritty_terminal::term::cell::Flags;
+use alacritty_terminal::term::cell::{Cell, Flags};
 use alacritty_terminal::term::TermMode;
-use alacritty_terminal::vte::ansi::{Color, NamedColor};
+use alacritty_terminal::vte::ansi::{Color, CursorShape as AnsiCursorShape, NamedColor};
 use gpui::*;
 use parking_lot::Mutex;
+use std::ops::{Range, RangeInclusive};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use crate::app::AppState;
-use crate::config::ColorScheme;
-use crate::terminal::{keystroke_to_escape, terminal::{color_to_rgb_with_scheme, hex_to_rgb}, Terminal, TerminalSize};
+use crate::config::{ColorScheme, CursorShape as ConfiguredCursorShape, ShellExitAction, TerminalAppearance};
+use crate::terminal::{disruptive_control_key, keystroke_to_escape, paste_sequence, text_input, terminal::{color_to_rgb_with_scheme, cursor_color_with_scheme, hex_to_rgb}, Terminal, TerminalEvent, TerminalSize};
+use super::broadcast_dialog::{send_broadcast, toggle_broadcast, BroadcastConfirmDialog, BroadcastWrites};
+use super::pane_layout::Direction;
 use super::search_bar::{SearchBar, SearchBarEvent};
+use super::host_key_dialog::HostKeyDialog;
+use super::zmodem_dialog::ZmodemDialog;
 
-/// Cursor blink interval in milliseconds
-const CURSOR_BLINK_INTERVAL_MS: u64 = 500;
+/// Shortest cursor blink interval honoured from the config, in milliseconds
+const MIN_CURSOR_BLINK_INTERVAL_MS: u64 = 100;
+
+/// Clicks on the same cell this close together make a double or triple click
+const MULTI_CLICK_INTERVAL_MS: u64 = 300;
+
+/// Interval between auto-scroll steps while drag-selecting past the view edge
+const SELECTION_SCROLL_INTERVAL_MS: u64 = 50;
+
+/// Scrollbar track width and its gap from the right edge, in pixels
+const SCROLLBAR_WIDTH: f32 = 6.0;
+const SCROLLBAR_MARGIN: f32 = 2.0;
+
+/// Shortest the scrollbar thumb gets, so it stays easy to grab
+const SCROLLBAR_MIN_THUMB: f32 = 20.0;
+
+/// How long a local shell must have run to be restarted when it exits
+const MIN_SHELL_RUN_BEFORE_RESTART: Duration = Duration::from_secs(1);
 
 /// Terminal view element for rendering a terminal
 pub struct TerminalView {
@@ -31,10 +52,23 @@ pub struct TerminalView {
     bounds_origin: Arc<Mutex<Point<Pixels>>>,
     /// Whether mouse is currently selecting
     is_selecting: bool,
+    /// Last view-local mouse position while selecting
+    selection_position: Point<Pixels>,
+    /// Lines to scroll per tick while dragging past the top (positive) or bottom (negative) edge
+    selection_scroll: i32,
+    /// Last selection auto-scroll time
+    last_selection_scroll: Instant,
+    /// Time and cell of the last click, and its place in a run of quick clicks
+    last_click: Option<(Instant, TermPoint, u8)>,
+    /// Link under the mouse, drawn underlined
+    hovered_link: Option<HoveredLink>,
     /// Cursor blink state - true means cursor is visible in the blink cycle
     cursor_visible: bool,
     /// Last cursor blink toggle time
     last_blink_toggle: Instant,
+    /// Whether the cursor blinks, from the appearance config
+    cursor_blink: bool,
+    cursor_blink_interval: Duration,
     /// Whether terminal was focused in previous frame
     was_focused: bool,
     /// Color scheme override for this terminal (None = use global)
@@ -45,6 +79,16 @@ pub struct TerminalView {
     search_matches: Vec<(i32, usize, usize)>,
     /// Current search match index
     current_search_match: usize,
+    /// Scroll position when the search bar opened, restored when it closes
+    search_return_offset: usize,
+    /// Text being composed in an input method, shown at the cursor until committed
+    marked_text: Option<String>,
+    /// The session is waiting at a `sudo` prompt we can answer
+    sudo_prompt: bool,
+    /// Whether the scrollbar is enabled, as of the last render
+    scrollbar_enabled: bool,
+    /// Where the scrollbar thumb was grabbed, from its top, while dragging it
+    scrollbar_drag: Option<f32>,
     /// Subscriptions
     _subscriptions: Vec<Subscription>,
 }
@@ -55,6 +99,14 @@ impl TerminalView {
 
         let terminal_weak = Arc::downgrade(&terminal);
 
+        let (cursor_blink, cursor_blink_interval_ms) = cx
+            .try_global::<AppState>()
+            .map(|state| {
+                let app = state.app.lock();
+                (app.config.appearance.cursor_blink, app.config.appearance.cursor_blink_interval_ms)
+            })
+            .unwrap_or((true, 500));
+
         // Get dirty flag for lock-free checking of new SSH content
         let dirty_flag = {
             let term = terminal.lock();
@@ -71,22 +123,60 @@ impl TerminalView {
                 // Check dirty flag first (lock-free, fast path for SSH)
                 let has_new_content = dirty_flag.swap(false, std::sync::atomic::Ordering::AcqRel);
 
-                // Also check for terminal events (title changes, etc.)
-                let has_events = terminal_weak.upgrade().map(|t| {
+                // Also check for terminal events (title changes, bells, etc.)
+                let events = terminal_weak.upgrade().map(|t| {
                     let mut term = t.lock();
-                    !term.poll_events().is_empty()
-                }).unwrap_or(false);
+                    let events = term.poll_events();
+                    // New output (a dirty flag or PTY wakeup) may push scrollback over its memory cap
+                    if has_new_content || !events.is_empty() {
+                        term.enforce_memory_limit();
+                    }
+                    events
+                }).unwrap_or_default();
+                let has_events = !events.is_empty();
 
                 let should_notify = has_new_content || has_events;
 
                 // Handle cursor blinking - always update, render will check focus state
                 let _ = entity.update(cx, |view, cx| {
                     let now = Instant::now();
-                    if now.duration_since(view.last_blink_toggle).as_millis() >= CURSOR_BLINK_INTERVAL_MS as u128 {
-                        view.cursor_visible = !view.cursor_visible;
+                    if now.duration_since(view.last_blink_toggle) >= view.cursor_blink_interval {
                         view.last_blink_toggle = now;
+                        // A steady cursor, by config or by the application, stays shown
+                        let blinks = view.cursor_blink && !view.terminal.lock().last_content.cursor_steady;
+                        let visible = !blinks || !view.cursor_visible;
+                        if visible != view.cursor_visible {
+                            view.cursor_visible = visible;
+                            cx.notify();
+                        }
+                    }
+                    if view.is_selecting
+                        && view.selection_scroll != 0
+                        && now.duration_since(view.last_selection_scroll).as_millis() >= SELECTION_SCROLL_INTERVAL_MS as u128
+                    {
+                        view.last_selection_scroll = now;
+                        view.autoscroll_selection();
                         cx.notify();
                     }
+                    if has_events {
+                        crate::notifications::notify_for_events(&view.terminal, &events, !view.was_focused, cx);
+                        for event in &events {
+                            match *event {
+                                TerminalEvent::ZmodemDetected(direction) => {
+                                    let terminal = view.terminal.clone();
+                                    cx.defer(move |cx| ZmodemDialog::open(terminal, direction, cx));
+                                }
+                                TerminalEvent::SudoPrompt => view.sudo_prompt = true,
+                                TerminalEvent::HostKeyPrompt => {
+                                    if let Some(request) = view.terminal.lock().take_host_key_request() {
+                                        cx.defer(move |cx| HostKeyDialog::open(request, cx));
+                                    }
+                                }
+                                TerminalEvent::Exit(status) => view.handle_shell_exit(status, cx),
+                                _ => {}
+                            }
+                        }
+                    }
                     if should_notify {
                         cx.notify();
                     }
@@ -104,13 +194,25 @@ impl TerminalView {
             cell_height: px(14.0),
             bounds_origin: Arc::new(Mutex::new(point(px(0.0), px(0.0)))),
             is_selecting: false,
+            selection_position: point(px(0.0), px(0.0)),
+            selection_scroll: 0,
+            last_selection_scroll: Instant::now(),
+            last_click: None,
+            hovered_link: None,
             cursor_visible: true,
             last_blink_toggle: Instant::now(),
+            cursor_blink,
+            cursor_blink_interval: Duration::from_millis(cursor_blink_interval_ms.max(MIN_CURSOR_BLINK_INTERVAL_MS)),
             was_focused: false,
             color_scheme_override,
             search_bar: None,
             search_matches: Vec::new(),
             current_search_match: 0,
+            search_return_offset: 0,
+            marked_text: None,
+            sudo_prompt: false,
+            scrollbar_enabled: true,
+            scrollbar_drag: None,
             _subscriptions: Vec::new(),
         }
     }
@@ -145,6 +247,7 @@ impl TerminalView {
 
             self._subscriptions.push(subscription);
             self.search_bar = Some(search_bar.clone());
+            self.search_return_offset = self.terminal.lock().display_offset();
 
             // Focus the search bar
             window.focus(&search_bar.focus_handle(cx), cx);
@@ -155,8 +258,11 @@ impl TerminalView {
         cx.notify();
     }
 
-    /// Close the search bar
+    /// Close the search bar and go back to where the search started
     fn close_search(&mut self, cx: &mut Context<Self>) {
+        if self.search_bar.is_some() && !self.search_matches.is_empty() {
+            self.terminal.lock().scroll_to_offset(self.search_return_offset);
+        }
         self.search_bar = None;
         self.search_matches.clear();
         self.current_search_match = 0;
@@ -221,6 +327,15 @@ impl TerminalView {
 
         let keystroke = &event.keystroke;
 
+        // Typing at a sudo prompt means the user is answering it themselves
+        self.sudo_prompt = false;
+
+        // While an input method is composing, its keys (Enter, arrows,
+        // Backspace) belong to the composition
+        if self.marked_text.is_some() {
+            return;
+        }
+
         // Handle paste (Cmd+V on Mac, Ctrl+Shift+V elsewhere)
         let is_paste = (keystroke.modifiers.platform && keystroke.key == "v")
             || (keystroke.modifiers.control && keystroke.modifiers.shift && keystroke.key == "v");
@@ -233,7 +348,7 @@ impl TerminalView {
                         let term = self.terminal.lock();
                         term.clear_selection();
                     }
-                    self.paste_text(&text);
+                    self.paste_text(&text, cx);
                     cx.stop_propagation();
                     cx.notify();
                     return;
@@ -273,30 +388,34 @@ impl TerminalView {
             return;
         }
 
-        // Skip other platform modifier shortcuts
-        if keystroke.modifiers.platform {
+        // Skip other platform modifier shortcuts, and leave pane focus keys
+        // to the split container
+        if keystroke.modifiers.platform || Direction::from_keystroke(keystroke).is_some() {
             return;
         }
 
+        let scroll_on_input = scroll_to_bottom_on_input(cx);
+
         // Single lock acquisition for mode check and write to minimize latency
-        let handled = {
+        let sent = {
             let term = self.terminal.lock();
             let mode = term.mode();
 
             // Try escape sequence conversion
-            if let Some(escape_str) = keystroke_to_escape(keystroke, &mode, false) {
+            if let Some(escape_str) = keystroke_to_escape(keystroke, &mode, false, term.newline_mode()) {
                 tracing::debug!("Terminal escape sequence: {:?}", escape_str);
+                if scroll_on_input {
+                    term.scroll_to_bottom();
+                }
                 term.write(escape_str.as_bytes());
-                true
+                Some((escape_str.into_owned(), true))
             } else if !keystroke.modifiers.control && !keystroke.modifiers.alt {
-                // Handle regular character input
-                // Use key_char if available (for proper Unicode handling), otherwise key
-                let input = if let Some(key_char) = &keystroke.key_char {
-                    if !key_char.is_empty() && key_char.chars().all(|c| !c.is_control() || c == '\t') {
-                        Some(key_char.to_string())
-                    } else {
-                        None
-                    }
+                // Keys that produce text are left unhandled: the platform then
+                // delivers the text through the input handler
+                // (`replace_text_in_range`), which also carries IME commits
+                // and multi-codepoint graphemes as one unit
+                let input = if keystroke.key_char.is_some() {
+                    None
                 } else if keystroke.key.len() == 1 {
                     let c = keystroke.key.chars().next().unwrap();
                     // Only send printable ASCII characters
@@ -311,33 +430,108 @@ impl TerminalView {
 
                 if let Some(input) = input {
                     tracing::debug!("Terminal input: {:?}", input);
+                    if scroll_on_input {
+                        term.scroll_to_bottom();
+                    }
                     term.write(input.as_bytes());
-                    true
+                    Some((input, false))
                 } else {
-                    false
+                    None
                 }
             } else {
-                false
+                None
             }
         }; // Lock released here
 
-        if handled {
+        if let Some((sent, escaped)) = sent {
+            // Other terminals encode the key by their own modes (cursor keys, Enter)
+            self.broadcast_input(sent.as_bytes(), cx, |term| {
+                if escaped {
+                    keystroke_to_escape(keystroke, &term.mode(), false, term.newline_mode()).map(|s| s.into_owned().into_bytes())
+                } else {
+                    Some(sent.clone().into_bytes())
+                }
+            });
             cx.stop_propagation();
             cx.notify();
         }
     }
 
+    /// Repeat input typed here in the other tabs of the broadcast set
+    ///
+    /// `encode` gives the bytes for each receiving terminal. A lone Ctrl-C,
+    /// Ctrl-D or Ctrl-Z in `sent` (what this terminal got) is held for
+    /// confirmation first, unless `confirm_broadcast_control_keys` is off.
+    fn broadcast_input(&self, sent: &[u8], cx: &mut Context<Self>, encode: impl Fn(&Terminal) -> Option<Vec<u8>>) {
+        let Some((peers, confirm)) = cx.try_global::<AppState>().map(|state| {
+            let app = state.app.lock();
+            (app.broadcast_peers(&self.terminal), app.config.confirm_broadcast_control_keys)
+        }) else {
+            return;
+        };
+        let writes: BroadcastWrites = peers
+            .into_iter()
+            .filter_map(|peer| {
+                let bytes = encode(&peer.lock())?;
+                Some((peer, bytes))
+            })
+            .collect();
+        if writes.is_empty() {
+            return;
+        }
+
+        match disruptive_control_key(sent) {
+            Some(key) if confirm => cx.defer(move |cx| BroadcastConfirmDialog::open_for_control_key(key, writes, cx)),
+            _ => send_broadcast(&writes),
+        }
+    }
+
     /// Paste text, wrapping with bracketed paste sequences if mode is enabled
-    fn paste_text(&self, text: &str) {
-        let term = self.terminal.lock();
-        let mode = term.mode();
+    fn paste_text(&self, text: &str, cx: &mut Context<Self>) {
+        let pasted = {
+            let term = self.terminal.lock();
+            let pasted = paste_sequence(text, &term.mode()).into_owned();
+            term.write(pasted.as_bytes());
+            pasted
+        };
+        self.broadcast_input(pasted.as_bytes(), cx, |term| {
+            Some(paste_sequence(text, &term.mode()).into_owned().into_bytes())
+        });
+    }
 
-        if mode.contains(TermMode::BRACKETED_PASTE) {
-            let bracketed = format!("\x1b[200~{}\x1b[201~", text);
-            term.write(bracketed.as_bytes());
-        } else {
-            term.write(text.as_bytes());
+    /// Send text from the keyboard or an input method in a single write
+    fn commit_text(&mut self, text: &str, cx: &mut Context<Self>) {
+        self.marked_text = None;
+        if let Some(text) = text_input(text) {
+            tracing::debug!("Terminal input: {:?}", text);
+            self.cursor_visible = true;
+            self.last_blink_toggle = Instant::now();
+            let scroll_on_input = scroll_to_bottom_on_input(cx);
+            {
+                let term = self.terminal.lock();
+                if scroll_on_input {
+                    term.scroll_to_bottom();
+                }
+                term.write(text.as_bytes());
+            }
+            self.broadcast_input(text.as_bytes(), cx, |_| Some(text.as_bytes().to_vec()));
         }
+        cx.notify();
+    }
+
+    /// Answer the pending `sudo` prompt with the session's stored password
+    fn send_sudo_password(&mut self, cx: &mut Context<Self>) {
+        self.sudo_prompt = false;
+        self.terminal.lock().send_sudo_password();
+        cx.notify();
+    }
+
+    /// Screen bounds of the cursor cell, for placing the IME candidate window
+    fn cursor_bounds(&self, element_bounds: Bounds<Pixels>) -> Bounds<Pixels> {
+        let cursor = self.terminal.lock().last_content.cursor_point;
+        let x = element_bounds.origin.x + self.cell_width * cursor.column.0 as f32;
+        let y = element_bounds.origin.y + self.cell_height * cursor.line.0.max(0) as f32;
+        Bounds::new(point(x, y), size(self.cell_width, self.cell_height))
     }
 
     fn handle_mouse_down(&mut self, event: &MouseDownEvent, window: &mut Window, cx: &mut Context<Self>) {
@@ -351,6 +545,19 @@ impl TerminalView {
             event.position.y - bounds_origin.y,
         );
 
+        if event.button == MouseButton::Left && self.scrollbar_mouse_down(local_position) {
+            cx.notify();
+            return;
+        }
+
+        // Cmd/Ctrl-click opens a link, even when the application takes the mouse
+        if event.modifiers.secondary() {
+            if let Some((uri, _)) = self.link_at(local_position) {
+                cx.open_url(&uri);
+                return;
+            }
+        }
+
         let term = self.terminal.lock();
         let mode = term.mode();
         let term_size = term.size();
@@ -394,32 +601,173 @@ impl TerminalView {
         // Normal selection behavior
         term.clear_selection();
 
-        // Start new selection
-        let point = self.mouse_to_point(event.position);
-        let side = self.mouse_to_side(event.position);
-        term.start_selection(SelectionType::Simple, point, side);
+        // Start new selection: a word on double click, whole lines on triple.
+        // Selection works in grid coordinates, which shift with the scrollback offset
+        let point = viewport_to_grid(self.mouse_to_point(local_position), term.display_offset());
+        let side = self.mouse_to_side(local_position);
+        let now = Instant::now();
+        let clicks = click_count(self.last_click, now, point);
+        self.last_click = Some((now, point, clicks));
+        let ty = match clicks {
+            2 => SelectionType::Semantic,
+            3 => SelectionType::Lines,
+            _ => SelectionType::Simple,
+        };
+        term.start_selection(ty, point, side);
         self.is_selecting = true;
 
         cx.notify();
     }
 
     fn handle_mouse_move(&mut self, event: &MouseMoveEvent, _window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(grab) = self.scrollbar_drag {
+            // Button released outside the view - the mouse up never reached us
+            if event.pressed_button != Some(MouseButton::Left) {
+                self.scrollbar_drag = None;
+                return;
+            }
+            let bounds_origin = *self.bounds_origin.lock();
+            let y: f32 = (event.position.y - bounds_origin.y).into();
+            let term = self.terminal.lock();
+            let (track, rows, history) = self.scrollbar_track(&term);
+            term.scroll_to_offset(scrollbar_offset_for_thumb(track, rows, history, y - grab));
+            drop(term);
+            cx.notify();
+            return;
+        }
+
         if self.is_selecting {
+            // Button released outside the view - the mouse up never reached us
+            if event.pressed_button != Some(MouseButton::Left) {
+                self.is_selecting = false;
+                self.selection_scroll = 0;
+                return;
+            }
+
             // Adjust mouse position from window coordinates to view-local coordinates
             let bounds_origin = *self.bounds_origin.lock();
             let local_position = point(
                 event.position.x - bounds_origin.x,
                 event.position.y - bounds_origin.y,
             );
-            let point = self.mouse_to_point(local_position);
-            let side = self.mouse_to_side(local_position);
+            self.selection_position = local_position;
+
             let term = self.terminal.lock();
-            term.update_selection(point, side);
+
+            // Auto-scroll when dragging above or below the visible rows,
+            // faster the further away the mouse is
+            let cell_h: f32 = self.cell_height.into();
+            let px_y: f32 = local_position.y.into();
+            let view_height = cell_h * term.size().rows as f32;
+            self.selection_scroll = if cell_h <= 0.0 {
+                0
+            } else if px_y < 0.0 {
+                ((-px_y / cell_h).ceil() as i32).min(5)
+            } else if px_y > view_height {
+                -(((px_y - view_height) / cell_h).ceil() as i32).min(5)
+            } else {
+                0
+            };
+
+            self.extend_selection(&term, local_position);
             cx.notify();
+        } else {
+            let bounds_origin = *self.bounds_origin.lock();
+            let link = self
+                .link_at(point(
+                    event.position.x - bounds_origin.x,
+                    event.position.y - bounds_origin.y,
+                ))
+                .map(|(_, link)| link);
+            if link != self.hovered_link {
+                self.hovered_link = link;
+                cx.notify();
+            }
         }
     }
 
+    /// Link at a view-local position: an OSC 8 hyperlink, else a bare URL
+    fn link_at(&self, position: Point<Pixels>) -> Option<(String, HoveredLink)> {
+        if position.x < px(0.0) || position.y < px(0.0) {
+            return None;
+        }
+        let term = self.terminal.lock();
+        let point = viewport_to_grid(self.mouse_to_point(position), term.display_offset());
+        if let Some(uri) = term.hyperlink_at(point) {
+            return Some((uri.clone(), HoveredLink::Hyperlink(uri)));
+        }
+        term.url_under_point(point).map(|(url, cells)| (url, HoveredLink::Url(cells)))
+    }
+
+    /// Close, keep or restart a local terminal whose shell exited, as configured
+    fn handle_shell_exit(&mut self, status: Option<i32>, cx: &mut Context<Self>) {
+        let action = cx
+            .try_global::<AppState>()
+            .map(|state| state.app.lock().config.local_shell_exit)
+            .unwrap_or_default();
+        let notice = match status {
+            Some(code) => format!("Process exited with code {}", code),
+            None => "Process exited".to_string(),
+        };
+        let show = |terminal: &Arc<Mutex<Terminal>>, text: &str| {
+            terminal.lock().print_notice(&format!("\r\n\x1b[1;33m[{}]\x1b[0m\r\n", text));
+        };
+
+        match action {
+            ShellExitAction::Keep => show(&self.terminal, &notice),
+            ShellExitAction::Restart => {
+                // A shell that dies straight away would otherwise restart forever
+                let ran_for = self.terminal.lock().uptime().unwrap_or_default();
+                if ran_for < MIN_SHELL_RUN_BEFORE_RESTART {
+                    show(&self.terminal, &format!("{}; not restarting a shell that exited right away", notice));
+                    return;
+                }
+                show(&self.terminal, &format!("{}, restarting", notice));
+                if let Err(e) = self.terminal.lock().restart_shell() {
+                    tracing::error!("Failed to restart shell: {}", e);
+                    show(&self.terminal, &format!("Could not start a new shell: {}", e));
+                }
+            }
+            ShellExitAction::Close => {
+                let terminal = self.terminal.clone();
+                cx.defer(move |cx| {
+                    let closed = cx.try_global::<AppState>().is_some_and(|state| {
+                        let mut app = state.app.lock();
+                        let tab_id = app.tabs.iter().find(|tab| Arc::ptr_eq(&tab.terminal, &terminal)).map(|tab| tab.id);
+                        tab_id.map(|id| app.close_tab(id)).is_some()
+                    });
+                    // A split pane isn't a tab of its own
+                    if !closed {
+                        show(&terminal, &notice);
+                    }
+                    cx.refresh_windows();
+                });
+            }
+        }
+    }
+
+    /// Scroll one auto-scroll step and extend the selection to the new edge row
+    fn autoscroll_selection(&mut self) {
+        let term = self.terminal.lock();
+        term.scroll(self.selection_scroll);
+        self.extend_selection(&term, self.selection_position);
+    }
+
+    /// Extend the selection to a view-local position, clamped to the visible rows
+    fn extend_selection(&self, term: &Terminal, position: Point<Pixels>) {
+        let rows = term.size().rows.max(1) as f32;
+        let max_y = self.cell_height * rows - px(1.0);
+        let clamped = point(position.x, position.y.max(px(0.0)).min(max_y));
+        let grid_point = viewport_to_grid(self.mouse_to_point(clamped), term.display_offset());
+        term.update_selection(grid_point, self.mouse_to_side(clamped));
+    }
+
     fn handle_mouse_up(&mut self, event: &MouseUpEvent, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.scrollbar_drag.take().is_some() {
+            cx.notify();
+            return;
+        }
+
         // Adjust mouse position from window coordinates to view-local coordinates
         let bounds_origin = *self.bounds_origin.lock();
         let local_position = point(
@@ -465,10 +813,36 @@ impl TerminalView {
         }
 
         drop(term);
+        if self.is_selecting && appearance_flag(cx, |appearance| appearance.copy_on_select) {
+            if let Some(text) = self.selected_text().filter(|text| !text.is_empty()) {
+                cx.write_to_clipboard(ClipboardItem::new_string(text));
+            }
+        }
         self.is_selecting = false;
+        self.selection_scroll = 0;
         cx.notify();
     }
 
+    /// Paste the clipboard on middle click, if enabled
+    ///
+    /// Applications that take the mouse get the click instead, unless Shift is held.
+    fn handle_middle_click(&mut self, event: &MouseDownEvent, window: &mut Window, cx: &mut Context<Self>) {
+        if !appearance_flag(cx, |appearance| appearance.middle_click_paste) {
+            return;
+        }
+        let app_mouse = self.terminal.lock().mode().intersects(
+            TermMode::MOUSE_REPORT_CLICK | TermMode::MOUSE_DRAG | TermMode::MOUSE_MOTION,
+        );
+        if app_mouse && !event.modifiers.shift {
+            return;
+        }
+        cx.focus_self(window);
+        if let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) {
+            self.paste_text(&text, cx);
+            cx.notify();
+        }
+    }
+
     fn handle_scroll(&mut self, event: &ScrollWheelEvent, _window: &mut Window, cx: &mut Context<Self>) {
         // Adjust mouse position from window coordinates to view-local coordinates
         let bounds_origin = *self.bounds_origin.lock();
@@ -480,45 +854,48 @@ impl TerminalView {
         let term = self.terminal.lock();
         let mode = term.mode();
 
-        // Check if terminal wants mouse events (scroll = mouse button 64/65)
+        // Check if terminal wants mouse events (scroll = mouse button 64/65,
+        // horizontal scroll = 66/67)
         if mode.contains(TermMode::MOUSE_REPORT_CLICK)
             || mode.contains(TermMode::MOUSE_DRAG)
             || mode.contains(TermMode::MOUSE_MOTION)
         {
-            let lines = match event.delta {
-                ScrollDelta::Lines(lines) => -lines.y as i32,
+            let (lines, columns) = match event.delta {
+                ScrollDelta::Lines(lines) => (-lines.y as i32, -lines.x as i32),
                 ScrollDelta::Pixels(pixels) => {
+                    let cell_w: f32 = self.cell_width.into();
                     let cell_h: f32 = self.cell_height.into();
-                    if cell_h > 0.0 {
-                        let px_y: f32 = pixels.y.into();
-                        -(px_y / cell_h).round() as i32
-                    } else {
-                        0
-                    }
+                    let px_x: f32 = pixels.x.into();
+                    let px_y: f32 = pixels.y.into();
+                    let lines = if cell_h > 0.0 { -(px_y / cell_h).round() as i32 } else { 0 };
+                    let columns = if cell_w > 0.0 { -(px_x / cell_w).round() as i32 } else { 0 };
+                    (lines, columns)
                 }
             };
 
-            if lines != 0 {
+            if lines != 0 || columns != 0 {
                 let point = self.mouse_to_point(local_position);
                 let term_size = term.size();
                 // Clamp coordinates to terminal dimensions (and ensure positive)
                 let col = (point.column.0 as u32 + 1).max(1).min(term_size.cols as u32);
                 let row = (point.line.0 as u32 + 1).max(1).min(term_size.rows as u32);
 
-                // Button 64 = scroll up, 65 = scroll down
-                let button = if lines < 0 { 64 } else { 65 };
-                let scroll_count = lines.abs();
-
-                for _ in 0..scroll_count {
-                    let mouse_report = if mode.contains(TermMode::SGR_MOUSE) {
-                        format!("\x1b[<{};{};{}M", button, col, row)
-                    } else {
-                        let cb = (32 + button) as u8;
-                        let cx_char = (32 + col.min(223)) as u8;
-                        let cy_char = (32 + row.min(223)) as u8;
-                        format!("\x1b[M{}{}{}", cb as char, cx_char as char, cy_char as char)
-                    };
-                    term.write(mouse_report.as_bytes());
+                // Button 64 = scroll up, 65 = scroll down, 66 = left, 67 = right
+                let vertical = (if lines < 0 { 64 } else { 65 }, lines.unsigned_abs());
+                let horizontal = (if columns < 0 { 66 } else { 67 }, columns.unsigned_abs());
+
+                for (button, scroll_count) in [vertical, horizontal] {
+                    for _ in 0..scroll_count {
+                        let mouse_report = if mode.contains(TermMode::SGR_MOUSE) {
+                            format!("\x1b[<{};{};{}M", button, col, row)
+                        } else {
+                            let cb = (32 + button) as u8;
+                            let cx_char = (32 + col.min(223)) as u8;
+                            let cy_char = (32 + row.min(223)) as u8;
+                            format!("\x1b[M{}{}{}", cb as char, cx_char as char, cy_char as char)
+                        };
+                        term.write(mouse_report.as_bytes());
+                    }
                 }
                 drop(term);
                 cx.notify();
@@ -526,7 +903,8 @@ impl TerminalView {
             }
         }
 
-        // Normal scroll behavior (scrollback)
+        // Normal scroll behavior (scrollback). There is no horizontal viewport,
+        // so the x component is ignored outside mouse mode.
         // On macOS with natural scrolling: swipe up = positive delta = scroll into history
         let lines = match event.delta {
             ScrollDelta::Lines(lines) => lines.y as i32,
@@ -547,7 +925,46 @@ impl TerminalView {
         }
     }
 
+    /// Track height, screen rows and history size the scrollbar is drawn for
+    fn scrollbar_track(&self, term: &Terminal) -> (f32, usize, usize) {
+        let rows = term.size().rows as usize;
+        let track: f32 = (self.cell_height * rows as f32).into();
+        (track, rows, term.last_content.history_size)
+    }
+
+    /// Start dragging the scrollbar if a click at a view-local position hit it
+    ///
+    /// A click on the track away from the thumb first jumps there, centring
+    /// the clicked spot. Returns whether the click was the scrollbar's.
+    fn scrollbar_mouse_down(&mut self, position: Point<Pixels>) -> bool {
+        let term = self.terminal.lock();
+        let (track, rows, history) = self.scrollbar_track(&term);
+        let right: f32 = (self.cell_width * term.size().cols as f32).into();
+        let (x, y): (f32, f32) = (position.x.into(), position.y.into());
+        let on_scrollbar = self.scrollbar_enabled
+            && history > 0
+            && x >= right - SCROLLBAR_WIDTH - 2.0 * SCROLLBAR_MARGIN
+            && x < right
+            && (0.0..track).contains(&y);
+        if !on_scrollbar {
+            return false;
+        }
+
+        let (top, height) = scrollbar_thumb(track, rows, history, term.display_offset());
+        let grab = if (top..top + height).contains(&y) {
+            y - top
+        } else {
+            term.scroll_to_offset(scrollbar_offset_for_click(track, rows, history, y));
+            height / 2.0
+        };
+        self.scrollbar_drag = Some(grab);
+        true
+    }
+
     /// Convert mouse position to terminal point
+    ///
+    /// The point is in viewport coordinates, as mouse reports want it; use
+    /// [`viewport_to_grid`] for selections and cell lookups.
     fn mouse_to_point(&self, position: Point<Pixels>) -> TermPoint {
         let cell_w: f32 = self.cell_width.into();
         let cell_h: f32 = self.cell_height.into();
@@ -586,8 +1003,26 @@ impl TerminalView {
     }
 }
 
+/// Link under the mouse
+#[derive(Clone, PartialEq)]
+enum HoveredLink {
+    /// OSC 8 hyperlink, by URI; every cell linking there is underlined
+    Hyperlink(String),
+    /// Bare URL spanning these grid cells
+    Url(RangeInclusive<TermPoint>),
+}
+
+impl HoveredLink {
+    fn covers(&self, point: TermPoint, cell: &Cell) -> bool {
+        match self {
+            HoveredLink::Hyperlink(uri) => cell.hyperlink().is_some_and(|link| link.uri() == uri),
+            HoveredLink::Url(cells) => cells.contains(&point),
+        }
+    }
+}
+
 /// Cursor shape for rendering
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum CursorShape {
     Block,
     Hollow,
@@ -602,6 +1037,10 @@ struct PositionedTextRun {
     text: String,
     fg_color: Hsla,
     bold: bool,
+    italic: bool,
+    dim: bool,
+    underline: bool,
+    strikethrough: bool,
 }
 
 /// Data prepared in prepaint for use in paint
@@ -616,10 +1055,60 @@ struct TerminalPaintData {
     cursor: Option<(usize, usize, CursorShape)>,
     background_color: Hsla,
     cursor_color: Hsla,
-    /// Scrollbar data: (display_offset, history_size, show_scrollbar)
+    /// Scrollbar data: (display_offset, history_size)
     scrollbar: Option<(usize, usize)>,
+    /// The scrollbar thumb is being dragged
+    scrollbar_dragging: bool,
+    /// Scrollbar markers: (position as a share of the track from the top, color)
+    scrollbar_markers: Vec<(f32, Hsla)>,
     /// Search highlight cells: (col, row, is_current_match)
     search_highlights: Vec<(usize, usize, bool)>,
+    /// IME composition shown at the cursor: (col, row, text)
+    marked_text: Option<(usize, usize, SharedString)>,
+    /// Screen rows that soft-wrap onto the next one
+    wrapped_rows: Vec<usize>,
+    /// Column to draw the ruler after, if within the screen
+    column_ruler: Option<usize>,
+}
+
+/// Whether typing should first jump back to the live screen
+fn scroll_to_bottom_on_input(cx: &App) -> bool {
+    cx.try_global::<AppState>()
+        .map(|state| state.app.lock().config.scroll_to_bottom_on_input)
+        .unwrap_or(true)
+}
+
+/// Scrollbar thumb top and height within a track of `track` pixels
+///
+/// The thumb is as tall as the share of the content on screen, and sits at
+/// the bottom when `display_offset` is 0.
+fn scrollbar_thumb(track: f32, rows: usize, history: usize, display_offset: usize) -> (f32, f32) {
+    let total = (history + rows).max(1) as f32;
+    let height = (track * rows as f32 / total).max(SCROLLBAR_MIN_THUMB).min(track);
+    let range = track - height;
+    let ratio = if history > 0 { display_offset.min(history) as f32 / history as f32 } else { 0.0 };
+    (range * (1.0 - ratio), height)
+}
+
+/// Display offset that puts the scrollbar thumb's top at `top`
+fn scrollbar_offset_for_thumb(track: f32, rows: usize, history: usize, top: f32) -> usize {
+    let (_, height) = scrollbar_thumb(track, rows, history, 0);
+    let range = track - height;
+    if range <= 0.0 {
+        return 0;
+    }
+    let ratio = 1.0 - (top / range).clamp(0.0, 1.0);
+    (ratio * history as f32).round() as usize
+}
+
+/// Display offset that centres the line under a click at `y` on the track
+fn scrollbar_offset_for_click(track: f32, rows: usize, history: usize, y: f32) -> usize {
+    if track <= 0.0 {
+        return 0;
+    }
+    let line = (y / track).clamp(0.0, 1.0) * (history + rows) as f32;
+    let top = (line - rows as f32 / 2.0).round().clamp(0.0, history as f32);
+    history - top as usize
 }
 
 fn color_to_hsla(color: Color, colors: &alacritty_terminal::term::color::Colors, scheme: &ColorScheme) -> Hsla {
@@ -627,6 +1116,71 @@ fn color_to_hsla(color: Color, colors: &alacritty_terminal::term::color::Colors,
     rgb_to_hsla(rgb)
 }
 
+/// A setting from the appearance config, false if the app state isn't available
+fn appearance_flag(cx: &App, flag: impl FnOnce(&TerminalAppearance) -> bool) -> bool {
+    cx.try_global::<AppState>()
+        .map(|state| flag(&state.app.lock().config.appearance))
+        .unwrap_or(false)
+}
+
+/// Grid point for a point on screen while scrolled `display_offset` lines back
+fn viewport_to_grid(point: TermPoint, display_offset: usize) -> TermPoint {
+    TermPoint::new(Line(point.line.0 - display_offset as i32), point.column)
+}
+
+/// Place of a click at `point` in a run of quick clicks on one cell: 1, 2 or 3
+///
+/// A fourth click starts over as a single click.
+fn click_count(last: Option<(Instant, TermPoint, u8)>, now: Instant, point: TermPoint) -> u8 {
+    match last {
+        Some((at, last_point, count))
+            if last_point == point
+                && now.duration_since(at) <= Duration::from_millis(MULTI_CLICK_INTERVAL_MS) =>
+        {
+            count % 3 + 1
+        }
+        _ => 1,
+    }
+}
+
+/// Part of the canvas the grid is laid out in, `padding` pixels in from each
+/// edge. The padding shrinks to fit so a tiny view still keeps a grid.
+fn grid_bounds(bounds: Bounds<Pixels>, padding: f32) -> Bounds<Pixels> {
+    let width: f32 = bounds.size.width.into();
+    let height: f32 = bounds.size.height.into();
+    let padding = padding.max(0.0).min(width / 2.0).min(height / 2.0);
+    Bounds::new(
+        point(bounds.origin.x + px(padding), bounds.origin.y + px(padding)),
+        size(px(width - 2.0 * padding), px(height - 2.0 * padding)),
+    )
+}
+
+/// Cursor to draw: hollow when unfocused, otherwise the shape the application
+/// asked for with DECSCUSR, falling back to the configured one
+fn cursor_shape(focused: bool, requested: Option<AnsiCursorShape>, configured: ConfiguredCursorShape) -> CursorShape {
+    if !focused {
+        return CursorShape::Hollow;
+    }
+    match requested {
+        Some(AnsiCursorShape::Block) => CursorShape::Block,
+        Some(AnsiCursorShape::Beam) => CursorShape::Bar,
+        Some(AnsiCursorShape::Underline) => CursorShape::Underline,
+        Some(AnsiCursorShape::HollowBlock) => CursorShape::Hollow,
+        Some(AnsiCursorShape::Hidden) | None => match configured {
+            ConfiguredCursorShape::Block => CursorShape::Block,
+            ConfiguredCursorShape::Bar => CursorShape::Bar,
+            ConfiguredCursorShape::Underline => CursorShape::Underline,
+        },
+    }
+}
+
+/// Faint text (SGR 2) keeps its hue and is drawn at this fraction of its lightness
+const DIM_LIGHTNESS: f32 = 0.6;
+
+fn dim_color(color: Hsla) -> Hsla {
+    Hsla { l: color.l * DIM_LIGHTNESS, ..color }
+}
+
 fn rgb_to_hsla(rgb: alacritty_terminal::vte::ansi::Rgb) -> Hsla {
     let r = rgb.r as f32 / 255.0;
     let g = rgb.g as f32 / 255.0;
@@ -664,25 +1218,35 @@ impl Render for TerminalView {
         let focused = self.focus_handle.is_focused(window);
 
         // Get color scheme - check override first, then global
-        let (scheme, show_scrollbar) = {
-            let global_config = cx.try_global::<AppState>().map(|state| {
+        // Resolved every frame, so theme switches apply to open terminals live
+        let override_name = self.color_scheme_override.as_deref();
+        let (scheme, show_scrollbar, refresh_on_focus, show_wrap_indicator, column_ruler, ligatures, configured_cursor, padding, broadcast_peers) = cx
+            .try_global::<AppState>()
+            .map(|state| {
                 let app = state.app.lock();
-                (app.config.appearance.color_scheme(), app.config.show_scrollbar)
+                let appearance = &app.config.appearance;
+                let scheme = override_name
+                    .and_then(|name| appearance.find_scheme(name))
+                    .unwrap_or_else(|| appearance.color_scheme());
+                (
+                    scheme,
+                    app.config.show_scrollbar,
+                    app.config.refresh_on_focus,
+                    app.config.show_wrap_indicator,
+                    app.config.column_ruler,
+                    appearance.ligatures,
+                    appearance.cursor_shape,
+                    app.config.terminal_padding,
+                    app.broadcast_peers(&self.terminal).len(),
+                )
+            })
+            .unwrap_or_else(|| {
+                let scheme = override_name
+                    .and_then(ColorScheme::builtin)
+                    .unwrap_or_else(ColorScheme::default_dark);
+                (scheme, true, true, false, 0, false, ConfiguredCursorShape::Block, 0.0, 0)
             });
-
-            let scheme = self
-                .color_scheme_override
-                .as_ref()
-                .and_then(|name| ColorScheme::builtin(name))
-                .unwrap_or_else(|| {
-                    global_config.as_ref()
-                        .map(|(s, _)| s.clone())
-                        .unwrap_or_else(ColorScheme::default_dark)
-                });
-
-            let show_scrollbar = global_config.map(|(_, sb)| sb).unwrap_or(true);
-            (scheme, show_scrollbar)
-        };
+        self.scrollbar_enabled = show_scrollbar;
 
         // Reset cursor blink when focus changes
         if focused != self.was_focused {
@@ -690,12 +1254,23 @@ impl Render for TerminalView {
                 // Just gained focus - reset blink to visible
                 self.cursor_visible = true;
                 self.last_blink_toggle = Instant::now();
+                // Don't trust anything drawn while the tab was in the background
+                if refresh_on_focus {
+                    self.terminal.lock().refresh_display();
+                }
             }
             self.was_focused = focused;
         }
 
         let font_family = self.font_family.clone();
         let font_family_paint = self.font_family.clone();
+        // Each glyph is still placed in its own cell; this only decides
+        // whether the shaper may substitute ligature glyphs
+        let font_features = if ligatures {
+            FontFeatures::default()
+        } else {
+            FontFeatures::disable_ligatures()
+        };
         let font_size = self.font_size;
 
         // Update cell dimensions from font metrics for accurate mouse coordinate conversion
@@ -720,12 +1295,16 @@ impl Render for TerminalView {
         // Clone search bar for use in render
         let search_bar_opt = self.search_bar.clone();
 
+        // Weak handle so paint can route drags outside the view back to us
+        let view = cx.entity().downgrade();
+
         let mut container = div()
             .relative()
             .size_full()
             .bg(bg_color)
             .track_focus(&self.focus_handle)
             .on_mouse_down(MouseButton::Left, cx.listener(Self::handle_mouse_down))
+            .on_mouse_down(MouseButton::Middle, cx.listener(Self::handle_middle_click))
             .on_mouse_move(cx.listener(Self::handle_mouse_move))
             .on_mouse_up(MouseButton::Left, cx.listener(Self::handle_mouse_up))
             .on_scroll_wheel(cx.listener(Self::handle_scroll))
@@ -738,7 +1317,11 @@ impl Render for TerminalView {
                         let scheme = scheme.clone();
                         let search_matches = self.search_matches.clone();
                         let current_search_match = self.current_search_match;
+                        let marked_text = self.marked_text.clone();
+                        let hovered_link = self.hovered_link.clone();
+                        let scrollbar_dragging = self.scrollbar_drag.is_some();
                         move |bounds, window, _cx| {
+                            let bounds = grid_bounds(bounds, padding);
                             // Update bounds origin for mouse coordinate conversion
                             *bounds_origin.lock() = bounds.origin;
 
@@ -757,7 +1340,7 @@ impl Render for TerminalView {
                             let rows = (bounds.size.height / cell_height).floor() as usize;
 
                             // Sync and clone content - resize BEFORE sync if needed
-                            let content = {
+                            let (content, command_marks) = {
                                 let mut terminal = terminal.lock();
 
                                 // Check if resize is needed BEFORE syncing
@@ -779,7 +1362,12 @@ impl Render for TerminalView {
                                 }
 
                                 terminal.sync();
-                                terminal.last_content.clone()
+                                let command_marks = if show_scrollbar && terminal.last_content.history_size > 0 {
+                                    terminal.command_marks()
+                                } else {
+                                    Vec::new()
+                                };
+                                (terminal.last_content.clone(), command_marks)
                             };
                             // Lock is now RELEASED
 
@@ -799,6 +1387,8 @@ impl Render for TerminalView {
                             let mut current_run: Option<PositionedTextRun> = None;
                             let mut current_grid_line: Option<i32> = None;
                             let mut screen_row: usize = 0;
+                            let mut cursor_cell = None;
+                            let mut wrapped_rows = Vec::new();
 
                             // Process cached cells (already extracted, no lock needed)
                             for indexed_cell in &content.cells {
@@ -807,6 +1397,13 @@ impl Render for TerminalView {
                                 let grid_line = pt.line.0;
                                 let col_idx = pt.column.0;
 
+                                // Set on the last cell of the row, which may be a wide char spacer
+                                if show_wrap_indicator && cell.flags.contains(Flags::WRAPLINE) {
+                                    if let Ok(row) = usize::try_from(grid_line + render_display_offset as i32) {
+                                        wrapped_rows.push(row);
+                                    }
+                                }
+
                                 if cell.flags.contains(Flags::WIDE_CHAR_SPACER) {
                                     continue;
                                 }
@@ -822,6 +1419,10 @@ impl Render for TerminalView {
                                     current_grid_line = Some(grid_line);
                                 }
 
+                                if pt == cursor_pos {
+                                    cursor_cell = Some(cell);
+                                }
+
                                 // Check selection
                                 if let Some(ref range) = content.selection {
                                     if range.contains(pt) {
@@ -857,14 +1458,26 @@ impl Render for TerminalView {
                                     continue;
                                 }
 
+                                let dim = cell.flags.contains(Flags::DIM);
                                 let fg_color = color_to_hsla(cell_fg, colors, &scheme);
+                                let fg_color = if dim { dim_color(fg_color) } else { fg_color };
                                 let bold = cell.flags.contains(Flags::BOLD);
+                                let italic = cell.flags.contains(Flags::ITALIC);
+                                // Double, curly and dotted underlines are drawn as a plain one,
+                                // as is the link under the mouse
+                                let underline = cell.flags.intersects(Flags::ALL_UNDERLINES)
+                                    || hovered_link.as_ref().is_some_and(|link| link.covers(pt, cell));
+                                let strikethrough = cell.flags.contains(Flags::STRIKEOUT);
 
                                 let can_extend = current_run.as_ref().map_or(false, |run| {
                                     run.line == screen_row
                                         && run.col + run.text.chars().count() == col_idx
                                         && run.fg_color == fg_color
                                         && run.bold == bold
+                                        && run.italic == italic
+                                        && run.dim == dim
+                                        && run.underline == underline
+                                        && run.strikethrough == strikethrough
                                 });
 
                                 if can_extend {
@@ -879,6 +1492,10 @@ impl Render for TerminalView {
                                         text: c.to_string(),
                                         fg_color,
                                         bold,
+                                        italic,
+                                        dim,
+                                        underline,
+                                        strikethrough,
                                     });
                                 }
                             }
@@ -896,11 +1513,7 @@ impl Render for TerminalView {
 
                                 // Only show cursor if it's within visible area
                                 if line >= 0 && (line as usize) < rows && col < cols {
-                                    let shape = if focused {
-                                        CursorShape::Block
-                                    } else {
-                                        CursorShape::Hollow
-                                    };
+                                    let shape = cursor_shape(focused, content.cursor_shape, configured_cursor);
                                     Some((col, line as usize, shape))
                                 } else {
                                     None
@@ -909,8 +1522,8 @@ impl Render for TerminalView {
                                 None
                             };
 
-                            // Compute cursor color from scheme
-                            let cursor_color = rgb_to_hsla(hex_to_rgb(scheme.cursor));
+                            // Compute cursor color (OSC 12 override, then reverse video, then scheme)
+                            let cursor_color = rgb_to_hsla(cursor_color_with_scheme(colors, &scheme, cursor_cell));
                             let background_color = rgb_to_hsla(hex_to_rgb(scheme.background));
 
                             // Scrollbar data: only show if enabled and there's history to scroll
@@ -920,6 +1533,32 @@ impl Render for TerminalView {
                                 None
                             };
 
+                            // Scrollbar markers: command starts (red if the command
+                            // failed), then search matches on top
+                            let mut scrollbar_markers = Vec::new();
+                            if scrollbar.is_some() {
+                                let total = (render_history_size + rows).max(1) as f32;
+                                for &(line, exit_code) in &command_marks {
+                                    let color = if exit_code.is_some_and(|code| code != 0) {
+                                        hsla(0.97, 0.8, 0.7, 0.9)
+                                    } else {
+                                        hsla(0.6, 0.6, 0.7, 0.6)
+                                    };
+                                    scrollbar_markers.push((line as f32 / total, color));
+                                }
+                                for (idx, &(match_line, _, _)) in search_matches.iter().enumerate() {
+                                    let line = match_line + render_history_size as i32;
+                                    if line >= 0 {
+                                        let color = if idx == current_search_match {
+                                            hsla(0.08, 0.9, 0.5, 0.9)
+                                        } else {
+                                            hsla(0.15, 0.9, 0.5, 0.8)
+                                        };
+                                        scrollbar_markers.push((line as f32 / total, color));
+                                    }
+                                }
+                            }
+
                             // Build search highlights - convert line offsets to screen positions
                             let mut search_highlights = Vec::new();
                             for (idx, &(match_line, match_col, match_len)) in search_matches.iter().enumerate() {
@@ -935,6 +1574,14 @@ impl Render for TerminalView {
                                 }
                             }
 
+                            // Composition sits at the cursor on the live screen
+                            let marked_text = marked_text.filter(|_| render_display_offset == 0).and_then(|text| {
+                                let line = usize::try_from(cursor_pos.line.0).ok().filter(|&l| l < rows)?;
+                                Some((cursor_pos.column.0, line, SharedString::from(text)))
+                            });
+
+                            let column_ruler = Some(column_ruler).filter(|&col| col > 0 && col < cols);
+
                             TerminalPaintData {
                                 cell_width,
                                 cell_height,
@@ -947,14 +1594,36 @@ impl Render for TerminalView {
                                 background_color,
                                 cursor_color,
                                 scrollbar,
+                                scrollbar_dragging,
+                                scrollbar_markers,
                                 search_highlights,
+                                marked_text,
+                                wrapped_rows,
+                                column_ruler,
                             }
                         }
                     },
                     {
                         let terminal = terminal.clone();
+                        let focus_handle = self.focus_handle.clone();
+                        let input_view = cx.entity();
                         move |bounds, data, window, cx| {
-                            let origin = bounds.origin;
+                            let grid = grid_bounds(bounds, padding);
+                            let origin = grid.origin;
+
+                            // Route typed text and IME composition to the view
+                            window.handle_input(&focus_handle, ElementInputHandler::new(grid, input_view), cx);
+
+                            // Drag-selection continues outside the view bounds (for auto-scroll),
+                            // where the element's own mouse move handler doesn't fire
+                            window.on_mouse_event({
+                                let view = view.clone();
+                                move |event: &MouseMoveEvent, phase, window, cx| {
+                                    if phase == DispatchPhase::Bubble && !bounds.contains(&event.position) {
+                                        let _ = view.update(cx, |view, cx| view.handle_mouse_move(event, window, cx));
+                                    }
+                                }
+                            });
 
                             // Draw background rects
                             for (col, line, color) in &data.bg_rects {
@@ -992,6 +1661,15 @@ impl Render for TerminalView {
                                 ));
                             }
 
+                            // Draw column ruler behind the text
+                            if let Some(col) = data.column_ruler {
+                                let x = origin.x + data.cell_width * col as f32;
+                                window.paint_quad(fill(
+                                    Bounds::new(point(x, origin.y), size(px(1.0), data.cell_height * data.rows as f32)),
+                                    hsla(0.0, 0.0, 0.5, 0.25),
+                                ));
+                            }
+
                             // Draw text runs
                             for run in &data.text_runs {
                                 let x = origin.x + data.cell_width * run.col as f32;
@@ -999,18 +1677,28 @@ impl Render for TerminalView {
 
                                 let text: SharedString = run.text.clone().into();
                                 let font_weight = if run.bold { FontWeight::BOLD } else { FontWeight::NORMAL };
+                                let font_style = if run.italic { FontStyle::Italic } else { FontStyle::Normal };
 
                                 let text_run = gpui::TextRun {
                                     len: text.len(),
                                     font: Font {
                                         family: font_family_paint.clone(),
+                                        features: font_features.clone(),
                                         weight: font_weight,
+                                        style: font_style,
                                         ..Default::default()
                                     },
                                     color: run.fg_color,
                                     background_color: None,
-                                    underline: None,
-                                    strikethrough: None,
+                                    underline: run.underline.then(|| UnderlineStyle {
+                                        thickness: px(1.0),
+                                        color: Some(run.fg_color),
+                                        wavy: false,
+                                    }),
+                                    strikethrough: run.strikethrough.then(|| StrikethroughStyle {
+                                        thickness: px(1.0),
+                                        color: Some(run.fg_color),
+                                    }),
                                 };
 
                                 let shaped = window.text_system().shape_line(
@@ -1088,32 +1776,49 @@ impl Render for TerminalView {
                                 }
                             }
 
+                            // Draw IME composition over the cursor, underlined like other text fields
+                            if let Some((col, line, text)) = &data.marked_text {
+                                let x = origin.x + data.cell_width * *col as f32;
+                                let y = origin.y + data.cell_height * *line as f32;
+                                let text_run = gpui::TextRun {
+                                    len: text.len(),
+                                    font: Font {
+                                        family: font_family_paint.clone(),
+                                        ..Default::default()
+                                    },
+                                    color: data.cursor_color,
+                                    background_color: Some(data.background_color),
+                                    underline: Some(UnderlineStyle {
+                                        thickness: px(1.0),
+                                        color: Some(data.cursor_color),
+                                        wavy: false,
+                                    }),
+                                    strikethrough: None,
+                                };
+                                let shaped = window.text_system().shape_line(text.clone(), font_size, &[text_run], None);
+                                let _ = shaped.paint(point(x, y), data.cell_height, TextAlign::Left, None, window, cx);
+                            }
+
+                            // Mark soft-wrapped rows at the right edge of the last column
+                            let wrap_marker_width = px(2.0);
+                            for row in &data.wrapped_rows {
+                                let x = origin.x + data.cell_width * data.cols as f32 - wrap_marker_width;
+                                let y = origin.y + data.cell_height * *row as f32;
+                                window.paint_quad(fill(
+                                    Bounds::new(point(x, y), size(wrap_marker_width, data.cell_height)),
+                                    hsla(0.6, 0.5, 0.7, 0.5),
+                                ));
+                            }
+
                             // Draw scrollbar if enabled and there's scrollback content
                             if let Some((display_offset, history_size)) = data.scrollbar {
-                                let scrollbar_width = px(6.0);
-                                let scrollbar_margin = px(2.0);
+                                let scrollbar_width = px(SCROLLBAR_WIDTH);
                                 let terminal_height = data.cell_height * data.rows as f32;
-
-                                // Total scrollable content = history + screen
-                                let total_lines = history_size + data.rows;
-                                let visible_ratio = data.rows as f32 / total_lines as f32;
-
-                                // Thumb size (minimum 20px for usability)
-                                let thumb_height = (terminal_height * visible_ratio).max(px(20.0));
-
-                                // Thumb position: when display_offset=history_size, thumb at top
-                                // when display_offset=0, thumb at bottom
-                                let scroll_range = terminal_height - thumb_height;
-                                let scroll_ratio = if history_size > 0 {
-                                    display_offset as f32 / history_size as f32
-                                } else {
-                                    0.0
-                                };
-                                // Invert: high display_offset = scrolled up = thumb at top
-                                let thumb_y = origin.y + scroll_range * (1.0 - scroll_ratio);
+                                let (thumb_top, thumb_height) =
+                                    scrollbar_thumb(terminal_height.into(), data.rows, history_size, display_offset);
 
                                 // Scrollbar track (subtle background)
-                                let track_x = origin.x + data.cell_width * data.cols as f32 - scrollbar_width - scrollbar_margin;
+                                let track_x = origin.x + data.cell_width * data.cols as f32 - scrollbar_width - px(SCROLLBAR_MARGIN);
                                 window.paint_quad(fill(
                                     Bounds::new(
                                         point(track_x, origin.y),
@@ -1122,14 +1827,25 @@ impl Render for TerminalView {
                                     hsla(0.0, 0.0, 0.5, 0.1),
                                 ));
 
-                                // Scrollbar thumb
+                                // Scrollbar thumb, brighter while held
+                                let thumb_alpha = if data.scrollbar_dragging { 0.7 } else { 0.4 };
                                 window.paint_quad(fill(
                                     Bounds::new(
-                                        point(track_x, thumb_y),
-                                        size(scrollbar_width, thumb_height),
+                                        point(track_x, origin.y + px(thumb_top)),
+                                        size(scrollbar_width, px(thumb_height)),
                                     ),
-                                    hsla(0.0, 0.0, 0.6, 0.4),
+                                    hsla(0.0, 0.0, 0.6, thumb_alpha),
                                 ));
+
+                                // Markers over the thumb, so they stay visible while scrolling past them
+                                let marker_height = px(2.0);
+                                for (position, color) in &data.scrollbar_markers {
+                                    let y = origin.y + (terminal_height - marker_height) * position.clamp(0.0, 1.0);
+                                    window.paint_quad(fill(
+                                        Bounds::new(point(track_x, y), size(scrollbar_width, marker_height)),
+                                        *color,
+                                    ));
+                                }
                             }
 
                             // Resize is now handled in prepaint before sync()
@@ -1139,15 +1855,163 @@ impl Render for TerminalView {
                 .size_full(),
             );
 
+        if self.hovered_link.is_some() {
+            container = container.cursor_pointer();
+        }
+
         // Add search bar overlay if present
         if let Some(search_bar) = search_bar_opt {
             container = container.child(search_bar);
         }
 
+        // Typing here also goes to other tabs; say so, with a way out
+        if broadcast_peers > 0 {
+            container = container.child(
+                div()
+                    .id("broadcast-banner")
+                    .absolute()
+                    .top_2()
+                    .left_2()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .px_3()
+                    .py_1()
+                    .rounded_md()
+                    .bg(rgb(0x313244))
+                    .border_1()
+                    .border_color(rgb(0xf38ba8))
+                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                    .child(div().text_sm().text_color(rgb(0xf38ba8)).child(format!(
+                        "Broadcasting to {} other tab{}",
+                        broadcast_peers,
+                        if broadcast_peers == 1 { "" } else { "s" }
+                    )))
+                    .child(
+                        div()
+                            .id("broadcast-stop")
+                            .text_sm()
+                            .text_color(rgb(0x6c7086))
+                            .cursor_pointer()
+                            .hover(|style| style.text_color(rgb(0xcdd6f4)))
+                            .on_click(cx.listener(|this, _event, window, cx| {
+                                toggle_broadcast(cx);
+                                window.focus(&this.focus_handle, cx);
+                            }))
+                            .child("Stop"),
+                    ),
+            );
+        }
+
+        // One-click answer for a sudo password prompt
+        if self.sudo_prompt {
+            container = container.child(
+                div()
+                    .id("sudo-autofill")
+                    .absolute()
+                    .bottom_2()
+                    .right_2()
+                    .px_3()
+                    .py_1()
+                    .rounded_md()
+                    .bg(rgb(0x313244))
+                    .border_1()
+                    .border_color(rgb(0xf9e2af))
+                    .cursor_pointer()
+                    .hover(|style| style.bg(rgb(0x45475a)))
+                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                    .on_click(cx.listener(|this, _event, window, cx| {
+                        this.send_sudo_password(cx);
+                        window.focus(&this.focus_handle, cx);
+                    }))
+                    .child(div().text_sm().text_color(rgb(0xf9e2af)).child("Send sudo password")),
+            );
+        }
+
         container
     }
 }
 
+/// Text input from the platform: typed characters and IME composition
+///
+/// The terminal has no editable buffer, so ranges are ignored: committed
+/// text is sent to the session and marked text is only displayed.
+impl EntityInputHandler for TerminalView {
+    fn text_for_range(
+        &mut self,
+        _range: Range<usize>,
+        _adjusted_range: &mut Option<Range<usize>>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<String> {
+        None
+    }
+
+    fn selected_text_range(
+        &mut self,
+        _ignore_disabled_input: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<UTF16Selection> {
+        Some(UTF16Selection {
+            range: 0..0,
+            reversed: false,
+        })
+    }
+
+    fn marked_text_range(&self, _window: &mut Window, _cx: &mut Context<Self>) -> Option<Range<usize>> {
+        self.marked_text
+            .as_ref()
+            .map(|text| 0..text.encode_utf16().count())
+    }
+
+    fn unmark_text(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
+        self.marked_text = None;
+        cx.notify();
+    }
+
+    fn replace_text_in_range(
+        &mut self,
+        _range: Option<Range<usize>>,
+        text: &str,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.commit_text(text, cx);
+    }
+
+    fn replace_and_mark_text_in_range(
+        &mut self,
+        _range: Option<Range<usize>>,
+        new_text: &str,
+        _new_selected_range: Option<Range<usize>>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.marked_text = Some(new_text.to_string()).filter(|text| !text.is_empty());
+        cx.notify();
+    }
+
+    fn bounds_for_range(
+        &mut self,
+        _range_utf16: Range<usize>,
+        element_bounds: Bounds<Pixels>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<Bounds<Pixels>> {
+        Some(self.cursor_bounds(element_bounds))
+    }
+
+    fn character_index_for_point(
+        &mut self,
+        _point: Point<Pixels>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<usize> {
+        None
+    }
+}
+
 pub fn terminal_view(terminal: Arc<Mutex<Terminal>>, color_scheme: Option<String>, _window: &mut Window, cx: &mut App) -> Entity<TerminalView> {
     cx.new(|cx| TerminalView::new(terminal, color_scheme, cx))
 }
@@ -1158,7 +2022,7 @@ pub fn terminal_view(terminal: Arc<Mutex<Terminal>>, color_scheme: Option<String
 /// - Windows: Cascadia Code (ships with Windows Terminal), falls back to Consolas
 /// - macOS: SF Mono (system font), falls back to Monaco
 /// - Linux: JetBrains Mono, falls back to Ubuntu Mono or DejaVu Sans Mono
-fn default_terminal_font() -> &'static str {
+pub(crate) fn default_terminal_font() -> &'static str {
     #[cfg(target_os = "windows")]
     {
         "Consolas"
@@ -1176,3 +2040,74 @@ fn default_terminal_font() -> &'static str {
         "monospace"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_viewport_to_grid() {
+        use alacritty_terminal::event::VoidListener;
+        use alacritty_terminal::grid::Scroll;
+        use alacritty_terminal::term::test::TermSize;
+        use alacritty_terminal::term::{Config, Term};
+        use alacritty_terminal::vte::ansi::{Processor, StdSyncHandler};
+
+        let mut term = Term::new(Config::default(), &TermSize::new(20, 5), VoidListener);
+        let mut processor = Processor::<StdSyncHandler>::new();
+        for i in 0..20 {
+            processor.advance(&mut term, format!("\r\nline {}", i).as_bytes());
+        }
+        // The top row on screen now shows "line 12"
+        term.scroll_display(Scroll::Delta(3));
+
+        let point = viewport_to_grid(TermPoint::new(Line(0), Column(5)), term.grid().display_offset());
+        assert_eq!(point, TermPoint::new(Line(-3), Column(5)));
+        let row = &term.grid()[point.line];
+        assert_eq!((row[Column(5)].c, row[Column(6)].c), ('1', '2'));
+    }
+
+    #[test]
+    fn test_click_count() {
+        let cell = TermPoint::new(Line(2), Column(5));
+        let t0 = Instant::now();
+        let soon = t0 + Duration::from_millis(100);
+        assert_eq!(click_count(None, t0, cell), 1);
+        assert_eq!(click_count(Some((t0, cell, 1)), soon, cell), 2);
+        assert_eq!(click_count(Some((t0, cell, 2)), soon, cell), 3);
+        assert_eq!(click_count(Some((t0, cell, 3)), soon, cell), 1);
+        // Too slow, or on another cell
+        assert_eq!(click_count(Some((t0, cell, 1)), t0 + Duration::from_millis(400), cell), 1);
+        assert_eq!(click_count(Some((t0, cell, 1)), soon, TermPoint::new(Line(2), Column(6))), 1);
+    }
+
+    #[test]
+    fn test_grid_bounds() {
+        let view = Bounds::new(point(px(10.0), px(20.0)), size(px(300.0), px(200.0)));
+        assert_eq!(grid_bounds(view, 0.0), view);
+        assert_eq!(
+            grid_bounds(view, 8.0),
+            Bounds::new(point(px(18.0), px(28.0)), size(px(284.0), px(184.0)))
+        );
+        // Padding never grows past the middle of the view
+        let thin = Bounds::new(point(px(0.0), px(0.0)), size(px(300.0), px(10.0)));
+        assert_eq!(grid_bounds(thin, 8.0).size.height, px(0.0));
+    }
+
+    #[test]
+    fn test_cursor_shape() {
+        assert_eq!(cursor_shape(true, None, ConfiguredCursorShape::Bar), CursorShape::Bar);
+        // An editor switching to a block in normal mode wins over the setting
+        assert_eq!(cursor_shape(true, Some(AnsiCursorShape::Block), ConfiguredCursorShape::Bar), CursorShape::Block);
+        assert_eq!(cursor_shape(true, Some(AnsiCursorShape::Beam), ConfiguredCursorShape::Block), CursorShape::Bar);
+        assert_eq!(cursor_shape(false, Some(AnsiCursorShape::Beam), ConfiguredCursorShape::Bar), CursorShape::Hollow);
+    }
+
+    #[test]
+    fn test_dim_color() {
+        let color = Hsla { h: 0.6, s: 0.5, l: 0.8, a: 1.0 };
+        let dimmed = dim_color(color);
+        assert!((dimmed.l - 0.48).abs() < 1e-6);
+        assert_eq!((dimmed.h, dimmed.s, dimmed.a), (color.h, color.s, color.a));
+    }
+}
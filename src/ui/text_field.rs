@@ -7,6 +7,18 @@ pub enum TextFieldEvent {
     Changed(String),
     /// Enter key pressed (submit)
     Submit,
+    /// A suggestion was picked (index into the current suggestions)
+    SuggestionAccepted(usize),
+}
+
+/// An entry in a text field's suggestion popup
+#[derive(Clone, Debug)]
+pub struct Suggestion {
+    pub label: String,
+    /// Secondary text shown after the label
+    pub detail: Option<String>,
+    /// Character positions in `label` to highlight as matched
+    pub highlights: Vec<usize>,
 }
 
 impl EventEmitter<TextFieldEvent> for TextField {}
@@ -19,6 +31,9 @@ pub struct TextField {
     placeholder: SharedString,
     on_change: Option<Box<dyn Fn(&str, &mut Context<Self>) + 'static>>,
     is_password: bool,
+    /// Suggestions shown below the field while it has focus
+    suggestions: Vec<Suggestion>,
+    selected_suggestion: usize,
 }
 
 impl TextField {
@@ -31,6 +46,8 @@ impl TextField {
             placeholder: placeholder.into(),
             on_change: None,
             is_password: false,
+            suggestions: Vec::new(),
+            selected_suggestion: 0,
         }
     }
 
@@ -44,6 +61,8 @@ impl TextField {
             placeholder: placeholder.into(),
             on_change: None,
             is_password: false,
+            suggestions: Vec::new(),
+            selected_suggestion: 0,
         }
     }
 
@@ -69,6 +88,30 @@ impl TextField {
         self.cursor_pos = self.content.len();
     }
 
+    /// Show suggestions below the field (an empty list hides the popup)
+    pub fn set_suggestions(&mut self, suggestions: Vec<Suggestion>) {
+        self.suggestions = suggestions;
+        self.selected_suggestion = 0;
+    }
+
+    /// Move the suggestion highlight up or down, wrapping around
+    fn move_suggestion(&mut self, down: bool, cx: &mut Context<Self>) {
+        let len = self.suggestions.len();
+        self.selected_suggestion = if down {
+            (self.selected_suggestion + 1) % len
+        } else {
+            (self.selected_suggestion + len - 1) % len
+        };
+        cx.notify();
+    }
+
+    /// Pick a suggestion and close the popup
+    fn accept_suggestion(&mut self, index: usize, cx: &mut Context<Self>) {
+        self.suggestions.clear();
+        cx.emit(TextFieldEvent::SuggestionAccepted(index));
+        cx.notify();
+    }
+
     /// Get the focus handle
     pub fn focus_handle(&self) -> &FocusHandle {
         &self.focus_handle
@@ -147,6 +190,49 @@ impl TextField {
         cx.notify();
     }
 
+    /// Popup listing the current suggestions, with matched characters highlighted
+    fn render_suggestions(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .w_full()
+            .flex()
+            .flex_col()
+            .py_1()
+            .bg(rgb(0x181825))
+            .border_1()
+            .border_color(rgb(0x45475a))
+            .rounded_md()
+            .children(self.suggestions.iter().enumerate().map(|(index, suggestion)| {
+                let is_selected = index == self.selected_suggestion;
+                let label = suggestion.label.chars().enumerate().map(|(i, ch)| {
+                    let ch_str = if ch == ' ' { "\u{00A0}".to_string() } else { ch.to_string() };
+                    if suggestion.highlights.contains(&i) {
+                        div().text_color(rgb(0x89b4fa)).font_weight(FontWeight::BOLD).child(ch_str)
+                    } else {
+                        div().child(ch_str)
+                    }
+                });
+
+                div()
+                    .id(("suggestion", index))
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .px_2()
+                    .py_1()
+                    .text_sm()
+                    .cursor_pointer()
+                    .when(is_selected, |this| this.bg(rgb(0x313244)))
+                    .hover(|style| style.bg(rgb(0x313244)))
+                    .on_click(cx.listener(move |this, _event, _window, cx| {
+                        this.accept_suggestion(index, cx);
+                    }))
+                    .child(div().flex().text_color(rgb(0xcdd6f4)).children(label))
+                    .when_some(suggestion.detail.clone(), |this, detail| {
+                        this.child(div().text_xs().text_color(rgb(0x6c7086)).child(detail))
+                    })
+            }))
+    }
+
     /// Get the display text (masked if password)
     fn display_text(&self) -> String {
         if self.is_password {
@@ -168,9 +254,8 @@ impl Render for TextField {
         // Collect chars for character-by-character rendering (enables wrapping)
         let chars: Vec<char> = display_text.chars().collect();
 
-        div()
-            .id("text-field")
-            .track_focus(&self.focus_handle)
+        let field = div()
+            .id("text-field-box")
             .w_full()
             .min_h(px(32.0))
             .max_h(px(80.0))  // Max ~3 lines
@@ -187,60 +272,6 @@ impl Render for TextField {
                 this.border_color(rgb(0x45475a))
             })
             .cursor_text()
-            .on_key_down(cx.listener(|this, event: &KeyDownEvent, _window, cx| {
-                let keystroke = &event.keystroke;
-                let is_cmd_or_ctrl = keystroke.modifiers.platform || keystroke.modifiers.control;
-
-                match keystroke.key.as_str() {
-                    "enter" if !keystroke.modifiers.shift => {
-                        cx.emit(TextFieldEvent::Submit);
-                    }
-                    "backspace" => this.handle_backspace(cx),
-                    "delete" => this.handle_delete(cx),
-                    "left" => this.move_left(cx),
-                    "right" => this.move_right(cx),
-                    "home" => this.move_to_start(cx),
-                    "end" => this.move_to_end(cx),
-                    "a" if is_cmd_or_ctrl => {
-                        // Select all - for now just move to end
-                        this.move_to_end(cx);
-                    }
-                    "v" if is_cmd_or_ctrl => {
-                        // Paste
-                        if let Some(item) = cx.read_from_clipboard() {
-                            if let Some(text) = item.text() {
-                                this.handle_input(&text, cx);
-                            }
-                        }
-                    }
-                    "c" if is_cmd_or_ctrl => {
-                        // Copy all content
-                        if !this.content.is_empty() {
-                            cx.write_to_clipboard(ClipboardItem::new_string(this.content.clone()));
-                        }
-                    }
-                    "x" if is_cmd_or_ctrl => {
-                        // Cut all content
-                        if !this.content.is_empty() {
-                            cx.write_to_clipboard(ClipboardItem::new_string(this.content.clone()));
-                            this.content.clear();
-                            this.cursor_pos = 0;
-                            if let Some(ref callback) = this.on_change {
-                                callback(&this.content, cx);
-                            }
-                            cx.emit(TextFieldEvent::Changed(this.content.clone()));
-                            cx.notify();
-                        }
-                    }
-                    "space" => {
-                        this.handle_input(" ", cx);
-                    }
-                    key if key.len() == 1 && !keystroke.modifiers.control && !keystroke.modifiers.platform && !keystroke.modifiers.alt => {
-                        this.handle_input(key, cx);
-                    }
-                    _ => {}
-                }
-            }))
             .on_click(cx.listener(|this, _event, window, cx| {
                 window.focus(&this.focus_handle, cx);
             }))
@@ -304,7 +335,85 @@ impl Render for TextField {
                                     })
                             )
                     })
-            )
+            );
+
+        // Focus and keys live on the wrapper so clicking a suggestion keeps focus
+        div()
+            .id("text-field")
+            .track_focus(&self.focus_handle)
+            .w_full()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .on_key_down(cx.listener(|this, event: &KeyDownEvent, _window, cx| {
+                let keystroke = &event.keystroke;
+                let is_cmd_or_ctrl = keystroke.modifiers.platform || keystroke.modifiers.control;
+
+                let has_suggestions = !this.suggestions.is_empty();
+
+                match keystroke.key.as_str() {
+                    "down" if has_suggestions => this.move_suggestion(true, cx),
+                    "up" if has_suggestions => this.move_suggestion(false, cx),
+                    "enter" if has_suggestions => this.accept_suggestion(this.selected_suggestion, cx),
+                    "escape" if has_suggestions => {
+                        // Dismiss the popup without closing the surrounding dialog
+                        this.suggestions.clear();
+                        cx.stop_propagation();
+                        cx.notify();
+                    }
+                    "enter" if !keystroke.modifiers.shift => {
+                        cx.emit(TextFieldEvent::Submit);
+                    }
+                    "backspace" => this.handle_backspace(cx),
+                    "delete" => this.handle_delete(cx),
+                    "left" => this.move_left(cx),
+                    "right" => this.move_right(cx),
+                    "home" => this.move_to_start(cx),
+                    "end" => this.move_to_end(cx),
+                    "a" if is_cmd_or_ctrl => {
+                        // Select all - for now just move to end
+                        this.move_to_end(cx);
+                    }
+                    "v" if is_cmd_or_ctrl => {
+                        // Paste
+                        if let Some(item) = cx.read_from_clipboard() {
+                            if let Some(text) = item.text() {
+                                this.handle_input(&text, cx);
+                            }
+                        }
+                    }
+                    "c" if is_cmd_or_ctrl => {
+                        // Copy all content
+                        if !this.content.is_empty() {
+                            cx.write_to_clipboard(ClipboardItem::new_string(this.content.clone()));
+                        }
+                    }
+                    "x" if is_cmd_or_ctrl => {
+                        // Cut all content
+                        if !this.content.is_empty() {
+                            cx.write_to_clipboard(ClipboardItem::new_string(this.content.clone()));
+                            this.content.clear();
+                            this.cursor_pos = 0;
+                            if let Some(ref callback) = this.on_change {
+                                callback(&this.content, cx);
+                            }
+                            cx.emit(TextFieldEvent::Changed(this.content.clone()));
+                            cx.notify();
+                        }
+                    }
+                    "space" => {
+                        this.handle_input(" ", cx);
+                    }
+                    key if key.len() == 1 && !keystroke.modifiers.control && !keystroke.modifiers.platform && !keystroke.modifiers.alt => {
+                        this.handle_input(key, cx);
+                    }
+                    _ => {}
+                }
+            }))
+            .child(field)
+            .when(is_focused && !self.suggestions.is_empty(), |this| {
+                this.child(self.render_suggestions(cx))
+            })
     }
 }
 
@@ -0,0 +1,375 @@
+use std::ops::Range;
+
+use gpui::*;
+use gpui::prelude::*;
+
+use super::terminal_view::default_terminal_font;
+use super::text_field::{TextField, TextFieldEvent};
+
+/// Height of one line in the viewer
+const LINE_HEIGHT: f32 = 18.0;
+
+/// Read-only pager for long text: scrollback, command output, error details
+///
+/// Lines are rendered lazily, so the text can be as long as a full
+/// scrollback buffer. The search field highlights every match; Enter (or the
+/// arrow buttons) steps through them. A viewer can hold several pages (one
+/// per command, say) with buttons to move between them.
+pub struct TextViewer {
+    title: SharedString,
+    /// Pages as (heading, text); the heading replaces the title when there
+    /// is more than one
+    pages: Vec<(SharedString, String)>,
+    page: usize,
+    lines: Vec<SharedString>,
+    search_field: Entity<TextField>,
+    /// Matches as (line, byte range within the line), in reading order
+    matches: Vec<(usize, Range<usize>)>,
+    current_match: usize,
+    /// Line with the most characters, used to size the list for horizontal scrolling
+    widest_line: Option<usize>,
+    scroll_handle: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl TextViewer {
+    pub fn new(title: impl Into<SharedString>, text: &str, cx: &mut Context<Self>) -> Self {
+        let title = title.into();
+        Self::with_pages(title.clone(), vec![(title, text.to_string())], 0, cx)
+    }
+
+    /// A viewer showing `pages`, starting at `page`
+    pub fn with_pages(
+        title: impl Into<SharedString>,
+        pages: Vec<(SharedString, String)>,
+        page: usize,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let search_field = cx.new(|cx| TextField::new(cx, "Search"));
+        let subscription = cx.subscribe(&search_field, |this: &mut Self, _field, event, cx| match event {
+            TextFieldEvent::Changed(query) => this.update_matches(query, cx),
+            TextFieldEvent::Submit => this.step_match(true, cx),
+            TextFieldEvent::SuggestionAccepted(_) => {}
+        });
+
+        let mut viewer = Self {
+            title: title.into(),
+            pages,
+            page: 0,
+            lines: Vec::new(),
+            search_field,
+            matches: Vec::new(),
+            current_match: 0,
+            widest_line: None,
+            scroll_handle: UniformListScrollHandle::new(),
+            focus_handle: cx.focus_handle(),
+            _subscriptions: vec![subscription],
+        };
+        viewer.load_page(page, cx);
+        viewer
+    }
+
+    /// Open `text` in its own window
+    pub fn open(title: impl Into<SharedString>, text: String, cx: &mut App) {
+        let title = title.into();
+        Self::open_pages(title.clone(), vec![(title, text)], 0, cx);
+    }
+
+    /// Open several pages of text in one window, starting at `page`
+    pub fn open_pages(title: impl Into<SharedString>, pages: Vec<(SharedString, String)>, page: usize, cx: &mut App) {
+        let title = title.into();
+        let window_options = WindowOptions {
+            window_bounds: Some(WindowBounds::Windowed(Bounds::centered(
+                None,
+                size(px(820.0), px(600.0)),
+                cx,
+            ))),
+            titlebar: Some(TitlebarOptions {
+                title: Some(title.clone()),
+                appears_transparent: false,
+                ..Default::default()
+            }),
+            kind: WindowKind::Normal,
+            ..Default::default()
+        };
+
+        let _ = cx.open_window(window_options, |window, cx| {
+            let viewer = cx.new(|cx| TextViewer::with_pages(title, pages, page, cx));
+            let search_focus = viewer.read(cx).search_field.read(cx).focus_handle().clone();
+            window.focus(&search_focus, cx);
+            viewer
+        });
+    }
+
+    /// The full text, as shown
+    fn text(&self) -> String {
+        self.lines.iter().map(|line| line.as_ref()).collect::<Vec<&str>>().join("\n")
+    }
+
+    /// Show page `page`, keeping the search query
+    fn load_page(&mut self, page: usize, cx: &mut Context<Self>) {
+        self.page = page.min(self.pages.len().saturating_sub(1));
+        let text = self.pages.get(self.page).map_or("", |(_, text)| text.as_str());
+        self.lines = text.lines().map(|line| line.replace('\t', "    ").into()).collect();
+        self.widest_line = self
+            .lines
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, line)| line.chars().count())
+            .map(|(i, _)| i);
+        self.scroll_handle.scroll_to_item(0, ScrollStrategy::Top);
+        let query = self.search_field.read(cx).content().to_string();
+        self.update_matches(&query, cx);
+    }
+
+    /// Move to the next (or previous) page, stopping at either end
+    fn step_page(&mut self, forward: bool, cx: &mut Context<Self>) {
+        let page = if forward { self.page + 1 } else { self.page.saturating_sub(1) };
+        if page != self.page && page < self.pages.len() {
+            self.load_page(page, cx);
+        }
+    }
+
+    /// Recompute matches for a new query and jump to the first one
+    fn update_matches(&mut self, query: &str, cx: &mut Context<Self>) {
+        self.matches = find_matches(&self.lines, query);
+        self.current_match = 0;
+        self.scroll_to_current();
+        cx.notify();
+    }
+
+    /// Move to the next (or previous) match, wrapping around
+    fn step_match(&mut self, forward: bool, cx: &mut Context<Self>) {
+        if self.matches.is_empty() {
+            return;
+        }
+        let count = self.matches.len();
+        self.current_match = if forward {
+            (self.current_match + 1) % count
+        } else {
+            (self.current_match + count - 1) % count
+        };
+        self.scroll_to_current();
+        cx.notify();
+    }
+
+    fn scroll_to_current(&self) {
+        if let Some((line, _)) = self.matches.get(self.current_match) {
+            self.scroll_handle.scroll_to_item(*line, ScrollStrategy::Center);
+        }
+    }
+
+    fn copy_all(&mut self, cx: &mut Context<Self>) {
+        cx.write_to_clipboard(ClipboardItem::new_string(self.text()));
+    }
+
+    /// Render one line, splitting out highlighted matches
+    fn render_line(&self, ix: usize, font_family: &SharedString) -> Div {
+        let line = &self.lines[ix];
+        let first = self.matches.partition_point(|(l, _)| *l < ix);
+        let line_matches = self.matches[first..].iter().take_while(|(l, _)| *l == ix);
+
+        let mut row = div()
+            .flex()
+            .h(px(LINE_HEIGHT))
+            .px_3()
+            .font_family(font_family.clone())
+            .text_sm()
+            .whitespace_nowrap()
+            .text_color(rgb(0xcdd6f4));
+        let mut end = 0;
+        for (offset, (_, range)) in line_matches.enumerate() {
+            if range.start > end {
+                row = row.child(line[end..range.start].to_string());
+            }
+            let is_current = first + offset == self.current_match;
+            row = row.child(
+                div()
+                    .bg(if is_current { rgb(0xf9e2af) } else { rgb(0x45475a) })
+                    .when(is_current, |this| this.text_color(rgb(0x1e1e2e)))
+                    .rounded_sm()
+                    .child(line[range.clone()].to_string()),
+            );
+            end = range.end;
+        }
+        if end < line.len() {
+            row = row.child(line[end..].to_string());
+        }
+        row
+    }
+}
+
+/// Find every occurrence of `query` in `lines`
+///
+/// The search ignores case unless the query contains an uppercase letter.
+/// Lines whose lowercase form has a different byte length (a few non-ASCII
+/// letters) are searched as written.
+fn find_matches(lines: &[SharedString], query: &str) -> Vec<(usize, Range<usize>)> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let ignore_case = !query.chars().any(char::is_uppercase);
+
+    let mut matches = Vec::new();
+    for (ix, line) in lines.iter().enumerate() {
+        let lowered;
+        let haystack: &str = if ignore_case {
+            lowered = line.to_lowercase();
+            if lowered.len() == line.len() { &lowered } else { line }
+        } else {
+            line
+        };
+        matches.extend(haystack.match_indices(query).map(|(start, m)| (ix, start..start + m.len())));
+    }
+    matches
+}
+
+impl Focusable for TextViewer {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for TextViewer {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let font_family: SharedString = default_terminal_font().into();
+        let line_count = self.lines.len();
+        let has_query = !self.search_field.read(cx).content().is_empty();
+        let status = match (has_query, self.matches.len()) {
+            (false, _) => format!("{} lines", line_count),
+            (true, 0) => "No matches".to_string(),
+            (true, count) => format!("{} of {}", self.current_match + 1, count),
+        };
+        let nav_enabled = !self.matches.is_empty();
+        let paged = self.pages.len() > 1;
+        let heading = match self.pages.get(self.page) {
+            Some((heading, _)) if paged => heading.clone(),
+            _ => self.title.clone(),
+        };
+
+        let nav_button = |id: &'static str, label: &'static str, forward: bool, cx: &mut Context<Self>| {
+            div()
+                .id(id)
+                .px_2()
+                .py_1()
+                .rounded_md()
+                .text_sm()
+                .text_color(if nav_enabled { rgb(0xcdd6f4) } else { rgb(0x6c7086) })
+                .when(nav_enabled, |this| {
+                    this.cursor_pointer()
+                        .hover(|style| style.bg(rgb(0x313244)))
+                        .on_click(cx.listener(move |this, _event, _window, cx| {
+                            this.step_match(forward, cx);
+                        }))
+                })
+                .child(label)
+        };
+
+        let page_button = |id: &'static str, label: &'static str, forward: bool, cx: &mut Context<Self>| {
+            let enabled = if forward { self.page + 1 < self.pages.len() } else { self.page > 0 };
+            div()
+                .id(id)
+                .px_2()
+                .py_1()
+                .rounded_md()
+                .text_sm()
+                .text_color(if enabled { rgb(0xcdd6f4) } else { rgb(0x6c7086) })
+                .when(enabled, |this| {
+                    this.cursor_pointer()
+                        .hover(|style| style.bg(rgb(0x313244)))
+                        .on_click(cx.listener(move |this, _event, _window, cx| {
+                            this.step_page(forward, cx);
+                        }))
+                })
+                .child(label)
+        };
+
+        div()
+            .id("text-viewer")
+            .track_focus(&self.focus_handle)
+            .flex()
+            .flex_col()
+            .size_full()
+            .bg(rgb(0x1e1e2e))
+            .on_key_down(cx.listener(|_this, event: &KeyDownEvent, window, _cx| {
+                if event.keystroke.key == "escape" {
+                    window.remove_window();
+                }
+            }))
+            // Header: title, search and actions
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .px_4()
+                    .py_3()
+                    .border_b_1()
+                    .border_color(rgb(0x313244))
+                    .child(
+                        div()
+                            .flex_1()
+                            .text_lg()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(rgb(0xcdd6f4))
+                            .overflow_hidden()
+                            .whitespace_nowrap()
+                            .child(heading),
+                    )
+                    .when(paged, |this| {
+                        this.child(page_button("prev-page", "‹", false, cx))
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(rgb(0x6c7086))
+                                    .child(format!("{} / {}", self.page + 1, self.pages.len())),
+                            )
+                            .child(page_button("next-page", "›", true, cx))
+                    })
+                    .child(div().w(px(220.0)).child(self.search_field.clone()))
+                    .child(nav_button("prev-match", "↑", false, cx))
+                    .child(nav_button("next-match", "↓", true, cx))
+                    .child(
+                        div()
+                            .id("copy-all-btn")
+                            .px_3()
+                            .py_1()
+                            .rounded_md()
+                            .bg(rgb(0x313244))
+                            .cursor_pointer()
+                            .hover(|style| style.bg(rgb(0x45475a)))
+                            .on_click(cx.listener(|this, _event, _window, cx| {
+                                this.copy_all(cx);
+                            }))
+                            .child(div().text_sm().text_color(rgb(0xcdd6f4)).child("Copy All")),
+                    ),
+            )
+            // Body
+            .child(
+                uniform_list(
+                    "text-viewer-lines",
+                    line_count,
+                    cx.processor(move |this, range: Range<usize>, _window, _cx| {
+                        range.map(|ix| this.render_line(ix, &font_family)).collect::<Vec<_>>()
+                    }),
+                )
+                .flex_1()
+                .py_2()
+                .with_horizontal_sizing_behavior(ListHorizontalSizingBehavior::Unconstrained)
+                .with_width_from_item(self.widest_line)
+                .track_scroll(&self.scroll_handle),
+            )
+            // Footer: line count or match position
+            .child(
+                div()
+                    .px_4()
+                    .py_2()
+                    .border_t_1()
+                    .border_color(rgb(0x313244))
+                    .text_xs()
+                    .text_color(rgb(0x6c7086))
+                    .child(status),
+            )
+    }
+}
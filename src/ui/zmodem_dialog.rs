@@ -0,0 +1,213 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use gpui::*;
+use gpui::prelude::*;
+use parking_lot::Mutex;
+
+use crate::app::AppState;
+use crate::terminal::{Terminal, ZmodemDirection};
+use super::text_field::TextField;
+
+/// Prompt shown when the remote starts `sz` or `rz`
+///
+/// Accepting hands the session to the ZMODEM transfer; cancelling aborts the
+/// remote program so the shell comes back straight away.
+pub struct ZmodemDialog {
+    terminal: Arc<Mutex<Terminal>>,
+    direction: ZmodemDirection,
+    /// Download folder (receive) or file to upload (send)
+    path_field: Entity<TextField>,
+    error: Option<String>,
+}
+
+impl ZmodemDialog {
+    pub fn new(terminal: Arc<Mutex<Terminal>>, direction: ZmodemDirection, cx: &mut Context<Self>) -> Self {
+        let path_field = match direction {
+            ZmodemDirection::Receive => {
+                let dir = cx
+                    .try_global::<AppState>()
+                    .map(|state| state.app.lock().config.zmodem.download_dir())
+                    .unwrap_or_else(|| PathBuf::from("."));
+                cx.new(|cx| TextField::with_content(cx, "Folder to save into", dir.to_string_lossy().into_owned()))
+            }
+            ZmodemDirection::Send => cx.new(|cx| TextField::new(cx, "Path of the file to send")),
+        };
+
+        Self {
+            terminal,
+            direction,
+            path_field,
+            error: None,
+        }
+    }
+
+    /// Open as a modal window
+    pub fn open(terminal: Arc<Mutex<Terminal>>, direction: ZmodemDirection, cx: &mut App) {
+        let window_options = WindowOptions {
+            window_bounds: Some(WindowBounds::Windowed(Bounds::centered(
+                None,
+                size(px(460.0), px(240.0)),
+                cx,
+            ))),
+            titlebar: Some(TitlebarOptions {
+                title: Some("ZMODEM Transfer".into()),
+                appears_transparent: false,
+                ..Default::default()
+            }),
+            kind: WindowKind::Normal,
+            ..Default::default()
+        };
+
+        let _ = cx.open_window(window_options, |_window, cx| {
+            cx.new(|cx| ZmodemDialog::new(terminal, direction, cx))
+        });
+    }
+
+    fn handle_accept(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let path = self.path_field.read(cx).content().trim().to_string();
+        if path.is_empty() {
+            self.error = Some("Path is required".to_string());
+            cx.notify();
+            return;
+        }
+        let path = match path.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().map(|h| h.join(rest)).unwrap_or_else(|| PathBuf::from(&path)),
+            None => PathBuf::from(&path),
+        };
+
+        let terminal = self.terminal.lock();
+        let result = match self.direction {
+            ZmodemDirection::Receive => terminal.accept_zmodem_receive(&path),
+            ZmodemDirection::Send => terminal.accept_zmodem_send(&path),
+        };
+        drop(terminal);
+
+        match result {
+            Ok(()) => window.remove_window(),
+            Err(e) => {
+                self.error = Some(format!("{}: {}", path.display(), e));
+                cx.notify();
+            }
+        }
+    }
+
+    fn handle_cancel(&mut self, window: &mut Window) {
+        self.terminal.lock().cancel_zmodem();
+        window.remove_window();
+    }
+}
+
+impl Render for ZmodemDialog {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let (title, description, field_label, accept_label) = match self.direction {
+            ZmodemDirection::Receive => (
+                "Incoming file",
+                "The remote host started sz and wants to send files.",
+                "Save to folder",
+                "Accept file",
+            ),
+            ZmodemDirection::Send => (
+                "Send file",
+                "The remote host started rz and is waiting for a file.",
+                "File to send",
+                "Send file",
+            ),
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .size_full()
+            .bg(rgb(0x1e1e2e))
+            // Header
+            .child(
+                div()
+                    .px_4()
+                    .py_3()
+                    .border_b_1()
+                    .border_color(rgb(0x313244))
+                    .child(
+                        div()
+                            .text_lg()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(rgb(0xcdd6f4))
+                            .child(title),
+                    ),
+            )
+            // Form content
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .flex_1()
+                    .gap_3()
+                    .p_4()
+                    .child(div().text_sm().text_color(rgb(0x6c7086)).child(description))
+                    .when_some(self.error.clone(), |this, error| {
+                        this.child(
+                            div()
+                                .p_2()
+                                .bg(rgba(0xf38ba833))
+                                .rounded_md()
+                                .text_sm()
+                                .text_color(rgb(0xf38ba8))
+                                .child(error),
+                        )
+                    })
+                    .child(
+                        div()
+                            .flex()
+                            .flex_col()
+                            .gap_1()
+                            .child(div().text_sm().text_color(rgb(0xcdd6f4)).child(field_label))
+                            .child(self.path_field.clone()),
+                    ),
+            )
+            // Footer with buttons
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_end()
+                    .gap_2()
+                    .px_4()
+                    .py_3()
+                    .border_t_1()
+                    .border_color(rgb(0x313244))
+                    .child(
+                        div()
+                            .id("cancel-btn")
+                            .px_4()
+                            .py_2()
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|style| style.bg(rgb(0x313244)))
+                            .on_click(cx.listener(|this, _event, window, _cx| {
+                                this.handle_cancel(window);
+                            }))
+                            .child(div().text_sm().text_color(rgb(0x6c7086)).child("Cancel")),
+                    )
+                    .child(
+                        div()
+                            .id("zmodem-accept-btn")
+                            .px_4()
+                            .py_2()
+                            .bg(rgb(0x89b4fa))
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|style| style.bg(rgb(0x74c7ec)))
+                            .on_click(cx.listener(|this, _event, window, cx| {
+                                this.handle_accept(window, cx);
+                            }))
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .text_color(rgb(0x1e1e2e))
+                                    .font_weight(FontWeight::SEMIBOLD)
+                                    .child(accept_label),
+                            ),
+                    ),
+            )
+    }
+}